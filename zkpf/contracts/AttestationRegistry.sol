@@ -33,6 +33,15 @@ interface IAttestationRegistry {
         bytes32 nullifier
     ) external returns (bytes32 attestationId);
 
+    /// @notice Record several attestations in one transaction.
+    /// @dev Arrays are parallel; one `Attested` event is emitted per entry, in order.
+    function attestBatch(
+        bytes32[] calldata holderIds,
+        uint256[] calldata policyIds,
+        bytes32[] calldata snapshotIds,
+        bytes32[] calldata nullifiers
+    ) external returns (bytes32[] memory attestationIds);
+
     /// @notice Fetch an attestation by ID.
     function getAttestation(bytes32 attestationId) external view returns (Attestation memory);
 
@@ -57,6 +66,35 @@ contract AttestationRegistry is IAttestationRegistry {
         bytes32 nullifier
     ) external override returns (bytes32 attestationId) {
         // NOTE: add access control before production (e.g. onlyRole(VERIFIER_ROLE)).
+        attestationId = _attest(holderId, policyId, snapshotId, nullifier);
+    }
+
+    /// @inheritdoc IAttestationRegistry
+    function attestBatch(
+        bytes32[] calldata holderIds,
+        uint256[] calldata policyIds,
+        bytes32[] calldata snapshotIds,
+        bytes32[] calldata nullifiers
+    ) external override returns (bytes32[] memory attestationIds) {
+        // NOTE: add access control before production (e.g. onlyRole(VERIFIER_ROLE)).
+        uint256 count = holderIds.length;
+        require(
+            policyIds.length == count && snapshotIds.length == count && nullifiers.length == count,
+            "Batch length mismatch"
+        );
+
+        attestationIds = new bytes32[](count);
+        for (uint256 i = 0; i < count; i++) {
+            attestationIds[i] = _attest(holderIds[i], policyIds[i], snapshotIds[i], nullifiers[i]);
+        }
+    }
+
+    function _attest(
+        bytes32 holderId,
+        uint256 policyId,
+        bytes32 snapshotId,
+        bytes32 nullifier
+    ) internal returns (bytes32 attestationId) {
         attestationId = keccak256(
             abi.encodePacked(holderId, policyId, snapshotId, nullifier, msg.sender, block.timestamp)
         );
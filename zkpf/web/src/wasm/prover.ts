@@ -215,6 +215,8 @@ export interface OrchardPublicInputs {
 export async function generateOrchardBundle(
   publicInputs: OrchardPublicInputs,
   noteValues: number[],
+  holderId?: string,
+  fvk?: string,
 ): Promise<ProofBundle> {
   await ensureWasmLoaded();
   
@@ -249,7 +251,7 @@ export async function generateOrchardBundle(
   let raw;
   try {
     console.log('[ZKPF Orchard] Calling generateOrchardProofBundleCached...');
-    raw = generateOrchardProofBundleCached(publicInputsJson, noteValuesJson);
+    raw = generateOrchardProofBundleCached(publicInputsJson, noteValuesJson, holderId, fvk);
     console.log('[ZKPF Orchard] ✓ generateOrchardProofBundleCached returned successfully');
   } catch (err) {
     const message =
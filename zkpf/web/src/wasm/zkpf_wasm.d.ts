@@ -55,7 +55,7 @@ export function computeNullifier(account_id_hash_bytes: Uint8Array, verifier_sco
 /**
  * Generate an Orchard proof bundle using the cached Orchard artifacts.
  */
-export function generateOrchardProofBundleCached(public_inputs_json: string, note_values_json: string): any;
+export function generateOrchardProofBundleCached(public_inputs_json: string, note_values_json: string, holder_id?: string | null, fvk?: string | null): any;
 
 export function generateProofBundle(attestation_json: string, params_bytes: Uint8Array, pk_bytes: Uint8Array): any;
 
@@ -22,7 +22,7 @@ use reqwest::blocking::Client;
 use serde::Serialize;
 use serde_json::{json, Value};
 use zkpf_circuit::{
-    gadgets::attestation::{AttestationWitness, EcdsaSignature, Secp256k1Pubkey},
+    gadgets::attestation::{AttestationWitness, EcdsaSignature, Secp256k1Pubkey, SignatureFormat},
     PublicInputs, ZkpfCircuitInput,
 };
 use zkpf_common::{
@@ -466,6 +466,7 @@ fn generate_sample_input() -> Result<ZkpfCircuitInput> {
         custodian_pubkey: derived_pubkey,
         signature,
         message_hash,
+        signature_format: SignatureFormat::RawHash,
     };
 
     let nullifier = poseidon_hash([
@@ -484,6 +485,8 @@ fn generate_sample_input() -> Result<ZkpfCircuitInput> {
         policy_id: POLICY_ID,
         nullifier,
         custodian_pubkey_hash: pubkey_hash,
+        proven_sum: None,
+        challenge: None,
     };
 
     Ok(ZkpfCircuitInput {
@@ -8,11 +8,14 @@
 use serde::{Deserialize, Serialize};
 use sha3::{Digest, Keccak256};
 
-use crate::{chains, AxelarGmpError, ChainSubscription, ChainType, GmpMessage, PoFReceipt};
 use crate::zcash::{
     CreditLineConfig, RevocationReason, ZcashBridgeMessage, ZecCredential, ZecTier,
     ZCASH_CHAIN_ID, ZCASH_MAINNET_ID,
 };
+use crate::{
+    chains, encoding, AxelarGmpError, BalanceUpdate, ChainSubscription, ChainType, GmpMessage,
+    MessageType, PoFReceipt,
+};
 
 // ═══════════════════════════════════════════════════════════════════════════════
 // BRIDGE CONFIGURATION
@@ -138,6 +141,19 @@ impl ZcashBridgeConfig {
         self
     }
 
+    /// Override the gas budget for a destination chain
+    pub fn set_gas_budget(&mut self, chain_name: &str, gas: u64) -> &mut Self {
+        self.gas_budget.insert(chain_name.to_string(), gas);
+        if let Some(sub) = self
+            .subscriptions
+            .iter_mut()
+            .find(|s| s.chain_name == chain_name)
+        {
+            sub.default_gas = gas;
+        }
+        self
+    }
+
     /// Get active subscriptions
     pub fn active_subscriptions(&self) -> Vec<&ChainSubscription> {
         self.subscriptions.iter().filter(|s| s.active).collect()
@@ -435,6 +451,37 @@ impl ZcashBridge {
         }
     }
 
+    /// Encode a balance update message
+    pub fn encode_balance_update(
+        &self,
+        update: &BalanceUpdate,
+        chain_name: &str,
+    ) -> Result<Vec<u8>, AxelarGmpError> {
+        let sub = self
+            .config
+            .subscriptions
+            .iter()
+            .find(|s| s.chain_name == chain_name && s.active)
+            .ok_or_else(|| AxelarGmpError::InvalidChain(chain_name.into()))?;
+
+        match sub.chain_type {
+            ChainType::Evm => {
+                // Message type (1 byte) + ABI-encoded
+                // (bytes32 credentialId, uint8 tier, uint64 expiresAt)
+                let mut payload = vec![MessageType::BalanceUpdate as u8];
+                payload.extend_from_slice(&encoding::encode_balance_update(update)?);
+                Ok(payload)
+            }
+            ChainType::Cosmos => {
+                let msg = ZcashBridgeMessage::BalanceUpdate(update.clone());
+                msg.encode()
+            }
+            _ => Err(AxelarGmpError::InvalidChain(
+                "unsupported chain type".into(),
+            )),
+        }
+    }
+
     /// Convert credential to PoFReceipt for legacy GMP format
     pub fn credential_to_receipt(&self, credential: &ZecCredential) -> PoFReceipt {
         credential.to_pof_receipt()
@@ -545,6 +592,7 @@ impl ZcashBridge {
 pub struct CredentialBuilder {
     account_tag: Option<[u8; 32]>,
     tier: Option<ZecTier>,
+    asset_code: Option<String>,
     policy_id: Option<u64>,
     state_root: Option<[u8; 32]>,
     block_height: Option<u64>,
@@ -571,6 +619,12 @@ impl CredentialBuilder {
         self
     }
 
+    /// Set the asset the tier is measured in (defaults to ZEC)
+    pub fn asset_code(mut self, asset_code: impl Into<String>) -> Self {
+        self.asset_code = Some(asset_code.into());
+        self
+    }
+
     /// Set the policy ID
     pub fn policy_id(mut self, id: u64) -> Self {
         self.policy_id = Some(id);
@@ -617,7 +671,7 @@ impl CredentialBuilder {
             .policy_id
             .unwrap_or_else(|| crate::zcash::tier_to_policy_id(tier));
 
-        Ok(ZecCredential::new(
+        let mut credential = ZecCredential::new(
             self.account_tag
                 .ok_or_else(|| AxelarGmpError::Encoding("account_tag is required".into()))?,
             tier,
@@ -631,7 +685,11 @@ impl CredentialBuilder {
                 .ok_or_else(|| AxelarGmpError::Encoding("proof_commitment is required".into()))?,
             self.attestation_hash
                 .ok_or_else(|| AxelarGmpError::Encoding("attestation_hash is required".into()))?,
-        ))
+        );
+        if let Some(asset_code) = self.asset_code {
+            credential.asset_code = asset_code.trim().to_ascii_uppercase();
+        }
+        Ok(credential)
     }
 }
 
@@ -654,6 +712,18 @@ mod tests {
         assert_eq!(active[0].chain_name, chains::ETHEREUM);
     }
 
+    #[test]
+    fn test_set_gas_budget() {
+        let mut config = ZcashBridgeConfig::with_evm_chains();
+        config.subscribe(chains::ETHEREUM, "0x1234567890abcdef");
+
+        config.set_gas_budget(chains::ETHEREUM, 750_000);
+
+        assert_eq!(config.gas_budget[chains::ETHEREUM], 750_000);
+        assert_eq!(config.active_subscriptions()[0].default_gas, 750_000);
+        assert_eq!(ZcashBridge::new(config).estimate_total_gas(), 750_000);
+    }
+
     #[test]
     fn test_credential_builder() {
         let cred = CredentialBuilder::new()
@@ -735,5 +805,27 @@ mod tests {
         assert_eq!(encoded.len(), 1 + 32 + 32);
         assert_eq!(encoded[0], 1); // Revocation type
     }
+
+    #[test]
+    fn test_balance_update_encoding() {
+        let mut config = ZcashBridgeConfig::default();
+        config.subscribe(chains::ARBITRUM, "0xreceiver");
+
+        let bridge = ZcashBridge::new(config);
+
+        let update = BalanceUpdate {
+            credential_id: [5u8; 32],
+            new_tier: ZecTier::Tier1000,
+            expires_at: 1700086400,
+        };
+        let encoded = bridge
+            .encode_balance_update(&update, chains::ARBITRUM)
+            .unwrap();
+
+        // 1 byte message type + credential ID, tier and expiry as 32-byte words
+        assert_eq!(encoded.len(), 1 + 3 * 32);
+        assert_eq!(encoded[0], 3); // Balance update type
+        assert!(bridge.encode_balance_update(&update, chains::OSMOSIS).is_err());
+    }
 }
 
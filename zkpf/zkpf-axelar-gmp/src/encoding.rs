@@ -3,14 +3,15 @@
 //! This module provides encoding/decoding logic compatible with Solidity ABI
 //! encoding for cross-chain message passing.
 
-use crate::{AxelarGmpError, PoFQuery, PoFReceipt, PoFRevocation};
+use crate::{AxelarGmpError, BalanceUpdate, PoFQuery, PoFReceipt, PoFRevocation, ZecTier};
 
 /// Encode a PoF receipt for GMP transmission
 pub fn encode_receipt(receipt: &PoFReceipt) -> Result<Vec<u8>, AxelarGmpError> {
     // ABI-encode the receipt fields
     // Layout: holder_id (32) + policy_id (32) + snapshot_id (32) + chain_id_origin (32)
     //       + attestation_hash (32) + validity_window (32) + issued_at (32)
-    let mut encoded = Vec::with_capacity(7 * 32);
+    //       + receipt_hash (32)
+    let mut encoded = Vec::with_capacity(8 * 32);
 
     // bytes32 holder_id
     encoded.extend_from_slice(&receipt.holder_id);
@@ -41,9 +42,19 @@ pub fn encode_receipt(receipt: &PoFReceipt) -> Result<Vec<u8>, AxelarGmpError> {
     issued_bytes[24..].copy_from_slice(&receipt.issued_at.to_be_bytes());
     encoded.extend_from_slice(&issued_bytes);
 
+    // bytes32 receipt_hash, so receivers can check the fields arrived intact
+    encoded.extend_from_slice(&receipt.compute_hash());
+
     Ok(encoded)
 }
 
+/// Read the `receipt_hash` word of an ABI-encoded receipt, if present
+///
+/// Receipts encoded before the hash was added carry only the seven fields.
+pub fn decode_receipt_hash(bytes: &[u8]) -> Option<[u8; 32]> {
+    bytes.get(7 * 32..8 * 32)?.try_into().ok()
+}
+
 /// Decode a PoF receipt from ABI-encoded bytes
 pub fn decode_receipt(bytes: &[u8]) -> Result<PoFReceipt, AxelarGmpError> {
     if bytes.len() < 7 * 32 {
@@ -148,6 +159,58 @@ pub fn decode_revocation(bytes: &[u8]) -> Result<PoFRevocation, AxelarGmpError>
     })
 }
 
+/// Encode a balance update for GMP transmission
+pub fn encode_balance_update(update: &BalanceUpdate) -> Result<Vec<u8>, AxelarGmpError> {
+    // Layout: credential_id (32) + new_tier (32) + expires_at (32)
+    let mut encoded = Vec::with_capacity(3 * 32);
+
+    // bytes32 credential_id
+    encoded.extend_from_slice(&update.credential_id);
+
+    // uint8 new_tier (padded to 32 bytes)
+    let mut tier_bytes = [0u8; 32];
+    tier_bytes[31] = update.new_tier.as_u8();
+    encoded.extend_from_slice(&tier_bytes);
+
+    // uint64 expires_at (padded to 32 bytes)
+    let mut expires_bytes = [0u8; 32];
+    expires_bytes[24..].copy_from_slice(&update.expires_at.to_be_bytes());
+    encoded.extend_from_slice(&expires_bytes);
+
+    Ok(encoded)
+}
+
+/// Decode a balance update from ABI-encoded bytes
+pub fn decode_balance_update(bytes: &[u8]) -> Result<BalanceUpdate, AxelarGmpError> {
+    if bytes.len() < 3 * 32 {
+        return Err(AxelarGmpError::Decoding(format!(
+            "balance update payload too short: {} < {}",
+            bytes.len(),
+            3 * 32
+        )));
+    }
+
+    let mut offset = 0;
+
+    // bytes32 credential_id
+    let mut credential_id = [0u8; 32];
+    credential_id.copy_from_slice(&bytes[offset..offset + 32]);
+    offset += 32;
+
+    // uint8 new_tier
+    let new_tier = ZecTier::try_from(bytes[offset + 31])?;
+    offset += 32;
+
+    // uint64 expires_at
+    let expires_at = u64::from_be_bytes(bytes[offset + 24..offset + 32].try_into().unwrap());
+
+    Ok(BalanceUpdate {
+        credential_id,
+        new_tier,
+        expires_at,
+    })
+}
+
 /// Encode a PoF query for GMP transmission
 pub fn encode_query(query: &PoFQuery) -> Result<Vec<u8>, AxelarGmpError> {
     // Layout: holder_id (32) + policy_id (32) + has_snapshot (32) + snapshot_id (32)
@@ -303,6 +366,7 @@ mod tests {
         assert_eq!(encoded.len(), 7 * 32);
 
         let decoded = decode_receipt(&encoded).unwrap();
+        assert_eq!(decode_receipt_hash(&encoded), Some(receipt.compute_hash()));
         assert_eq!(decoded.holder_id, receipt.holder_id);
         assert_eq!(decoded.policy_id, receipt.policy_id);
         assert_eq!(decoded.snapshot_id, receipt.snapshot_id);
@@ -329,6 +393,28 @@ mod tests {
         assert_eq!(decoded.snapshot_id, revocation.snapshot_id);
     }
 
+    #[test]
+    fn test_balance_update_roundtrip() {
+        let update = BalanceUpdate {
+            credential_id: [6u8; 32],
+            new_tier: ZecTier::Tier1000,
+            expires_at: 1700086400,
+        };
+
+        let encoded = encode_balance_update(&update).unwrap();
+        assert_eq!(encoded.len(), 3 * 32);
+
+        let decoded = decode_balance_update(&encoded).unwrap();
+        assert_eq!(decoded.credential_id, update.credential_id);
+        assert_eq!(decoded.new_tier, update.new_tier);
+        assert_eq!(decoded.expires_at, update.expires_at);
+
+        // Unknown tiers are rejected rather than silently mapped
+        let mut bad = encoded.clone();
+        bad[2 * 32 - 1] = 9;
+        assert!(decode_balance_update(&bad).is_err());
+    }
+
     #[test]
     fn test_hex_conversion() {
         let bytes = [0xab; 32];
@@ -15,8 +15,9 @@ pub mod zcash;
 
 // Re-export zcash types
 pub use zcash::{
-    CreditLineConfig, RevocationReason, ZcashBridgeMessage, ZecCredential, ZecTier,
-    ZCASH_CHAIN_ID, ZCASH_MAINNET_ID, ZEC_DECIMALS, ZEC_IN_ZATOSHIS,
+    AssetTier, AssetTierTable, CreditLineConfig, RevocationReason, ZcashBridgeMessage,
+    ZecCredential, ZecTier, ZCASH_CHAIN_ID, ZCASH_MAINNET_ID, ZEC_ASSET_CODE, ZEC_DECIMALS,
+    ZEC_IN_ZATOSHIS,
 };
 
 // Re-export bridge types
@@ -82,6 +83,8 @@ pub enum MessageType {
     PoFRevocation = 1,
     /// Query PoF status (for pull-based integrations)
     PoFQuery = 2,
+    /// Amend the tier and expiry of a previously broadcast credential
+    BalanceUpdate = 3,
 }
 
 impl TryFrom<u8> for MessageType {
@@ -92,6 +95,7 @@ impl TryFrom<u8> for MessageType {
             0 => Ok(Self::PoFReceipt),
             1 => Ok(Self::PoFRevocation),
             2 => Ok(Self::PoFQuery),
+            3 => Ok(Self::BalanceUpdate),
             _ => Err(AxelarGmpError::InvalidMessageType(value)),
         }
     }
@@ -162,6 +166,13 @@ impl PoFReceipt {
     }
 }
 
+/// Check that `claimed_hash` is the hash of `receipt`'s fields
+///
+/// Receivers use this to reject receipts altered in transit before storing them.
+pub fn verify_receipt_integrity(receipt: &PoFReceipt, claimed_hash: &[u8; 32]) -> bool {
+    receipt.compute_hash() == *claimed_hash
+}
+
 /// Revocation payload
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PoFRevocation {
@@ -173,6 +184,20 @@ pub struct PoFRevocation {
     pub snapshot_id: [u8; 32],
 }
 
+/// Balance update payload
+///
+/// Lets destination chains amend a credential in place when the holder's
+/// balance moves to a new tier, instead of revoking and re-issuing it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BalanceUpdate {
+    /// Credential being amended (32 bytes)
+    pub credential_id: [u8; 32],
+    /// Tier proven by the fresh proof
+    pub new_tier: ZecTier,
+    /// New expiration timestamp
+    pub expires_at: u64,
+}
+
 /// Query payload (for pull-based integrations)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PoFQuery {
@@ -216,6 +241,15 @@ impl GmpMessage {
         })
     }
 
+    /// Create a balance update message
+    pub fn balance_update(update: BalanceUpdate) -> Result<Self, AxelarGmpError> {
+        let payload = encoding::encode_balance_update(&update)?;
+        Ok(Self {
+            msg_type: MessageType::BalanceUpdate,
+            payload,
+        })
+    }
+
     /// Create a query message
     pub fn query(query: PoFQuery) -> Result<Self, AxelarGmpError> {
         let payload = encoding::encode_query(&query)?;
@@ -252,6 +286,14 @@ impl GmpMessage {
         encoding::decode_receipt(&self.payload)
     }
 
+    /// The receipt hash carried alongside a receipt payload, if any
+    pub fn receipt_hash(&self) -> Option<[u8; 32]> {
+        if self.msg_type != MessageType::PoFReceipt {
+            return None;
+        }
+        encoding::decode_receipt_hash(&self.payload)
+    }
+
     /// Decode the payload as a revocation
     pub fn as_revocation(&self) -> Result<PoFRevocation, AxelarGmpError> {
         if self.msg_type != MessageType::PoFRevocation {
@@ -259,6 +301,14 @@ impl GmpMessage {
         }
         encoding::decode_revocation(&self.payload)
     }
+
+    /// Decode the payload as a balance update
+    pub fn as_balance_update(&self) -> Result<BalanceUpdate, AxelarGmpError> {
+        if self.msg_type != MessageType::BalanceUpdate {
+            return Err(AxelarGmpError::InvalidMessageType(self.msg_type as u8));
+        }
+        encoding::decode_balance_update(&self.payload)
+    }
 }
 
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -340,6 +390,12 @@ pub struct StoredReceipt {
     pub expires_at: u64,
     /// Whether receipt is currently valid
     pub valid: bool,
+    /// BLAKE3 hash of the proof the receipt was broadcast from, if one was supplied
+    #[serde(default)]
+    pub proof_hash: Option<[u8; 32]>,
+    /// Policy ID carried in that proof's public inputs
+    #[serde(default)]
+    pub proof_policy_id: Option<u64>,
 }
 
 impl StoredReceipt {
@@ -354,9 +410,18 @@ impl StoredReceipt {
             issued_at: receipt.issued_at,
             expires_at: receipt.expires_at(),
             valid: true,
+            proof_hash: None,
+            proof_policy_id: None,
         }
     }
 
+    /// Link the receipt to the proof it was issued from
+    pub fn with_proof(mut self, proof_hash: [u8; 32], proof_policy_id: u64) -> Self {
+        self.proof_hash = Some(proof_hash);
+        self.proof_policy_id = Some(proof_policy_id);
+        self
+    }
+
     /// Check if the receipt is currently valid
     pub fn is_valid(&self, current_timestamp: u64) -> bool {
         self.valid && current_timestamp < self.expires_at
@@ -480,12 +545,57 @@ mod tests {
         assert_eq!(decoded_receipt.policy_id, receipt.policy_id);
     }
 
+    #[test]
+    fn test_receipt_hash_detects_tampering() {
+        let receipt = PoFReceipt::new(
+            [1u8; 32],
+            271828,
+            [2u8; 32],
+            1,
+            [3u8; 32],
+            86400,
+            1700000000,
+        );
+        let mut encoded = GmpMessage::receipt(receipt).unwrap().encode();
+        let intact = |encoded: &[u8]| {
+            let message = GmpMessage::decode(encoded).unwrap();
+            let claimed = message.receipt_hash().unwrap();
+            verify_receipt_integrity(&message.as_receipt().unwrap(), &claimed)
+        };
+        assert!(intact(&encoded));
+
+        // Bump policy_id (last byte of the second word, after the type byte)
+        encoded[1 + 2 * 32 - 1] ^= 1;
+        assert!(!intact(&encoded));
+    }
+
     #[test]
     fn test_message_type_conversion() {
         assert_eq!(MessageType::try_from(0).unwrap(), MessageType::PoFReceipt);
         assert_eq!(MessageType::try_from(1).unwrap(), MessageType::PoFRevocation);
         assert_eq!(MessageType::try_from(2).unwrap(), MessageType::PoFQuery);
-        assert!(MessageType::try_from(3).is_err());
+        assert_eq!(MessageType::try_from(3).unwrap(), MessageType::BalanceUpdate);
+        assert!(MessageType::try_from(4).is_err());
+    }
+
+    #[test]
+    fn test_balance_update_message_roundtrip() {
+        let update = BalanceUpdate {
+            credential_id: [7u8; 32],
+            new_tier: ZecTier::Tier100,
+            expires_at: 1700086400,
+        };
+
+        let encoded = GmpMessage::balance_update(update.clone()).unwrap().encode();
+        assert_eq!(encoded[0], MessageType::BalanceUpdate as u8);
+
+        let decoded = GmpMessage::decode(&encoded).unwrap();
+        assert!(decoded.as_receipt().is_err());
+
+        let decoded_update = decoded.as_balance_update().unwrap();
+        assert_eq!(decoded_update.credential_id, update.credential_id);
+        assert_eq!(decoded_update.new_tier, update.new_tier);
+        assert_eq!(decoded_update.expires_at, update.expires_at);
     }
 
     #[test]
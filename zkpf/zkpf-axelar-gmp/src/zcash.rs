@@ -7,7 +7,7 @@
 use serde::{Deserialize, Serialize};
 use sha3::{Digest, Keccak256};
 
-use crate::{AxelarGmpError, PoFReceipt};
+use crate::{AxelarGmpError, BalanceUpdate, PoFReceipt};
 
 // ═══════════════════════════════════════════════════════════════════════════════
 // CONSTANTS
@@ -25,6 +25,9 @@ pub const ZEC_DECIMALS: u8 = 8;
 /// 1 ZEC in zatoshis
 pub const ZEC_IN_ZATOSHIS: u64 = 100_000_000;
 
+/// Asset code of native ZEC, assumed when a credential names no asset
+pub const ZEC_ASSET_CODE: &str = "ZEC";
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // CREDENTIAL TIERS
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -118,6 +121,150 @@ impl TryFrom<u8> for ZecTier {
     }
 }
 
+// ═══════════════════════════════════════════════════════════════════════════════
+// ASSET TIERS
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// One row of a tier table: holding at least `threshold_raw` base units of
+/// `asset_code` qualifies for tier `tier_index`.
+///
+/// Tier indices are shared across assets (they are what credentials and GMP
+/// messages carry), so `tier_index` must be a valid [`ZecTier`] value.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssetTier {
+    /// Asset code, e.g. "ZEC" (matched case-insensitively)
+    pub asset_code: String,
+    /// Tier level (0-5)
+    pub tier_index: u8,
+    /// Minimum balance in the asset's smallest unit
+    pub threshold_raw: u64,
+}
+
+impl AssetTier {
+    /// The tier level as a [`ZecTier`]
+    pub fn tier(&self) -> ZecTier {
+        ZecTier::try_from(self.tier_index).expect("tier index validated by AssetTierTable")
+    }
+
+    /// Get human-readable tier name
+    pub fn name(&self) -> String {
+        if self.asset_code == ZEC_ASSET_CODE {
+            self.tier().name().to_string()
+        } else {
+            format!("{}+ {} (raw)", self.threshold_raw, self.asset_code)
+        }
+    }
+}
+
+/// Tier thresholds for every asset the rail issues credentials for.
+///
+/// The ZEC rows default to the [`ZecTier`] thresholds and are added whenever a
+/// loaded table does not define ZEC itself.
+#[derive(Debug, Clone, Serialize)]
+#[serde(transparent)]
+pub struct AssetTierTable {
+    tiers: Vec<AssetTier>,
+}
+
+impl Default for AssetTierTable {
+    fn default() -> Self {
+        Self {
+            tiers: zec_default_tiers(),
+        }
+    }
+}
+
+impl AssetTierTable {
+    /// Build a table, normalizing asset codes to upper case.
+    ///
+    /// Each `(asset_code, tier_index)` pair may appear once, and an asset's
+    /// thresholds must strictly increase with the tier index.
+    pub fn new(tiers: Vec<AssetTier>) -> Result<Self, AxelarGmpError> {
+        let mut tiers: Vec<AssetTier> = tiers
+            .into_iter()
+            .map(|tier| AssetTier {
+                asset_code: tier.asset_code.trim().to_ascii_uppercase(),
+                ..tier
+            })
+            .collect();
+        if !tiers.iter().any(|tier| tier.asset_code == ZEC_ASSET_CODE) {
+            tiers.extend(zec_default_tiers());
+        }
+        tiers.sort_by(|a, b| {
+            a.asset_code
+                .cmp(&b.asset_code)
+                .then(a.tier_index.cmp(&b.tier_index))
+        });
+
+        for tier in &tiers {
+            if tier.asset_code.is_empty() {
+                return Err(AxelarGmpError::Decoding("empty asset code".into()));
+            }
+            ZecTier::try_from(tier.tier_index)?;
+        }
+        for pair in tiers.windows(2) {
+            let (lower, upper) = (&pair[0], &pair[1]);
+            if lower.asset_code != upper.asset_code {
+                continue;
+            }
+            if lower.tier_index == upper.tier_index {
+                return Err(AxelarGmpError::Decoding(format!(
+                    "duplicate tier {} for {}",
+                    upper.tier_index, upper.asset_code
+                )));
+            }
+            if lower.threshold_raw >= upper.threshold_raw {
+                return Err(AxelarGmpError::Decoding(format!(
+                    "{} tier {} threshold must exceed tier {}",
+                    upper.asset_code, upper.tier_index, lower.tier_index
+                )));
+            }
+        }
+
+        Ok(Self { tiers })
+    }
+
+    /// Parse a JSON array of [`AssetTier`] rows
+    pub fn from_json(bytes: &[u8]) -> Result<Self, AxelarGmpError> {
+        let tiers: Vec<AssetTier> =
+            serde_json::from_slice(bytes).map_err(|e| AxelarGmpError::Decoding(e.to_string()))?;
+        Self::new(tiers)
+    }
+
+    /// Look up the row for an asset's tier
+    pub fn tier(&self, asset_code: &str, tier: ZecTier) -> Option<&AssetTier> {
+        let asset_code = asset_code.trim().to_ascii_uppercase();
+        self.tiers
+            .iter()
+            .find(|row| row.asset_code == asset_code && row.tier_index == tier.as_u8())
+    }
+
+    /// Highest tier of `asset_code` that `balance_raw` qualifies for
+    pub fn tier_from_balance(&self, asset_code: &str, balance_raw: u64) -> Option<&AssetTier> {
+        let asset_code = asset_code.trim().to_ascii_uppercase();
+        self.tiers
+            .iter()
+            .filter(|row| row.asset_code == asset_code && balance_raw >= row.threshold_raw)
+            .max_by_key(|row| row.tier_index)
+    }
+
+    /// All rows, sorted by asset code and tier index
+    pub fn tiers(&self) -> &[AssetTier] {
+        &self.tiers
+    }
+}
+
+fn zec_default_tiers() -> Vec<AssetTier> {
+    (0..=ZecTier::Tier10000.as_u8())
+        .filter_map(|index| ZecTier::try_from(index).ok())
+        .map(|tier| AssetTier {
+            asset_code: ZEC_ASSET_CODE.to_string(),
+            tier_index: tier.as_u8(),
+            threshold_raw: tier.threshold_zatoshis(),
+        })
+        .collect()
+}
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // ZEC CREDENTIAL
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -134,6 +281,10 @@ pub struct ZecCredential {
     /// Minimum balance tier proven
     pub tier: ZecTier,
 
+    /// Asset the tier is measured in (see [`AssetTierTable`])
+    #[serde(default = "default_asset_code")]
+    pub asset_code: String,
+
     /// Policy ID for the proof (defines the verification rules)
     pub policy_id: u64,
 
@@ -180,6 +331,7 @@ impl ZecCredential {
         Self {
             account_tag,
             tier,
+            asset_code: ZEC_ASSET_CODE.to_string(),
             policy_id,
             state_root,
             block_height,
@@ -197,10 +349,16 @@ impl ZecCredential {
     }
 
     /// Compute a unique credential ID
+    ///
+    /// The asset code is only hashed for non-ZEC credentials, so ZEC
+    /// credential IDs are unchanged from before assets were tracked.
     pub fn credential_id(&self) -> [u8; 32] {
         let mut hasher = Keccak256::new();
         hasher.update(self.account_tag);
         hasher.update(self.tier.as_u8().to_be_bytes());
+        if self.asset_code != ZEC_ASSET_CODE {
+            hasher.update(self.asset_code.as_bytes());
+        }
         hasher.update(self.policy_id.to_be_bytes());
         hasher.update(self.proof_commitment);
         hasher.update(self.issued_at.to_be_bytes());
@@ -221,6 +379,10 @@ impl ZecCredential {
     }
 }
 
+fn default_asset_code() -> String {
+    ZEC_ASSET_CODE.to_string()
+}
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // CREDIT LINE CONFIG
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -312,6 +474,9 @@ pub enum ZcashBridgeMessage {
         old_credential_id: [u8; 32],
         new_credential: ZecCredential,
     },
+
+    /// Amend the tier and expiry of an existing credential in place
+    BalanceUpdate(BalanceUpdate),
 }
 
 /// Reasons for credential revocation
@@ -474,6 +639,90 @@ mod tests {
         assert_eq!(policy_id_to_tier(400006), None);
     }
 
+    #[test]
+    fn test_asset_tier_table_defaults_to_zec() {
+        let table = AssetTierTable::default();
+        let row = table.tier("zec", ZecTier::Tier100).unwrap();
+        assert_eq!(row.threshold_raw, ZecTier::Tier100.threshold_zatoshis());
+        assert_eq!(row.name(), "100+ ZEC");
+        assert_eq!(
+            table
+                .tier_from_balance(ZEC_ASSET_CODE, 15_000_000_000)
+                .unwrap()
+                .tier(),
+            ZecTier::Tier100
+        );
+        assert!(table.tier("USDC", ZecTier::Tier1).is_none());
+    }
+
+    #[test]
+    fn test_asset_tier_table_from_config() {
+        let table = AssetTierTable::from_json(
+            br#"[
+                {"asset_code": "usdc", "tier_index": 1, "threshold_raw": 1000000000},
+                {"asset_code": "usdc", "tier_index": 0, "threshold_raw": 100000000}
+            ]"#,
+        )
+        .unwrap();
+
+        // ZEC defaults are kept alongside the configured asset.
+        assert!(table.tier(ZEC_ASSET_CODE, ZecTier::Tier10000).is_some());
+        assert_eq!(
+            table.tier("USDC", ZecTier::Tier1).unwrap().threshold_raw,
+            1_000_000_000
+        );
+        assert_eq!(
+            table.tier_from_balance("USDC", 500_000_000).unwrap().tier(),
+            ZecTier::Tier01
+        );
+        assert!(table.tier_from_balance("USDC", 1).is_none());
+
+        // Thresholds must increase with the tier, and tiers must exist.
+        assert!(AssetTierTable::new(vec![
+            AssetTier {
+                asset_code: "USDC".into(),
+                tier_index: 0,
+                threshold_raw: 10
+            },
+            AssetTier {
+                asset_code: "USDC".into(),
+                tier_index: 1,
+                threshold_raw: 10
+            },
+        ])
+        .is_err());
+        assert!(AssetTierTable::new(vec![AssetTier {
+            asset_code: "USDC".into(),
+            tier_index: 6,
+            threshold_raw: 10,
+        }])
+        .is_err());
+    }
+
+    #[test]
+    fn test_credential_id_only_hashes_non_zec_assets() {
+        let zec = ZecCredential::new(
+            [1u8; 32],
+            ZecTier::Tier10,
+            policy_ids::ZEC_TIER_10,
+            [2u8; 32],
+            1000000,
+            86400,
+            [3u8; 32],
+            [4u8; 32],
+        );
+        let mut usdc = zec.clone();
+        usdc.asset_code = "USDC".to_string();
+        assert_ne!(zec.credential_id(), usdc.credential_id());
+
+        // Credentials serialized before assets were tracked are ZEC.
+        let mut legacy = serde_json::to_value(&zec).unwrap();
+        legacy.as_object_mut().unwrap().remove("asset_code");
+        let legacy: ZecCredential = serde_json::from_value(legacy).unwrap();
+        assert_eq!(legacy.asset_code, ZEC_ASSET_CODE);
+        assert_eq!(legacy.credential_id(), zec.credential_id());
+    }
+
     #[test]
     fn test_bridge_message_encoding() {
         let cred = ZecCredential::new(
@@ -12,7 +12,7 @@ use axum::{
     extract::{Json, Path, State},
     http::StatusCode,
     response::IntoResponse,
-    routing::{get, post},
+    routing::{get, patch, post},
     Router,
 };
 use serde::{Deserialize, Serialize};
@@ -21,11 +21,14 @@ use tower_http::cors::{Any, CorsLayer};
 
 use zkpf_axelar_gmp::{
     bridge::{BroadcastStatus, CredentialBuilder, ZcashBridge, ZcashBridgeConfig},
-    chains, AxelarGmpError, ChainSubscription, ChainType, GmpMessage, PoFReceipt,
-    RevocationReason, StoredReceipt, TrustedSource, ZecCredential, ZecTier,
-    DEFAULT_VALIDITY_WINDOW_SECS, RAIL_ID_AXELAR_GMP,
+    chains, verify_receipt_integrity,
+    zcash::tier_to_policy_id,
+    AssetTier, AssetTierTable, AxelarGmpError, BalanceUpdate, ChainSubscription, ChainType,
+    GmpMessage, PoFReceipt, RevocationReason, StoredReceipt, TrustedSource, ZecCredential, ZecTier,
+    DEFAULT_VALIDITY_WINDOW_SECS, RAIL_ID_AXELAR_GMP, ZEC_ASSET_CODE,
 };
-use zkpf_common::ProofBundle;
+pub use zkpf_common::ErrorCode;
+use zkpf_common::{load_verifier_artifacts, ProofBundle, VerifierArtifacts};
 
 // ═══════════════════════════════════════════════════════════════════════════════
 // ENVIRONMENT VARIABLES
@@ -36,6 +39,18 @@ const AXELAR_GAS_SERVICE_ENV: &str = "ZKPF_AXELAR_GAS_SERVICE";
 const ORIGIN_CHAIN_ID_ENV: &str = "ZKPF_ORIGIN_CHAIN_ID";
 const ORIGIN_CHAIN_NAME_ENV: &str = "ZKPF_ORIGIN_CHAIN_NAME";
 const VALIDITY_WINDOW_ENV: &str = "ZKPF_AXELAR_VALIDITY_WINDOW";
+/// JSON array of `AssetTier` rows; ZEC keeps its default tiers unless listed
+const ASSET_TIERS_ENV: &str = "ZKPF_AXELAR_ASSET_TIERS_PATH";
+/// Custodial artifact manifest used to re-verify proofs linked to receipts
+const VERIFIER_MANIFEST_ENV: &str = "ZKPF_AXELAR_MANIFEST_PATH";
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// LIMITS
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Accepted range for per-chain gas overrides
+const MIN_DEFAULT_GAS: u64 = 50_000;
+const MAX_DEFAULT_GAS: u64 = 10_000_000;
 
 // ═══════════════════════════════════════════════════════════════════════════════
 // STATE
@@ -66,6 +81,10 @@ pub struct AppState {
     pub credentials: Arc<RwLock<HashMap<String, ZecCredential>>>,
     /// Revoked credential IDs
     pub revoked_credentials: Arc<RwLock<HashMap<String, RevocationReason>>>,
+    /// Tier thresholds per asset
+    pub asset_tiers: Arc<AssetTierTable>,
+    /// Verifier artifacts for re-checking receipt proofs; `None` disables it
+    pub verifier: Option<Arc<VerifierArtifacts>>,
 }
 
 impl Default for AppState {
@@ -92,23 +111,48 @@ impl Default for AppState {
             zcash_bridge: Arc::new(RwLock::new(zcash_bridge)),
             credentials: Arc::new(RwLock::new(HashMap::new())),
             revoked_credentials: Arc::new(RwLock::new(HashMap::new())),
+            asset_tiers: Arc::new(asset_tiers_from_env()),
+            verifier: verifier_from_env().map(Arc::new),
         }
     }
 }
 
+/// Load the asset tier table from `ZKPF_AXELAR_ASSET_TIERS_PATH`, or the ZEC
+/// defaults when it is unset.
+fn asset_tiers_from_env() -> AssetTierTable {
+    let Ok(path) = env::var(ASSET_TIERS_ENV) else {
+        return AssetTierTable::default();
+    };
+    let bytes = std::fs::read(&path)
+        .unwrap_or_else(|err| panic!("failed to read asset tiers from {}: {}", path, err));
+    AssetTierTable::from_json(&bytes)
+        .unwrap_or_else(|err| panic!("invalid asset tiers in {}: {}", path, err))
+}
+
+/// Load verifier artifacts from `ZKPF_AXELAR_MANIFEST_PATH`, if it is set.
+fn verifier_from_env() -> Option<VerifierArtifacts> {
+    let path = env::var(VERIFIER_MANIFEST_ENV).ok()?;
+    let artifacts = load_verifier_artifacts(&path)
+        .unwrap_or_else(|err| panic!("failed to load verifier artifacts from {}: {}", path, err));
+    Some(artifacts)
+}
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // ROUTER
 // ═══════════════════════════════════════════════════════════════════════════════
 
 /// Build the router
 pub fn app_router() -> Router {
+    app_router_with_state(AppState::default())
+}
+
+/// Build the router around an existing state
+pub fn app_router_with_state(state: AppState) -> Router {
     let cors = CorsLayer::new()
         .allow_origin(Any)
         .allow_methods(Any)
         .allow_headers(Any);
 
-    let state = AppState::default();
-
     Router::new()
         // Health & info
         .route("/health", get(health))
@@ -117,6 +161,10 @@ pub fn app_router() -> Router {
         .route("/rails/axelar/chains", get(list_chains))
         .route("/rails/axelar/chains/supported", get(list_supported_chains))
         .route("/rails/axelar/subscriptions", get(list_subscriptions))
+        .route(
+            "/rails/axelar/subscriptions/:chain",
+            patch(update_subscription),
+        )
         .route("/rails/axelar/subscribe", post(subscribe_chain))
         .route("/rails/axelar/unsubscribe", post(unsubscribe_chain))
         // Broadcasting
@@ -130,19 +178,24 @@ pub fn app_router() -> Router {
         // Gas estimation
         .route("/rails/axelar/estimate-gas", post(estimate_gas))
         // === ZEC CREDENTIAL ROUTES ===
-        // Issue a new ZEC credential
-        .route("/rails/axelar/zec/issue", post(issue_zec_credential))
+        // Issue a new credential (ZEC unless the request names another asset)
+        .route("/rails/axelar/zec/issue", post(issue_credential))
+        .route("/rails/axelar/issue", post(issue_credential))
         // Broadcast ZEC credential to chains
         .route("/rails/axelar/zec/broadcast", post(broadcast_zec_credential))
         .route("/rails/axelar/zec/broadcast/:chain", post(broadcast_zec_to_chain))
         // Revoke a credential
         .route("/rails/axelar/zec/revoke", post(revoke_credential))
+        .route("/rails/axelar/zec/revoke-account", post(revoke_account))
+        // Amend a credential's tier from a fresh proof
+        .route("/rails/axelar/zec/update", post(update_credential))
         // Query credentials
         .route("/rails/axelar/zec/credential/:credential_id", get(get_credential))
         .route("/rails/axelar/zec/credentials/:account_tag", get(get_account_credentials))
         .route("/rails/axelar/zec/check", post(check_zec_credential))
         // Tier information
         .route("/rails/axelar/zec/tiers", get(list_tiers))
+        .route("/rails/axelar/tiers", get(list_asset_tiers))
         // Bridge stats
         .route("/rails/axelar/zec/bridge/stats", get(get_bridge_stats))
         .route("/rails/axelar/zec/bridge/pending", get(get_pending_broadcasts))
@@ -241,28 +294,27 @@ async fn subscribe_chain(
         .as_ref()
         .map(|c| c.chain_type)
         .unwrap_or(ChainType::Other);
-    let default_gas = req
-        .default_gas
-        .or_else(|| chain_info.map(|c| c.default_gas))
-        .unwrap_or(500_000);
-
-    let subscription = ChainSubscription {
-        chain_name: req.chain_name.clone(),
-        receiver_contract: req.receiver_contract.clone(),
-        active: true,
-        default_gas,
-        chain_type,
-    };
-
     let mut subs = state.subscriptions.write().await;
 
-    // Check if already subscribed
+    // Check if already subscribed; keep any gas override unless a new one is given
     if let Some(existing) = subs.iter_mut().find(|s| s.chain_name == req.chain_name) {
         existing.receiver_contract = req.receiver_contract.clone();
         existing.active = true;
-        existing.default_gas = default_gas;
+        if let Some(default_gas) = req.default_gas {
+            existing.default_gas = default_gas;
+        }
     } else {
-        subs.push(subscription);
+        let default_gas = req
+            .default_gas
+            .or_else(|| chain_info.map(|c| c.default_gas))
+            .unwrap_or(500_000);
+        subs.push(ChainSubscription {
+            chain_name: req.chain_name.clone(),
+            receiver_contract: req.receiver_contract.clone(),
+            active: true,
+            default_gas,
+            chain_type,
+        });
     }
 
     Ok(Json(serde_json::json!({
@@ -272,6 +324,52 @@ async fn subscribe_chain(
     })))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct UpdateSubscriptionRequest {
+    pub default_gas: u64,
+}
+
+/// Retune the gas for a subscribed chain without touching its receiver contract
+async fn update_subscription(
+    State(state): State<AppState>,
+    Path(chain): Path<String>,
+    Json(req): Json<UpdateSubscriptionRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    if !(MIN_DEFAULT_GAS..=MAX_DEFAULT_GAS).contains(&req.default_gas) {
+        return Err(ApiError {
+            status: StatusCode::BAD_REQUEST,
+            message: format!(
+                "default_gas must be between {} and {}",
+                MIN_DEFAULT_GAS, MAX_DEFAULT_GAS
+            ),
+            code: ErrorCode::InvalidGas,
+        });
+    }
+
+    let mut subs = state.subscriptions.write().await;
+    let Some(sub) = subs.iter_mut().find(|s| s.chain_name == chain) else {
+        return Err(ApiError {
+            status: StatusCode::NOT_FOUND,
+            message: format!("Chain {} not subscribed", chain),
+            code: ErrorCode::ChainNotFound,
+        });
+    };
+    sub.default_gas = req.default_gas;
+
+    state
+        .zcash_bridge
+        .write()
+        .await
+        .config
+        .set_gas_budget(&chain, req.default_gas);
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "chain_name": chain,
+        "default_gas": req.default_gas
+    })))
+}
+
 #[derive(Debug, Deserialize)]
 pub struct UnsubscribeRequest {
     pub chain_name: String,
@@ -293,7 +391,7 @@ async fn unsubscribe_chain(
         Err(ApiError {
             status: StatusCode::NOT_FOUND,
             message: format!("Chain {} not subscribed", req.chain_name),
-            code: "CHAIN_NOT_FOUND".into(),
+            code: ErrorCode::ChainNotFound,
         })
     }
 }
@@ -314,7 +412,8 @@ pub struct BroadcastRequest {
     pub attestation_hash: String,
     /// Optional: override validity window (seconds)
     pub validity_window: Option<u64>,
-    /// Optional: ProofBundle to verify before broadcasting
+    /// Optional: ProofBundle backing the receipt; its proof hash and policy
+    /// are stored with the receipt so `check-pof` can re-verify it
     pub bundle: Option<ProofBundle>,
 }
 
@@ -324,7 +423,7 @@ pub struct BroadcastResponse {
     pub receipt_hash: Option<String>,
     pub chains_broadcast: Vec<String>,
     pub error: Option<String>,
-    pub error_code: Option<String>,
+    pub error_code: Option<ErrorCode>,
 }
 
 async fn broadcast_receipt(
@@ -336,6 +435,19 @@ async fn broadcast_receipt(
     let snapshot_id = parse_hex32(&req.snapshot_id)?;
     let attestation_hash = parse_hex32(&req.attestation_hash)?;
 
+    if let Some(bundle) = &req.bundle {
+        if bundle.public_inputs.policy_id != req.policy_id {
+            return Err(ApiError {
+                status: StatusCode::BAD_REQUEST,
+                message: format!(
+                    "Bundle policy {} does not match receipt policy {}",
+                    bundle.public_inputs.policy_id, req.policy_id
+                ),
+                code: ErrorCode::PolicyMismatch,
+            });
+        }
+    }
+
     let validity_window = req.validity_window.unwrap_or(state.validity_window);
     let issued_at = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
@@ -366,7 +478,7 @@ async fn broadcast_receipt(
             receipt_hash: Some(receipt_hash),
             chains_broadcast: vec![],
             error: Some("No active chain subscriptions".into()),
-            error_code: Some("NO_SUBSCRIPTIONS".into()),
+            error_code: Some(ErrorCode::NoSubscriptions),
         }));
     }
 
@@ -380,7 +492,10 @@ async fn broadcast_receipt(
     let chains_broadcast: Vec<String> = active.iter().map(|s| s.chain_name.clone()).collect();
 
     // Store locally for demo
-    let stored = StoredReceipt::from_receipt(&receipt);
+    let mut stored = StoredReceipt::from_receipt(&receipt);
+    if let Some(bundle) = &req.bundle {
+        stored = stored.with_proof(proof_hash(bundle)?, bundle.public_inputs.policy_id);
+    }
     let key = format!("{}:{}", hex::encode(holder_id), req.policy_id);
     state.receipts.write().await.insert(key, stored);
 
@@ -409,7 +524,7 @@ async fn broadcast_to_chain(
     let sub = sub.ok_or_else(|| ApiError {
         status: StatusCode::NOT_FOUND,
         message: format!("Chain {} not subscribed or inactive", chain),
-        code: "CHAIN_NOT_FOUND".into(),
+        code: ErrorCode::ChainNotFound,
     })?;
 
     // Parse and build receipt
@@ -475,7 +590,7 @@ async fn receive_message(
             return Err(ApiError {
                 status: StatusCode::FORBIDDEN,
                 message: "Untrusted source".into(),
-                code: "UNTRUSTED_SOURCE".into(),
+                code: ErrorCode::UntrustedSource,
             });
         }
     }
@@ -486,7 +601,7 @@ async fn receive_message(
         .map_err(|e| ApiError {
             status: StatusCode::BAD_REQUEST,
             message: format!("Invalid payload hex: {}", e),
-            code: "INVALID_PAYLOAD".into(),
+            code: ErrorCode::InvalidPayload,
         })?;
 
     let message = GmpMessage::decode(&payload_bytes).map_err(ApiError::from_gmp_error)?;
@@ -494,6 +609,17 @@ async fn receive_message(
     match message.msg_type {
         zkpf_axelar_gmp::MessageType::PoFReceipt => {
             let receipt = message.as_receipt().map_err(ApiError::from_gmp_error)?;
+            // Only store receipts whose fields match the hash they were sent with
+            let intact = message
+                .receipt_hash()
+                .is_some_and(|claimed| verify_receipt_integrity(&receipt, &claimed));
+            if !intact {
+                return Err(ApiError {
+                    status: StatusCode::BAD_REQUEST,
+                    message: "Receipt hash is missing or does not match the receipt".into(),
+                    code: ErrorCode::ReceiptHashMismatch,
+                });
+            }
             let stored = StoredReceipt::from_receipt(&receipt);
             let key = format!("{}:{}", hex::encode(receipt.holder_id), receipt.policy_id);
             state.receipts.write().await.insert(key, stored);
@@ -524,6 +650,23 @@ async fn receive_message(
                 error: None,
             }))
         }
+        zkpf_axelar_gmp::MessageType::BalanceUpdate => {
+            let update = message
+                .as_balance_update()
+                .map_err(ApiError::from_gmp_error)?;
+            let key = hex::encode(update.credential_id);
+
+            if let Some(credential) = state.credentials.write().await.get_mut(&key) {
+                credential.tier = update.new_tier;
+                credential.expires_at = update.expires_at;
+            }
+
+            Ok(Json(ReceiveResponse {
+                success: true,
+                message_type: Some("BALANCE_UPDATE".into()),
+                error: None,
+            }))
+        }
         _ => Ok(Json(ReceiveResponse {
             success: false,
             message_type: None,
@@ -540,13 +683,117 @@ async fn receive_message(
 pub struct CheckPoFRequest {
     pub holder_id: String,
     pub policy_id: u64,
+    /// Optional: the bundle the receipt was broadcast from, to re-verify it
+    #[serde(default)]
+    pub verify: Option<ProofBundle>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct CheckPoFResponse {
     pub has_pof: bool,
     pub receipt: Option<StoredReceipt>,
     pub expired: bool,
+    /// Outcome of re-verifying `verify` against the receipt, when requested
+    pub proof: Option<ReceiptProofCheck>,
+}
+
+/// Whether a supplied bundle is the valid, policy-matching proof behind a receipt
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReceiptProofCheck {
+    pub valid: bool,
+    pub error: Option<String>,
+    pub error_code: Option<ErrorCode>,
+}
+
+impl ReceiptProofCheck {
+    fn passed() -> Self {
+        Self {
+            valid: true,
+            error: None,
+            error_code: None,
+        }
+    }
+
+    fn failed(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            valid: false,
+            error: Some(message.into()),
+            error_code: Some(code),
+        }
+    }
+}
+
+/// BLAKE3 hash of a bundle's decoded proof
+fn proof_hash(bundle: &ProofBundle) -> Result<[u8; 32], ApiError> {
+    bundle.proof_hash().map_err(|err| ApiError {
+        status: StatusCode::BAD_REQUEST,
+        message: format!("Invalid proof bundle: {}", err),
+        code: ErrorCode::BundleInvalid,
+    })
+}
+
+/// Check that `bundle` is the proof linked to `receipt`, proves the receipt's
+/// policy, and verifies against the configured artifacts.
+fn check_receipt_proof(
+    verifier: Option<&VerifierArtifacts>,
+    receipt: &StoredReceipt,
+    bundle: &ProofBundle,
+) -> ReceiptProofCheck {
+    let Some(linked_hash) = receipt.proof_hash else {
+        return ReceiptProofCheck::failed(
+            ErrorCode::ReceiptProofMismatch,
+            "Receipt was not broadcast with a proof",
+        );
+    };
+    match bundle.proof_hash() {
+        Ok(hash) if hash == linked_hash => {}
+        Ok(_) => {
+            return ReceiptProofCheck::failed(
+                ErrorCode::ReceiptProofMismatch,
+                "Proof is not the one the receipt was broadcast from",
+            )
+        }
+        Err(err) => {
+            return ReceiptProofCheck::failed(
+                ErrorCode::BundleInvalid,
+                format!("Invalid proof bundle: {}", err),
+            )
+        }
+    }
+
+    let policy_id = bundle.public_inputs.policy_id;
+    if policy_id != receipt.policy_id || receipt.proof_policy_id != Some(policy_id) {
+        return ReceiptProofCheck::failed(
+            ErrorCode::PolicyMismatch,
+            format!(
+                "Proof policy {} does not match receipt policy {}",
+                policy_id, receipt.policy_id
+            ),
+        );
+    }
+
+    let Some(verifier) = verifier else {
+        return ReceiptProofCheck::failed(
+            ErrorCode::ArtifactNotFound,
+            format!("Proof verification is not configured; set {VERIFIER_MANIFEST_ENV}"),
+        );
+    };
+    if bundle.circuit_version != verifier.manifest.circuit_version {
+        return ReceiptProofCheck::failed(
+            ErrorCode::CircuitVersionMismatch,
+            format!(
+                "Bundle circuit version {} does not match verifier version {}",
+                bundle.circuit_version, verifier.manifest.circuit_version
+            ),
+        );
+    }
+    match zkpf_verifier::verify_bundle(&verifier.params, &verifier.vk, bundle) {
+        Ok(true) => ReceiptProofCheck::passed(),
+        Ok(false) => {
+            ReceiptProofCheck::failed(ErrorCode::ProofInvalid, "Proof verification failed")
+        }
+        Err(err) => ReceiptProofCheck::failed(ErrorCode::ProofInvalid, err.to_string()),
+    }
 }
 
 async fn check_pof(
@@ -569,16 +816,22 @@ async fn check_pof(
         Some(r) => {
             let expired = now >= r.expires_at;
             let has_pof = r.valid && !expired;
+            let proof = req
+                .verify
+                .as_ref()
+                .map(|bundle| check_receipt_proof(state.verifier.as_deref(), &r, bundle));
             Ok(Json(CheckPoFResponse {
                 has_pof,
                 receipt: Some(r),
                 expired,
+                proof,
             }))
         }
         None => Ok(Json(CheckPoFResponse {
             has_pof: false,
             receipt: None,
             expired: false,
+            proof: None,
         })),
     }
 }
@@ -594,7 +847,7 @@ async fn get_receipt(
     receipts.get(&key).cloned().ok_or_else(|| ApiError {
         status: StatusCode::NOT_FOUND,
         message: "Receipt not found".into(),
-        code: "RECEIPT_NOT_FOUND".into(),
+        code: ErrorCode::ReceiptNotFound,
     }).map(Json)
 }
 
@@ -649,8 +902,12 @@ async fn estimate_gas(
 pub struct IssueCredentialRequest {
     /// Account tag (hex-encoded 32 bytes)
     pub account_tag: String,
-    /// Balance tier (0-5)
-    pub tier: u8,
+    /// Asset the tier is measured in (defaults to ZEC)
+    pub asset_code: Option<String>,
+    /// Balance tier (0-5); required unless `balance_raw` is given
+    pub tier: Option<u8>,
+    /// Proven balance in the asset's smallest unit; selects the highest tier it meets
+    pub balance_raw: Option<u64>,
     /// State root (hex-encoded 32 bytes)
     pub state_root: String,
     /// Block height
@@ -668,20 +925,23 @@ pub struct IssueCredentialResponse {
     pub success: bool,
     pub credential_id: Option<String>,
     pub tier: Option<String>,
+    pub asset_code: Option<String>,
+    pub threshold_raw: Option<u64>,
     pub expires_at: Option<u64>,
     pub error: Option<String>,
 }
 
-async fn issue_zec_credential(
+/// Issue a tiered credential for ZEC or any asset in the tier table.
+///
+/// The tier comes from the asset's table: either the row for `tier`, or the
+/// highest row `balance_raw` meets.
+async fn issue_credential(
     State(state): State<AppState>,
     Json(req): Json<IssueCredentialRequest>,
 ) -> Result<Json<IssueCredentialResponse>, ApiError> {
-    // Parse tier
-    let tier = ZecTier::try_from(req.tier).map_err(|_| ApiError {
-        status: StatusCode::BAD_REQUEST,
-        message: format!("Invalid tier: {}", req.tier),
-        code: "INVALID_TIER".into(),
-    })?;
+    let asset_code = req.asset_code.as_deref().unwrap_or(ZEC_ASSET_CODE);
+    let asset_tier = resolve_asset_tier(&state.asset_tiers, asset_code, req.tier, req.balance_raw)?;
+    let tier = asset_tier.tier();
 
     // Parse hex values
     let account_tag = parse_hex32(&req.account_tag)?;
@@ -695,6 +955,7 @@ async fn issue_zec_credential(
     let credential = CredentialBuilder::new()
         .account_tag(account_tag)
         .tier(tier)
+        .asset_code(asset_tier.asset_code.clone())
         .state_root(state_root)
         .block_height(req.block_height)
         .proof_commitment(proof_commitment)
@@ -712,12 +973,44 @@ async fn issue_zec_credential(
     Ok(Json(IssueCredentialResponse {
         success: true,
         credential_id: Some(credential_id),
-        tier: Some(tier.name().to_string()),
+        tier: Some(asset_tier.name()),
+        asset_code: Some(asset_tier.asset_code.clone()),
+        threshold_raw: Some(asset_tier.threshold_raw),
         expires_at: Some(expires_at),
         error: None,
     }))
 }
 
+fn resolve_asset_tier<'a>(
+    tiers: &'a AssetTierTable,
+    asset_code: &str,
+    tier: Option<u8>,
+    balance_raw: Option<u64>,
+) -> Result<&'a AssetTier, ApiError> {
+    let invalid = |message: String| ApiError {
+        status: StatusCode::BAD_REQUEST,
+        message,
+        code: ErrorCode::InvalidTier,
+    };
+    match (tier, balance_raw) {
+        (Some(tier), _) => ZecTier::try_from(tier)
+            .ok()
+            .and_then(|tier| tiers.tier(asset_code, tier))
+            .ok_or_else(|| invalid(format!("Invalid tier {} for {}", tier, asset_code))),
+        (None, Some(balance_raw)) => {
+            tiers
+                .tier_from_balance(asset_code, balance_raw)
+                .ok_or_else(|| {
+                    invalid(format!(
+                        "Balance {} meets no {} tier",
+                        balance_raw, asset_code
+                    ))
+                })
+        }
+        (None, None) => Err(invalid("Either tier or balance_raw is required".into())),
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct BroadcastCredentialRequest {
     /// Credential ID (hex-encoded)
@@ -743,7 +1036,7 @@ async fn broadcast_zec_credential(
     let credential = credentials.get(&req.credential_id).cloned().ok_or_else(|| ApiError {
         status: StatusCode::NOT_FOUND,
         message: "Credential not found".into(),
-        code: "CREDENTIAL_NOT_FOUND".into(),
+        code: ErrorCode::CredentialNotFound,
     })?;
     drop(credentials);
 
@@ -752,7 +1045,7 @@ async fn broadcast_zec_credential(
         return Err(ApiError {
             status: StatusCode::FORBIDDEN,
             message: "Credential has been revoked".into(),
-            code: "CREDENTIAL_REVOKED".into(),
+            code: ErrorCode::CredentialRevoked,
         });
     }
 
@@ -789,7 +1082,7 @@ async fn broadcast_zec_to_chain(
     let credential = credentials.get(&req.credential_id).cloned().ok_or_else(|| ApiError {
         status: StatusCode::NOT_FOUND,
         message: "Credential not found".into(),
-        code: "CREDENTIAL_NOT_FOUND".into(),
+        code: ErrorCode::CredentialNotFound,
     })?;
     drop(credentials);
 
@@ -824,12 +1117,16 @@ pub struct RevokeCredentialRequest {
     pub reason: u8,
     /// Optional: broadcast revocation to chains
     pub broadcast: Option<bool>,
+    /// Optional: re-broadcast even if the credential is already revoked
+    pub force: Option<bool>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct RevokeCredentialResponse {
     pub success: bool,
     pub chains_notified: Vec<String>,
+    /// True if the credential was revoked by an earlier call
+    pub already_revoked: bool,
     pub error: Option<String>,
 }
 
@@ -837,41 +1134,292 @@ async fn revoke_credential(
     State(state): State<AppState>,
     Json(req): Json<RevokeCredentialRequest>,
 ) -> Result<Json<RevokeCredentialResponse>, ApiError> {
-    let reason = match req.reason {
-        0 => RevocationReason::UserRequested,
-        1 => RevocationReason::BalanceDropped,
-        2 => RevocationReason::FraudAttempt,
-        3 => RevocationReason::Expired,
-        4 => RevocationReason::PolicyUpdate,
-        _ => return Err(ApiError {
-            status: StatusCode::BAD_REQUEST,
-            message: format!("Invalid revocation reason: {}", req.reason),
-            code: "INVALID_REASON".into(),
-        }),
+    let reason = parse_revocation_reason(req.reason)?;
+
+    // Mark as revoked, keeping the first recorded reason
+    let (reason, already_revoked) = {
+        let mut revoked = state.revoked_credentials.write().await;
+        match revoked.get(&req.credential_id) {
+            Some(existing) => (*existing, true),
+            None => {
+                revoked.insert(req.credential_id.clone(), reason);
+                (reason, false)
+            }
+        }
     };
 
-    // Mark as revoked
-    state.revoked_credentials.write().await.insert(req.credential_id.clone(), reason);
+    // Repeated revocations are a no-op unless explicitly forced
+    if already_revoked && !req.force.unwrap_or(false) {
+        return Ok(Json(RevokeCredentialResponse {
+            success: true,
+            chains_notified: Vec::new(),
+            already_revoked: true,
+            error: None,
+        }));
+    }
 
     let mut chains_notified = Vec::new();
 
     // Broadcast revocation if requested
     if req.broadcast.unwrap_or(true) {
         let bridge = state.zcash_bridge.read().await;
-        let subs = bridge.config.active_subscriptions();
+        chains_notified = broadcast_revocation(&bridge, &req.credential_id, reason);
+    }
 
-        // Parse credential ID
-        if let Ok(cred_id_bytes) = parse_hex32(&req.credential_id) {
-            for sub in subs {
-                if let Ok(_payload) = bridge.encode_revocation(cred_id_bytes, reason, &sub.chain_name) {
-                    chains_notified.push(sub.chain_name.clone());
+    Ok(Json(RevokeCredentialResponse {
+        success: true,
+        chains_notified,
+        already_revoked,
+        error: None,
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RevokeAccountRequest {
+    /// Account tag whose credentials should be revoked (hex-encoded 32 bytes)
+    pub account_tag: String,
+    /// Revocation reason (0-4)
+    pub reason: u8,
+    /// Optional: broadcast revocations to chains
+    pub broadcast: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RevokeAccountResponse {
+    pub success: bool,
+    /// Credentials revoked by this call, sorted by ID
+    pub revoked_credentials: Vec<String>,
+    pub chains_notified: Vec<String>,
+    pub error: Option<String>,
+}
+
+/// Revoke every active credential issued to an account tag.
+///
+/// Expired and already-revoked credentials are left alone, so repeating the
+/// call only picks up credentials issued since.
+async fn revoke_account(
+    State(state): State<AppState>,
+    Json(req): Json<RevokeAccountRequest>,
+) -> Result<Json<RevokeAccountResponse>, ApiError> {
+    let account_tag = parse_hex32(&req.account_tag)?;
+    let reason = parse_revocation_reason(req.reason)?;
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+
+    let revoked_credentials = {
+        let credentials = state.credentials.read().await;
+        let mut revoked = state.revoked_credentials.write().await;
+        let mut ids: Vec<String> = credentials
+            .iter()
+            .filter(|(id, c)| {
+                c.account_tag == account_tag && now < c.expires_at && !revoked.contains_key(*id)
+            })
+            .map(|(id, _)| id.clone())
+            .collect();
+        ids.sort();
+        for id in &ids {
+            revoked.insert(id.clone(), reason);
+        }
+        ids
+    };
+
+    let mut chains_notified = Vec::new();
+
+    if req.broadcast.unwrap_or(true) {
+        let bridge = state.zcash_bridge.read().await;
+        for id in &revoked_credentials {
+            for chain in broadcast_revocation(&bridge, id, reason) {
+                if !chains_notified.contains(&chain) {
+                    chains_notified.push(chain);
                 }
             }
         }
     }
 
-    Ok(Json(RevokeCredentialResponse {
+    Ok(Json(RevokeAccountResponse {
         success: true,
+        revoked_credentials,
+        chains_notified,
+        error: None,
+    }))
+}
+
+fn parse_revocation_reason(reason: u8) -> Result<RevocationReason, ApiError> {
+    match reason {
+        0 => Ok(RevocationReason::UserRequested),
+        1 => Ok(RevocationReason::BalanceDropped),
+        2 => Ok(RevocationReason::FraudAttempt),
+        3 => Ok(RevocationReason::Expired),
+        4 => Ok(RevocationReason::PolicyUpdate),
+        _ => Err(ApiError {
+            status: StatusCode::BAD_REQUEST,
+            message: format!("Invalid revocation reason: {}", reason),
+            code: ErrorCode::InvalidReason,
+        }),
+    }
+}
+
+/// Encode a revocation for every active subscription; returns the chains it
+/// was encoded for.
+fn broadcast_revocation(
+    bridge: &ZcashBridge,
+    credential_id: &str,
+    reason: RevocationReason,
+) -> Vec<String> {
+    let Ok(cred_id_bytes) = parse_hex32(credential_id) else {
+        return Vec::new();
+    };
+    bridge
+        .config
+        .active_subscriptions()
+        .into_iter()
+        .filter(|sub| {
+            bridge
+                .encode_revocation(cred_id_bytes, reason, &sub.chain_name)
+                .is_ok()
+        })
+        .map(|sub| sub.chain_name.clone())
+        .collect()
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateCredentialRequest {
+    /// Credential ID to amend (hex-encoded)
+    pub credential_id: String,
+    /// New balance tier (0-5)
+    pub new_tier: u8,
+    /// Fresh proof backing the new tier
+    pub bundle: ProofBundle,
+    /// Optional validity window override (seconds)
+    pub validity_window: Option<u64>,
+    /// Optional: broadcast the update to chains
+    pub broadcast: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateCredentialResponse {
+    pub success: bool,
+    pub credential_id: String,
+    pub tier: Option<String>,
+    pub expires_at: Option<u64>,
+    pub chains_notified: Vec<String>,
+    pub error: Option<String>,
+}
+
+/// Amend a credential's tier and expiry from a fresh proof.
+///
+/// The bundle must prove at least the new tier's threshold, be taken after
+/// the credential was issued, and carry a nullifier other than the one the
+/// credential was built from. The credential keeps its ID so destination
+/// contracts can amend it instead of revoking and re-issuing.
+async fn update_credential(
+    State(state): State<AppState>,
+    Json(req): Json<UpdateCredentialRequest>,
+) -> Result<Json<UpdateCredentialResponse>, ApiError> {
+    let new_tier = ZecTier::try_from(req.new_tier).map_err(|_| ApiError {
+        status: StatusCode::BAD_REQUEST,
+        message: format!("Invalid tier: {}", req.new_tier),
+        code: ErrorCode::InvalidTier,
+    })?;
+    let credential_id_bytes = parse_hex32(&req.credential_id)?;
+
+    if state
+        .revoked_credentials
+        .read()
+        .await
+        .contains_key(&req.credential_id)
+    {
+        return Err(ApiError {
+            status: StatusCode::FORBIDDEN,
+            message: "Credential has been revoked".into(),
+            code: ErrorCode::CredentialRevoked,
+        });
+    }
+
+    let public = &req.bundle.public_inputs;
+    let validity_window = req.validity_window.unwrap_or(state.validity_window);
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+
+    let (update, tier_name) = {
+        let mut credentials = state.credentials.write().await;
+        let credential = credentials
+            .get_mut(&req.credential_id)
+            .ok_or_else(|| ApiError {
+                status: StatusCode::NOT_FOUND,
+                message: "Credential not found".into(),
+                code: ErrorCode::CredentialNotFound,
+            })?;
+
+        // The new tier is measured in the credential's own asset.
+        let asset_tier = state
+            .asset_tiers
+            .tier(&credential.asset_code, new_tier)
+            .ok_or_else(|| ApiError {
+                status: StatusCode::BAD_REQUEST,
+                message: format!(
+                    "Invalid tier {} for {}",
+                    req.new_tier, credential.asset_code
+                ),
+                code: ErrorCode::InvalidTier,
+            })?;
+        if public.threshold_raw < asset_tier.threshold_raw {
+            return Err(ApiError {
+                status: StatusCode::BAD_REQUEST,
+                message: format!(
+                    "Proof threshold {} is below the {} tier",
+                    public.threshold_raw,
+                    asset_tier.name()
+                ),
+                code: ErrorCode::InsufficientProof,
+            });
+        }
+
+        if public.current_epoch < credential.issued_at
+            || public.nullifier == credential.proof_commitment
+        {
+            return Err(ApiError {
+                status: StatusCode::CONFLICT,
+                message: "Proof is not newer than the credential".into(),
+                code: ErrorCode::StaleProof,
+            });
+        }
+
+        credential.tier = new_tier;
+        credential.policy_id = tier_to_policy_id(new_tier);
+        credential.proof_commitment = public.nullifier;
+        credential.expires_at = now + validity_window;
+
+        let update = BalanceUpdate {
+            credential_id: credential_id_bytes,
+            new_tier,
+            expires_at: credential.expires_at,
+        };
+        (update, asset_tier.name())
+    };
+
+    let mut chains_notified = Vec::new();
+
+    // Broadcast the update if requested
+    if req.broadcast.unwrap_or(true) {
+        let bridge = state.zcash_bridge.read().await;
+        for sub in bridge.config.active_subscriptions() {
+            if let Ok(_payload) = bridge.encode_balance_update(&update, &sub.chain_name) {
+                chains_notified.push(sub.chain_name.clone());
+            }
+        }
+    }
+
+    Ok(Json(UpdateCredentialResponse {
+        success: true,
+        credential_id: req.credential_id,
+        tier: Some(tier_name),
+        expires_at: Some(update.expires_at),
         chains_notified,
         error: None,
     }))
@@ -885,7 +1433,7 @@ async fn get_credential(
     let credential = credentials.get(&credential_id).cloned().ok_or_else(|| ApiError {
         status: StatusCode::NOT_FOUND,
         message: "Credential not found".into(),
-        code: "CREDENTIAL_NOT_FOUND".into(),
+        code: ErrorCode::CredentialNotFound,
     })?;
     drop(credentials);
 
@@ -899,8 +1447,9 @@ async fn get_credential(
     Ok(Json(serde_json::json!({
         "credential_id": credential_id,
         "account_tag": hex::encode(credential.account_tag),
-        "tier": credential.tier.name(),
+        "tier": credential_tier_name(&state.asset_tiers, &credential),
         "tier_value": credential.tier.as_u8(),
+        "asset_code": credential.asset_code,
         "policy_id": credential.policy_id,
         "state_root": hex::encode(credential.state_root),
         "block_height": credential.block_height,
@@ -933,8 +1482,9 @@ async fn get_account_credentials(
             let is_revoked = revoked.contains_key(id);
             serde_json::json!({
                 "credential_id": id,
-                "tier": c.tier.name(),
+                "tier": credential_tier_name(&state.asset_tiers, c),
                 "tier_value": c.tier.as_u8(),
+                "asset_code": c.asset_code,
                 "issued_at": c.issued_at,
                 "expires_at": c.expires_at,
                 "revoked": is_revoked,
@@ -956,6 +1506,8 @@ pub struct CheckCredentialRequest {
     pub account_tag: String,
     /// Minimum tier required (0-5)
     pub min_tier: u8,
+    /// Asset the credential must be measured in (defaults to ZEC)
+    pub asset_code: Option<String>,
 }
 
 async fn check_zec_credential(
@@ -966,9 +1518,16 @@ async fn check_zec_credential(
     let min_tier = ZecTier::try_from(req.min_tier).map_err(|_| ApiError {
         status: StatusCode::BAD_REQUEST,
         message: format!("Invalid tier: {}", req.min_tier),
-        code: "INVALID_TIER".into(),
+        code: ErrorCode::InvalidTier,
     })?;
 
+    let asset_code = req
+        .asset_code
+        .as_deref()
+        .unwrap_or(ZEC_ASSET_CODE)
+        .trim()
+        .to_ascii_uppercase();
+
     let credentials = state.credentials.read().await;
     let revoked = state.revoked_credentials.read().await;
 
@@ -982,6 +1541,7 @@ async fn check_zec_credential(
         .iter()
         .filter(|(id, c)| {
             c.account_tag == account_tag_bytes
+                && c.asset_code == asset_code
                 && c.tier >= min_tier
                 && now < c.expires_at
                 && !revoked.contains_key(*id)
@@ -992,7 +1552,7 @@ async fn check_zec_credential(
         Some((id, c)) => Ok(Json(serde_json::json!({
             "has_credential": true,
             "credential_id": id,
-            "tier": c.tier.name(),
+            "tier": credential_tier_name(&state.asset_tiers, c),
             "tier_value": c.tier.as_u8(),
             "expires_at": c.expires_at,
             "time_remaining": c.expires_at - now
@@ -1018,6 +1578,12 @@ async fn list_tiers() -> impl IntoResponse {
     }))
 }
 
+async fn list_asset_tiers(State(state): State<AppState>) -> impl IntoResponse {
+    Json(serde_json::json!({
+        "tiers": state.asset_tiers.tiers()
+    }))
+}
+
 async fn get_bridge_stats(State(state): State<AppState>) -> impl IntoResponse {
     let bridge = state.zcash_bridge.read().await;
     let stats = bridge.stats();
@@ -1040,7 +1606,7 @@ async fn get_pending_broadcasts(State(state): State<AppState>) -> impl IntoRespo
         .map(|p| serde_json::json!({
             "broadcast_id": hex::encode(p.broadcast_id),
             "account_tag": hex::encode(p.credential.account_tag),
-            "tier": p.credential.tier.name(),
+            "tier": credential_tier_name(&state.asset_tiers, &p.credential),
             "target_chains": p.target_chains,
             "queued_at": p.queued_at,
             "chain_status": p.chain_status.iter().map(|(c, s)| {
@@ -1066,7 +1632,7 @@ async fn get_pending_broadcasts(State(state): State<AppState>) -> impl IntoRespo
 pub struct ApiError {
     pub status: StatusCode,
     pub message: String,
-    pub code: String,
+    pub code: ErrorCode,
 }
 
 impl ApiError {
@@ -1074,7 +1640,7 @@ impl ApiError {
         Self {
             status: StatusCode::BAD_REQUEST,
             message: err.to_string(),
-            code: "GMP_ERROR".into(),
+            code: ErrorCode::GmpError,
         }
     }
 }
@@ -1093,19 +1659,27 @@ impl IntoResponse for ApiError {
 // HELPERS
 // ═══════════════════════════════════════════════════════════════════════════════
 
+/// Display name of a credential's tier, measured in its own asset
+fn credential_tier_name(tiers: &AssetTierTable, credential: &ZecCredential) -> String {
+    tiers
+        .tier(&credential.asset_code, credential.tier)
+        .map(AssetTier::name)
+        .unwrap_or_else(|| credential.tier.name().to_string())
+}
+
 fn parse_hex32(hex: &str) -> Result<[u8; 32], ApiError> {
     let hex = hex.strip_prefix("0x").unwrap_or(hex);
     let bytes = hex::decode(hex).map_err(|e| ApiError {
         status: StatusCode::BAD_REQUEST,
         message: format!("Invalid hex: {}", e),
-        code: "INVALID_HEX".into(),
+        code: ErrorCode::InvalidHex,
     })?;
 
     if bytes.len() != 32 {
         return Err(ApiError {
             status: StatusCode::BAD_REQUEST,
             message: format!("Expected 32 bytes, got {}", bytes.len()),
-            code: "INVALID_LENGTH".into(),
+            code: ErrorCode::InvalidLength,
         });
     }
 
@@ -1207,5 +1781,289 @@ mod tests {
         assert!(body.success);
         assert!(body.chains_broadcast.contains(&"osmosis".to_string()));
     }
-}
 
+    #[tokio::test]
+    async fn test_receive_rejects_tampered_receipt() {
+        let server = TestServer::new(app_router()).unwrap();
+        let receipt = PoFReceipt::new(
+            [1u8; 32], 271828, [2u8; 32], 1, [3u8; 32], 86400, 1700000000,
+        );
+        let mut payload = GmpMessage::receipt(receipt).unwrap().encode();
+        let receive = |payload: &[u8]| {
+            server
+                .post("/rails/axelar/receive")
+                .json(&serde_json::json!({
+                    "source_chain": "ethereum",
+                    "source_address": "0xabc",
+                    "payload": hex::encode(payload),
+                }))
+        };
+
+        receive(&payload).await.assert_status_ok();
+
+        // Change the attestation hash but keep the original receipt hash
+        payload[1 + 4 * 32] ^= 0xff;
+        let response = receive(&payload).await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["error_code"], "RECEIPT_HASH_MISMATCH");
+    }
+
+    #[tokio::test]
+    async fn test_revoke_is_idempotent() {
+        let server = TestServer::new(app_router()).unwrap();
+        let credential_id = format!("0x{}", "04".repeat(32));
+
+        let first = server
+            .post("/rails/axelar/zec/revoke")
+            .json(&serde_json::json!({ "credential_id": credential_id, "reason": 0 }))
+            .await;
+        first.assert_status_ok();
+        let body: RevokeCredentialResponse = first.json();
+        assert!(body.success);
+        assert!(!body.already_revoked);
+
+        // A second call, even with a different reason, is a no-op
+        let second = server
+            .post("/rails/axelar/zec/revoke")
+            .json(&serde_json::json!({ "credential_id": credential_id, "reason": 2 }))
+            .await;
+        second.assert_status_ok();
+        let body: RevokeCredentialResponse = second.json();
+        assert!(body.success);
+        assert!(body.already_revoked);
+        assert!(body.chains_notified.is_empty());
+
+        // Forcing goes through the broadcast path but still reports the prior revocation
+        let forced = server
+            .post("/rails/axelar/zec/revoke")
+            .json(&serde_json::json!({
+                "credential_id": credential_id,
+                "reason": 2,
+                "force": true
+            }))
+            .await;
+        forced.assert_status_ok();
+        let body: RevokeCredentialResponse = forced.json();
+        assert!(body.success);
+        assert!(body.already_revoked);
+    }
+
+    #[tokio::test]
+    async fn test_revoke_account_revokes_only_that_account() {
+        let server = TestServer::new(app_router()).unwrap();
+        let account_tag = format!("0x{}", "05".repeat(32));
+        let other_tag = format!("0x{}", "06".repeat(32));
+
+        let mut issued = Vec::new();
+        for (tag, commitment) in [
+            (&account_tag, "07"),
+            (&account_tag, "08"),
+            (&other_tag, "09"),
+        ] {
+            let response = server
+                .post("/rails/axelar/zec/issue")
+                .json(&serde_json::json!({
+                    "account_tag": tag,
+                    "tier": 1,
+                    "state_root": format!("0x{}", "0a".repeat(32)),
+                    "block_height": 2_000_000,
+                    "proof_commitment": format!("0x{}", commitment.repeat(32)),
+                    "attestation_hash": format!("0x{}", "0b".repeat(32))
+                }))
+                .await;
+            response.assert_status_ok();
+            let body: serde_json::Value = response.json();
+            issued.push(body["credential_id"].as_str().unwrap().to_string());
+        }
+
+        let response = server
+            .post("/rails/axelar/zec/revoke-account")
+            .json(&serde_json::json!({ "account_tag": account_tag, "reason": 2 }))
+            .await;
+        response.assert_status_ok();
+        let body: RevokeAccountResponse = response.json();
+        let mut expected = issued[..2].to_vec();
+        expected.sort();
+        assert!(body.success);
+        assert_eq!(body.revoked_credentials, expected);
+
+        let other = server
+            .get(&format!("/rails/axelar/zec/credential/{}", issued[2]))
+            .await;
+        let other: serde_json::Value = other.json();
+        assert_eq!(other["revoked"], false);
+
+        // Nothing left to revoke on a repeat call
+        let again = server
+            .post("/rails/axelar/zec/revoke-account")
+            .json(&serde_json::json!({ "account_tag": account_tag, "reason": 2 }))
+            .await;
+        let body: RevokeAccountResponse = again.json();
+        assert!(body.revoked_credentials.is_empty());
+        assert!(body.chains_notified.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_issue_credential_for_configured_asset() {
+        let asset_tiers = AssetTierTable::new(vec![
+            AssetTier {
+                asset_code: "USDC".into(),
+                tier_index: 0,
+                threshold_raw: 100_000_000,
+            },
+            AssetTier {
+                asset_code: "USDC".into(),
+                tier_index: 1,
+                threshold_raw: 1_000_000_000,
+            },
+        ])
+        .unwrap();
+        let state = AppState {
+            asset_tiers: Arc::new(asset_tiers),
+            ..AppState::default()
+        };
+        let server = TestServer::new(app_router_with_state(state)).unwrap();
+        let account_tag = format!("0x{}", "0c".repeat(32));
+        let issue =
+            |asset: serde_json::Value, tier: serde_json::Value, balance: serde_json::Value| {
+                serde_json::json!({
+                    "account_tag": account_tag,
+                    "asset_code": asset,
+                    "tier": tier,
+                    "balance_raw": balance,
+                    "state_root": format!("0x{}", "0d".repeat(32)),
+                    "block_height": 2_000_000,
+                    "proof_commitment": format!("0x{}", "0e".repeat(32)),
+                    "attestation_hash": format!("0x{}", "0f".repeat(32))
+                })
+            };
+
+        // The tier is derived from the USDC table, not the ZEC thresholds.
+        let response = server
+            .post("/rails/axelar/issue")
+            .json(&issue(
+                "usdc".into(),
+                serde_json::Value::Null,
+                2_000_000_000u64.into(),
+            ))
+            .await;
+        response.assert_status_ok();
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["asset_code"], "USDC");
+        assert_eq!(body["threshold_raw"], 1_000_000_000u64);
+
+        // USDC has no tier 5, while ZEC keeps its defaults.
+        let response = server
+            .post("/rails/axelar/issue")
+            .json(&issue("USDC".into(), 5.into(), serde_json::Value::Null))
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let response = server
+            .post("/rails/axelar/zec/issue")
+            .json(&issue(
+                serde_json::Value::Null,
+                5.into(),
+                serde_json::Value::Null,
+            ))
+            .await;
+        response.assert_status_ok();
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["asset_code"], ZEC_ASSET_CODE);
+        assert_eq!(body["tier"], "10000+ ZEC");
+
+        // Checks only consider credentials of the requested asset.
+        let check = server
+            .post("/rails/axelar/zec/check")
+            .json(&serde_json::json!({
+                "account_tag": account_tag,
+                "min_tier": 1,
+                "asset_code": "USDC"
+            }))
+            .await;
+        let check: serde_json::Value = check.json();
+        assert_eq!(check["has_credential"], true);
+        assert_eq!(check["tier_value"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_check_pof_reverifies_linked_proof() {
+        let server = TestServer::new(app_router()).unwrap();
+        server
+            .post("/rails/axelar/subscribe")
+            .json(&serde_json::json!({
+                "chain_name": "osmosis",
+                "receiver_contract": "osmo1abc..."
+            }))
+            .await
+            .assert_status_ok();
+
+        let holder_id = format!("0x{}", "10".repeat(32));
+        let bundle = |proof: Vec<u8>, policy_id: u64| {
+            serde_json::json!({
+                "circuit_version": 5,
+                "proof": proof,
+                "public_inputs": {
+                    "threshold_raw": 1000,
+                    "required_currency_code": 840,
+                    "current_epoch": 1_700_000_000,
+                    "verifier_scope_id": 1,
+                    "policy_id": policy_id,
+                    "nullifier": [7u8; 32],
+                    "custodian_pubkey_hash": [8u8; 32]
+                }
+            })
+        };
+        let broadcast = |policy_id: u64| {
+            serde_json::json!({
+                "holder_id": holder_id,
+                "policy_id": 271828,
+                "snapshot_id": format!("0x{}", "11".repeat(32)),
+                "attestation_hash": format!("0x{}", "12".repeat(32)),
+                "bundle": bundle(vec![1, 2, 3], policy_id)
+            })
+        };
+
+        // A bundle for another policy cannot back the receipt.
+        let response = server
+            .post("/rails/axelar/broadcast")
+            .json(&broadcast(1))
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+
+        let response = server
+            .post("/rails/axelar/broadcast")
+            .json(&broadcast(271828))
+            .await;
+        response.assert_status_ok();
+
+        let check = |proof: Vec<u8>| {
+            serde_json::json!({
+                "holder_id": holder_id,
+                "policy_id": 271828,
+                "verify": bundle(proof, 271828)
+            })
+        };
+        let response = server
+            .post("/rails/axelar/check-pof")
+            .json(&check(vec![9, 9, 9]))
+            .await;
+        let body: CheckPoFResponse = response.json();
+        assert!(body.has_pof);
+        let proof = body.proof.unwrap();
+        assert!(!proof.valid);
+        assert_eq!(proof.error_code, Some(ErrorCode::ReceiptProofMismatch));
+
+        // The linked proof gets as far as verification, which is not configured here.
+        let response = server
+            .post("/rails/axelar/check-pof")
+            .json(&check(vec![1, 2, 3]))
+            .await;
+        let body: CheckPoFResponse = response.json();
+        assert!(body.receipt.unwrap().proof_hash.is_some());
+        assert_eq!(
+            body.proof.unwrap().error_code,
+            Some(ErrorCode::ArtifactNotFound)
+        );
+    }
+}
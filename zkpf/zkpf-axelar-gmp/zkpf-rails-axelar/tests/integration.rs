@@ -557,6 +557,108 @@ async fn test_broadcast_revoked_credential_fails() {
     assert_eq!(body["error_code"], "CREDENTIAL_REVOKED");
 }
 
+fn update_bundle(threshold_raw: u64, current_epoch: u64, nullifier: u8) -> serde_json::Value {
+    let nullifier = vec![nullifier; 32];
+    let custodian_pubkey_hash = vec![0u8; 32];
+    json!({
+        "rail_id": "ZCASH_ORCHARD",
+        "circuit_version": 1,
+        "proof": [],
+        "public_inputs": {
+            "threshold_raw": threshold_raw,
+            "required_currency_code": 999001,
+            "current_epoch": current_epoch,
+            "verifier_scope_id": 1,
+            "policy_id": 400003,
+            "nullifier": nullifier,
+            "custodian_pubkey_hash": custodian_pubkey_hash
+        }
+    })
+}
+
+#[tokio::test]
+async fn test_update_credential_tier() {
+    let server = create_test_server();
+
+    let response = server
+        .post("/rails/axelar/zec/issue")
+        .json(&json!({
+            "account_tag": random_hex32(),
+            "tier": 2,
+            "state_root": random_hex32(),
+            "block_height": 2000000,
+            "proof_commitment": random_hex32(),
+            "attestation_hash": random_hex32()
+        }))
+        .await;
+    response.assert_status_ok();
+
+    let issue_body: serde_json::Value = response.json();
+    let credential_id = issue_body["credential_id"].as_str().unwrap();
+
+    // A proof below the requested tier is rejected
+    let response = server
+        .post("/rails/axelar/zec/update")
+        .json(&json!({
+            "credential_id": credential_id,
+            "new_tier": 3,
+            "bundle": update_bundle(1_000_000_000, 4_102_444_800, 9),
+            "broadcast": false
+        }))
+        .await;
+    response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["error_code"], "INSUFFICIENT_PROOF");
+
+    // A proof taken before the credential was issued is stale
+    let response = server
+        .post("/rails/axelar/zec/update")
+        .json(&json!({
+            "credential_id": credential_id,
+            "new_tier": 3,
+            "bundle": update_bundle(10_000_000_000, 1_000, 9),
+            "broadcast": false
+        }))
+        .await;
+    response.assert_status(axum::http::StatusCode::CONFLICT);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["error_code"], "STALE_PROOF");
+
+    // A fresh proof amends the credential in place
+    let response = server
+        .post("/rails/axelar/zec/update")
+        .json(&json!({
+            "credential_id": credential_id,
+            "new_tier": 3,
+            "bundle": update_bundle(10_000_000_000, 4_102_444_800, 9),
+            "broadcast": false
+        }))
+        .await;
+    response.assert_status_ok();
+    let body: serde_json::Value = response.json();
+    assert!(body["success"].as_bool().unwrap());
+    assert_eq!(body["credential_id"], credential_id);
+
+    let response = server
+        .get(&format!("/rails/axelar/zec/credential/{}", credential_id))
+        .await;
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["tier_value"], 3);
+    assert!(body["is_valid"].as_bool().unwrap());
+
+    // Replaying the same proof is rejected
+    let response = server
+        .post("/rails/axelar/zec/update")
+        .json(&json!({
+            "credential_id": credential_id,
+            "new_tier": 3,
+            "bundle": update_bundle(10_000_000_000, 4_102_444_800, 9),
+            "broadcast": false
+        }))
+        .await;
+    response.assert_status(axum::http::StatusCode::CONFLICT);
+}
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // BRIDGE STATS TESTS
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -616,6 +718,53 @@ async fn test_estimate_gas() {
     assert!(body["estimates"].as_object().is_some());
 }
 
+#[tokio::test]
+async fn test_update_subscription_gas() {
+    let server = create_test_server();
+
+    server
+        .post("/rails/axelar/subscribe")
+        .json(&json!({
+            "chain_name": "ethereum",
+            "receiver_contract": "0xethereumreceiver"
+        }))
+        .await;
+
+    let response = server
+        .patch("/rails/axelar/subscriptions/ethereum")
+        .json(&json!({ "default_gas": 750_000 }))
+        .await;
+    response.assert_status_ok();
+
+    // Re-subscribing without a gas value keeps the override
+    server
+        .post("/rails/axelar/subscribe")
+        .json(&json!({
+            "chain_name": "ethereum",
+            "receiver_contract": "0xnewreceiver"
+        }))
+        .await;
+
+    let response = server
+        .post("/rails/axelar/estimate-gas")
+        .json(&json!({}))
+        .await;
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["estimates"]["ethereum"], 750_000);
+
+    let response = server
+        .patch("/rails/axelar/subscriptions/ethereum")
+        .json(&json!({ "default_gas": 1 }))
+        .await;
+    response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+
+    let response = server
+        .patch("/rails/axelar/subscriptions/arbitrum")
+        .json(&json!({ "default_gas": 750_000 }))
+        .await;
+    response.assert_status(axum::http::StatusCode::NOT_FOUND);
+}
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // END-TO-END FLOW TEST
 // ═══════════════════════════════════════════════════════════════════════════════
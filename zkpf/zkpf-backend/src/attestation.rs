@@ -0,0 +1,365 @@
+//! On-chain attestation backends.
+//!
+//! After `/zkpf/attest` verifies a bundle it records the result in an
+//! `AttestationRegistry` contract through an [`AttestationBackend`];
+//! `/zkpf/attest-batch` records several verified bundles with one
+//! [`AttestationBackend::attest_batch`] call. The backend is chosen from the
+//! environment:
+//!
+//! - `ZKPF_ATTESTATION_ENABLED` unset or false: no backend, and `/zkpf/attest`
+//!   answers `ATTESTATION_DISABLED`.
+//! - enabled, built with the `onchain-attestation` feature:
+//!   [`EthersAttestationBackend`] submits `attest(...)` transactions using
+//!   `ZKPF_ATTESTATION_RPC_URL`, `ZKPF_ATTESTATION_CHAIN_ID`,
+//!   `ZKPF_ATTESTATION_REGISTRY_ADDRESS` and `ZKPF_ATTESTOR_PRIVATE_KEY`.
+//! - enabled, built without it: [`NoopAttestationBackend`], which fails every
+//!   request with an explanatory error.
+//!
+//! Embedders and tests can install their own backend with
+//! [`crate::AppState::with_attestation_backend`].
+
+use std::{env, sync::Arc};
+
+use async_trait::async_trait;
+
+const ATTESTATION_ENABLED_ENV: &str = "ZKPF_ATTESTATION_ENABLED";
+const ATTESTATION_RPC_URL_ENV: &str = "ZKPF_ATTESTATION_RPC_URL";
+const ATTESTATION_CHAIN_ID_ENV: &str = "ZKPF_ATTESTATION_CHAIN_ID";
+const ATTESTATION_REGISTRY_ADDRESS_ENV: &str = "ZKPF_ATTESTATION_REGISTRY_ADDRESS";
+const ATTESTOR_PRIVATE_KEY_ENV: &str = "ZKPF_ATTESTOR_PRIVATE_KEY";
+
+/// Outcome of a recorded attestation.
+#[derive(Clone, Debug)]
+pub struct OnchainAttestationResult {
+    pub tx_hash: String,
+    pub attestation_id: String,
+    pub chain_id: u64,
+}
+
+/// One attestation to record, with hashed holder and snapshot identifiers.
+#[derive(Clone, Copy, Debug)]
+pub struct AttestationEntry {
+    pub holder_id: [u8; 32],
+    pub policy_id: u64,
+    pub snapshot_id: [u8; 32],
+    pub nullifier: [u8; 32],
+}
+
+/// Records verified proofs in an on-chain attestation registry.
+#[async_trait]
+pub trait AttestationBackend: Send + Sync {
+    /// Record an attestation for the hashed holder and snapshot identifiers.
+    async fn attest(
+        &self,
+        holder_id: [u8; 32],
+        policy_id: u64,
+        snapshot_id: [u8; 32],
+        nullifier: [u8; 32],
+    ) -> Result<OnchainAttestationResult, String>;
+
+    /// Record several attestations, returning one result per entry in order.
+    ///
+    /// Backends that can write a batch in a single transaction should override
+    /// this. The default records each entry with [`Self::attest`] and stops at
+    /// the first failure, leaving the earlier entries recorded.
+    async fn attest_batch(
+        &self,
+        entries: &[AttestationEntry],
+    ) -> Result<Vec<OnchainAttestationResult>, String> {
+        let mut results = Vec::with_capacity(entries.len());
+        for entry in entries {
+            results.push(
+                self.attest(
+                    entry.holder_id,
+                    entry.policy_id,
+                    entry.snapshot_id,
+                    entry.nullifier,
+                )
+                .await?,
+            );
+        }
+        Ok(results)
+    }
+}
+
+/// Placeholder for builds without an on-chain client; every call fails.
+#[derive(Clone, Debug, Default)]
+pub struct NoopAttestationBackend;
+
+#[async_trait]
+impl AttestationBackend for NoopAttestationBackend {
+    async fn attest(
+        &self,
+        _holder_id: [u8; 32],
+        _policy_id: u64,
+        _snapshot_id: [u8; 32],
+        _nullifier: [u8; 32],
+    ) -> Result<OnchainAttestationResult, String> {
+        Err("on-chain attestation is not available in this build".into())
+    }
+}
+
+#[cfg_attr(not(feature = "onchain-attestation"), allow(dead_code))]
+struct AttestationConfig {
+    rpc_url: String,
+    chain_id: u64,
+    registry_address: String,
+    private_key: String,
+}
+
+impl AttestationConfig {
+    /// Read the attestation settings, listing every missing or invalid one.
+    fn from_env() -> Result<Self, Vec<String>> {
+        let mut config_warnings = Vec::new();
+
+        let rpc_url = non_empty_env(ATTESTATION_RPC_URL_ENV);
+        if rpc_url.is_none() {
+            config_warnings.push(format!("{} is missing", ATTESTATION_RPC_URL_ENV));
+        }
+
+        let chain_id = env::var(ATTESTATION_CHAIN_ID_ENV)
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok());
+        if chain_id.is_none() {
+            config_warnings.push(format!(
+                "{} is missing or invalid",
+                ATTESTATION_CHAIN_ID_ENV
+            ));
+        }
+
+        let registry_address = non_empty_env(ATTESTATION_REGISTRY_ADDRESS_ENV);
+        if registry_address.is_none() {
+            config_warnings.push(format!("{} is missing", ATTESTATION_REGISTRY_ADDRESS_ENV));
+        }
+
+        let private_key = non_empty_env(ATTESTOR_PRIVATE_KEY_ENV);
+        if private_key.is_none() {
+            config_warnings.push(format!("{} is missing", ATTESTOR_PRIVATE_KEY_ENV));
+        }
+
+        match (rpc_url, chain_id, registry_address, private_key) {
+            (Some(rpc_url), Some(chain_id), Some(registry_address), Some(private_key)) => {
+                Ok(Self {
+                    rpc_url,
+                    chain_id,
+                    registry_address,
+                    private_key,
+                })
+            }
+            _ => Err(config_warnings),
+        }
+    }
+}
+
+fn non_empty_env(name: &str) -> Option<String> {
+    env::var(name)
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// Build the backend selected by the environment (see the module docs).
+pub fn attestation_backend_from_env() -> Option<Arc<dyn AttestationBackend>> {
+    let enabled = env::var(ATTESTATION_ENABLED_ENV)
+        .ok()
+        .map(|v| v.to_ascii_lowercase())
+        .map(|v| matches!(v.as_str(), "1" | "true" | "yes"))
+        .unwrap_or(false);
+    if !enabled {
+        return None;
+    }
+
+    let config = AttestationConfig::from_env();
+    if let Err(config_warnings) = &config {
+        eprintln!(
+            "Attestation config is incomplete: {}",
+            config_warnings.join(", ")
+        );
+    }
+
+    #[cfg(feature = "onchain-attestation")]
+    {
+        let config = config.ok()?;
+        match EthersAttestationBackend::new(&config) {
+            Ok(backend) => Some(Arc::new(backend)),
+            Err(err) => {
+                eprintln!("on-chain attestation is disabled: {}", err);
+                None
+            }
+        }
+    }
+
+    #[cfg(not(feature = "onchain-attestation"))]
+    {
+        let _ = config;
+        eprintln!(
+            "ATTESTATION_ENABLED is set, but this build does not ship the on-chain \
+             attestation client. Disable {} or build with the `onchain-attestation` \
+             feature to submit attestations on-chain.",
+            ATTESTATION_ENABLED_ENV
+        );
+        Some(Arc::new(NoopAttestationBackend))
+    }
+}
+
+#[cfg(feature = "onchain-attestation")]
+pub use ethers_backend::EthersAttestationBackend;
+
+#[cfg(feature = "onchain-attestation")]
+mod ethers_backend {
+    use std::sync::Arc;
+
+    use async_trait::async_trait;
+    use ethers::{
+        abi::Token,
+        prelude::*,
+        types::{Address, Bytes, H256, U256, U64},
+        utils::keccak256,
+    };
+
+    use super::{
+        AttestationBackend, AttestationConfig, AttestationEntry, OnchainAttestationResult,
+    };
+
+    const ATTEST_SIGNATURE: &str = "attest(bytes32,uint256,bytes32,bytes32)";
+    const ATTEST_BATCH_SIGNATURE: &str = "attestBatch(bytes32[],uint256[],bytes32[],bytes32[])";
+    const ATTESTED_EVENT_SIGNATURE: &str =
+        "Attested(bytes32,bytes32,uint256,bytes32,bytes32,address)";
+
+    /// Submits attestations to an `AttestationRegistry` over JSON-RPC.
+    pub struct EthersAttestationBackend {
+        client: Arc<SignerMiddleware<Provider<Http>, LocalWallet>>,
+        registry: Address,
+        chain_id: u64,
+    }
+
+    impl EthersAttestationBackend {
+        pub(super) fn new(config: &AttestationConfig) -> Result<Self, String> {
+            let provider = Provider::<Http>::try_from(config.rpc_url.as_str())
+                .map_err(|err| format!("invalid attestation RPC URL: {err}"))?;
+            let wallet = config
+                .private_key
+                .parse::<LocalWallet>()
+                .map_err(|_| "invalid attestor private key".to_string())?
+                .with_chain_id(config.chain_id);
+            let registry = config
+                .registry_address
+                .parse::<Address>()
+                .map_err(|err| format!("invalid attestation registry address: {err}"))?;
+
+            Ok(Self {
+                client: Arc::new(SignerMiddleware::new(provider, wallet)),
+                registry,
+                chain_id: config.chain_id,
+            })
+        }
+
+        /// Send `attest`/`attestBatch` calldata to the registry and return the
+        /// transaction hash with the attestation IDs of its `Attested` events.
+        async fn submit(&self, calldata: Vec<u8>) -> Result<(H256, Vec<H256>), String> {
+            let tx = TransactionRequest::new()
+                .to(self.registry)
+                .data(Bytes::from(calldata));
+            let pending = self
+                .client
+                .send_transaction(tx, None)
+                .await
+                .map_err(|err| format!("failed to submit attestation: {err}"))?;
+            let receipt = pending
+                .await
+                .map_err(|err| format!("failed to confirm attestation: {err}"))?
+                .ok_or_else(|| "attestation transaction was dropped".to_string())?;
+            if receipt.status != Some(U64::from(1)) {
+                return Err(format!(
+                    "attestation transaction {:?} reverted",
+                    receipt.transaction_hash
+                ));
+            }
+
+            // attestationId is the first indexed topic of the Attested event.
+            let event_topic = H256::from(keccak256(ATTESTED_EVENT_SIGNATURE));
+            let attestation_ids = receipt
+                .logs
+                .iter()
+                .filter(|log| log.address == self.registry)
+                .filter(|log| log.topics.first() == Some(&event_topic))
+                .filter_map(|log| log.topics.get(1).copied())
+                .collect();
+            Ok((receipt.transaction_hash, attestation_ids))
+        }
+
+        fn result(&self, tx_hash: H256, attestation_id: H256) -> OnchainAttestationResult {
+            OnchainAttestationResult {
+                tx_hash: format!("{:?}", tx_hash),
+                attestation_id: format!("{:?}", attestation_id),
+                chain_id: self.chain_id,
+            }
+        }
+    }
+
+    #[async_trait]
+    impl AttestationBackend for EthersAttestationBackend {
+        async fn attest(
+            &self,
+            holder_id: [u8; 32],
+            policy_id: u64,
+            snapshot_id: [u8; 32],
+            nullifier: [u8; 32],
+        ) -> Result<OnchainAttestationResult, String> {
+            let mut calldata = keccak256(ATTEST_SIGNATURE)[..4].to_vec();
+            calldata.extend_from_slice(&ethers::abi::encode(&[
+                Token::FixedBytes(holder_id.to_vec()),
+                Token::Uint(U256::from(policy_id)),
+                Token::FixedBytes(snapshot_id.to_vec()),
+                Token::FixedBytes(nullifier.to_vec()),
+            ]));
+
+            let (tx_hash, attestation_ids) = self.submit(calldata).await?;
+            let attestation_id = attestation_ids
+                .first()
+                .copied()
+                .ok_or_else(|| "attestation receipt has no Attested event".to_string())?;
+            Ok(self.result(tx_hash, attestation_id))
+        }
+
+        async fn attest_batch(
+            &self,
+            entries: &[AttestationEntry],
+        ) -> Result<Vec<OnchainAttestationResult>, String> {
+            let bytes32_array = |field: fn(&AttestationEntry) -> [u8; 32]| {
+                Token::Array(
+                    entries
+                        .iter()
+                        .map(|entry| Token::FixedBytes(field(entry).to_vec()))
+                        .collect(),
+                )
+            };
+            let mut calldata = keccak256(ATTEST_BATCH_SIGNATURE)[..4].to_vec();
+            calldata.extend_from_slice(&ethers::abi::encode(&[
+                bytes32_array(|entry| entry.holder_id),
+                Token::Array(
+                    entries
+                        .iter()
+                        .map(|entry| Token::Uint(U256::from(entry.policy_id)))
+                        .collect(),
+                ),
+                bytes32_array(|entry| entry.snapshot_id),
+                bytes32_array(|entry| entry.nullifier),
+            ]));
+
+            // The registry emits one Attested event per entry, in entry order.
+            let (tx_hash, attestation_ids) = self.submit(calldata).await?;
+            if attestation_ids.len() != entries.len() {
+                return Err(format!(
+                    "attestation receipt has {} Attested events for {} entries",
+                    attestation_ids.len(),
+                    entries.len()
+                ));
+            }
+            Ok(attestation_ids
+                .into_iter()
+                .map(|attestation_id| self.result(tx_hash, attestation_id))
+                .collect())
+        }
+    }
+}
@@ -0,0 +1,196 @@
+//! Client address extraction behind reverse proxies.
+//!
+//! Behind Fly.io or a load balancer the socket peer is the proxy, not the
+//! client. Forwarding headers carry the client address, but anyone can send
+//! them, so they are only believed when the peer is listed in
+//! `ZKPF_TRUSTED_PROXIES`: a comma-separated list of CIDR blocks or bare
+//! addresses (`10.0.0.0/8,fdaa::/16,127.0.0.1`).
+//!
+//! [`client_ip`] reads the `Forwarded` header (RFC 7239) when present and
+//! `X-Forwarded-For` otherwise, and walks the hops from the right: the first
+//! address that is not a trusted proxy is the client. A hop that cannot be
+//! parsed (`unknown`, an obfuscated identifier) stops the walk at the last
+//! trusted address, so a client cannot inject an address past it.
+
+use std::{
+    env,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    str::FromStr,
+};
+
+use axum::{
+    extract::ConnectInfo,
+    http::{header, request::Parts, HeaderMap},
+};
+
+const TRUSTED_PROXIES_ENV: &str = "ZKPF_TRUSTED_PROXIES";
+const X_FORWARDED_FOR: &str = "x-forwarded-for";
+
+/// An address block such as `10.0.0.0/8`; a bare address is a single-host block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IpNet {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+impl IpNet {
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.addr, ip.to_canonical()) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                prefix_matches(u32::from(net).into(), u32::from(ip).into(), 32, self.prefix)
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                prefix_matches(net.into(), ip.into(), 128, self.prefix)
+            }
+            _ => false,
+        }
+    }
+}
+
+fn prefix_matches(net: u128, ip: u128, bits: u8, prefix: u8) -> bool {
+    let host_bits = u32::from(bits - prefix);
+    net.checked_shr(host_bits).unwrap_or(0) == ip.checked_shr(host_bits).unwrap_or(0)
+}
+
+impl FromStr for IpNet {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (addr, prefix) = match value.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (value, None),
+        };
+        let addr = addr
+            .trim()
+            .parse::<IpAddr>()
+            .map_err(|_| format!("invalid address in '{value}'"))?
+            .to_canonical();
+        let bits = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(prefix) => prefix
+                .trim()
+                .parse::<u8>()
+                .ok()
+                .filter(|prefix| *prefix <= bits)
+                .ok_or_else(|| format!("invalid prefix length in '{value}'"))?,
+            None => bits,
+        };
+        Ok(Self { addr, prefix })
+    }
+}
+
+/// Proxies whose forwarding headers are believed.
+#[derive(Clone, Debug, Default)]
+pub struct TrustedProxies {
+    nets: Vec<IpNet>,
+}
+
+impl TrustedProxies {
+    pub fn new(nets: Vec<IpNet>) -> Self {
+        Self { nets }
+    }
+
+    /// Parse a comma-separated list of blocks; empty entries are ignored.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(IpNet::from_str)
+            .collect::<Result<_, _>>()
+            .map(Self::new)
+    }
+
+    /// Proxies from `ZKPF_TRUSTED_PROXIES`. An invalid list is logged and
+    /// ignored, so forwarding headers are never trusted by mistake.
+    pub fn from_env() -> Self {
+        let Ok(value) = env::var(TRUSTED_PROXIES_ENV) else {
+            return Self::default();
+        };
+        Self::parse(&value).unwrap_or_else(|err| {
+            eprintln!("zkpf-backend: ignoring {TRUSTED_PROXIES_ENV}: {err}");
+            Self::default()
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nets.is_empty()
+    }
+
+    pub fn is_trusted(&self, ip: IpAddr) -> bool {
+        self.nets.iter().any(|net| net.contains(ip))
+    }
+}
+
+/// The client address of a request.
+///
+/// Uses the socket peer from axum's `ConnectInfo<SocketAddr>` unless that peer
+/// is a trusted proxy, in which case the forwarding headers are walked as
+/// described in the module docs. Without `ConnectInfo` (a router not served
+/// with `into_make_service_with_connect_info`) the peer is unknown and
+/// `0.0.0.0` is returned.
+pub fn client_ip(parts: &Parts, trusted: &TrustedProxies) -> IpAddr {
+    let Some(ConnectInfo(peer)) = parts.extensions.get::<ConnectInfo<SocketAddr>>() else {
+        return IpAddr::V4(Ipv4Addr::UNSPECIFIED);
+    };
+    let mut client = peer.ip().to_canonical();
+    if !trusted.is_trusted(client) {
+        return client;
+    }
+    for hop in forwarded_hops(&parts.headers).iter().rev() {
+        let Some(ip) = hop.as_deref().and_then(parse_node) else {
+            break;
+        };
+        client = ip;
+        if !trusted.is_trusted(ip) {
+            break;
+        }
+    }
+    client
+}
+
+/// Client-side hops listed by `Forwarded`, or by `X-Forwarded-For` when no
+/// `Forwarded` header is present, oldest first. Repeated headers are joined in
+/// order. A `Forwarded` element without a `for` parameter yields `None`.
+fn forwarded_hops(headers: &HeaderMap) -> Vec<Option<String>> {
+    let forwarded: Vec<&str> = headers
+        .get_all(header::FORWARDED)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .collect();
+    if !forwarded.is_empty() {
+        return forwarded
+            .iter()
+            .flat_map(|value| value.split(','))
+            .map(|element| {
+                element.split(';').find_map(|pair| {
+                    let (key, value) = pair.split_once('=')?;
+                    key.trim()
+                        .eq_ignore_ascii_case("for")
+                        .then(|| value.trim().to_string())
+                })
+            })
+            .collect();
+    }
+    headers
+        .get_all(X_FORWARDED_FOR)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|hop| Some(hop.trim().to_string()))
+        .collect()
+}
+
+/// An address as forwarding headers write it: bare, with a port, bracketed
+/// IPv6, and optionally quoted.
+fn parse_node(node: &str) -> Option<IpAddr> {
+    let node = node.trim().trim_matches('"');
+    let ip = match node.strip_prefix('[') {
+        Some(rest) => rest.split_once(']')?.0.parse::<IpAddr>().ok()?,
+        None => node
+            .parse::<IpAddr>()
+            .ok()
+            .or_else(|| node.parse::<SocketAddr>().ok().map(|addr| addr.ip()))?,
+    };
+    Some(ip.to_canonical())
+}
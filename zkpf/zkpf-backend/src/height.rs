@@ -0,0 +1,176 @@
+//! Chain-height oracles.
+//!
+//! Freshness checks need the current height of the chain a proof's snapshot
+//! was taken on. `HeightProvider` decouples those checks from any particular
+//! data source; the backend picks an implementation from the environment:
+//!
+//! - `ZKPF_HEIGHT_ENDPOINT` set: [`HttpHeightProvider`] polls that URL, which
+//!   must return a JSON object mapping chain ids to heights
+//!   (`{ "ZEC": 2750000, "STARKNET": 912345 }`). Heights older than
+//!   `ZKPF_HEIGHT_TTL_SECS` (default 60) are treated as unknown.
+//! - otherwise: [`StaticHeightProvider`] reads fixed heights from
+//!   `ZKPF_CHAIN_HEIGHTS` (`ZEC=2750000,STARKNET=912345`), which may be empty.
+
+use std::{
+    collections::HashMap,
+    env,
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+
+const HEIGHT_ENDPOINT_ENV: &str = "ZKPF_HEIGHT_ENDPOINT";
+const HEIGHT_TTL_ENV: &str = "ZKPF_HEIGHT_TTL_SECS";
+const CHAIN_HEIGHTS_ENV: &str = "ZKPF_CHAIN_HEIGHTS";
+const DEFAULT_HEIGHT_TTL_SECS: u64 = 60;
+const HEIGHT_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Source of the current height of a chain, keyed by chain id (e.g. `"ZEC"`).
+pub trait HeightProvider: Send + Sync {
+    /// Current height of `chain`, or an error when it is unknown or stale.
+    fn current_height(&self, chain: &str) -> Result<u64, String>;
+}
+
+/// Fixed heights, typically from `ZKPF_CHAIN_HEIGHTS`.
+#[derive(Clone, Debug, Default)]
+pub struct StaticHeightProvider {
+    heights: HashMap<String, u64>,
+}
+
+impl StaticHeightProvider {
+    pub fn new(heights: HashMap<String, u64>) -> Self {
+        Self { heights }
+    }
+
+    /// Parse `CHAIN=HEIGHT` pairs separated by commas. Malformed pairs are skipped.
+    pub fn from_env() -> Self {
+        let heights = env::var(CHAIN_HEIGHTS_ENV)
+            .map(|value| parse_chain_heights(&value))
+            .unwrap_or_default();
+        Self::new(heights)
+    }
+}
+
+impl HeightProvider for StaticHeightProvider {
+    fn current_height(&self, chain: &str) -> Result<u64, String> {
+        self.heights
+            .get(chain)
+            .copied()
+            .ok_or_else(|| format!("no height configured for chain {chain}"))
+    }
+}
+
+fn parse_chain_heights(value: &str) -> HashMap<String, u64> {
+    value
+        .split(',')
+        .filter_map(|pair| {
+            let (chain, height) = pair.split_once('=')?;
+            let height = height.trim().parse::<u64>().ok()?;
+            Some((chain.trim().to_string(), height))
+        })
+        .collect()
+}
+
+#[derive(Default)]
+struct HeightCache {
+    heights: HashMap<String, u64>,
+    fetched_at: Option<Instant>,
+}
+
+/// Polls an HTTP endpoint for chain heights and serves them from a cache.
+///
+/// Polling runs on a background task every half TTL, so lookups never block
+/// on the network. A height is only returned while the last successful poll
+/// is younger than the TTL.
+#[derive(Clone)]
+pub struct HttpHeightProvider {
+    ttl: Duration,
+    cache: Arc<RwLock<HeightCache>>,
+}
+
+impl HttpHeightProvider {
+    /// Start polling `url`. Must be called from within a Tokio runtime.
+    pub fn spawn(url: String, ttl: Duration) -> Result<Self, String> {
+        let client = reqwest::Client::builder()
+            .timeout(HEIGHT_REQUEST_TIMEOUT)
+            .build()
+            .map_err(|err| format!("failed to build height client: {err}"))?;
+        let runtime = tokio::runtime::Handle::try_current()
+            .map_err(|_| "height polling requires a Tokio runtime".to_string())?;
+
+        let provider = Self {
+            ttl,
+            cache: Arc::new(RwLock::new(HeightCache::default())),
+        };
+        let cache = provider.cache.clone();
+        let interval = (ttl / 2).max(Duration::from_secs(1));
+        runtime.spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match fetch_heights(&client, &url).await {
+                    Ok(heights) => {
+                        let mut cache = cache.write().unwrap_or_else(|e| e.into_inner());
+                        cache.heights = heights;
+                        cache.fetched_at = Some(Instant::now());
+                    }
+                    Err(err) => eprintln!("[HeightProvider] poll of {} failed: {}", url, err),
+                }
+            }
+        });
+        Ok(provider)
+    }
+}
+
+impl HeightProvider for HttpHeightProvider {
+    fn current_height(&self, chain: &str) -> Result<u64, String> {
+        let cache = self.cache.read().unwrap_or_else(|e| e.into_inner());
+        match cache.fetched_at {
+            Some(at) if at.elapsed() <= self.ttl => {}
+            Some(_) => return Err("cached chain heights are stale".into()),
+            None => return Err("chain heights not fetched yet".into()),
+        }
+        cache
+            .heights
+            .get(chain)
+            .copied()
+            .ok_or_else(|| format!("height endpoint did not report chain {chain}"))
+    }
+}
+
+async fn fetch_heights(
+    client: &reqwest::Client,
+    url: &str,
+) -> Result<HashMap<String, u64>, String> {
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|err| err.to_string())?;
+    let body = response.bytes().await.map_err(|err| err.to_string())?;
+    serde_json::from_slice(&body).map_err(|err| format!("invalid height response: {err}"))
+}
+
+/// Build the provider selected by the environment (see the module docs).
+///
+/// Falls back to the static provider if the HTTP provider cannot start.
+pub fn height_provider_from_env() -> Arc<dyn HeightProvider> {
+    if let Some(url) = env::var(HEIGHT_ENDPOINT_ENV)
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+    {
+        let ttl = env::var(HEIGHT_TTL_ENV)
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_HEIGHT_TTL_SECS);
+        match HttpHeightProvider::spawn(url, Duration::from_secs(ttl)) {
+            Ok(provider) => return Arc::new(provider),
+            Err(err) => eprintln!(
+                "{} is set but height polling is disabled: {}",
+                HEIGHT_ENDPOINT_ENV, err
+            ),
+        }
+    }
+    Arc::new(StaticHeightProvider::from_env())
+}
@@ -1,46 +1,88 @@
 use std::{
-    collections::{HashMap, HashSet},
+    borrow::Cow,
+    collections::{hash_map::Entry, BTreeMap, HashMap, HashSet, VecDeque},
     env, fs,
-    path::Path,
-    sync::{Arc, Mutex, RwLock},
-    time::{Duration, SystemTime, UNIX_EPOCH},
+    io::Write,
+    net::{IpAddr, SocketAddr},
+    path::{Path, PathBuf},
+    sync::{Arc, LockResult, Mutex, RwLock},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
+use async_trait::async_trait;
 use axum::{
     body::Body,
-    extract::{Path as AxumPath, State},
-    http::{header, HeaderValue, StatusCode},
+    extract::{
+        multipart::{Multipart, MultipartError},
+        DefaultBodyLimit, FromRequestParts, Path as AxumPath, Query, State,
+    },
+    http::{header, request::Parts, HeaderMap, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
-    routing::{get, post},
+    routing::{get, post, MethodRouter},
     Json, Router,
 };
 use once_cell::sync::Lazy;
+use rand::{rngs::OsRng, RngCore};
 
+pub mod attestation;
+pub mod client_ip;
+pub mod height;
+mod openapi;
 pub mod personhood;
+pub mod policy_source;
+mod webhook;
+
 use serde_json::Value as JsonValue;
-use sled::Db;
+use sha3::{Digest, Keccak256};
+use sled::{
+    transaction::{self, TransactionError},
+    Db, Transactional,
+};
 use tokio::{fs::File, net::TcpListener};
 use tokio_util::io::ReaderStream;
-use tower_http::cors::{Any, CorsLayer};
+use tower_http::{
+    compression::{
+        predicate::{DefaultPredicate, NotForContentType, Predicate},
+        CompressionLayer,
+    },
+    cors::{Any, CorsLayer},
+    decompression::RequestDecompressionLayer,
+};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 use zkpf_circuit::{
-    gadgets::attestation::{AttestationWitness, EcdsaSignature, Secp256k1Pubkey},
-    PublicInputs, ZkpfCircuitInput,
+    gadgets::{
+        attestation::{AttestationWitness, EcdsaSignature, Secp256k1Pubkey, SignatureFormat},
+        CURRENCY_WILDCARD,
+    },
+    PublicInputs, ZkpfCircuitInput, DEFAULT_CURRENCY_CODE_BITS,
 };
+/// Error codes returned in `error_code`; shared with the WASM bindings and rails.
+pub use zkpf_common::ErrorCode;
 use zkpf_common::{
-    compute_nullifier_fr, custodian_pubkey_hash, deserialize_verifier_public_inputs,
+    account_id_hash_from_tag, compute_nullifier_fr, constant_time_eq, custodian_pubkey_hash,
+    derive_account_nullifier, deserialize_verifier_public_inputs, fr_to_bytes,
     load_prover_artifacts_lazy, load_prover_artifacts_without_pk, load_verifier_artifacts,
-    nullifier_fr, public_inputs_to_instances_with_layout, public_to_verifier_inputs,
-    reduce_be_bytes_to_fr, Attestation, ProofBundle, ProverArtifacts, PublicInputLayout,
-    VerifierArtifacts, VerifierPublicInputs,
+    public_inputs_to_instances_with_layout, public_to_verifier_inputs, reduce_be_bytes_to_fr,
+    Attestation, EpochProofSet, Fr, ProofBundle, ProverArtifacts, PublicInputLayout,
+    VerifierArtifacts, VerifierPublicInputs, CHALLENGE_RAIL_ID, REVEAL_SUM_RAIL_ID,
 };
 use zkpf_prover::prove_bundle;
-use zkpf_verifier::verify;
-use zkpf_zcash_orchard_circuit::{load_orchard_verifier_artifacts, RAIL_ID_ZCASH_ORCHARD};
+use zkpf_verify_core::verify;
+use zkpf_zcash_orchard_circuit::{
+    load_orchard_verifier_artifacts, load_orchard_verifier_only, RAIL_ID_ZCASH_ORCHARD,
+};
+
+use crate::attestation::{
+    attestation_backend_from_env, AttestationBackend, AttestationEntry, OnchainAttestationResult,
+};
+use crate::client_ip::TrustedProxies;
+use crate::height::{height_provider_from_env, HeightProvider};
+use crate::webhook::{AttestWebhook, AttestWebhookPayload};
 
 // k256 for secp256k1 ECDSA signature verification
 use k256::ecdsa::{
-    signature::Verifier as K256Verifier,
+    signature::{hazmat::PrehashVerifier as K256PrehashVerifier, Verifier as K256Verifier},
     Signature as K256Signature,
     VerifyingKey as K256VerifyingKey,
 };
@@ -51,41 +93,62 @@ const SNAP_DIR_ENV: &str = "ZKPF_SNAP_DIR";
 const DEFAULT_SNAP_DIR: &str = "snap";
 const EPOCH_OVERRIDE_ENV: &str = "ZKPF_VERIFIER_EPOCH";
 const EPOCH_DRIFT_ENV: &str = "ZKPF_VERIFIER_MAX_DRIFT_SECS";
+const PREVIOUS_EPOCHS_ENV: &str = "ZKPF_VERIFIER_ACCEPT_PREVIOUS_EPOCHS";
+const EPOCH_SECS_ENV: &str = "ZKPF_VERIFIER_EPOCH_SECS";
+const DEFAULT_EPOCH_SECS: u64 = 3600;
 const DEFAULT_MAX_EPOCH_DRIFT_SECS: u64 = 10000;
 const POLICY_PATH_ENV: &str = "ZKPF_POLICY_PATH";
 const DEFAULT_POLICY_PATH: &str = "config/policies.json";
 const NULLIFIER_DB_ENV: &str = "ZKPF_NULLIFIER_DB";
 const DEFAULT_NULLIFIER_DB_PATH: &str = "data/nullifiers.db";
+/// `sled` (default) or `log` for the append-only nullifier file.
+const NULLIFIER_BACKEND_ENV: &str = "ZKPF_NULLIFIER_BACKEND";
+const DEFAULT_NULLIFIER_LOG_PATH: &str = "data/nullifiers.log";
+/// Known-spent keys cached in front of the nullifier backend; 0 disables the cache.
+const NULLIFIER_CACHE_SIZE_ENV: &str = "ZKPF_NULLIFIER_CACHE_SIZE";
+const DEFAULT_NULLIFIER_CACHE_SIZE: usize = 10_000;
+const ATTESTATION_HISTORY_DB_ENV: &str = "ZKPF_ATTESTATION_HISTORY_DB";
+const DEFAULT_ATTESTATION_HISTORY_DB_PATH: &str = "data/attestations.db";
+const DEFAULT_ATTESTATION_PAGE_SIZE: usize = 50;
+const MAX_ATTESTATION_PAGE_SIZE: usize = 500;
 const MULTIRAIL_MANIFEST_ENV: &str = "ZKPF_MULTI_RAIL_MANIFEST_PATH";
-const ATTESTATION_ENABLED_ENV: &str = "ZKPF_ATTESTATION_ENABLED";
-const ATTESTATION_RPC_URL_ENV: &str = "ZKPF_ATTESTATION_RPC_URL";
-const ATTESTATION_CHAIN_ID_ENV: &str = "ZKPF_ATTESTATION_CHAIN_ID";
-const ATTESTATION_REGISTRY_ADDRESS_ENV: &str = "ZKPF_ATTESTATION_REGISTRY_ADDRESS";
-const ATTESTOR_PRIVATE_KEY_ENV: &str = "ZKPF_ATTESTOR_PRIVATE_KEY";
+const REVEAL_SUM_MANIFEST_ENV: &str = "ZKPF_REVEAL_SUM_MANIFEST_PATH";
+const CHALLENGE_MANIFEST_ENV: &str = "ZKPF_CHALLENGE_MANIFEST_PATH";
 const ENABLE_PROVER_ENV: &str = "ZKPF_ENABLE_PROVER";
+const RESPONSE_COMPRESSION_ENV: &str = "ZKPF_RESPONSE_COMPRESSION";
+/// Set to `1` to load the proving key in the background once the server listens.
+const PREWARM_PK_ENV: &str = "ZKPF_PREWARM_PK";
+const DEEP_LINK_SCHEMES_ENV: &str = "ZKPF_ALLOWED_DEEP_LINK_SCHEMES";
+const ADMIN_TOKEN_ENV: &str = "ZKPF_ADMIN_TOKEN";
+/// `blake3` (default) or `keccak256` for the on-chain attestation identifiers.
+const ONCHAIN_HASH_ENV: &str = "ZKPF_ONCHAIN_HASH";
+/// Tenant API keys as comma-separated `namespace=key` pairs.
+const API_KEYS_ENV: &str = "ZKPF_API_KEYS";
+const API_KEY_HEADER: &str = "x-api-key";
+/// Policy compositions allowed per namespace per minute; 0 disables the limit.
+const COMPOSE_RATE_LIMIT_ENV: &str = "ZKPF_COMPOSE_RATE_LIMIT";
+const DEFAULT_COMPOSE_RATE_LIMIT: u64 = 30;
+const COMPOSE_RATE_WINDOW_SECS: u64 = 60;
+const VERIFY_TIMEOUT_MS_ENV: &str = "ZKPF_VERIFY_TIMEOUT_MS";
+/// Hex-encoded 32-byte key the prover tags `issued_at` with.
+const PROVER_SIGNING_KEY_ENV: &str = "ZKPF_PROVER_SIGNING_KEY";
+const REPLAY_LOG_SIZE_ENV: &str = "ZKPF_REPLAY_LOG_SIZE";
+/// Set to `1` on read replicas to accept `record_nullifier: false`.
+const ALLOW_UNRECORDED_VERIFY_ENV: &str = "ZKPF_ALLOW_UNRECORDED_VERIFY";
+const DEFAULT_REPLAY_LOG_SIZE: usize = 100;
 const NULLIFIER_SPENT_ERR: &str = "nullifier already spent for this scope/policy";
-const CODE_CIRCUIT_VERSION: &str = "CIRCUIT_VERSION_MISMATCH";
-const CODE_PUBLIC_INPUTS: &str = "PUBLIC_INPUTS_INVALID";
-const CODE_POLICY_NOT_FOUND: &str = "POLICY_NOT_FOUND";
-const CODE_POLICY_MISMATCH: &str = "POLICY_MISMATCH";
-const CODE_EPOCH_DRIFT: &str = "EPOCH_DRIFT";
-const CODE_NULLIFIER_REPLAY: &str = "NULLIFIER_REPLAY";
-const CODE_NULLIFIER_STORE_ERROR: &str = "NULLIFIER_STORE_ERROR";
-const CODE_PROOF_INVALID: &str = "PROOF_INVALID";
-const CODE_RAIL_UNKNOWN: &str = "RAIL_UNKNOWN";
-const CODE_ATTESTATION_DISABLED: &str = "ATTESTATION_DISABLED";
-const CODE_ATTESTATION_VERIFICATION_FAILED: &str = "ATTESTATION_VERIFICATION_FAILED";
-const CODE_ATTESTATION_ONCHAIN_ERROR: &str = "ATTESTATION_ONCHAIN_ERROR";
-const CODE_INTERNAL: &str = "INTERNAL_SERVER_ERROR";
-const CODE_PROVER_DISABLED: &str = "PROVER_DISABLED";
-const CODE_POLICY_COMPOSE_INVALID: &str = "POLICY_COMPOSE_INVALID";
-const CODE_SESSION_NOT_FOUND: &str = "SESSION_NOT_FOUND";
-const CODE_SESSION_STATE: &str = "SESSION_STATE_INVALID";
-const CODE_ARTIFACT_NOT_FOUND: &str = "ARTIFACT_NOT_FOUND";
+const EPOCH_REWIND_ERR: &str =
+    "current_epoch is in an epoch older than one already accepted for this scope/policy";
+const EPOCH_WATERMARK_TREE: &str = "epoch_watermarks";
 const DEFAULT_RAIL_ID: &str = "CUSTODIAL_ATTESTATION";
 const PROVIDER_BALANCE_RAIL_ID: &str = "PROVIDER_BALANCE_V2";
 const PROVIDER_SESSION_TTL_SECS: u64 = 15 * 60;
 const PROVIDER_SESSION_RETENTION_SECS: u64 = 60 * 60;
+const CHALLENGE_TTL_SECS: u64 = 5 * 60;
+const CHALLENGE_MAX_OUTSTANDING_ENV: &str = "ZKPF_CHALLENGE_MAX_OUTSTANDING";
+const DEFAULT_CHALLENGE_MAX_OUTSTANDING: usize = 100_000;
+const CHALLENGE_MAX_PER_CLIENT_ENV: &str = "ZKPF_CHALLENGE_MAX_PER_CLIENT";
+const DEFAULT_CHALLENGE_MAX_PER_CLIENT: usize = 32;
 const DEFAULT_DEEP_LINK_SCHEME: &str = "zashi";
 
 // ============================================================
@@ -98,6 +161,23 @@ const DEFAULT_DEEP_LINK_SCHEME: &str = "zashi";
 /// Maximum proof size in bytes (current circuit produces ~1.5KB proofs)
 const MAX_PROOF_SIZE_BYTES: usize = 16 * 1024; // 16 KB - generous headroom
 
+/// Size of one commitment or evaluation in a bn256 KZG transcript.
+const PROOF_ELEMENT_BYTES: usize = 32;
+
+/// Smallest proof a circuit with `instance_columns` public columns can produce.
+///
+/// Counts the elements every halo2 KZG proof must carry: one advice commitment
+/// and evaluation, the permutation product commitment with its two evaluations
+/// and one sigma evaluation per permuted column (each instance column plus at
+/// least one advice column), the vanishing argument's random commitment,
+/// evaluation and at least one `h` piece, and one opening witness. KZG
+/// openings are constant-size, so the bound does not grow with `k`.
+fn min_proof_size_bytes(instance_columns: usize) -> usize {
+    let commitments = 5;
+    let evaluations = instance_columns + 5;
+    (commitments + evaluations) * PROOF_ELEMENT_BYTES
+}
+
 /// Maximum public inputs size in bytes
 const MAX_PUBLIC_INPUTS_SIZE_BYTES: usize = 4 * 1024; // 4 KB
 
@@ -107,14 +187,97 @@ const MAX_HEX_32_LEN: usize = 66;
 /// Maximum category/label/rail_id length in policy composition
 const MAX_POLICY_STRING_LEN: usize = 256;
 
+/// Largest composable `threshold_raw` for known currency codes, in base units.
+///
+/// Every code must fit in `DEFAULT_CURRENCY_CODE_BITS`, the width the custodial
+/// circuit range-checks attested currency codes to.
+const CURRENCY_THRESHOLD_LIMITS: &[(u32, u64)] = &[
+    // ZEC in zatoshi: the 21M ZEC supply cap.
+    (999_001, 2_100_000_000_000_000),
+    // USDC (6 decimals): one trillion USDC.
+    (2001, 1_000_000_000_000_000_000),
+];
+
 /// Maximum account tag length (hex string for 32 bytes)
 const MAX_ACCOUNT_TAG_LEN: usize = 66;
 
+/// Maximum request body size after gzip decompression on bundle endpoints.
+/// A JSON-encoded bundle at MAX_PROOF_SIZE_BYTES is ~64 KB; anything far beyond
+/// that is rejected before it is fully inflated (zip-bomb protection).
+const MAX_DECOMPRESSED_BODY_BYTES: usize = 256 * 1024;
+
+/// Maximum proof size on `/zkpf/verify-bundle/multipart`. The raw proof streams
+/// in as a file part instead of a JSON byte array, so this route can take
+/// proofs well past MAX_PROOF_SIZE_BYTES (e.g. recursive or aggregated proofs).
+const MAX_MULTIPART_PROOF_SIZE_BYTES: usize = 1024 * 1024;
+
+/// Body limit for the multipart route: the proof part plus room for the
+/// public inputs, the other form fields and part headers.
+const MAX_MULTIPART_BODY_BYTES: usize = MAX_MULTIPART_PROOF_SIZE_BYTES + 64 * 1024;
+
+/// Maximum number of accepted policies in one bundle verification request.
+const MAX_ACCEPTED_POLICIES: usize = 16;
+
+/// Maximum number of bundles in one `/zkpf/verify-bundle/batch` request.
+const MAX_BATCH_ITEMS: usize = 16;
+
+/// Decompressed body limit for batch requests: one bundle allowance per item.
+const MAX_BATCH_BODY_BYTES: usize = MAX_BATCH_ITEMS * MAX_DECOMPRESSED_BODY_BYTES;
+
 static ARTIFACTS: Lazy<Arc<ProverArtifacts>> = Lazy::new(|| Arc::new(load_artifacts()));
+static REVEAL_SUM_ARTIFACTS: Lazy<Option<Arc<ProverArtifacts>>> =
+    Lazy::new(|| load_reveal_sum_artifacts().map(Arc::new));
+static CHALLENGE_ARTIFACTS: Lazy<Option<Arc<ProverArtifacts>>> =
+    Lazy::new(|| load_challenge_artifacts().map(Arc::new));
 static POLICIES: Lazy<PolicyStore> = Lazy::new(PolicyStore::from_env);
 static RAILS: Lazy<RailRegistry> = Lazy::new(RailRegistry::from_env);
-static ATTESTATION_SERVICE: Lazy<Option<OnchainAttestationService>> =
-    Lazy::new(OnchainAttestationService::from_env);
+static ATTESTATION_BACKEND: Lazy<Option<Arc<dyn AttestationBackend>>> =
+    Lazy::new(attestation_backend_from_env);
+static ATTEST_WEBHOOK: Lazy<Option<AttestWebhook>> = Lazy::new(AttestWebhook::from_env);
+static ATTESTATION_HISTORY: Lazy<AttestationHistory> = Lazy::new(AttestationHistory::from_env);
+static ONCHAIN_HASH: Lazy<OnchainHash> = Lazy::new(OnchainHash::from_env);
+static HEIGHTS: Lazy<Arc<dyn HeightProvider>> = Lazy::new(height_provider_from_env);
+static TRUSTED_PROXIES: Lazy<TrustedProxies> = Lazy::new(TrustedProxies::from_env);
+static ALLOWED_DEEP_LINK_SCHEMES: Lazy<Vec<String>> = Lazy::new(allowed_deep_link_schemes);
+/// Deadline for a single proof verification; unset or 0 disables it.
+static VERIFY_TIMEOUT: Lazy<Option<Duration>> = Lazy::new(|| {
+    parse_env_u64(VERIFY_TIMEOUT_MS_ENV)
+        .filter(|ms| *ms > 0)
+        .map(Duration::from_millis)
+});
+/// Poseidon and public-input layout self-checks, run once before serving.
+/// A failure does not stop the server but is reported by `/readyz`.
+static SELF_CHECK: Lazy<Result<(), String>> = Lazy::new(|| {
+    zkpf_common::poseidon_self_test()
+        .and_then(|()| zkpf_common::layout_self_test())
+        .map_err(|err| format!("{err:#}"))
+});
+/// Key for prover-signed issuance times; without it bundles are not stamped
+/// and policies with `max_proof_age_secs` reject every bundle.
+static PROVER_SIGNING_KEY: Lazy<Option<[u8; 32]>> = Lazy::new(|| {
+    let value = env::var(PROVER_SIGNING_KEY_ENV).ok()?;
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+    let key = hex::decode(value.trim_start_matches("0x"))
+        .ok()
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .unwrap_or_else(|| panic!("{PROVER_SIGNING_KEY_ENV} must be 32 hex-encoded bytes"));
+    Some(key)
+});
+static ADMIN_TOKEN: Lazy<Option<String>> = Lazy::new(|| {
+    env::var(ADMIN_TOKEN_ENV)
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+});
+/// `(namespace, key)` pairs from `ZKPF_API_KEYS`.
+static API_KEYS: Lazy<Vec<(String, String)>> = Lazy::new(|| {
+    env::var(API_KEYS_ENV)
+        .map(|value| parse_api_keys(&value))
+        .unwrap_or_default()
+});
 
 #[derive(Clone, Debug, serde::Deserialize)]
 struct RailManifestEntry {
@@ -175,6 +338,18 @@ impl RailArtifacts {
         }
     }
 
+    /// Instance columns the verifying key expects: the manifest's
+    /// `num_instance_columns` when set, otherwise the layout's count.
+    fn instance_columns(&self, layout: PublicInputLayout) -> usize {
+        let manifest = match self {
+            RailArtifacts::Prover(a) => &a.manifest,
+            RailArtifacts::Verifier(a) => &a.manifest,
+        };
+        manifest
+            .num_instance_columns
+            .unwrap_or_else(|| layout.instance_columns())
+    }
+
     /// Get the manifest for this rail's artifacts.
     fn manifest(&self) -> &zkpf_common::ArtifactManifest {
         match self {
@@ -185,12 +360,12 @@ impl RailArtifacts {
 }
 
 impl RailVerifier {
-    /// Get the directory containing artifacts based on manifest_path.
+    /// Get the directory containing artifacts based on manifest_path, or
+    /// `ZKPF_ARTIFACT_DIR` when that override is set.
     fn artifact_dir(&self) -> Option<std::path::PathBuf> {
-        self.manifest_path.as_ref().map(|p| {
-            let path = std::path::Path::new(p);
-            path.parent().unwrap_or(path).to_path_buf()
-        })
+        self.manifest_path
+            .as_ref()
+            .map(|p| zkpf_common::artifact_base_dir(std::path::Path::new(p)))
     }
 
     /// Get the path to a specific artifact (params, vk, pk).
@@ -205,6 +380,29 @@ impl RailVerifier {
             _ => None,
         }
     }
+
+    fn artifact_exists(&self, kind: &str) -> bool {
+        self.artifact_path(kind).is_some_and(|path| path.exists())
+    }
+
+    /// Hash of the rail's break_points.json, if it ships one. Only halo2-base
+    /// circuits such as Orchard need break points.
+    fn break_points_hash(&self, rail_id: &str) -> Option<String> {
+        let path = self.artifact_dir()?.join("break_points.json");
+        if !path.exists() {
+            return None;
+        }
+        match fs::read(&path) {
+            Ok(bytes) => Some(zkpf_common::hash_bytes_hex(&bytes)),
+            Err(err) => {
+                eprintln!(
+                    "zkpf-backend: failed to read break_points.json for rail '{}': {}",
+                    rail_id, err
+                );
+                None
+            }
+        }
+    }
 }
 
 impl RailRegistry {
@@ -277,6 +475,45 @@ impl RailRegistry {
         );
         map.insert(RAIL_ID_ZCASH_ORCHARD.to_string(), orchard_dev);
 
+        // Custodial proofs that reveal proven_sum have an extra instance column
+        // and their own keys, so they get a rail of their own when configured.
+        if let Some(artifacts) = REVEAL_SUM_ARTIFACTS.as_ref() {
+            let reveal_sum = RailVerifier {
+                circuit_version: artifacts.manifest.circuit_version,
+                layout: PublicInputLayout::V1WithSum,
+                artifacts: RailArtifacts::Prover(artifacts.clone()),
+                manifest_path: env::var(REVEAL_SUM_MANIFEST_ENV).ok(),
+            };
+            eprintln!(
+                "[RailRegistry] rail_id={} cv={} layout={:?} k={} artifact_key={}",
+                REVEAL_SUM_RAIL_ID,
+                reveal_sum.circuit_version,
+                reveal_sum.layout,
+                reveal_sum.artifacts.k(),
+                reveal_sum.artifacts.artifact_key()
+            );
+            map.insert(REVEAL_SUM_RAIL_ID.to_string(), reveal_sum);
+        }
+
+        // Challenge-bound custodial proofs likewise carry an extra column.
+        if let Some(artifacts) = CHALLENGE_ARTIFACTS.as_ref() {
+            let challenge = RailVerifier {
+                circuit_version: artifacts.manifest.circuit_version,
+                layout: PublicInputLayout::V1,
+                artifacts: RailArtifacts::Prover(artifacts.clone()),
+                manifest_path: env::var(CHALLENGE_MANIFEST_ENV).ok(),
+            };
+            eprintln!(
+                "[RailRegistry] rail_id={} cv={} layout={:?} k={} artifact_key={}",
+                CHALLENGE_RAIL_ID,
+                challenge.circuit_version,
+                challenge.layout,
+                challenge.artifacts.k(),
+                challenge.artifacts.artifact_key()
+            );
+            map.insert(CHALLENGE_RAIL_ID.to_string(), challenge);
+        }
+
         if let Ok(path) = multi_rail_path {
             eprintln!("[RailRegistry] Loading multi-rail manifest from: {}", path);
             let bytes = fs::read(&path).unwrap_or_else(|err| {
@@ -300,11 +537,21 @@ impl RailRegistry {
                     "V1" => PublicInputLayout::V1,
                     "V2_ORCHARD" => PublicInputLayout::V2Orchard,
                     "V3_STARKNET" => PublicInputLayout::V3Starknet,
+                    "V4_AGGREGATE" => PublicInputLayout::V4Aggregate,
+                    "V5_RANGE" => PublicInputLayout::V5Range,
+                    "V1_WITH_SUM" => PublicInputLayout::V1WithSum,
+                    "V6_SNAPSHOT_COMMITMENT" => PublicInputLayout::V6SnapshotCommitment,
                     other => panic!("unsupported public-input layout '{}'", other),
                 };
 
                 let artifacts = if rail.rail_id == RAIL_ID_ZCASH_ORCHARD {
-                    load_orchard_verifier_artifacts(&rail.manifest_path).unwrap_or_else(|err| {
+                    // Verifier-only deployments may not ship the Orchard pk.
+                    let load_orchard = if prover_enabled_from_env() {
+                        load_orchard_verifier_artifacts
+                    } else {
+                        load_orchard_verifier_only
+                    };
+                    load_orchard(&rail.manifest_path).unwrap_or_else(|err| {
                         panic!(
                             "failed to load Orchard verifier artifacts for rail {} from {}: {}",
                             rail.rail_id, rail.manifest_path, err
@@ -375,97 +622,57 @@ impl RailRegistry {
             .map(|s| s.as_str())
             .collect()
     }
-}
-
-fn policy_config_path() -> String {
-    env::var(POLICY_PATH_ENV).unwrap_or_else(|_| DEFAULT_POLICY_PATH.to_string())
-}
-
-#[derive(Clone)]
-struct OnchainAttestationService;
-
-#[derive(Clone)]
-struct OnchainAttestationResult {
-    tx_hash: String,
-    attestation_id: String,
-    chain_id: u64,
-}
-
-impl OnchainAttestationService {
-    fn from_env() -> Option<Self> {
-        let enabled = env::var(ATTESTATION_ENABLED_ENV)
-            .ok()
-            .map(|v| v.to_ascii_lowercase())
-            .map(|v| matches!(v.as_str(), "1" | "true" | "yes"))
-            .unwrap_or(false);
-
-        if enabled {
-            let mut config_warnings = Vec::new();
-
-            let rpc_url_ready = env::var(ATTESTATION_RPC_URL_ENV)
-                .map(|v| !v.trim().is_empty())
-                .unwrap_or(false);
-            if !rpc_url_ready {
-                config_warnings.push(format!("{} is missing", ATTESTATION_RPC_URL_ENV));
-            }
-
-            let chain_id_ready = env::var(ATTESTATION_CHAIN_ID_ENV)
-                .ok()
-                .and_then(|v| v.parse::<u64>().ok())
-                .is_some();
-            if !chain_id_ready {
-                config_warnings.push(format!(
-                    "{} is missing or invalid",
-                    ATTESTATION_CHAIN_ID_ENV
-                ));
-            }
-
-            let registry_ready = env::var(ATTESTATION_REGISTRY_ADDRESS_ENV)
-                .map(|v| !v.trim().is_empty())
-                .unwrap_or(false);
-            if !registry_ready {
-                config_warnings.push(format!(
-                    "{} is missing",
-                    ATTESTATION_REGISTRY_ADDRESS_ENV
-                ));
-            }
-
-            let private_key_ready = env::var(ATTESTOR_PRIVATE_KEY_ENV)
-                .map(|v| !v.trim().is_empty())
-                .unwrap_or(false);
-            if !private_key_ready {
-                config_warnings.push(format!("{} is missing", ATTESTOR_PRIVATE_KEY_ENV));
-            }
-
-            if !config_warnings.is_empty() {
-                eprintln!(
-                    "Attestation config is incomplete: {}",
-                    config_warnings.join(", ")
-                );
-            }
-
-            eprintln!(
-                "ATTESTATION_ENABLED is set, but the lightweight binary build does not ship \
-                 the on-chain attestation client. Disable {} or build with the full feature \
-                 set to submit attestations on-chain.",
-                ATTESTATION_ENABLED_ENV
-            );
-        }
 
-        None
+    /// Describe every registered rail, sorted by rail ID.
+    fn rail_infos(&self) -> Vec<RailInfo> {
+        let mut infos: Vec<RailInfo> = self
+            .rail_ids()
+            .into_iter()
+            .filter_map(|rail_id| {
+                self.rails.get(rail_id).map(|rail| RailInfo {
+                    rail_id: rail_id.to_string(),
+                    circuit_version: rail.circuit_version,
+                    layout: rail.layout,
+                })
+            })
+            .collect();
+        infos.sort_by(|a, b| a.rail_id.cmp(&b.rail_id));
+        infos
     }
 
-    async fn attest(
-        &self,
-        _holder_id: [u8; 32],
-        _policy_id: u64,
-        _snapshot_id: [u8; 32],
-        _nullifier: [u8; 32],
-    ) -> Result<OnchainAttestationResult, String> {
-        Err("on-chain attestation is not available in this build".into())
+    /// Report what each registered rail has loaded, sorted by rail ID.
+    fn rail_statuses(&self) -> Vec<RailStatus> {
+        let mut statuses: Vec<RailStatus> = self
+            .rail_ids()
+            .into_iter()
+            .filter_map(|rail_id| {
+                self.rails.get(rail_id).map(|rail| RailStatus {
+                    rail_id: rail_id.to_string(),
+                    circuit_version: rail.circuit_version,
+                    layout: rail.layout,
+                    k: rail.artifacts.k(),
+                    artifact_key: rail.artifacts.artifact_key(),
+                    prover_enabled: match &rail.artifacts {
+                        RailArtifacts::Prover(a) => a.prover_enabled(),
+                        RailArtifacts::Verifier(_) => false,
+                    },
+                    artifacts_on_disk: ArtifactPresence {
+                        params: rail.artifact_exists("params"),
+                        vk: rail.artifact_exists("vk"),
+                        pk: rail.artifact_exists("pk"),
+                    },
+                })
+            })
+            .collect();
+        statuses.sort_by(|a, b| a.rail_id.cmp(&b.rail_id));
+        statuses
     }
 }
 
+fn policy_config_path() -> String {
+    env::var(POLICY_PATH_ENV).unwrap_or_else(|_| DEFAULT_POLICY_PATH.to_string())
+}
+
 #[derive(Clone)]
 pub struct AppState {
     artifacts: Arc<ProverArtifacts>,
@@ -473,6 +680,13 @@ pub struct AppState {
     nullifiers: NullifierStore,
     policies: PolicyStore,
     provider_sessions: ProviderSessionStore,
+    challenges: ChallengeStore,
+    heights: Arc<dyn HeightProvider>,
+    attestation: Option<Arc<dyn AttestationBackend>>,
+    attestation_history: AttestationHistory,
+    replays: ReplayLog,
+    compose_limiter: ComposeRateLimiter,
+    trusted_proxies: TrustedProxies,
 }
 
 impl AppState {
@@ -495,13 +709,60 @@ impl AppState {
     ) -> Self {
         Self {
             artifacts,
+            // The watermark counts epochs, and admits previous ones, the way
+            // the verifier does.
+            nullifiers: nullifiers
+                .with_epoch_secs(epoch.epoch_secs)
+                .with_previous_epochs(epoch.previous_epochs),
             epoch,
-            nullifiers,
             policies,
             provider_sessions,
+            challenges: ChallengeStore::from_env(),
+            heights: HEIGHTS.clone(),
+            attestation: ATTESTATION_BACKEND.clone(),
+            attestation_history: ATTESTATION_HISTORY.clone(),
+            replays: ReplayLog::from_env(),
+            compose_limiter: ComposeRateLimiter::from_env(),
+            trusted_proxies: TRUSTED_PROXIES.clone(),
         }
     }
 
+    /// Replace the chain-height oracle used for snapshot freshness checks.
+    pub fn with_height_provider(mut self, heights: Arc<dyn HeightProvider>) -> Self {
+        self.heights = heights;
+        self
+    }
+
+    /// Replace the store of challenges issued by `/zkpf/challenge`.
+    pub fn with_challenge_store(mut self, challenges: ChallengeStore) -> Self {
+        self.challenges = challenges;
+        self
+    }
+
+    /// Replace the backend `/zkpf/attest` uses to record attestations on-chain.
+    pub fn with_attestation_backend(mut self, backend: Arc<dyn AttestationBackend>) -> Self {
+        self.attestation = Some(backend);
+        self
+    }
+
+    /// Replace the ledger successful attestations are appended to.
+    pub fn with_attestation_history(mut self, history: AttestationHistory) -> Self {
+        self.attestation_history = history;
+        self
+    }
+
+    /// Replace the per-namespace limit on `/zkpf/policies/compose`.
+    pub fn with_compose_rate_limiter(mut self, limiter: ComposeRateLimiter) -> Self {
+        self.compose_limiter = limiter;
+        self
+    }
+
+    /// Replace the proxies whose forwarding headers `client_ip` believes.
+    pub fn with_trusted_proxies(mut self, proxies: TrustedProxies) -> Self {
+        self.trusted_proxies = proxies;
+        self
+    }
+
     pub fn with_epoch_config(artifacts: Arc<ProverArtifacts>, epoch: EpochConfig) -> Self {
         Self::with_components(
             artifacts,
@@ -535,61 +796,111 @@ impl AppState {
     pub fn provider_sessions(&self) -> &ProviderSessionStore {
         &self.provider_sessions
     }
+
+    pub fn challenge_store(&self) -> &ChallengeStore {
+        &self.challenges
+    }
+
+    pub fn replay_log(&self) -> &ReplayLog {
+        &self.replays
+    }
+
+    pub fn compose_rate_limiter(&self) -> &ComposeRateLimiter {
+        &self.compose_limiter
+    }
+
+    pub fn trusted_proxies(&self) -> &TrustedProxies {
+        &self.trusted_proxies
+    }
+
+    pub fn height_provider(&self) -> &dyn HeightProvider {
+        self.heights.as_ref()
+    }
+
+    pub fn attestation_backend(&self) -> Option<&dyn AttestationBackend> {
+        self.attestation.as_deref()
+    }
+
+    pub fn attestation_history(&self) -> &AttestationHistory {
+        &self.attestation_history
+    }
 }
 
 #[derive(Debug)]
 struct ApiError {
     status: StatusCode,
-    code: &'static str,
+    code: ErrorCode,
     message: String,
+    /// Set on `CIRCUIT_VERSION_MISMATCH`, so clients can fetch matching artifacts.
+    expected_circuit_version: Option<u32>,
 }
 
 impl ApiError {
-    fn new(status: StatusCode, code: &'static str, message: impl Into<String>) -> Self {
+    fn new(status: StatusCode, code: ErrorCode, message: impl Into<String>) -> Self {
         Self {
             status,
             code,
             message: message.into(),
+            expected_circuit_version: None,
         }
     }
 
-    fn bad_request(code: &'static str, message: impl Into<String>) -> Self {
+    /// Error with the code's canonical HTTP status.
+    fn from_code(code: ErrorCode, message: impl Into<String>) -> Self {
+        let status =
+            StatusCode::from_u16(code.http_status()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        Self::new(status, code, message)
+    }
+
+    fn bad_request(code: ErrorCode, message: impl Into<String>) -> Self {
         Self::new(StatusCode::BAD_REQUEST, code, message)
     }
 
+    fn circuit_version_mismatch(expected: u32, got: u32) -> Self {
+        Self {
+            expected_circuit_version: Some(expected),
+            ..Self::bad_request(
+                ErrorCode::CircuitVersionMismatch,
+                format!(
+                    "circuit version mismatch: rail expects {}, got {}",
+                    expected, got
+                ),
+            )
+        }
+    }
+
     fn policy_not_found(policy_id: u64) -> Self {
-        Self::new(
-            StatusCode::NOT_FOUND,
-            CODE_POLICY_NOT_FOUND,
+        Self::from_code(
+            ErrorCode::PolicyNotFound,
             format!("policy_id {} not found", policy_id),
         )
     }
 
     fn nullifier_store(err: impl Into<String>) -> Self {
-        Self::new(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            CODE_NULLIFIER_STORE_ERROR,
-            err,
-        )
+        Self::from_code(ErrorCode::NullifierStoreError, err)
     }
 
     fn internal(err: impl Into<String>) -> Self {
-        Self::new(StatusCode::INTERNAL_SERVER_ERROR, CODE_INTERNAL, err)
+        Self::from_code(ErrorCode::InternalServerError, err)
     }
 
     fn prover_disabled(err: impl Into<String>) -> Self {
-        Self::new(StatusCode::SERVICE_UNAVAILABLE, CODE_PROVER_DISABLED, err)
+        Self::from_code(ErrorCode::ProverDisabled, err)
     }
 
     fn not_found(message: impl Into<String>) -> Self {
-        Self::new(StatusCode::NOT_FOUND, CODE_ARTIFACT_NOT_FOUND, message)
+        Self::from_code(ErrorCode::ArtifactNotFound, message)
     }
 }
 
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, ToSchema)]
 struct ErrorResponse {
     error: String,
-    error_code: &'static str,
+    #[schema(value_type = String)]
+    error_code: ErrorCode,
+    /// Circuit version the rail accepts; present on `CIRCUIT_VERSION_MISMATCH`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expected_circuit_version: Option<u32>,
 }
 
 impl IntoResponse for ApiError {
@@ -597,6 +908,7 @@ impl IntoResponse for ApiError {
         let body = ErrorResponse {
             error: self.message,
             error_code: self.code,
+            expected_circuit_version: self.expected_circuit_version,
         };
         (self.status, Json(body)).into_response()
     }
@@ -624,30 +936,93 @@ pub async fn serve() {
         .allow_methods(Any)
         .allow_headers(Any);
 
+    if env_flag(PREWARM_PK_ENV) {
+        prewarm_proving_key(state.artifacts.clone());
+    }
+
+    policy_source::spawn_policy_refresh(state.policy_store().clone());
+
+    if let Err(err) = &*SELF_CHECK {
+        eprintln!("zkpf-backend: self-check FAILED, /readyz will report it: {err}");
+    }
+
     let app = app_router(state).layer(cors);
     
     eprintln!("zkpf-backend: server ready");
     
-    axum::serve(listener, app.into_make_service())
-        .await
-        .unwrap();
+    // Connect info carries the socket peer that `client_ip::client_ip` starts from.
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    .unwrap();
 }
 
 pub fn app_router(state: AppState) -> Router {
     let router = Router::new()
         // Health check endpoint - responds immediately, no state required
         .route("/health", get(health_check))
+        .route("/readyz", get(readiness_check))
+        .route("/openapi.json", get(openapi::openapi_json))
         .route("/zkpf/policies", get(list_policies))
         .route("/zkpf/policies/compose", post(compose_policy_handler))
         .route("/zkpf/params", get(get_params))
         .route("/zkpf/artifacts/:kind", get(get_artifact))
+        .route("/zkpf/rails", get(list_rails))
+        .route("/zkpf/status", get(status_handler))
+        .route("/zkpf/info", get(info_handler))
         // Rail-specific artifact endpoints for multi-rail support (e.g., Orchard k=19)
         .route("/zkpf/rails/:rail_id/params", get(get_rail_params))
         .route("/zkpf/rails/:rail_id/artifacts/:kind", get(get_rail_artifact))
         .route("/zkpf/epoch", get(get_epoch))
+        .route("/zkpf/challenge", post(issue_challenge_handler))
         .route("/zkpf/verify", post(verify_handler))
-        .route("/zkpf/verify-bundle", post(verify_bundle_handler))
-        .route("/zkpf/attest", post(attest_handler))
+        .route(
+            "/zkpf/verify-bundle",
+            accept_gzip_body(post(verify_bundle_handler)),
+        )
+        .route(
+            "/zkpf/verify-by-criteria",
+            accept_gzip_body(post(verify_by_criteria_handler)),
+        )
+        .route(
+            "/zkpf/verify-bundle/batch",
+            post(verify_bundle_batch_handler)
+                .layer(DefaultBodyLimit::max(MAX_BATCH_BODY_BYTES))
+                .layer(RequestDecompressionLayer::new()),
+        )
+        .route(
+            "/zkpf/verify-bundle/dry-run",
+            post(verify_bundle_dry_run_handler),
+        )
+        .route(
+            "/zkpf/epoch-proof/verify",
+            post(epoch_proof_verify_handler)
+                .layer(DefaultBodyLimit::max(MAX_BATCH_BODY_BYTES))
+                .layer(RequestDecompressionLayer::new()),
+        )
+        .route(
+            "/zkpf/verify-bundle/multipart",
+            post(verify_bundle_multipart_handler)
+                .layer(DefaultBodyLimit::max(MAX_MULTIPART_BODY_BYTES)),
+        )
+        .route("/zkpf/attest", accept_gzip_body(post(attest_handler)))
+        .route(
+            "/zkpf/verify-and-attest",
+            accept_gzip_body(post(verify_and_attest_handler)),
+        )
+        .route(
+            "/zkpf/attest-batch",
+            post(attest_batch_handler)
+                .layer(DefaultBodyLimit::max(MAX_BATCH_BODY_BYTES))
+                .layer(RequestDecompressionLayer::new()),
+        )
+        .route("/zkpf/attestations", get(attestations_handler))
+        .route(
+            "/zkpf/prove-bundle/preview",
+            post(prove_bundle_preview_handler),
+        )
         // MetaMask Snap hosting routes
         .route("/snap/snap.manifest.json", get(serve_snap_manifest))
         .route("/snap/dist/bundle.js", get(serve_snap_bundle))
@@ -655,7 +1030,10 @@ pub fn app_router(state: AppState) -> Router {
 
     let router = if state.artifacts().prover_enabled() {
         router
-            .route("/zkpf/prove-bundle", post(prove_bundle_handler))
+            .route(
+                "/zkpf/prove-bundle",
+                accept_gzip_body(post(prove_bundle_handler)),
+            )
             .route(
                 "/zkpf/provider/prove-balance",
                 post(provider_prove_balance_handler),
@@ -667,13 +1045,57 @@ pub fn app_router(state: AppState) -> Router {
         router
     };
 
+    let router = if ADMIN_TOKEN.is_some() {
+        router
+            .route(
+                "/zkpf/admin/nullifiers/:scope_id/:policy_id/:nullifier",
+                get(nullifier_audit_handler),
+            )
+            .route("/zkpf/admin/replays", get(replays_handler))
+            .route("/zkpf/admin/sessions/purge", post(purge_sessions_handler))
+            .route(
+                "/zkpf/admin/sessions/expired",
+                get(expired_sessions_handler),
+            )
+    } else {
+        router
+    };
+
     let router = router.with_state(state);
 
     // Merge Personhood routes (has its own state)
     eprintln!("zkpf-backend: Personhood routes enabled at /api/personhood/*");
-    Router::new()
+    let router = Router::new()
         .merge(router)
-        .merge(personhood::personhood_router_with_state())
+        .merge(personhood::personhood_router_with_state());
+
+    if response_compression_from_env() {
+        router.layer(response_compression())
+    } else {
+        router
+    }
+}
+
+/// gzip or brotli response compression, negotiated from `Accept-Encoding`.
+///
+/// Artifact downloads are served as `application/octet-stream` and skipped:
+/// they are mostly incompressible key material, streamed with ETags, and
+/// compressing them would only cost CPU.
+fn response_compression() -> CompressionLayer<impl Predicate> {
+    CompressionLayer::new().compress_when(
+        DefaultPredicate::new().and(NotForContentType::const_new("application/octet-stream")),
+    )
+}
+
+/// Accept `Content-Encoding: gzip` request bodies on a route.
+///
+/// The body limit is applied to the decompressed stream, so a small compressed
+/// payload cannot expand past MAX_DECOMPRESSED_BODY_BYTES. Handlers still run
+/// their own MAX_* checks on the parsed request.
+fn accept_gzip_body(route: MethodRouter<AppState>) -> MethodRouter<AppState> {
+    route
+        .layer(DefaultBodyLimit::max(MAX_DECOMPRESSED_BODY_BYTES))
+        .layer(RequestDecompressionLayer::new())
 }
 
 async fn get_artifact(
@@ -687,7 +1109,7 @@ async fn get_artifact(
         "pk" => artifacts.pk_path(),
         _ => {
             return Err(ApiError::bad_request(
-                CODE_ARTIFACT_NOT_FOUND,
+                ErrorCode::ArtifactNotFound,
                 "unknown artifact kind",
             ))
         }
@@ -775,33 +1197,16 @@ async fn get_rail_params(
         PublicInputLayout::V1 => "V1",
         PublicInputLayout::V2Orchard => "V2_ORCHARD",
         PublicInputLayout::V3Starknet => "V3_STARKNET",
+        PublicInputLayout::V4Aggregate => "V4_AGGREGATE",
+        PublicInputLayout::V5Range => "V5_RANGE",
+        PublicInputLayout::V1WithSum => "V1_WITH_SUM",
+        PublicInputLayout::V6SnapshotCommitment => "V6_SNAPSHOT_COMMITMENT",
     };
 
-    // Check if break_points.json exists for halo2-base circuits (Orchard, etc.)
-    // Break points are REQUIRED for proof generation in these circuits.
-    let break_points_path = rail.artifact_dir().map(|dir| dir.join("break_points.json"));
-    let has_break_points = break_points_path.as_ref().map(|p| p.exists()).unwrap_or(false);
-    
-    // Compute break_points hash if the file exists
-    let break_points_hash = if has_break_points {
-        if let Some(ref path) = break_points_path {
-            match fs::read(path) {
-                Ok(bytes) => Some(zkpf_common::hash_bytes_hex(&bytes)),
-                Err(err) => {
-                    eprintln!(
-                        "zkpf-backend: failed to read break_points.json for rail '{}': {}",
-                        rail_id, err
-                    );
-                    None
-                }
-            }
-        } else {
-            None
-        }
-    } else {
-        None
-    };
-    
+    // Break points are REQUIRED for proof generation in halo2-base circuits (Orchard, etc.)
+    let break_points_hash = rail.break_points_hash(&rail_id);
+    let has_break_points = break_points_hash.is_some();
+
     // Check if artifacts are available on disk
     let artifact_urls = RailArtifactUrls {
         params: format!("/zkpf/rails/{}/artifacts/params", rail_id),
@@ -864,11 +1269,14 @@ async fn get_rail_artifact(
         rail.artifact_dir()
             .map(|dir| dir.join("break_points.json"))
             .ok_or_else(|| {
-                ApiError::bad_request(CODE_ARTIFACT_NOT_FOUND, "artifact directory not configured")
+                ApiError::bad_request(
+                    ErrorCode::ArtifactNotFound,
+                    "artifact directory not configured",
+                )
             })?
     } else {
         rail.artifact_path(&kind).ok_or_else(|| {
-            ApiError::bad_request(CODE_ARTIFACT_NOT_FOUND, "unknown artifact kind")
+            ApiError::bad_request(ErrorCode::ArtifactNotFound, "unknown artifact kind")
         })?
     };
 
@@ -929,7 +1337,30 @@ async fn get_rail_artifact(
     Ok(response)
 }
 
-async fn get_params(State(state): State<AppState>) -> Result<Json<ParamsResponse>, ApiError> {
+#[utoipa::path(
+    get,
+    path = "/zkpf/params",
+    tag = "artifacts",
+    params(ParamsQuery),
+    responses(
+        (status = 200, description = "Manifest hashes and params/vk/pk bytes or download URLs", body = ParamsResponse),
+        (status = 400, description = "Unknown rail", body = ErrorResponse),
+        (status = 404, description = "The rail's artifacts are not deployed", body = ErrorResponse),
+        (status = 500, description = "Artifacts could not be read", body = ErrorResponse),
+    )
+)]
+async fn get_params(
+    State(state): State<AppState>,
+    Query(query): Query<ParamsQuery>,
+) -> Result<Json<ParamsResponse>, ApiError> {
+    if let Some(rail_id) = query
+        .rail
+        .as_deref()
+        .filter(|rail_id| !rail_id.is_empty() && *rail_id != DEFAULT_RAIL_ID)
+    {
+        return rail_params_response(rail_id).map(Json);
+    }
+
     let artifacts = state.artifacts();
     let manifest = &artifacts.manifest;
     // When the prover is disabled for this deployment we avoid loading large
@@ -948,6 +1379,7 @@ async fn get_params(State(state): State<AppState>) -> Result<Json<ParamsResponse
                 params: "/zkpf/artifacts/params".to_string(),
                 vk: "/zkpf/artifacts/vk".to_string(),
                 pk: "/zkpf/artifacts/pk".to_string(),
+                break_points: None,
             })
         } else {
             // Log which artifacts are missing for debugging
@@ -961,6 +1393,8 @@ async fn get_params(State(state): State<AppState>) -> Result<Json<ParamsResponse
         };
 
         return Ok(Json(ParamsResponse {
+            rail_id: DEFAULT_RAIL_ID.to_string(),
+            layout: PublicInputLayout::V1,
             circuit_version: manifest.circuit_version,
             manifest_version: manifest.manifest_version,
             params_hash: manifest.params.blake3.clone(),
@@ -984,6 +1418,8 @@ async fn get_params(State(state): State<AppState>) -> Result<Json<ParamsResponse
         .map_err(|err| ApiError::internal(format!("failed to load pk blob: {err}")))?;
 
     Ok(Json(ParamsResponse {
+        rail_id: DEFAULT_RAIL_ID.to_string(),
+        layout: PublicInputLayout::V1,
         circuit_version: manifest.circuit_version,
         manifest_version: manifest.manifest_version,
         params_hash: manifest.params.blake3.clone(),
@@ -996,219 +1432,434 @@ async fn get_params(State(state): State<AppState>) -> Result<Json<ParamsResponse
     }))
 }
 
-async fn list_policies(State(state): State<AppState>) -> Json<PoliciesResponse> {
-    Json(PoliciesResponse {
-        policies: state.policy_store().all(),
+/// Describes a registered rail for `GET /zkpf/params?rail=<id>`. Blobs are
+/// never inlined; clients download them from the rail's artifact URLs.
+fn rail_params_response(rail_id: &str) -> Result<ParamsResponse, ApiError> {
+    let rail = RAILS.get(rail_id).ok_or_else(|| {
+        ApiError::bad_request(
+            ErrorCode::RailUnknown,
+            format!("unknown rail '{}'", rail_id),
+        )
+    })?;
+    if !rail.artifact_exists("params") || !rail.artifact_exists("vk") {
+        return Err(ApiError::not_found(format!(
+            "artifacts for rail '{}' not available on this deployment",
+            rail_id
+        )));
+    }
+
+    // Verifier-only deployments may not ship the proving key; only advertise
+    // what can actually be downloaded.
+    let manifest = rail.artifacts.manifest();
+    let artifact_urls = rail.artifact_exists("pk").then(|| ArtifactUrls {
+        params: format!("/zkpf/rails/{}/artifacts/params", rail_id),
+        vk: format!("/zkpf/rails/{}/artifacts/vk", rail_id),
+        pk: format!("/zkpf/rails/{}/artifacts/pk", rail_id),
+        break_points: rail
+            .break_points_hash(rail_id)
+            .map(|_| format!("/zkpf/rails/{}/artifacts/break_points", rail_id)),
+    });
+
+    Ok(ParamsResponse {
+        rail_id: rail_id.to_string(),
+        layout: rail.layout,
+        circuit_version: rail.circuit_version,
+        manifest_version: manifest.manifest_version,
+        params_hash: manifest.params.blake3.clone(),
+        vk_hash: manifest.vk.blake3.clone(),
+        pk_hash: manifest.pk.blake3.clone(),
+        params: None,
+        vk: None,
+        pk: None,
+        artifact_urls,
     })
 }
 
-async fn compose_policy_handler(
+#[utoipa::path(
+    get,
+    path = "/zkpf/policies",
+    tag = "policies",
+    responses(
+        (status = 200, description = "Policies in the caller's namespace", body = PoliciesResponse),
+        (status = 401, description = "UNAUTHORIZED", body = ErrorResponse),
+    )
+)]
+async fn list_policies(
     State(state): State<AppState>,
-    Json(req): Json<PolicyComposeRequest>,
-) -> Result<Json<PolicyComposeResponse>, ApiError> {
-    validate_policy_compose_request(&req)?;
+    headers: HeaderMap,
+) -> Result<Json<PoliciesResponse>, ApiError> {
+    let namespace = caller_namespace(&headers)?;
+    Ok(Json(PoliciesResponse {
+        policies: state.policy_store().all_in(namespace.as_deref()),
+    }))
+}
 
-    let path = policy_config_path();
-    let path_ref = Path::new(&path);
+/// List the rails this deployment can verify, with their layouts and circuit versions.
+#[utoipa::path(
+    get,
+    path = "/zkpf/rails",
+    tag = "rails",
+    responses((status = 200, description = "Verifiable rails sorted by rail_id", body = RailsResponse))
+)]
+async fn list_rails() -> Json<RailsResponse> {
+    Json(RailsResponse {
+        rails: RAILS.rail_infos(),
+    })
+}
 
-    let mut entries: Vec<JsonValue> = if path_ref.exists() {
-        let bytes = fs::read(path_ref).map_err(|err| {
-            ApiError::internal(format!(
-                "failed to read policy configuration from {}: {}",
-                path_ref.display(),
-                err
-            ))
-        })?;
-        serde_json::from_slice(&bytes).map_err(|err| {
-            ApiError::internal(format!(
-                "failed to parse policy configuration from {}: {}",
-                path_ref.display(),
-                err
-            ))
-        })?
-    } else {
-        Vec::new()
-    };
+/// Diagnostic inventory: registered rails, their loaded artifacts, and which
+/// artifact files exist on disk. Read-only; exposes hashes but no paths.
+#[utoipa::path(
+    get,
+    path = "/zkpf/status",
+    tag = "health",
+    responses((status = 200, description = "Rail and artifact inventory", body = StatusResponse))
+)]
+async fn status_handler(State(state): State<AppState>) -> Json<StatusResponse> {
+    let artifacts = state.artifacts();
+    Json(StatusResponse {
+        prover_enabled: artifacts.prover_enabled(),
+        prover_degraded: artifacts.prover_degraded(),
+        rails: RAILS.rail_statuses(),
+    })
+}
 
-    let key_category = req.category.to_ascii_uppercase();
-    let key_rail = req.rail_id.clone();
-    let key_threshold = req.threshold_raw;
-    let key_currency = req.required_currency_code as u64;
-    let key_scope = req.verifier_scope_id;
-
-    let mut max_policy_id: u64 = 0;
-    let mut existing: Option<JsonValue> = None;
-
-    for entry in &entries {
-        let policy_id = entry.get("policy_id").and_then(|v| v.as_u64()).unwrap_or(0);
-        if policy_id > max_policy_id {
-            max_policy_id = policy_id;
-        }
-
-        let category = entry
-            .get("category")
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_ascii_uppercase();
-        let rail = entry.get("rail_id").and_then(|v| v.as_str()).unwrap_or("");
-        let threshold = entry
-            .get("threshold_raw")
-            .and_then(|v| v.as_u64())
-            .unwrap_or(0);
-        let currency = entry
-            .get("required_currency_code")
-            .and_then(|v| v.as_u64())
-            .unwrap_or(0);
-        let scope = entry
-            .get("verifier_scope_id")
-            .and_then(|v| v.as_u64())
-            .unwrap_or(0);
-
-        if category == key_category
-            && rail == key_rail
-            && threshold == key_threshold
-            && currency == key_currency
-            && scope == key_scope
-        {
-            existing = Some(entry.clone());
-            break;
+/// Deployment capabilities in one response: circuit and manifest versions,
+/// proving, rails and layouts, size limits, attestation and Snap hosting.
+#[utoipa::path(
+    get,
+    path = "/zkpf/info",
+    tag = "health",
+    responses((status = 200, description = "Deployment capabilities", body = InfoResponse))
+)]
+async fn info_handler(State(state): State<AppState>) -> Json<InfoResponse> {
+    let artifacts = state.artifacts();
+    let rails = RAILS.rail_infos();
+    let mut layouts: Vec<PublicInputLayout> = Vec::new();
+    for rail in &rails {
+        if !layouts.contains(&rail.layout) {
+            layouts.push(rail.layout);
         }
     }
+    Json(InfoResponse {
+        circuit_version: artifacts.manifest.circuit_version,
+        manifest_version: artifacts.manifest.manifest_version,
+        prover_enabled: artifacts.prover_enabled(),
+        pk_url: artifacts
+            .pk_path()
+            .exists()
+            .then(|| "/zkpf/artifacts/pk".to_string()),
+        rails,
+        layouts,
+        max_proof_size: MAX_PROOF_SIZE_BYTES,
+        attestation_enabled: state.attestation_backend().is_some(),
+        snap_hosting: Path::new(&snap_dir()).join("snap.manifest.json").exists(),
+    })
+}
 
-    // Check if a specific policy_id was requested and if it already exists
-    let requested_id_exists = req.policy_id.map(|id| {
-        entries.iter().any(|e| e.get("policy_id").and_then(|v| v.as_u64()) == Some(id))
-    }).unwrap_or(false);
+/// Audit lookup: when, and on which rail, a nullifier was consumed.
+///
+/// Only routed when `ZKPF_ADMIN_TOKEN` is set; callers must send it as a
+/// bearer token.
+#[utoipa::path(
+    get,
+    path = "/zkpf/admin/nullifiers/{scope_id}/{policy_id}/{nullifier}",
+    tag = "admin",
+    params(
+        ("scope_id" = u64, Path, description = "Verifier scope the nullifier was consumed under"),
+        ("policy_id" = u64, Path, description = "Policy the nullifier was consumed under"),
+        ("nullifier" = String, Path, description = "Nullifier as 32-byte hex"),
+    ),
+    responses(
+        (status = 200, description = "Consumption record", body = NullifierRecord),
+        (status = 400, description = "PUBLIC_INPUTS_INVALID", body = ErrorResponse),
+        (status = 401, description = "UNAUTHORIZED", body = ErrorResponse),
+        (status = 404, description = "NULLIFIER_NOT_FOUND", body = ErrorResponse),
+        (status = 500, description = "NULLIFIER_STORE_ERROR", body = ErrorResponse),
+    )
+)]
+async fn nullifier_audit_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    AxumPath((scope_id, policy_id, nullifier)): AxumPath<(u64, u64, String)>,
+) -> Result<Json<NullifierRecord>, ApiError> {
+    ensure_admin(&headers)?;
+    let key = NullifierKey {
+        scope_id,
+        policy_id,
+        nullifier: parse_hex_32(&nullifier)?,
+    };
+    match state.nullifier_store().lookup(&key) {
+        Ok(Some(record)) => Ok(Json(record)),
+        Ok(None) => Err(ApiError::new(
+            StatusCode::NOT_FOUND,
+            ErrorCode::NullifierNotFound,
+            "nullifier has not been consumed",
+        )),
+        Err(_) => Err(ApiError::nullifier_store("nullifier store error")),
+    }
+}
 
-    let (policy_value, created, policy_id) = if let Some(value) = existing {
-        // Found existing policy with matching parameters
-        let policy_id = value
-            .get("policy_id")
-            .and_then(|v| v.as_u64())
-            .ok_or_else(|| {
-                ApiError::internal("existing policy entry missing policy_id field".to_string())
-            })?;
-        (value, false, policy_id)
-    } else if let Some(requested_id) = req.policy_id {
-        if requested_id_exists {
-            // Requested policy_id exists but with different parameters
-            return Err(ApiError::bad_request(
-                CODE_POLICY_COMPOSE_INVALID,
-                format!(
-                    "policy_id {} already exists with different parameters",
-                    requested_id
-                ),
-            ));
-        }
-        // Use the requested policy_id for the new policy
-        let entry = serde_json::json!({
-            "category": req.category,
-            "label": req.label,
-            "rail_id": req.rail_id,
-            "options": req.options,
-            "threshold_raw": req.threshold_raw,
-            "required_currency_code": req.required_currency_code,
-            "verifier_scope_id": req.verifier_scope_id,
-            "policy_id": requested_id,
-        });
+/// Most recent nullifier replay rejections, newest first.
+///
+/// Only routed when `ZKPF_ADMIN_TOKEN` is set; callers must send it as a
+/// bearer token.
+#[utoipa::path(
+    get,
+    path = "/zkpf/admin/replays",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Recent replay rejections", body = [ReplayEvent]),
+        (status = 401, description = "UNAUTHORIZED", body = ErrorResponse),
+    )
+)]
+async fn replays_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<ReplayEvent>>, ApiError> {
+    ensure_admin(&headers)?;
+    Ok(Json(state.replay_log().recent()))
+}
 
-        // Insert into in-memory store FIRST (before file write) so verification
-        // can proceed even if file persistence fails (e.g., on read-only filesystems)
-        let expectations = PolicyExpectations {
-            threshold_raw: req.threshold_raw,
-            required_currency_code: req.required_currency_code,
-            verifier_scope_id: req.verifier_scope_id,
-            policy_id: requested_id,
-            category: Some(req.category.clone()),
-            rail_id: Some(req.rail_id.clone()),
-            label: Some(req.label.clone()),
-            options: Some(req.options.clone()),
-        };
-        state.policy_store().insert(expectations);
+/// Drop provider sessions kept past their retention window now, instead of on
+/// the next session request.
+///
+/// Only routed when `ZKPF_ADMIN_TOKEN` is set; callers must send it as a
+/// bearer token.
+#[utoipa::path(
+    post,
+    path = "/zkpf/admin/sessions/purge",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Number of sessions removed", body = SessionPurgeResponse),
+        (status = 401, description = "UNAUTHORIZED", body = ErrorResponse),
+    )
+)]
+async fn purge_sessions_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<SessionPurgeResponse>, ApiError> {
+    ensure_admin(&headers)?;
+    let removed = state.provider_sessions().purge();
+    eprintln!("zkpf-backend: admin purge removed {removed} expired provider sessions");
+    Ok(Json(SessionPurgeResponse { removed }))
+}
 
-        // Try to persist to file (best-effort, non-fatal on failure)
-        entries.push(entry.clone());
-        if let Ok(json_bytes) = serde_json::to_vec_pretty(&entries) {
-            if let Err(err) = fs::write(path_ref, &json_bytes) {
-                eprintln!(
-                    "warning: failed to persist policy {} to {}: {} (in-memory store updated)",
-                    requested_id,
-                    path_ref.display(),
-                    err
-                );
-            }
-        }
+/// Provider sessions past their expiry that are still retained, oldest expiry
+/// first.
+///
+/// Only routed when `ZKPF_ADMIN_TOKEN` is set; callers must send it as a
+/// bearer token.
+#[utoipa::path(
+    get,
+    path = "/zkpf/admin/sessions/expired",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Expired sessions still retained", body = [ExpiredSession]),
+        (status = 401, description = "UNAUTHORIZED", body = ErrorResponse),
+    )
+)]
+async fn expired_sessions_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<ExpiredSession>>, ApiError> {
+    ensure_admin(&headers)?;
+    Ok(Json(state.provider_sessions().expired()))
+}
 
-        (entry, true, requested_id)
+/// Require `Authorization: Bearer <ZKPF_ADMIN_TOKEN>`.
+fn ensure_admin(headers: &HeaderMap) -> Result<(), ApiError> {
+    let unauthorized = || {
+        ApiError::new(
+            StatusCode::UNAUTHORIZED,
+            ErrorCode::Unauthorized,
+            "admin token required",
+        )
+    };
+    let expected = ADMIN_TOKEN.as_deref().ok_or_else(unauthorized)?;
+    let provided = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or_else(unauthorized)?;
+    // Compare in constant time so the token cannot be guessed byte by byte.
+    if constant_time_eq(provided.as_bytes(), expected.as_bytes()) {
+        Ok(())
     } else {
-        // Auto-assign a new policy_id
-        let new_policy_id = max_policy_id.saturating_add(1);
-
-        let entry = serde_json::json!({
-            "category": req.category,
-            "label": req.label,
-            "rail_id": req.rail_id,
-            "options": req.options,
-            "threshold_raw": req.threshold_raw,
-            "required_currency_code": req.required_currency_code,
-            "verifier_scope_id": req.verifier_scope_id,
-            "policy_id": new_policy_id,
-        });
-
-        // Insert into in-memory store FIRST (before file write) so verification
-        // can proceed even if file persistence fails (e.g., on read-only filesystems)
-        let expectations = PolicyExpectations {
-            threshold_raw: req.threshold_raw,
-            required_currency_code: req.required_currency_code,
-            verifier_scope_id: req.verifier_scope_id,
-            policy_id: new_policy_id,
-            category: Some(req.category.clone()),
-            rail_id: Some(req.rail_id.clone()),
-            label: Some(req.label.clone()),
-            options: Some(req.options.clone()),
-        };
-        state.policy_store().insert(expectations);
+        Err(unauthorized())
+    }
+}
 
-        // Try to persist to file (best-effort, non-fatal on failure)
-        entries.push(entry.clone());
-        if let Ok(json_bytes) = serde_json::to_vec_pretty(&entries) {
-            if let Err(err) = fs::write(path_ref, &json_bytes) {
-                eprintln!(
-                    "warning: failed to persist policy {} to {}: {} (in-memory store updated)",
-                    new_policy_id,
-                    path_ref.display(),
-                    err
-                );
+fn parse_api_keys(value: &str) -> Vec<(String, String)> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .filter_map(|entry| match entry.split_once('=') {
+            Some((namespace, key)) if !namespace.trim().is_empty() && !key.trim().is_empty() => {
+                Some((namespace.trim().to_string(), key.trim().to_string()))
             }
-        }
+            _ => {
+                eprintln!("warning: ignoring malformed {API_KEYS_ENV} entry");
+                None
+            }
+        })
+        .collect()
+}
 
-        (entry, true, new_policy_id)
+/// Resolve the caller's policy namespace from its `x-api-key` header.
+///
+/// Requests without a key use the shared namespace (`None`); an unknown key is
+/// rejected rather than falling back to it.
+fn caller_namespace(headers: &HeaderMap) -> Result<Option<String>, ApiError> {
+    let Some(value) = headers.get(API_KEY_HEADER) else {
+        return Ok(None);
+    };
+    let unauthorized = || {
+        ApiError::new(
+            StatusCode::UNAUTHORIZED,
+            ErrorCode::Unauthorized,
+            "unknown API key",
+        )
     };
+    let provided = value.to_str().map_err(|_| unauthorized())?.trim();
+    API_KEYS
+        .iter()
+        .find(|(_, key)| constant_time_eq(provided.as_bytes(), key.as_bytes()))
+        .map(|(namespace, _)| Some(namespace.clone()))
+        .ok_or_else(unauthorized)
+}
 
-    // Policy was already inserted into store above for new policies.
-    // For existing policies returned from the match, ensure store is also updated.
-    if !created {
-        let expectations = PolicyExpectations {
-            threshold_raw: req.threshold_raw,
-            required_currency_code: req.required_currency_code,
-            verifier_scope_id: req.verifier_scope_id,
-            policy_id,
-            category: Some(req.category.clone()),
-            rail_id: Some(req.rail_id.clone()),
-            label: Some(req.label.clone()),
-            options: Some(req.options.clone()),
-        };
-        if state.policy_store().get(policy_id).is_none() {
-            state.policy_store().insert(expectations);
+#[utoipa::path(
+    post,
+    path = "/zkpf/policies/compose",
+    tag = "policies",
+    request_body = PolicyComposeRequest,
+    responses(
+        (status = 200, description = "Existing or newly created policy", body = PolicyComposeResponse),
+        (status = 400, description = "POLICY_COMPOSE_INVALID", body = ErrorResponse),
+        (status = 401, description = "UNAUTHORIZED", body = ErrorResponse),
+        (status = 429, description = "RATE_LIMITED", body = ErrorResponse),
+        (status = 500, description = "INTERNAL_SERVER_ERROR", body = ErrorResponse),
+    )
+)]
+async fn compose_policy_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<PolicyComposeRequest>,
+) -> Result<Json<PolicyComposeResponse>, ApiError> {
+    let namespace = caller_namespace(&headers)?;
+    // Once tenants are configured, the shared namespace is only edited through
+    // the policy file.
+    if namespace.is_none() && !API_KEYS.is_empty() {
+        return Err(ApiError::new(
+            StatusCode::UNAUTHORIZED,
+            ErrorCode::Unauthorized,
+            "API key required to compose policies",
+        ));
+    }
+    if !state
+        .compose_rate_limiter()
+        .try_acquire(namespace.as_deref().unwrap_or(""))
+    {
+        return Err(ApiError::from_code(
+            ErrorCode::RateLimited,
+            "policy compose rate limit exceeded",
+        ));
+    }
+    validate_policy_compose_request(&req)?;
+
+    let path = policy_config_path();
+    let path_ref = Path::new(&path);
+
+    let mut entries: Vec<JsonValue> = if path_ref.exists() {
+        let bytes = fs::read(path_ref).map_err(|err| {
+            ApiError::internal(format!(
+                "failed to read policy configuration from {}: {}",
+                path_ref.display(),
+                err
+            ))
+        })?;
+        serde_json::from_slice(&bytes).map_err(|err| {
+            ApiError::internal(format!(
+                "failed to parse policy configuration from {}: {}",
+                path_ref.display(),
+                err
+            ))
+        })?
+    } else {
+        Vec::new()
+    };
+
+    let policy = PolicyExpectations {
+        threshold_raw: req.threshold_raw,
+        threshold_max: req.threshold_max,
+        layout: req.layout,
+        required_currency_code: req.required_currency_code,
+        verifier_scope_id: req.verifier_scope_id,
+        verifier_scope_prefix: req.verifier_scope_prefix,
+        allowed_anchor_heights: req.allowed_anchor_heights.clone(),
+        max_proof_age_secs: req.max_proof_age_secs,
+        policy_id: 0,
+        category: Some(req.category.clone()),
+        rail_id: Some(req.rail_id.clone()),
+        label: Some(req.label.clone()),
+        options: Some(req.options.clone()),
+        namespace: namespace.clone(),
+    };
+    let encode = |policy: &PolicyExpectations| {
+        serde_json::to_value(policy)
+            .map_err(|err| ApiError::internal(format!("failed to encode policy: {err}")))
+    };
+
+    if !req.force_new {
+        // Reuse a policy with the same terms, whether sourced or composed.
+        let existing = state
+            .policy_store()
+            .all_in(namespace.as_deref())
+            .into_iter()
+            .filter(|existing| existing.same_terms(&policy))
+            .min_by_key(|existing| existing.policy_id);
+        if let Some(existing) = existing {
+            return Ok(Json(PolicyComposeResponse {
+                policy: encode(&existing)?,
+                summary: req.label,
+                created: false,
+            }));
         }
     }
 
-    let summary = req.label;
+    // Insert into in-memory store FIRST (before file write) so verification
+    // can proceed even if file persistence fails (e.g., on read-only filesystems)
+    let policy = state
+        .policy_store()
+        .insert_composed(policy, req.policy_id)
+        .map_err(|taken| {
+            ApiError::bad_request(
+                ErrorCode::PolicyComposeInvalid,
+                format!("policy_id {taken} already exists with different parameters"),
+            )
+        })?;
+    let policy_id = policy.policy_id;
+    let entry = encode(&policy)?;
+
+    // Try to persist to file (best-effort, non-fatal on failure)
+    entries.push(entry.clone());
+    if let Ok(json_bytes) = serde_json::to_vec_pretty(&entries) {
+        if let Err(err) = fs::write(path_ref, &json_bytes) {
+            eprintln!(
+                "warning: failed to persist policy {} to {}: {} (in-memory store updated)",
+                policy_id,
+                path_ref.display(),
+                err
+            );
+        }
+    }
 
     Ok(Json(PolicyComposeResponse {
-        policy: policy_value,
-        summary,
-        created,
+        policy: entry,
+        summary: req.label,
+        created: true,
     }))
 }
 
@@ -1216,19 +1867,19 @@ fn validate_policy_compose_request(req: &PolicyComposeRequest) -> Result<(), Api
     // Non-empty checks
     if req.category.trim().is_empty() {
         return Err(ApiError::bad_request(
-            CODE_POLICY_COMPOSE_INVALID,
+            ErrorCode::PolicyComposeInvalid,
             "category must not be empty",
         ));
     }
     if req.rail_id.trim().is_empty() {
         return Err(ApiError::bad_request(
-            CODE_POLICY_COMPOSE_INVALID,
+            ErrorCode::PolicyComposeInvalid,
             "rail_id must not be empty",
         ));
     }
     if req.label.trim().is_empty() {
         return Err(ApiError::bad_request(
-            CODE_POLICY_COMPOSE_INVALID,
+            ErrorCode::PolicyComposeInvalid,
             "label must not be empty",
         ));
     }
@@ -1236,19 +1887,19 @@ fn validate_policy_compose_request(req: &PolicyComposeRequest) -> Result<(), Api
     // Length limits to prevent abuse
     if req.category.len() > MAX_POLICY_STRING_LEN {
         return Err(ApiError::bad_request(
-            CODE_POLICY_COMPOSE_INVALID,
+            ErrorCode::PolicyComposeInvalid,
             "category exceeds maximum allowed length",
         ));
     }
     if req.rail_id.len() > MAX_POLICY_STRING_LEN {
         return Err(ApiError::bad_request(
-            CODE_POLICY_COMPOSE_INVALID,
+            ErrorCode::PolicyComposeInvalid,
             "rail_id exceeds maximum allowed length",
         ));
     }
     if req.label.len() > MAX_POLICY_STRING_LEN {
         return Err(ApiError::bad_request(
-            CODE_POLICY_COMPOSE_INVALID,
+            ErrorCode::PolicyComposeInvalid,
             "label exceeds maximum allowed length",
         ));
     }
@@ -1257,7 +1908,7 @@ fn validate_policy_compose_request(req: &PolicyComposeRequest) -> Result<(), Api
     let options_str = serde_json::to_string(&req.options).unwrap_or_default();
     if options_str.len() > 10 * 1024 {
         return Err(ApiError::bad_request(
-            CODE_POLICY_COMPOSE_INVALID,
+            ErrorCode::PolicyComposeInvalid,
             "options exceeds maximum allowed size",
         ));
     }
@@ -1266,24 +1917,103 @@ fn validate_policy_compose_request(req: &PolicyComposeRequest) -> Result<(), Api
     if let Some(policy_id) = req.policy_id {
         if policy_id == 0 {
             return Err(ApiError::bad_request(
-                CODE_POLICY_COMPOSE_INVALID,
+                ErrorCode::PolicyComposeInvalid,
                 "policy_id must be greater than 0",
             ));
         }
     }
 
+    // A zero threshold is satisfied by any balance, which is almost always a
+    // misconfiguration.
+    if req.threshold_raw == 0 && !req.allow_zero_threshold {
+        return Err(ApiError::bad_request(
+            ErrorCode::PolicyComposeInvalid,
+            "threshold_raw must be greater than 0 (set allow_zero_threshold to override)",
+        ));
+    }
+    if req.verifier_scope_id == 0 {
+        return Err(ApiError::bad_request(
+            ErrorCode::PolicyComposeInvalid,
+            "verifier_scope_id must be greater than 0",
+        ));
+    }
+    if let Some(mask) = req.verifier_scope_prefix {
+        if mask == 0 || mask.leading_ones() != mask.count_ones() {
+            return Err(ApiError::bad_request(
+                ErrorCode::PolicyComposeInvalid,
+                "verifier_scope_prefix must be a non-zero mask of contiguous high bits",
+            ));
+        }
+    }
+    if req
+        .allowed_anchor_heights
+        .as_ref()
+        .is_some_and(|heights| heights.is_empty())
+    {
+        return Err(ApiError::bad_request(
+            ErrorCode::PolicyComposeInvalid,
+            "allowed_anchor_heights must not be empty (omit it to accept any height)",
+        ));
+    }
+    if req.max_proof_age_secs == Some(0) {
+        return Err(ApiError::bad_request(
+            ErrorCode::PolicyComposeInvalid,
+            "max_proof_age_secs must be greater than 0",
+        ));
+    }
+    // Attestations in a wider currency could never satisfy the circuit's
+    // currency range check, so such a policy could not be proven against.
+    if req.required_currency_code != CURRENCY_WILDCARD
+        && u64::from(req.required_currency_code) >= 1 << DEFAULT_CURRENCY_CODE_BITS
+    {
+        return Err(ApiError::bad_request(
+            ErrorCode::PolicyComposeInvalid,
+            format!(
+                "required_currency_code {} does not fit in {} bits",
+                req.required_currency_code, DEFAULT_CURRENCY_CODE_BITS
+            ),
+        ));
+    }
+    if let Some(&(_, max)) = CURRENCY_THRESHOLD_LIMITS
+        .iter()
+        .find(|(code, _)| *code == req.required_currency_code)
+    {
+        if req.threshold_raw > max {
+            return Err(ApiError::bad_request(
+                ErrorCode::PolicyComposeInvalid,
+                format!(
+                    "threshold_raw {} exceeds the maximum of {} for currency {}",
+                    req.threshold_raw, max, req.required_currency_code
+                ),
+            ));
+        }
+    }
+
     Ok(())
 }
 
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, ToSchema)]
 struct ArtifactUrls {
     params: String,
     vk: String,
     pk: String,
+    /// break_points.json URL, present only for halo2-base rails such as Orchard.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    break_points: Option<String>,
 }
 
-#[derive(serde::Serialize)]
+#[derive(serde::Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct ParamsQuery {
+    /// Rail to describe. Omitted or `CUSTODIAL_ATTESTATION` selects the default custodial rail.
+    rail: Option<String>,
+}
+
+#[derive(serde::Serialize, ToSchema)]
 struct ParamsResponse {
+    rail_id: String,
+    #[schema(value_type = String, example = "V1")]
+    layout: PublicInputLayout,
     circuit_version: u32,
     manifest_version: u32,
     params_hash: String,
@@ -1299,17 +2029,114 @@ struct ParamsResponse {
     artifact_urls: Option<ArtifactUrls>,
 }
 
-#[derive(serde::Serialize)]
+/// Readiness report. `status` is `"degraded"` when the node was configured to
+/// prove but its proving key turned out to be unusable; verification still works.
+/// It is `"failed"` when the startup self-check found Poseidon parameters or
+/// public-input layouts that disagree with the circuits.
+#[derive(serde::Serialize, ToSchema)]
+struct ReadyResponse {
+    status: &'static str,
+    prover_enabled: bool,
+    prover_degraded: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    self_check_error: Option<String>,
+}
+
+#[derive(serde::Serialize, ToSchema)]
 struct EpochResponse {
     current_epoch: u64,
     max_drift_secs: u64,
+    /// Number of epochs before `current_epoch` that are also accepted.
+    previous_epochs: u64,
+    /// Length of one epoch in `current_epoch` units (1 for epoch counters).
+    epoch_secs: u64,
 }
 
-#[derive(serde::Serialize)]
+#[derive(serde::Deserialize, ToSchema)]
+struct ChallengeRequest {
+    policy_id: u64,
+}
+
+#[derive(serde::Serialize, ToSchema)]
+struct ChallengeResponse {
+    /// Pass as `challenge_session_id` to `/zkpf/verify-bundle`.
+    #[schema(value_type = String)]
+    session_id: Uuid,
+    /// Hex-encoded field element to prove with as the `challenge` public input.
+    challenge: String,
+    expires_at: u64,
+}
+
+#[derive(serde::Serialize, ToSchema)]
 struct PoliciesResponse {
     policies: Vec<PolicyExpectations>,
 }
 
+#[derive(serde::Serialize, ToSchema)]
+struct RailInfo {
+    rail_id: String,
+    circuit_version: u32,
+    #[schema(value_type = String, example = "V1")]
+    layout: PublicInputLayout,
+}
+
+#[derive(serde::Serialize, ToSchema)]
+struct RailsResponse {
+    rails: Vec<RailInfo>,
+}
+
+/// Which of a rail's artifact files are present on disk.
+#[derive(serde::Serialize, ToSchema)]
+struct ArtifactPresence {
+    params: bool,
+    vk: bool,
+    pk: bool,
+}
+
+#[derive(serde::Serialize, ToSchema)]
+struct RailStatus {
+    rail_id: String,
+    circuit_version: u32,
+    #[schema(value_type = String, example = "V1")]
+    layout: PublicInputLayout,
+    k: u32,
+    /// Short params+vk hash prefixes identifying the loaded key set.
+    artifact_key: String,
+    /// Whether this rail's artifacts can prove as well as verify.
+    prover_enabled: bool,
+    artifacts_on_disk: ArtifactPresence,
+}
+
+#[derive(serde::Serialize, ToSchema)]
+struct StatusResponse {
+    prover_enabled: bool,
+    prover_degraded: bool,
+    rails: Vec<RailStatus>,
+}
+
+/// Deployment capabilities, for client feature detection.
+#[derive(serde::Serialize, ToSchema)]
+struct InfoResponse {
+    /// Circuit version of the default custodial rail.
+    circuit_version: u32,
+    manifest_version: u32,
+    /// Whether this node proves (`/zkpf/prove-bundle` and friends are routed).
+    prover_enabled: bool,
+    /// Download URL for the custodial proving key, when it is on disk for
+    /// client-side proving.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pk_url: Option<String>,
+    rails: Vec<RailInfo>,
+    /// Distinct public-input layouts across `rails`.
+    #[schema(value_type = Vec<String>)]
+    layouts: Vec<PublicInputLayout>,
+    /// Largest proof, in bytes, the verify endpoints accept.
+    max_proof_size: usize,
+    attestation_enabled: bool,
+    /// Whether the MetaMask Snap is served from `/snap/*`.
+    snap_hosting: bool,
+}
+
 #[derive(serde::Deserialize)]
 struct ZashiSessionStartRequest {
     policy_id: u64,
@@ -1329,6 +2156,9 @@ struct ZashiSessionStartResponse {
 struct ZashiSessionSubmitRequest {
     session_id: Uuid,
     attestation: Attestation,
+    /// Reveal the attested balance as `proven_sum` in the proof.
+    #[serde(default)]
+    reveal_sum: bool,
 }
 
 #[derive(serde::Deserialize)]
@@ -1346,147 +2176,576 @@ struct ProviderBalanceAttestation {
     /// 32-byte message hash that the provider signed, encoded as a raw byte
     /// array in JSON (matching the existing circuit input conventions).
     message_hash: [u8; 32],
+    /// How `message_hash` was signed; defaults to a raw-hash signature.
+    #[serde(default)]
+    signature_format: SignatureFormat,
 }
 
 #[derive(serde::Deserialize)]
 struct ProviderProveBalanceRequest {
     policy_id: u64,
     attestation: ProviderBalanceAttestation,
+    /// Reveal the attested balance as `proven_sum` in the proof.
+    #[serde(default)]
+    reveal_sum: bool,
 }
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, ToSchema)]
 struct PolicyComposeRequest {
     category: String,
     rail_id: String,
     label: String,
     #[serde(default)]
+    #[schema(value_type = Object)]
     options: JsonValue,
     threshold_raw: u64,
+    /// Optional upper balance bound for range policies; see `PolicyExpectations`.
+    #[serde(default)]
+    threshold_max: Option<u64>,
+    /// Optional required public-input layout; see `PolicyExpectations`.
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    layout: Option<PublicInputLayout>,
     required_currency_code: u32,
     verifier_scope_id: u64,
+    /// Optional scope-prefix mask; see `PolicyExpectations`.
+    #[serde(default)]
+    verifier_scope_prefix: Option<u64>,
+    /// Optional Orchard snapshot height allowlist; see `PolicyExpectations`.
+    #[serde(default)]
+    allowed_anchor_heights: Option<Vec<u64>>,
+    /// Optional bound on a bundle's prover-signed age; see `PolicyExpectations`.
+    #[serde(default)]
+    max_proof_age_secs: Option<u64>,
     /// Optional policy ID. If provided and not already in use, this ID will be used.
     /// If omitted, a new ID will be auto-assigned.
     #[serde(default)]
     policy_id: Option<u64>,
+    /// Accept `threshold_raw = 0`, which any balance satisfies (e.g. demo policies).
+    #[serde(default)]
+    allow_zero_threshold: bool,
+    /// Create a new policy even if one with the same parameters already exists.
+    #[serde(default)]
+    force_new: bool,
 }
 
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, ToSchema)]
 struct PolicyComposeResponse {
+    #[schema(value_type = Object)]
     policy: JsonValue,
     summary: String,
     created: bool,
 }
 
-#[derive(serde::Deserialize)]
+/// Send each of the proof and public inputs either as a byte array or as a
+/// hex string (`0x` prefix optional), not both.
+#[derive(serde::Deserialize, ToSchema)]
 struct VerifyRequest {
     circuit_version: u32,
-    proof: Vec<u8>,
-    public_inputs: Vec<u8>,
+    #[serde(default)]
+    proof: Option<Vec<u8>>,
+    #[serde(default)]
+    proof_hex: Option<String>,
+    #[serde(default)]
+    public_inputs: Option<Vec<u8>>,
+    #[serde(default)]
+    public_inputs_hex: Option<String>,
     policy_id: u64,
 }
 
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, ToSchema)]
 struct VerifyResponse {
     valid: bool,
     circuit_version: u32,
     error: Option<String>,
-    error_code: Option<&'static str>,
+    #[schema(value_type = Option<String>)]
+    error_code: Option<ErrorCode>,
+    /// Policy the proof was accepted under; set on success.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    matched_policy_id: Option<u64>,
+    /// The same proof was accepted before and its nullifier is already
+    /// recorded, so this was a retry: `valid` is true but nothing new was
+    /// consumed. A different proof with that nullifier is `NULLIFIER_REPLAY`.
+    already_verified: bool,
+    /// Verified with `record_nullifier: false`: the nullifier store was not
+    /// consulted, so this says nothing about whether the proof was replayed.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    nullifier_unchecked: bool,
 }
 
 impl VerifyResponse {
-    fn success(circuit_version: u32) -> Self {
+    fn success(circuit_version: u32, policy_id: u64) -> Self {
         Self {
             valid: true,
             circuit_version,
             error: None,
             error_code: None,
+            matched_policy_id: Some(policy_id),
+            already_verified: false,
+            nullifier_unchecked: false,
+        }
+    }
+
+    fn already_verified(circuit_version: u32, policy_id: u64) -> Self {
+        Self {
+            already_verified: true,
+            ..Self::success(circuit_version, policy_id)
         }
     }
 
-    fn failure(circuit_version: u32, code: &'static str, message: impl Into<String>) -> Self {
+    fn unrecorded(circuit_version: u32, policy_id: u64) -> Self {
+        Self {
+            nullifier_unchecked: true,
+            ..Self::success(circuit_version, policy_id)
+        }
+    }
+
+    fn failure(circuit_version: u32, code: ErrorCode, message: impl Into<String>) -> Self {
         Self {
             valid: false,
             circuit_version,
             error: Some(message.into()),
             error_code: Some(code),
+            matched_policy_id: None,
+            already_verified: false,
+            nullifier_unchecked: false,
         }
     }
 }
 
-#[derive(serde::Deserialize)]
+/// Bundle verification request. The bundle is accepted if it satisfies
+/// `policy_id` or any of `policy_ids`; at least one must be given.
+#[derive(serde::Deserialize, ToSchema)]
 struct VerifyBundleRequest {
-    policy_id: u64,
+    #[serde(default)]
+    policy_id: Option<u64>,
+    /// Additional equivalent policies, e.g. the same requirement under
+    /// different verifier scopes.
+    #[serde(default)]
+    policy_ids: Vec<u64>,
+    /// Session from `/zkpf/challenge`. Required when the bundle carries a
+    /// challenge, which must match the one issued for the session.
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    challenge_session_id: Option<Uuid>,
+    /// Consume the nullifier on success (default). `false` verifies without
+    /// checking or recording it, for display on read replicas, and is only
+    /// accepted when `ZKPF_ALLOW_UNRECORDED_VERIFY` is set.
+    #[serde(default)]
+    record_nullifier: Option<bool>,
+    #[schema(value_type = Object)]
     bundle: ProofBundle,
 }
 
-#[derive(serde::Deserialize)]
-struct AttestRequest {
-    holder_id: String,
-    snapshot_id: String,
-    policy_id: u64,
+impl VerifyBundleRequest {
+    /// `policy_id` followed by `policy_ids`, without duplicates.
+    fn accepted_policy_ids(&self) -> Result<Vec<u64>, ApiError> {
+        let mut ids = Vec::with_capacity(1 + self.policy_ids.len());
+        for id in self.policy_id.iter().chain(&self.policy_ids) {
+            if !ids.contains(id) {
+                ids.push(*id);
+            }
+        }
+        if ids.is_empty() {
+            return Err(ApiError::bad_request(
+                ErrorCode::PolicyMismatch,
+                "policy_id or policy_ids is required",
+            ));
+        }
+        if ids.len() > MAX_ACCEPTED_POLICIES {
+            return Err(ApiError::bad_request(
+                ErrorCode::PolicyMismatch,
+                format!("at most {MAX_ACCEPTED_POLICIES} accepted policies per request"),
+            ));
+        }
+        Ok(ids)
+    }
+
+    /// Verify the bundle within the caller's policy namespace.
+    fn verify(
+        &self,
+        state: &AppState,
+        namespace: Option<&str>,
+    ) -> Result<VerifyResponse, ApiError> {
+        let record_nullifier = self.record_nullifier.unwrap_or(true);
+        // Without the flag, `false` would let any caller skip replay protection
+        // on the authoritative node.
+        if !record_nullifier && !env_flag(ALLOW_UNRECORDED_VERIFY_ENV) {
+            return Err(ApiError::from_code(
+                ErrorCode::Unauthorized,
+                "record_nullifier=false is not enabled on this node",
+            ));
+        }
+        self.verify_recording(state, namespace, record_nullifier)
+    }
+
+    /// `verify`, with whether the nullifier is checked and recorded decided by
+    /// the caller rather than the request.
+    fn verify_recording(
+        &self,
+        state: &AppState,
+        namespace: Option<&str>,
+        record_nullifier: bool,
+    ) -> Result<VerifyResponse, ApiError> {
+        let policy_ids = self.accepted_policy_ids()?;
+        verify_bundle(
+            state,
+            namespace,
+            &policy_ids,
+            &self.bundle,
+            self.challenge_session_id,
+            MAX_PROOF_SIZE_BYTES,
+            record_nullifier,
+        )
+    }
+}
+
+/// Bundle verification against the one policy with these criteria, for
+/// clients that do not know its id.
+#[derive(serde::Deserialize, ToSchema)]
+struct VerifyByCriteriaRequest {
+    verifier_scope_id: u64,
+    required_currency_code: u32,
+    threshold_raw: u64,
+    /// As on `/zkpf/verify-bundle`.
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    challenge_session_id: Option<Uuid>,
+    #[schema(value_type = Object)]
     bundle: ProofBundle,
 }
 
-#[derive(Clone)]
-struct AttestResponseBase {
-    holder_id: String,
-    policy_id: u64,
-    snapshot_id: String,
+#[derive(serde::Deserialize, ToSchema)]
+struct VerifyBatchRequest {
+    items: Vec<VerifyBundleRequest>,
+    /// Record the items' nullifiers all-or-nothing: unless every item verifies
+    /// and none of their nullifiers conflicts, no nullifier is recorded.
+    #[serde(default)]
+    atomic: bool,
 }
 
-#[derive(serde::Serialize)]
-struct AttestResponse {
+/// Per-bundle result of `/zkpf/verify-bundle/batch`.
+///
+/// `error_code` distinguishes policy, epoch, replay and proof failures the same
+/// way as `/zkpf/verify-bundle`. `nullifier_recorded` is true only when this
+/// item's nullifier was consumed by this request.
+#[derive(serde::Serialize, ToSchema)]
+struct BatchVerifyOutcome {
+    index: usize,
     valid: bool,
-    tx_hash: Option<String>,
-    attestation_id: Option<String>,
-    chain_id: Option<u64>,
-    holder_id: String,
-    policy_id: u64,
-    snapshot_id: String,
     error: Option<String>,
-    error_code: Option<&'static str>,
+    #[schema(value_type = Option<String>)]
+    error_code: Option<ErrorCode>,
+    nullifier_recorded: bool,
 }
 
-impl AttestResponse {
-    fn success(
-        base: AttestResponseBase,
-        tx_hash: String,
-        attestation_id: String,
-        chain_id: u64,
-    ) -> Self {
+impl BatchVerifyOutcome {
+    fn from_result(index: usize, result: Result<VerifyResponse, ApiError>) -> Self {
+        match result {
+            Ok(response) => Self {
+                index,
+                valid: response.valid,
+                error: response.error,
+                error_code: response.error_code,
+                // process_verification records the nullifier exactly when it
+                // succeeds on a proof it has not accepted before, unless asked
+                // not to.
+                nullifier_recorded: response.valid
+                    && !response.already_verified
+                    && !response.nullifier_unchecked,
+            },
+            Err(err) => Self::failure(index, err.code, err.message),
+        }
+    }
+
+    fn recorded(index: usize) -> Self {
         Self {
+            index,
             valid: true,
-            tx_hash: Some(tx_hash),
-            attestation_id: Some(attestation_id),
-            chain_id: Some(chain_id),
-            holder_id: base.holder_id,
-            policy_id: base.policy_id,
-            snapshot_id: base.snapshot_id,
             error: None,
             error_code: None,
+            nullifier_recorded: true,
         }
     }
 
-    fn failure(base: AttestResponseBase, code: &'static str, message: impl Into<String>) -> Self {
+    fn failure(index: usize, code: ErrorCode, message: impl Into<String>) -> Self {
         Self {
+            index,
             valid: false,
-            tx_hash: None,
-            attestation_id: None,
-            chain_id: None,
-            holder_id: base.holder_id,
-            policy_id: base.policy_id,
-            snapshot_id: base.snapshot_id,
             error: Some(message.into()),
             error_code: Some(code),
+            nullifier_recorded: false,
         }
     }
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
-pub struct PolicyExpectations {
-    pub threshold_raw: u64,
+/// Bundle fields checked by `/zkpf/verify-bundle/dry-run`; no proof is needed.
+#[derive(serde::Deserialize, ToSchema)]
+struct DryRunRequest {
+    policy_id: u64,
+    #[serde(default)]
+    rail_id: String,
+    circuit_version: u32,
+    #[schema(value_type = Object)]
+    public_inputs: VerifierPublicInputs,
+}
+
+/// Verification stages covered by the dry run, in evaluation order.
+#[derive(Clone, Copy, Debug, serde::Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+enum DryRunStage {
+    Rail,
+    CircuitVersion,
+    Policy,
+    Epoch,
+    SnapshotHeight,
+    Nullifier,
+    PublicInputs,
+}
+
+#[derive(serde::Serialize, ToSchema)]
+struct DryRunResponse {
+    valid: bool,
+    failed_stage: Option<DryRunStage>,
+    error: Option<String>,
+    #[schema(value_type = Option<String>)]
+    error_code: Option<ErrorCode>,
+}
+
+impl DryRunResponse {
+    fn passed() -> Self {
+        Self {
+            valid: true,
+            failed_stage: None,
+            error: None,
+            error_code: None,
+        }
+    }
+
+    fn failure(stage: DryRunStage, code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            valid: false,
+            failed_stage: Some(stage),
+            error: Some(message.into()),
+            error_code: Some(code),
+        }
+    }
+}
+
+/// Result of `/zkpf/epoch-proof/verify`.
+#[derive(serde::Serialize, ToSchema)]
+struct EpochProofVerifyResponse {
+    valid: bool,
+    epoch: u64,
+    rail_id: String,
+    /// Hex commitment to the set's nullifiers, checked against its entries.
+    nullifier_root: String,
+    proof_count: usize,
+    error: Option<String>,
+    #[schema(value_type = Option<String>)]
+    error_code: Option<ErrorCode>,
+    /// Entry whose proof failed, when the failure is specific to one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    failed_index: Option<usize>,
+}
+
+impl EpochProofVerifyResponse {
+    fn failure(
+        mut self,
+        failed_index: Option<usize>,
+        code: ErrorCode,
+        message: impl Into<String>,
+    ) -> Self {
+        self.valid = false;
+        self.error = Some(message.into());
+        self.error_code = Some(code);
+        self.failed_index = failed_index;
+        self
+    }
+}
+
+#[derive(serde::Deserialize, ToSchema)]
+struct AttestRequest {
+    holder_id: String,
+    snapshot_id: String,
+    policy_id: u64,
+    #[schema(value_type = Object)]
+    bundle: ProofBundle,
+}
+
+impl AttestRequest {
+    fn response_base(&self) -> AttestResponseBase {
+        AttestResponseBase {
+            holder_id: self.holder_id.clone(),
+            policy_id: self.policy_id,
+            snapshot_id: self.snapshot_id.clone(),
+        }
+    }
+}
+
+#[derive(serde::Deserialize, ToSchema)]
+struct AttestBatchRequest {
+    items: Vec<AttestRequest>,
+}
+
+#[derive(serde::Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct AttestationsQuery {
+    /// Holder to list, as sent in `holder_id` to `/zkpf/attest`.
+    holder_id: String,
+    /// Only attestations under this policy.
+    policy_id: Option<u64>,
+    /// Matching attestations to skip, counted newest first.
+    #[serde(default)]
+    offset: usize,
+    /// Page size; defaults to 50, at most 500.
+    limit: Option<usize>,
+}
+
+#[derive(serde::Serialize, ToSchema)]
+struct AttestationsResponse {
+    attestations: Vec<AttestationRecord>,
+    /// `offset` of the next page; absent on the last page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next_offset: Option<usize>,
+}
+
+#[derive(Clone)]
+struct AttestResponseBase {
+    holder_id: String,
+    policy_id: u64,
+    snapshot_id: String,
+}
+
+#[derive(serde::Serialize, ToSchema)]
+struct AttestResponse {
+    valid: bool,
+    tx_hash: Option<String>,
+    attestation_id: Option<String>,
+    chain_id: Option<u64>,
+    holder_id: String,
+    policy_id: u64,
+    snapshot_id: String,
+    error: Option<String>,
+    #[schema(value_type = Option<String>)]
+    error_code: Option<ErrorCode>,
+    /// Circuit version the rail accepts; present on `CIRCUIT_VERSION_MISMATCH`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expected_circuit_version: Option<u32>,
+}
+
+impl AttestResponse {
+    fn success(
+        base: AttestResponseBase,
+        tx_hash: String,
+        attestation_id: String,
+        chain_id: u64,
+    ) -> Self {
+        Self {
+            valid: true,
+            tx_hash: Some(tx_hash),
+            attestation_id: Some(attestation_id),
+            chain_id: Some(chain_id),
+            holder_id: base.holder_id,
+            policy_id: base.policy_id,
+            snapshot_id: base.snapshot_id,
+            error: None,
+            error_code: None,
+            expected_circuit_version: None,
+        }
+    }
+
+    /// Success response for an attestation written on-chain, notifying the
+    /// attestation webhook if one is configured.
+    fn recorded(base: AttestResponseBase, result: OnchainAttestationResult) -> Self {
+        if let Some(webhook) = ATTEST_WEBHOOK.as_ref() {
+            webhook.notify(AttestWebhookPayload {
+                holder_id: base.holder_id.clone(),
+                policy_id: base.policy_id,
+                snapshot_id: base.snapshot_id.clone(),
+                tx_hash: result.tx_hash.clone(),
+                attestation_id: result.attestation_id.clone(),
+                chain_id: result.chain_id,
+            });
+        }
+        Self::success(base, result.tx_hash, result.attestation_id, result.chain_id)
+    }
+
+    fn failure(base: AttestResponseBase, code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            valid: false,
+            tx_hash: None,
+            attestation_id: None,
+            chain_id: None,
+            holder_id: base.holder_id,
+            policy_id: base.policy_id,
+            snapshot_id: base.snapshot_id,
+            error: Some(message.into()),
+            error_code: Some(code),
+            expected_circuit_version: None,
+        }
+    }
+
+    fn from_error(base: AttestResponseBase, err: ApiError) -> Self {
+        Self {
+            expected_circuit_version: err.expected_circuit_version,
+            ..Self::failure(base, err.code, err.message)
+        }
+    }
+}
+
+/// Result of `/zkpf/attest-batch`: the shared transaction and one
+/// `AttestResponse` per item.
+#[derive(serde::Serialize, ToSchema)]
+struct AttestBatchResponse {
+    /// Transaction that recorded the batch; absent when no item was attested.
+    tx_hash: Option<String>,
+    chain_id: Option<u64>,
+    items: Vec<AttestResponse>,
+}
+
+impl AttestBatchResponse {
+    fn new(items: Vec<AttestResponse>) -> Self {
+        let recorded = items.iter().find(|item| item.valid);
+        Self {
+            tx_hash: recorded.and_then(|item| item.tx_hash.clone()),
+            chain_id: recorded.and_then(|item| item.chain_id),
+            items,
+        }
+    }
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, ToSchema)]
+pub struct PolicyExpectations {
+    pub threshold_raw: u64,
+    /// Upper balance bound for range policies. When set, bundles must come from
+    /// a range rail (V5_RANGE layout) and carry the same `threshold_max`.
+    #[serde(default)]
+    pub threshold_max: Option<u64>,
+    /// Public-input layout bundles must use. `V1_WITH_SUM` asks holders to
+    /// disclose their exact balance as `proven_sum`; unset accepts any layout.
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub layout: Option<PublicInputLayout>,
     pub required_currency_code: u32,
     pub verifier_scope_id: u64,
+    /// Mask of the high `verifier_scope_id` bits a bundle's scope must share
+    /// with this one, so one policy covers a family of sub-scopes (e.g.
+    /// `0xFFFF_FFFF_0000_0000` accepts any scope with the same upper 32 bits).
+    /// Unset requires the exact scope. Nullifiers still key on the bundle's own
+    /// scope, so replay protection stays per sub-scope.
+    #[serde(default)]
+    pub verifier_scope_prefix: Option<u64>,
+    /// Orchard snapshot heights (e.g. daily checkpoints) whose anchors this
+    /// policy trusts. V2_ORCHARD bundles must carry one of them as
+    /// `snapshot_block_height`; unset accepts any height. Other layouts ignore it.
+    #[serde(default)]
+    pub allowed_anchor_heights: Option<Vec<u64>>,
+    /// Oldest a bundle may be, measured from its prover-signed `issued_at` to
+    /// server time. Bundles without a stamp that verifies under
+    /// `ZKPF_PROVER_SIGNING_KEY` are rejected. Unset accepts any age.
+    #[serde(default)]
+    pub max_proof_age_secs: Option<u64>,
     pub policy_id: u64,
     #[serde(default)]
     pub category: Option<String>,
@@ -1495,10 +2754,35 @@ pub struct PolicyExpectations {
     #[serde(default)]
     pub label: Option<String>,
     #[serde(default)]
+    #[schema(value_type = Option<Object>)]
     pub options: Option<JsonValue>,
+    /// Tenant that owns the policy, set from the composing API key. Policies
+    /// without one live in the shared namespace.
+    #[serde(default)]
+    pub namespace: Option<String>,
 }
 
 impl PolicyExpectations {
+    /// Whether `other` asks bundles for exactly what this policy does, so
+    /// composing it again can reuse this one. Every field that changes what a
+    /// bundle must satisfy takes part; a field added to the struct belongs here
+    /// too.
+    fn same_terms(&self, other: &PolicyExpectations) -> bool {
+        let category =
+            |policy: &PolicyExpectations| policy.category.as_deref().map(str::to_ascii_uppercase);
+        category(self) == category(other)
+            && self.rail_id == other.rail_id
+            && self.threshold_raw == other.threshold_raw
+            && self.threshold_max == other.threshold_max
+            && self.layout == other.layout
+            && self.required_currency_code == other.required_currency_code
+            && self.verifier_scope_id == other.verifier_scope_id
+            && self.verifier_scope_prefix == other.verifier_scope_prefix
+            && self.allowed_anchor_heights == other.allowed_anchor_heights
+            && self.max_proof_age_secs == other.max_proof_age_secs
+            && self.namespace == other.namespace
+    }
+
     fn validate_against(&self, inputs: &VerifierPublicInputs) -> Result<(), String> {
         if inputs.threshold_raw != self.threshold_raw {
             return Err(format!(
@@ -1506,17 +2790,34 @@ impl PolicyExpectations {
                 self.threshold_raw, inputs.threshold_raw
             ));
         }
+        if inputs.threshold_max != self.threshold_max {
+            return Err(format!(
+                "threshold_max mismatch: expected {:?}, got {:?}",
+                self.threshold_max, inputs.threshold_max
+            ));
+        }
         if inputs.required_currency_code != self.required_currency_code {
             return Err(format!(
                 "required_currency_code mismatch: expected {}, got {}",
                 self.required_currency_code, inputs.required_currency_code
             ));
         }
-        if inputs.verifier_scope_id != self.verifier_scope_id {
-            return Err(format!(
-                "verifier_scope_id mismatch: expected {}, got {}",
-                self.verifier_scope_id, inputs.verifier_scope_id
-            ));
+        match self.verifier_scope_prefix {
+            Some(mask) if inputs.verifier_scope_id & mask != self.verifier_scope_id & mask => {
+                return Err(format!(
+                    "verifier_scope_id mismatch: expected prefix {:#x} (mask {:#x}), got {}",
+                    self.verifier_scope_id & mask,
+                    mask,
+                    inputs.verifier_scope_id
+                ));
+            }
+            None if inputs.verifier_scope_id != self.verifier_scope_id => {
+                return Err(format!(
+                    "verifier_scope_id mismatch: expected {}, got {}",
+                    self.verifier_scope_id, inputs.verifier_scope_id
+                ));
+            }
+            _ => {}
         }
         if inputs.policy_id != self.policy_id {
             return Err(format!(
@@ -1524,19 +2825,104 @@ impl PolicyExpectations {
                 self.policy_id, inputs.policy_id
             ));
         }
+        if self.layout == Some(PublicInputLayout::V1WithSum) && inputs.proven_sum.is_none() {
+            return Err("policy requires proven_sum to be revealed".to_string());
+        }
+        Ok(())
+    }
+
+    /// Check that a bundle verified under `layout` is acceptable for this policy.
+    fn validate_layout(&self, layout: PublicInputLayout) -> Result<(), String> {
+        match self.layout {
+            Some(required) if required != layout => Err(format!(
+                "layout mismatch: policy requires {:?}, rail uses {:?}",
+                required, layout
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Check an Orchard bundle's snapshot height against `allowed_anchor_heights`.
+    fn validate_anchor_height(
+        &self,
+        layout: PublicInputLayout,
+        inputs: &VerifierPublicInputs,
+    ) -> Result<(), String> {
+        let Some(allowed) = &self.allowed_anchor_heights else {
+            return Ok(());
+        };
+        if layout != PublicInputLayout::V2Orchard {
+            return Ok(());
+        }
+        match inputs.snapshot_block_height {
+            Some(height) if allowed.contains(&height) => Ok(()),
+            Some(height) => Err(format!(
+                "snapshot_block_height {} is not an allowed anchor height",
+                height
+            )),
+            None => Err("policy requires an allowed snapshot_block_height".to_string()),
+        }
+    }
+
+    /// Check a bundle's authenticated issuance time against `max_proof_age_secs`.
+    fn validate_proof_age(&self, issued_at: Option<u64>, now: u64) -> Result<(), String> {
+        let Some(max_age) = self.max_proof_age_secs else {
+            return Ok(());
+        };
+        let Some(issued_at) = issued_at else {
+            return Err("policy requires a prover-signed issued_at".to_string());
+        };
+        // A stamp slightly ahead of `now` (another prover's clock) counts as fresh.
+        let age = now.saturating_sub(issued_at);
+        if age > max_age {
+            return Err(format!(
+                "proof is {}s old, policy accepts at most {}s",
+                age, max_age
+            ));
+        }
         Ok(())
     }
 }
 
+/// Take a lock guard even if a panic poisoned the lock.
+///
+/// The guarded maps stay structurally valid when a request panics mid-update,
+/// so continuing with them beats failing every later request that touches the
+/// lock. `what` names the lock in the warning.
+pub(crate) fn recover_lock<G>(result: LockResult<G>, what: &str) -> G {
+    result.unwrap_or_else(|poisoned| {
+        eprintln!("{what} lock poisoned by a panicked request; recovering its data");
+        poisoned.into_inner()
+    })
+}
+
 #[derive(Clone)]
 pub struct PolicyStore {
     policies: Arc<RwLock<HashMap<u64, PolicyExpectations>>>,
+    /// Ids of policies added through `insert` (policy composition), which a
+    /// refresh from the policy source must not remove or overwrite.
+    composed: Arc<Mutex<HashSet<u64>>>,
 }
 
 impl PolicyStore {
+    /// Load from the policy file. With `ZKPF_POLICY_SOURCE=http` the file only
+    /// holds policies composed on this node: they are loaded as composed and
+    /// the sourced ones are filled in by the refresh task.
     fn from_env() -> Self {
         let path = policy_config_path();
-        Self::from_path(path)
+        if policy_source::http_policy_url().is_none() {
+            return Self::from_path(path);
+        }
+        if !Path::new(&path).exists() {
+            return Self::from_policies(Vec::new());
+        }
+        let store = Self::from_path(path);
+        let ids = recover_lock(store.policies.read(), "policy store")
+            .keys()
+            .copied()
+            .collect();
+        *recover_lock(store.composed.lock(), "composed policy ids") = ids;
+        store
     }
 
     pub fn from_path(path: impl AsRef<Path>) -> Self {
@@ -1569,104 +2955,395 @@ impl PolicyStore {
         }
         Self {
             policies: Arc::new(RwLock::new(map)),
+            composed: Arc::new(Mutex::new(HashSet::new())),
         }
     }
 
     pub fn get(&self, policy_id: u64) -> Option<PolicyExpectations> {
-        self.policies
-            .read()
-            .expect("policy store poisoned")
+        recover_lock(self.policies.read(), "policy store")
             .get(&policy_id)
             .cloned()
     }
 
     pub fn all(&self) -> Vec<PolicyExpectations> {
-        self.policies
-            .read()
-            .expect("policy store poisoned")
+        recover_lock(self.policies.read(), "policy store")
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    /// Like `get`, but only if the policy belongs to `namespace` (`None` is the
+    /// shared namespace). Other tenants' policies look the same as missing ones.
+    pub fn get_in(&self, namespace: Option<&str>, policy_id: u64) -> Option<PolicyExpectations> {
+        self.get(policy_id)
+            .filter(|policy| policy.namespace.as_deref() == namespace)
+    }
+
+    pub fn all_in(&self, namespace: Option<&str>) -> Vec<PolicyExpectations> {
+        recover_lock(self.policies.read(), "policy store")
             .values()
+            .filter(|policy| policy.namespace.as_deref() == namespace)
             .cloned()
             .collect()
     }
 
+    /// Ids of the policies in `namespace` with exactly this scope, currency and
+    /// threshold, in ascending order. `verifier_scope_prefix` is not applied.
+    pub fn find_in(
+        &self,
+        namespace: Option<&str>,
+        verifier_scope_id: u64,
+        required_currency_code: u32,
+        threshold_raw: u64,
+    ) -> Vec<u64> {
+        let mut ids: Vec<u64> = recover_lock(self.policies.read(), "policy store")
+            .values()
+            .filter(|policy| {
+                policy.namespace.as_deref() == namespace
+                    && policy.verifier_scope_id == verifier_scope_id
+                    && policy.required_currency_code == required_currency_code
+                    && policy.threshold_raw == threshold_raw
+            })
+            .map(|policy| policy.policy_id)
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+
     pub fn insert(&self, policy: PolicyExpectations) {
-        let mut guard = self.policies.write().expect("policy store poisoned");
+        let mut guard = recover_lock(self.policies.write(), "policy store");
         let id = policy.policy_id;
         // Upsert - allows re-registering the same policy without panic
         guard.insert(id, policy);
+        recover_lock(self.composed.lock(), "composed policy ids").insert(id);
+    }
+
+    /// Add a composed policy under `requested_id`, or else under the id after
+    /// the highest sourced or composed one, and return it with that id.
+    ///
+    /// An existing policy is never replaced: a requested id that is already in
+    /// use is returned as the error.
+    pub fn insert_composed(
+        &self,
+        mut policy: PolicyExpectations,
+        requested_id: Option<u64>,
+    ) -> Result<PolicyExpectations, u64> {
+        let mut guard = recover_lock(self.policies.write(), "policy store");
+        policy.policy_id = match requested_id {
+            Some(id) if guard.contains_key(&id) => return Err(id),
+            Some(id) => id,
+            None => guard.keys().max().map_or(1, |id| id.saturating_add(1)),
+        };
+        guard.insert(policy.policy_id, policy.clone());
+        recover_lock(self.composed.lock(), "composed policy ids").insert(policy.policy_id);
+        Ok(policy)
+    }
+
+    /// Replace the policies that came from the policy source with `policies`.
+    ///
+    /// Composed policies are kept, and win over a sourced policy with the same
+    /// id. A set with duplicate ids is rejected and leaves the store unchanged.
+    pub fn replace_sourced(&self, policies: Vec<PolicyExpectations>) -> Result<(), String> {
+        let mut sourced = HashMap::with_capacity(policies.len());
+        for policy in policies {
+            let id = policy.policy_id;
+            if sourced.insert(id, policy).is_some() {
+                return Err(format!("duplicate policy_id {id} in policy source"));
+            }
+        }
+        let mut guard = recover_lock(self.policies.write(), "policy store");
+        let composed = recover_lock(self.composed.lock(), "composed policy ids");
+        guard.retain(|id, _| composed.contains(id));
+        for (id, policy) in sourced {
+            guard.entry(id).or_insert(policy);
+        }
+        Ok(())
     }
 }
 
+/// Fixed-window limit on `/zkpf/policies/compose` calls, counted per namespace.
 #[derive(Clone)]
-pub struct ProviderSessionStore {
-    ttl: Duration,
-    retention: Duration,
-    sessions: Arc<RwLock<HashMap<Uuid, ProviderSessionRecord>>>,
+pub struct ComposeRateLimiter {
+    limit: u64,
+    window: Duration,
+    windows: Arc<Mutex<HashMap<String, (Instant, u64)>>>,
 }
 
-impl Default for ProviderSessionStore {
-    fn default() -> Self {
+impl ComposeRateLimiter {
+    /// `limit` calls per `window`; a limit of 0 disables rate limiting.
+    pub fn new(limit: u64, window: Duration) -> Self {
         Self {
-            ttl: Duration::from_secs(PROVIDER_SESSION_TTL_SECS),
-            retention: Duration::from_secs(PROVIDER_SESSION_RETENTION_SECS),
-            sessions: Arc::new(RwLock::new(HashMap::new())),
+            limit,
+            window,
+            windows: Arc::new(Mutex::new(HashMap::new())),
         }
     }
-}
 
-impl ProviderSessionStore {
-    pub(crate) fn start_session(&self, policy: PolicyExpectations) -> ProviderSessionStart {
-        let mut guard = self.sessions.write().expect("provider sessions poisoned");
-        self.purge_locked(&mut guard);
-        let now = SystemTime::now();
-        let expires_at = now + self.ttl;
-        let session_id = Uuid::new_v4();
-        guard.insert(
-            session_id,
-            ProviderSessionRecord {
-                policy: policy.clone(),
-                status: ProviderSessionStatus::Pending,
-                bundle: None,
-                last_error: None,
-                created_at: now,
-                updated_at: now,
-                expires_at,
-            },
-        );
-        ProviderSessionStart {
-            session_id,
-            policy: SessionPolicyView::from(&policy),
-            expires_at,
-        }
+    pub fn from_env() -> Self {
+        let limit = parse_env_u64(COMPOSE_RATE_LIMIT_ENV).unwrap_or(DEFAULT_COMPOSE_RATE_LIMIT);
+        Self::new(limit, Duration::from_secs(COMPOSE_RATE_WINDOW_SECS))
     }
 
-    pub(crate) fn begin_submission(
-        &self,
-        session_id: &Uuid,
-    ) -> Result<PolicyExpectations, SessionError> {
-        let mut guard = self.sessions.write().expect("provider sessions poisoned");
-        self.purge_locked(&mut guard);
-        let record = guard.get_mut(session_id).ok_or(SessionError::NotFound)?;
-        record.expire_if_needed();
-        match record.status {
-            ProviderSessionStatus::Pending | ProviderSessionStatus::Invalid => {
-                record.status = ProviderSessionStatus::Proving;
-                record.last_error = None;
-                record.updated_at = SystemTime::now();
-                Ok(record.policy.clone())
-            }
-            ProviderSessionStatus::Proving => Err(SessionError::State("session already proving")),
-            ProviderSessionStatus::Ready => Err(SessionError::State("session already completed")),
-            ProviderSessionStatus::Expired => Err(SessionError::Expired),
+    /// Count one call for `namespace`; false once its current window is used up.
+    fn try_acquire(&self, namespace: &str) -> bool {
+        if self.limit == 0 {
+            return true;
         }
+        let now = Instant::now();
+        let mut windows = recover_lock(self.windows.lock(), "compose rate limiter");
+        let (started, count) = windows.entry(namespace.to_string()).or_insert((now, 0));
+        if now.duration_since(*started) >= self.window {
+            *started = now;
+            *count = 0;
+        }
+        if *count >= self.limit {
+            return false;
+        }
+        *count += 1;
+        true
     }
+}
 
-    pub(crate) fn finish_success(
-        &self,
-        session_id: &Uuid,
-        bundle: ProofBundle,
-    ) -> Result<ProviderSessionSnapshot, SessionError> {
-        let mut guard = self.sessions.write().expect("provider sessions poisoned");
+/// Challenges issued by `/zkpf/challenge`, keyed by session.
+///
+/// Each challenge can be redeemed once; expired ones are dropped on access.
+/// Unredeemed challenges are capped in total and per client address, and
+/// issuing past either cap is refused until older challenges are redeemed or
+/// expire.
+#[derive(Clone)]
+pub struct ChallengeStore {
+    ttl: Duration,
+    max_outstanding: usize,
+    max_per_client: usize,
+    challenges: Arc<RwLock<HashMap<Uuid, ChallengeRecord>>>,
+}
+
+#[derive(Clone)]
+struct ChallengeRecord {
+    policy_id: u64,
+    challenge: [u8; 32],
+    expires_at: SystemTime,
+    /// Address the challenge was issued to, for the per-client cap.
+    client: IpAddr,
+}
+
+impl Default for ChallengeStore {
+    fn default() -> Self {
+        Self::new(
+            DEFAULT_CHALLENGE_MAX_OUTSTANDING,
+            DEFAULT_CHALLENGE_MAX_PER_CLIENT,
+        )
+    }
+}
+
+impl ChallengeStore {
+    /// At most `max_outstanding` unredeemed challenges, `max_per_client` of
+    /// them for any one client address; 0 disables either cap.
+    pub fn new(max_outstanding: usize, max_per_client: usize) -> Self {
+        Self {
+            ttl: Duration::from_secs(CHALLENGE_TTL_SECS),
+            max_outstanding,
+            max_per_client,
+            challenges: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        let cap = |var: &str, default: usize| {
+            env::var(var)
+                .ok()
+                .and_then(|value| value.trim().parse().ok())
+                .unwrap_or(default)
+        };
+        Self::new(
+            cap(
+                CHALLENGE_MAX_OUTSTANDING_ENV,
+                DEFAULT_CHALLENGE_MAX_OUTSTANDING,
+            ),
+            cap(
+                CHALLENGE_MAX_PER_CLIENT_ENV,
+                DEFAULT_CHALLENGE_MAX_PER_CLIENT,
+            ),
+        )
+    }
+
+    /// Issue a challenge to `client`, or say which cap refused it.
+    pub(crate) fn issue(
+        &self,
+        policy_id: u64,
+        client: IpAddr,
+    ) -> Result<(Uuid, ChallengeRecord), &'static str> {
+        let mut guard = recover_lock(self.challenges.write(), "challenge store");
+        Self::purge_locked(&mut guard);
+        if self.max_outstanding > 0 && guard.len() >= self.max_outstanding {
+            return Err("too many outstanding challenges");
+        }
+        let issued_to_client = guard
+            .values()
+            .filter(|record| record.client == client)
+            .count();
+        if self.max_per_client > 0 && issued_to_client >= self.max_per_client {
+            return Err("too many outstanding challenges for this client");
+        }
+        let record = ChallengeRecord {
+            policy_id,
+            challenge: random_challenge(),
+            expires_at: SystemTime::now() + self.ttl,
+            client,
+        };
+        let session_id = Uuid::new_v4();
+        guard.insert(session_id, record.clone());
+        Ok((session_id, record))
+    }
+
+    /// Remove the session's challenge, failing if it is unknown or expired.
+    pub(crate) fn redeem(&self, session_id: &Uuid) -> Result<ChallengeRecord, SessionError> {
+        let mut guard = recover_lock(self.challenges.write(), "challenge store");
+        let record = guard.remove(session_id).ok_or(SessionError::NotFound)?;
+        if SystemTime::now() >= record.expires_at {
+            return Err(SessionError::Expired);
+        }
+        Ok(record)
+    }
+
+    fn purge_locked(challenges: &mut HashMap<Uuid, ChallengeRecord>) {
+        let now = SystemTime::now();
+        challenges.retain(|_, record| record.expires_at > now);
+    }
+}
+
+/// Client address of a request, resolved by `client_ip::client_ip` with the
+/// state's trusted proxies.
+struct ClientIp(IpAddr);
+
+#[async_trait]
+impl FromRequestParts<AppState> for ClientIp {
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        Ok(Self(client_ip::client_ip(parts, state.trusted_proxies())))
+    }
+}
+
+/// A random challenge, encoded as a canonical field element.
+fn random_challenge() -> [u8; 32] {
+    let mut seed = [0u8; 32];
+    OsRng.fill_bytes(&mut seed);
+    fr_to_bytes(&reduce_be_bytes_to_fr(&seed))
+}
+
+/// Check a proof's challenge against the one issued for `session_id`.
+///
+/// Proofs without a challenge need no session; routes that take no session
+/// pass `None`, so challenge-bound proofs are refused there. The session is
+/// consumed even when the check fails, so each challenge backs a single
+/// verification attempt.
+fn redeem_challenge(
+    state: &AppState,
+    session_id: Option<Uuid>,
+    policy_ids: &[u64],
+    challenge: Option<[u8; 32]>,
+) -> Result<(), String> {
+    let session_id = match (session_id, challenge) {
+        (None, None) => return Ok(()),
+        (None, Some(_)) => {
+            return Err("challenge_session_id is required for a challenge-bound bundle".into())
+        }
+        (Some(session_id), _) => session_id,
+    };
+    let record = state
+        .challenge_store()
+        .redeem(&session_id)
+        .map_err(|err| match err {
+            SessionError::Expired => "challenge has expired".to_string(),
+            _ => "unknown challenge session".to_string(),
+        })?;
+    if !policy_ids.contains(&record.policy_id) {
+        return Err(format!(
+            "challenge was issued for policy {}",
+            record.policy_id
+        ));
+    }
+    match challenge {
+        Some(challenge) if constant_time_eq(&challenge, &record.challenge) => Ok(()),
+        Some(_) => Err("challenge does not match the one issued for this session".into()),
+        None => Err("bundle does not carry the issued challenge".into()),
+    }
+}
+
+#[derive(Clone)]
+pub struct ProviderSessionStore {
+    ttl: Duration,
+    retention: Duration,
+    sessions: Arc<RwLock<HashMap<Uuid, ProviderSessionRecord>>>,
+}
+
+impl Default for ProviderSessionStore {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::from_secs(PROVIDER_SESSION_TTL_SECS),
+            retention: Duration::from_secs(PROVIDER_SESSION_RETENTION_SECS),
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+}
+
+impl ProviderSessionStore {
+    pub(crate) fn start_session(&self, policy: PolicyExpectations) -> ProviderSessionStart {
+        let mut guard = recover_lock(self.sessions.write(), "provider sessions");
+        self.purge_locked(&mut guard);
+        let now = SystemTime::now();
+        let expires_at = now + self.ttl;
+        let session_id = Uuid::new_v4();
+        guard.insert(
+            session_id,
+            ProviderSessionRecord {
+                policy: policy.clone(),
+                status: ProviderSessionStatus::Pending,
+                bundle: None,
+                last_error: None,
+                created_at: now,
+                updated_at: now,
+                expires_at,
+            },
+        );
+        ProviderSessionStart {
+            session_id,
+            policy: SessionPolicyView::from(&policy),
+            expires_at,
+        }
+    }
+
+    pub(crate) fn begin_submission(
+        &self,
+        session_id: &Uuid,
+    ) -> Result<PolicyExpectations, SessionError> {
+        let mut guard = recover_lock(self.sessions.write(), "provider sessions");
+        self.purge_locked(&mut guard);
+        let record = guard.get_mut(session_id).ok_or(SessionError::NotFound)?;
+        record.expire_if_needed();
+        match record.status {
+            ProviderSessionStatus::Pending | ProviderSessionStatus::Invalid => {
+                record.status = ProviderSessionStatus::Proving;
+                record.last_error = None;
+                record.updated_at = SystemTime::now();
+                Ok(record.policy.clone())
+            }
+            ProviderSessionStatus::Proving => Err(SessionError::State("session already proving")),
+            ProviderSessionStatus::Ready => Err(SessionError::State("session already completed")),
+            ProviderSessionStatus::Expired => Err(SessionError::Expired),
+        }
+    }
+
+    pub(crate) fn finish_success(
+        &self,
+        session_id: &Uuid,
+        bundle: ProofBundle,
+    ) -> Result<ProviderSessionSnapshot, SessionError> {
+        let mut guard = recover_lock(self.sessions.write(), "provider sessions");
         let record = guard.get_mut(session_id).ok_or(SessionError::NotFound)?;
         record.expire_if_needed();
         if record.status != ProviderSessionStatus::Proving {
@@ -1680,24 +3357,23 @@ impl ProviderSessionStore {
     }
 
     pub fn finish_failure(&self, session_id: &Uuid, message: String) {
-        if let Ok(mut guard) = self.sessions.write() {
-            if let Some(record) = guard.get_mut(session_id) {
-                record.expire_if_needed();
-                if matches!(
-                    record.status,
-                    ProviderSessionStatus::Ready | ProviderSessionStatus::Expired
-                ) {
-                    return;
-                }
-                record.status = ProviderSessionStatus::Invalid;
-                record.last_error = Some(message);
-                record.updated_at = SystemTime::now();
+        let mut guard = recover_lock(self.sessions.write(), "provider sessions");
+        if let Some(record) = guard.get_mut(session_id) {
+            record.expire_if_needed();
+            if matches!(
+                record.status,
+                ProviderSessionStatus::Ready | ProviderSessionStatus::Expired
+            ) {
+                return;
             }
+            record.status = ProviderSessionStatus::Invalid;
+            record.last_error = Some(message);
+            record.updated_at = SystemTime::now();
         }
     }
 
     pub(crate) fn snapshot(&self, session_id: &Uuid) -> Option<ProviderSessionSnapshot> {
-        let mut guard = self.sessions.write().expect("provider sessions poisoned");
+        let mut guard = recover_lock(self.sessions.write(), "provider sessions");
         self.purge_locked(&mut guard);
         guard.get_mut(session_id).map(|record| {
             record.expire_if_needed();
@@ -1705,6 +3381,37 @@ impl ProviderSessionStore {
         })
     }
 
+    /// Drop records kept past their retention window now, instead of on the
+    /// next access. Returns how many were removed.
+    pub fn purge(&self) -> usize {
+        let mut guard = recover_lock(self.sessions.write(), "provider sessions");
+        let before = guard.len();
+        self.purge_locked(&mut guard);
+        before - guard.len()
+    }
+
+    /// Records past their expiry that have not been purged yet, oldest expiry
+    /// first.
+    pub(crate) fn expired(&self) -> Vec<ExpiredSession> {
+        let guard = recover_lock(self.sessions.read(), "provider sessions");
+        let now = SystemTime::now();
+        let mut expired: Vec<ExpiredSession> = guard
+            .iter()
+            .filter(|(_, record)| record.expires_at <= now)
+            .map(|(id, record)| ExpiredSession {
+                session_id: *id,
+                status: record.status.clone(),
+                policy_id: record.policy.policy_id,
+                created_at: system_time_secs(record.created_at),
+                updated_at: system_time_secs(record.updated_at),
+                expires_at: system_time_secs(record.expires_at),
+                purge_after: system_time_secs(record.expires_at + self.retention),
+            })
+            .collect();
+        expired.sort_by_key(|session| session.expires_at);
+        expired
+    }
+
     fn purge_locked(&self, sessions: &mut HashMap<Uuid, ProviderSessionRecord>) {
         let now = SystemTime::now();
         sessions.retain(|_, record| match now.duration_since(record.expires_at) {
@@ -1737,7 +3444,7 @@ impl ProviderSessionRecord {
     }
 }
 
-#[derive(Clone, Debug, serde::Serialize, PartialEq, Eq)]
+#[derive(Clone, Debug, serde::Serialize, PartialEq, Eq, ToSchema)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 enum ProviderSessionStatus {
     Pending,
@@ -1752,6 +3459,8 @@ struct SessionPolicyView {
     policy_id: u64,
     verifier_scope_id: u64,
     threshold_raw: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    threshold_max: Option<u64>,
     required_currency_code: u32,
     rail_id: String,
     label: Option<String>,
@@ -1763,6 +3472,7 @@ impl From<&PolicyExpectations> for SessionPolicyView {
             policy_id: policy.policy_id,
             verifier_scope_id: policy.verifier_scope_id,
             threshold_raw: policy.threshold_raw,
+            threshold_max: policy.threshold_max,
             required_currency_code: policy.required_currency_code,
             rail_id: policy
                 .rail_id
@@ -1800,6 +3510,28 @@ impl ProviderSessionSnapshot {
     }
 }
 
+/// A provider session past its expiry that is still retained.
+#[derive(Clone, Debug, serde::Serialize, ToSchema)]
+pub struct ExpiredSession {
+    #[schema(value_type = String)]
+    session_id: Uuid,
+    /// Status as last recorded. A session that was never touched after its
+    /// expiry keeps the status it expired in, e.g. `PROVING` for a stuck proof.
+    status: ProviderSessionStatus,
+    policy_id: u64,
+    created_at: u64,
+    updated_at: u64,
+    expires_at: u64,
+    /// Unix time after which the next purge removes the session.
+    purge_after: u64,
+}
+
+#[derive(serde::Serialize, ToSchema)]
+pub struct SessionPurgeResponse {
+    /// Number of sessions removed.
+    removed: usize,
+}
+
 struct ProviderSessionStart {
     session_id: Uuid,
     policy: SessionPolicyView,
@@ -1824,265 +3556,1171 @@ enum SessionError {
     State(&'static str),
 }
 
+#[utoipa::path(
+    post,
+    path = "/zkpf/verify",
+    tag = "verification",
+    request_body = VerifyRequest,
+    responses(
+        (status = 200, description = "Verification result; failures carry `error_code`", body = VerifyResponse),
+        (status = 400, description = "PROOF_INVALID, PUBLIC_INPUTS_INVALID, CIRCUIT_VERSION_MISMATCH or RAIL_UNKNOWN", body = ErrorResponse),
+        (status = 404, description = "POLICY_NOT_FOUND", body = ErrorResponse),
+        (status = 500, description = "NULLIFIER_STORE_ERROR or INTERNAL_SERVER_ERROR", body = ErrorResponse),
+    )
+)]
 async fn verify_handler(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(req): Json<VerifyRequest>,
 ) -> Result<Json<VerifyResponse>, ApiError> {
+    let namespace = caller_namespace(&headers)?;
     // Input size validation
-    if req.proof.len() > MAX_PROOF_SIZE_BYTES {
-        return Err(ApiError::bad_request(
-            CODE_PROOF_INVALID,
-            "proof exceeds maximum allowed size",
-        ));
-    }
-    if req.public_inputs.len() > MAX_PUBLIC_INPUTS_SIZE_BYTES {
-        return Err(ApiError::bad_request(
-            CODE_PUBLIC_INPUTS,
-            "public_inputs exceeds maximum allowed size",
-        ));
-    }
+    let proof = bytes_or_hex(
+        "proof",
+        req.proof,
+        req.proof_hex.as_deref(),
+        MAX_PROOF_SIZE_BYTES,
+        ErrorCode::ProofInvalid,
+    )?;
+    let public_inputs = bytes_or_hex(
+        "public_inputs",
+        req.public_inputs,
+        req.public_inputs_hex.as_deref(),
+        MAX_PUBLIC_INPUTS_SIZE_BYTES,
+        ErrorCode::PublicInputsInvalid,
+    )?;
 
     // Legacy /zkpf/verify endpoint is bound to the default custodial rail.
     let rail = RAILS
         .get("")
         .expect("default custodial rail not configured in RailRegistry");
     if req.circuit_version != rail.circuit_version {
-        return Err(ApiError::bad_request(
-            CODE_CIRCUIT_VERSION,
-            "circuit version mismatch",
+        return Err(ApiError::circuit_version_mismatch(
+            rail.circuit_version,
+            req.circuit_version,
         ));
     }
 
     let policy = state
         .policy_store()
-        .get(req.policy_id)
+        .get_in(namespace.as_deref(), req.policy_id)
         .ok_or_else(|| ApiError::policy_not_found(req.policy_id))?;
 
-    let public_inputs = deserialize_verifier_public_inputs(&req.public_inputs).map_err(|_| {
-        ApiError::bad_request(CODE_PUBLIC_INPUTS, "invalid public_inputs encoding")
+    let public_inputs = deserialize_verifier_public_inputs(&public_inputs).map_err(|_| {
+        ApiError::bad_request(
+            ErrorCode::PublicInputsInvalid,
+            "invalid public_inputs encoding",
+        )
     })?;
+    if let Err(err) = redeem_challenge(&state, None, &[req.policy_id], public_inputs.challenge) {
+        return Ok(Json(VerifyResponse::failure(
+            rail.circuit_version,
+            ErrorCode::ChallengeInvalid,
+            err,
+        )));
+    }
 
-    let response = process_verification(&state, rail, &policy, &public_inputs, &req.proof)?;
+    let response = process_verification(
+        &state,
+        DEFAULT_RAIL_ID,
+        rail,
+        std::slice::from_ref(&policy),
+        &public_inputs,
+        &proof,
+        None,
+        true,
+    )?;
     Ok(Json(response))
 }
 
+#[utoipa::path(
+    post,
+    path = "/zkpf/verify-bundle",
+    tag = "verification",
+    request_body = VerifyBundleRequest,
+    responses(
+        (status = 200, description = "Verification result; failures carry `error_code`", body = VerifyResponse),
+        (status = 400, description = "PROOF_INVALID, CIRCUIT_VERSION_MISMATCH or RAIL_UNKNOWN", body = ErrorResponse),
+        (status = 404, description = "POLICY_NOT_FOUND", body = ErrorResponse),
+        (status = 500, description = "NULLIFIER_STORE_ERROR or INTERNAL_SERVER_ERROR", body = ErrorResponse),
+    )
+)]
 async fn verify_bundle_handler(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(req): Json<VerifyBundleRequest>,
 ) -> Result<Json<VerifyResponse>, ApiError> {
-    // Input size validation
-    if req.bundle.proof.len() > MAX_PROOF_SIZE_BYTES {
-        return Err(ApiError::bad_request(
-            CODE_PROOF_INVALID,
-            "proof exceeds maximum allowed size",
+    let namespace = caller_namespace(&headers)?;
+    Ok(Json(req.verify(&state, namespace.as_deref())?))
+}
+
+/// `/zkpf/verify-bundle` with the policy resolved from its scope, currency
+/// and threshold instead of its id.
+///
+/// Exactly one policy in the caller's namespace must match; none is
+/// POLICY_NOT_FOUND and several is POLICY_MISMATCH, listing their ids so the
+/// client can pick one and use `/zkpf/verify-bundle`.
+#[utoipa::path(
+    post,
+    path = "/zkpf/verify-by-criteria",
+    tag = "verification",
+    request_body = VerifyByCriteriaRequest,
+    responses(
+        (status = 200, description = "Verification result; failures carry `error_code`", body = VerifyResponse),
+        (status = 400, description = "POLICY_MISMATCH, PROOF_INVALID, CIRCUIT_VERSION_MISMATCH or RAIL_UNKNOWN", body = ErrorResponse),
+        (status = 404, description = "POLICY_NOT_FOUND", body = ErrorResponse),
+        (status = 500, description = "NULLIFIER_STORE_ERROR or INTERNAL_SERVER_ERROR", body = ErrorResponse),
+    )
+)]
+async fn verify_by_criteria_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<VerifyByCriteriaRequest>,
+) -> Result<Json<VerifyResponse>, ApiError> {
+    let namespace = caller_namespace(&headers)?;
+    let ids = state.policy_store().find_in(
+        namespace.as_deref(),
+        req.verifier_scope_id,
+        req.required_currency_code,
+        req.threshold_raw,
+    );
+    let policy_id = match ids.as_slice() {
+        [id] => *id,
+        [] => {
+            return Err(ApiError::from_code(
+                ErrorCode::PolicyNotFound,
+                format!(
+                    "no policy with verifier_scope_id {}, required_currency_code {} and threshold_raw {}",
+                    req.verifier_scope_id, req.required_currency_code, req.threshold_raw
+                ),
+            ))
+        }
+        _ => {
+            return Err(ApiError::bad_request(
+                ErrorCode::PolicyMismatch,
+                format!("criteria match several policies {ids:?}; verify by policy_id"),
+            ))
+        }
+    };
+    let request = VerifyBundleRequest {
+        policy_id: Some(policy_id),
+        policy_ids: Vec::new(),
+        challenge_session_id: req.challenge_session_id,
+        record_nullifier: None,
+        bundle: req.bundle,
+    };
+    Ok(Json(request.verify(&state, namespace.as_deref())?))
+}
+
+/// `multipart/form-data` variant of `/zkpf/verify-bundle`.
+///
+/// Expects a `proof` file part with the raw proof bytes and text fields
+/// `policy_id`, `circuit_version`, `public_inputs` (the JSON public inputs
+/// object) and optionally `rail_id` and `challenge_session_id`, the latter
+/// required for a challenge-bound proof. The proof is read chunk by chunk and
+/// rejected as soon as it passes MAX_MULTIPART_PROOF_SIZE_BYTES, which is
+/// higher than the limit on the JSON endpoints.
+#[utoipa::path(
+    post,
+    path = "/zkpf/verify-bundle/multipart",
+    tag = "verification",
+    request_body(content_type = "multipart/form-data", description = "`proof` file part plus `policy_id`, `circuit_version`, `public_inputs` and optional `rail_id` and `challenge_session_id` fields"),
+    responses(
+        (status = 200, description = "Verification result; failures carry `error_code`", body = VerifyResponse),
+        (status = 400, description = "MULTIPART_INVALID, PROOF_INVALID, PUBLIC_INPUTS_INVALID, CIRCUIT_VERSION_MISMATCH or RAIL_UNKNOWN", body = ErrorResponse),
+        (status = 404, description = "POLICY_NOT_FOUND", body = ErrorResponse),
+        (status = 500, description = "NULLIFIER_STORE_ERROR or INTERNAL_SERVER_ERROR", body = ErrorResponse),
+    )
+)]
+async fn verify_bundle_multipart_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    mut multipart: Multipart,
+) -> Result<Json<VerifyResponse>, ApiError> {
+    let namespace = caller_namespace(&headers)?;
+    let mut policy_id = None;
+    let mut rail_id = String::new();
+    let mut circuit_version = None;
+    let mut public_inputs = None;
+    let mut challenge_session_id = None;
+    let mut proof = None;
+
+    while let Some(mut field) = multipart.next_field().await.map_err(multipart_error)? {
+        let name = field.name().unwrap_or_default().to_string();
+        match name.as_str() {
+            "proof" => {
+                let mut bytes = Vec::new();
+                while let Some(chunk) = field.chunk().await.map_err(multipart_error)? {
+                    if bytes.len() + chunk.len() > MAX_MULTIPART_PROOF_SIZE_BYTES {
+                        return Err(ApiError::bad_request(
+                            ErrorCode::ProofInvalid,
+                            "proof exceeds maximum allowed size",
+                        ));
+                    }
+                    bytes.extend_from_slice(&chunk);
+                }
+                proof = Some(bytes);
+            }
+            "policy_id" => {
+                let text = field.text().await.map_err(multipart_error)?;
+                policy_id = Some(text.trim().parse::<u64>().map_err(|_| {
+                    ApiError::bad_request(ErrorCode::MultipartInvalid, "policy_id must be a u64")
+                })?);
+            }
+            "circuit_version" => {
+                let text = field.text().await.map_err(multipart_error)?;
+                circuit_version = Some(text.trim().parse::<u32>().map_err(|_| {
+                    ApiError::bad_request(
+                        ErrorCode::MultipartInvalid,
+                        "circuit_version must be a u32",
+                    )
+                })?);
+            }
+            "rail_id" => {
+                rail_id = field.text().await.map_err(multipart_error)?;
+            }
+            "challenge_session_id" => {
+                let text = field.text().await.map_err(multipart_error)?;
+                challenge_session_id = Some(text.trim().parse::<Uuid>().map_err(|_| {
+                    ApiError::bad_request(
+                        ErrorCode::MultipartInvalid,
+                        "challenge_session_id must be a UUID",
+                    )
+                })?);
+            }
+            "public_inputs" => {
+                let text = field.text().await.map_err(multipart_error)?;
+                if text.len() > MAX_PUBLIC_INPUTS_SIZE_BYTES {
+                    return Err(ApiError::bad_request(
+                        ErrorCode::PublicInputsInvalid,
+                        "public_inputs exceeds maximum allowed size",
+                    ));
+                }
+                public_inputs = Some(serde_json::from_str::<VerifierPublicInputs>(&text).map_err(
+                    |err| {
+                        ApiError::bad_request(
+                            ErrorCode::PublicInputsInvalid,
+                            format!("invalid public_inputs JSON: {err}"),
+                        )
+                    },
+                )?);
+            }
+            other => {
+                return Err(ApiError::bad_request(
+                    ErrorCode::MultipartInvalid,
+                    format!("unexpected multipart field '{other}'"),
+                ));
+            }
+        }
+    }
+
+    let missing = |field: &str| {
+        ApiError::bad_request(
+            ErrorCode::MultipartInvalid,
+            format!("missing '{field}' field"),
+        )
+    };
+    let policy_id = policy_id.ok_or_else(|| missing("policy_id"))?;
+    let bundle = ProofBundle {
+        rail_id,
+        circuit_version: circuit_version.ok_or_else(|| missing("circuit_version"))?,
+        proof: proof.ok_or_else(|| missing("proof"))?,
+        proof_encoding: None,
+        issued_at: None,
+        issued_at_tag: None,
+        public_inputs: public_inputs.ok_or_else(|| missing("public_inputs"))?,
+    };
+
+    let response = verify_bundle(
+        &state,
+        namespace.as_deref(),
+        &[policy_id],
+        &bundle,
+        challenge_session_id,
+        MAX_MULTIPART_PROOF_SIZE_BYTES,
+        true,
+    )?;
+    Ok(Json(response))
+}
+
+fn multipart_error(err: MultipartError) -> ApiError {
+    ApiError::bad_request(ErrorCode::MultipartInvalid, err.body_text())
+}
+
+/// Shared path for the bundle endpoints: redeem the bundle's challenge,
+/// resolve the rail and accepted policies, then hand off to
+/// `process_verification`. `max_proof_size` is the calling route's proof limit.
+fn verify_bundle(
+    state: &AppState,
+    namespace: Option<&str>,
+    policy_ids: &[u64],
+    bundle: &ProofBundle,
+    challenge_session_id: Option<Uuid>,
+    max_proof_size: usize,
+    record_nullifier: bool,
+) -> Result<VerifyResponse, ApiError> {
+    if let Err(err) = redeem_challenge(
+        state,
+        challenge_session_id,
+        policy_ids,
+        bundle.public_inputs.challenge,
+    ) {
+        return Ok(VerifyResponse::failure(
+            bundle.circuit_version,
+            ErrorCode::ChallengeInvalid,
+            err,
         ));
     }
+    let proof = decoded_bundle_proof(bundle, max_proof_size)?;
     // Validate rail_id length to prevent abuse
-    if req.bundle.rail_id.len() > MAX_POLICY_STRING_LEN {
+    if bundle.rail_id.len() > MAX_POLICY_STRING_LEN {
         return Err(ApiError::bad_request(
-            CODE_RAIL_UNKNOWN,
+            ErrorCode::RailUnknown,
             "rail_id exceeds maximum allowed length",
         ));
     }
 
-    // Determine effective rail_id based on bundle content.
-    // If bundle claims to be ZCASH_ORCHARD but lacks required Orchard fields,
-    // fall back to V1 (custodial) layout to support legacy or demo bundles.
-    let effective_rail_id = if req.bundle.rail_id == RAIL_ID_ZCASH_ORCHARD
-        && (req.bundle.public_inputs.snapshot_block_height.is_none()
-            || req.bundle.public_inputs.snapshot_anchor_orchard.is_none())
-    {
-        eprintln!(
-            "[ZKPF Warning] Bundle claims rail_id={} but lacks required Orchard fields \
-             (snapshot_block_height={:?}, snapshot_anchor_orchard={:?}). \
-             Falling back to V1 (custodial) layout.",
-            req.bundle.rail_id,
-            req.bundle.public_inputs.snapshot_block_height,
-            req.bundle.public_inputs.snapshot_anchor_orchard.as_ref().map(|_| "present"),
-        );
-        "" // Empty string maps to default custodial rail with V1 layout
-    } else {
-        req.bundle.rail_id.as_str()
-    };
-
+    let effective_rail_id = effective_rail_id(&bundle.rail_id, &bundle.public_inputs);
     let rail = RAILS.get(effective_rail_id).ok_or_else(|| {
-        ApiError::bad_request(CODE_RAIL_UNKNOWN, "unknown rail_id")
+        ApiError::bad_request(ErrorCode::RailUnknown, "unknown rail_id")
     })?;
 
-    if req.bundle.circuit_version != rail.circuit_version {
-        return Err(ApiError::bad_request(
-            CODE_CIRCUIT_VERSION,
-            "circuit version mismatch",
+    if bundle.circuit_version != rail.circuit_version {
+        return Err(ApiError::circuit_version_mismatch(
+            rail.circuit_version,
+            bundle.circuit_version,
         ));
     }
 
-    let policy = state
-        .policy_store()
-        .get(req.policy_id)
-        .ok_or_else(|| ApiError::policy_not_found(req.policy_id))?;
+    let policies = policy_ids
+        .iter()
+        .map(|&policy_id| {
+            state
+                .policy_store()
+                .get_in(namespace, policy_id)
+                .ok_or_else(|| ApiError::policy_not_found(policy_id))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
 
-    let response = process_verification(
-        &state,
+    process_verification(
+        state,
+        effective_rail_id,
         rail,
-        &policy,
-        &req.bundle.public_inputs,
-        &req.bundle.proof,
-    )?;
-    Ok(Json(response))
+        &policies,
+        &bundle.public_inputs,
+        &proof,
+        bundle_issued_at(bundle),
+        record_nullifier,
+    )
 }
 
-async fn attest_handler(
-    State(state): State<AppState>,
-    Json(req): Json<AttestRequest>,
-) -> Json<AttestResponse> {
-    let base = AttestResponseBase {
-        holder_id: req.holder_id.clone(),
-        policy_id: req.policy_id,
-        snapshot_id: req.snapshot_id.clone(),
-    };
+/// The bundle's `issued_at`, if it is stamped under this server's prover key.
+fn bundle_issued_at(bundle: &ProofBundle) -> Option<u64> {
+    PROVER_SIGNING_KEY
+        .as_ref()
+        .and_then(|key| bundle.authenticated_issued_at(key))
+}
 
-    // Input validation
-    if req.bundle.proof.len() > MAX_PROOF_SIZE_BYTES {
-        return Json(AttestResponse::failure(
-            base,
-            CODE_PROOF_INVALID,
+/// Raw proof bytes of a bundle, decompressed according to `proof_encoding`.
+///
+/// `max_size` applies to both the transported and the decoded proof.
+fn decoded_bundle_proof(bundle: &ProofBundle, max_size: usize) -> Result<Cow<'_, [u8]>, ApiError> {
+    let too_large = || {
+        ApiError::bad_request(
+            ErrorCode::ProofInvalid,
             "proof exceeds maximum allowed size",
-        ));
+        )
+    };
+    if bundle.proof.len() > max_size {
+        return Err(too_large());
     }
-    if req.bundle.rail_id.len() > MAX_POLICY_STRING_LEN {
-        return Json(AttestResponse::failure(
-            base,
-            CODE_RAIL_UNKNOWN,
-            "rail_id exceeds maximum allowed length",
+    let proof = bundle
+        .decoded_proof()
+        .map_err(|err| ApiError::bad_request(ErrorCode::ProofInvalid, format!("{:#}", err)))?;
+    if proof.len() > max_size {
+        return Err(too_large());
+    }
+    Ok(proof)
+}
+
+/// Verify several bundles in one request.
+///
+/// Items are verified in order, each exactly as `/zkpf/verify-bundle` would,
+/// so a nullifier repeated within the batch is accepted once and reported as
+/// NULLIFIER_REPLAY afterwards. Errors that would be an HTTP error for a single
+/// bundle are reported in that item's outcome instead. With `atomic`, see
+/// `verify_batch_atomically`.
+#[utoipa::path(
+    post,
+    path = "/zkpf/verify-bundle/batch",
+    tag = "verification",
+    request_body = VerifyBatchRequest,
+    responses(
+        (status = 200, description = "One outcome per item, in request order", body = [BatchVerifyOutcome]),
+        (status = 400, description = "BATCH_INVALID", body = ErrorResponse),
+    )
+)]
+async fn verify_bundle_batch_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<VerifyBatchRequest>,
+) -> Result<Json<Vec<BatchVerifyOutcome>>, ApiError> {
+    let namespace = caller_namespace(&headers)?;
+    if req.items.len() > MAX_BATCH_ITEMS {
+        return Err(ApiError::bad_request(
+            ErrorCode::BatchInvalid,
+            format!("batch exceeds {MAX_BATCH_ITEMS} items"),
         ));
     }
+    if req.atomic {
+        return verify_batch_atomically(&state, namespace.as_deref(), &req.items).map(Json);
+    }
+    let outcomes = req
+        .items
+        .iter()
+        .enumerate()
+        .map(|(index, item)| {
+            BatchVerifyOutcome::from_result(index, item.verify(&state, namespace.as_deref()))
+        })
+        .collect();
+    Ok(Json(outcomes))
+}
+
+/// Verify every item without touching the nullifier store, then record all
+/// their nullifiers with `NullifierStore::record_batch_atomic`.
+///
+/// Nothing is recorded if an item fails verification or its nullifier
+/// conflicts (already spent, even by the same proof; repeated in the batch; or
+/// behind its epoch watermark). The failing items carry their own error and
+/// every other item fails with BATCH_INVALID. Each item's `record_nullifier`
+/// is ignored.
+fn verify_batch_atomically(
+    state: &AppState,
+    namespace: Option<&str>,
+    items: &[VerifyBundleRequest],
+) -> Result<Vec<BatchVerifyOutcome>, ApiError> {
+    let results: Vec<_> = items
+        .iter()
+        .map(|item| item.verify_recording(state, namespace, false))
+        .collect();
+    let failed: Vec<usize> = results
+        .iter()
+        .enumerate()
+        .filter(|(_, result)| !matches!(result, Ok(response) if response.valid))
+        .map(|(index, _)| index)
+        .collect();
+    if let Some(&first) = failed.first() {
+        return Ok(results
+            .into_iter()
+            .enumerate()
+            .map(|(index, result)| {
+                if failed.contains(&index) {
+                    BatchVerifyOutcome::from_result(index, result)
+                } else {
+                    BatchVerifyOutcome::failure(
+                        index,
+                        ErrorCode::BatchInvalid,
+                        format!("batch not recorded: item {first} failed"),
+                    )
+                }
+            })
+            .collect());
+    }
 
-    let service = match ATTESTATION_SERVICE.as_ref() {
-        Some(service) => service,
-        None => {
-            return Json(AttestResponse::failure(
-                base,
-                CODE_ATTESTATION_DISABLED,
-                "on-chain attestation is not configured",
-            ))
+    // An aggregate proof spends one nullifier per account; `owners` maps each
+    // batch entry back to its item.
+    let mut entries = Vec::with_capacity(items.len());
+    let mut owners = Vec::with_capacity(items.len());
+    for (index, item) in items.iter().enumerate() {
+        let bundle = &item.bundle;
+        let proof = decoded_bundle_proof(bundle, MAX_PROOF_SIZE_BYTES)?;
+        let rail_id = effective_rail_id(&bundle.rail_id, &bundle.public_inputs);
+        let record = NullifierRecord::now(
+            rail_id,
+            bundle.circuit_version,
+            &zkpf_common::hash_bytes_hex(&proof),
+        );
+        for key in NullifierKey::all_from_inputs(&bundle.public_inputs) {
+            entries.push((key, bundle.public_inputs.current_epoch, record.clone()));
+            owners.push(index);
+        }
+    }
+    let recorded = state
+        .nullifier_store()
+        .record_batch_atomic(entries)
+        .map_err(ApiError::nullifier_store)?;
+    Ok(match recorded {
+        Ok(()) => (0..items.len()).map(BatchVerifyOutcome::recorded).collect(),
+        Err(conflicts) => {
+            let mut conflicts: Vec<usize> =
+                conflicts.into_iter().map(|entry| owners[entry]).collect();
+            conflicts.dedup();
+            (0..items.len())
+                .map(|index| {
+                    if conflicts.contains(&index) {
+                        BatchVerifyOutcome::failure(
+                            index,
+                            ErrorCode::NullifierReplay,
+                            "nullifier already spent, repeated in the batch, or behind its epoch",
+                        )
+                    } else {
+                        BatchVerifyOutcome::failure(
+                            index,
+                            ErrorCode::BatchInvalid,
+                            format!("batch not recorded: item {} conflicts", conflicts[0]),
+                        )
+                    }
+                })
+                .collect()
         }
+    })
+}
+
+/// Verify an epoch's shard proofs in one call.
+///
+/// There is no recursive aggregation circuit yet, so the "epoch proof" is the
+/// `EpochProofSet` itself: the set is validated (one rail and circuit version,
+/// every entry stated for the set's `epoch`, distinct nullifiers, matching
+/// `nullifier_root`) and every shard proof is
+/// checked against its rail. Nothing is recorded, so verifiers can re-check a
+/// set freely.
+#[utoipa::path(
+    post,
+    path = "/zkpf/epoch-proof/verify",
+    tag = "verification",
+    request_body(content = Object, description = "`EpochProofSet`: `{ epoch, rail_id, entries, nullifier_root }`"),
+    responses(
+        (status = 200, description = "Verification result; failures carry `error_code` (BUNDLE_INVALID, RAIL_UNKNOWN, CIRCUIT_VERSION_MISMATCH, PUBLIC_INPUTS_INVALID or PROOF_INVALID)", body = EpochProofVerifyResponse),
+        (status = 400, description = "BATCH_INVALID", body = ErrorResponse),
+    )
+)]
+async fn epoch_proof_verify_handler(
+    Json(set): Json<EpochProofSet>,
+) -> Result<Json<EpochProofVerifyResponse>, ApiError> {
+    if set.entries.len() > MAX_BATCH_ITEMS {
+        return Err(ApiError::bad_request(
+            ErrorCode::BatchInvalid,
+            format!("epoch proof set exceeds {MAX_BATCH_ITEMS} entries"),
+        ));
+    }
+    Ok(Json(verify_epoch_proof_set(&set)))
+}
+
+fn verify_epoch_proof_set(set: &EpochProofSet) -> EpochProofVerifyResponse {
+    let response = EpochProofVerifyResponse {
+        valid: true,
+        epoch: set.epoch,
+        rail_id: set.rail_id.clone(),
+        nullifier_root: hex::encode(set.nullifier_root),
+        proof_count: set.entries.len(),
+        error: None,
+        error_code: None,
+        failed_index: None,
     };
+    if let Err(err) = set.validate() {
+        return response.failure(None, ErrorCode::BundleInvalid, format!("{err:#}"));
+    }
+    for (index, entry) in set.entries.iter().enumerate() {
+        if let Err(err) = verify_shard_proof(entry) {
+            return response.failure(Some(index), err.code, err.message);
+        }
+    }
+    response
+}
+
+/// Check one bundle's proof against its rail, without policy, epoch or
+/// nullifier checks. Challenge-bound bundles are refused: there is no session
+/// to redeem their challenge against.
+fn verify_shard_proof(bundle: &ProofBundle) -> Result<(), ApiError> {
+    if bundle.public_inputs.challenge.is_some() {
+        return Err(ApiError::bad_request(
+            ErrorCode::ChallengeInvalid,
+            "challenge-bound bundles cannot be part of an epoch proof set",
+        ));
+    }
+    let proof = decoded_bundle_proof(bundle, MAX_PROOF_SIZE_BYTES)?;
+    let rail = RAILS
+        .get(effective_rail_id(&bundle.rail_id, &bundle.public_inputs))
+        .ok_or_else(|| ApiError::bad_request(ErrorCode::RailUnknown, "unknown rail_id"))?;
+    if bundle.circuit_version != rail.circuit_version {
+        return Err(ApiError::circuit_version_mismatch(
+            rail.circuit_version,
+            bundle.circuit_version,
+        ));
+    }
+    let instances = rail_instances(rail, &bundle.public_inputs)?;
+    match verify_on_rail(rail, &proof, &instances) {
+        Some(true) => Ok(()),
+        Some(false) => Err(ApiError::bad_request(
+            ErrorCode::ProofInvalid,
+            "proof verification failed",
+        )),
+        None => Err(ApiError::bad_request(
+            ErrorCode::ProofInvalid,
+            "verification timed out",
+        )),
+    }
+}
 
-    // Determine effective rail_id based on bundle content.
-    // If bundle claims to be ZCASH_ORCHARD but lacks required Orchard fields,
-    // fall back to V1 (custodial) layout to support legacy or demo bundles.
-    let effective_rail_id = if req.bundle.rail_id == RAIL_ID_ZCASH_ORCHARD
-        && (req.bundle.public_inputs.snapshot_block_height.is_none()
-            || req.bundle.public_inputs.snapshot_anchor_orchard.is_none())
+/// Determine the rail a bundle is verified against.
+///
+/// If the bundle claims to be ZCASH_ORCHARD but lacks required Orchard fields,
+/// fall back to V1 (custodial) layout to support legacy or demo bundles.
+fn effective_rail_id<'a>(rail_id: &'a str, public_inputs: &VerifierPublicInputs) -> &'a str {
+    if rail_id == RAIL_ID_ZCASH_ORCHARD
+        && (public_inputs.snapshot_block_height.is_none()
+            || public_inputs.snapshot_anchor_orchard.is_none())
     {
         eprintln!(
-            "[ZKPF Warning] Attest: Bundle claims rail_id={} but lacks required Orchard fields. \
+            "[ZKPF Warning] Bundle claims rail_id={} but lacks required Orchard fields \
+             (snapshot_block_height={:?}, snapshot_anchor_orchard={:?}). \
              Falling back to V1 (custodial) layout.",
-            req.bundle.rail_id,
+            rail_id,
+            public_inputs.snapshot_block_height,
+            public_inputs
+                .snapshot_anchor_orchard
+                .as_ref()
+                .map(|_| "present"),
         );
         "" // Empty string maps to default custodial rail with V1 layout
     } else {
-        req.bundle.rail_id.as_str()
+        rail_id
+    }
+}
+
+/// Structural pre-check for `/zkpf/verify-bundle`.
+///
+/// Runs the same checks as `verify_bundle` and `process_verification`, in the
+/// same order, but without a proof: no pairing check is done and no nullifier
+/// is recorded. The nullifier check is a read-only lookup. The first failing
+/// stage is reported in the response rather than as an HTTP error.
+#[utoipa::path(
+    post,
+    path = "/zkpf/verify-bundle/dry-run",
+    tag = "verification",
+    request_body = DryRunRequest,
+    responses(
+        (status = 200, description = "Dry-run result; failures carry `failed_stage` and `error_code`", body = DryRunResponse),
+        (status = 500, description = "NULLIFIER_STORE_ERROR", body = ErrorResponse),
+    )
+)]
+async fn verify_bundle_dry_run_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<DryRunRequest>,
+) -> Result<Json<DryRunResponse>, ApiError> {
+    let namespace = caller_namespace(&headers)?;
+    Ok(Json(dry_run_bundle(&state, namespace.as_deref(), &req)?))
+}
+
+fn dry_run_bundle(
+    state: &AppState,
+    namespace: Option<&str>,
+    req: &DryRunRequest,
+) -> Result<DryRunResponse, ApiError> {
+    if req.rail_id.len() > MAX_POLICY_STRING_LEN {
+        return Ok(DryRunResponse::failure(
+            DryRunStage::Rail,
+            ErrorCode::RailUnknown,
+            "rail_id exceeds maximum allowed length",
+        ));
+    }
+    let Some(rail) = RAILS.get(effective_rail_id(&req.rail_id, &req.public_inputs)) else {
+        return Ok(DryRunResponse::failure(
+            DryRunStage::Rail,
+            ErrorCode::RailUnknown,
+            "unknown rail_id",
+        ));
     };
 
-    let rail = match RAILS.get(effective_rail_id) {
-        Some(rail) => rail,
-        None => {
-            return Json(AttestResponse::failure(
-                base,
-                CODE_RAIL_UNKNOWN,
-                "unknown rail_id",
+    if req.circuit_version != rail.circuit_version {
+        return Ok(DryRunResponse::failure(
+            DryRunStage::CircuitVersion,
+            ErrorCode::CircuitVersionMismatch,
+            format!(
+                "circuit version mismatch: rail expects {}",
+                rail.circuit_version
+            ),
+        ));
+    }
+
+    let Some(policy) = state.policy_store().get_in(namespace, req.policy_id) else {
+        return Ok(DryRunResponse::failure(
+            DryRunStage::Policy,
+            ErrorCode::PolicyNotFound,
+            format!("policy_id {} not found", req.policy_id),
+        ));
+    };
+    if let Err(err) = policy
+        .validate_against(&req.public_inputs)
+        .and_then(|()| policy.validate_layout(rail.layout))
+        .and_then(|()| policy.validate_anchor_height(rail.layout, &req.public_inputs))
+    {
+        return Ok(DryRunResponse::failure(
+            DryRunStage::Policy,
+            ErrorCode::PolicyMismatch,
+            err,
+        ));
+    }
+
+    if let Err(err) = validate_epoch(state.epoch_config(), &req.public_inputs) {
+        return Ok(DryRunResponse::failure(
+            DryRunStage::Epoch,
+            ErrorCode::EpochDrift,
+            err,
+        ));
+    }
+
+    if let Err(err) =
+        validate_snapshot_height(state.height_provider(), rail.layout, &req.public_inputs)
+    {
+        return Ok(DryRunResponse::failure(
+            DryRunStage::SnapshotHeight,
+            ErrorCode::SnapshotHeightInvalid,
+            err,
+        ));
+    }
+
+    let nullifier_keys = NullifierKey::all_from_inputs(&req.public_inputs);
+    for nullifier_key in &nullifier_keys {
+        match state.nullifier_store().already_spent(nullifier_key) {
+            Ok(true) => {
+                return Ok(DryRunResponse::failure(
+                    DryRunStage::Nullifier,
+                    ErrorCode::NullifierReplay,
+                    NULLIFIER_SPENT_ERR,
+                ))
+            }
+            Ok(false) => {}
+            Err(err) => return Err(ApiError::nullifier_store(err)),
+        }
+    }
+    let current_epoch = req.public_inputs.current_epoch;
+    match state
+        .nullifier_store()
+        .epoch_rewound(&nullifier_keys[0], current_epoch)
+    {
+        Ok(true) => {
+            return Ok(DryRunResponse::failure(
+                DryRunStage::Nullifier,
+                ErrorCode::EpochRewind,
+                EPOCH_REWIND_ERR,
             ))
         }
-    };
+        Ok(false) => {}
+        Err(err) => return Err(ApiError::nullifier_store(err)),
+    }
 
-    if req.bundle.circuit_version != rail.circuit_version {
-        return Json(AttestResponse::failure(
-            base,
-            CODE_CIRCUIT_VERSION,
-            "circuit version mismatch",
+    if let Err(err) = public_inputs_to_instances_with_layout(rail.layout, &req.public_inputs) {
+        return Ok(DryRunResponse::failure(
+            DryRunStage::PublicInputs,
+            ErrorCode::PublicInputsInvalid,
+            format!(
+                "invalid public inputs for layout {:?}: {}",
+                rail.layout, err
+            ),
         ));
     }
 
-    let policy = match state.policy_store().get(req.policy_id) {
-        Some(policy) => policy,
+    Ok(DryRunResponse::passed())
+}
+
+#[utoipa::path(
+    post,
+    path = "/zkpf/attest",
+    tag = "attestation",
+    request_body = AttestRequest,
+    responses((status = 200, description = "Attestation result; failures carry `error_code`", body = AttestResponse))
+)]
+async fn attest_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<AttestRequest>,
+) -> Json<AttestResponse> {
+    let base = req.response_base();
+    let namespace = match caller_namespace(&headers) {
+        Ok(namespace) => namespace,
+        Err(err) => return Json(AttestResponse::from_error(base, err)),
+    };
+
+    let backend = match state.attestation_backend() {
+        Some(backend) => backend,
         None => {
             return Json(AttestResponse::failure(
                 base,
-                CODE_POLICY_NOT_FOUND,
-                format!("policy_id {} not found", req.policy_id),
+                ErrorCode::AttestationDisabled,
+                "on-chain attestation is not configured",
             ))
         }
     };
 
-    let verification = match process_verification(
-        &state,
-        rail,
-        &policy,
-        &req.bundle.public_inputs,
-        &req.bundle.proof,
-    ) {
-        Ok(response) => response,
+    let entry = match verify_attest_request(&state, namespace.as_deref(), &req) {
+        Ok(entry) => entry,
+        Err(err) => return Json(AttestResponse::from_error(base, err)),
+    };
+
+    let attest_result = match backend
+        .attest(
+            entry.holder_id,
+            entry.policy_id,
+            entry.snapshot_id,
+            entry.nullifier,
+        )
+        .await
+    {
+        Ok(result) => result,
         Err(err) => {
-            return Json(AttestResponse::failure(base, err.code, err.message));
+            return Json(AttestResponse::failure(
+                base,
+                ErrorCode::AttestationOnchainError,
+                err,
+            ))
         }
     };
 
-    if !verification.valid {
-        let code = verification
-            .error_code
-            .unwrap_or(CODE_ATTESTATION_VERIFICATION_FAILED);
-        let message = verification
-            .error
-            .unwrap_or_else(|| "verification failed".to_string());
-        return Json(AttestResponse::failure(base, code, message));
-    }
-
-    // At this point the bundle has been fully verified and the nullifier recorded.
-    //
-    // Identifiers are hashed to 32-byte values off-chain before being sent on-chain.
-    // We intentionally use BLAKE3 here; the EVM contracts only see opaque `bytes32`
-    // values and do not rely on Keccak for these particular identifiers.
-    let holder_hash = blake3_32(req.holder_id.as_bytes());
-    let snapshot_hash = blake3_32(req.snapshot_id.as_bytes());
+    record_attestation_history(
+        &state,
+        namespace.as_deref(),
+        &base,
+        &entry.nullifier,
+        &attest_result,
+    );
+    Json(AttestResponse::recorded(base, attest_result))
+}
 
-    let mut holder_id_bytes = [0u8; 32];
-    holder_id_bytes.copy_from_slice(&holder_hash);
+/// Verify a bundle and attest it on-chain as one operation.
+///
+/// Behaves like `/zkpf/attest`, except that when the on-chain write fails the
+/// nullifier recorded during verification is released again, so the holder
+/// can retry with the same bundle instead of proving a fresh one.
+///
+/// The rollback only covers failures the backend reports. If the transaction
+/// lands but the response is lost (timeout, dropped connection), the
+/// nullifier stays spent and a retry is rejected as `NULLIFIER_REPLAY`; the
+/// attestation itself is on-chain. That window is left to request
+/// idempotency keys (replaying the stored response), which this endpoint does
+/// not accept yet; until then a client seeing `NULLIFIER_REPLAY` on a retry
+/// should look the attestation up in the registry.
+#[utoipa::path(
+    post,
+    path = "/zkpf/verify-and-attest",
+    tag = "attestation",
+    request_body = AttestRequest,
+    responses((status = 200, description = "Attestation result; failures carry `error_code` and leave the nullifier unspent", body = AttestResponse))
+)]
+async fn verify_and_attest_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<AttestRequest>,
+) -> Json<AttestResponse> {
+    let base = req.response_base();
+    let namespace = match caller_namespace(&headers) {
+        Ok(namespace) => namespace,
+        Err(err) => return Json(AttestResponse::from_error(base, err)),
+    };
 
-    let mut snapshot_id_bytes = [0u8; 32];
-    snapshot_id_bytes.copy_from_slice(&snapshot_hash);
+    // Checked before verifying so a disabled backend never consumes a nullifier.
+    let Some(backend) = state.attestation_backend() else {
+        return Json(AttestResponse::failure(
+            base,
+            ErrorCode::AttestationDisabled,
+            "on-chain attestation is not configured",
+        ));
+    };
 
-    let nullifier = req.bundle.public_inputs.nullifier;
+    let entry = match verify_attest_request(&state, namespace.as_deref(), &req) {
+        Ok(entry) => entry,
+        Err(err) => return Json(AttestResponse::from_error(base, err)),
+    };
 
-    let attest_result = match service
-        .attest(holder_id_bytes, req.policy_id, snapshot_id_bytes, nullifier)
+    match backend
+        .attest(
+            entry.holder_id,
+            entry.policy_id,
+            entry.snapshot_id,
+            entry.nullifier,
+        )
         .await
     {
-        Ok(result) => result,
+        Ok(result) => {
+            record_attestation_history(
+                &state,
+                namespace.as_deref(),
+                &base,
+                &entry.nullifier,
+                &result,
+            );
+            Json(AttestResponse::recorded(base, result))
+        }
         Err(err) => {
-            return Json(AttestResponse::failure(
+            let released = NullifierKey::all_from_inputs(&req.bundle.public_inputs)
+                .iter()
+                .try_for_each(|key| state.nullifier_store().forget(key));
+            if let Err(store_err) = released {
+                eprintln!("failed to release nullifier after attestation error: {store_err}");
+                return Json(AttestResponse::failure(
+                    base,
+                    ErrorCode::AttestationOnchainError,
+                    format!("{err}; nullifier could not be released, it remains spent"),
+                ));
+            }
+            Json(AttestResponse::failure(
                 base,
-                CODE_ATTESTATION_ONCHAIN_ERROR,
+                ErrorCode::AttestationOnchainError,
                 err,
             ))
         }
+    }
+}
+
+/// Verify and attest several bundles with a single on-chain call.
+///
+/// Items are verified in order, each as `/zkpf/attest` would (recording its
+/// nullifier); the verified ones are then written with one
+/// `AttestationBackend::attest_batch` call and share its `tx_hash`. Items that
+/// fail verification are reported individually and not attested.
+#[utoipa::path(
+    post,
+    path = "/zkpf/attest-batch",
+    tag = "attestation",
+    request_body = AttestBatchRequest,
+    responses(
+        (status = 200, description = "Shared transaction plus one result per item, in request order", body = AttestBatchResponse),
+        (status = 400, description = "BATCH_INVALID", body = ErrorResponse),
+    )
+)]
+async fn attest_batch_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<AttestBatchRequest>,
+) -> Result<Json<AttestBatchResponse>, ApiError> {
+    let namespace = caller_namespace(&headers)?;
+    if req.items.len() > MAX_BATCH_ITEMS {
+        return Err(ApiError::bad_request(
+            ErrorCode::BatchInvalid,
+            format!("batch exceeds {MAX_BATCH_ITEMS} items"),
+        ));
+    }
+
+    let Some(backend) = state.attestation_backend() else {
+        let items = req
+            .items
+            .iter()
+            .map(|item| {
+                AttestResponse::failure(
+                    item.response_base(),
+                    ErrorCode::AttestationDisabled,
+                    "on-chain attestation is not configured",
+                )
+            })
+            .collect();
+        return Ok(Json(AttestBatchResponse::new(items)));
+    };
+
+    let mut items: Vec<Option<AttestResponse>> = Vec::with_capacity(req.items.len());
+    let mut entries = Vec::new();
+    let mut pending = Vec::new();
+    for item in &req.items {
+        match verify_attest_request(&state, namespace.as_deref(), item) {
+            Ok(entry) => {
+                entries.push(entry);
+                pending.push(items.len());
+                items.push(None);
+            }
+            Err(err) => items.push(Some(AttestResponse::from_error(item.response_base(), err))),
+        }
+    }
+
+    if !entries.is_empty() {
+        let attested = backend.attest_batch(&entries).await.and_then(|results| {
+            if results.len() == entries.len() {
+                Ok(results)
+            } else {
+                Err(format!(
+                    "attestation backend returned {} results for {} entries",
+                    results.len(),
+                    entries.len()
+                ))
+            }
+        });
+        match attested {
+            Ok(results) => {
+                for ((index, entry), result) in pending.into_iter().zip(&entries).zip(results) {
+                    let base = req.items[index].response_base();
+                    record_attestation_history(
+                        &state,
+                        namespace.as_deref(),
+                        &base,
+                        &entry.nullifier,
+                        &result,
+                    );
+                    items[index] = Some(AttestResponse::recorded(base, result));
+                }
+            }
+            Err(err) => {
+                for index in pending {
+                    let base = req.items[index].response_base();
+                    items[index] = Some(AttestResponse::failure(
+                        base,
+                        ErrorCode::AttestationOnchainError,
+                        err.clone(),
+                    ));
+                }
+            }
+        }
+    }
+
+    Ok(Json(AttestBatchResponse::new(
+        items.into_iter().flatten().collect(),
+    )))
+}
+
+/// Append an attestation written on-chain to the attestation history.
+///
+/// A failure is logged rather than returned: the attestation is already
+/// on-chain, and the registry remains the authoritative record.
+fn record_attestation_history(
+    state: &AppState,
+    namespace: Option<&str>,
+    base: &AttestResponseBase,
+    nullifier: &[u8; 32],
+    result: &OnchainAttestationResult,
+) {
+    let record = AttestationRecord {
+        holder_id: base.holder_id.clone(),
+        policy_id: base.policy_id,
+        snapshot_id: base.snapshot_id.clone(),
+        nullifier: hex::encode(nullifier),
+        tx_hash: result.tx_hash.clone(),
+        attestation_id: result.attestation_id.clone(),
+        chain_id: result.chain_id,
+        recorded_at: system_time_secs(SystemTime::now()),
     };
+    if let Err(err) = state.attestation_history().record(namespace, &record) {
+        eprintln!(
+            "failed to record attestation {} in history: {err}",
+            record.tx_hash
+        );
+    }
+}
+
+/// A holder's attestations, newest first.
+///
+/// Lists what the backend recorded after each successful on-chain write, so
+/// the registry does not have to be scanned. Callers only see attestations
+/// made under their own `x-api-key` namespace.
+#[utoipa::path(
+    get,
+    path = "/zkpf/attestations",
+    tag = "attestation",
+    params(AttestationsQuery),
+    responses(
+        (status = 200, description = "One page of attestations", body = AttestationsResponse),
+        (status = 401, description = "UNAUTHORIZED", body = ErrorResponse),
+        (status = 500, description = "INTERNAL_SERVER_ERROR", body = ErrorResponse),
+    )
+)]
+async fn attestations_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<AttestationsQuery>,
+) -> Result<Json<AttestationsResponse>, ApiError> {
+    let namespace = caller_namespace(&headers)?;
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_ATTESTATION_PAGE_SIZE)
+        .clamp(1, MAX_ATTESTATION_PAGE_SIZE);
+    let (attestations, more) = state
+        .attestation_history()
+        .query(
+            namespace.as_deref(),
+            &query.holder_id,
+            query.policy_id,
+            query.offset,
+            limit,
+        )
+        .map_err(|err| ApiError::internal(format!("attestation history error: {err}")))?;
+    let next_offset = more.then_some(query.offset + attestations.len());
+    Ok(Json(AttestationsResponse {
+        attestations,
+        next_offset,
+    }))
+}
+
+/// Verify an attestation request's bundle, recording its nullifier, and return
+/// the hashed tuple to write on-chain.
+///
+/// Every failure is reported through the returned error's `code` and
+/// `message`; the HTTP status is not used.
+fn verify_attest_request(
+    state: &AppState,
+    namespace: Option<&str>,
+    req: &AttestRequest,
+) -> Result<AttestationEntry, ApiError> {
+    // Attestation requests carry no challenge session, so challenge-bound
+    // bundles are refused by `verify_bundle`.
+    let verification = verify_bundle(
+        state,
+        namespace,
+        &[req.policy_id],
+        &req.bundle,
+        None,
+        MAX_PROOF_SIZE_BYTES,
+        true,
+    )?;
+
+    if !verification.valid {
+        let code = verification
+            .error_code
+            .unwrap_or(ErrorCode::AttestationVerificationFailed);
+        let message = verification
+            .error
+            .unwrap_or_else(|| "verification failed".to_string());
+        return Err(ApiError::bad_request(code, message));
+    }
+    // A retried bundle was attested by the earlier request; attesting it again
+    // (or releasing its nullifier if that failed) would be wrong.
+    if verification.already_verified {
+        return Err(ApiError::bad_request(
+            ErrorCode::NullifierReplay,
+            NULLIFIER_SPENT_ERR,
+        ));
+    }
 
-    Json(AttestResponse::success(
-        base,
-        attest_result.tx_hash,
-        attest_result.attestation_id,
-        attest_result.chain_id,
-    ))
+    // At this point the bundle has been fully verified and the nullifier recorded.
+    //
+    // Identifiers are hashed to 32-byte values off-chain before being sent on-chain.
+    // BLAKE3 by default; `ZKPF_ONCHAIN_HASH=keccak256` lets contracts recompute them.
+    Ok(AttestationEntry {
+        holder_id: ONCHAIN_HASH.digest(req.holder_id.as_bytes()),
+        policy_id: req.policy_id,
+        snapshot_id: ONCHAIN_HASH.digest(req.snapshot_id.as_bytes()),
+        nullifier: req.bundle.public_inputs.nullifier,
+    })
 }
 
+#[utoipa::path(
+    post,
+    path = "/zkpf/prove-bundle",
+    tag = "proving",
+    request_body(content = Object, description = "`ZkpfCircuitInput`: attestation witness plus public inputs"),
+    responses(
+        (status = 200, description = "Normalized `ProofBundle`", body = Object),
+        (status = 400, description = "POLICY_MISMATCH, EPOCH_DRIFT or NULLIFIER_REPLAY", body = ErrorResponse),
+        (status = 404, description = "POLICY_NOT_FOUND", body = ErrorResponse),
+        (status = 500, description = "NULLIFIER_STORE_ERROR", body = ErrorResponse),
+        (status = 503, description = "PROVER_DISABLED", body = ErrorResponse),
+    )
+)]
 async fn prove_bundle_handler(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(input): Json<ZkpfCircuitInput>,
 ) -> Result<Json<ProofBundle>, ApiError> {
+    let namespace = caller_namespace(&headers)?;
     let policy = state
         .policy_store()
-        .get(input.public.policy_id)
+        .get_in(namespace.as_deref(), input.public.policy_id)
         .ok_or_else(|| ApiError::policy_not_found(input.public.policy_id))?;
 
     let bundle = prove_with_policy(&state, &policy, input)?;
@@ -2113,14 +4751,78 @@ fn prove_with_policy(
     policy: &PolicyExpectations,
     input: ZkpfCircuitInput,
 ) -> Result<ProofBundle, ApiError> {
+    check_prove_input(state, policy, &input)?;
+
+    // A revealed sum or a challenge is proven with that shape's keys.
+    let artifacts = match (input.public.proven_sum, input.public.challenge) {
+        (Some(_), Some(_)) => {
+            return Err(ApiError::bad_request(
+                ErrorCode::ChallengeInvalid,
+                "challenge-bound proofs cannot reveal the sum",
+            ))
+        }
+        (Some(_), None) => REVEAL_SUM_ARTIFACTS
+            .as_deref()
+            .ok_or_else(|| ApiError::prover_disabled("revealing proofs are not configured"))?,
+        (None, Some(_)) => CHALLENGE_ARTIFACTS.as_deref().ok_or_else(|| {
+            ApiError::prover_disabled("challenge-bound proofs are not configured")
+        })?,
+        (None, None) => state.artifacts(),
+    };
+    let pk = artifacts.proving_key().map_err(|_| {
+        if artifacts.prover_degraded() {
+            ApiError::prover_disabled("prover disabled: proving key is unusable")
+        } else {
+            ApiError::prover_disabled("prover is not available")
+        }
+    })?;
+    let mut bundle = prove_bundle(&artifacts.params, pk.as_ref(), input);
+    if let Some(key) = PROVER_SIGNING_KEY.as_ref() {
+        bundle
+            .stamp_issued_at(system_time_secs(SystemTime::now()), key)
+            .map_err(|err| ApiError::internal(format!("failed to stamp issued_at: {err}")))?;
+    }
+    Ok(bundle)
+}
+
+/// Run the checks `prove_with_policy` performs before proving and return the
+/// public inputs the resulting proof would carry.
+fn check_prove_input(
+    state: &AppState,
+    policy: &PolicyExpectations,
+    input: &ZkpfCircuitInput,
+) -> Result<VerifierPublicInputs, ApiError> {
     let verifier_inputs = public_to_verifier_inputs(&input.public);
 
     if policy.validate_against(&verifier_inputs).is_err() {
-        return Err(ApiError::bad_request(CODE_POLICY_MISMATCH, "policy validation failed"));
+        return Err(ApiError::bad_request(ErrorCode::PolicyMismatch, "policy validation failed"));
     }
 
     if validate_epoch(state.epoch_config(), &verifier_inputs).is_err() {
-        return Err(ApiError::bad_request(CODE_EPOCH_DRIFT, "epoch validation failed"));
+        return Err(ApiError::bad_request(ErrorCode::EpochDrift, "epoch validation failed"));
+    }
+
+    // The circuit recomputes these from the witness, so a mismatch here would
+    // only surface as a proof that never verifies.
+    let expected_nullifier = compute_nullifier_fr(
+        &input.attestation.account_id_hash,
+        input.public.verifier_scope_id,
+        input.public.policy_id,
+        input.public.current_epoch,
+    );
+    if input.public.nullifier != expected_nullifier {
+        return Err(ApiError::bad_request(
+            ErrorCode::PublicInputsInvalid,
+            "nullifier does not match the attestation witness",
+        ));
+    }
+    if input.public.custodian_pubkey_hash
+        != custodian_pubkey_hash(&input.attestation.custodian_pubkey)
+    {
+        return Err(ApiError::bad_request(
+            ErrorCode::PublicInputsInvalid,
+            "custodian_pubkey_hash does not match the attestation witness",
+        ));
     }
 
     // Optimistic pre-check: avoid generating a proof that will be rejected.
@@ -2129,7 +4831,7 @@ fn prove_with_policy(
     match state.nullifier_store().already_spent(&nullifier_key) {
         Ok(true) => {
             return Err(ApiError::bad_request(
-                CODE_NULLIFIER_REPLAY,
+                ErrorCode::NullifierReplay,
                 NULLIFIER_SPENT_ERR,
             ))
         }
@@ -2137,51 +4839,99 @@ fn prove_with_policy(
         Err(_) => return Err(ApiError::nullifier_store("nullifier store error")),
     }
 
-    let artifacts = state.artifacts();
-    let pk = artifacts
-        .proving_key()
-        .map_err(|_| ApiError::prover_disabled("prover is not available"))?;
-    Ok(prove_bundle(&artifacts.params, pk.as_ref(), input))
+    // A revealed sum must be the attested balance.
+    if input
+        .public
+        .proven_sum
+        .is_some_and(|proven_sum| proven_sum != input.attestation.balance_raw)
+    {
+        return Err(ApiError::bad_request(
+            ErrorCode::PublicInputsInvalid,
+            "proven_sum must equal the attested balance",
+        ));
+    }
+
+    Ok(verifier_inputs)
 }
 
 fn parse_hex_32(value: &str) -> Result<[u8; 32], ApiError> {
+    let bytes = parse_hex_bounded(value, MAX_HEX_32_LEN, ErrorCode::PublicInputsInvalid)?;
+    if bytes.len() != 32 {
+        return Err(ApiError::bad_request(
+            ErrorCode::PublicInputsInvalid,
+            "expected 32 bytes",
+        ));
+    }
+    let mut out = [0u8; 32];
+    out.copy_from_slice(&bytes);
+    Ok(out)
+}
+
+/// Decode hex (`0x` prefix optional) of at most `max_len` characters.
+fn parse_hex_bounded(value: &str, max_len: usize, code: ErrorCode) -> Result<Vec<u8>, ApiError> {
     let trimmed = value.trim();
     // Length check before decoding to prevent DoS via extremely long strings
-    if trimmed.len() > MAX_HEX_32_LEN {
+    if trimmed.len() > max_len {
         return Err(ApiError::bad_request(
-            CODE_PUBLIC_INPUTS,
+            code,
             "hex string exceeds maximum allowed length",
         ));
     }
     let without_prefix = trimmed.strip_prefix("0x").unwrap_or(trimmed);
-    let bytes = hex::decode(without_prefix).map_err(|_| {
-        ApiError::bad_request(CODE_PUBLIC_INPUTS, "invalid hex encoding")
-    })?;
-    if bytes.len() != 32 {
+    hex::decode(without_prefix).map_err(|_| ApiError::bad_request(code, "invalid hex encoding"))
+}
+
+/// The bytes of `field`, sent either as a byte array or as `<field>_hex`.
+/// Sending both is ambiguous and rejected, as is sending neither.
+fn bytes_or_hex(
+    field: &str,
+    bytes: Option<Vec<u8>>,
+    hex: Option<&str>,
+    max_bytes: usize,
+    code: ErrorCode,
+) -> Result<Vec<u8>, ApiError> {
+    let bytes = match (bytes, hex) {
+        (Some(bytes), None) => bytes,
+        (None, Some(hex)) => parse_hex_bounded(hex, 2 * max_bytes + 2, code)?,
+        (Some(_), Some(_)) => {
+            return Err(ApiError::bad_request(
+                code,
+                format!("send either {field} or {field}_hex, not both"),
+            ))
+        }
+        (None, None) => {
+            return Err(ApiError::bad_request(
+                code,
+                format!("{field} or {field}_hex is required"),
+            ))
+        }
+    };
+    if bytes.len() > max_bytes {
         return Err(ApiError::bad_request(
-            CODE_PUBLIC_INPUTS,
-            "expected 32 bytes",
+            code,
+            format!("{field} exceeds maximum allowed size"),
         ));
     }
-    let mut out = [0u8; 32];
-    out.copy_from_slice(&bytes);
-    Ok(out)
+    Ok(bytes)
 }
 
 async fn zashi_session_start(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(req): Json<ZashiSessionStartRequest>,
 ) -> Result<Json<ZashiSessionStartResponse>, ApiError> {
+    let namespace = caller_namespace(&headers)?;
     let policy = state
         .policy_store()
-        .get(req.policy_id)
+        .get_in(namespace.as_deref(), req.policy_id)
         .ok_or_else(|| ApiError::policy_not_found(req.policy_id))?;
     ensure_zashi_policy(&policy)?;
-    let session = state.provider_sessions().start_session(policy);
     let scheme = req
         .deep_link_scheme
         .as_deref()
         .unwrap_or(DEFAULT_DEEP_LINK_SCHEME);
+    ensure_deep_link_scheme_allowed(scheme)?;
+    let session = state.provider_sessions().start_session(policy);
     let deep_link = format!(
         "{scheme}://zkpf-proof?session_id={}&policy_id={}",
         session.session_id, req.policy_id
@@ -2189,6 +4939,62 @@ async fn zashi_session_start(
     Ok(Json(session.into_response(deep_link)))
 }
 
+/// Reject deep-link schemes that are malformed or not on the configured allowlist.
+///
+/// The scheme is interpolated into the URL handed back to the client, so an
+/// arbitrary value would let a caller point the deep link anywhere.
+fn ensure_deep_link_scheme_allowed(scheme: &str) -> Result<(), ApiError> {
+    if !is_valid_deep_link_scheme(scheme) {
+        return Err(ApiError::bad_request(
+            ErrorCode::DeepLinkSchemeInvalid,
+            "deep_link_scheme must match [a-z][a-z0-9+.-]*",
+        ));
+    }
+    if !ALLOWED_DEEP_LINK_SCHEMES
+        .iter()
+        .any(|allowed| allowed == scheme)
+    {
+        return Err(ApiError::bad_request(
+            ErrorCode::DeepLinkSchemeInvalid,
+            format!("deep_link_scheme '{scheme}' is not allowed"),
+        ));
+    }
+    Ok(())
+}
+
+fn is_valid_deep_link_scheme(scheme: &str) -> bool {
+    let mut chars = scheme.chars();
+    matches!(chars.next(), Some('a'..='z'))
+        && chars.all(|c| matches!(c, 'a'..='z' | '0'..='9' | '+' | '.' | '-'))
+}
+
+/// Comma-separated allowlist from `ZKPF_ALLOWED_DEEP_LINK_SCHEMES`, defaulting
+/// to just `zashi`. Malformed entries are dropped.
+fn allowed_deep_link_schemes() -> Vec<String> {
+    let schemes: Vec<String> = env::var(DEEP_LINK_SCHEMES_ENV)
+        .ok()
+        .map(|value| {
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|scheme| {
+                    let valid = is_valid_deep_link_scheme(scheme);
+                    if !valid && !scheme.is_empty() {
+                        eprintln!("[ZashiSession] ignoring invalid deep-link scheme '{scheme}'");
+                    }
+                    valid
+                })
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+    if schemes.is_empty() {
+        vec![DEFAULT_DEEP_LINK_SCHEME.to_string()]
+    } else {
+        schemes
+    }
+}
+
 async fn zashi_session_submit(
     State(state): State<AppState>,
     Json(req): Json<ZashiSessionSubmitRequest>,
@@ -2211,7 +5017,7 @@ async fn zashi_session_submit(
             .provider_sessions()
             .finish_failure(&req.session_id, "currency mismatch".into());
         return Err(ApiError::bad_request(
-            CODE_POLICY_MISMATCH,
+            ErrorCode::PolicyMismatch,
             "attestation currency_code_int does not match policy",
         ));
     }
@@ -2220,7 +5026,7 @@ async fn zashi_session_submit(
             .provider_sessions()
             .finish_failure(&req.session_id, "threshold not met".into());
         return Err(ApiError::bad_request(
-            CODE_POLICY_MISMATCH,
+            ErrorCode::PolicyMismatch,
             "balance_raw does not satisfy policy threshold",
         ));
     }
@@ -2230,7 +5036,7 @@ async fn zashi_session_submit(
         state
             .provider_sessions()
             .finish_failure(&req.session_id, "invalid attestation hash".into());
-        return Err(ApiError::bad_request(CODE_PUBLIC_INPUTS, "invalid attestation hash"));
+        return Err(ApiError::bad_request(ErrorCode::PublicInputsInvalid, "invalid attestation hash"));
     }
 
     // Verify the ECDSA signature over the message hash.
@@ -2240,23 +5046,23 @@ async fn zashi_session_submit(
         &attestation.custodian_pubkey,
         &attestation.signature,
         &attestation.message_hash,
+        attestation.signature_format,
     ).is_err() {
         state
             .provider_sessions()
             .finish_failure(&req.session_id, "invalid attestation signature".into());
-        return Err(ApiError::bad_request(CODE_PUBLIC_INPUTS, "invalid attestation signature"));
+        return Err(ApiError::bad_request(ErrorCode::PublicInputsInvalid, "invalid attestation signature"));
     }
 
     let witness = attestation.to_witness();
-    let account_id_hash = witness.account_id_hash;
     // Derive the custodian_pubkey_hash directly from the attestation’s
     // secp256k1 public key instead of requiring an out-of-band allowlist
     // entry for the custodian_id.
     let pubkey_hash = custodian_pubkey_hash(&witness.custodian_pubkey);
 
     let current_epoch = state.epoch_config().current_epoch();
-    let nullifier = compute_nullifier_fr(
-        &account_id_hash,
+    let nullifier = derive_account_nullifier(
+        &attestation.account_id_hash,
         policy.verifier_scope_id,
         policy.policy_id,
         current_epoch,
@@ -2270,6 +5076,8 @@ async fn zashi_session_submit(
         policy_id: policy.policy_id,
         nullifier,
         custodian_pubkey_hash: pubkey_hash,
+        proven_sum: req.reveal_sum.then_some(witness.balance_raw),
+        challenge: None,
     };
 
     let input = ZkpfCircuitInput {
@@ -2306,7 +5114,7 @@ async fn zashi_session_status(
         .ok_or_else(|| {
             ApiError::new(
                 StatusCode::NOT_FOUND,
-                CODE_SESSION_NOT_FOUND,
+                ErrorCode::SessionNotFound,
                 format!("session {} not found", session_id),
             )
         })
@@ -2314,12 +5122,59 @@ async fn zashi_session_status(
 
 async fn provider_prove_balance_handler(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(req): Json<ProviderProveBalanceRequest>,
 ) -> Result<Json<ProofBundle>, ApiError> {
+    let namespace = caller_namespace(&headers)?;
+    let reveal_sum = req.reveal_sum;
+    let (policy, circuit_input) = provider_circuit_input(&state, namespace.as_deref(), req)?;
+
+    let mut bundle = prove_with_policy(&state, &policy, circuit_input)?;
+
+    // Mark this bundle as belonging to the provider-balance rail so that
+    // multi-rail verification routes it correctly. Revealing proofs keep the
+    // reveal-sum rail, which carries their verifying key.
+    if !reveal_sum {
+        bundle.rail_id = PROVIDER_BALANCE_RAIL_ID.to_string();
+    }
+
+    Ok(Json(bundle))
+}
+
+#[utoipa::path(
+    post,
+    path = "/zkpf/prove-bundle/preview",
+    tag = "proving",
+    request_body(content = Object, description = "Provider balance attestation, `policy_id` and optional `reveal_sum`, as for `/zkpf/provider/prove-balance`"),
+    responses(
+        (status = 200, description = "`VerifierPublicInputs` the proof would carry", body = Object),
+        (status = 400, description = "PUBLIC_INPUTS, POLICY_MISMATCH, EPOCH_DRIFT or NULLIFIER_REPLAY", body = ErrorResponse),
+        (status = 404, description = "POLICY_NOT_FOUND", body = ErrorResponse),
+        (status = 500, description = "NULLIFIER_STORE_ERROR", body = ErrorResponse),
+    )
+)]
+async fn prove_bundle_preview_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<ProviderProveBalanceRequest>,
+) -> Result<Json<VerifierPublicInputs>, ApiError> {
+    let namespace = caller_namespace(&headers)?;
+    let (policy, circuit_input) = provider_circuit_input(&state, namespace.as_deref(), req)?;
+    let public_inputs = check_prove_input(&state, &policy, &circuit_input)?;
+    Ok(Json(public_inputs))
+}
+
+/// Derive the circuit input for a provider balance attestation under the
+/// requested policy and the current epoch.
+fn provider_circuit_input(
+    state: &AppState,
+    namespace: Option<&str>,
+    req: ProviderProveBalanceRequest,
+) -> Result<(PolicyExpectations, ZkpfCircuitInput), ApiError> {
     // Validate account_tag length before processing
     if req.attestation.account_tag.len() > MAX_ACCOUNT_TAG_LEN {
         return Err(ApiError::bad_request(
-            CODE_PUBLIC_INPUTS,
+            ErrorCode::PublicInputsInvalid,
             "account_tag exceeds maximum allowed length",
         ));
     }
@@ -2328,21 +5183,20 @@ async fn provider_prove_balance_handler(
     // required provider identifier (re-using the custodial ID field).
     let policy = state
         .policy_store()
-        .get(req.policy_id)
+        .get_in(namespace, req.policy_id)
         .ok_or_else(|| ApiError::policy_not_found(req.policy_id))?;
 
     let current_epoch = state.epoch_config().current_epoch();
 
     let att = req.attestation;
 
-    // Normalize the opaque account_tag into a field element using the same
-    // big-endian reduction helper used elsewhere in the stack.
+    // The opaque account_tag plays the role of the attestation's
+    // account_id_hash, so the account gets the same nullifier as in the Zashi
+    // session flow.
     let account_tag_bytes = parse_hex_32(&att.account_tag)?;
-    let account_id_hash = reduce_be_bytes_to_fr(&account_tag_bytes);
-
-    // Compute the canonical nullifier field element that the circuit expects.
-    let nullifier = nullifier_fr(
-        account_id_hash,
+    let account_id_hash = account_id_hash_from_tag(&account_tag_bytes);
+    let nullifier = derive_account_nullifier(
+        &account_tag_bytes,
         policy.verifier_scope_id,
         policy.policy_id,
         current_epoch,
@@ -2360,6 +5214,8 @@ async fn provider_prove_balance_handler(
         policy_id: policy.policy_id,
         nullifier,
         custodian_pubkey_hash: pubkey_hash,
+        proven_sum: req.reveal_sum.then_some(att.balance_raw),
+        challenge: None,
     };
 
     let witness = AttestationWitness {
@@ -2373,6 +5229,7 @@ async fn provider_prove_balance_handler(
         custodian_pubkey: att.custodian_pubkey,
         signature: att.signature,
         message_hash: att.message_hash,
+        signature_format: att.signature_format,
     };
 
     let circuit_input = ZkpfCircuitInput {
@@ -2380,35 +5237,57 @@ async fn provider_prove_balance_handler(
         public,
     };
 
-    let mut bundle = prove_with_policy(&state, &policy, circuit_input)?;
-
-    // Mark this bundle as belonging to the provider-balance rail so that
-    // multi-rail verification routes it correctly.
-    bundle.rail_id = PROVIDER_BALANCE_RAIL_ID.to_string();
-
-    Ok(Json(bundle))
+    Ok((policy, circuit_input))
 }
 
+/// Verify a proof against the first of `policies` its public inputs satisfy.
+///
+/// With several policies, the mismatch error of the first one is reported when
+/// none match. `issued_at` is the bundle's authenticated issuance time, if any.
+#[allow(clippy::too_many_arguments)]
 fn process_verification(
     state: &AppState,
+    rail_id: &str,
     rail: &RailVerifier,
-    policy: &PolicyExpectations,
+    policies: &[PolicyExpectations],
     public_inputs: &VerifierPublicInputs,
     proof: &[u8],
+    issued_at: Option<u64>,
+    record_nullifier: bool,
 ) -> Result<VerifyResponse, ApiError> {
-    // Input size validation to prevent DoS
-    if proof.len() > MAX_PROOF_SIZE_BYTES {
+    // Input size validation to prevent DoS. Routes enforce their own, usually
+    // lower, limit before this; this is the ceiling across all of them.
+    if proof.len() > MAX_MULTIPART_PROOF_SIZE_BYTES {
         return Ok(VerifyResponse::failure(
             rail.circuit_version,
-            CODE_PROOF_INVALID,
+            ErrorCode::ProofInvalid,
             "proof exceeds maximum allowed size",
         ));
     }
 
-    if let Err(err) = policy.validate_against(public_inputs) {
+    let validate = |policy: &PolicyExpectations| {
+        policy
+            .validate_against(public_inputs)
+            .and_then(|()| policy.validate_layout(rail.layout))
+            .and_then(|()| policy.validate_anchor_height(rail.layout, public_inputs))
+    };
+    let matched = policies.iter().find(|policy| validate(policy).is_ok());
+    let Some(policy) = matched else {
+        let err = policies
+            .first()
+            .and_then(|policy| validate(policy).err())
+            .unwrap_or_else(|| "no accepted policy".to_string());
+        return Ok(VerifyResponse::failure(
+            rail.circuit_version,
+            ErrorCode::PolicyMismatch,
+            err,
+        ));
+    };
+
+    if let Err(err) = policy.validate_proof_age(issued_at, system_time_secs(SystemTime::now())) {
         return Ok(VerifyResponse::failure(
             rail.circuit_version,
-            CODE_POLICY_MISMATCH,
+            ErrorCode::StaleProof,
             err,
         ));
     }
@@ -2416,7 +5295,16 @@ fn process_verification(
     if let Err(err) = validate_epoch(state.epoch_config(), public_inputs) {
         return Ok(VerifyResponse::failure(
             rail.circuit_version,
-            CODE_EPOCH_DRIFT,
+            ErrorCode::EpochDrift,
+            err,
+        ));
+    }
+
+    if let Err(err) = validate_snapshot_height(state.height_provider(), rail.layout, public_inputs)
+    {
+        return Ok(VerifyResponse::failure(
+            rail.circuit_version,
+            ErrorCode::SnapshotHeightInvalid,
             err,
         ));
     }
@@ -2424,44 +5312,73 @@ fn process_verification(
     // Optimistic pre-check for already-spent nullifiers.
     // This allows fast rejection before expensive proof verification.
     // The authoritative check happens atomically in record_atomic below.
-    let nullifier_key = NullifierKey::from_inputs(public_inputs);
-    match state.nullifier_store().already_spent(&nullifier_key) {
-        Ok(true) => {
-            return Ok(VerifyResponse::failure(
-                rail.circuit_version,
-                CODE_NULLIFIER_REPLAY,
-                NULLIFIER_SPENT_ERR,
-            ))
+    // A spent nullifier recorded by this very proof is a retry: it is still
+    // verified below, then reported as already verified.
+    // With record_nullifier=false (read replicas) the store is not consulted.
+    // An aggregate proof spends one nullifier per account and each is checked.
+    let nullifier_keys = NullifierKey::all_from_inputs(public_inputs);
+    let nullifier_key = nullifier_keys[0].clone();
+    let proof_hash = zkpf_common::hash_bytes_hex(proof);
+    let current_epoch = public_inputs.current_epoch;
+    let mut retry = false;
+    if record_nullifier {
+        for key in &nullifier_keys {
+            match state.nullifier_store().already_spent(key) {
+                Ok(true) => {
+                    if !state
+                        .nullifier_store()
+                        .recorded_by(key, &proof_hash)
+                        .map_err(ApiError::nullifier_store)?
+                    {
+                        state.replay_log().record(rail_id, key);
+                        return Ok(VerifyResponse::failure(
+                            rail.circuit_version,
+                            ErrorCode::NullifierReplay,
+                            NULLIFIER_SPENT_ERR,
+                        ));
+                    }
+                    retry = true;
+                }
+                Ok(false) => {}
+                Err(err) => return Err(ApiError::nullifier_store(err)),
+            }
+        }
+        match state
+            .nullifier_store()
+            .epoch_rewound(&nullifier_key, current_epoch)
+        {
+            Ok(true) if !retry => {
+                return Ok(VerifyResponse::failure(
+                    rail.circuit_version,
+                    ErrorCode::EpochRewind,
+                    EPOCH_REWIND_ERR,
+                ))
+            }
+            Ok(_) => {}
+            Err(err) => return Err(ApiError::nullifier_store(err)),
         }
-        Ok(false) => {}
-        Err(err) => return Err(ApiError::nullifier_store(err)),
     }
 
-    let instances =
-        public_inputs_to_instances_with_layout(rail.layout, public_inputs).map_err(|err| {
-            eprintln!(
-                "[ZKPF Error] public_inputs_to_instances_with_layout failed: layout={:?}, error={}",
-                rail.layout, err
-            );
-            ApiError::bad_request(
-                CODE_PUBLIC_INPUTS,
-                format!("invalid public inputs for layout {:?}: {}", rail.layout, err),
-            )
-        })?;
+    let instances = rail_instances(rail, public_inputs)?;
 
-    let (params, vk, artifact_k, vk_hash) = match &rail.artifacts {
-        RailArtifacts::Prover(a) => (
-            &a.params,
-            &a.vk,
-            a.manifest.k,
-            a.manifest.vk.blake3.clone(),
-        ),
-        RailArtifacts::Verifier(a) => (
-            &a.params,
-            &a.vk,
-            a.manifest.k,
-            a.manifest.vk.blake3.clone(),
-        ),
+    // Fast-fail truncated proofs before the pairing checks.
+    let min_proof_len = min_proof_size_bytes(instances.len());
+    if proof.len() < min_proof_len {
+        return Ok(VerifyResponse::failure(
+            rail.circuit_version,
+            ErrorCode::ProofInvalid,
+            format!(
+                "proof is {} bytes, below the {} byte minimum for {} instance columns",
+                proof.len(),
+                min_proof_len,
+                instances.len()
+            ),
+        ));
+    }
+
+    let (artifact_k, vk_hash) = match &rail.artifacts {
+        RailArtifacts::Prover(a) => (a.manifest.k, a.manifest.vk.blake3.clone()),
+        RailArtifacts::Verifier(a) => (a.manifest.k, a.manifest.vk.blake3.clone()),
     };
 
     // Comprehensive diagnostic logging for proof verification
@@ -2540,7 +5457,19 @@ fn process_verification(
     }
     eprintln!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
 
-    if !verify(params, vk, proof, &instances) {
+    let Some(verified) = verify_on_rail(rail, proof, &instances) else {
+        eprintln!(
+            "[ZKPF Debug] ❌ VERIFICATION TIMED OUT after {} ms",
+            VERIFY_TIMEOUT.unwrap_or_default().as_millis()
+        );
+        return Ok(VerifyResponse::failure(
+            rail.circuit_version,
+            ErrorCode::ProofInvalid,
+            "verification timed out",
+        ));
+    };
+
+    if !verified {
         eprintln!("[ZKPF Debug] ❌ VERIFICATION FAILED!");
         eprintln!(
             "[ZKPF Debug] Possible causes: VK mismatch, instance count mismatch (expected {} for k={}), proof corruption",
@@ -2548,26 +5477,139 @@ fn process_verification(
         );
         return Ok(VerifyResponse::failure(
             rail.circuit_version,
-            CODE_PROOF_INVALID,
+            ErrorCode::ProofInvalid,
             "proof verification failed",
         ));
     }
     eprintln!("[ZKPF Debug] ✓ Verification succeeded");
 
+    if !record_nullifier {
+        return Ok(VerifyResponse::unrecorded(
+            rail.circuit_version,
+            policy.policy_id,
+        ));
+    }
+
+    if retry {
+        return Ok(VerifyResponse::already_verified(
+            rail.circuit_version,
+            policy.policy_id,
+        ));
+    }
+
     // Atomic nullifier recording using compare-and-swap.
     // This prevents race conditions where two concurrent requests could both
     // pass the optimistic already_spent check but only one should succeed.
-    match state.nullifier_store().record_atomic(nullifier_key) {
-        Ok(()) => Ok(VerifyResponse::success(rail.circuit_version)),
-        Err(err) if err == NULLIFIER_SPENT_ERR => Ok(VerifyResponse::failure(
+    let record = NullifierRecord::now(rail_id, rail.circuit_version, &proof_hash);
+    match state
+        .nullifier_store()
+        .record_all_atomic(nullifier_keys, current_epoch, record)
+    {
+        Ok(()) => Ok(VerifyResponse::success(
             rail.circuit_version,
-            CODE_NULLIFIER_REPLAY,
-            NULLIFIER_SPENT_ERR,
+            policy.policy_id,
+        )),
+        // A concurrent request with the same proof recorded it first.
+        Err(err)
+            if err == NULLIFIER_SPENT_ERR
+                && state
+                    .nullifier_store()
+                    .recorded_by(&nullifier_key, &proof_hash)
+                    .unwrap_or(false) =>
+        {
+            Ok(VerifyResponse::already_verified(
+                rail.circuit_version,
+                policy.policy_id,
+            ))
+        }
+        Err(err) if err == NULLIFIER_SPENT_ERR => {
+            state.replay_log().record(rail_id, &nullifier_key);
+            Ok(VerifyResponse::failure(
+                rail.circuit_version,
+                ErrorCode::NullifierReplay,
+                NULLIFIER_SPENT_ERR,
+            ))
+        }
+        Err(err) if err == EPOCH_REWIND_ERR => Ok(VerifyResponse::failure(
+            rail.circuit_version,
+            ErrorCode::EpochRewind,
+            EPOCH_REWIND_ERR,
         )),
         Err(_) => Err(ApiError::nullifier_store("nullifier store error")),
     }
 }
 
+/// Instances for `public_inputs` in the rail's layout, checked against the
+/// column count its verifying key expects.
+fn rail_instances(
+    rail: &RailVerifier,
+    public_inputs: &VerifierPublicInputs,
+) -> Result<Vec<Vec<Fr>>, ApiError> {
+    let instances =
+        public_inputs_to_instances_with_layout(rail.layout, public_inputs).map_err(|err| {
+            eprintln!(
+                "[ZKPF Error] public_inputs_to_instances_with_layout failed: layout={:?}, error={}",
+                rail.layout, err
+            );
+            ApiError::bad_request(
+                ErrorCode::PublicInputsInvalid,
+                format!("invalid public inputs for layout {:?}: {}", rail.layout, err),
+            )
+        })?;
+
+    // A column count the verifying key was not built for can only fail the
+    // pairing checks, with no hint why.
+    let expected_columns = rail.artifacts.instance_columns(rail.layout);
+    if instances.len() != expected_columns {
+        return Err(ApiError::bad_request(
+            ErrorCode::PublicInputsInvalid,
+            format!(
+                "public inputs have {} instance columns, but the rail's verifying key for layout {:?} expects {}",
+                instances.len(),
+                rail.layout,
+                expected_columns
+            ),
+        ));
+    }
+    Ok(instances)
+}
+
+/// Verify `proof` with the rail's keys, within `ZKPF_VERIFY_TIMEOUT_MS` when
+/// set. `None` means the deadline passed.
+fn verify_on_rail(rail: &RailVerifier, proof: &[u8], instances: &[Vec<Fr>]) -> Option<bool> {
+    let Some(timeout) = *VERIFY_TIMEOUT else {
+        return Some(match &rail.artifacts {
+            RailArtifacts::Prover(a) => verify(&a.params, &a.vk, proof, instances),
+            RailArtifacts::Verifier(a) => verify(&a.params, &a.vk, proof, instances),
+        });
+    };
+    let artifacts = rail.artifacts.clone();
+    let proof = proof.to_vec();
+    let instances = instances.to_vec();
+    let job = move || match &artifacts {
+        RailArtifacts::Prover(a) => verify(&a.params, &a.vk, &proof, &instances),
+        RailArtifacts::Verifier(a) => verify(&a.params, &a.vk, &proof, &instances),
+    };
+    run_with_deadline(timeout, job)
+}
+
+/// Run `job` on its own thread and wait at most `timeout` for its result.
+///
+/// Returns `None` on timeout. The thread cannot be interrupted, so it runs to
+/// completion in the background and its result is discarded; the caller is
+/// released at the deadline.
+fn run_with_deadline<T, F>(timeout: Duration, job: F) -> Option<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(job());
+    });
+    rx.recv_timeout(timeout).ok()
+}
+
 fn load_artifacts() -> ProverArtifacts {
     let path = env::var(MANIFEST_ENV).unwrap_or_else(|_| DEFAULT_MANIFEST_PATH.to_string());
     let prover_enabled = prover_enabled_from_env();
@@ -2596,16 +5638,82 @@ fn load_artifacts() -> ProverArtifacts {
     })
 }
 
+/// Load the revealing-circuit artifacts named by `ZKPF_REVEAL_SUM_MANIFEST_PATH`,
+/// if set. Loaded the same way as the default artifacts.
+fn load_reveal_sum_artifacts() -> Option<ProverArtifacts> {
+    load_shape_artifacts(REVEAL_SUM_MANIFEST_ENV, "reveal-sum")
+}
+
+/// Load the challenge-bound circuit artifacts named by
+/// `ZKPF_CHALLENGE_MANIFEST_PATH`, if set.
+fn load_challenge_artifacts() -> Option<ProverArtifacts> {
+    load_shape_artifacts(CHALLENGE_MANIFEST_ENV, "challenge")
+}
+
+fn load_shape_artifacts(manifest_env: &str, label: &str) -> Option<ProverArtifacts> {
+    let path = env::var(manifest_env).ok()?;
+    let loader = if prover_enabled_from_env() {
+        load_prover_artifacts_lazy
+    } else {
+        load_prover_artifacts_without_pk
+    };
+    eprintln!("zkpf-backend: loading {label} artifacts from {}", path);
+    let artifacts = loader(&path)
+        .unwrap_or_else(|err| panic!("failed to load {label} artifacts from {path}: {err}"));
+    Some(artifacts)
+}
+
 fn prover_enabled_from_env() -> bool {
     env::var(ENABLE_PROVER_ENV)
         .map(|value| matches!(value.to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
         .unwrap_or(true)
 }
 
+/// Response compression is on unless `ZKPF_RESPONSE_COMPRESSION` is set to
+/// something other than `1`, `true` or `yes`.
+fn response_compression_from_env() -> bool {
+    env::var(RESPONSE_COMPRESSION_ENV)
+        .map(|value| matches!(value.to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+        .unwrap_or(true)
+}
+
+/// True if `name` is set to `1`, `true` or `yes`; unset means false.
+fn env_flag(name: &str) -> bool {
+    env::var(name)
+        .map(|value| matches!(value.to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+        .unwrap_or(false)
+}
+
+/// Deserialize the lazily loaded proving key on a blocking thread, so the
+/// first proof after a deploy does not pay for it.
+fn prewarm_proving_key(artifacts: Arc<ProverArtifacts>) {
+    if !artifacts.prover_enabled() {
+        eprintln!("zkpf-backend: {PREWARM_PK_ENV} ignored, prover is disabled");
+        return;
+    }
+    tokio::task::spawn_blocking(move || {
+        let started = Instant::now();
+        match artifacts.proving_key() {
+            Ok(_) => eprintln!(
+                "zkpf-backend: proving key pre-warmed in {:.1}s",
+                started.elapsed().as_secs_f64()
+            ),
+            Err(err) => eprintln!("zkpf-backend: proving key pre-warm failed: {err:#}"),
+        }
+    });
+}
+
 fn validate_epoch(config: &EpochConfig, inputs: &VerifierPublicInputs) -> Result<(), String> {
     let server_epoch = config.current_epoch();
     let drift = config.max_drift_secs();
     let epoch = inputs.current_epoch;
+    // A proof built just before the epoch ticked over carries the previous
+    // epoch. Its nullifier is bound to that epoch, so it is checked and
+    // recorded under it; the epoch watermark admits the same previous epochs
+    // and rejects anything older once a later epoch has been accepted.
+    if config.within_previous_epochs(epoch) {
+        return Ok(());
+    }
     if epoch > server_epoch {
         let delta = epoch - server_epoch;
         if delta > drift {
@@ -2626,6 +5734,48 @@ fn validate_epoch(config: &EpochConfig, inputs: &VerifierPublicInputs) -> Result
     Ok(())
 }
 
+/// Chain whose height a layout's `snapshot_block_height` refers to.
+///
+/// V6_SNAPSHOT_COMMITMENT is shared by rails on different chains, so its
+/// height is not checked against a provider.
+fn snapshot_chain(layout: PublicInputLayout) -> Option<&'static str> {
+    match layout {
+        PublicInputLayout::V2Orchard => Some("ZEC"),
+        PublicInputLayout::V3Starknet => Some("STARKNET"),
+        PublicInputLayout::V1
+        | PublicInputLayout::V4Aggregate
+        | PublicInputLayout::V5Range
+        | PublicInputLayout::V1WithSum
+        | PublicInputLayout::V6SnapshotCommitment => None,
+    }
+}
+
+/// Reject snapshots taken above the current chain height.
+///
+/// Skipped when the layout carries no snapshot height or the provider has no
+/// height for the chain, so deployments without a height oracle behave as before.
+fn validate_snapshot_height(
+    heights: &dyn HeightProvider,
+    layout: PublicInputLayout,
+    inputs: &VerifierPublicInputs,
+) -> Result<(), String> {
+    let (Some(chain), Some(snapshot_height)) =
+        (snapshot_chain(layout), inputs.snapshot_block_height)
+    else {
+        return Ok(());
+    };
+    let Ok(current_height) = heights.current_height(chain) else {
+        return Ok(());
+    };
+    if snapshot_height > current_height {
+        return Err(format!(
+            "snapshot_block_height {} is ahead of current {} height {}",
+            snapshot_height, chain, current_height
+        ));
+    }
+    Ok(())
+}
+
 fn ensure_zashi_policy(_policy: &PolicyExpectations) -> Result<(), ApiError> {
     Ok(())
 }
@@ -2634,14 +5784,16 @@ fn session_error(err: SessionError) -> ApiError {
     match err {
         SessionError::NotFound => ApiError::new(
             StatusCode::NOT_FOUND,
-            CODE_SESSION_NOT_FOUND,
+            ErrorCode::SessionNotFound,
             "session not found",
         ),
-        SessionError::Expired => {
-            ApiError::new(StatusCode::GONE, CODE_SESSION_STATE, "session expired")
-        }
+        SessionError::Expired => ApiError::new(
+            StatusCode::GONE,
+            ErrorCode::SessionStateInvalid,
+            "session expired",
+        ),
         SessionError::State(reason) => {
-            ApiError::new(StatusCode::CONFLICT, CODE_SESSION_STATE, reason)
+            ApiError::new(StatusCode::CONFLICT, ErrorCode::SessionStateInvalid, reason)
         }
     }
 }
@@ -2656,13 +5808,28 @@ fn system_time_secs(time: SystemTime) -> u64 {
 pub struct EpochConfig {
     epoch_override: Option<u64>,
     max_drift_secs: u64,
+    /// How many epochs before the current one are accepted outright.
+    previous_epochs: u64,
+    /// Length of one epoch in `current_epoch` units. An overridden epoch is a
+    /// counter, so this is 1; the wall clock is in seconds.
+    epoch_secs: u64,
 }
 
 impl EpochConfig {
     fn from_env() -> Self {
+        let epoch_override = parse_env_u64(EPOCH_OVERRIDE_ENV);
+        let default_epoch_secs = if epoch_override.is_some() {
+            1
+        } else {
+            DEFAULT_EPOCH_SECS
+        };
         Self {
-            epoch_override: parse_env_u64(EPOCH_OVERRIDE_ENV),
+            epoch_override,
             max_drift_secs: parse_env_u64(EPOCH_DRIFT_ENV).unwrap_or(DEFAULT_MAX_EPOCH_DRIFT_SECS),
+            previous_epochs: parse_env_u64(PREVIOUS_EPOCHS_ENV).unwrap_or(0),
+            epoch_secs: parse_env_u64(EPOCH_SECS_ENV)
+                .filter(|secs| *secs > 0)
+                .unwrap_or(default_epoch_secs),
         }
     }
 
@@ -2670,9 +5837,25 @@ impl EpochConfig {
         Self {
             epoch_override: Some(epoch),
             max_drift_secs: 0,
+            previous_epochs: 0,
+            epoch_secs: 1,
         }
     }
 
+    /// Also accept proofs stated for any of the `count` epochs before the
+    /// current one.
+    pub fn with_previous_epochs(mut self, count: u64) -> Self {
+        self.previous_epochs = count;
+        self
+    }
+
+    /// Measure epochs in spans of `secs` `current_epoch` units; zero is
+    /// treated as 1.
+    pub fn with_epoch_secs(mut self, secs: u64) -> Self {
+        self.epoch_secs = secs.max(1);
+        self
+    }
+
     fn current_epoch(&self) -> u64 {
         if let Some(epoch) = self.epoch_override {
             epoch
@@ -2687,6 +5870,24 @@ impl EpochConfig {
     fn max_drift_secs(&self) -> u64 {
         self.max_drift_secs
     }
+
+    /// Index of the epoch `value` falls in.
+    fn epoch_index(&self, value: u64) -> u64 {
+        value / self.epoch_secs
+    }
+
+    /// Whether `epoch` is no later than now and falls in the current epoch or
+    /// one of the `previous_epochs` before it, which skips the drift check.
+    ///
+    /// Always false when no previous epochs are accepted, so the drift check
+    /// still applies within the current epoch.
+    fn within_previous_epochs(&self, epoch: u64) -> bool {
+        let current = self.current_epoch();
+        self.previous_epochs > 0
+            && epoch <= current
+            && self.epoch_index(epoch).saturating_add(self.previous_epochs)
+                >= self.epoch_index(current)
+    }
 }
 
 fn parse_env_u64(var: &str) -> Option<u64> {
@@ -2700,6 +5901,39 @@ fn blake3_32(input: &[u8]) -> [u8; 32] {
     *hash.as_bytes()
 }
 
+/// Digest used for the `holder_id` and `snapshot_id` sent to the attestation registry.
+///
+/// Switching it changes every on-chain identifier: attestations recorded under
+/// one digest cannot be looked up with the other.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum OnchainHash {
+    Blake3,
+    /// Matches Solidity's `keccak256(bytes(id))`, so contracts can derive the
+    /// same `bytes32` without a BLAKE3 precompile.
+    Keccak256,
+}
+
+impl OnchainHash {
+    fn from_env() -> Self {
+        let hash = env::var(ONCHAIN_HASH_ENV).unwrap_or_else(|_| "blake3".to_string());
+        match hash.trim().to_ascii_lowercase().as_str() {
+            "blake3" => OnchainHash::Blake3,
+            "keccak256" => OnchainHash::Keccak256,
+            other => panic!(
+                "unknown {} '{}', expected 'blake3' or 'keccak256'",
+                ONCHAIN_HASH_ENV, other
+            ),
+        }
+    }
+
+    fn digest(self, input: &[u8]) -> [u8; 32] {
+        match self {
+            OnchainHash::Blake3 => blake3_32(input),
+            OnchainHash::Keccak256 => Keccak256::digest(input).into(),
+        }
+    }
+}
+
 /// Verify a secp256k1 ECDSA signature over a message hash.
 ///
 /// This provides early rejection of invalid signatures before expensive proof
@@ -2709,7 +5943,8 @@ fn blake3_32(input: &[u8]) -> [u8; 32] {
 /// # Arguments
 /// * `pubkey` - The secp256k1 public key (x, y coordinates)
 /// * `signature` - The ECDSA signature (r, s components)
-/// * `message_hash` - The 32-byte message hash that was signed
+/// * `message_hash` - The 32-byte attestation message hash
+/// * `format` - How the signer wrapped `message_hash` (raw, EIP-191 or EIP-712)
 ///
 /// # Returns
 /// * `Ok(())` if signature is valid
@@ -2718,6 +5953,7 @@ fn verify_secp256k1_ecdsa(
     pubkey: &Secp256k1Pubkey,
     signature: &EcdsaSignature,
     message_hash: &[u8; 32],
+    format: SignatureFormat,
 ) -> Result<(), String> {
     // Construct uncompressed SEC1 public key: 0x04 || x || y
     let mut pubkey_bytes = [0u8; 65];
@@ -2736,32 +5972,120 @@ fn verify_secp256k1_ecdsa(
     let sig = K256Signature::from_slice(&sig_bytes)
         .map_err(|_| "invalid signature format".to_string())?;
 
-    // Verify signature over the message hash
-    // Note: k256 uses the prehash variant for raw message hashes
-    verifying_key
-        .verify(message_hash, &sig)
-        .map_err(|_| "signature verification failed".to_string())
+    // A raw-hash signature is over the SHA-256 of the message hash, as it has
+    // always been checked here. The EIP-191 and EIP-712 digests are the
+    // Keccak-256 hashes wallets sign, so they are checked as prehashes.
+    let verified = match format {
+        SignatureFormat::RawHash => verifying_key.verify(message_hash, &sig),
+        SignatureFormat::Eip191 | SignatureFormat::Eip712 => {
+            verifying_key.verify_prehash(&format.signed_digest(message_hash), &sig)
+        }
+    };
+    verified.map_err(|_| "signature verification failed".to_string())
 }
 
+#[utoipa::path(
+    get,
+    path = "/zkpf/epoch",
+    tag = "verification",
+    responses((status = 200, description = "Verifier epoch and allowed drift", body = EpochResponse))
+)]
 async fn get_epoch(State(state): State<AppState>) -> Json<EpochResponse> {
-    let epoch = state.epoch_config().current_epoch();
-    let drift = state.epoch_config().max_drift_secs();
+    let config = state.epoch_config();
     Json(EpochResponse {
-        current_epoch: epoch,
-        max_drift_secs: drift,
+        current_epoch: config.current_epoch(),
+        max_drift_secs: config.max_drift_secs(),
+        previous_epochs: config.previous_epochs,
+        epoch_secs: config.epoch_secs,
     })
 }
 
+/// Issue a single-use challenge for `policy_id`.
+///
+/// The prover includes `challenge` as a public input, and `/zkpf/verify-bundle`
+/// only accepts the proof with the matching `challenge_session_id`, once,
+/// before `expires_at`.
+#[utoipa::path(
+    post,
+    path = "/zkpf/challenge",
+    tag = "verification",
+    request_body = ChallengeRequest,
+    responses(
+        (status = 200, description = "Issued challenge", body = ChallengeResponse),
+        (status = 404, description = "POLICY_NOT_FOUND", body = ErrorResponse),
+        (status = 429, description = "RATE_LIMITED", body = ErrorResponse),
+    )
+)]
+async fn issue_challenge_handler(
+    State(state): State<AppState>,
+    ClientIp(client): ClientIp,
+    headers: HeaderMap,
+    Json(req): Json<ChallengeRequest>,
+) -> Result<Json<ChallengeResponse>, ApiError> {
+    let namespace = caller_namespace(&headers)?;
+    if state
+        .policy_store()
+        .get_in(namespace.as_deref(), req.policy_id)
+        .is_none()
+    {
+        return Err(ApiError::policy_not_found(req.policy_id));
+    }
+    let (session_id, record) = state
+        .challenge_store()
+        .issue(req.policy_id, client)
+        .map_err(|err| ApiError::from_code(ErrorCode::RateLimited, err))?;
+    Ok(Json(ChallengeResponse {
+        session_id,
+        challenge: hex::encode(record.challenge),
+        expires_at: system_time_secs(record.expires_at),
+    }))
+}
+
 /// Health check endpoint for load balancers and orchestrators.
 /// Returns 200 OK immediately without requiring any state initialization.
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "health",
+    responses((status = 200, description = "Service is up", body = String))
+)]
 async fn health_check() -> &'static str {
     "ok"
 }
 
-fn snap_dir() -> String {
-    env::var(SNAP_DIR_ENV).unwrap_or_else(|_| DEFAULT_SNAP_DIR.to_string())
-}
-
+/// Readiness endpoint: reports whether the prover is usable and whether the
+/// startup self-check passed.
+#[utoipa::path(
+    get,
+    path = "/readyz",
+    tag = "health",
+    responses(
+        (status = 200, description = "Service readiness", body = ReadyResponse),
+        (status = 503, description = "Startup self-check failed", body = ReadyResponse),
+    )
+)]
+async fn readiness_check(State(state): State<AppState>) -> (StatusCode, Json<ReadyResponse>) {
+    let artifacts = state.artifacts();
+    let prover_degraded = artifacts.prover_degraded();
+    let self_check_error = SELF_CHECK.as_ref().err().cloned();
+    let (status_code, status) = match (&self_check_error, prover_degraded) {
+        (Some(_), _) => (StatusCode::SERVICE_UNAVAILABLE, "failed"),
+        (None, true) => (StatusCode::OK, "degraded"),
+        (None, false) => (StatusCode::OK, "ready"),
+    };
+    let response = ReadyResponse {
+        status,
+        prover_enabled: artifacts.prover_enabled(),
+        prover_degraded,
+        self_check_error,
+    };
+    (status_code, Json(response))
+}
+
+fn snap_dir() -> String {
+    env::var(SNAP_DIR_ENV).unwrap_or_else(|_| DEFAULT_SNAP_DIR.to_string())
+}
+
 async fn serve_snap_manifest() -> Result<Response, ApiError> {
     let path = format!("{}/snap.manifest.json", snap_dir());
     serve_snap_file(&path, "application/json").await
@@ -2783,7 +6107,7 @@ async fn serve_snap_file(path: &str, content_type: &'static str) -> Result<Respo
         eprintln!("snap file not found at {}: {}", path, err);
         ApiError::new(
             StatusCode::NOT_FOUND,
-            CODE_ARTIFACT_NOT_FOUND,
+            ErrorCode::ArtifactNotFound,
             "snap file not found",
         )
     })?;
@@ -2807,17 +6131,469 @@ async fn serve_snap_file(path: &str, content_type: &'static str) -> Result<Respo
 #[derive(Clone)]
 pub struct NullifierStore {
     backend: Arc<NullifierBackend>,
+    cache: Arc<SpentCache>,
+    /// Length of one epoch in `current_epoch` units. Watermarks hold the start
+    /// of the newest accepted epoch and are compared in whole epochs.
+    epoch_secs: u64,
+    /// Epochs before the watermark that are still accepted, matching
+    /// `EpochConfig::previous_epochs`.
+    previous_epochs: u64,
 }
 
 enum NullifierBackend {
-    InMemory(Mutex<HashSet<NullifierKey>>),
+    InMemory(Mutex<InMemoryNullifiers>),
     Persistent(Db),
+    AppendLog(Mutex<AppendLogNullifiers>),
+}
+
+/// Bounded LRU set of keys confirmed spent, checked before the backend.
+///
+/// It only ever holds positives, so a hit is definitely spent and a miss falls
+/// through to the backend. `NullifierStore::forget` evicts the key it releases.
+struct SpentCache {
+    capacity: usize,
+    entries: Mutex<SpentCacheEntries>,
+}
+
+#[derive(Default)]
+struct SpentCacheEntries {
+    /// Bumped on every touch; the smallest stamp is the least recently used key.
+    clock: u64,
+    stamps: HashMap<NullifierKey, u64>,
+    by_stamp: BTreeMap<u64, NullifierKey>,
+}
+
+impl SpentCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::default(),
+        }
+    }
+
+    /// Whether `key` is cached as spent, marking it recently used if so.
+    fn contains(&self, key: &NullifierKey) -> bool {
+        if self.capacity == 0 {
+            return false;
+        }
+        let mut entries = recover_lock(self.entries.lock(), "nullifier cache");
+        let SpentCacheEntries {
+            clock,
+            stamps,
+            by_stamp,
+        } = &mut *entries;
+        let Some(stamp) = stamps.get_mut(key) else {
+            return false;
+        };
+        by_stamp.remove(&*stamp);
+        *clock += 1;
+        *stamp = *clock;
+        by_stamp.insert(*clock, key.clone());
+        true
+    }
+
+    fn insert(&self, key: &NullifierKey) {
+        if self.capacity == 0 || self.contains(key) {
+            return;
+        }
+        let mut entries = recover_lock(self.entries.lock(), "nullifier cache");
+        entries.clock += 1;
+        let stamp = entries.clock;
+        entries.stamps.insert(key.clone(), stamp);
+        entries.by_stamp.insert(stamp, key.clone());
+        if entries.stamps.len() > self.capacity {
+            if let Some((_, oldest)) = entries.by_stamp.pop_first() {
+                entries.stamps.remove(&oldest);
+            }
+        }
+    }
+
+    fn remove(&self, key: &NullifierKey) {
+        let mut entries = recover_lock(self.entries.lock(), "nullifier cache");
+        if let Some(stamp) = entries.stamps.remove(key) {
+            entries.by_stamp.remove(&stamp);
+        }
+    }
+}
+
+/// A verification rejected because its nullifier was already consumed.
+#[derive(Clone, Debug, serde::Serialize, ToSchema)]
+pub struct ReplayEvent {
+    /// Unix time at which the replay was rejected.
+    recorded_at: u64,
+    rail_id: String,
+    policy_id: u64,
+    verifier_scope_id: u64,
+    /// First 8 bytes of the replayed nullifier, hex-encoded.
+    nullifier_prefix: String,
+}
+
+/// Bounded in-memory log of the most recent replay rejections.
+///
+/// Sized by `ZKPF_REPLAY_LOG_SIZE` (default 100); the oldest event is
+/// dropped once the log is full, and a size of 0 disables it.
+#[derive(Clone)]
+pub struct ReplayLog {
+    capacity: usize,
+    events: Arc<Mutex<VecDeque<ReplayEvent>>>,
+}
+
+impl ReplayLog {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            events: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        let capacity = env::var(REPLAY_LOG_SIZE_ENV)
+            .ok()
+            .and_then(|value| value.trim().parse().ok())
+            .unwrap_or(DEFAULT_REPLAY_LOG_SIZE);
+        Self::new(capacity)
+    }
+
+    fn record(&self, rail_id: &str, key: &NullifierKey) {
+        if self.capacity == 0 {
+            return;
+        }
+        let rail_id = if rail_id.is_empty() {
+            DEFAULT_RAIL_ID
+        } else {
+            rail_id
+        };
+        let event = ReplayEvent {
+            recorded_at: system_time_secs(SystemTime::now()),
+            rail_id: rail_id.to_string(),
+            policy_id: key.policy_id,
+            verifier_scope_id: key.scope_id,
+            nullifier_prefix: hex::encode(&key.nullifier[..8]),
+        };
+        let mut events = recover_lock(self.events.lock(), "replay log");
+        if events.len() == self.capacity {
+            events.pop_front();
+        }
+        events.push_back(event);
+    }
+
+    /// Logged events, newest first.
+    pub fn recent(&self) -> Vec<ReplayEvent> {
+        let events = recover_lock(self.events.lock(), "replay log");
+        events.iter().rev().cloned().collect()
+    }
+}
+
+/// An attestation the backend wrote on-chain.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, ToSchema)]
+pub struct AttestationRecord {
+    pub holder_id: String,
+    pub policy_id: u64,
+    pub snapshot_id: String,
+    /// Nullifier of the attested bundle, hex-encoded.
+    pub nullifier: String,
+    pub tx_hash: String,
+    pub attestation_id: String,
+    pub chain_id: u64,
+    /// Unix time at which the attestation was recorded.
+    pub recorded_at: u64,
+}
+
+/// Stored form of an `AttestationRecord`, tagged with the API-key namespace it
+/// was made under; only that namespace's callers can list it.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct StoredAttestation {
+    namespace: Option<String>,
+    #[serde(flatten)]
+    record: AttestationRecord,
+}
+
+/// sled-backed ledger of successful attestations, read by
+/// `/zkpf/attestations`.
+///
+/// Records are keyed by the BLAKE3 hash of `holder_id` followed by an ID from
+/// `Db::generate_id`, so a holder's records are one prefix scan and come back
+/// in the order they were written.
+#[derive(Clone)]
+pub struct AttestationHistory {
+    db: Db,
+}
+
+impl AttestationHistory {
+    pub fn persistent(path: impl AsRef<Path>) -> Self {
+        let path_ref = path.as_ref();
+        if let Some(parent) = path_ref.parent() {
+            if !parent.as_os_str().is_empty() {
+                fs::create_dir_all(parent).unwrap_or_else(|err| {
+                    panic!(
+                        "failed to create directory for attestation history at {}: {}",
+                        path_ref.display(),
+                        err
+                    )
+                });
+            }
+        }
+        let db = sled::open(path_ref).unwrap_or_else(|err| {
+            panic!(
+                "failed to open attestation history at {}: {}",
+                path_ref.display(),
+                err
+            )
+        });
+        Self { db }
+    }
+
+    /// History that is discarded when the last handle is dropped.
+    pub fn in_memory() -> Self {
+        let db = sled::Config::new()
+            .temporary(true)
+            .open()
+            .unwrap_or_else(|err| panic!("failed to open in-memory attestation history: {err}"));
+        Self { db }
+    }
+
+    pub fn from_env() -> Self {
+        let path = env::var(ATTESTATION_HISTORY_DB_ENV)
+            .unwrap_or_else(|_| DEFAULT_ATTESTATION_HISTORY_DB_PATH.to_string());
+        Self::persistent(path)
+    }
+
+    pub fn record(
+        &self,
+        namespace: Option<&str>,
+        record: &AttestationRecord,
+    ) -> Result<(), String> {
+        let id = self.db.generate_id().map_err(|err| err.to_string())?;
+        let mut key = Self::holder_prefix(&record.holder_id).to_vec();
+        key.extend_from_slice(&id.to_be_bytes());
+        let stored = StoredAttestation {
+            namespace: namespace.map(str::to_string),
+            record: record.clone(),
+        };
+        let value = serde_json::to_vec(&stored).map_err(|err| err.to_string())?;
+        self.db.insert(key, value).map_err(|err| err.to_string())?;
+        Ok(())
+    }
+
+    /// Up to `limit` of a holder's attestations under `namespace`, newest
+    /// first, after skipping `offset`, and whether more follow.
+    pub fn query(
+        &self,
+        namespace: Option<&str>,
+        holder_id: &str,
+        policy_id: Option<u64>,
+        offset: usize,
+        limit: usize,
+    ) -> Result<(Vec<AttestationRecord>, bool), String> {
+        let mut matching = self
+            .db
+            .scan_prefix(Self::holder_prefix(holder_id))
+            .rev()
+            .map(|entry| {
+                let (_, value) = entry.map_err(|err| err.to_string())?;
+                serde_json::from_slice::<StoredAttestation>(&value).map_err(|err| err.to_string())
+            })
+            .filter(|stored| match stored {
+                Ok(stored) => {
+                    stored.namespace.as_deref() == namespace
+                        && policy_id.is_none_or(|policy_id| stored.record.policy_id == policy_id)
+                }
+                Err(_) => true,
+            })
+            .map(|stored| stored.map(|stored| stored.record))
+            .skip(offset);
+        let page = matching
+            .by_ref()
+            .take(limit)
+            .collect::<Result<Vec<_>, _>>()?;
+        let more = matching.next().is_some();
+        Ok((page, more))
+    }
+
+    fn holder_prefix(holder_id: &str) -> [u8; 32] {
+        *blake3::hash(holder_id.as_bytes()).as_bytes()
+    }
+}
+
+#[derive(Default)]
+struct InMemoryNullifiers {
+    spent: HashMap<NullifierKey, NullifierRecord>,
+    /// Start of the newest accepted epoch per (scope, policy).
+    epochs: HashMap<(u64, u64), u64>,
+}
+
+/// Append-only nullifier file of fixed 57-byte records, indexed in memory and
+/// replayed on startup.
+///
+/// A record is a one-byte kind, a 48-byte `NullifierKey::storage_key` and the
+/// big-endian start of the epoch it was accepted in. A spent record marks the key
+/// consumed; a watermark record only carries its scope/policy's epoch, so a
+/// rewrite can keep watermarks whose keys were all forgotten. Replaying both
+/// rebuilds the epoch watermarks. No audit metadata is written, so lookups
+/// read back defaults.
+struct AppendLogNullifiers {
+    path: PathBuf,
+    file: fs::File,
+    spent: HashSet<NullifierKey>,
+    /// Start of the newest accepted epoch per (scope, policy).
+    epochs: HashMap<(u64, u64), u64>,
+}
+
+impl AppendLogNullifiers {
+    const RECORD_LEN: usize = 57;
+    const SPENT: u8 = 1;
+    const WATERMARK: u8 = 2;
+
+    /// Open (or create) the log and rebuild the index from it.
+    ///
+    /// A trailing partial record, left by a crash mid-append, is ignored and
+    /// cut off so later appends stay aligned. An unknown record kind fails the
+    /// open rather than dropping keys.
+    fn open(path: &Path) -> std::io::Result<Self> {
+        let bytes = match fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(err),
+        };
+        let complete = bytes.len() - bytes.len() % Self::RECORD_LEN;
+        let mut spent = HashSet::new();
+        let mut epochs: HashMap<(u64, u64), u64> = HashMap::new();
+        for (index, record) in bytes[..complete].chunks_exact(Self::RECORD_LEN).enumerate() {
+            let key = NullifierKey::from_storage_key(&record[1..49]);
+            match record[0] {
+                Self::SPENT => {
+                    spent.insert(key.clone());
+                }
+                Self::WATERMARK => {}
+                kind => {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        format!("record {index} has unknown kind {kind}"),
+                    ))
+                }
+            }
+            let epoch = decode_epoch(&record[49..]).unwrap_or(0);
+            let watermark = epochs.entry(key.scope_policy()).or_default();
+            *watermark = (*watermark).max(epoch);
+        }
+
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        if complete < bytes.len() {
+            eprintln!(
+                "nullifier log {}: dropping {} byte partial record",
+                path.display(),
+                bytes.len() - complete
+            );
+            file.set_len(complete as u64)?;
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+            spent,
+            epochs,
+        })
+    }
+
+    fn record(kind: u8, key: &NullifierKey, epoch: u64) -> [u8; Self::RECORD_LEN] {
+        let mut buf = [0u8; Self::RECORD_LEN];
+        buf[0] = kind;
+        buf[1..49].copy_from_slice(&key.storage_key());
+        buf[49..].copy_from_slice(&epoch.to_be_bytes());
+        buf
+    }
+
+    /// Append a spent key and flush it to disk.
+    fn append(&mut self, key: &NullifierKey, epoch: u64) -> std::io::Result<()> {
+        self.file
+            .write_all(&Self::record(Self::SPENT, key, epoch))?;
+        self.file.sync_data()
+    }
+
+    /// Append several spent keys with a single write and flush.
+    fn append_batch(&mut self, entries: &[(&NullifierKey, u64)]) -> std::io::Result<()> {
+        let bytes: Vec<u8> = entries
+            .iter()
+            .flat_map(|(key, epoch)| Self::record(Self::SPENT, key, *epoch))
+            .collect();
+        self.file.write_all(&bytes)?;
+        self.file.sync_data()
+    }
+
+    /// Rewrite the log from the in-memory index, replacing it atomically.
+    ///
+    /// Each watermark is written first as its own record, so it survives even
+    /// when no spent key of its scope/policy remains.
+    fn rewrite(&mut self) -> std::io::Result<()> {
+        let tmp_path = self.path.with_extension("log.tmp");
+        let mut tmp = fs::File::create(&tmp_path)?;
+        for (&(scope_id, policy_id), &epoch) in &self.epochs {
+            let scope_policy = NullifierKey {
+                scope_id,
+                policy_id,
+                nullifier: [0u8; 32],
+            };
+            tmp.write_all(&Self::record(Self::WATERMARK, &scope_policy, epoch))?;
+        }
+        for key in &self.spent {
+            let epoch = self.epochs.get(&key.scope_policy()).copied().unwrap_or(0);
+            tmp.write_all(&Self::record(Self::SPENT, key, epoch))?;
+        }
+        tmp.sync_all()?;
+        fs::rename(&tmp_path, &self.path)?;
+        self.file = fs::OpenOptions::new().append(true).open(&self.path)?;
+        Ok(())
+    }
+}
+
+/// Audit metadata stored with each consumed nullifier.
+///
+/// Nullifiers recorded before metadata was kept read back with zero/empty
+/// fields.
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(default)]
+struct NullifierRecord {
+    /// Unix time at which the nullifier was consumed.
+    recorded_at: u64,
+    /// Rail the consuming proof was verified on.
+    rail_id: String,
+    circuit_version: u32,
+    /// BLAKE3 hex of the consuming proof's bytes, used to recognise retries.
+    proof_hash: String,
+}
+
+impl NullifierRecord {
+    fn now(rail_id: &str, circuit_version: u32, proof_hash: &str) -> Self {
+        let rail_id = if rail_id.is_empty() {
+            DEFAULT_RAIL_ID
+        } else {
+            rail_id
+        };
+        Self {
+            recorded_at: system_time_secs(SystemTime::now()),
+            rail_id: rail_id.to_string(),
+            circuit_version,
+            proof_hash: proof_hash.to_string(),
+        }
+    }
+
+    fn from_stored(bytes: &[u8]) -> Self {
+        if bytes.is_empty() {
+            return Self::default();
+        }
+        serde_json::from_slice(bytes).unwrap_or_default()
+    }
 }
 
 impl NullifierStore {
     pub fn in_memory() -> Self {
         Self {
-            backend: Arc::new(NullifierBackend::InMemory(Mutex::new(HashSet::new()))),
+            backend: Arc::new(NullifierBackend::InMemory(Mutex::default())),
+            cache: Arc::new(SpentCache::new(DEFAULT_NULLIFIER_CACHE_SIZE)),
+            epoch_secs: 1,
+            previous_epochs: 0,
         }
     }
 
@@ -2843,13 +6619,92 @@ impl NullifierStore {
         });
         Self {
             backend: Arc::new(NullifierBackend::Persistent(db)),
+            cache: Arc::new(SpentCache::new(DEFAULT_NULLIFIER_CACHE_SIZE)),
+            epoch_secs: 1,
+            previous_epochs: 0,
+        }
+    }
+
+    /// Store backed by an append-only file of nullifier keys; see
+    /// `AppendLogNullifiers`.
+    pub fn append_log(path: impl AsRef<Path>) -> Self {
+        let path_ref = path.as_ref();
+        if let Some(parent) = path_ref.parent() {
+            if !parent.as_os_str().is_empty() {
+                fs::create_dir_all(parent).unwrap_or_else(|err| {
+                    panic!(
+                        "failed to create directory for nullifier log at {}: {}",
+                        path_ref.display(),
+                        err
+                    )
+                });
+            }
+        }
+        let log = AppendLogNullifiers::open(path_ref).unwrap_or_else(|err| {
+            panic!(
+                "failed to open nullifier log at {}: {}",
+                path_ref.display(),
+                err
+            )
+        });
+        Self {
+            backend: Arc::new(NullifierBackend::AppendLog(Mutex::new(log))),
+            cache: Arc::new(SpentCache::new(DEFAULT_NULLIFIER_CACHE_SIZE)),
+            epoch_secs: 1,
+            previous_epochs: 0,
+        }
+    }
+
+    /// Bound the cache of known-spent keys consulted before the backend; 0
+    /// disables it.
+    pub fn with_cache_size(self, capacity: usize) -> Self {
+        Self {
+            cache: Arc::new(SpentCache::new(capacity)),
+            ..self
+        }
+    }
+
+    /// Count watermark epochs in spans of `secs` `current_epoch` units, as
+    /// `EpochConfig` does; zero is treated as 1.
+    pub fn with_epoch_secs(self, secs: u64) -> Self {
+        Self {
+            epoch_secs: secs.max(1),
+            ..self
+        }
+    }
+
+    /// Keep accepting proofs from the `count` epochs before the newest one
+    /// recorded for a scope/policy, as `EpochConfig` accepts them.
+    pub fn with_previous_epochs(self, count: u64) -> Self {
+        Self {
+            previous_epochs: count,
+            ..self
         }
     }
 
     pub fn from_env() -> Self {
-        let path =
-            env::var(NULLIFIER_DB_ENV).unwrap_or_else(|_| DEFAULT_NULLIFIER_DB_PATH.to_string());
-        Self::persistent(path)
+        let cache_size = env::var(NULLIFIER_CACHE_SIZE_ENV)
+            .ok()
+            .and_then(|value| value.trim().parse().ok())
+            .unwrap_or(DEFAULT_NULLIFIER_CACHE_SIZE);
+        let backend = env::var(NULLIFIER_BACKEND_ENV).unwrap_or_else(|_| "sled".to_string());
+        let store = match backend.as_str() {
+            "sled" => {
+                let path = env::var(NULLIFIER_DB_ENV)
+                    .unwrap_or_else(|_| DEFAULT_NULLIFIER_DB_PATH.to_string());
+                Self::persistent(path)
+            }
+            "log" => {
+                let path = env::var(NULLIFIER_DB_ENV)
+                    .unwrap_or_else(|_| DEFAULT_NULLIFIER_LOG_PATH.to_string());
+                Self::append_log(path)
+            }
+            other => panic!(
+                "unknown {} '{}', expected 'sled' or 'log'",
+                NULLIFIER_BACKEND_ENV, other
+            ),
+        };
+        store.with_cache_size(cache_size)
     }
 
     /// Check if a nullifier has already been spent (non-authoritative).
@@ -2857,19 +6712,82 @@ impl NullifierStore {
     /// This is an optimistic pre-check that can be used to fast-fail before
     /// expensive proof verification. However, due to potential race conditions,
     /// the authoritative check is in `record_atomic` which uses compare-and-swap.
+    ///
+    /// Cached positives are answered without touching the backend.
     fn already_spent(&self, key: &NullifierKey) -> Result<bool, String> {
+        if self.cache.contains(key) {
+            return Ok(true);
+        }
+        let spent = self.spent_in_backend(key)?;
+        if spent {
+            self.cache.insert(key);
+        }
+        Ok(spent)
+    }
+
+    fn spent_in_backend(&self, key: &NullifierKey) -> Result<bool, String> {
         match &*self.backend {
-            NullifierBackend::InMemory(store) => Ok(store
-                .lock()
-                .expect("nullifier store poisoned")
-                .contains(key)),
+            NullifierBackend::InMemory(store) => Ok(recover_lock(store.lock(), "nullifier store")
+                .spent
+                .contains_key(key)),
             NullifierBackend::Persistent(db) => db
                 .contains_key(key.storage_key())
                 .map_err(|_| "nullifier store error".to_string()),
+            NullifierBackend::AppendLog(log) => Ok(recover_lock(log.lock(), "nullifier log")
+                .spent
+                .contains(key)),
         }
     }
 
-    /// Atomically record a nullifier, returning an error if already spent.
+    /// Check if `current_epoch` falls in an epoch older than the newest one
+    /// already accepted for the key's scope/policy, less the accepted previous
+    /// epochs (non-authoritative, like `already_spent`).
+    ///
+    /// Drift tolerance lets a client present an epoch slightly behind the
+    /// server; without this watermark it could step back across an epoch
+    /// boundary and re-derive a nullifier that is no longer marked spent.
+    /// Proofs within one epoch never rewind each other, whatever order they
+    /// arrive in, and neither do proofs from the previous epochs the verifier
+    /// accepts: their nullifiers stay recorded under their own epoch.
+    fn epoch_rewound(&self, key: &NullifierKey, current_epoch: u64) -> Result<bool, String> {
+        Ok(self
+            .epoch_watermark(key)?
+            .is_some_and(|watermark| self.behind_watermark(current_epoch, watermark)))
+    }
+
+    /// Whether `current_epoch` is in an earlier epoch than `watermark` and
+    /// outside the accepted previous epochs.
+    fn behind_watermark(&self, current_epoch: u64, watermark: u64) -> bool {
+        (current_epoch / self.epoch_secs).saturating_add(self.previous_epochs)
+            < watermark / self.epoch_secs
+    }
+
+    /// Start of the epoch `current_epoch` falls in: what a watermark is raised
+    /// to. Watermarks written as raw `current_epoch` values compare the same.
+    fn epoch_start(&self, current_epoch: u64) -> u64 {
+        current_epoch - current_epoch % self.epoch_secs
+    }
+
+    fn epoch_watermark(&self, key: &NullifierKey) -> Result<Option<u64>, String> {
+        match &*self.backend {
+            NullifierBackend::InMemory(store) => Ok(recover_lock(store.lock(), "nullifier store")
+                .epochs
+                .get(&key.scope_policy())
+                .copied()),
+            NullifierBackend::Persistent(db) => db
+                .open_tree(EPOCH_WATERMARK_TREE)
+                .and_then(|tree| tree.get(key.watermark_key()))
+                .map(|value| value.and_then(|bytes| decode_epoch(&bytes)))
+                .map_err(|_| "nullifier store error".to_string()),
+            NullifierBackend::AppendLog(log) => Ok(recover_lock(log.lock(), "nullifier log")
+                .epochs
+                .get(&key.scope_policy())
+                .copied()),
+        }
+    }
+
+    /// Atomically record a nullifier, returning an error if already spent or
+    /// if `current_epoch` is behind the scope/policy's epoch watermark.
     ///
     /// This method uses atomic compare-and-swap to prevent race conditions
     /// where two concurrent requests could both pass the `already_spent` check.
@@ -2885,41 +6803,366 @@ impl NullifierStore {
     ///
     /// If two requests race between steps 1 and 3, only one will succeed
     /// in step 3; the other will get a NULLIFIER_REPLAY error.
-    fn record_atomic(&self, key: NullifierKey) -> Result<(), String> {
+    ///
+    /// Every backend checks the watermark, inserts the key and raises the
+    /// watermark as one step: under one lock in memory and in the log, and in
+    /// one sled transaction over both trees when persistent.
+    ///
+    /// The backend always decides; its answer only feeds the spent-key cache.
+    fn record_atomic(
+        &self,
+        key: NullifierKey,
+        current_epoch: u64,
+        record: NullifierRecord,
+    ) -> Result<(), String> {
+        let result = self.record_in_backend(key.clone(), current_epoch, record);
+        match &result {
+            Ok(()) => self.cache.insert(&key),
+            Err(err) if err == NULLIFIER_SPENT_ERR => self.cache.insert(&key),
+            Err(_) => {}
+        }
+        result
+    }
+
+    fn record_in_backend(
+        &self,
+        key: NullifierKey,
+        current_epoch: u64,
+        record: NullifierRecord,
+    ) -> Result<(), String> {
         match &*self.backend {
             NullifierBackend::InMemory(store) => {
-                let mut guard = store.lock().expect("nullifier store poisoned");
-                // HashSet::insert returns false if the key was already present
-                if !guard.insert(key) {
-                    return Err(NULLIFIER_SPENT_ERR.into());
+                let mut guard = recover_lock(store.lock(), "nullifier store");
+                let scope_policy = key.scope_policy();
+                if guard
+                    .epochs
+                    .get(&scope_policy)
+                    .is_some_and(|watermark| self.behind_watermark(current_epoch, *watermark))
+                {
+                    return Err(EPOCH_REWIND_ERR.into());
+                }
+                match guard.spent.entry(key) {
+                    Entry::Occupied(_) => return Err(NULLIFIER_SPENT_ERR.into()),
+                    Entry::Vacant(slot) => {
+                        slot.insert(record);
+                    }
                 }
+                let watermark = guard.epochs.entry(scope_policy).or_default();
+                *watermark = (*watermark).max(self.epoch_start(current_epoch));
                 Ok(())
             }
             NullifierBackend::Persistent(db) => {
-                // Use compare_and_swap for atomic insert-if-not-exists.
-                // old=None means "only insert if key doesn't exist"
-                // new=Some(&[]) is the value to insert
-                let storage_key = key.storage_key();
-                match db.compare_and_swap(
-                    storage_key,
-                    None::<&[u8]>,  // old: key must not exist
-                    Some(&[] as &[u8]),  // new: insert empty value
-                ) {
-                    Ok(Ok(())) => Ok(()), // Successfully inserted (key didn't exist)
-                    Ok(Err(_)) => {
-                        // CAS failed: key already existed
-                        Err(NULLIFIER_SPENT_ERR.into())
+                let value =
+                    serde_json::to_vec(&record).map_err(|_| "nullifier store error".to_string())?;
+                let watermarks = db
+                    .open_tree(EPOCH_WATERMARK_TREE)
+                    .map_err(|_| "nullifier store error".to_string())?;
+                // The watermark check, the insert-if-absent and the watermark
+                // raise commit together, so concurrent proofs cannot both pass
+                // the watermark across an epoch boundary.
+                let result = (&**db, &watermarks).transaction(|(spent, watermarks)| {
+                    let watermark = watermarks
+                        .get(key.watermark_key())?
+                        .and_then(|bytes| decode_epoch(&bytes));
+                    if watermark
+                        .is_some_and(|watermark| self.behind_watermark(current_epoch, watermark))
+                    {
+                        return transaction::abort(EPOCH_REWIND_ERR);
                     }
-                    Err(err) => {
+                    if spent.get(key.storage_key())?.is_some() {
+                        return transaction::abort(NULLIFIER_SPENT_ERR);
+                    }
+                    spent.insert(&key.storage_key()[..], value.as_slice())?;
+                    let raised = watermark.unwrap_or(0).max(self.epoch_start(current_epoch));
+                    watermarks.insert(&key.watermark_key()[..], &raised.to_be_bytes()[..])?;
+                    Ok(())
+                });
+                match result {
+                    Ok(()) => Ok(()),
+                    Err(TransactionError::Abort(err)) => Err(err.into()),
+                    Err(TransactionError::Storage(err)) => {
                         // Database error - don't expose internal details
-                        eprintln!("nullifier db CAS error: {err}");
+                        eprintln!("nullifier db transaction error: {err}");
+                        Err("nullifier store error".into())
+                    }
+                }
+            }
+            NullifierBackend::AppendLog(log) => {
+                let mut guard = recover_lock(log.lock(), "nullifier log");
+                let scope_policy = key.scope_policy();
+                if guard
+                    .epochs
+                    .get(&scope_policy)
+                    .is_some_and(|watermark| self.behind_watermark(current_epoch, *watermark))
+                {
+                    return Err(EPOCH_REWIND_ERR.into());
+                }
+                // The set insert is the authoritative check; only a new key
+                // reaches the file.
+                if !guard.spent.insert(key.clone()) {
+                    return Err(NULLIFIER_SPENT_ERR.into());
+                }
+                let epoch_start = self.epoch_start(current_epoch);
+                if let Err(err) = guard.append(&key, epoch_start) {
+                    eprintln!("nullifier log append error: {err}");
+                    guard.spent.remove(&key);
+                    return Err("nullifier store error".into());
+                }
+                let watermark = guard.epochs.entry(scope_policy).or_default();
+                *watermark = (*watermark).max(epoch_start);
+                Ok(())
+            }
+        }
+    }
+
+    /// Record every nullifier one proof spends, all-or-nothing, with
+    /// `record_atomic`'s errors.
+    ///
+    /// A single key goes through `record_atomic`. Several keys (an aggregate
+    /// proof's accounts) go through `record_batch_atomic`, so an account
+    /// already spent alone or in another set rejects the whole proof.
+    fn record_all_atomic(
+        &self,
+        mut keys: Vec<NullifierKey>,
+        current_epoch: u64,
+        record: NullifierRecord,
+    ) -> Result<(), String> {
+        if keys.len() == 1 {
+            let key = keys.pop().expect("one key");
+            return self.record_atomic(key, current_epoch, record);
+        }
+        let entries = keys
+            .iter()
+            .map(|key| (key.clone(), current_epoch, record.clone()))
+            .collect();
+        match self.record_batch_atomic(entries)? {
+            Ok(()) => Ok(()),
+            // The keys share one scope/policy, hence one watermark.
+            Err(_) if self.epoch_rewound(&keys[0], current_epoch)? => Err(EPOCH_REWIND_ERR.into()),
+            Err(_) => Err(NULLIFIER_SPENT_ERR.into()),
+        }
+    }
+
+    /// Record several nullifiers all-or-nothing.
+    ///
+    /// Each entry is a key with the epoch and audit record `record_atomic`
+    /// would take. Either every key is recorded, or none is and the inner error
+    /// lists the indices that conflicted: keys already spent, keys repeated
+    /// earlier in the batch, and epochs behind their scope/policy's watermark.
+    /// The outer error is a store failure, after which nothing was recorded.
+    ///
+    /// Entries are checked against the watermark from before the batch, so one
+    /// batch may mix epochs of the same scope/policy.
+    fn record_batch_atomic(
+        &self,
+        entries: Vec<(NullifierKey, u64, NullifierRecord)>,
+    ) -> Result<Result<(), Vec<usize>>, String> {
+        let result = self.record_batch_in_backend(&entries);
+        if let Ok(Ok(())) = &result {
+            for (key, _, _) in &entries {
+                self.cache.insert(key);
+            }
+        }
+        result
+    }
+
+    fn record_batch_in_backend(
+        &self,
+        entries: &[(NullifierKey, u64, NullifierRecord)],
+    ) -> Result<Result<(), Vec<usize>>, String> {
+        match &*self.backend {
+            NullifierBackend::InMemory(store) => {
+                let mut guard = recover_lock(store.lock(), "nullifier store");
+                let conflicts = self.batch_conflicts(entries, |key| {
+                    (
+                        guard.spent.contains_key(key),
+                        guard.epochs.get(&key.scope_policy()).copied(),
+                    )
+                });
+                if !conflicts.is_empty() {
+                    return Ok(Err(conflicts));
+                }
+                for (key, current_epoch, record) in entries {
+                    let watermark = guard.epochs.entry(key.scope_policy()).or_default();
+                    *watermark = (*watermark).max(self.epoch_start(*current_epoch));
+                    guard.spent.insert(key.clone(), record.clone());
+                }
+                Ok(Ok(()))
+            }
+            NullifierBackend::Persistent(db) => {
+                let values = entries
+                    .iter()
+                    .map(|(_, _, record)| serde_json::to_vec(record))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|_| "nullifier store error".to_string())?;
+                let watermarks = db
+                    .open_tree(EPOCH_WATERMARK_TREE)
+                    .map_err(|_| "nullifier store error".to_string())?;
+                let result = (&**db, &watermarks).transaction(|(spent, watermarks)| {
+                    // sled may rerun this closure on a conflict, so it keeps
+                    // no state outside the transaction.
+                    let mut stored = Vec::with_capacity(entries.len());
+                    for (key, _, _) in entries {
+                        let watermark = watermarks.get(key.watermark_key())?;
+                        stored.push((
+                            spent.get(key.storage_key())?.is_some(),
+                            watermark.and_then(|bytes| decode_epoch(&bytes)),
+                        ));
+                    }
+                    let mut stored = stored.into_iter();
+                    let conflicts = self.batch_conflicts(entries, |_| {
+                        stored.next().expect("one stored state per entry")
+                    });
+                    if !conflicts.is_empty() {
+                        return transaction::abort(conflicts);
+                    }
+                    let mut raised: HashMap<[u8; 16], u64> = HashMap::new();
+                    for ((key, current_epoch, _), value) in entries.iter().zip(&values) {
+                        spent.insert(&key.storage_key()[..], value.as_slice())?;
+                        let watermark = raised.entry(key.watermark_key()).or_default();
+                        *watermark = (*watermark).max(self.epoch_start(*current_epoch));
+                    }
+                    for (watermark_key, epoch) in raised {
+                        let current = watermarks
+                            .get(watermark_key)?
+                            .and_then(|bytes| decode_epoch(&bytes))
+                            .unwrap_or(0);
+                        watermarks
+                            .insert(&watermark_key[..], &epoch.max(current).to_be_bytes()[..])?;
+                    }
+                    Ok(())
+                });
+                match result {
+                    Ok(()) => Ok(Ok(())),
+                    Err(TransactionError::Abort(conflicts)) => Ok(Err(conflicts)),
+                    Err(TransactionError::Storage(err)) => {
+                        eprintln!("nullifier db batch error: {err}");
                         Err("nullifier store error".into())
                     }
                 }
             }
+            NullifierBackend::AppendLog(log) => {
+                let mut guard = recover_lock(log.lock(), "nullifier log");
+                let conflicts = self.batch_conflicts(entries, |key| {
+                    (
+                        guard.spent.contains(key),
+                        guard.epochs.get(&key.scope_policy()).copied(),
+                    )
+                });
+                if !conflicts.is_empty() {
+                    return Ok(Err(conflicts));
+                }
+                let appended: Vec<(&NullifierKey, u64)> = entries
+                    .iter()
+                    .map(|(key, current_epoch, _)| (key, self.epoch_start(*current_epoch)))
+                    .collect();
+                if let Err(err) = guard.append_batch(&appended) {
+                    eprintln!("nullifier log append error: {err}");
+                    // Drop whatever part of the batch reached the file.
+                    if let Err(err) = guard.rewrite() {
+                        eprintln!("nullifier log rewrite error: {err}");
+                    }
+                    return Err("nullifier store error".into());
+                }
+                for (key, current_epoch, _) in entries {
+                    guard.spent.insert(key.clone());
+                    let watermark = guard.epochs.entry(key.scope_policy()).or_default();
+                    *watermark = (*watermark).max(self.epoch_start(*current_epoch));
+                }
+                Ok(Ok(()))
+            }
         }
     }
 
+    /// Indices of batch entries that cannot be recorded, given each key's
+    /// stored state as `(spent, epoch watermark)`. `stored` is called once per
+    /// entry, in order.
+    fn batch_conflicts(
+        &self,
+        entries: &[(NullifierKey, u64, NullifierRecord)],
+        mut stored: impl FnMut(&NullifierKey) -> (bool, Option<u64>),
+    ) -> Vec<usize> {
+        let mut seen = HashSet::with_capacity(entries.len());
+        entries
+            .iter()
+            .enumerate()
+            .filter_map(|(index, (key, current_epoch, _))| {
+                let (spent, watermark) = stored(key);
+                let rewound = watermark
+                    .is_some_and(|watermark| self.behind_watermark(*current_epoch, watermark));
+                let repeated = !seen.insert(key);
+                (spent || rewound || repeated).then_some(index)
+            })
+            .collect()
+    }
+
+    /// Release a nullifier recorded by `record_atomic` so the same proof can
+    /// be presented again.
+    ///
+    /// Only for undoing a consumption whose follow-up work failed; the epoch
+    /// watermark is left where it is, so a retry must use the same or a newer
+    /// epoch.
+    ///
+    /// The backend forgets the key before the cache does. Evicting first would
+    /// let a concurrent `already_spent` read the key back from the backend and
+    /// cache it again, leaving it cached as spent after the backend forgot it.
+    fn forget(&self, key: &NullifierKey) -> Result<(), String> {
+        self.forget_in_backend(key)?;
+        self.cache.remove(key);
+        Ok(())
+    }
+
+    fn forget_in_backend(&self, key: &NullifierKey) -> Result<(), String> {
+        match &*self.backend {
+            NullifierBackend::InMemory(store) => {
+                recover_lock(store.lock(), "nullifier store")
+                    .spent
+                    .remove(key);
+                Ok(())
+            }
+            NullifierBackend::Persistent(db) => db
+                .remove(key.storage_key())
+                .map(|_| ())
+                .map_err(|_| "nullifier store error".to_string()),
+            NullifierBackend::AppendLog(log) => {
+                let mut guard = recover_lock(log.lock(), "nullifier log");
+                if !guard.spent.remove(key) {
+                    return Ok(());
+                }
+                guard.rewrite().map_err(|err| {
+                    eprintln!("nullifier log rewrite error: {err}");
+                    "nullifier store error".to_string()
+                })
+            }
+        }
+    }
+
+    /// Whether `key` was consumed by the proof whose bytes hash to
+    /// `proof_hash`. Always false for records without a proof hash, including
+    /// every record of the append-log backend, which keeps no metadata.
+    fn recorded_by(&self, key: &NullifierKey, proof_hash: &str) -> Result<bool, String> {
+        Ok(self
+            .lookup(key)?
+            .is_some_and(|record| !record.proof_hash.is_empty() && record.proof_hash == proof_hash))
+    }
+
+    /// Look up when and where a nullifier was consumed, if it was.
+    fn lookup(&self, key: &NullifierKey) -> Result<Option<NullifierRecord>, String> {
+        match &*self.backend {
+            NullifierBackend::InMemory(store) => Ok(recover_lock(store.lock(), "nullifier store")
+                .spent
+                .get(key)
+                .cloned()),
+            NullifierBackend::Persistent(db) => db
+                .get(key.storage_key())
+                .map(|value| value.map(|bytes| NullifierRecord::from_stored(&bytes)))
+                .map_err(|_| "nullifier store error".to_string()),
+            NullifierBackend::AppendLog(log) => Ok(recover_lock(log.lock(), "nullifier log")
+                .spent
+                .contains(key)
+                .then(NullifierRecord::default)),
+        }
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
@@ -2938,6 +7181,36 @@ impl NullifierKey {
         }
     }
 
+    /// Every nullifier `inputs` spends: `nullifier`, then the aggregated
+    /// accounts' `extra_nullifiers`, all under the same scope/policy.
+    fn all_from_inputs(inputs: &VerifierPublicInputs) -> Vec<Self> {
+        let first = Self::from_inputs(inputs);
+        let extras = inputs
+            .extra_nullifiers
+            .iter()
+            .flatten()
+            .map(|nullifier| Self {
+                nullifier: *nullifier,
+                ..first.clone()
+            });
+        std::iter::once(first.clone()).chain(extras).collect()
+    }
+
+    /// Inverse of `storage_key`; `bytes` must be 48 bytes long.
+    fn from_storage_key(bytes: &[u8]) -> Self {
+        let mut scope_id = [0u8; 8];
+        let mut policy_id = [0u8; 8];
+        let mut nullifier = [0u8; 32];
+        scope_id.copy_from_slice(&bytes[..8]);
+        policy_id.copy_from_slice(&bytes[8..16]);
+        nullifier.copy_from_slice(&bytes[16..48]);
+        Self {
+            scope_id: u64::from_be_bytes(scope_id),
+            policy_id: u64::from_be_bytes(policy_id),
+            nullifier,
+        }
+    }
+
     fn storage_key(&self) -> [u8; 48] {
         let mut buf = [0u8; 48];
         buf[..8].copy_from_slice(&self.scope_id.to_be_bytes());
@@ -2945,5 +7218,744 @@ impl NullifierKey {
         buf[16..].copy_from_slice(&self.nullifier);
         buf
     }
+
+    fn scope_policy(&self) -> (u64, u64) {
+        (self.scope_id, self.policy_id)
+    }
+
+    /// The scope/policy prefix of `storage_key`, keying the epoch watermark.
+    fn watermark_key(&self) -> [u8; 16] {
+        let mut buf = [0u8; 16];
+        buf[..8].copy_from_slice(&self.scope_id.to_be_bytes());
+        buf[8..].copy_from_slice(&self.policy_id.to_be_bytes());
+        buf
+    }
 }
 
+fn decode_epoch(bytes: &[u8]) -> Option<u64> {
+    bytes.try_into().ok().map(u64::from_be_bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(byte: u8) -> NullifierKey {
+        NullifierKey {
+            scope_id: 1,
+            policy_id: 2,
+            nullifier: [byte; 32],
+        }
+    }
+
+    #[test]
+    fn append_log_ignores_truncated_trailing_record() {
+        let dir = env::temp_dir().join(format!("zkpf-nullifier-log-{}", std::process::id()));
+        let path = dir.join("nullifiers.log");
+        let _ = fs::remove_dir_all(&dir);
+        let record = || NullifierRecord::now(DEFAULT_RAIL_ID, 1, "");
+
+        let store = NullifierStore::append_log(&path);
+        store.record_atomic(key(1), 10, record()).unwrap();
+        store.record_atomic(key(2), 10, record()).unwrap();
+        drop(store);
+
+        // Simulate a crash part-way through appending a third key.
+        let mut file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(&key(3).storage_key()[..20]).unwrap();
+        drop(file);
+
+        let store = NullifierStore::append_log(&path);
+        assert!(store.already_spent(&key(1)).unwrap());
+        assert!(store.already_spent(&key(2)).unwrap());
+        assert!(!store.already_spent(&key(3)).unwrap());
+        assert_eq!(
+            store.record_atomic(key(1), 10, record()),
+            Err(NULLIFIER_SPENT_ERR.to_string())
+        );
+        store.record_atomic(key(3), 10, record()).unwrap();
+        drop(store);
+
+        // The partial record was cut off, so the retried key replays cleanly.
+        assert_eq!(
+            fs::metadata(&path).unwrap().len(),
+            3 * AppendLogNullifiers::RECORD_LEN as u64
+        );
+        let store = NullifierStore::append_log(&path);
+        assert!(store.already_spent(&key(3)).unwrap());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn append_log_restores_epoch_watermarks_after_restart() {
+        let dir = env::temp_dir().join(format!("zkpf-nullifier-epochs-{}", std::process::id()));
+        let path = dir.join("nullifiers.log");
+        let _ = fs::remove_dir_all(&dir);
+
+        let store = NullifierStore::append_log(&path);
+        store
+            .record_atomic(key(1), 12, NullifierRecord::default())
+            .unwrap();
+        drop(store);
+
+        let store = NullifierStore::append_log(&path);
+        assert!(store.epoch_rewound(&key(2), 11).unwrap());
+        // Forgetting the only key of the scope/policy keeps its watermark.
+        store.forget(&key(1)).unwrap();
+        drop(store);
+
+        let store = NullifierStore::append_log(&path);
+        assert!(!store.already_spent(&key(1)).unwrap());
+        assert_eq!(
+            store.record_atomic(key(2), 11, NullifierRecord::default()),
+            Err(EPOCH_REWIND_ERR.to_string())
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn batch_record_is_all_or_nothing_on_every_backend() {
+        let dir = env::temp_dir().join(format!("zkpf-nullifier-batch-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let stores = [
+            NullifierStore::in_memory(),
+            NullifierStore::persistent(dir.join("nullifiers.db")),
+            NullifierStore::append_log(dir.join("nullifiers.log")),
+        ];
+        let entry = |byte: u8, epoch: u64| (key(byte), epoch, NullifierRecord::default());
+
+        for store in stores {
+            store
+                .record_atomic(key(1), 10, NullifierRecord::default())
+                .unwrap();
+            let batch = vec![
+                entry(2, 10),
+                entry(1, 10),
+                entry(3, 10),
+                entry(3, 10),
+                entry(4, 9),
+            ];
+            assert_eq!(store.record_batch_atomic(batch), Ok(Err(vec![1, 3, 4])));
+            for byte in 2..=4 {
+                assert!(!store.already_spent(&key(byte)).unwrap());
+            }
+
+            store
+                .record_batch_atomic(vec![entry(2, 10), entry(3, 11)])
+                .unwrap()
+                .unwrap();
+            assert!(store.already_spent(&key(2)).unwrap());
+            assert!(store.already_spent(&key(3)).unwrap());
+            assert!(store.epoch_rewound(&key(4), 10).unwrap());
+        }
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn single_record_checks_and_raises_the_watermark_on_every_backend() {
+        let dir = env::temp_dir().join(format!("zkpf-nullifier-single-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let stores = [
+            NullifierStore::in_memory(),
+            NullifierStore::persistent(dir.join("nullifiers.db")),
+            NullifierStore::append_log(dir.join("nullifiers.log")),
+        ];
+        let record = NullifierRecord::default;
+
+        for store in stores {
+            store.record_atomic(key(1), 10, record()).unwrap();
+            assert_eq!(
+                store.record_atomic(key(1), 11, record()),
+                Err(NULLIFIER_SPENT_ERR.to_string())
+            );
+            assert_eq!(
+                store.record_atomic(key(2), 9, record()),
+                Err(EPOCH_REWIND_ERR.to_string())
+            );
+            assert!(!store.already_spent(&key(2)).unwrap());
+            store.record_atomic(key(2), 12, record()).unwrap();
+            assert!(store.epoch_rewound(&key(3), 11).unwrap());
+        }
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn watermark_compares_whole_epochs_on_every_backend() {
+        let dir = env::temp_dir().join(format!("zkpf-nullifier-epochs-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let stores = [
+            NullifierStore::in_memory(),
+            NullifierStore::persistent(dir.join("nullifiers.db")),
+            NullifierStore::append_log(dir.join("nullifiers.log")),
+        ];
+        let record = NullifierRecord::default;
+        let entry = |byte: u8, epoch: u64| (key(byte), epoch, record());
+
+        for store in stores {
+            let store = store.with_epoch_secs(3600);
+            store.record_atomic(key(1), 7_300, record()).unwrap();
+            // Earlier in the same epoch: another holder's honest late proof.
+            store.record_atomic(key(2), 7_250, record()).unwrap();
+            assert_eq!(
+                store.record_batch_atomic(vec![entry(3, 7_200), entry(4, 7_199)]),
+                Ok(Err(vec![1]))
+            );
+            assert_eq!(
+                store.record_atomic(key(4), 7_199, record()),
+                Err(EPOCH_REWIND_ERR.to_string())
+            );
+            assert_eq!(store.epoch_watermark(&key(5)), Ok(Some(7_200)));
+        }
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn forget_clears_the_backend_and_the_cache() {
+        let dir = env::temp_dir().join(format!("zkpf-nullifier-forget-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let stores = [
+            NullifierStore::in_memory(),
+            NullifierStore::persistent(dir.join("nullifiers.db")),
+            NullifierStore::append_log(dir.join("nullifiers.log")),
+        ];
+
+        for store in stores {
+            store
+                .record_atomic(key(1), 10, NullifierRecord::default())
+                .unwrap();
+            assert!(store.already_spent(&key(1)).unwrap());
+            store.forget(&key(1)).unwrap();
+            assert!(!store.already_spent(&key(1)).unwrap());
+            store
+                .record_atomic(key(1), 10, NullifierRecord::default())
+                .unwrap();
+        }
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn recorded_by_matches_only_the_consuming_proof() {
+        let store = NullifierStore::in_memory();
+        store
+            .record_atomic(key(1), 10, NullifierRecord::now(DEFAULT_RAIL_ID, 1, "aa"))
+            .unwrap();
+        store
+            .record_atomic(key(2), 10, NullifierRecord::now(DEFAULT_RAIL_ID, 1, ""))
+            .unwrap();
+
+        assert!(store.recorded_by(&key(1), "aa").unwrap());
+        assert!(!store.recorded_by(&key(1), "bb").unwrap());
+        assert!(!store.recorded_by(&key(2), "").unwrap());
+        assert!(!store.recorded_by(&key(3), "aa").unwrap());
+    }
+
+    #[test]
+    fn overlapping_aggregate_sets_are_rejected() {
+        let store = NullifierStore::in_memory();
+        let inputs = zkpf_test_fixtures::fixtures().public_inputs().clone();
+        // Spend the accounts whose nullifiers are `[byte; 32]`, the first as
+        // `nullifier` and the rest as `extra_nullifiers`.
+        let spend = |accounts: &[u8]| {
+            let mut set = inputs.clone();
+            set.nullifier = [accounts[0]; 32];
+            set.extra_nullifiers = Some(accounts[1..].iter().map(|byte| [*byte; 32]).collect());
+            store.record_all_atomic(
+                NullifierKey::all_from_inputs(&set),
+                set.current_epoch,
+                NullifierRecord::default(),
+            )
+        };
+
+        assert_eq!(spend(&[1, 2]), Ok(()));
+        assert_eq!(spend(&[1, 3]), Err(NULLIFIER_SPENT_ERR.to_string()));
+        assert_eq!(spend(&[3, 2]), Err(NULLIFIER_SPENT_ERR.to_string()));
+        assert_eq!(spend(&[1]), Err(NULLIFIER_SPENT_ERR.to_string()));
+        // The rejected sets recorded nothing, so their other account is free.
+        assert_eq!(spend(&[3]), Ok(()));
+    }
+
+    #[test]
+    fn policies_are_found_by_exact_criteria_within_a_namespace() {
+        let policy = |policy_id: u64, threshold_raw: u64, namespace: Option<&str>| {
+            serde_json::from_value::<PolicyExpectations>(serde_json::json!({
+                "threshold_raw": threshold_raw,
+                "required_currency_code": 840,
+                "verifier_scope_id": 42,
+                "policy_id": policy_id,
+                "namespace": namespace,
+            }))
+            .unwrap()
+        };
+        let store = PolicyStore::from_policies(vec![
+            policy(1, 100, None),
+            policy(2, 200, None),
+            policy(3, 200, None),
+            policy(4, 100, Some("tenant")),
+        ]);
+
+        assert_eq!(store.find_in(None, 42, 840, 100), [1]);
+        assert_eq!(store.find_in(None, 42, 840, 200), [2, 3]);
+        assert_eq!(store.find_in(Some("tenant"), 42, 840, 100), [4]);
+        assert!(store.find_in(None, 42, 978, 100).is_empty());
+        assert!(store.find_in(None, 43, 840, 100).is_empty());
+    }
+
+    #[test]
+    fn composed_policies_never_take_a_sourced_id() {
+        let policy = |policy_id: u64, namespace: Option<&str>| {
+            serde_json::from_value::<PolicyExpectations>(serde_json::json!({
+                "threshold_raw": 100,
+                "required_currency_code": 840,
+                "verifier_scope_id": 42,
+                "policy_id": policy_id,
+                "namespace": namespace,
+            }))
+            .unwrap()
+        };
+        let store = PolicyStore::from_policies(Vec::new());
+        store.replace_sourced(vec![policy(7, None)]).unwrap();
+
+        let tenant = policy(0, Some("tenant"));
+        let taken = store.insert_composed(tenant.clone(), Some(7));
+        assert_eq!(taken.unwrap_err(), 7);
+        assert_eq!(store.insert_composed(tenant, None).unwrap().policy_id, 8);
+
+        store
+            .replace_sourced(vec![policy(7, None), policy(9, None)])
+            .unwrap();
+        assert_eq!(store.get(7).unwrap().namespace, None);
+        assert_eq!(store.get(8).unwrap().namespace.as_deref(), Some("tenant"));
+        let next = store.insert_composed(policy(0, None), None).unwrap();
+        assert_eq!(next.policy_id, 10);
+    }
+
+    #[test]
+    fn scope_prefix_policy_accepts_sub_scopes() {
+        let mut policy: PolicyExpectations = serde_json::from_value(serde_json::json!({
+            "threshold_raw": 100,
+            "required_currency_code": 840,
+            "verifier_scope_id": 0x7_0000_0001u64,
+            "policy_id": 9,
+        }))
+        .unwrap();
+        let inputs = |scope: u64| VerifierPublicInputs {
+            threshold_raw: 100,
+            required_currency_code: 840,
+            current_epoch: 0,
+            verifier_scope_id: scope,
+            policy_id: 9,
+            nullifier: [0; 32],
+            custodian_pubkey_hash: [0; 32],
+            snapshot_block_height: None,
+            snapshot_anchor_orchard: None,
+            holder_binding: None,
+            threshold_max: None,
+            proven_sum: None,
+            challenge: None,
+            extra_nullifiers: None,
+        };
+
+        assert!(policy.validate_against(&inputs(0x7_0000_0001)).is_ok());
+        assert!(policy.validate_against(&inputs(0x7_0000_0002)).is_err());
+
+        policy.verifier_scope_prefix = Some(0xFFFF_FFFF_0000_0000);
+        assert!(policy.validate_against(&inputs(0x7_0000_0002)).is_ok());
+        assert!(policy.validate_against(&inputs(0x8_0000_0001)).is_err());
+    }
+
+    #[test]
+    fn anchor_allowlist_only_binds_orchard_bundles() {
+        let policy: PolicyExpectations = serde_json::from_value(serde_json::json!({
+            "threshold_raw": 100,
+            "required_currency_code": 999001,
+            "verifier_scope_id": 1,
+            "policy_id": 9,
+            "allowed_anchor_heights": [2_500_000, 2_501_152],
+        }))
+        .unwrap();
+        let inputs = |height: Option<u64>| VerifierPublicInputs {
+            threshold_raw: 100,
+            required_currency_code: 999001,
+            current_epoch: 0,
+            verifier_scope_id: 1,
+            policy_id: 9,
+            nullifier: [0; 32],
+            custodian_pubkey_hash: [0; 32],
+            snapshot_block_height: height,
+            snapshot_anchor_orchard: height.map(|_| [0; 32]),
+            holder_binding: None,
+            threshold_max: None,
+            proven_sum: None,
+            challenge: None,
+            extra_nullifiers: None,
+        };
+        let check = |layout, height| policy.validate_anchor_height(layout, &inputs(height));
+        let orchard = PublicInputLayout::V2Orchard;
+
+        assert!(check(orchard, Some(2_501_152)).is_ok());
+        assert!(check(orchard, Some(2_501_000)).is_err());
+        assert!(check(orchard, None).is_err());
+        assert!(check(PublicInputLayout::V1, None).is_ok());
+    }
+
+    #[test]
+    fn previous_epochs_are_counted_in_whole_epochs() {
+        // 7_250 seconds is 50 seconds into the third hour-long epoch.
+        let config = EpochConfig::fixed(7_250)
+            .with_previous_epochs(1)
+            .with_epoch_secs(3600);
+        assert!(config.within_previous_epochs(7_250));
+        assert!(config.within_previous_epochs(3_600));
+        assert!(!config.within_previous_epochs(3_599));
+        assert!(!config.within_previous_epochs(7_251));
+
+        let counter = EpochConfig::fixed(10).with_previous_epochs(2);
+        assert!(counter.within_previous_epochs(8));
+        assert!(!counter.within_previous_epochs(7));
+        assert!(!EpochConfig::fixed(10).within_previous_epochs(10));
+    }
+
+    #[test]
+    fn previous_epoch_proofs_verify_after_a_current_epoch_proof() {
+        let fixtures = zkpf_test_fixtures::fixtures();
+        let inputs = fixtures.public_inputs().clone();
+        let stated = inputs.current_epoch;
+        let state = AppState::with_components(
+            fixtures.artifacts(),
+            EpochConfig::fixed(stated + 1).with_previous_epochs(1),
+            NullifierStore::in_memory(),
+            PolicyStore::from_policies(Vec::new()),
+            ProviderSessionStore::default(),
+        );
+        let rail = RailVerifier {
+            circuit_version: fixtures.bundle().circuit_version,
+            layout: PublicInputLayout::V1,
+            artifacts: RailArtifacts::Prover(fixtures.artifacts()),
+            manifest_path: None,
+        };
+        let policy: PolicyExpectations = serde_json::from_value(serde_json::json!({
+            "threshold_raw": inputs.threshold_raw,
+            "required_currency_code": inputs.required_currency_code,
+            "verifier_scope_id": inputs.verifier_scope_id,
+            "policy_id": inputs.policy_id,
+        }))
+        .unwrap();
+        // Another holder already proved in the current epoch.
+        let other = NullifierKey {
+            nullifier: [0xAB; 32],
+            ..NullifierKey::from_inputs(&inputs)
+        };
+        state
+            .nullifier_store()
+            .record_atomic(other.clone(), stated + 1, NullifierRecord::default())
+            .unwrap();
+
+        let response = process_verification(
+            &state,
+            DEFAULT_RAIL_ID,
+            &rail,
+            &[policy],
+            &inputs,
+            fixtures.proof(),
+            None,
+            true,
+        )
+        .unwrap();
+        assert!(response.valid, "{:?}", response.error);
+        let own = NullifierKey::from_inputs(&inputs);
+        assert!(state.nullifier_store().already_spent(&own).unwrap());
+        // Two epochs back is outside the window.
+        let store = state.nullifier_store();
+        assert!(store.epoch_rewound(&other, stated - 1).unwrap());
+        assert!(!store.epoch_rewound(&other, stated).unwrap());
+    }
+
+    #[test]
+    fn proof_age_bound_requires_a_fresh_signed_stamp() {
+        let mut policy: PolicyExpectations = serde_json::from_value(serde_json::json!({
+            "threshold_raw": 100,
+            "required_currency_code": 840,
+            "verifier_scope_id": 1,
+            "policy_id": 9,
+        }))
+        .unwrap();
+        let now = 1_700_000_000;
+        assert!(policy.validate_proof_age(None, now).is_ok());
+
+        policy.max_proof_age_secs = Some(300);
+        assert!(policy.validate_proof_age(Some(now - 300), now).is_ok());
+        assert!(policy.validate_proof_age(Some(now + 5), now).is_ok());
+        assert!(policy.validate_proof_age(Some(now - 301), now).is_err());
+        assert!(policy.validate_proof_age(None, now).is_err());
+    }
+
+    #[test]
+    fn ecdsa_check_uses_the_declared_signature_format() {
+        use k256::ecdsa::{
+            signature::{hazmat::PrehashSigner, Signer},
+            SigningKey,
+        };
+
+        let signing_key = SigningKey::from_slice(&[7u8; 32]).unwrap();
+        let point = signing_key.verifying_key().to_encoded_point(false);
+        let pubkey = Secp256k1Pubkey {
+            x: point.x().unwrap().as_slice().try_into().unwrap(),
+            y: point.y().unwrap().as_slice().try_into().unwrap(),
+        };
+        let message_hash = [0x42; 32];
+        let digest = SignatureFormat::Eip191.signed_digest(&message_hash);
+        let sig: K256Signature = signing_key.sign_prehash(&digest).unwrap();
+        let signature = EcdsaSignature {
+            r: sig.r().to_bytes().into(),
+            s: sig.s().to_bytes().into(),
+        };
+
+        let check = |signature: &EcdsaSignature, format| {
+            verify_secp256k1_ecdsa(&pubkey, signature, &message_hash, format)
+        };
+        assert!(check(&signature, SignatureFormat::Eip191).is_ok());
+        assert!(check(&signature, SignatureFormat::RawHash).is_err());
+        assert!(check(&signature, SignatureFormat::Eip712).is_err());
+
+        // Raw-hash signatures cover the SHA-256 of the message hash.
+        let sig: K256Signature = signing_key.sign(&message_hash);
+        let raw = EcdsaSignature {
+            r: sig.r().to_bytes().into(),
+            s: sig.s().to_bytes().into(),
+        };
+        assert!(check(&raw, SignatureFormat::RawHash).is_ok());
+        assert!(check(&raw, SignatureFormat::Eip191).is_err());
+    }
+
+    #[test]
+    fn verify_fields_accept_bytes_or_hex_but_not_both() {
+        let decode = |bytes: Option<Vec<u8>>, hex: Option<&str>| {
+            bytes_or_hex("proof", bytes, hex, 4, ErrorCode::ProofInvalid)
+        };
+        assert_eq!(decode(Some(vec![1, 2]), None).unwrap(), vec![1, 2]);
+        assert_eq!(decode(None, Some("0x0102")).unwrap(), vec![1, 2]);
+        assert!(decode(Some(vec![1, 2]), Some("0102")).is_err());
+        assert!(decode(None, None).is_err());
+        assert!(decode(None, Some("0102030405")).is_err());
+        assert!(decode(None, Some("zz")).is_err());
+    }
+
+    #[test]
+    fn session_purge_removes_only_records_past_retention() {
+        let store = ProviderSessionStore {
+            ttl: Duration::ZERO,
+            retention: Duration::from_secs(60),
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+        };
+        let policy: PolicyExpectations = serde_json::from_value(serde_json::json!({
+            "threshold_raw": 100,
+            "required_currency_code": 840,
+            "verifier_scope_id": 1,
+            "policy_id": 9,
+        }))
+        .unwrap();
+        let retained = store.start_session(policy.clone()).session_id;
+        let stale = store.start_session(policy).session_id;
+        recover_lock(store.sessions.write(), "provider sessions")
+            .get_mut(&stale)
+            .unwrap()
+            .expires_at -= Duration::from_secs(120);
+
+        let expired = store.expired();
+        assert_eq!(expired.len(), 2);
+        assert_eq!(expired[0].session_id, stale);
+        assert_eq!(expired[0].status, ProviderSessionStatus::Pending);
+
+        assert_eq!(store.purge(), 1);
+        assert_eq!(store.purge(), 0);
+        let expired = store.expired();
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].session_id, retained);
+    }
+
+    #[test]
+    fn attestation_history_pages_a_holders_records_newest_first() {
+        let history = AttestationHistory::in_memory();
+        let record = |holder_id: &str, policy_id: u64, tx: u8| AttestationRecord {
+            holder_id: holder_id.to_string(),
+            policy_id,
+            snapshot_id: "snap".to_string(),
+            nullifier: hex::encode([tx; 32]),
+            tx_hash: format!("0x{tx:02x}"),
+            attestation_id: format!("att-{tx}"),
+            chain_id: 1,
+            recorded_at: 1_700_000_000 + u64::from(tx),
+        };
+        for (policy_id, tx) in [(1, 1), (2, 2), (1, 3), (1, 4)] {
+            history
+                .record(None, &record("alice", policy_id, tx))
+                .unwrap();
+        }
+        history.record(None, &record("bob", 1, 5)).unwrap();
+        history
+            .record(Some("tenant"), &record("alice", 1, 6))
+            .unwrap();
+
+        let tx_hashes = |records: Vec<AttestationRecord>| {
+            records
+                .into_iter()
+                .map(|record| record.tx_hash)
+                .collect::<Vec<_>>()
+        };
+        let (page, more) = history.query(None, "alice", None, 0, 2).unwrap();
+        assert_eq!(tx_hashes(page), ["0x04", "0x03"]);
+        assert!(more);
+        let (page, more) = history.query(None, "alice", None, 2, 2).unwrap();
+        assert_eq!(tx_hashes(page), ["0x02", "0x01"]);
+        assert!(!more);
+
+        let (page, _) = history.query(None, "alice", Some(2), 0, 10).unwrap();
+        assert_eq!(tx_hashes(page), ["0x02"]);
+        let (page, _) = history.query(Some("tenant"), "alice", None, 0, 10).unwrap();
+        assert_eq!(tx_hashes(page), ["0x06"]);
+        let (page, _) = history.query(None, "carol", None, 0, 10).unwrap();
+        assert!(page.is_empty());
+    }
+
+    #[test]
+    fn challenge_store_caps_outstanding_challenges() {
+        let ip = |value: &str| value.parse::<IpAddr>().unwrap();
+        let store = ChallengeStore::new(3, 2);
+        store.issue(1, ip("203.0.113.9")).unwrap();
+        let (session_id, _) = store.issue(1, ip("203.0.113.9")).unwrap();
+        assert!(store.issue(1, ip("203.0.113.9")).is_err());
+
+        store.issue(1, ip("198.51.100.1")).unwrap();
+        assert!(store.issue(1, ip("198.51.100.2")).is_err());
+
+        // Redeeming frees a slot for the same client.
+        store.redeem(&session_id).unwrap();
+        store.issue(1, ip("203.0.113.9")).unwrap();
+    }
+
+    #[test]
+    fn client_ip_believes_forwarding_headers_only_from_trusted_proxies() {
+        use axum::{extract::ConnectInfo, http::Request};
+        use client_ip::client_ip;
+
+        let trusted = TrustedProxies::parse("10.0.0.0/8, fdaa::/16").unwrap();
+        let parts = |peer: &str, headers: &[(&str, &str)]| {
+            let mut request =
+                Request::builder().extension(ConnectInfo(peer.parse::<SocketAddr>().unwrap()));
+            for (name, value) in headers {
+                request = request.header(*name, *value);
+            }
+            request.body(()).unwrap().into_parts().0
+        };
+        let ip = |value: &str| value.parse::<std::net::IpAddr>().unwrap();
+
+        let spoofed = [("x-forwarded-for", "198.51.100.1")];
+        assert_eq!(
+            client_ip(&parts("203.0.113.9:4000", &spoofed), &trusted),
+            ip("203.0.113.9")
+        );
+        // The client prepended a fake hop; the walk stops at the first untrusted one.
+        let chain = [("x-forwarded-for", "198.51.100.1, 203.0.113.9, 10.1.2.3")];
+        assert_eq!(
+            client_ip(&parts("10.0.0.1:4000", &chain), &trusted),
+            ip("203.0.113.9")
+        );
+        let forwarded = [
+            ("forwarded", "for=\"[2001:db8::1]:4711\";proto=https"),
+            ("x-forwarded-for", "198.51.100.1"),
+        ];
+        assert_eq!(
+            client_ip(&parts("[fdaa::3]:4000", &forwarded), &trusted),
+            ip("2001:db8::1")
+        );
+        let unknown = [("forwarded", "for=203.0.113.9, for=unknown")];
+        assert_eq!(
+            client_ip(&parts("10.0.0.1:4000", &unknown), &trusted),
+            ip("10.0.0.1")
+        );
+        assert!(TrustedProxies::parse("10.0.0.0/33").is_err());
+    }
+
+    #[tokio::test]
+    async fn challenge_bound_bundles_are_refused_without_a_session() {
+        use axum::{body::Body, http::Request, response::Response};
+        use tower::ServiceExt;
+
+        let fixtures = zkpf_test_fixtures::fixtures();
+        let mut bundle = fixtures.bundle().clone();
+        bundle.public_inputs.challenge = Some([7; 32]);
+        let nullifiers = NullifierStore::in_memory();
+        let state = AppState::with_components(
+            fixtures.artifacts(),
+            EpochConfig::fixed(bundle.public_inputs.current_epoch),
+            nullifiers.clone(),
+            PolicyStore::from_policies(Vec::new()),
+            ProviderSessionStore::default(),
+        )
+        .with_attestation_backend(Arc::new(attestation::NoopAttestationBackend));
+        let router = app_router(state);
+        let error_code = |response: Response| async move {
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            serde_json::from_slice::<serde_json::Value>(&body).unwrap()["error_code"].clone()
+        };
+
+        let boundary = "zkpf-test-boundary";
+        let part = |name: &str, value: &[u8]| {
+            let mut part =
+                format!("--{boundary}\r\nContent-Disposition: form-data; name=\"{name}\"\r\n\r\n")
+                    .into_bytes();
+            part.extend_from_slice(value);
+            part.extend_from_slice(b"\r\n");
+            part
+        };
+        let public_inputs = serde_json::to_string(&bundle.public_inputs).unwrap();
+        let mut form = [
+            part(
+                "policy_id",
+                bundle.public_inputs.policy_id.to_string().as_bytes(),
+            ),
+            part(
+                "circuit_version",
+                bundle.circuit_version.to_string().as_bytes(),
+            ),
+            part("public_inputs", public_inputs.as_bytes()),
+            part("proof", &bundle.proof),
+        ]
+        .concat();
+        form.extend_from_slice(format!("--{boundary}--\r\n").as_bytes());
+        let multipart = Request::post("/zkpf/verify-bundle/multipart")
+            .header(
+                "content-type",
+                format!("multipart/form-data; boundary={boundary}"),
+            )
+            .body(Body::from(form))
+            .unwrap();
+        let response = router.clone().oneshot(multipart).await.unwrap();
+        assert_eq!(error_code(response).await, "CHALLENGE_INVALID");
+
+        let attest = serde_json::json!({
+            "holder_id": "holder",
+            "snapshot_id": "snapshot",
+            "policy_id": bundle.public_inputs.policy_id,
+            "bundle": bundle,
+        });
+        let attest = Request::post("/zkpf/attest")
+            .header("content-type", "application/json")
+            .body(Body::from(attest.to_string()))
+            .unwrap();
+        let response = router.oneshot(attest).await.unwrap();
+        assert_eq!(error_code(response).await, "CHALLENGE_INVALID");
+
+        assert!(!nullifiers
+            .already_spent(&NullifierKey::from_inputs(&bundle.public_inputs))
+            .unwrap());
+    }
+}
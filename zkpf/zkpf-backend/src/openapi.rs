@@ -0,0 +1,107 @@
+//! Machine-readable API description served at `GET /openapi.json`.
+//!
+//! The spec is generated from the `#[utoipa::path]` annotations on the handlers
+//! and the `ToSchema` derives on the request/response types, so it stays in
+//! sync with the code. Errors share the `ErrorResponse` shape; each path lists
+//! the `error_code` values it can return in the response descriptions.
+//!
+//! Types owned by other crates (`ProofBundle`, `ZkpfCircuitInput`) are described
+//! as free-form objects; see the README for their field layout.
+
+use axum::Json;
+use once_cell::sync::Lazy;
+use utoipa::OpenApi;
+
+use crate::{
+    ArtifactPresence, ArtifactUrls, AttestBatchRequest, AttestBatchResponse, AttestRequest,
+    AttestResponse, AttestationRecord, AttestationsResponse, BatchVerifyOutcome, ChallengeRequest,
+    ChallengeResponse, DryRunRequest, DryRunResponse, DryRunStage, EpochProofVerifyResponse,
+    EpochResponse, ErrorResponse, ExpiredSession, InfoResponse, NullifierRecord, ParamsResponse,
+    PoliciesResponse, PolicyComposeRequest, PolicyComposeResponse, PolicyExpectations, RailInfo,
+    RailStatus, RailsResponse, ReadyResponse, ReplayEvent, SessionPurgeResponse, StatusResponse,
+    VerifyBatchRequest, VerifyBundleRequest, VerifyByCriteriaRequest, VerifyRequest,
+    VerifyResponse,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "zkpf backend",
+        description = "Proof-of-funds verification API"
+    ),
+    paths(
+        crate::health_check,
+        crate::readiness_check,
+        crate::list_policies,
+        crate::compose_policy_handler,
+        crate::get_params,
+        crate::get_epoch,
+        crate::issue_challenge_handler,
+        crate::list_rails,
+        crate::status_handler,
+        crate::info_handler,
+        crate::nullifier_audit_handler,
+        crate::replays_handler,
+        crate::purge_sessions_handler,
+        crate::expired_sessions_handler,
+        crate::verify_handler,
+        crate::verify_bundle_handler,
+        crate::verify_by_criteria_handler,
+        crate::verify_bundle_dry_run_handler,
+        crate::verify_bundle_batch_handler,
+        crate::verify_bundle_multipart_handler,
+        crate::epoch_proof_verify_handler,
+        crate::attest_handler,
+        crate::verify_and_attest_handler,
+        crate::attest_batch_handler,
+        crate::attestations_handler,
+        crate::prove_bundle_handler,
+        crate::prove_bundle_preview_handler,
+    ),
+    components(schemas(
+        ArtifactPresence,
+        ArtifactUrls,
+        AttestBatchRequest,
+        AttestBatchResponse,
+        AttestRequest,
+        AttestResponse,
+        AttestationRecord,
+        AttestationsResponse,
+        BatchVerifyOutcome,
+        ChallengeRequest,
+        ChallengeResponse,
+        DryRunRequest,
+        DryRunResponse,
+        DryRunStage,
+        EpochProofVerifyResponse,
+        EpochResponse,
+        ErrorResponse,
+        ExpiredSession,
+        InfoResponse,
+        NullifierRecord,
+        ParamsResponse,
+        PoliciesResponse,
+        PolicyComposeRequest,
+        PolicyComposeResponse,
+        PolicyExpectations,
+        RailInfo,
+        RailStatus,
+        RailsResponse,
+        ReadyResponse,
+        ReplayEvent,
+        SessionPurgeResponse,
+        StatusResponse,
+        VerifyBatchRequest,
+        VerifyBundleRequest,
+        VerifyByCriteriaRequest,
+        VerifyRequest,
+        VerifyResponse,
+    ))
+)]
+struct ApiDoc;
+
+static SPEC: Lazy<utoipa::openapi::OpenApi> = Lazy::new(ApiDoc::openapi);
+
+pub(crate) async fn openapi_json() -> Json<utoipa::openapi::OpenApi> {
+    Json(SPEC.clone())
+}
@@ -43,6 +43,8 @@ use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use sled::Db;
 
+use crate::recover_lock;
+
 // ============================================================================
 // Constants
 // ============================================================================
@@ -307,9 +309,7 @@ impl PersonhoodStore {
 
     /// Get a personhood credential
     pub fn get_credential(&self, personhood_id: &str) -> Option<PersonhoodCredential> {
-        self.credentials_cache
-            .read()
-            .expect("cache poisoned")
+        recover_lock(self.credentials_cache.read(), "credentials cache")
             .get(personhood_id)
             .cloned()
     }
@@ -322,9 +322,7 @@ impl PersonhoodStore {
             .map_err(|e| e.to_string())?;
         
         // Update cache
-        self.credentials_cache
-            .write()
-            .expect("cache poisoned")
+        recover_lock(self.credentials_cache.write(), "credentials cache")
             .insert(credential.personhood_id.clone(), credential);
         
         Ok(())
@@ -332,9 +330,7 @@ impl PersonhoodStore {
 
     /// Get active wallet links for a personhood
     pub fn get_active_links(&self, personhood_id: &str) -> Vec<WalletPersonhoodLink> {
-        self.links_cache
-            .read()
-            .expect("cache poisoned")
+        recover_lock(self.links_cache.read(), "links cache")
             .get(personhood_id)
             .map(|links| {
                 links
@@ -348,7 +344,7 @@ impl PersonhoodStore {
 
     /// Get link by wallet binding ID
     pub fn get_link_by_wallet(&self, wallet_binding_id: &str) -> Option<WalletPersonhoodLink> {
-        let cache = self.links_cache.read().expect("cache poisoned");
+        let cache = recover_lock(self.links_cache.read(), "links cache");
         for links in cache.values() {
             for link in links {
                 if link.wallet_binding_id == wallet_binding_id && link.revoked_at.is_none() {
@@ -361,9 +357,7 @@ impl PersonhoodStore {
 
     /// Check if a wallet-personhood link already exists
     pub fn link_exists(&self, personhood_id: &str, wallet_binding_id: &str) -> bool {
-        self.links_cache
-            .read()
-            .expect("cache poisoned")
+        recover_lock(self.links_cache.read(), "links cache")
             .get(personhood_id)
             .map(|links| {
                 links.iter().any(|l| {
@@ -379,7 +373,7 @@ impl PersonhoodStore {
         
         // Get next ID
         let id = {
-            let mut id_guard = self.next_link_id.write().expect("id counter poisoned");
+            let mut id_guard = recover_lock(self.next_link_id.write(), "link id counter");
             let id = *id_guard;
             *id_guard += 1;
             id
@@ -400,9 +394,7 @@ impl PersonhoodStore {
         tree.insert(key.as_bytes(), value).map_err(|e| e.to_string())?;
 
         // Update cache
-        self.links_cache
-            .write()
-            .expect("cache poisoned")
+        recover_lock(self.links_cache.write(), "links cache")
             .entry(personhood_id.to_string())
             .or_default()
             .push(link.clone());
@@ -0,0 +1,148 @@
+//! Policy sources.
+//!
+//! The backend's policy set comes from one of two places, picked by
+//! `ZKPF_POLICY_SOURCE`:
+//!
+//! - unset or `file`: [`FilePolicySource`] reads the JSON array at
+//!   `ZKPF_POLICY_PATH` (default `config/policies.json`). It is loaded at
+//!   startup and only re-read when `ZKPF_POLICY_REFRESH_SECS` is set.
+//! - `http`: [`HttpPolicySource`] fetches the same JSON array from
+//!   `ZKPF_POLICY_URL`. The store starts with only the policies composed into
+//!   the local file and is filled by the first fetch, then re-fetched every
+//!   `ZKPF_POLICY_REFRESH_SECS` (default 60; 0 fetches once).
+//!
+//! A refresh replaces the sourced policies but keeps the ones composed through
+//! `/zkpf/policies/compose`, which win over a sourced policy with the same id.
+//! A failed or invalid load leaves the current set in place.
+
+use std::{env, fs, path::PathBuf, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+
+use crate::{parse_env_u64, policy_config_path, PolicyExpectations, PolicyStore};
+
+const POLICY_SOURCE_ENV: &str = "ZKPF_POLICY_SOURCE";
+const POLICY_URL_ENV: &str = "ZKPF_POLICY_URL";
+const POLICY_REFRESH_SECS_ENV: &str = "ZKPF_POLICY_REFRESH_SECS";
+const DEFAULT_HTTP_REFRESH_SECS: u64 = 60;
+const POLICY_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Somewhere the full policy set can be loaded from.
+#[async_trait]
+pub trait PolicySource: Send + Sync {
+    /// Load every policy the source currently holds.
+    async fn load(&self) -> Result<Vec<PolicyExpectations>, String>;
+}
+
+/// Policies in a local JSON file.
+#[derive(Clone, Debug)]
+pub struct FilePolicySource {
+    path: PathBuf,
+}
+
+impl FilePolicySource {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[async_trait]
+impl PolicySource for FilePolicySource {
+    async fn load(&self) -> Result<Vec<PolicyExpectations>, String> {
+        let bytes = fs::read(&self.path)
+            .map_err(|err| format!("failed to read {}: {}", self.path.display(), err))?;
+        serde_json::from_slice(&bytes)
+            .map_err(|err| format!("failed to parse {}: {}", self.path.display(), err))
+    }
+}
+
+/// Policies served as a JSON array by an HTTP endpoint.
+#[derive(Clone)]
+pub struct HttpPolicySource {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl HttpPolicySource {
+    pub fn new(url: String) -> Result<Self, String> {
+        let client = reqwest::Client::builder()
+            .timeout(POLICY_REQUEST_TIMEOUT)
+            .build()
+            .map_err(|err| format!("failed to build policy client: {err}"))?;
+        Ok(Self { client, url })
+    }
+}
+
+#[async_trait]
+impl PolicySource for HttpPolicySource {
+    async fn load(&self) -> Result<Vec<PolicyExpectations>, String> {
+        let response = self
+            .client
+            .get(&self.url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|err| err.to_string())?;
+        let body = response.bytes().await.map_err(|err| err.to_string())?;
+        serde_json::from_slice(&body).map_err(|err| format!("invalid policy response: {err}"))
+    }
+}
+
+/// The URL to fetch policies from, if `ZKPF_POLICY_SOURCE=http`.
+///
+/// Panics on an unknown source, or on `http` without `ZKPF_POLICY_URL`, so a
+/// misconfigured deployment fails at startup rather than serving no policies.
+pub(crate) fn http_policy_url() -> Option<String> {
+    let source = env::var(POLICY_SOURCE_ENV).unwrap_or_default();
+    match source.trim().to_ascii_lowercase().as_str() {
+        "" | "file" => None,
+        "http" => {
+            let url = env::var(POLICY_URL_ENV)
+                .ok()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+                .unwrap_or_else(|| panic!("{POLICY_SOURCE_ENV}=http requires {POLICY_URL_ENV}"));
+            Some(url)
+        }
+        other => panic!("unknown {POLICY_SOURCE_ENV} '{other}' (expected 'file' or 'http')"),
+    }
+}
+
+/// Start reloading `store` from the configured source (see the module docs).
+/// Must be called from within a Tokio runtime.
+pub fn spawn_policy_refresh(store: PolicyStore) {
+    let remote = http_policy_url();
+    let (source, default_secs): (Arc<dyn PolicySource>, u64) = match remote.clone() {
+        Some(url) => match HttpPolicySource::new(url) {
+            Ok(source) => (Arc::new(source), DEFAULT_HTTP_REFRESH_SECS),
+            Err(err) => {
+                eprintln!("[PolicySource] policy refresh is disabled: {err}");
+                return;
+            }
+        },
+        None => (Arc::new(FilePolicySource::new(policy_config_path())), 0),
+    };
+    let secs = parse_env_u64(POLICY_REFRESH_SECS_ENV).unwrap_or(default_secs);
+    // The file was loaded at startup; the remote policies are still missing and must
+    // be fetched at least once.
+    if secs == 0 && remote.is_none() {
+        return;
+    }
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(Duration::from_secs(secs.max(1)));
+        loop {
+            ticker.tick().await;
+            let result = source
+                .load()
+                .await
+                .and_then(|policies| store.replace_sourced(policies));
+            if let Err(err) = result {
+                eprintln!("[PolicySource] refresh failed, keeping current policies: {err}");
+            }
+            if secs == 0 {
+                break;
+            }
+        }
+    });
+}
@@ -0,0 +1,142 @@
+//! Outbound webhook for successful on-chain attestations.
+//!
+//! When `ZKPF_ATTEST_WEBHOOK_URL` is set, `attest_handler` POSTs a JSON event to
+//! that URL after each successful attestation. Delivery runs on a background task
+//! so a slow receiver never delays the API response.
+//!
+//! ## Authentication
+//!
+//! The raw request body is signed with HMAC-SHA256 using the shared secret from
+//! `ZKPF_ATTEST_WEBHOOK_SECRET`. The hex digest is sent as
+//! `X-Zkpf-Signature: sha256=<hex>`; receivers should recompute it over the exact
+//! bytes they received and compare in constant time.
+
+use std::{env, time::Duration};
+
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+
+const WEBHOOK_URL_ENV: &str = "ZKPF_ATTEST_WEBHOOK_URL";
+const WEBHOOK_SECRET_ENV: &str = "ZKPF_ATTEST_WEBHOOK_SECRET";
+const SIGNATURE_HEADER: &str = "X-Zkpf-Signature";
+
+/// Per-attempt request timeout.
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+/// Total delivery attempts, including the first.
+const WEBHOOK_MAX_ATTEMPTS: u32 = 3;
+/// Base delay between attempts; doubled after each failure.
+const WEBHOOK_RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Event body delivered to the webhook receiver.
+#[derive(Clone, Debug, Serialize)]
+pub struct AttestWebhookPayload {
+    pub holder_id: String,
+    pub policy_id: u64,
+    pub snapshot_id: String,
+    pub tx_hash: String,
+    pub attestation_id: String,
+    pub chain_id: u64,
+}
+
+#[derive(Clone)]
+pub struct AttestWebhook {
+    url: String,
+    secret: Vec<u8>,
+    client: reqwest::Client,
+}
+
+impl AttestWebhook {
+    /// Build the webhook from the environment.
+    ///
+    /// Returns `None` when no URL is configured. A URL without a secret is
+    /// rejected so events are never delivered unsigned.
+    pub fn from_env() -> Option<Self> {
+        let url = env::var(WEBHOOK_URL_ENV)
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())?;
+
+        let secret = match env::var(WEBHOOK_SECRET_ENV) {
+            Ok(secret) if !secret.is_empty() => secret.into_bytes(),
+            _ => {
+                eprintln!(
+                    "{} is set but {} is missing; attestation webhook disabled",
+                    WEBHOOK_URL_ENV, WEBHOOK_SECRET_ENV
+                );
+                return None;
+            }
+        };
+
+        let client = match reqwest::Client::builder().timeout(WEBHOOK_TIMEOUT).build() {
+            Ok(client) => client,
+            Err(err) => {
+                eprintln!("failed to build attestation webhook client: {}", err);
+                return None;
+            }
+        };
+
+        eprintln!("Attestation webhook enabled: {}", url);
+        Some(Self {
+            url,
+            secret,
+            client,
+        })
+    }
+
+    /// Deliver `payload` in the background. Failures are logged, never surfaced.
+    pub fn notify(&self, payload: AttestWebhookPayload) {
+        let webhook = self.clone();
+        tokio::spawn(async move {
+            if let Err(err) = webhook.deliver(&payload).await {
+                eprintln!(
+                    "attestation webhook delivery failed for attestation_id={}: {}",
+                    payload.attestation_id, err
+                );
+            }
+        });
+    }
+
+    async fn deliver(&self, payload: &AttestWebhookPayload) -> Result<(), String> {
+        let body = serde_json::to_vec(payload).map_err(|err| err.to_string())?;
+        let signature = format!("sha256={}", sign(&self.secret, &body));
+
+        let mut delay = WEBHOOK_RETRY_BASE_DELAY;
+        let mut last_error = String::new();
+        for attempt in 1..=WEBHOOK_MAX_ATTEMPTS {
+            let result = self
+                .client
+                .post(&self.url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(SIGNATURE_HEADER, &signature)
+                .body(body.clone())
+                .send()
+                .await;
+
+            match result {
+                Ok(response) if response.status().is_success() => return Ok(()),
+                Ok(response) => last_error = format!("receiver returned {}", response.status()),
+                Err(err) => last_error = err.to_string(),
+            }
+
+            if attempt < WEBHOOK_MAX_ATTEMPTS {
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+            }
+        }
+
+        Err(format!(
+            "giving up after {} attempts: {}",
+            WEBHOOK_MAX_ATTEMPTS, last_error
+        ))
+    }
+}
+
+/// Hex-encoded HMAC-SHA256 of `body` under `secret`.
+fn sign(secret: &[u8], body: &[u8]) -> String {
+    let mut mac = HmacSha256::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
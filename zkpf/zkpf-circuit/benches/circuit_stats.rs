@@ -0,0 +1,37 @@
+//! Constraint-building cost and cell counts for the custodial circuits.
+//!
+//! Run with `cargo bench -p zkpf-circuit`; the cell counts are printed once
+//! per circuit before timing starts.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use zkpf_circuit::{
+    circuit_stats,
+    ranged::{range_circuit_stats, ZkpfRangeCircuitInput},
+    ZkpfCircuitInput,
+};
+
+fn sample_input() -> ZkpfCircuitInput {
+    serde_json::from_str(include_str!("../src/sample_input.json")).expect("valid sample input")
+}
+
+fn bench_circuit_stats(c: &mut Criterion) {
+    let input = sample_input();
+    let range_input = ZkpfRangeCircuitInput {
+        attestation: input.attestation.clone(),
+        public: input.public.clone(),
+        threshold_max: u64::MAX,
+    };
+
+    println!("custodial: {:?}", circuit_stats(&input));
+    println!("range: {:?}", range_circuit_stats(&range_input));
+
+    c.bench_function("custodial_circuit_stats", |b| {
+        b.iter(|| circuit_stats(&input))
+    });
+    c.bench_function("range_circuit_stats", |b| {
+        b.iter(|| range_circuit_stats(&range_input))
+    });
+}
+
+criterion_group!(benches, bench_circuit_stats);
+criterion_main!(benches);
@@ -0,0 +1,354 @@
+// zkpf/zkpf-circuit/src/aggregate.rs
+// Numan Thabit 2025
+
+//! Multi-account aggregate proof-of-funds circuit.
+//!
+//! Proves that the combined balance of `num_accounts` custodian attestations
+//! meets the public threshold without revealing how it is split. Every
+//! attestation goes through the same checks as the single-account circuit
+//! (validity window, currency, Poseidon message hash), account hashes must be
+//! pairwise distinct, and every account's nullifier is exposed.
+//!
+//! The public inputs reuse the seven-column [`PublicInputs`] shape:
+//! - the nullifier column ([`NULLIFIER_COLUMN`]) has one row per attestation,
+//!   `Poseidon(account_id_hash_i, scope, policy, epoch)`: the same nullifier a
+//!   single-account proof of that account carries. `public.nullifier` is the
+//!   first row and [`ZkpfAggregateCircuitInput::extra_nullifiers`] the rest.
+//! - `custodian_pubkey_hash` is the custodian-set digest `Σ Poseidon(x_i, y_i)`.
+//!
+//! The verifier records each nullifier on its own, so an account counted in one
+//! proof cannot be counted again, alone or in any other set.
+//!
+//! As with [`crate::ZkpfCircuit`], ECDSA signatures are verified outside the
+//! circuit before proving.
+
+use halo2_base::gates::{
+    circuit::builder::BaseCircuitBuilder,
+    circuit::{BaseCircuitParams, BaseConfig, CircuitBuilderStage},
+    GateInstructions, RangeInstructions,
+};
+use halo2_proofs_axiom::{
+    circuit::{Layouter, SimpleFloorPlanner},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use halo2curves_axiom::bn256::Fr;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    assign_u32, assign_u64, expose_public_inputs,
+    gadgets::{
+        attestation::{constrain_attestation, AttestationWitness},
+        nullifier::compute_nullifier,
+    },
+    stats::{collect_stats, CircuitStats},
+    v1_instances, CircuitError, PublicInputs, DEFAULT_ADVICE_PER_PHASE, DEFAULT_CURRENCY_CODE_BITS,
+    DEFAULT_FIXED_COLUMNS, DEFAULT_LOOKUP_ADVICE_PER_PHASE, DEFAULT_LOOKUP_BITS,
+    NUM_INSTANCE_COLUMNS, SAMPLE_INPUT,
+};
+
+/// Upper bound on the number of attestations covered by one aggregate proof.
+pub const MAX_AGGREGATE_ACCOUNTS: usize = 8;
+
+/// Instance column holding the per-account nullifiers, one row per attestation.
+pub const NULLIFIER_COLUMN: usize = 5;
+
+// Sized for MAX_AGGREGATE_ACCOUNTS copies of the single-account constraints.
+const AGGREGATE_K: usize = 16;
+
+// 64-bit balances plus ceil(log2(MAX_AGGREGATE_ACCOUNTS)) bits of carry headroom
+// for the running sum.
+const AGGREGATE_SUM_BITS: usize =
+    64 + (usize::BITS - (MAX_AGGREGATE_ACCOUNTS - 1).leading_zeros()) as usize;
+
+/// Circuit parameters shared by every aggregate circuit, independent of `num_accounts`.
+pub fn aggregate_circuit_params() -> BaseCircuitParams {
+    BaseCircuitParams {
+        k: AGGREGATE_K,
+        num_advice_per_phase: vec![DEFAULT_ADVICE_PER_PHASE],
+        num_fixed: DEFAULT_FIXED_COLUMNS,
+        num_lookup_advice_per_phase: vec![DEFAULT_LOOKUP_ADVICE_PER_PHASE],
+        lookup_bits: Some(DEFAULT_LOOKUP_BITS),
+        num_instance_columns: NUM_INSTANCE_COLUMNS,
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ZkpfAggregateCircuitInput {
+    pub attestations: Vec<AttestationWitness>,
+    /// `nullifier` is the first attestation's nullifier.
+    pub public: PublicInputs,
+    /// Nullifiers of the second and later attestations, in order.
+    #[serde(default)]
+    pub extra_nullifiers: Vec<Fr>,
+}
+
+/// Instances for an aggregate proof: the V1 columns, with `extra_nullifiers`
+/// as further rows of the nullifier column.
+pub fn aggregate_public_instances(public: &PublicInputs, extra_nullifiers: &[Fr]) -> Vec<Vec<Fr>> {
+    let mut cols = v1_instances(public);
+    cols[NULLIFIER_COLUMN].extend_from_slice(extra_nullifiers);
+    cols
+}
+
+/// Aggregate circuit over a fixed number of attestations.
+///
+/// `num_accounts` is part of the circuit shape: proving and verifying keys are
+/// specific to it, so a deployment generates one key pair per supported size.
+#[derive(Clone, Debug)]
+pub struct ZkpfAggregateCircuit {
+    pub input: Option<ZkpfAggregateCircuitInput>,
+    num_accounts: usize,
+    params: BaseCircuitParams,
+    stage: CircuitBuilderStage,
+}
+
+impl ZkpfAggregateCircuit {
+    /// Creates a witness-free circuit for key generation.
+    pub fn keygen(num_accounts: usize) -> Result<Self, CircuitError> {
+        ensure_num_accounts(num_accounts)?;
+        Ok(Self {
+            input: None,
+            num_accounts,
+            params: aggregate_circuit_params(),
+            stage: CircuitBuilderStage::Keygen,
+        })
+    }
+
+    /// Creates a new circuit for MockProver testing.
+    /// Use `new_prover` for production proof generation.
+    pub fn new(
+        num_accounts: usize,
+        input: Option<ZkpfAggregateCircuitInput>,
+    ) -> Result<Self, CircuitError> {
+        ensure_num_accounts(num_accounts)?;
+        let stage = match &input {
+            Some(input) => {
+                ensure_input_len(num_accounts, input)?;
+                CircuitBuilderStage::Mock
+            }
+            None => CircuitBuilderStage::Keygen,
+        };
+        Ok(Self {
+            input,
+            num_accounts,
+            params: aggregate_circuit_params(),
+            stage,
+        })
+    }
+
+    /// Creates a circuit for production proof generation, sized to the input.
+    ///
+    /// Uses the Mock stage for the same reason as [`crate::ZkpfCircuit::new_prover`].
+    pub fn new_prover(input: ZkpfAggregateCircuitInput) -> Result<Self, CircuitError> {
+        let num_accounts = input.attestations.len();
+        ensure_num_accounts(num_accounts)?;
+        ensure_input_len(num_accounts, &input)?;
+        Ok(Self {
+            input: Some(input),
+            num_accounts,
+            params: aggregate_circuit_params(),
+            stage: CircuitBuilderStage::Mock,
+        })
+    }
+
+    pub fn num_accounts(&self) -> usize {
+        self.num_accounts
+    }
+}
+
+/// Cell usage of the aggregate circuit sized to `input`'s attestations.
+pub fn aggregate_circuit_stats(
+    input: &ZkpfAggregateCircuitInput,
+) -> Result<CircuitStats, CircuitError> {
+    ensure_num_accounts(input.attestations.len())?;
+    ensure_input_len(input.attestations.len(), input)?;
+    Ok(collect_stats(&aggregate_circuit_params(), |builder| {
+        build_aggregate_constraints(builder, input)
+    }))
+}
+
+fn ensure_num_accounts(num_accounts: usize) -> Result<(), CircuitError> {
+    if num_accounts == 0 || num_accounts > MAX_AGGREGATE_ACCOUNTS {
+        return Err(CircuitError::Synthesis(format!(
+            "aggregate circuit supports 1..={} accounts, got {}",
+            MAX_AGGREGATE_ACCOUNTS, num_accounts
+        )));
+    }
+    Ok(())
+}
+
+fn ensure_input_len(
+    num_accounts: usize,
+    input: &ZkpfAggregateCircuitInput,
+) -> Result<(), CircuitError> {
+    if input.attestations.len() != num_accounts {
+        return Err(CircuitError::Synthesis(format!(
+            "aggregate circuit sized for {} accounts but input has {} attestations",
+            num_accounts,
+            input.attestations.len()
+        )));
+    }
+    if input.extra_nullifiers.len() + 1 != num_accounts {
+        return Err(CircuitError::Synthesis(format!(
+            "aggregate circuit sized for {} accounts but input has {} extra nullifiers",
+            num_accounts,
+            input.extra_nullifiers.len()
+        )));
+    }
+    Ok(())
+}
+
+impl Circuit<Fr> for ZkpfAggregateCircuit {
+    type Config = BaseConfig<Fr>;
+    type FloorPlanner = SimpleFloorPlanner;
+    type Params = BaseCircuitParams;
+
+    fn params(&self) -> Self::Params {
+        self.params.clone()
+    }
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            input: None,
+            num_accounts: self.num_accounts,
+            params: self.params.clone(),
+            stage: CircuitBuilderStage::Keygen,
+        }
+    }
+
+    fn configure_with_params(
+        meta: &mut ConstraintSystem<Fr>,
+        params: Self::Params,
+    ) -> Self::Config {
+        BaseConfig::configure(meta, params)
+    }
+
+    fn configure(_: &mut ConstraintSystem<Fr>) -> Self::Config {
+        unreachable!("ZkpfAggregateCircuit must be configured with explicit parameters")
+    }
+
+    fn synthesize(&self, config: Self::Config, layouter: impl Layouter<Fr>) -> Result<(), Error> {
+        let sample;
+        let input = match self.input.as_ref() {
+            Some(input) => input,
+            None => {
+                sample = sample_input(self.num_accounts);
+                &sample
+            }
+        };
+
+        let mut builder = BaseCircuitBuilder::<Fr>::from_stage(self.stage)
+            .use_params(self.params.clone())
+            .use_instance_columns(self.params.num_instance_columns);
+
+        if let Some(bits) = self.params.lookup_bits {
+            builder = builder.use_lookup_bits(bits);
+        }
+
+        build_aggregate_constraints(&mut builder, input);
+
+        <BaseCircuitBuilder<Fr> as Circuit<Fr>>::synthesize(&builder, config, layouter)
+    }
+}
+
+/// Keygen placeholder: the single-account sample attestation repeated with
+/// distinct account hashes. Keygen ignores witness values, so the nullifiers
+/// only need the right count.
+fn sample_input(num_accounts: usize) -> ZkpfAggregateCircuitInput {
+    let attestations = (0..num_accounts)
+        .map(|i| {
+            let mut att = SAMPLE_INPUT.attestation.clone();
+            att.account_id_hash += Fr::from(i as u64);
+            att
+        })
+        .collect();
+    ZkpfAggregateCircuitInput {
+        attestations,
+        public: SAMPLE_INPUT.public.clone(),
+        extra_nullifiers: vec![Fr::zero(); num_accounts - 1],
+    }
+}
+
+fn build_aggregate_constraints(
+    builder: &mut BaseCircuitBuilder<Fr>,
+    input: &ZkpfAggregateCircuitInput,
+) {
+    let range = builder.range_chip();
+    let gate = range.gate();
+
+    let pub_in = &input.public;
+
+    let ctx = builder.main(0);
+
+    let threshold = assign_u64(ctx, &range, pub_in.threshold_raw);
+    let req_currency = assign_u32(ctx, &range, pub_in.required_currency_code);
+    let current_epoch = assign_u64(ctx, &range, pub_in.current_epoch);
+    let verifier_scope = assign_u64(ctx, &range, pub_in.verifier_scope_id);
+    let policy_id = assign_u64(ctx, &range, pub_in.policy_id);
+
+    let mut balance_sum = ctx.load_zero();
+    let mut custodian_set_hash = ctx.load_zero();
+    let mut account_hashes = Vec::with_capacity(input.attestations.len());
+    let mut nullifiers = Vec::with_capacity(input.attestations.len());
+
+    for att in &input.attestations {
+        let balance = assign_u64(ctx, &range, att.balance_raw);
+        let cells = constrain_attestation(
+            ctx,
+            &range,
+            att,
+            balance,
+            current_epoch,
+            req_currency,
+            DEFAULT_CURRENCY_CODE_BITS,
+        );
+
+        custodian_set_hash = gate.add(ctx, custodian_set_hash, cells.pubkey_hash);
+
+        balance_sum = gate.add(ctx, balance_sum, balance);
+        account_hashes.push(cells.account_id_hash);
+        nullifiers.push(compute_nullifier(
+            ctx,
+            gate,
+            cells.account_id_hash,
+            verifier_scope,
+            policy_id,
+            current_epoch,
+        ));
+    }
+
+    // The same account may not be counted twice towards the sum.
+    crate::gadgets::compare::enforce_distinct(ctx, gate, &account_hashes);
+
+    let below_threshold = range.is_less_than(ctx, balance_sum, threshold, AGGREGATE_SUM_BITS);
+    gate.assert_is_const(ctx, &below_threshold, &Fr::zero());
+
+    // One nullifier per account, so each account is spent on its own.
+    let claimed = std::iter::once(pub_in.nullifier).chain(input.extra_nullifiers.iter().copied());
+    let public_nullifiers: Vec<_> = nullifiers
+        .iter()
+        .zip(claimed)
+        .map(|(computed, claimed)| {
+            let public_nullifier = ctx.load_witness(claimed);
+            ctx.constrain_equal(computed, &public_nullifier);
+            public_nullifier
+        })
+        .collect();
+
+    let public_custodian_set_hash = ctx.load_witness(pub_in.custodian_pubkey_hash);
+    ctx.constrain_equal(&custodian_set_hash, &public_custodian_set_hash);
+
+    expose_public_inputs(
+        builder,
+        [
+            threshold,
+            req_currency,
+            current_epoch,
+            verifier_scope,
+            policy_id,
+            public_nullifiers[0],
+            public_custodian_set_hash,
+        ],
+    );
+    builder.assigned_instances[NULLIFIER_COLUMN].extend_from_slice(&public_nullifiers[1..]);
+}
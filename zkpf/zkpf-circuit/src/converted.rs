@@ -0,0 +1,255 @@
+// zkpf/zkpf-circuit/src/converted.rs
+// Numan Thabit 2025
+
+//! Cross-currency proof-of-funds circuit.
+//!
+//! Same checks as [`crate::ZkpfCircuit`], except the balance is converted with a
+//! public fixed-point exchange rate before it is compared against the
+//! threshold. This supports statements like "the USD value of my ZEC is at
+//! least $10k":
+//! - `required_currency_code` is the currency the attestation is denominated in.
+//! - `threshold_raw` is denominated in the rate's quote currency.
+//!
+//! The rate (`numerator / denominator`) and the oracle timestamp it was taken
+//! at (`rate_epoch`) are appended as public inputs after the V1 columns, so a
+//! verifier can check them against its own oracle. `rate_epoch` must not be
+//! later than `current_epoch`.
+
+use halo2_base::{
+    gates::{
+        circuit::builder::BaseCircuitBuilder,
+        circuit::{BaseCircuitParams, BaseConfig, CircuitBuilderStage},
+        RangeInstructions,
+    },
+    AssignedValue,
+};
+use halo2_proofs_axiom::{
+    circuit::{Layouter, SimpleFloorPlanner},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use halo2curves_axiom::bn256::Fr;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    assign_u32, assign_u64, default_params,
+    gadgets::attestation::{bind_single_attestation, constrain_attestation, AttestationWitness},
+    stats::{collect_stats, CircuitStats},
+    v1_instances, PublicInputs, DEFAULT_CURRENCY_CODE_BITS, NUM_INSTANCE_COLUMNS, SAMPLE_INPUT,
+};
+
+/// Instance columns: the V1 columns followed by rate numerator, denominator and epoch.
+pub const NUM_CONVERTED_INSTANCE_COLUMNS: usize = NUM_INSTANCE_COLUMNS + 3;
+
+/// Public fixed-point exchange rate: one unit of the attested currency is worth
+/// `numerator / denominator` units of the threshold currency.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConversionRate {
+    pub numerator: u64,
+    pub denominator: u64,
+    /// Oracle timestamp the rate was observed at.
+    pub rate_epoch: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ZkpfConvertedCircuitInput {
+    pub attestation: AttestationWitness,
+    pub public: PublicInputs,
+    pub rate: ConversionRate,
+}
+
+#[derive(Clone, Debug)]
+pub struct ZkpfConvertedCircuit {
+    pub input: Option<ZkpfConvertedCircuitInput>,
+    params: BaseCircuitParams,
+    stage: CircuitBuilderStage,
+}
+
+impl Default for ZkpfConvertedCircuit {
+    fn default() -> Self {
+        Self {
+            input: None,
+            params: converted_params(),
+            stage: CircuitBuilderStage::Keygen,
+        }
+    }
+}
+
+impl ZkpfConvertedCircuit {
+    /// Creates a new circuit for MockProver testing.
+    /// Use `new_prover` for production proof generation.
+    pub fn new(input: Option<ZkpfConvertedCircuitInput>) -> Self {
+        let stage = if input.is_some() {
+            CircuitBuilderStage::Mock
+        } else {
+            CircuitBuilderStage::Keygen
+        };
+        Self {
+            input,
+            params: converted_params(),
+            stage,
+        }
+    }
+
+    /// Creates a circuit for production proof generation.
+    ///
+    /// Uses the Mock stage for the same reason as [`crate::ZkpfCircuit::new_prover`].
+    pub fn new_prover(input: ZkpfConvertedCircuitInput) -> Self {
+        Self {
+            input: Some(input),
+            params: converted_params(),
+            stage: CircuitBuilderStage::Mock,
+        }
+    }
+}
+
+fn converted_params() -> BaseCircuitParams {
+    BaseCircuitParams {
+        num_instance_columns: NUM_CONVERTED_INSTANCE_COLUMNS,
+        ..default_params()
+    }
+}
+
+pub fn converted_public_instances(public: &PublicInputs, rate: &ConversionRate) -> Vec<Vec<Fr>> {
+    let mut cols = v1_instances(public);
+    cols.push(vec![Fr::from(rate.numerator)]);
+    cols.push(vec![Fr::from(rate.denominator)]);
+    cols.push(vec![Fr::from(rate.rate_epoch)]);
+    cols
+}
+
+/// Cell usage of the converted-threshold circuit.
+pub fn converted_circuit_stats(input: &ZkpfConvertedCircuitInput) -> CircuitStats {
+    collect_stats(&converted_params(), |builder| {
+        build_converted_constraints(builder, input)
+    })
+}
+
+impl Circuit<Fr> for ZkpfConvertedCircuit {
+    type Config = BaseConfig<Fr>;
+    type FloorPlanner = SimpleFloorPlanner;
+    type Params = BaseCircuitParams;
+
+    fn params(&self) -> Self::Params {
+        self.params.clone()
+    }
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            input: None,
+            params: self.params.clone(),
+            stage: CircuitBuilderStage::Keygen,
+        }
+    }
+
+    fn configure_with_params(
+        meta: &mut ConstraintSystem<Fr>,
+        params: Self::Params,
+    ) -> Self::Config {
+        BaseConfig::configure(meta, params)
+    }
+
+    fn configure(_: &mut ConstraintSystem<Fr>) -> Self::Config {
+        unreachable!("ZkpfConvertedCircuit must be configured with explicit parameters")
+    }
+
+    fn synthesize(&self, config: Self::Config, layouter: impl Layouter<Fr>) -> Result<(), Error> {
+        let sample;
+        let input = match self.input.as_ref() {
+            Some(input) => input,
+            None => {
+                sample = sample_input();
+                &sample
+            }
+        };
+
+        let mut builder = BaseCircuitBuilder::<Fr>::from_stage(self.stage)
+            .use_params(self.params.clone())
+            .use_instance_columns(self.params.num_instance_columns);
+
+        if let Some(bits) = self.params.lookup_bits {
+            builder = builder.use_lookup_bits(bits);
+        }
+
+        build_converted_constraints(&mut builder, input);
+
+        <BaseCircuitBuilder<Fr> as Circuit<Fr>>::synthesize(&builder, config, layouter)
+    }
+}
+
+/// Keygen placeholder: the single-account sample input at a 1:1 rate.
+fn sample_input() -> ZkpfConvertedCircuitInput {
+    ZkpfConvertedCircuitInput {
+        attestation: SAMPLE_INPUT.attestation.clone(),
+        public: SAMPLE_INPUT.public.clone(),
+        rate: ConversionRate {
+            numerator: 1,
+            denominator: 1,
+            rate_epoch: SAMPLE_INPUT.public.current_epoch,
+        },
+    }
+}
+
+fn build_converted_constraints(
+    builder: &mut BaseCircuitBuilder<Fr>,
+    input: &ZkpfConvertedCircuitInput,
+) {
+    let range = builder.range_chip();
+    let gate = range.gate();
+
+    let att = &input.attestation;
+    let pub_in = &input.public;
+
+    let ctx = builder.main(0);
+
+    let balance = assign_u64(ctx, &range, att.balance_raw);
+    let threshold = assign_u64(ctx, &range, pub_in.threshold_raw);
+    let req_currency = assign_u32(ctx, &range, pub_in.required_currency_code);
+    let current_epoch = assign_u64(ctx, &range, pub_in.current_epoch);
+    let verifier_scope = assign_u64(ctx, &range, pub_in.verifier_scope_id);
+    let policy_id = assign_u64(ctx, &range, pub_in.policy_id);
+    let rate_num = assign_u64(ctx, &range, input.rate.numerator);
+    let rate_den = assign_u64(ctx, &range, input.rate.denominator);
+    let rate_epoch = assign_u64(ctx, &range, input.rate.rate_epoch);
+
+    let cells = constrain_attestation(
+        ctx,
+        &range,
+        att,
+        balance,
+        current_epoch,
+        req_currency,
+        DEFAULT_CURRENCY_CODE_BITS,
+    );
+
+    crate::gadgets::compare::enforce_leq(ctx, gate, &range, rate_epoch, current_epoch);
+
+    crate::gadgets::convert::enforce_converted_geq(
+        ctx, gate, &range, balance, rate_num, rate_den, threshold,
+    );
+
+    let (public_nullifier, public_pubkey_hash) = bind_single_attestation(
+        ctx,
+        gate,
+        &cells,
+        verifier_scope,
+        policy_id,
+        current_epoch,
+        pub_in,
+    );
+
+    let values: [AssignedValue<Fr>; NUM_CONVERTED_INSTANCE_COLUMNS] = [
+        threshold,
+        req_currency,
+        current_epoch,
+        verifier_scope,
+        policy_id,
+        public_nullifier,
+        public_pubkey_hash,
+        rate_num,
+        rate_den,
+        rate_epoch,
+    ];
+    for (idx, value) in values.into_iter().enumerate() {
+        builder.assigned_instances[idx].push(value);
+    }
+}
@@ -1,8 +1,25 @@
 // zkpf/zkpf-circuit/src/gadgets/attestation.rs
 // Numan Thabit 2025
 
+use halo2_base::{
+    gates::{
+        flex_gate::GateChip,
+        range::{RangeChip, RangeInstructions},
+    },
+    AssignedValue, Context,
+};
 use halo2curves_axiom::bn256::Fr;
 use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+
+use crate::{
+    assign_pubkey_coords, assign_u32, assign_u64, fr_from_be_bytes,
+    gadgets::{
+        compare::enforce_leq, nullifier::compute_nullifier, policy::enforce_currency,
+        poseidon::hash_attestation,
+    },
+    hash_pubkey_coords, PublicInputs,
+};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct EcdsaSignature {
@@ -16,17 +33,189 @@ pub struct Secp256k1Pubkey {
     pub y: [u8; 32],
 }
 
+/// How the custodian signed `message_hash`.
+///
+/// Whatever the format, `message_hash` is the Poseidon digest the circuit
+/// recomputes; the format only decides which 32-byte digest the ECDSA
+/// signature is over (see [`SignatureFormat::signed_digest`]).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignatureFormat {
+    /// ECDSA over `message_hash` itself.
+    #[default]
+    RawHash,
+    /// EIP-191 `personal_sign` of the 32 `message_hash` bytes.
+    Eip191,
+    /// EIP-712 typed data `ZkpfAttestation(bytes32 messageHash)` under the
+    /// domain `{ name: "zkpf", version: "1" }`.
+    Eip712,
+}
+
+impl SignatureFormat {
+    pub fn is_raw_hash(&self) -> bool {
+        *self == Self::RawHash
+    }
+
+    /// The digest the signature must verify against, as a prehash.
+    pub fn signed_digest(self, message_hash: &[u8; 32]) -> [u8; 32] {
+        match self {
+            Self::RawHash => *message_hash,
+            Self::Eip191 => Keccak256::new()
+                .chain_update(b"\x19Ethereum Signed Message:\n32")
+                .chain_update(message_hash)
+                .finalize()
+                .into(),
+            Self::Eip712 => {
+                let domain_separator = Keccak256::new()
+                    .chain_update(Keccak256::digest(
+                        b"EIP712Domain(string name,string version)",
+                    ))
+                    .chain_update(Keccak256::digest(b"zkpf"))
+                    .chain_update(Keccak256::digest(b"1"))
+                    .finalize();
+                let struct_hash = Keccak256::new()
+                    .chain_update(Keccak256::digest(b"ZkpfAttestation(bytes32 messageHash)"))
+                    .chain_update(message_hash)
+                    .finalize();
+                Keccak256::new()
+                    .chain_update(b"\x19\x01")
+                    .chain_update(domain_separator)
+                    .chain_update(struct_hash)
+                    .finalize()
+                    .into()
+            }
+        }
+    }
+}
+
+/// Attestation fields as witnessed by the circuit.
+///
+/// JSON keys are pinned with explicit renames to match `zkpf_common::Attestation`.
+/// `message_hash` commits to the fields in the order absorbed by
+/// [`crate::gadgets::poseidon::hash_attestation`]: `balance_raw`,
+/// `attestation_id`, `currency_code_int`, `custodian_id`, `issued_at`,
+/// `valid_until`, `account_id_hash`.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct AttestationWitness {
+    #[serde(rename = "balance_raw")]
     pub balance_raw: u64,
+    #[serde(rename = "currency_code_int")]
     pub currency_code_int: u32,
+    #[serde(rename = "custodian_id")]
     pub custodian_id: u32,
+    #[serde(rename = "attestation_id")]
     pub attestation_id: u64,
+    #[serde(rename = "issued_at")]
     pub issued_at: u64,
+    #[serde(rename = "valid_until")]
     pub valid_until: u64,
+    #[serde(rename = "account_id_hash")]
     pub account_id_hash: Fr,
+    #[serde(rename = "custodian_pubkey")]
     pub custodian_pubkey: Secp256k1Pubkey,
+    #[serde(rename = "signature")]
     pub signature: EcdsaSignature,
     /// Poseidon(attestation_fields) encoded as 32-byte big-endian digest for ECDSA.
+    #[serde(rename = "message_hash")]
     pub message_hash: [u8; 32],
+    /// Absent in JSON means [`SignatureFormat::RawHash`], which is also left
+    /// out when serializing so existing attestation JSON is unchanged.
+    #[serde(
+        rename = "signature_format",
+        default,
+        skip_serializing_if = "SignatureFormat::is_raw_hash"
+    )]
+    pub signature_format: SignatureFormat,
+}
+
+/// Attestation cells a circuit still needs once [`constrain_attestation`] has
+/// checked them.
+pub struct AttestationCells {
+    pub custodian: AssignedValue<Fr>,
+    pub account_id_hash: AssignedValue<Fr>,
+    /// `Poseidon(x, y)` over the custodian key coordinates.
+    pub pubkey_hash: AssignedValue<Fr>,
+}
+
+/// Constrains one attestation the way every circuit does: the currency code
+/// fits in `currency_code_bits` and matches `req_currency`,
+/// `issued_at <= current_epoch <= valid_until`, and `message_hash` is the
+/// Poseidon digest of the fields.
+///
+/// `balance` is assigned by the caller, which also decides how it is compared
+/// to the threshold.
+pub fn constrain_attestation(
+    ctx: &mut Context<Fr>,
+    range: &RangeChip<Fr>,
+    att: &AttestationWitness,
+    balance: AssignedValue<Fr>,
+    current_epoch: AssignedValue<Fr>,
+    req_currency: AssignedValue<Fr>,
+    currency_code_bits: usize,
+) -> AttestationCells {
+    let gate = range.gate();
+
+    let currency = ctx.load_witness(Fr::from(att.currency_code_int as u64));
+    range.range_check(ctx, currency, currency_code_bits);
+    let custodian = assign_u32(ctx, range, att.custodian_id);
+    let attestation_id = assign_u64(ctx, range, att.attestation_id);
+    let issued_at = assign_u64(ctx, range, att.issued_at);
+    let valid_until = assign_u64(ctx, range, att.valid_until);
+    let account_id_hash = ctx.load_witness(att.account_id_hash);
+
+    enforce_leq(ctx, gate, range, issued_at, current_epoch);
+    enforce_leq(ctx, gate, range, current_epoch, valid_until);
+
+    enforce_currency(ctx, gate, currency, req_currency);
+
+    let digest_fr = hash_attestation(
+        ctx,
+        gate,
+        balance,
+        attestation_id,
+        currency,
+        custodian,
+        issued_at,
+        valid_until,
+        account_id_hash,
+    );
+    let digest_from_bytes = fr_from_be_bytes(ctx, gate, range, &att.message_hash);
+    ctx.constrain_equal(&digest_fr, &digest_from_bytes);
+
+    let (pubkey_x, pubkey_y) = assign_pubkey_coords(ctx, gate, range, &att.custodian_pubkey);
+    let pubkey_hash = hash_pubkey_coords(ctx, gate, pubkey_x, pubkey_y);
+
+    AttestationCells {
+        custodian,
+        account_id_hash,
+        pubkey_hash,
+    }
+}
+
+/// Binds a single-attestation proof to its public nullifier and custodian key
+/// hash, returning the `(nullifier, custodian_pubkey_hash)` instance cells.
+pub fn bind_single_attestation(
+    ctx: &mut Context<Fr>,
+    gate: &GateChip<Fr>,
+    cells: &AttestationCells,
+    verifier_scope: AssignedValue<Fr>,
+    policy_id: AssignedValue<Fr>,
+    current_epoch: AssignedValue<Fr>,
+    public: &PublicInputs,
+) -> (AssignedValue<Fr>, AssignedValue<Fr>) {
+    let computed_nullifier = compute_nullifier(
+        ctx,
+        gate,
+        cells.account_id_hash,
+        verifier_scope,
+        policy_id,
+        current_epoch,
+    );
+    let public_nullifier = ctx.load_witness(public.nullifier);
+    ctx.constrain_equal(&computed_nullifier, &public_nullifier);
+
+    let public_pubkey_hash = ctx.load_witness(public.custodian_pubkey_hash);
+    ctx.constrain_equal(&cells.pubkey_hash, &public_pubkey_hash);
+
+    (public_nullifier, public_pubkey_hash)
 }
@@ -0,0 +1,201 @@
+// zkpf/zkpf-circuit/src/gadgets/blocklist.rs
+// Numan Thabit 2025
+
+//! Sorted-Merkle non-membership proofs.
+//!
+//! A blocklist is committed to as a Poseidon Merkle tree of depth
+//! [`BLOCKLIST_TREE_DEPTH`] whose leaves are the blocked values in ascending
+//! order. Absence of a value `x` is shown by opening two *adjacent* leaves
+//! `low` and `high` with `low < x < high`: since the leaves are sorted and
+//! nothing sits between two neighbours, `x` cannot be one of them.
+//!
+//! The tree is padded with sentinels (`0` at the front, `p - 1` at the back) so
+//! every other field element has a bracketing pair. Soundness relies on the
+//! published root being the root of a sorted list, which is the publisher's
+//! responsibility.
+
+use halo2_base::{
+    gates::{
+        flex_gate::{GateChip, GateInstructions},
+        range::{RangeChip, RangeInstructions},
+    },
+    AssignedValue, Context,
+    QuantumCell::{self, Constant},
+};
+use halo2curves_axiom::{bn256::Fr, ff::PrimeField};
+use serde::{Deserialize, Serialize};
+
+use crate::{gadgets::poseidon::hash_elements, CircuitError};
+
+/// Depth of the blocklist Merkle tree (up to 2^16 leaves, sentinels included).
+pub const BLOCKLIST_TREE_DEPTH: usize = 16;
+
+// BN256 scalar field modulus split into 128-bit limbs, used to force a
+// canonical limb decomposition.
+const MODULUS_HI: u128 = 0x30644e72e131a029b85045b68181585d;
+const MODULUS_LO: u128 = 0x2833e84879b9709143e1f593f0000001;
+
+/// Authentication path for one leaf. `siblings[0]` is the leaf's neighbour.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MerklePathWitness {
+    pub index: u64,
+    pub siblings: Vec<Fr>,
+}
+
+impl MerklePathWitness {
+    /// Check the path has one sibling per level and an index inside the tree.
+    pub fn validate(&self, name: &str) -> Result<(), CircuitError> {
+        if self.siblings.len() != BLOCKLIST_TREE_DEPTH {
+            return Err(CircuitError::Synthesis(format!(
+                "blocklist {} must have {} siblings, got {}",
+                name,
+                BLOCKLIST_TREE_DEPTH,
+                self.siblings.len()
+            )));
+        }
+        if self.index >> BLOCKLIST_TREE_DEPTH != 0 {
+            return Err(CircuitError::Synthesis(format!(
+                "blocklist {} index {} is outside a depth-{} tree",
+                name, self.index, BLOCKLIST_TREE_DEPTH
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Two adjacent leaves of the sorted blocklist that bracket the screened value.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NonMembershipWitness {
+    pub low_leaf: Fr,
+    pub low_path: MerklePathWitness,
+    pub high_leaf: Fr,
+    pub high_path: MerklePathWitness,
+}
+
+impl NonMembershipWitness {
+    /// Check both paths fit the tree; [`enforce_non_membership`] expects a
+    /// validated witness.
+    pub fn validate(&self) -> Result<(), CircuitError> {
+        self.low_path.validate("low_path")?;
+        self.high_path.validate("high_path")
+    }
+}
+
+/// Enforce that `value` is not a leaf of the sorted tree committed to by `root`.
+///
+/// `witness` must pass [`NonMembershipWitness::validate`].
+pub fn enforce_non_membership(
+    ctx: &mut Context<Fr>,
+    gate: &GateChip<Fr>,
+    range: &RangeChip<Fr>,
+    value: AssignedValue<Fr>,
+    root: AssignedValue<Fr>,
+    witness: &NonMembershipWitness,
+) {
+    let low_leaf = ctx.load_witness(witness.low_leaf);
+    let high_leaf = ctx.load_witness(witness.high_leaf);
+
+    let (low_root, low_index) = merkle_root(ctx, gate, low_leaf, &witness.low_path);
+    let (high_root, high_index) = merkle_root(ctx, gate, high_leaf, &witness.high_path);
+    ctx.constrain_equal(&low_root, &root);
+    ctx.constrain_equal(&high_root, &root);
+
+    // The two leaves must be neighbours.
+    let next_index = gate.add(ctx, low_index, Constant(Fr::one()));
+    ctx.constrain_equal(&next_index, &high_index);
+
+    let low_limbs = split_canonical(ctx, gate, range, low_leaf);
+    let value_limbs = split_canonical(ctx, gate, range, value);
+    let high_limbs = split_canonical(ctx, gate, range, high_leaf);
+
+    let above_low = limbs_less_than(ctx, gate, range, low_limbs, value_limbs);
+    gate.assert_is_const(ctx, &above_low, &Fr::one());
+    let below_high = limbs_less_than(ctx, gate, range, value_limbs, high_limbs);
+    gate.assert_is_const(ctx, &below_high, &Fr::one());
+}
+
+/// Recompute the Merkle root for `leaf`, returning it with the assigned leaf index.
+///
+/// `path` has been validated, so it has one sibling per level.
+fn merkle_root(
+    ctx: &mut Context<Fr>,
+    gate: &GateChip<Fr>,
+    leaf: AssignedValue<Fr>,
+    path: &MerklePathWitness,
+) -> (AssignedValue<Fr>, AssignedValue<Fr>) {
+    let mut node = leaf;
+    let mut index = ctx.load_zero();
+    for (level, sibling) in path.siblings.iter().enumerate() {
+        let bit = ctx.load_witness(Fr::from((path.index >> level) & 1));
+        gate.assert_bit(ctx, bit);
+        let sibling = ctx.load_witness(*sibling);
+
+        // bit = 1 means the current node is the right child.
+        let left = gate.select(ctx, sibling, node, bit);
+        let right = gate.select(ctx, node, sibling, bit);
+        node = hash_elements(ctx, gate, &[left, right]);
+
+        index = gate.mul_add(ctx, bit, Constant(Fr::from(1u64 << level)), index);
+    }
+    (node, index)
+}
+
+/// Split `x` into `(hi, lo)` with `x = hi * 2^128 + lo` and `(hi, lo) < p`.
+///
+/// The bound on the limbs rules out the second decomposition of `x + p`, so
+/// the limbs order field elements the same way as their integer values.
+fn split_canonical(
+    ctx: &mut Context<Fr>,
+    gate: &GateChip<Fr>,
+    range: &RangeChip<Fr>,
+    x: AssignedValue<Fr>,
+) -> (AssignedValue<Fr>, AssignedValue<Fr>) {
+    let repr = x.value().to_repr();
+    let bytes = repr.as_ref();
+    let lo_val = u128::from_le_bytes(bytes[..16].try_into().expect("16 bytes"));
+    let hi_val = u128::from_le_bytes(bytes[16..].try_into().expect("16 bytes"));
+
+    let lo = ctx.load_witness(Fr::from_u128(lo_val));
+    let hi = ctx.load_witness(Fr::from_u128(hi_val));
+    range.range_check(ctx, lo, 128);
+    range.range_check(ctx, hi, 126);
+
+    let recomposed = gate.mul_add(ctx, hi, Constant(two_pow_128()), lo);
+    ctx.constrain_equal(&recomposed, &x);
+
+    let canonical = limbs_less_than(
+        ctx,
+        gate,
+        range,
+        (hi, lo),
+        (
+            Constant(Fr::from_u128(MODULUS_HI)),
+            Constant(Fr::from_u128(MODULUS_LO)),
+        ),
+    );
+    gate.assert_is_const(ctx, &canonical, &Fr::one());
+
+    (hi, lo)
+}
+
+/// Lexicographic `(a_hi, a_lo) < (b_hi, b_lo)` over 128-bit limbs.
+fn limbs_less_than(
+    ctx: &mut Context<Fr>,
+    gate: &GateChip<Fr>,
+    range: &RangeChip<Fr>,
+    a: (impl Into<QuantumCell<Fr>>, impl Into<QuantumCell<Fr>>),
+    b: (impl Into<QuantumCell<Fr>>, impl Into<QuantumCell<Fr>>),
+) -> AssignedValue<Fr> {
+    let (a_hi, a_lo) = (a.0.into(), a.1.into());
+    let (b_hi, b_lo) = (b.0.into(), b.1.into());
+
+    let hi_lt = range.is_less_than(ctx, a_hi, b_hi, 128);
+    let hi_eq = gate.is_equal(ctx, a_hi, b_hi);
+    let lo_lt = range.is_less_than(ctx, a_lo, b_lo, 128);
+    // hi_lt and hi_eq are mutually exclusive, so the sum is their OR.
+    gate.mul_add(ctx, hi_eq, lo_lt, hi_lt)
+}
+
+fn two_pow_128() -> Fr {
+    Fr::from_u128(1u128 << 127) * Fr::from(2u64)
+}
@@ -0,0 +1,45 @@
+// zkpf/zkpf-circuit/src/gadgets/commitment.rs
+// Numan Thabit 2025
+
+//! Poseidon commitment to snapshot metadata.
+//!
+//! The Orchard and Starknet layouts spend one instance column on each snapshot
+//! field. A circuit can instead keep the height, anchor, holder binding and
+//! proven sum as witnesses and expose only [`commit_snapshot`] of them, which
+//! is what the V6_SNAPSHOT_COMMITMENT layout carries. The verifier recomputes
+//! the commitment from the fields in the bundle with
+//! [`snapshot_commitment_native`].
+
+use halo2_base::{gates::flex_gate::GateChip, AssignedValue, Context};
+use halo2curves_axiom::{bn256::Fr, ff::PrimeField};
+
+use crate::gadgets::poseidon::{hash_elements_witness, poseidon_hash4};
+
+/// Commit to the snapshot fields. `anchor` and `holder_binding` are the 32-byte
+/// values reduced into the field, as the V2_ORCHARD columns encode them; the
+/// caller is responsible for constraining each witness to its source.
+pub fn commit_snapshot(
+    ctx: &mut Context<Fr>,
+    gate: &GateChip<Fr>,
+    height: AssignedValue<Fr>,
+    anchor: AssignedValue<Fr>,
+    holder_binding: AssignedValue<Fr>,
+    proven_sum: AssignedValue<Fr>,
+) -> AssignedValue<Fr> {
+    poseidon_hash4(ctx, gate, height, anchor, holder_binding, proven_sum)
+}
+
+/// [`commit_snapshot`] on plain values, outside of any proof.
+pub fn snapshot_commitment_native(
+    height: u64,
+    anchor: Fr,
+    holder_binding: Fr,
+    proven_sum: u128,
+) -> Fr {
+    hash_elements_witness(&[
+        Fr::from(height),
+        anchor,
+        holder_binding,
+        Fr::from_u128(proven_sum),
+    ])
+}
@@ -7,8 +7,9 @@ use halo2_base::{
         range::{RangeChip, RangeInstructions},
     },
     AssignedValue, Context,
+    QuantumCell::Constant,
 };
-use halo2curves_axiom::bn256::Fr;
+use halo2curves_axiom::{bn256::Fr, ff::PrimeField};
 
 /// Enforce a >= b for 64-bit encoded values.
 pub fn enforce_geq(
@@ -18,10 +19,69 @@ pub fn enforce_geq(
     a: AssignedValue<Fr>,
     b: AssignedValue<Fr>,
 ) {
-    let lt = range.is_less_than(ctx, a, b, 64);
+    enforce_geq_bits(ctx, gate, range, a, b, 64);
+}
+
+/// Enforce a >= b for values already range-checked to `num_bits` bits.
+///
+/// Use this when `a` can exceed 64 bits, e.g. a sum of several 64-bit values.
+pub fn enforce_geq_bits(
+    ctx: &mut Context<Fr>,
+    gate: &GateChip<Fr>,
+    range: &RangeChip<Fr>,
+    a: AssignedValue<Fr>,
+    b: AssignedValue<Fr>,
+    num_bits: usize,
+) {
+    let lt = range.is_less_than(ctx, a, b, num_bits);
     gate.assert_is_const(ctx, &lt, &Fr::zero());
 }
 
+/// A `u128` witnessed as two 64-bit limbs, `hi * 2^64 + lo`.
+#[derive(Clone, Copy, Debug)]
+pub struct AssignedU128 {
+    pub lo: AssignedValue<Fr>,
+    pub hi: AssignedValue<Fr>,
+}
+
+impl AssignedU128 {
+    /// Witness `value` as two limbs, each range-checked to 64 bits.
+    pub fn assign(ctx: &mut Context<Fr>, range: &RangeChip<Fr>, value: u128) -> Self {
+        let lo = ctx.load_witness(Fr::from(value as u64));
+        let hi = ctx.load_witness(Fr::from((value >> 64) as u64));
+        range.range_check(ctx, lo, 64);
+        range.range_check(ctx, hi, 64);
+        Self { lo, hi }
+    }
+
+    /// The recombined value as one cell. Both limbs are range-checked, so it
+    /// is below 2^128 and cannot wrap the field.
+    pub fn value(&self, ctx: &mut Context<Fr>, gate: &GateChip<Fr>) -> AssignedValue<Fr> {
+        let shift = Constant(Fr::from_u128(1u128 << 64));
+        gate.mul_add(ctx, self.hi, shift, self.lo)
+    }
+}
+
+/// Enforce a >= b for 128-bit values.
+///
+/// Compares limb by limb, so no comparison is wider than 64 bits:
+/// `a >= b` iff `a.hi > b.hi`, or `a.hi == b.hi` and `a.lo >= b.lo`.
+pub fn enforce_geq_u128(
+    ctx: &mut Context<Fr>,
+    gate: &GateChip<Fr>,
+    range: &RangeChip<Fr>,
+    a: AssignedU128,
+    b: AssignedU128,
+) {
+    let hi_gt = range.is_less_than(ctx, b.hi, a.hi, 64);
+    let hi_eq = gate.is_equal(ctx, a.hi, b.hi);
+    let lo_lt = range.is_less_than(ctx, a.lo, b.lo, 64);
+    let lo_geq = gate.not(ctx, lo_lt);
+    let hi_eq_lo_geq = gate.and(ctx, hi_eq, lo_geq);
+    let geq = gate.or(ctx, hi_gt, hi_eq_lo_geq);
+    gate.assert_is_const(ctx, &geq, &Fr::one());
+}
+
 /// Enforce a <= b.
 pub fn enforce_leq(
     ctx: &mut Context<Fr>,
@@ -33,3 +93,29 @@ pub fn enforce_leq(
     let lt = range.is_less_than(ctx, b, a, 64);
     gate.assert_is_const(ctx, &lt, &Fr::zero());
 }
+
+/// Enforce min <= value <= max.
+pub fn enforce_range(
+    ctx: &mut Context<Fr>,
+    gate: &GateChip<Fr>,
+    range: &RangeChip<Fr>,
+    value: AssignedValue<Fr>,
+    min: AssignedValue<Fr>,
+    max: AssignedValue<Fr>,
+) {
+    enforce_geq(ctx, gate, range, value, min);
+    enforce_leq(ctx, gate, range, value, max);
+}
+
+/// Enforce that all values are pairwise distinct.
+///
+/// Cost is quadratic in `values.len()`, which is fine for the small, bounded
+/// sets used by the aggregate circuit.
+pub fn enforce_distinct(ctx: &mut Context<Fr>, gate: &GateChip<Fr>, values: &[AssignedValue<Fr>]) {
+    for (i, a) in values.iter().enumerate() {
+        for b in &values[i + 1..] {
+            let eq = gate.is_equal(ctx, *a, *b);
+            gate.assert_is_const(ctx, &eq, &Fr::zero());
+        }
+    }
+}
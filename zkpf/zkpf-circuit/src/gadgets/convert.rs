@@ -0,0 +1,43 @@
+// zkpf/zkpf-circuit/src/gadgets/convert.rs
+// Numan Thabit 2025
+
+use halo2_base::{
+    gates::{
+        flex_gate::{GateChip, GateInstructions},
+        range::{RangeChip, RangeInstructions},
+    },
+    AssignedValue, Context,
+};
+use halo2curves_axiom::bn256::Fr;
+
+/// Bit width of a product of two 64-bit values.
+const RATE_PRODUCT_BITS: usize = 128;
+
+/// Enforce `floor(balance * rate_num / rate_den) >= threshold`.
+///
+/// The rate is a public fixed-point value `rate_num / rate_den`. Rather than
+/// dividing in-circuit, both sides are cross-multiplied and compared as
+/// `balance * rate_num >= threshold * rate_den`, which is exact for the floored
+/// conversion because `threshold` is an integer.
+///
+/// All four inputs must already be range-checked to 64 bits so that each
+/// product fits in 128 bits and cannot wrap the field. `rate_den` must be
+/// non-zero.
+pub fn enforce_converted_geq(
+    ctx: &mut Context<Fr>,
+    gate: &GateChip<Fr>,
+    range: &RangeChip<Fr>,
+    balance: AssignedValue<Fr>,
+    rate_num: AssignedValue<Fr>,
+    rate_den: AssignedValue<Fr>,
+    threshold: AssignedValue<Fr>,
+) {
+    let den_is_zero = gate.is_zero(ctx, rate_den);
+    gate.assert_is_const(ctx, &den_is_zero, &Fr::zero());
+
+    let converted_scaled = gate.mul(ctx, balance, rate_num);
+    let threshold_scaled = gate.mul(ctx, threshold, rate_den);
+
+    let lt = range.is_less_than(ctx, converted_scaled, threshold_scaled, RATE_PRODUCT_BITS);
+    gate.assert_is_const(ctx, &lt, &Fr::zero());
+}
@@ -108,8 +108,9 @@ pub fn verify_ecdsa_over_attestation(
     constrain_pubkey_on_curve(ctx, &fp_chip, &pk);
 
     let (r, s) = load_signature(ctx, &fq_chip, &att.signature)?;
-    let msghash = load_scalar(ctx, &fq_chip, &att.message_hash)
-        .map_err(|_| EcdsaError::InvalidMessageHash)?;
+    let signed_digest = att.signature_format.signed_digest(&att.message_hash);
+    let msghash =
+        load_scalar(ctx, &fq_chip, &signed_digest).map_err(|_| EcdsaError::InvalidMessageHash)?;
 
     let verified = ecdsa_verify_no_pubkey_check::<Fr, Fp, Fq, Secp256k1Affine>(
         &ecc_chip, ctx, pk, r, s, msghash, 4, 4,
@@ -2,7 +2,10 @@
 // Numan Thabit 2025
 
 pub mod attestation;
+pub mod blocklist;
+pub mod commitment;
 pub mod compare;
+pub mod convert;
 pub mod ecdsa;
 pub mod nullifier;
 pub mod policy;
@@ -1,10 +1,13 @@
 // zkpf/zkpf-circuit/src/gadgets/nullifier.rs
 // Numan Thabit 2025
 
-use halo2_base::{gates::flex_gate::GateChip, AssignedValue, Context};
+use halo2_base::{
+    gates::flex_gate::{GateChip, GateInstructions},
+    AssignedValue, Context,
+};
 use halo2curves_axiom::bn256::Fr;
 
-use crate::gadgets::poseidon::poseidon_hash4;
+use crate::gadgets::poseidon::{hash_elements, poseidon_hash4};
 
 pub fn compute_nullifier(
     ctx: &mut Context<Fr>,
@@ -16,3 +19,24 @@ pub fn compute_nullifier(
 ) -> AssignedValue<Fr> {
     poseidon_hash4(ctx, gate, account_id_hash, verifier_scope, policy_id, epoch)
 }
+
+/// Compute a single nullifier covering a set of accounts.
+///
+/// Each account hash is compressed with Poseidon and the results are summed, so
+/// the digest is independent of attestation order. The digest then takes the
+/// place of `account_id_hash` in [`compute_nullifier`].
+pub fn compute_aggregate_nullifier(
+    ctx: &mut Context<Fr>,
+    gate: &GateChip<Fr>,
+    account_id_hashes: &[AssignedValue<Fr>],
+    verifier_scope: AssignedValue<Fr>,
+    policy_id: AssignedValue<Fr>,
+    epoch: AssignedValue<Fr>,
+) -> AssignedValue<Fr> {
+    let mut digest = ctx.load_zero();
+    for account_id_hash in account_id_hashes {
+        let leaf = hash_elements(ctx, gate, &[*account_id_hash]);
+        digest = gate.add(ctx, digest, leaf);
+    }
+    compute_nullifier(ctx, gate, digest, verifier_scope, policy_id, epoch)
+}
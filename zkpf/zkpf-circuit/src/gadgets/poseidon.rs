@@ -2,7 +2,10 @@
 // Numan Thabit 2025
 
 use halo2_base::{
-    gates::flex_gate::GateChip,
+    gates::{
+        circuit::{builder::BaseCircuitBuilder, CircuitBuilderStage},
+        flex_gate::GateChip,
+    },
     poseidon::hasher::{spec::OptimizedPoseidonSpec, PoseidonHasher},
     AssignedValue, Context,
 };
@@ -106,6 +109,19 @@ pub fn hash_elements(
     hasher.hash_fix_len_array(ctx, gate, inputs)
 }
 
+/// Run [`hash_elements`] on plain values, outside of any proof, so native
+/// Poseidon implementations can be checked against the circuit's.
+pub fn hash_elements_witness(inputs: &[Fr]) -> Fr {
+    let mut builder = BaseCircuitBuilder::<Fr>::from_stage(CircuitBuilderStage::Mock);
+    let gate = GateChip::default();
+    let ctx = builder.main(0);
+    let inputs: Vec<_> = inputs
+        .iter()
+        .map(|value| ctx.load_witness(*value))
+        .collect();
+    *hash_elements(ctx, &gate, &inputs).value()
+}
+
 fn poseidon_spec() -> OptimizedPoseidonSpec<Fr, POSEIDON_T, POSEIDON_RATE> {
     OptimizedPoseidonSpec::new::<POSEIDON_FULL_ROUNDS, POSEIDON_PARTIAL_ROUNDS, 0>()
 }
@@ -1,7 +1,15 @@
 // zkpf/zkpf-circuit/src/lib.rs
 // Numan Thabit 2025
 
+pub mod aggregate;
+pub mod converted;
 pub mod gadgets;
+pub mod multi_custodian;
+pub mod notes;
+pub mod ranged;
+pub mod screened;
+pub mod stats;
+pub mod wide;
 
 use halo2_base::{
     gates::{
@@ -21,7 +29,10 @@ use halo2curves_axiom::bn256::Fr;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 
-use crate::gadgets::attestation::{AttestationWitness, Secp256k1Pubkey};
+use crate::gadgets::attestation::{
+    bind_single_attestation, constrain_attestation, AttestationWitness, Secp256k1Pubkey,
+};
+use crate::stats::{collect_stats, CircuitStats};
 
 /// Errors that can occur during circuit synthesis.
 /// 
@@ -53,6 +64,15 @@ const DEFAULT_ADVICE_PER_PHASE: usize = 4;
 const DEFAULT_FIXED_COLUMNS: usize = 1;
 const DEFAULT_LOOKUP_ADVICE_PER_PHASE: usize = 1;
 
+/// Default width of the range check on the attested currency code.
+///
+/// 20 bits covers ISO-4217 codes and the 999xxx codes used for crypto assets
+/// (ZEC is 999001), and needs two 13-bit lookups instead of the three a 32-bit
+/// check takes. The required currency keeps its 32-bit check because the
+/// wildcard is `u32::MAX`; outside the wildcard it must equal the attested code
+/// and is bounded by it.
+pub const DEFAULT_CURRENCY_CODE_BITS: usize = 20;
+
 fn default_params() -> BaseCircuitParams {
     BaseCircuitParams {
         k: DEFAULT_K,
@@ -73,6 +93,53 @@ pub struct PublicInputs {
     pub policy_id: u64,
     pub nullifier: Fr,
     pub custodian_pubkey_hash: Fr,
+    /// Opt-in transparency: when set, the circuit exposes the attested balance
+    /// as an eighth public input, constrained equal to the witnessed balance.
+    /// Revealing proofs use their own keys (see [`ZkpfCircuit::keygen`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proven_sum: Option<u64>,
+    /// Verifier-issued freshness nonce. When set, it is exposed as the last
+    /// public input, so the proof can only have been made after the verifier
+    /// issued it. Challenge-bound proofs use their own keys (see [`CircuitShape`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub challenge: Option<Fr>,
+}
+
+/// The optional public columns a custodial proof exposes after the seven V1
+/// columns, in this order. Each shape has its own proving and verifying keys.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CircuitShape {
+    /// Expose the attested balance as `proven_sum`.
+    pub reveal_sum: bool,
+    /// Expose a verifier-issued `challenge`.
+    pub challenge: bool,
+}
+
+impl CircuitShape {
+    /// The shape needed to prove `public`.
+    pub fn of(public: &PublicInputs) -> Self {
+        Self {
+            reveal_sum: public.proven_sum.is_some(),
+            challenge: public.challenge.is_some(),
+        }
+    }
+
+    /// Number of instance columns in this shape.
+    pub fn instance_columns(self) -> usize {
+        NUM_INSTANCE_COLUMNS + usize::from(self.reveal_sum) + usize::from(self.challenge)
+    }
+
+    /// Keygen placeholder with this shape's optional inputs filled in.
+    fn sample_input(self) -> ZkpfCircuitInput {
+        let mut input = SAMPLE_INPUT.clone();
+        if self.reveal_sum {
+            input.public.proven_sum = Some(input.attestation.balance_raw);
+        }
+        if self.challenge {
+            input.public.challenge = Some(Fr::zero());
+        }
+        input
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -81,10 +148,56 @@ pub struct ZkpfCircuitInput {
     pub public: PublicInputs,
 }
 
+impl ZkpfCircuitInput {
+    /// `public` with `nullifier` and `custodian_pubkey_hash` recomputed from the
+    /// attestation witness.
+    ///
+    /// The values come from the circuit's own gadgets, run without proving, so
+    /// they do not depend on prover randomness: every proof of this input
+    /// exposes exactly these values. Integrators can compare them against their
+    /// own derivation before proving.
+    pub fn derived_public_inputs(&self) -> PublicInputs {
+        let params = default_params();
+        let mut builder =
+            BaseCircuitBuilder::<Fr>::from_stage(CircuitBuilderStage::Mock).use_params(params);
+        builder = builder.use_lookup_bits(DEFAULT_LOOKUP_BITS);
+        let range = builder.range_chip();
+        let gate = range.gate();
+        let ctx = builder.main(0);
+
+        let account_id_hash = ctx.load_witness(self.attestation.account_id_hash);
+        let verifier_scope = ctx.load_witness(Fr::from(self.public.verifier_scope_id));
+        let policy_id = ctx.load_witness(Fr::from(self.public.policy_id));
+        let current_epoch = ctx.load_witness(Fr::from(self.public.current_epoch));
+        let nullifier = crate::gadgets::nullifier::compute_nullifier(
+            ctx,
+            gate,
+            account_id_hash,
+            verifier_scope,
+            policy_id,
+            current_epoch,
+        );
+
+        let (pubkey_x, pubkey_y) =
+            assign_pubkey_coords(ctx, gate, &range, &self.attestation.custodian_pubkey);
+        let pubkey_hash = hash_pubkey_coords(ctx, gate, pubkey_x, pubkey_y);
+
+        PublicInputs {
+            nullifier: *nullifier.value(),
+            custodian_pubkey_hash: *pubkey_hash.value(),
+            ..self.public.clone()
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct ZkpfCircuit {
     pub input: Option<ZkpfCircuitInput>,
     params: BaseCircuitParams,
+    shape: CircuitShape,
+    /// Range-check width of the attested currency code. Part of the circuit
+    /// layout, so changing it changes the proving and verifying keys.
+    currency_code_bits: usize,
     /// Circuit builder stage. Determines optimization level during synthesis:
     /// - `Keygen`: Used during proving key generation (no witness values)
     /// - `Prover`: Optimized for real proof generation (witness-gen only, skips constraints)
@@ -97,6 +210,8 @@ impl Default for ZkpfCircuit {
         Self {
             input: None,
             params: default_params(),
+            shape: CircuitShape::default(),
+            currency_code_bits: DEFAULT_CURRENCY_CODE_BITS,
             stage: CircuitBuilderStage::Keygen,
         }
     }
@@ -111,13 +226,40 @@ impl ZkpfCircuit {
         } else {
             CircuitBuilderStage::Keygen
         };
+        let shape = input.as_ref().map_or_else(CircuitShape::default, |input| {
+            CircuitShape::of(&input.public)
+        });
         Self {
             input,
-            params: default_params(),
+            params: circuit_params(shape),
+            shape,
+            currency_code_bits: DEFAULT_CURRENCY_CODE_BITS,
             stage,
         }
     }
 
+    /// Creates an empty circuit for key generation.
+    ///
+    /// `reveal_sum` selects the shape with the extra `proven_sum` column; the
+    /// two shapes have different verifying keys.
+    pub fn keygen(reveal_sum: bool) -> Self {
+        Self::keygen_for(CircuitShape {
+            reveal_sum,
+            challenge: false,
+        })
+    }
+
+    /// Creates an empty circuit for key generation of any [`CircuitShape`].
+    pub fn keygen_for(shape: CircuitShape) -> Self {
+        Self {
+            input: None,
+            params: circuit_params(shape),
+            shape,
+            currency_code_bits: DEFAULT_CURRENCY_CODE_BITS,
+            stage: CircuitBuilderStage::Keygen,
+        }
+    }
+
     /// Creates a circuit for production proof generation.
     /// 
     /// # Implementation Note
@@ -133,15 +275,61 @@ impl ZkpfCircuit {
     /// constraint verification overhead, but this is negligible compared to the polynomial
     /// commitment operations that dominate proving time.
     pub fn new_prover(input: ZkpfCircuitInput) -> Self {
+        let shape = CircuitShape::of(&input.public);
         Self {
+            params: circuit_params(shape),
+            shape,
             input: Some(input),
-            params: default_params(),
+            currency_code_bits: DEFAULT_CURRENCY_CODE_BITS,
             stage: CircuitBuilderStage::Mock,
         }
     }
+
+    /// Range-check the attested currency code to `bits` bits instead of
+    /// [`DEFAULT_CURRENCY_CODE_BITS`]. Keys must be generated with the same
+    /// width they are used with. Fails unless `bits` is between 1 and 32.
+    pub fn with_currency_code_bits(mut self, bits: usize) -> Result<Self, CircuitError> {
+        if !(1..=32).contains(&bits) {
+            return Err(CircuitError::Synthesis(format!(
+                "currency_code_bits must be between 1 and 32, got {bits}"
+            )));
+        }
+        self.currency_code_bits = bits;
+        Ok(self)
+    }
+}
+
+fn circuit_params(shape: CircuitShape) -> BaseCircuitParams {
+    BaseCircuitParams {
+        num_instance_columns: shape.instance_columns(),
+        ..default_params()
+    }
 }
 
+/// Instance columns for `public`: the seven V1 columns, followed by
+/// `proven_sum` when it is revealed and `challenge` when one is bound.
 pub fn public_instances(public: &PublicInputs) -> Vec<Vec<Fr>> {
+    let mut cols = v1_instances(public);
+    if let Some(proven_sum) = public.proven_sum {
+        cols.push(vec![Fr::from(proven_sum)]);
+    }
+    if let Some(challenge) = public.challenge {
+        cols.push(vec![challenge]);
+    }
+    cols
+}
+
+/// Cell usage of the custodial circuit for `input`'s shape.
+pub fn circuit_stats(input: &ZkpfCircuitInput) -> CircuitStats {
+    collect_stats(
+        &circuit_params(CircuitShape::of(&input.public)),
+        |builder| build_constraints(builder, input, DEFAULT_CURRENCY_CODE_BITS),
+    )
+}
+
+/// The seven V1 columns, which the variant circuits extend. Variants never
+/// reveal the sum or bind a challenge, so both are ignored here.
+pub(crate) fn v1_instances(public: &PublicInputs) -> Vec<Vec<Fr>> {
     vec![
         vec![Fr::from(public.threshold_raw)],
         vec![Fr::from(public.required_currency_code as u64)],
@@ -166,6 +354,8 @@ impl Circuit<Fr> for ZkpfCircuit {
         Self {
             input: None,
             params: self.params.clone(),
+            shape: self.shape,
+            currency_code_bits: self.currency_code_bits,
             stage: CircuitBuilderStage::Keygen,
         }
     }
@@ -186,7 +376,15 @@ impl Circuit<Fr> for ZkpfCircuit {
         // - Keygen: Key generation phase, uses sample input with `unknown(true)`
         // - Mock: MockProver testing, stores constraints for verification  
         // - Prover: Production proving, `witness_gen_only(true)` for performance
-        let input = self.input.as_ref().unwrap_or(&SAMPLE_INPUT);
+        let sample;
+        let input = match self.input.as_ref() {
+            Some(input) => input,
+            None if self.shape != CircuitShape::default() => {
+                sample = self.shape.sample_input();
+                &sample
+            }
+            None => &SAMPLE_INPUT,
+        };
 
         let mut builder = BaseCircuitBuilder::<Fr>::from_stage(self.stage)
             .use_params(self.params.clone())
@@ -198,7 +396,7 @@ impl Circuit<Fr> for ZkpfCircuit {
 
         // Build constraints for the simplified proof-of-funds circuit.
         // ECDSA verification has been moved to the backend for smaller pk.bin.
-        build_constraints(&mut builder, input);
+        build_constraints(&mut builder, input, self.currency_code_bits);
 
         <BaseCircuitBuilder<Fr> as Circuit<Fr>>::synthesize(&builder, config, layouter)
     }
@@ -211,6 +409,7 @@ static SAMPLE_INPUT: Lazy<ZkpfCircuitInput> = Lazy::new(|| {
 fn build_constraints(
     builder: &mut BaseCircuitBuilder<Fr>,
     input: &ZkpfCircuitInput,
+    currency_code_bits: usize,
 ) {
     let range = builder.range_chip();
     let gate = range.gate();
@@ -222,40 +421,22 @@ fn build_constraints(
 
     let balance = assign_u64(ctx, &range, att.balance_raw);
     let threshold = assign_u64(ctx, &range, pub_in.threshold_raw);
-    let currency = assign_u32(ctx, &range, att.currency_code_int);
     let req_currency = assign_u32(ctx, &range, pub_in.required_currency_code);
-    let custodian = assign_u32(ctx, &range, att.custodian_id);
-    let attestation_id = assign_u64(ctx, &range, att.attestation_id);
-    let issued_at = assign_u64(ctx, &range, att.issued_at);
-    let valid_until = assign_u64(ctx, &range, att.valid_until);
     let current_epoch = assign_u64(ctx, &range, pub_in.current_epoch);
     let verifier_scope = assign_u64(ctx, &range, pub_in.verifier_scope_id);
     let policy_id = assign_u64(ctx, &range, pub_in.policy_id);
-    let account_id_hash = ctx.load_witness(att.account_id_hash);
 
-    crate::gadgets::compare::enforce_leq(ctx, gate, &range, issued_at, current_epoch);
-    crate::gadgets::compare::enforce_leq(ctx, gate, &range, current_epoch, valid_until);
-
-    crate::gadgets::policy::enforce_currency(ctx, gate, currency, req_currency);
-
-    crate::gadgets::compare::enforce_geq(ctx, gate, &range, balance, threshold);
-
-    let digest_fr = crate::gadgets::poseidon::hash_attestation(
+    let cells = constrain_attestation(
         ctx,
-        gate,
+        &range,
+        att,
         balance,
-        attestation_id,
-        currency,
-        custodian,
-        issued_at,
-        valid_until,
-        account_id_hash,
+        current_epoch,
+        req_currency,
+        currency_code_bits,
     );
-    let digest_from_bytes = fr_from_be_bytes(ctx, gate, &range, &att.message_hash);
-    ctx.constrain_equal(&digest_fr, &digest_from_bytes);
 
-    let (witness_pubkey_x, witness_pubkey_y) =
-        assign_pubkey_coords(ctx, gate, &range, &att.custodian_pubkey);
+    crate::gadgets::compare::enforce_geq(ctx, gate, &range, balance, threshold);
 
     // NOTE: ECDSA signature verification has been moved OUT of the circuit.
     // The backend verifies the signature before calling the prover.
@@ -270,27 +451,15 @@ fn build_constraints(
     // - Pubkey hash matches (binds proof to specific custodian key)
     // - Message hash matches Poseidon(attestation_fields)
 
-    let computed_nullifier = crate::gadgets::nullifier::compute_nullifier(
+    let (public_nullifier, public_pubkey_hash) = bind_single_attestation(
         ctx,
         gate,
-        account_id_hash,
+        &cells,
         verifier_scope,
         policy_id,
         current_epoch,
+        pub_in,
     );
-    let public_nullifier = ctx.load_witness(pub_in.nullifier);
-    ctx.constrain_equal(&computed_nullifier, &public_nullifier);
-
-    let pubkey_hash = hash_pubkey_coords(
-        ctx,
-        gate,
-        witness_pubkey_x,
-        witness_pubkey_y,
-    );
-    let public_pubkey_hash = ctx.load_witness(pub_in.custodian_pubkey_hash);
-    ctx.constrain_equal(&pubkey_hash, &public_pubkey_hash);
-
-    let _ = ctx;
 
     expose_public_inputs(
         builder,
@@ -304,6 +473,21 @@ fn build_constraints(
             public_pubkey_hash,
         ],
     );
+
+    // The revealed sum is the balance cell itself, so the instance can only
+    // match the attested balance.
+    let mut next_column = NUM_INSTANCE_COLUMNS;
+    if pub_in.proven_sum.is_some() {
+        builder.assigned_instances[next_column].push(balance);
+        next_column += 1;
+    }
+
+    // The challenge only has to appear in the instance: the verifier supplies
+    // it, so a proof made before it was issued cannot verify.
+    if let Some(challenge) = pub_in.challenge {
+        let challenge = builder.main(0).load_witness(challenge);
+        builder.assigned_instances[next_column].push(challenge);
+    }
 }
 
 fn assign_u64(ctx: &mut Context<Fr>, range: &RangeChip<Fr>, value: u64) -> AssignedValue<Fr> {
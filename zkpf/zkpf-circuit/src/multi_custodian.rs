@@ -0,0 +1,335 @@
+// zkpf/zkpf-circuit/src/multi_custodian.rs
+// Numan Thabit 2025
+
+//! Proof of funds held across several custodians.
+//!
+//! Proves that the combined balance of up to [`MAX_CUSTODIANS`] attestations,
+//! each from a different custodian, meets the public threshold. Every
+//! attestation goes through the same checks as the single-account circuit
+//! (validity window, currency, Poseidon message hash), and the attestations
+//! must be ordered by strictly increasing `custodian_id`, so no custodian can
+//! be counted twice and each custodian set has exactly one valid ordering.
+//!
+//! The public inputs reuse the seven-column [`PublicInputs`] shape:
+//! - `nullifier` is [`crate::gadgets::nullifier::compute_aggregate_nullifier`]
+//!   over the attestations' `account_id_hash`es.
+//! - `custodian_pubkey_hash` is the root of a depth-[`CUSTODIAN_TREE_DEPTH`]
+//!   Poseidon Merkle tree whose leaves are `Poseidon(x_i, y_i)` in attestation
+//!   order, padded with zero leaves (`zkpf_common::custodian_set_root`).
+//!
+//! As with [`crate::ZkpfCircuit`], ECDSA signatures are verified outside the
+//! circuit before proving.
+
+use halo2_base::{
+    gates::{
+        circuit::builder::BaseCircuitBuilder,
+        circuit::{BaseCircuitParams, BaseConfig, CircuitBuilderStage},
+        GateChip, GateInstructions, RangeInstructions,
+    },
+    AssignedValue, Context,
+};
+use halo2_proofs_axiom::{
+    circuit::{Layouter, SimpleFloorPlanner},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use halo2curves_axiom::bn256::Fr;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    aggregate::aggregate_circuit_params,
+    assign_u32, assign_u64, expose_public_inputs,
+    gadgets::{
+        attestation::{constrain_attestation, AttestationWitness},
+        poseidon::hash_elements,
+    },
+    stats::{collect_stats, CircuitStats},
+    CircuitError, PublicInputs, DEFAULT_CURRENCY_CODE_BITS, SAMPLE_INPUT,
+};
+
+/// Depth of the custodian pubkey-hash tree.
+pub const CUSTODIAN_TREE_DEPTH: usize = 2;
+
+/// Upper bound on the number of custodians covered by one proof.
+pub const MAX_CUSTODIANS: usize = 1 << CUSTODIAN_TREE_DEPTH;
+
+// 64-bit balances plus carry headroom for summing MAX_CUSTODIANS of them.
+const MULTI_CUSTODIAN_SUM_BITS: usize = 64 + CUSTODIAN_TREE_DEPTH;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ZkpfMultiCustodianCircuitInput {
+    /// One attestation per custodian, ordered by strictly increasing
+    /// `custodian_id`.
+    pub attestations: Vec<AttestationWitness>,
+    pub public: PublicInputs,
+}
+
+/// Multi-custodian circuit over a fixed number of custodians.
+///
+/// Like [`crate::aggregate::ZkpfAggregateCircuit`], `num_custodians` is part
+/// of the circuit shape, so keys are generated per supported size.
+#[derive(Clone, Debug)]
+pub struct ZkpfMultiCustodianCircuit {
+    pub input: Option<ZkpfMultiCustodianCircuitInput>,
+    num_custodians: usize,
+    params: BaseCircuitParams,
+    stage: CircuitBuilderStage,
+}
+
+impl ZkpfMultiCustodianCircuit {
+    /// Creates a witness-free circuit for key generation.
+    pub fn keygen(num_custodians: usize) -> Result<Self, CircuitError> {
+        ensure_num_custodians(num_custodians)?;
+        Ok(Self {
+            input: None,
+            num_custodians,
+            params: aggregate_circuit_params(),
+            stage: CircuitBuilderStage::Keygen,
+        })
+    }
+
+    /// Creates a new circuit for MockProver testing.
+    /// Use `new_prover` for production proof generation.
+    pub fn new(
+        num_custodians: usize,
+        input: Option<ZkpfMultiCustodianCircuitInput>,
+    ) -> Result<Self, CircuitError> {
+        ensure_num_custodians(num_custodians)?;
+        let stage = match &input {
+            Some(input) => {
+                ensure_input_len(num_custodians, input)?;
+                CircuitBuilderStage::Mock
+            }
+            None => CircuitBuilderStage::Keygen,
+        };
+        Ok(Self {
+            input,
+            num_custodians,
+            params: aggregate_circuit_params(),
+            stage,
+        })
+    }
+
+    /// Creates a circuit for production proof generation, sized to the input.
+    ///
+    /// Uses the Mock stage for the same reason as [`crate::ZkpfCircuit::new_prover`].
+    pub fn new_prover(input: ZkpfMultiCustodianCircuitInput) -> Result<Self, CircuitError> {
+        let num_custodians = input.attestations.len();
+        ensure_num_custodians(num_custodians)?;
+        Ok(Self {
+            input: Some(input),
+            num_custodians,
+            params: aggregate_circuit_params(),
+            stage: CircuitBuilderStage::Mock,
+        })
+    }
+
+    pub fn num_custodians(&self) -> usize {
+        self.num_custodians
+    }
+}
+
+/// Cell usage of the multi-custodian circuit sized to `input`'s attestations.
+pub fn multi_custodian_circuit_stats(
+    input: &ZkpfMultiCustodianCircuitInput,
+) -> Result<CircuitStats, CircuitError> {
+    ensure_num_custodians(input.attestations.len())?;
+    Ok(collect_stats(&aggregate_circuit_params(), |builder| {
+        build_multi_custodian_constraints(builder, input)
+    }))
+}
+
+fn ensure_num_custodians(num_custodians: usize) -> Result<(), CircuitError> {
+    if num_custodians == 0 || num_custodians > MAX_CUSTODIANS {
+        return Err(CircuitError::Synthesis(format!(
+            "multi-custodian circuit supports 1..={} custodians, got {}",
+            MAX_CUSTODIANS, num_custodians
+        )));
+    }
+    Ok(())
+}
+
+fn ensure_input_len(
+    num_custodians: usize,
+    input: &ZkpfMultiCustodianCircuitInput,
+) -> Result<(), CircuitError> {
+    if input.attestations.len() != num_custodians {
+        return Err(CircuitError::Synthesis(format!(
+            "multi-custodian circuit sized for {} custodians but input has {} attestations",
+            num_custodians,
+            input.attestations.len()
+        )));
+    }
+    Ok(())
+}
+
+impl Circuit<Fr> for ZkpfMultiCustodianCircuit {
+    type Config = BaseConfig<Fr>;
+    type FloorPlanner = SimpleFloorPlanner;
+    type Params = BaseCircuitParams;
+
+    fn params(&self) -> Self::Params {
+        self.params.clone()
+    }
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            input: None,
+            num_custodians: self.num_custodians,
+            params: self.params.clone(),
+            stage: CircuitBuilderStage::Keygen,
+        }
+    }
+
+    fn configure_with_params(
+        meta: &mut ConstraintSystem<Fr>,
+        params: Self::Params,
+    ) -> Self::Config {
+        BaseConfig::configure(meta, params)
+    }
+
+    fn configure(_: &mut ConstraintSystem<Fr>) -> Self::Config {
+        unreachable!("ZkpfMultiCustodianCircuit must be configured with explicit parameters")
+    }
+
+    fn synthesize(&self, config: Self::Config, layouter: impl Layouter<Fr>) -> Result<(), Error> {
+        let sample;
+        let input = match self.input.as_ref() {
+            Some(input) => input,
+            None => {
+                sample = sample_input(self.num_custodians);
+                &sample
+            }
+        };
+
+        let mut builder = BaseCircuitBuilder::<Fr>::from_stage(self.stage)
+            .use_params(self.params.clone())
+            .use_instance_columns(self.params.num_instance_columns);
+
+        if let Some(bits) = self.params.lookup_bits {
+            builder = builder.use_lookup_bits(bits);
+        }
+
+        build_multi_custodian_constraints(&mut builder, input);
+
+        <BaseCircuitBuilder<Fr> as Circuit<Fr>>::synthesize(&builder, config, layouter)
+    }
+}
+
+/// Keygen placeholder: the single-account sample attestation repeated under
+/// increasing custodian ids.
+fn sample_input(num_custodians: usize) -> ZkpfMultiCustodianCircuitInput {
+    let attestations = (0..num_custodians)
+        .map(|i| {
+            let mut att = SAMPLE_INPUT.attestation.clone();
+            att.custodian_id += i as u32;
+            att
+        })
+        .collect();
+    ZkpfMultiCustodianCircuitInput {
+        attestations,
+        public: SAMPLE_INPUT.public.clone(),
+    }
+}
+
+fn build_multi_custodian_constraints(
+    builder: &mut BaseCircuitBuilder<Fr>,
+    input: &ZkpfMultiCustodianCircuitInput,
+) {
+    let range = builder.range_chip();
+    let gate = range.gate();
+
+    let pub_in = &input.public;
+
+    let ctx = builder.main(0);
+
+    let threshold = assign_u64(ctx, &range, pub_in.threshold_raw);
+    let req_currency = assign_u32(ctx, &range, pub_in.required_currency_code);
+    let current_epoch = assign_u64(ctx, &range, pub_in.current_epoch);
+    let verifier_scope = assign_u64(ctx, &range, pub_in.verifier_scope_id);
+    let policy_id = assign_u64(ctx, &range, pub_in.policy_id);
+
+    let mut balance_sum = ctx.load_zero();
+    let mut previous_custodian: Option<AssignedValue<Fr>> = None;
+    let mut account_hashes = Vec::with_capacity(input.attestations.len());
+    let mut custodian_leaves = Vec::with_capacity(MAX_CUSTODIANS);
+
+    for att in &input.attestations {
+        let balance = assign_u64(ctx, &range, att.balance_raw);
+        let cells = constrain_attestation(
+            ctx,
+            &range,
+            att,
+            balance,
+            current_epoch,
+            req_currency,
+            DEFAULT_CURRENCY_CODE_BITS,
+        );
+
+        // Strictly increasing ids: each custodian counts once towards the sum.
+        if let Some(previous) = previous_custodian {
+            let increasing = range.is_less_than(ctx, previous, cells.custodian, 32);
+            gate.assert_is_const(ctx, &increasing, &Fr::one());
+        }
+        previous_custodian = Some(cells.custodian);
+
+        custodian_leaves.push(cells.pubkey_hash);
+
+        balance_sum = gate.add(ctx, balance_sum, balance);
+        account_hashes.push(cells.account_id_hash);
+    }
+
+    crate::gadgets::compare::enforce_geq_bits(
+        ctx,
+        gate,
+        &range,
+        balance_sum,
+        threshold,
+        MULTI_CUSTODIAN_SUM_BITS,
+    );
+
+    let computed_nullifier = crate::gadgets::nullifier::compute_aggregate_nullifier(
+        ctx,
+        gate,
+        &account_hashes,
+        verifier_scope,
+        policy_id,
+        current_epoch,
+    );
+    let public_nullifier = ctx.load_witness(pub_in.nullifier);
+    ctx.constrain_equal(&computed_nullifier, &public_nullifier);
+
+    let custodian_root = merkle_root(ctx, gate, custodian_leaves);
+    let public_custodian_root = ctx.load_witness(pub_in.custodian_pubkey_hash);
+    ctx.constrain_equal(&custodian_root, &public_custodian_root);
+
+    expose_public_inputs(
+        builder,
+        [
+            threshold,
+            req_currency,
+            current_epoch,
+            verifier_scope,
+            policy_id,
+            public_nullifier,
+            public_custodian_root,
+        ],
+    );
+}
+
+/// Root of the custodian tree over `leaves`, padded with zero leaves to
+/// `MAX_CUSTODIANS`. Matches `zkpf_common::merkle::MerkleTree` with zero
+/// padding.
+fn merkle_root(
+    ctx: &mut Context<Fr>,
+    gate: &GateChip<Fr>,
+    mut level: Vec<AssignedValue<Fr>>,
+) -> AssignedValue<Fr> {
+    level.resize_with(MAX_CUSTODIANS, || ctx.load_zero());
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| hash_elements(ctx, gate, &[pair[0], pair[1]]))
+            .collect();
+    }
+    level[0]
+}
@@ -0,0 +1,43 @@
+// zkpf/zkpf-circuit/src/notes.rs
+// Numan Thabit 2025
+
+//! Fixed-width note sets for shielded-pool rails.
+//!
+//! A pool circuit loads one cell per note, so its layout (and verifying key)
+//! would otherwise depend on how many notes the wallet holds. Rails pad every
+//! note set to the circuit's maximum width with zero-value notes before
+//! synthesis; padded notes add nothing to the proven sum.
+
+use thiserror::Error;
+
+/// The note set does not fit in the circuit.
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+#[error("too many notes: got {got}, max supported is {max}")]
+pub struct TooManyNotes {
+    pub got: usize,
+    pub max: usize,
+}
+
+/// Pad note values to exactly `max` entries with zero-value notes.
+pub fn pad_notes(notes: Vec<u64>, max: usize) -> Result<Vec<u64>, TooManyNotes> {
+    pad_note_witnesses(notes, max)
+}
+
+/// Pad per-note witnesses to exactly `max` entries with `T::default()`.
+///
+/// Use this for witness columns that sit alongside the note values (asset
+/// types, commitments): the padding slot's value is zero, so `T::default()`
+/// only needs to be well-formed, not meaningful.
+pub fn pad_note_witnesses<T: Default>(
+    mut notes: Vec<T>,
+    max: usize,
+) -> Result<Vec<T>, TooManyNotes> {
+    if notes.len() > max {
+        return Err(TooManyNotes {
+            got: notes.len(),
+            max,
+        });
+    }
+    notes.resize_with(max, T::default);
+    Ok(notes)
+}
@@ -0,0 +1,231 @@
+// zkpf/zkpf-circuit/src/ranged.rs
+// Numan Thabit 2025
+
+//! Proof that a balance lies within a band.
+//!
+//! Runs the same checks as [`crate::ZkpfCircuit`] but replaces the one-sided
+//! `balance >= threshold` comparison with `threshold_min <= balance <=
+//! threshold_max`, so a holder can meet a bounded-disclosure policy ("between
+//! $10k and $50k") without revealing how far above the lower bound they are.
+//! The V1 `threshold_raw` column carries `threshold_min`; `threshold_max` is
+//! appended as an eighth public input.
+
+use halo2_base::{
+    gates::{
+        circuit::builder::BaseCircuitBuilder,
+        circuit::{BaseCircuitParams, BaseConfig, CircuitBuilderStage},
+        RangeInstructions,
+    },
+    AssignedValue,
+};
+use halo2_proofs_axiom::{
+    circuit::{Layouter, SimpleFloorPlanner},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use halo2curves_axiom::bn256::Fr;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    assign_u32, assign_u64, default_params,
+    gadgets::attestation::{bind_single_attestation, constrain_attestation, AttestationWitness},
+    stats::{collect_stats, CircuitStats},
+    v1_instances, PublicInputs, DEFAULT_CURRENCY_CODE_BITS, NUM_INSTANCE_COLUMNS, SAMPLE_INPUT,
+};
+
+/// Instance columns: the V1 columns followed by `threshold_max`.
+pub const NUM_RANGE_INSTANCE_COLUMNS: usize = NUM_INSTANCE_COLUMNS + 1;
+
+/// Witness for the range circuit. `public.threshold_raw` is the lower bound.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ZkpfRangeCircuitInput {
+    pub attestation: AttestationWitness,
+    pub public: PublicInputs,
+    pub threshold_max: u64,
+}
+
+#[derive(Clone, Debug)]
+pub struct ZkpfRangeCircuit {
+    pub input: Option<ZkpfRangeCircuitInput>,
+    params: BaseCircuitParams,
+    stage: CircuitBuilderStage,
+}
+
+impl Default for ZkpfRangeCircuit {
+    fn default() -> Self {
+        Self {
+            input: None,
+            params: range_params(),
+            stage: CircuitBuilderStage::Keygen,
+        }
+    }
+}
+
+impl ZkpfRangeCircuit {
+    /// Creates a new circuit for MockProver testing.
+    /// Use `new_prover` for production proof generation.
+    pub fn new(input: Option<ZkpfRangeCircuitInput>) -> Self {
+        let stage = if input.is_some() {
+            CircuitBuilderStage::Mock
+        } else {
+            CircuitBuilderStage::Keygen
+        };
+        Self {
+            input,
+            params: range_params(),
+            stage,
+        }
+    }
+
+    /// Creates a circuit for production proof generation.
+    ///
+    /// Uses the Mock stage for the same reason as [`crate::ZkpfCircuit::new_prover`].
+    pub fn new_prover(input: ZkpfRangeCircuitInput) -> Self {
+        Self {
+            input: Some(input),
+            params: range_params(),
+            stage: CircuitBuilderStage::Mock,
+        }
+    }
+}
+
+fn range_params() -> BaseCircuitParams {
+    BaseCircuitParams {
+        num_instance_columns: NUM_RANGE_INSTANCE_COLUMNS,
+        ..default_params()
+    }
+}
+
+pub fn range_public_instances(public: &PublicInputs, threshold_max: u64) -> Vec<Vec<Fr>> {
+    let mut cols = v1_instances(public);
+    cols.push(vec![Fr::from(threshold_max)]);
+    cols
+}
+
+/// Cell usage of the range circuit.
+pub fn range_circuit_stats(input: &ZkpfRangeCircuitInput) -> CircuitStats {
+    collect_stats(&range_params(), |builder| {
+        build_range_constraints(builder, input)
+    })
+}
+
+impl Circuit<Fr> for ZkpfRangeCircuit {
+    type Config = BaseConfig<Fr>;
+    type FloorPlanner = SimpleFloorPlanner;
+    type Params = BaseCircuitParams;
+
+    fn params(&self) -> Self::Params {
+        self.params.clone()
+    }
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            input: None,
+            params: self.params.clone(),
+            stage: CircuitBuilderStage::Keygen,
+        }
+    }
+
+    fn configure_with_params(
+        meta: &mut ConstraintSystem<Fr>,
+        params: Self::Params,
+    ) -> Self::Config {
+        BaseConfig::configure(meta, params)
+    }
+
+    fn configure(_: &mut ConstraintSystem<Fr>) -> Self::Config {
+        unreachable!("ZkpfRangeCircuit must be configured with explicit parameters")
+    }
+
+    fn synthesize(&self, config: Self::Config, layouter: impl Layouter<Fr>) -> Result<(), Error> {
+        let sample;
+        let input = match self.input.as_ref() {
+            Some(input) => input,
+            None => {
+                sample = sample_input();
+                &sample
+            }
+        };
+
+        let mut builder = BaseCircuitBuilder::<Fr>::from_stage(self.stage)
+            .use_params(self.params.clone())
+            .use_instance_columns(self.params.num_instance_columns);
+
+        if let Some(bits) = self.params.lookup_bits {
+            builder = builder.use_lookup_bits(bits);
+        }
+
+        build_range_constraints(&mut builder, input);
+
+        <BaseCircuitBuilder<Fr> as Circuit<Fr>>::synthesize(&builder, config, layouter)
+    }
+}
+
+/// Keygen placeholder: the sample attestation with an unbounded upper limit.
+fn sample_input() -> ZkpfRangeCircuitInput {
+    ZkpfRangeCircuitInput {
+        attestation: SAMPLE_INPUT.attestation.clone(),
+        public: SAMPLE_INPUT.public.clone(),
+        threshold_max: u64::MAX,
+    }
+}
+
+fn build_range_constraints(builder: &mut BaseCircuitBuilder<Fr>, input: &ZkpfRangeCircuitInput) {
+    let range = builder.range_chip();
+    let gate = range.gate();
+
+    let att = &input.attestation;
+    let pub_in = &input.public;
+
+    let ctx = builder.main(0);
+
+    let balance = assign_u64(ctx, &range, att.balance_raw);
+    let threshold_min = assign_u64(ctx, &range, pub_in.threshold_raw);
+    let threshold_max = assign_u64(ctx, &range, input.threshold_max);
+    let req_currency = assign_u32(ctx, &range, pub_in.required_currency_code);
+    let current_epoch = assign_u64(ctx, &range, pub_in.current_epoch);
+    let verifier_scope = assign_u64(ctx, &range, pub_in.verifier_scope_id);
+    let policy_id = assign_u64(ctx, &range, pub_in.policy_id);
+
+    let cells = constrain_attestation(
+        ctx,
+        &range,
+        att,
+        balance,
+        current_epoch,
+        req_currency,
+        DEFAULT_CURRENCY_CODE_BITS,
+    );
+
+    crate::gadgets::compare::enforce_range(
+        ctx,
+        gate,
+        &range,
+        balance,
+        threshold_min,
+        threshold_max,
+    );
+
+    let (public_nullifier, public_pubkey_hash) = bind_single_attestation(
+        ctx,
+        gate,
+        &cells,
+        verifier_scope,
+        policy_id,
+        current_epoch,
+        pub_in,
+    );
+
+    let values: [AssignedValue<Fr>; NUM_RANGE_INSTANCE_COLUMNS] = [
+        threshold_min,
+        req_currency,
+        current_epoch,
+        verifier_scope,
+        policy_id,
+        public_nullifier,
+        public_pubkey_hash,
+        threshold_max,
+    ];
+    for (idx, value) in values.into_iter().enumerate() {
+        builder.assigned_instances[idx].push(value);
+    }
+}
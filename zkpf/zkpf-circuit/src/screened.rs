@@ -0,0 +1,265 @@
+// zkpf/zkpf-circuit/src/screened.rs
+// Numan Thabit 2025
+
+//! Proof-of-funds with blocklist screening.
+//!
+//! Runs the same checks as [`crate::ZkpfCircuit`] and additionally proves that
+//! the attestation's `account_id_hash` is not a leaf of a sorted blocklist
+//! tree (see [`crate::gadgets::blocklist`]). The blocklist root is appended as
+//! an eighth public input after the V1 columns, so a verifier can pin the
+//! exact list version it screens against.
+
+use halo2_base::{
+    gates::{
+        circuit::builder::BaseCircuitBuilder,
+        circuit::{BaseCircuitParams, BaseConfig, CircuitBuilderStage},
+        RangeInstructions,
+    },
+    AssignedValue,
+};
+use halo2_proofs_axiom::{
+    circuit::{Layouter, SimpleFloorPlanner},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use halo2curves_axiom::bn256::Fr;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    assign_u32, assign_u64, default_params,
+    gadgets::{
+        attestation::{bind_single_attestation, constrain_attestation, AttestationWitness},
+        blocklist::{MerklePathWitness, NonMembershipWitness, BLOCKLIST_TREE_DEPTH},
+    },
+    stats::{collect_stats, CircuitStats},
+    v1_instances, CircuitError, PublicInputs, DEFAULT_CURRENCY_CODE_BITS, NUM_INSTANCE_COLUMNS,
+    SAMPLE_INPUT,
+};
+
+/// Instance columns: the V1 columns followed by the blocklist root.
+pub const NUM_SCREENED_INSTANCE_COLUMNS: usize = NUM_INSTANCE_COLUMNS + 1;
+
+// Two depth-16 Merkle openings roughly triple the V1 cell count.
+const SCREENED_K: usize = 16;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ZkpfScreenedCircuitInput {
+    pub attestation: AttestationWitness,
+    pub public: PublicInputs,
+    pub blocklist_root: Fr,
+    pub non_membership: NonMembershipWitness,
+}
+
+#[derive(Clone, Debug)]
+pub struct ZkpfScreenedCircuit {
+    pub input: Option<ZkpfScreenedCircuitInput>,
+    params: BaseCircuitParams,
+    stage: CircuitBuilderStage,
+}
+
+impl Default for ZkpfScreenedCircuit {
+    fn default() -> Self {
+        Self {
+            input: None,
+            params: screened_params(),
+            stage: CircuitBuilderStage::Keygen,
+        }
+    }
+}
+
+impl ZkpfScreenedCircuit {
+    /// Creates a new circuit for MockProver testing.
+    /// Use `new_prover` for production proof generation.
+    pub fn new(input: Option<ZkpfScreenedCircuitInput>) -> Result<Self, CircuitError> {
+        let stage = match &input {
+            Some(input) => {
+                input.non_membership.validate()?;
+                CircuitBuilderStage::Mock
+            }
+            None => CircuitBuilderStage::Keygen,
+        };
+        Ok(Self {
+            input,
+            params: screened_params(),
+            stage,
+        })
+    }
+
+    /// Creates a circuit for production proof generation.
+    ///
+    /// Uses the Mock stage for the same reason as [`crate::ZkpfCircuit::new_prover`].
+    pub fn new_prover(input: ZkpfScreenedCircuitInput) -> Result<Self, CircuitError> {
+        input.non_membership.validate()?;
+        Ok(Self {
+            input: Some(input),
+            params: screened_params(),
+            stage: CircuitBuilderStage::Mock,
+        })
+    }
+}
+
+fn screened_params() -> BaseCircuitParams {
+    BaseCircuitParams {
+        k: SCREENED_K,
+        num_instance_columns: NUM_SCREENED_INSTANCE_COLUMNS,
+        ..default_params()
+    }
+}
+
+pub fn screened_public_instances(public: &PublicInputs, blocklist_root: Fr) -> Vec<Vec<Fr>> {
+    let mut cols = v1_instances(public);
+    cols.push(vec![blocklist_root]);
+    cols
+}
+
+/// Cell usage of the screened circuit.
+pub fn screened_circuit_stats(
+    input: &ZkpfScreenedCircuitInput,
+) -> Result<CircuitStats, CircuitError> {
+    input.non_membership.validate()?;
+    Ok(collect_stats(&screened_params(), |builder| {
+        build_screened_constraints(builder, input)
+    }))
+}
+
+impl Circuit<Fr> for ZkpfScreenedCircuit {
+    type Config = BaseConfig<Fr>;
+    type FloorPlanner = SimpleFloorPlanner;
+    type Params = BaseCircuitParams;
+
+    fn params(&self) -> Self::Params {
+        self.params.clone()
+    }
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            input: None,
+            params: self.params.clone(),
+            stage: CircuitBuilderStage::Keygen,
+        }
+    }
+
+    fn configure_with_params(
+        meta: &mut ConstraintSystem<Fr>,
+        params: Self::Params,
+    ) -> Self::Config {
+        BaseConfig::configure(meta, params)
+    }
+
+    fn configure(_: &mut ConstraintSystem<Fr>) -> Self::Config {
+        unreachable!("ZkpfScreenedCircuit must be configured with explicit parameters")
+    }
+
+    fn synthesize(&self, config: Self::Config, layouter: impl Layouter<Fr>) -> Result<(), Error> {
+        let sample;
+        let input = match self.input.as_ref() {
+            Some(input) => input,
+            None => {
+                sample = sample_input();
+                &sample
+            }
+        };
+        // `input` is public, so it may have changed since the constructor
+        // validated it.
+        input
+            .non_membership
+            .validate()
+            .map_err(|_| Error::Synthesis)?;
+
+        let mut builder = BaseCircuitBuilder::<Fr>::from_stage(self.stage)
+            .use_params(self.params.clone())
+            .use_instance_columns(self.params.num_instance_columns);
+
+        if let Some(bits) = self.params.lookup_bits {
+            builder = builder.use_lookup_bits(bits);
+        }
+
+        build_screened_constraints(&mut builder, input);
+
+        <BaseCircuitBuilder<Fr> as Circuit<Fr>>::synthesize(&builder, config, layouter)
+    }
+}
+
+/// Keygen placeholder: the sample attestation with an all-zero witness path.
+fn sample_input() -> ZkpfScreenedCircuitInput {
+    let path = |index| MerklePathWitness {
+        index,
+        siblings: vec![Fr::zero(); BLOCKLIST_TREE_DEPTH],
+    };
+    ZkpfScreenedCircuitInput {
+        attestation: SAMPLE_INPUT.attestation.clone(),
+        public: SAMPLE_INPUT.public.clone(),
+        blocklist_root: Fr::zero(),
+        non_membership: NonMembershipWitness {
+            low_leaf: Fr::zero(),
+            low_path: path(0),
+            high_leaf: -Fr::one(),
+            high_path: path(1),
+        },
+    }
+}
+
+fn build_screened_constraints(
+    builder: &mut BaseCircuitBuilder<Fr>,
+    input: &ZkpfScreenedCircuitInput,
+) {
+    let range = builder.range_chip();
+    let gate = range.gate();
+
+    let att = &input.attestation;
+    let pub_in = &input.public;
+
+    let ctx = builder.main(0);
+
+    let balance = assign_u64(ctx, &range, att.balance_raw);
+    let threshold = assign_u64(ctx, &range, pub_in.threshold_raw);
+    let req_currency = assign_u32(ctx, &range, pub_in.required_currency_code);
+    let current_epoch = assign_u64(ctx, &range, pub_in.current_epoch);
+    let verifier_scope = assign_u64(ctx, &range, pub_in.verifier_scope_id);
+    let policy_id = assign_u64(ctx, &range, pub_in.policy_id);
+    let blocklist_root = ctx.load_witness(input.blocklist_root);
+
+    let cells = constrain_attestation(
+        ctx,
+        &range,
+        att,
+        balance,
+        current_epoch,
+        req_currency,
+        DEFAULT_CURRENCY_CODE_BITS,
+    );
+
+    crate::gadgets::compare::enforce_geq(ctx, gate, &range, balance, threshold);
+
+    crate::gadgets::blocklist::enforce_non_membership(
+        ctx,
+        gate,
+        &range,
+        cells.account_id_hash,
+        blocklist_root,
+        &input.non_membership,
+    );
+
+    let (public_nullifier, public_pubkey_hash) = bind_single_attestation(
+        ctx,
+        gate,
+        &cells,
+        verifier_scope,
+        policy_id,
+        current_epoch,
+        pub_in,
+    );
+
+    let values: [AssignedValue<Fr>; NUM_SCREENED_INSTANCE_COLUMNS] = [
+        threshold,
+        req_currency,
+        current_epoch,
+        verifier_scope,
+        policy_id,
+        public_nullifier,
+        public_pubkey_hash,
+        blocklist_root,
+    ];
+    for (idx, value) in values.into_iter().enumerate() {
+        builder.assigned_instances[idx].push(value);
+    }
+}
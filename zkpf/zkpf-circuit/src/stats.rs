@@ -0,0 +1,81 @@
+// zkpf/zkpf-circuit/src/stats.rs
+// Numan Thabit 2025
+
+//! Circuit size reporting.
+//!
+//! Each circuit has a `*circuit_stats` helper that lays its constraints out in
+//! keygen mode and reports the cells they use. Comparing `estimated_k` against
+//! the configured `k` catches a constraint change that no longer fits before it
+//! shows up as a keygen failure or a slower prover.
+
+use halo2_base::gates::circuit::{
+    builder::BaseCircuitBuilder, BaseCircuitParams, CircuitBuilderStage,
+};
+use halo2curves_axiom::bn256::Fr;
+use serde::{Deserialize, Serialize};
+
+/// Rows at the bottom of every column that halo2 reserves for blinding.
+const RESERVED_ROWS: usize = 9;
+
+/// Cell usage of one circuit shape.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CircuitStats {
+    /// Advice cells across all phases.
+    pub advice_cells: usize,
+    /// Advice cells copied into the lookup columns for range checks.
+    pub lookup_cells: usize,
+    /// Smallest `k` at which the cells fit the configured column counts.
+    pub estimated_k: usize,
+    /// `k` the circuit is configured with.
+    pub k: usize,
+}
+
+impl CircuitStats {
+    /// Whether the constraints still fit at the configured `k`.
+    pub fn fits(&self) -> bool {
+        self.estimated_k <= self.k
+    }
+}
+
+/// Build a circuit's constraints in keygen mode and count the cells used.
+pub(crate) fn collect_stats(
+    params: &BaseCircuitParams,
+    build: impl FnOnce(&mut BaseCircuitBuilder<Fr>),
+) -> CircuitStats {
+    let mut builder = BaseCircuitBuilder::<Fr>::from_stage(CircuitBuilderStage::Keygen)
+        .use_params(params.clone())
+        .use_instance_columns(params.num_instance_columns);
+    if let Some(bits) = params.lookup_bits {
+        builder = builder.use_lookup_bits(bits);
+    }
+
+    build(&mut builder);
+
+    let stats = builder.statistics();
+    let advice_cells = stats.gate.total_advice_per_phase.iter().sum();
+    let lookup_cells = stats.total_lookup_advice_per_phase.iter().sum();
+    CircuitStats {
+        advice_cells,
+        lookup_cells,
+        estimated_k: estimate_k(params, advice_cells, lookup_cells),
+        k: params.k,
+    }
+}
+
+/// Smallest `k` whose usable rows hold `advice_cells` and `lookup_cells` in the
+/// configured number of columns, and the lookup table itself.
+fn estimate_k(params: &BaseCircuitParams, advice_cells: usize, lookup_cells: usize) -> usize {
+    let advice_columns: usize = params.num_advice_per_phase.iter().sum();
+    let lookup_columns: usize = params.num_lookup_advice_per_phase.iter().sum();
+    let fits = |k: usize| {
+        let rows = (1usize << k) - RESERVED_ROWS;
+        advice_cells.div_ceil(rows) <= advice_columns
+            && lookup_cells.div_ceil(rows) <= lookup_columns
+    };
+
+    let mut k = params.lookup_bits.map_or(4, |bits| bits + 1);
+    while !fits(k) {
+        k += 1;
+    }
+    k
+}
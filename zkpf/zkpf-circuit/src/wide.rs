@@ -0,0 +1,256 @@
+// zkpf/zkpf-circuit/src/wide.rs
+// Numan Thabit 2025
+
+//! Proof of funds for balances wider than 64 bits.
+//!
+//! In the smallest unit of an 18-decimal asset, `u64::MAX` is about 18.4 whole
+//! tokens, so [`crate::ZkpfCircuit`]'s 64-bit balance and threshold cannot hold
+//! a realistic balance. This circuit runs the same checks with both values as
+//! `u128`, witnessed as two range-checked 64-bit limbs and compared with
+//! [`enforce_geq_u128`]. The attestation digest and the V1 `threshold_raw`
+//! column carry the recombined value, which is the same field element as the
+//! 64-bit circuit's when the high limb is zero.
+//!
+//! Which width a rail uses is given by [`BalanceWidth`]; ZEC and fiat rails
+//! stay on the 64-bit circuit.
+
+use halo2_base::{
+    gates::{
+        circuit::builder::BaseCircuitBuilder,
+        circuit::{BaseCircuitParams, BaseConfig, CircuitBuilderStage},
+        RangeInstructions,
+    },
+    AssignedValue,
+};
+use halo2_proofs_axiom::{
+    circuit::{Layouter, SimpleFloorPlanner},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use halo2curves_axiom::{bn256::Fr, ff::PrimeField};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    assign_u32, assign_u64, default_params,
+    gadgets::{
+        attestation::{bind_single_attestation, constrain_attestation, AttestationWitness},
+        compare::{enforce_geq_u128, AssignedU128},
+    },
+    stats::{collect_stats, CircuitStats},
+    v1_instances, PublicInputs, DEFAULT_CURRENCY_CODE_BITS, NUM_INSTANCE_COLUMNS, SAMPLE_INPUT,
+};
+
+/// Width of the balance and threshold a rail proves with.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BalanceWidth {
+    /// `u64` raw units, proven by [`crate::ZkpfCircuit`].
+    #[default]
+    U64,
+    /// `u128` raw units, proven by [`ZkpfWideCircuit`].
+    U128,
+}
+
+impl BalanceWidth {
+    pub fn bits(self) -> usize {
+        match self {
+            Self::U64 => 64,
+            Self::U128 => 128,
+        }
+    }
+
+    /// Whether `value` can be proven at this width.
+    pub fn fits(self, value: u128) -> bool {
+        match self {
+            Self::U64 => value <= u128::from(u64::MAX),
+            Self::U128 => true,
+        }
+    }
+}
+
+/// Witness for the wide circuit.
+///
+/// `balance_raw` and `threshold_raw` replace `attestation.balance_raw` and
+/// `public.threshold_raw`, which are ignored; `attestation.message_hash` must
+/// commit to the wide balance.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ZkpfWideCircuitInput {
+    pub attestation: AttestationWitness,
+    pub public: PublicInputs,
+    pub balance_raw: u128,
+    pub threshold_raw: u128,
+}
+
+#[derive(Clone, Debug)]
+pub struct ZkpfWideCircuit {
+    pub input: Option<ZkpfWideCircuitInput>,
+    params: BaseCircuitParams,
+    stage: CircuitBuilderStage,
+}
+
+impl Default for ZkpfWideCircuit {
+    fn default() -> Self {
+        Self {
+            input: None,
+            params: default_params(),
+            stage: CircuitBuilderStage::Keygen,
+        }
+    }
+}
+
+impl ZkpfWideCircuit {
+    /// Creates a new circuit for MockProver testing.
+    /// Use `new_prover` for production proof generation.
+    pub fn new(input: Option<ZkpfWideCircuitInput>) -> Self {
+        let stage = if input.is_some() {
+            CircuitBuilderStage::Mock
+        } else {
+            CircuitBuilderStage::Keygen
+        };
+        Self {
+            input,
+            params: default_params(),
+            stage,
+        }
+    }
+
+    /// Creates a circuit for production proof generation.
+    ///
+    /// Uses the Mock stage for the same reason as [`crate::ZkpfCircuit::new_prover`].
+    pub fn new_prover(input: ZkpfWideCircuitInput) -> Self {
+        Self {
+            input: Some(input),
+            params: default_params(),
+            stage: CircuitBuilderStage::Mock,
+        }
+    }
+}
+
+/// The V1 columns, with `threshold_raw` as the full 128-bit threshold.
+pub fn wide_public_instances(public: &PublicInputs, threshold_raw: u128) -> Vec<Vec<Fr>> {
+    let mut cols = v1_instances(public);
+    cols[0] = vec![Fr::from_u128(threshold_raw)];
+    cols
+}
+
+/// Cell usage of the wide circuit.
+pub fn wide_circuit_stats(input: &ZkpfWideCircuitInput) -> CircuitStats {
+    collect_stats(&default_params(), |builder| {
+        build_wide_constraints(builder, input)
+    })
+}
+
+impl Circuit<Fr> for ZkpfWideCircuit {
+    type Config = BaseConfig<Fr>;
+    type FloorPlanner = SimpleFloorPlanner;
+    type Params = BaseCircuitParams;
+
+    fn params(&self) -> Self::Params {
+        self.params.clone()
+    }
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            input: None,
+            params: self.params.clone(),
+            stage: CircuitBuilderStage::Keygen,
+        }
+    }
+
+    fn configure_with_params(
+        meta: &mut ConstraintSystem<Fr>,
+        params: Self::Params,
+    ) -> Self::Config {
+        BaseConfig::configure(meta, params)
+    }
+
+    fn configure(_: &mut ConstraintSystem<Fr>) -> Self::Config {
+        unreachable!("ZkpfWideCircuit must be configured with explicit parameters")
+    }
+
+    fn synthesize(&self, config: Self::Config, layouter: impl Layouter<Fr>) -> Result<(), Error> {
+        let sample;
+        let input = match self.input.as_ref() {
+            Some(input) => input,
+            None => {
+                sample = sample_input();
+                &sample
+            }
+        };
+
+        let mut builder = BaseCircuitBuilder::<Fr>::from_stage(self.stage)
+            .use_params(self.params.clone())
+            .use_instance_columns(self.params.num_instance_columns);
+
+        if let Some(bits) = self.params.lookup_bits {
+            builder = builder.use_lookup_bits(bits);
+        }
+
+        build_wide_constraints(&mut builder, input);
+
+        <BaseCircuitBuilder<Fr> as Circuit<Fr>>::synthesize(&builder, config, layouter)
+    }
+}
+
+/// Keygen placeholder: the sample attestation, whose balance fits the low limb.
+fn sample_input() -> ZkpfWideCircuitInput {
+    ZkpfWideCircuitInput {
+        attestation: SAMPLE_INPUT.attestation.clone(),
+        public: SAMPLE_INPUT.public.clone(),
+        balance_raw: SAMPLE_INPUT.attestation.balance_raw.into(),
+        threshold_raw: SAMPLE_INPUT.public.threshold_raw.into(),
+    }
+}
+
+fn build_wide_constraints(builder: &mut BaseCircuitBuilder<Fr>, input: &ZkpfWideCircuitInput) {
+    let range = builder.range_chip();
+    let gate = range.gate();
+
+    let att = &input.attestation;
+    let pub_in = &input.public;
+
+    let ctx = builder.main(0);
+
+    let balance_limbs = AssignedU128::assign(ctx, &range, input.balance_raw);
+    let threshold_limbs = AssignedU128::assign(ctx, &range, input.threshold_raw);
+    let balance = balance_limbs.value(ctx, gate);
+    let threshold = threshold_limbs.value(ctx, gate);
+    let req_currency = assign_u32(ctx, &range, pub_in.required_currency_code);
+    let current_epoch = assign_u64(ctx, &range, pub_in.current_epoch);
+    let verifier_scope = assign_u64(ctx, &range, pub_in.verifier_scope_id);
+    let policy_id = assign_u64(ctx, &range, pub_in.policy_id);
+
+    let cells = constrain_attestation(
+        ctx,
+        &range,
+        att,
+        balance,
+        current_epoch,
+        req_currency,
+        DEFAULT_CURRENCY_CODE_BITS,
+    );
+
+    enforce_geq_u128(ctx, gate, &range, balance_limbs, threshold_limbs);
+
+    let (public_nullifier, public_pubkey_hash) = bind_single_attestation(
+        ctx,
+        gate,
+        &cells,
+        verifier_scope,
+        policy_id,
+        current_epoch,
+        pub_in,
+    );
+
+    let values: [AssignedValue<Fr>; NUM_INSTANCE_COLUMNS] = [
+        threshold,
+        req_currency,
+        current_epoch,
+        verifier_scope,
+        policy_id,
+        public_nullifier,
+        public_pubkey_hash,
+    ];
+    for (idx, value) in values.into_iter().enumerate() {
+        builder.assigned_instances[idx].push(value);
+    }
+}
@@ -1,4 +1,6 @@
-use halo2_base::poseidon::hasher::spec::OptimizedPoseidonSpec;
+use halo2_base::{
+    gates::circuit::BaseCircuitParams, poseidon::hasher::spec::OptimizedPoseidonSpec,
+};
 use halo2_proofs_axiom::{dev::MockProver, plonk::Circuit};
 use halo2curves_axiom::{
     bn256::Fr,
@@ -7,8 +9,33 @@ use halo2curves_axiom::{
 use secp256k1::{ecdsa::Signature, Message, PublicKey, Secp256k1, SecretKey};
 use std::sync::OnceLock;
 use zkpf_circuit::{
-    gadgets::attestation::{AttestationWitness, EcdsaSignature, Secp256k1Pubkey},
-    PublicInputs, ZkpfCircuit, ZkpfCircuitInput,
+    aggregate::{
+        aggregate_circuit_stats, aggregate_public_instances, ZkpfAggregateCircuit,
+        ZkpfAggregateCircuitInput, MAX_AGGREGATE_ACCOUNTS, NULLIFIER_COLUMN,
+    },
+    circuit_stats,
+    converted::{
+        converted_circuit_stats, converted_public_instances, ConversionRate, ZkpfConvertedCircuit,
+        ZkpfConvertedCircuitInput,
+    },
+    gadgets::{
+        attestation::{AttestationWitness, EcdsaSignature, Secp256k1Pubkey, SignatureFormat},
+        blocklist::{MerklePathWitness, NonMembershipWitness, BLOCKLIST_TREE_DEPTH},
+    },
+    multi_custodian::{
+        multi_custodian_circuit_stats, ZkpfMultiCustodianCircuit, ZkpfMultiCustodianCircuitInput,
+        MAX_CUSTODIANS,
+    },
+    notes::{pad_note_witnesses, pad_notes, TooManyNotes},
+    ranged::{
+        range_circuit_stats, range_public_instances, ZkpfRangeCircuit, ZkpfRangeCircuitInput,
+    },
+    screened::{
+        screened_circuit_stats, screened_public_instances, ZkpfScreenedCircuit,
+        ZkpfScreenedCircuitInput,
+    },
+    wide::{wide_circuit_stats, wide_public_instances, ZkpfWideCircuit, ZkpfWideCircuitInput},
+    CircuitShape, PublicInputs, ZkpfCircuit, ZkpfCircuitInput,
 };
 
 // Poseidon parameters - MUST match zkpf_circuit::gadgets::poseidon constants
@@ -175,20 +202,901 @@ fn test_currency_wildcard_accepts_different_currency() {
     run_mock_prover(input).assert_satisfied();
 }
 
-fn valid_input() -> ZkpfCircuitInput {
-    FixtureBuilder::new().build()
+#[test]
+fn test_currency_code_wider_than_default_bits_fails() {
+    use zkpf_circuit::{gadgets::CURRENCY_WILDCARD, DEFAULT_CURRENCY_CODE_BITS};
+
+    let wide = 1u32 << DEFAULT_CURRENCY_CODE_BITS;
+    let input = FixtureBuilder::new()
+        .with_att(|att| att.currency_code_int = wide)
+        .with_public(|public| public.required_currency_code = CURRENCY_WILDCARD)
+        .build();
+    assert!(run_mock_prover(input.clone()).verify().is_err());
+
+    // A wider check accepts it, at the cost of separate keys.
+    let public_instances = zkpf_circuit::public_instances(&input.public);
+    let circuit = ZkpfCircuit::new(Some(input))
+        .with_currency_code_bits(32)
+        .expect("32 bits is a valid width");
+    run_mock(&circuit, public_instances).assert_satisfied();
+
+    assert!(ZkpfCircuit::new(None).with_currency_code_bits(0).is_err());
+    assert!(ZkpfCircuit::new(None).with_currency_code_bits(33).is_err());
 }
 
-fn run_mock_prover(input: ZkpfCircuitInput) -> MockProver<Fr> {
+/// The derived nullifier and custodian hash depend only on the witness: they
+/// match the off-circuit derivation for varied inputs, are stable across calls,
+/// and are what the circuit accepts.
+#[test]
+fn test_derived_public_inputs_are_deterministic() {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    let mut rng = StdRng::seed_from_u64(0x5EED);
+    for _ in 0..16 {
+        let account_hash = Fr::from(rng.gen::<u64>());
+        let scope: u64 = rng.gen();
+        let policy: u64 = rng.gen();
+        let epoch = BASE_ISSUED_AT + rng.gen_range(0..1_000_000);
+        let input = FixtureBuilder::new()
+            .with_att(|att| att.account_id_hash = account_hash)
+            .with_public(|public| {
+                public.verifier_scope_id = scope;
+                public.policy_id = policy;
+                public.current_epoch = epoch;
+            })
+            .build();
+
+        let derived = input.derived_public_inputs();
+        assert_eq!(derived.nullifier, input.public.nullifier);
+        assert_eq!(
+            derived.custodian_pubkey_hash,
+            input.public.custodian_pubkey_hash
+        );
+        assert_eq!(derived.threshold_raw, input.public.threshold_raw);
+        let again = input.derived_public_inputs();
+        assert_eq!(again.nullifier, derived.nullifier);
+        assert_eq!(again.custodian_pubkey_hash, derived.custodian_pubkey_hash);
+    }
+
+    // Wrong public values are replaced by the ones the circuit accepts.
+    let mut input = valid_input();
+    input.public.nullifier = Fr::from(1u64);
+    input.public.custodian_pubkey_hash = Fr::from(2u64);
+    input.public = input.derived_public_inputs();
+    run_mock_prover(input).assert_satisfied();
+}
+
+#[test]
+fn test_zec_currency_code_fits_default_bits() {
+    let input = FixtureBuilder::new()
+        .with_att(|att| att.currency_code_int = 999_001)
+        .with_public(|public| public.required_currency_code = 999_001)
+        .build();
+    run_mock_prover(input).assert_satisfied();
+}
+
+// ============================================================
+// Aggregate Circuit Tests
+// ============================================================
+
+#[test]
+fn test_aggregate_valid_proof_mock() {
+    let input = aggregate_input(&[400_000_000, 350_000_000, 250_000_000], BASE_THRESHOLD);
+    run_aggregate_mock_prover(input).assert_satisfied();
+}
+
+/// No single account meets the threshold, but the sum does.
+#[test]
+fn test_aggregate_sum_meets_threshold_passes() {
+    let input = aggregate_input(&[BASE_THRESHOLD / 2, BASE_THRESHOLD / 2], BASE_THRESHOLD);
+    run_aggregate_mock_prover(input).assert_satisfied();
+}
+
+#[test]
+fn test_aggregate_sum_below_threshold_fails() {
+    let input = aggregate_input(
+        &[BASE_THRESHOLD / 2, BASE_THRESHOLD / 2 - 1],
+        BASE_THRESHOLD,
+    );
+    assert!(run_aggregate_mock_prover(input).verify().is_err());
+}
+
+/// Sums above u64::MAX must not wrap around the comparison.
+#[test]
+fn test_aggregate_sum_exceeding_u64_passes() {
+    let input = aggregate_input(&[u64::MAX, u64::MAX], u64::MAX);
+    run_aggregate_mock_prover(input).assert_satisfied();
+}
+
+#[test]
+fn test_aggregate_duplicate_account_fails() {
+    let mut input = aggregate_input(&[BASE_BALANCE, BASE_BALANCE], BASE_THRESHOLD);
+    input.attestations[1] = input.attestations[0].clone();
+    input.extra_nullifiers[0] = input.public.nullifier;
+    assert!(run_aggregate_mock_prover(input).verify().is_err());
+}
+
+/// Every attestation gets the custodial circuit's currency-width check.
+#[test]
+fn test_aggregate_currency_code_wider_than_default_bits_fails() {
+    use zkpf_circuit::{gadgets::CURRENCY_WILDCARD, DEFAULT_CURRENCY_CODE_BITS};
+
+    let mut input = aggregate_input(&[BASE_BALANCE, BASE_BALANCE], BASE_THRESHOLD);
+    input.public.required_currency_code = CURRENCY_WILDCARD;
+    run_aggregate_mock_prover(input.clone()).assert_satisfied();
+
+    input.attestations[1] = FixtureBuilder::new()
+        .with_att(|att| {
+            att.currency_code_int = 1u32 << DEFAULT_CURRENCY_CODE_BITS;
+            att.attestation_id = BASE_ATTESTATION_ID + 1;
+            att.account_id_hash = Fr::from(ACCOUNT_HASH_SEED + 1);
+        })
+        .build()
+        .attestation;
+    assert!(run_aggregate_mock_prover(input).verify().is_err());
+}
+
+/// Each account's nullifier is the one a single-account proof of it carries,
+/// so an account already spent alone or in another set is spent here too.
+#[test]
+fn test_aggregate_exposes_each_account_nullifier() {
+    let input = aggregate_input(&[BASE_BALANCE, BASE_BALANCE, BASE_BALANCE], BASE_THRESHOLD);
+    let single = FixtureBuilder::new()
+        .with_att(|att| att.account_id_hash = Fr::from(ACCOUNT_HASH_SEED + 1))
+        .build();
+    let instances = aggregate_public_instances(&input.public, &input.extra_nullifiers);
+    assert_eq!(instances[NULLIFIER_COLUMN].len(), 3);
+    assert_eq!(instances[NULLIFIER_COLUMN][1], single.public.nullifier);
+    run_aggregate_mock_prover(input).assert_satisfied();
+}
+
+#[test]
+fn test_aggregate_nullifier_mismatch_fails() {
+    let mut input = aggregate_input(&[BASE_BALANCE, BASE_BALANCE], BASE_THRESHOLD);
+    input.public.nullifier += Fr::ONE;
+    assert!(run_aggregate_mock_prover(input).verify().is_err());
+
+    let mut input = aggregate_input(&[BASE_BALANCE, BASE_BALANCE], BASE_THRESHOLD);
+    input.extra_nullifiers[0] += Fr::ONE;
+    assert!(run_aggregate_mock_prover(input).verify().is_err());
+}
+
+#[test]
+fn test_aggregate_account_count_bounds() {
+    assert!(ZkpfAggregateCircuit::keygen(0).is_err());
+    assert!(ZkpfAggregateCircuit::keygen(MAX_AGGREGATE_ACCOUNTS).is_ok());
+    assert!(ZkpfAggregateCircuit::keygen(MAX_AGGREGATE_ACCOUNTS + 1).is_err());
+
+    let mut input = aggregate_input(&[BASE_BALANCE, BASE_BALANCE], BASE_THRESHOLD);
+    assert!(ZkpfAggregateCircuit::new(3, Some(input.clone())).is_err());
+    input.extra_nullifiers.clear();
+    assert!(ZkpfAggregateCircuit::new_prover(input).is_err());
+}
+
+fn aggregate_input(balances: &[u64], threshold: u64) -> ZkpfAggregateCircuitInput {
+    let mut first_public = None;
+    let mut nullifiers = Vec::with_capacity(balances.len());
+    let attestations: Vec<AttestationWitness> = balances
+        .iter()
+        .enumerate()
+        .map(|(idx, balance)| {
+            let input = FixtureBuilder::new()
+                .with_att(|att| {
+                    att.balance_raw = *balance;
+                    att.attestation_id = BASE_ATTESTATION_ID + idx as u64;
+                    att.account_id_hash = Fr::from(ACCOUNT_HASH_SEED + idx as u64);
+                })
+                .with_public(|public| public.threshold_raw = threshold)
+                .build();
+            nullifiers.push(input.public.nullifier);
+            first_public.get_or_insert(input.public);
+            input.attestation
+        })
+        .collect();
+
+    let mut public = first_public.expect("at least one balance");
+    public.custodian_pubkey_hash = attestations.iter().fold(Fr::ZERO, |acc, att| {
+        acc + hash_custodian_pubkey(&att.custodian_pubkey)
+    });
+
+    ZkpfAggregateCircuitInput {
+        attestations,
+        public,
+        extra_nullifiers: nullifiers.split_off(1),
+    }
+}
+
+fn aggregate_nullifier(attestations: &[AttestationWitness], public: &PublicInputs) -> Fr {
+    let digest = attestations.iter().fold(Fr::ZERO, |acc, att| {
+        acc + poseidon_hash(&[att.account_id_hash])
+    });
+    poseidon_hash(&[
+        digest,
+        fr_from_u64(public.verifier_scope_id),
+        fr_from_u64(public.policy_id),
+        fr_from_u64(public.current_epoch),
+    ])
+}
+
+fn run_aggregate_mock_prover(input: ZkpfAggregateCircuitInput) -> MockProver<Fr> {
+    let public_instances = aggregate_public_instances(&input.public, &input.extra_nullifiers);
+    let circuit = ZkpfAggregateCircuit::new_prover(input).expect("valid account count");
+    run_mock(&circuit, public_instances)
+}
+
+// ============================================================
+// Multi-Custodian Tests
+// ============================================================
+
+/// No single custodian meets the threshold, but the combined balance does.
+#[test]
+fn test_multi_custodian_sum_meets_threshold_passes() {
+    let third = BASE_THRESHOLD / 3 + 1;
+    let input = multi_custodian_input(&[third, third, third], BASE_THRESHOLD);
+    run_multi_custodian_mock_prover(input).assert_satisfied();
+}
+
+#[test]
+fn test_multi_custodian_sum_below_threshold_fails() {
+    let balances = [BASE_THRESHOLD / 2, BASE_THRESHOLD / 2 - 1];
+    let input = multi_custodian_input(&balances, BASE_THRESHOLD);
+    assert!(run_multi_custodian_mock_prover(input).verify().is_err());
+}
+
+#[test]
+fn test_multi_custodian_repeated_custodian_fails() {
+    let mut input = multi_custodian_input(&[BASE_BALANCE, BASE_BALANCE], BASE_THRESHOLD);
+    input.attestations[1] = input.attestations[0].clone();
+    input.public.custodian_pubkey_hash = custodian_root(&input.attestations);
+    assert!(run_multi_custodian_mock_prover(input).verify().is_err());
+}
+
+/// Custodians must be listed by increasing id, so the root has one ordering.
+#[test]
+fn test_multi_custodian_unsorted_custodians_fail() {
+    let mut input = multi_custodian_input(&[BASE_BALANCE, BASE_THRESHOLD], BASE_THRESHOLD);
+    input.attestations.reverse();
+    input.public.custodian_pubkey_hash = custodian_root(&input.attestations);
+    assert!(run_multi_custodian_mock_prover(input).verify().is_err());
+}
+
+#[test]
+fn test_multi_custodian_root_mismatch_fails() {
+    let mut input = multi_custodian_input(&[BASE_BALANCE, BASE_BALANCE], BASE_THRESHOLD);
+    input.public.custodian_pubkey_hash += Fr::ONE;
+    assert!(run_multi_custodian_mock_prover(input).verify().is_err());
+}
+
+#[test]
+fn test_multi_custodian_count_bounds() {
+    assert!(ZkpfMultiCustodianCircuit::keygen(0).is_err());
+    assert!(ZkpfMultiCustodianCircuit::keygen(MAX_CUSTODIANS).is_ok());
+    assert!(ZkpfMultiCustodianCircuit::keygen(MAX_CUSTODIANS + 1).is_err());
+}
+
+fn multi_custodian_input(balances: &[u64], threshold: u64) -> ZkpfMultiCustodianCircuitInput {
+    let mut first_public = None;
+    let attestations: Vec<AttestationWitness> = balances
+        .iter()
+        .enumerate()
+        .map(|(idx, balance)| {
+            let input = FixtureBuilder::new()
+                .with_att(|att| {
+                    att.balance_raw = *balance;
+                    att.custodian_id = BASE_CUSTODIAN + idx as u32;
+                })
+                .with_public(|public| public.threshold_raw = threshold)
+                .build();
+            first_public.get_or_insert(input.public);
+            input.attestation
+        })
+        .collect();
+
+    let mut public = first_public.expect("at least one balance");
+    public.nullifier = aggregate_nullifier(&attestations, &public);
+    public.custodian_pubkey_hash = custodian_root(&attestations);
+
+    ZkpfMultiCustodianCircuitInput {
+        attestations,
+        public,
+    }
+}
+
+/// Root of the zero-padded custodian tree, built level by level.
+fn custodian_root(attestations: &[AttestationWitness]) -> Fr {
+    let mut level: Vec<Fr> = attestations
+        .iter()
+        .map(|att| hash_custodian_pubkey(&att.custodian_pubkey))
+        .collect();
+    level.resize(MAX_CUSTODIANS, Fr::ZERO);
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| poseidon_hash(&[pair[0], pair[1]]))
+            .collect();
+    }
+    level[0]
+}
+
+fn run_multi_custodian_mock_prover(input: ZkpfMultiCustodianCircuitInput) -> MockProver<Fr> {
     let public_instances = zkpf_circuit::public_instances(&input.public);
+    let circuit = ZkpfMultiCustodianCircuit::new_prover(input).expect("valid custodian count");
+    run_mock(&circuit, public_instances)
+}
+
+// ============================================================
+// Currency Conversion Tests
+// ============================================================
+
+/// 1000 units at a rate of 2.5 converts to exactly 2500.
+#[test]
+fn test_converted_exact_threshold_passes() {
+    let input = converted_input(1_000, 25, 10, 2_500);
+    run_converted_mock_prover(input).assert_satisfied();
+}
+
+#[test]
+fn test_converted_one_above_threshold_fails() {
+    let input = converted_input(1_000, 25, 10, 2_501);
+    assert!(run_converted_mock_prover(input).verify().is_err());
+}
+
+/// 1 * 2/3 floors to 0, so a threshold of 1 must not be met.
+#[test]
+fn test_converted_rounds_down() {
+    let input = converted_input(1, 2, 3, 1);
+    assert!(run_converted_mock_prover(input).verify().is_err());
+
+    let input = converted_input(1, 2, 3, 0);
+    run_converted_mock_prover(input).assert_satisfied();
+}
+
+/// 3 * 1/3 is exactly 1 with no rounding loss.
+#[test]
+fn test_converted_exact_division_passes() {
+    let input = converted_input(3, 1, 3, 1);
+    run_converted_mock_prover(input).assert_satisfied();
+}
+
+/// Products close to 2^128 must not wrap around the comparison.
+#[test]
+fn test_converted_large_values_do_not_overflow() {
+    let input = converted_input(u64::MAX, u64::MAX, 1, u64::MAX);
+    run_converted_mock_prover(input).assert_satisfied();
+
+    let input = converted_input(1, 1, u64::MAX, 1);
+    assert!(run_converted_mock_prover(input).verify().is_err());
+}
+
+#[test]
+fn test_converted_zero_denominator_fails() {
+    let input = converted_input(1_000, 1, 0, 0);
+    assert!(run_converted_mock_prover(input).verify().is_err());
+}
+
+#[test]
+fn test_converted_future_rate_epoch_fails() {
+    let mut input = converted_input(1_000, 1, 1, 1_000);
+    input.rate.rate_epoch = input.public.current_epoch + 1;
+    assert!(run_converted_mock_prover(input).verify().is_err());
+}
+
+fn converted_input(
+    balance: u64,
+    numerator: u64,
+    denominator: u64,
+    threshold: u64,
+) -> ZkpfConvertedCircuitInput {
+    let base = FixtureBuilder::new()
+        .with_att(|att| att.balance_raw = balance)
+        .with_public(|public| public.threshold_raw = threshold)
+        .build();
+    ZkpfConvertedCircuitInput {
+        rate: ConversionRate {
+            numerator,
+            denominator,
+            rate_epoch: base.public.current_epoch,
+        },
+        attestation: base.attestation,
+        public: base.public,
+    }
+}
+
+fn run_converted_mock_prover(input: ZkpfConvertedCircuitInput) -> MockProver<Fr> {
+    let public_instances = converted_public_instances(&input.public, &input.rate);
+    let circuit = ZkpfConvertedCircuit::new(Some(input));
+    run_mock(&circuit, public_instances)
+}
+
+// ============================================================
+// Blocklist Screening Tests
+// ============================================================
+
+#[test]
+fn test_screened_valid_proof_mock() {
+    let account = valid_input().attestation.account_id_hash;
+    // Leaves: [0, 1, 2, account + 1, p - 1]
+    let blocklist = [Fr::from(2u64), account + Fr::ONE, Fr::from(1u64)];
+    let input = screened_input(&blocklist, 2, 3);
+    run_screened_mock_prover(input).assert_satisfied();
+}
+
+#[test]
+fn test_screened_blocked_account_fails() {
+    let account = valid_input().attestation.account_id_hash;
+    // Leaves: [0, 1, account, account + 1, p - 1]
+    let blocklist = [Fr::from(1u64), account, account + Fr::ONE];
+    for (low, high) in [(1, 2), (2, 3)] {
+        let input = screened_input(&blocklist, low, high);
+        assert!(run_screened_mock_prover(input).verify().is_err());
+    }
+}
+
+/// Leaves that bracket the account but skip an entry must be rejected.
+#[test]
+fn test_screened_non_adjacent_leaves_fail() {
+    let account = valid_input().attestation.account_id_hash;
+    // Leaves: [0, 1, account + 1, account + 2, p - 1]
+    let blocklist = [Fr::from(1u64), account + Fr::ONE, account + Fr::from(2u64)];
+    let input = screened_input(&blocklist, 1, 3);
+    assert!(run_screened_mock_prover(input).verify().is_err());
+}
+
+#[test]
+fn test_screened_short_path_is_rejected_before_synthesis() {
+    let account = valid_input().attestation.account_id_hash;
+    let mut input = screened_input(&[account + Fr::ONE], 1, 2);
+    input.non_membership.high_path.siblings.pop();
+
+    let err = ZkpfScreenedCircuit::new(Some(input.clone())).unwrap_err();
+    assert!(err.to_string().contains("high_path"), "{err}");
+    assert!(ZkpfScreenedCircuit::new_prover(input.clone()).is_err());
+    assert!(screened_circuit_stats(&input).is_err());
+}
+
+#[test]
+fn test_screened_wrong_root_fails() {
+    let account = valid_input().attestation.account_id_hash;
+    let blocklist = [Fr::from(1u64), account + Fr::ONE];
+    let input = screened_input(&blocklist, 1, 2);
+
+    let mut instances = screened_public_instances(&input.public, input.blocklist_root);
+    *instances.last_mut().unwrap() = vec![input.blocklist_root + Fr::ONE];
+    let circuit = ZkpfScreenedCircuit::new(Some(input)).unwrap();
+    assert!(run_mock(&circuit, instances).verify().is_err());
+}
+
+/// Builds the padded sorted tree over `blocklist` and opens leaves `low` and `high`.
+fn screened_input(blocklist: &[Fr], low: usize, high: usize) -> ZkpfScreenedCircuitInput {
+    let mut leaves = vec![Fr::ZERO, -Fr::ONE];
+    leaves.extend_from_slice(blocklist);
+    leaves.sort_by_key(fr_to_be_bytes);
+
+    let mut padding = -Fr::ONE;
+    let mut levels = vec![leaves];
+    for level in 0..BLOCKLIST_TREE_DEPTH {
+        if levels[level].len() % 2 == 1 {
+            levels[level].push(padding);
+        }
+        let parents: Vec<Fr> = levels[level]
+            .chunks(2)
+            .map(|pair| poseidon_hash(&[pair[0], pair[1]]))
+            .collect();
+        levels.push(parents);
+        padding = poseidon_hash(&[padding, padding]);
+    }
+    let path = |index: usize| MerklePathWitness {
+        index: index as u64,
+        siblings: (0..BLOCKLIST_TREE_DEPTH)
+            .map(|level| levels[level][(index >> level) ^ 1])
+            .collect(),
+    };
+
+    let base = valid_input();
+    ZkpfScreenedCircuitInput {
+        attestation: base.attestation,
+        public: base.public,
+        blocklist_root: levels[BLOCKLIST_TREE_DEPTH][0],
+        non_membership: NonMembershipWitness {
+            low_leaf: levels[0][low],
+            low_path: path(low),
+            high_leaf: levels[0][high],
+            high_path: path(high),
+        },
+    }
+}
+
+fn run_screened_mock_prover(input: ZkpfScreenedCircuitInput) -> MockProver<Fr> {
+    let public_instances = screened_public_instances(&input.public, input.blocklist_root);
+    let circuit = ZkpfScreenedCircuit::new(Some(input)).unwrap();
+    run_mock(&circuit, public_instances)
+}
+
+// ============================================================
+// Balance Range Tests
+// ============================================================
+
+const RANGE_MIN: u64 = 10_000;
+const RANGE_MAX: u64 = 50_000;
+
+#[test]
+fn test_range_boundaries_are_inclusive() {
+    for balance in [RANGE_MIN, RANGE_MIN + 1, RANGE_MAX - 1, RANGE_MAX] {
+        let input = range_input(balance, RANGE_MIN, RANGE_MAX);
+        run_range_mock_prover(input).assert_satisfied();
+    }
+}
+
+#[test]
+fn test_range_below_min_fails() {
+    let input = range_input(RANGE_MIN - 1, RANGE_MIN, RANGE_MAX);
+    assert!(run_range_mock_prover(input).verify().is_err());
+}
+
+#[test]
+fn test_range_above_max_fails() {
+    let input = range_input(RANGE_MAX + 1, RANGE_MIN, RANGE_MAX);
+    assert!(run_range_mock_prover(input).verify().is_err());
+}
+
+/// A degenerate band pins the balance to a single value.
+#[test]
+fn test_range_min_equal_max() {
+    let input = range_input(RANGE_MIN, RANGE_MIN, RANGE_MIN);
+    run_range_mock_prover(input).assert_satisfied();
+
+    let input = range_input(RANGE_MIN + 1, RANGE_MIN, RANGE_MIN);
+    assert!(run_range_mock_prover(input).verify().is_err());
+}
+
+#[test]
+fn test_range_extreme_bounds() {
+    let input = range_input(0, 0, u64::MAX);
+    run_range_mock_prover(input).assert_satisfied();
+
+    let input = range_input(u64::MAX, 0, u64::MAX);
+    run_range_mock_prover(input).assert_satisfied();
+}
+
+#[test]
+fn test_range_wrong_public_max_fails() {
+    let input = range_input(RANGE_MAX, RANGE_MIN, RANGE_MAX);
+    let mut instances = range_public_instances(&input.public, input.threshold_max);
+    *instances.last_mut().unwrap() = vec![fr_from_u64(RANGE_MAX - 1)];
+    let circuit = ZkpfRangeCircuit::new(Some(input));
+    assert!(run_mock(&circuit, instances).verify().is_err());
+}
+
+fn range_input(balance: u64, min: u64, max: u64) -> ZkpfRangeCircuitInput {
+    let base = FixtureBuilder::new()
+        .with_att(|att| att.balance_raw = balance)
+        .with_public(|public| public.threshold_raw = min)
+        .build();
+    ZkpfRangeCircuitInput {
+        attestation: base.attestation,
+        public: base.public,
+        threshold_max: max,
+    }
+}
+
+fn run_range_mock_prover(input: ZkpfRangeCircuitInput) -> MockProver<Fr> {
+    let public_instances = range_public_instances(&input.public, input.threshold_max);
+    let circuit = ZkpfRangeCircuit::new(Some(input));
+    run_mock(&circuit, public_instances)
+}
+
+// ============================================================
+// Wide Balance Tests
+// ============================================================
+
+/// 20 whole tokens of an 18-decimal asset, above `u64::MAX`.
+const WIDE_BALANCE: u128 = 20 * 10u128.pow(18);
+
+#[test]
+fn test_wide_balance_above_u64_passes() {
+    let threshold = u128::from(u64::MAX) + 1;
+    run_wide_mock_prover(wide_input(WIDE_BALANCE, threshold)).assert_satisfied();
+    run_wide_mock_prover(wide_input(WIDE_BALANCE, WIDE_BALANCE)).assert_satisfied();
+}
+
+#[test]
+fn test_wide_balance_below_threshold_fails() {
+    let input = wide_input(WIDE_BALANCE, WIDE_BALANCE + 1);
+    assert!(run_wide_mock_prover(input).verify().is_err());
+
+    // Equal high limbs, so only the low limbs decide.
+    let input = wide_input(WIDE_BALANCE, WIDE_BALANCE + (1 << 32));
+    assert!(run_wide_mock_prover(input).verify().is_err());
+}
+
+/// A larger low limb must not make up for a smaller high limb.
+#[test]
+fn test_wide_comparison_checks_high_limb_first() {
+    let balance = (1u128 << 64) | u128::from(u64::MAX);
+    let input = wide_input(balance, 2u128 << 64);
+    assert!(run_wide_mock_prover(input).verify().is_err());
+}
+
+/// A balance that fits 64 bits gives the same public inputs as the custodial
+/// circuit.
+#[test]
+fn test_wide_matches_custodial_below_u64() {
+    let input = wide_input(BASE_BALANCE.into(), BASE_THRESHOLD.into());
+    assert_eq!(
+        wide_public_instances(&input.public, input.threshold_raw),
+        zkpf_circuit::public_instances(&valid_input().public)
+    );
+    run_wide_mock_prover(input).assert_satisfied();
+}
+
+fn wide_input(balance: u128, threshold: u128) -> ZkpfWideCircuitInput {
+    let core = FixtureBuilder::new();
+    let mut base = core.build();
+    base.attestation.message_hash = message_hash_for_balance(&core.att, fr_from_u128(balance));
+    ZkpfWideCircuitInput {
+        attestation: base.attestation,
+        public: base.public,
+        balance_raw: balance,
+        threshold_raw: threshold,
+    }
+}
+
+fn run_wide_mock_prover(input: ZkpfWideCircuitInput) -> MockProver<Fr> {
+    let public_instances = wide_public_instances(&input.public, input.threshold_raw);
+    let circuit = ZkpfWideCircuit::new(Some(input));
+    run_mock(&circuit, public_instances)
+}
+
+// ============================================================
+// Revealed Sum Tests
+// ============================================================
+
+#[test]
+fn test_revealed_sum_matches_balance() {
+    let mut input = valid_input();
+    input.public.proven_sum = Some(BASE_BALANCE);
+    let prover = run_mock_prover(input);
+    prover.assert_satisfied();
+}
+
+#[test]
+fn test_revealed_sum_mismatch_fails() {
+    let mut input = valid_input();
+    input.public.proven_sum = Some(BASE_BALANCE - 1);
+    assert!(run_mock_prover(input).verify().is_err());
+}
+
+#[test]
+fn test_revealing_circuit_has_extra_column() {
+    let mut input = valid_input();
+    input.public.proven_sum = Some(BASE_BALANCE);
+    assert_eq!(zkpf_circuit::public_instances(&input.public).len(), 8);
     let circuit = ZkpfCircuit::new(Some(input));
+    assert_eq!(circuit.params().num_instance_columns, 8);
+    assert_eq!(ZkpfCircuit::keygen(true).params().num_instance_columns, 8);
+    assert_eq!(ZkpfCircuit::keygen(false).params().num_instance_columns, 7);
+}
+
+// ============================================================
+// Challenge Tests
+// ============================================================
+
+#[test]
+fn test_challenge_bound_proof_passes() {
+    let mut input = valid_input();
+    input.public.challenge = Some(Fr::from(0xC0FFEE_u64));
+    let prover = run_mock_prover(input);
+    prover.assert_satisfied();
+}
+
+#[test]
+fn test_challenge_instance_mismatch_fails() {
+    let mut input = valid_input();
+    input.public.challenge = Some(Fr::from(0xC0FFEE_u64));
+    let mut instances = zkpf_circuit::public_instances(&input.public);
+    instances[7][0] = Fr::from(0xBAD_u64);
+    let circuit = ZkpfCircuit::new(Some(input));
+    assert!(run_mock(&circuit, instances).verify().is_err());
+}
+
+#[test]
+fn test_challenge_circuit_shapes() {
+    let mut input = valid_input();
+    input.public.challenge = Some(Fr::from(1u64));
+    assert_eq!(zkpf_circuit::public_instances(&input.public).len(), 8);
+    input.public.proven_sum = Some(BASE_BALANCE);
+    let instances = zkpf_circuit::public_instances(&input.public);
+    assert_eq!(instances.len(), 9);
+    assert_eq!(instances[8][0], Fr::from(1u64));
+    run_mock_prover(input).assert_satisfied();
+
+    let both = CircuitShape {
+        reveal_sum: true,
+        challenge: true,
+    };
+    assert_eq!(both.instance_columns(), 9);
+    assert_eq!(
+        ZkpfCircuit::keygen_for(both).params().num_instance_columns,
+        9
+    );
+    assert_eq!(
+        ZkpfCircuit::keygen_for(CircuitShape::default())
+            .params()
+            .num_instance_columns,
+        7
+    );
+}
+
+// ============================================================
+// Circuit Size
+// ============================================================
+
+/// Every circuit must still fit the `k` its keys are generated at.
+#[test]
+fn test_circuits_fit_configured_k() {
+    let mut revealing = valid_input();
+    revealing.public.proven_sum = Some(BASE_BALANCE);
+    let account = valid_input().attestation.account_id_hash;
+    let blocklist = [Fr::from(2u64), account + Fr::ONE, Fr::from(1u64)];
+    let balances = [BASE_BALANCE; MAX_AGGREGATE_ACCOUNTS];
+
+    let stats = [
+        ("custodial", circuit_stats(&valid_input())),
+        ("revealing", circuit_stats(&revealing)),
+        (
+            "range",
+            range_circuit_stats(&range_input(RANGE_MIN, RANGE_MIN, RANGE_MAX)),
+        ),
+        (
+            "wide",
+            wide_circuit_stats(&wide_input(WIDE_BALANCE, WIDE_BALANCE)),
+        ),
+        (
+            "converted",
+            converted_circuit_stats(&converted_input(1_000, 25, 10, 2_500)),
+        ),
+        (
+            "screened",
+            screened_circuit_stats(&screened_input(&blocklist, 2, 3)).unwrap(),
+        ),
+        (
+            "aggregate",
+            aggregate_circuit_stats(&aggregate_input(&balances, BASE_THRESHOLD)).unwrap(),
+        ),
+        (
+            "multi-custodian",
+            multi_custodian_circuit_stats(&multi_custodian_input(
+                &balances[..MAX_CUSTODIANS],
+                BASE_THRESHOLD,
+            ))
+            .unwrap(),
+        ),
+    ];
+    for (name, stats) in stats {
+        assert!(stats.advice_cells > 0, "{name}: no advice cells counted");
+        assert!(stats.fits(), "{name} circuit outgrew k: {stats:?}");
+    }
+}
+
+#[test]
+fn test_aggregate_circuit_stats_rejects_empty_input() {
+    let mut input = aggregate_input(&[BASE_BALANCE], BASE_THRESHOLD);
+    input.attestations.clear();
+    assert!(aggregate_circuit_stats(&input).is_err());
+}
+
+// ============================================================
+// Note Padding
+// ============================================================
+
+#[test]
+fn test_pad_notes_fills_to_width_with_zero_notes() {
+    assert_eq!(pad_notes(vec![5, 7], 4).unwrap(), vec![5, 7, 0, 0]);
+    assert_eq!(pad_notes(vec![1; 4], 4).unwrap(), vec![1; 4]);
+
+    let assets = pad_note_witnesses(vec![[9u8; 32]], 3).unwrap();
+    assert_eq!(assets, vec![[9u8; 32], [0u8; 32], [0u8; 32]]);
+}
+
+#[test]
+fn test_pad_notes_rejects_oversized_sets() {
+    assert_eq!(
+        pad_notes(vec![1; 5], 4).unwrap_err(),
+        TooManyNotes { got: 5, max: 4 }
+    );
+}
+
+// ============================================================
+// Attestation Digest Vectors
+// ============================================================
+
+/// The published vectors must hash to the digest the circuit recomputes.
+#[test]
+fn test_attestation_message_hash_vectors() {
+    let file: serde_json::Value = serde_json::from_str(include_str!(
+        "../../zkpf-common/test-vectors/attestation_message_hash.json"
+    ))
+    .expect("vector file parses");
+    let vectors = file["vectors"].as_array().expect("vectors array");
+    assert!(!vectors.is_empty());
+
+    for vector in vectors {
+        let name = vector["name"].as_str().unwrap();
+        let preimage = decode_hex(vector["preimage"].as_str().unwrap());
+        let expected: [u8; 32] = decode_hex(vector["message_hash"].as_str().unwrap())
+            .try_into()
+            .unwrap();
+
+        let elements: Vec<Fr> = preimage
+            .chunks_exact(32)
+            .map(|chunk| fr_from_be_bytes(chunk.try_into().unwrap()))
+            .collect();
+        assert_eq!(elements.len(), 7, "preimage length for vector {name}");
+
+        let att = AttestationCore {
+            balance_raw: vector["balance_raw"].as_u64().unwrap(),
+            currency_code_int: vector["currency_code_int"].as_u64().unwrap() as u32,
+            custodian_id: vector["custodian_id"].as_u64().unwrap() as u32,
+            attestation_id: vector["attestation_id"].as_u64().unwrap(),
+            issued_at: vector["issued_at"].as_u64().unwrap(),
+            valid_until: vector["valid_until"].as_u64().unwrap(),
+            account_id_hash: elements[6],
+        };
+        assert_eq!(
+            fr_to_be_bytes(&poseidon_hash(&elements)),
+            expected,
+            "preimage digest for vector {name}"
+        );
+        assert_eq!(
+            attestation_message_hash(&att),
+            expected,
+            "field digest for vector {name}"
+        );
+
+        let input = FixtureBuilder::new()
+            .with_att(|a| *a = att.clone())
+            .with_public(|p| {
+                p.threshold_raw = 0;
+                p.required_currency_code = att.currency_code_int;
+                p.current_epoch = att.issued_at;
+            })
+            .build();
+        assert_eq!(input.attestation.message_hash, expected);
+        run_mock_prover(input).assert_satisfied();
+    }
+}
+
+fn decode_hex(input: &str) -> Vec<u8> {
+    let digits = input.strip_prefix("0x").unwrap_or(input);
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).expect("hex digit"))
+        .collect()
+}
+
+fn valid_input() -> ZkpfCircuitInput {
+    FixtureBuilder::new().build()
+}
+
+/// Runs the mock prover at the circuit's own `k`, panicking if the run itself
+/// fails; constraint failures are left to `verify` / `assert_satisfied`.
+fn run_mock<C: Circuit<Fr, Params = BaseCircuitParams>>(
+    circuit: &C,
+    instances: Vec<Vec<Fr>>,
+) -> MockProver<Fr> {
     let k = circuit.params().k as u32;
-    match MockProver::run(k, &circuit, public_instances) {
+    match MockProver::run(k, circuit, instances) {
         Ok(prover) => prover,
         Err(err) => panic!("mock prover run failed: {:?}", err),
     }
 }
 
+fn run_mock_prover(input: ZkpfCircuitInput) -> MockProver<Fr> {
+    let public_instances = zkpf_circuit::public_instances(&input.public);
+    let circuit = ZkpfCircuit::new(Some(input));
+    run_mock(&circuit, public_instances)
+}
+
 #[derive(Clone)]
 struct AttestationCore {
     balance_raw: u64,
@@ -274,6 +1182,7 @@ fn assemble_input(att: &AttestationCore, public: &PublicCore) -> ZkpfCircuitInpu
         custodian_pubkey,
         signature: EcdsaSignature { r: sig_r, s: sig_s },
         message_hash,
+        signature_format: SignatureFormat::RawHash,
     };
 
     let nullifier = poseidon_hash(&[
@@ -292,6 +1201,8 @@ fn assemble_input(att: &AttestationCore, public: &PublicCore) -> ZkpfCircuitInpu
         policy_id: public.policy_id,
         nullifier,
         custodian_pubkey_hash,
+        proven_sum: None,
+        challenge: None,
     };
 
     ZkpfCircuitInput {
@@ -305,8 +1216,13 @@ fn deterministic_signing_key() -> SecretKey {
 }
 
 fn attestation_message_hash(att: &AttestationCore) -> [u8; 32] {
+    message_hash_for_balance(att, fr_from_u64(att.balance_raw))
+}
+
+/// The message hash of `att` with its balance replaced by `balance`.
+fn message_hash_for_balance(att: &AttestationCore, balance: Fr) -> [u8; 32] {
     let digest = poseidon_hash(&[
-        fr_from_u64(att.balance_raw),
+        balance,
         fr_from_u64(att.attestation_id),
         fr_from_u32(att.currency_code_int),
         fr_from_u32(att.custodian_id),
@@ -481,6 +1397,10 @@ fn fr_from_u64(value: u64) -> Fr {
     Fr::from(value)
 }
 
+fn fr_from_u128(value: u128) -> Fr {
+    Fr::from_u128(value)
+}
+
 fn fr_from_u32(value: u32) -> Fr {
     Fr::from(value as u64)
 }
@@ -0,0 +1,411 @@
+// zkpf/zkpf-common/src/compact.rs
+// Numan Thabit 2025
+
+//! Compact binary envelope for [`ProofBundle`]s.
+//!
+//! JSON bundles spell every proof byte as a decimal number, which is several
+//! times too large for a QR code. The compact envelope is, in order:
+//!
+//! 1. a format version byte ([`COMPACT_BUNDLE_VERSION`]);
+//! 2. a rail tag byte: one of the well-known rails below, or
+//!    [`CUSTOM_RAIL_TAG`] followed by the varint-prefixed UTF-8 rail id;
+//! 3. the circuit version as a varint;
+//! 4. a proof encoding byte (0 for raw bytes, 1 for zstd);
+//! 5. the varint-prefixed proof bytes;
+//! 6. the varint-prefixed bincode (varint integers) encoding of the public
+//!    inputs;
+//! 7. an issuance byte: 0, or 1 followed by `issued_at` as a varint and the
+//!    32-byte `issued_at_tag`;
+//! 8. in version 2 only: the varint count of `extra_nullifiers` followed by
+//!    each 32-byte nullifier.
+//!
+//! Bundles without `extra_nullifiers` are written as version 1 and bundles
+//! with them as version 2. Varints are unsigned LEB128. Trailing bytes are rejected, so every bundle
+//! has exactly one encoding. Compress the proof with
+//! [`ProofBundle::compressed`] first to shrink the envelope further.
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use bincode::Options;
+use serde::{Deserialize, Serialize};
+
+use zkpf_circuit::aggregate::MAX_AGGREGATE_ACCOUNTS;
+
+use crate::{
+    ProofBundle, ProofEncoding, VerifierPublicInputs, AGGREGATE_RAIL_ID, CHALLENGE_RAIL_ID,
+    DEFAULT_RAIL_ID, MAX_DECODED_PROOF_BYTES, RANGE_RAIL_ID, REVEAL_SUM_RAIL_ID,
+};
+
+/// Current version of the compact envelope.
+pub const COMPACT_BUNDLE_VERSION: u8 = 2;
+
+/// Version written for bundles without `extra_nullifiers`, which older
+/// decoders still read.
+const COMPACT_BUNDLE_VERSION_V1: u8 = 1;
+
+/// Rail tag for a rail without a well-known tag; its id follows the tag.
+pub const CUSTOM_RAIL_TAG: u8 = 0xff;
+
+/// Well-known rails and their one-byte tags. Tags are part of the format and
+/// must never be reassigned.
+const RAIL_TAGS: &[(u8, &str)] = &[
+    (0, ""),
+    (1, DEFAULT_RAIL_ID),
+    (2, AGGREGATE_RAIL_ID),
+    (3, REVEAL_SUM_RAIL_ID),
+    (4, CHALLENGE_RAIL_ID),
+    (5, RANGE_RAIL_ID),
+    (6, "ZCASH_ORCHARD"),
+    (7, "STARKNET_L2"),
+    (8, "PROVIDER_BALANCE_V2"),
+];
+
+/// Longest encoded public inputs accepted; the largest layout needs well
+/// under this.
+const MAX_COMPACT_INPUTS_BYTES: u64 = 1024;
+
+/// [`VerifierPublicInputs`] without the JSON-only `skip_serializing_if`
+/// attributes, which a non-self-describing format cannot decode.
+#[derive(Serialize, Deserialize)]
+struct CompactInputs {
+    threshold_raw: u64,
+    required_currency_code: u32,
+    current_epoch: u64,
+    verifier_scope_id: u64,
+    policy_id: u64,
+    nullifier: [u8; 32],
+    custodian_pubkey_hash: [u8; 32],
+    snapshot_block_height: Option<u64>,
+    snapshot_anchor_orchard: Option<[u8; 32]>,
+    holder_binding: Option<[u8; 32]>,
+    threshold_max: Option<u64>,
+    proven_sum: Option<u128>,
+    challenge: Option<[u8; 32]>,
+}
+
+impl From<&VerifierPublicInputs> for CompactInputs {
+    fn from(inputs: &VerifierPublicInputs) -> Self {
+        Self {
+            threshold_raw: inputs.threshold_raw,
+            required_currency_code: inputs.required_currency_code,
+            current_epoch: inputs.current_epoch,
+            verifier_scope_id: inputs.verifier_scope_id,
+            policy_id: inputs.policy_id,
+            nullifier: inputs.nullifier,
+            custodian_pubkey_hash: inputs.custodian_pubkey_hash,
+            snapshot_block_height: inputs.snapshot_block_height,
+            snapshot_anchor_orchard: inputs.snapshot_anchor_orchard,
+            holder_binding: inputs.holder_binding,
+            threshold_max: inputs.threshold_max,
+            proven_sum: inputs.proven_sum,
+            challenge: inputs.challenge,
+        }
+    }
+}
+
+impl From<CompactInputs> for VerifierPublicInputs {
+    fn from(inputs: CompactInputs) -> Self {
+        Self {
+            threshold_raw: inputs.threshold_raw,
+            required_currency_code: inputs.required_currency_code,
+            current_epoch: inputs.current_epoch,
+            verifier_scope_id: inputs.verifier_scope_id,
+            policy_id: inputs.policy_id,
+            nullifier: inputs.nullifier,
+            custodian_pubkey_hash: inputs.custodian_pubkey_hash,
+            snapshot_block_height: inputs.snapshot_block_height,
+            snapshot_anchor_orchard: inputs.snapshot_anchor_orchard,
+            holder_binding: inputs.holder_binding,
+            threshold_max: inputs.threshold_max,
+            proven_sum: inputs.proven_sum,
+            challenge: inputs.challenge,
+            extra_nullifiers: None,
+        }
+    }
+}
+
+fn bincode_options() -> impl Options {
+    bincode::DefaultOptions::new().with_limit(MAX_COMPACT_INPUTS_BYTES)
+}
+
+impl ProofBundle {
+    /// Encodes the bundle in the compact binary envelope (see the module docs).
+    pub fn to_compact_bytes(&self) -> Result<Vec<u8>> {
+        let inputs = bincode_options()
+            .serialize(&CompactInputs::from(&self.public_inputs))
+            .context("failed to encode public inputs")?;
+
+        let extra_nullifiers = self.public_inputs.extra_nullifiers.as_deref();
+        let mut out = Vec::with_capacity(self.proof.len() + inputs.len() + 16);
+        out.push(match extra_nullifiers {
+            Some(_) => COMPACT_BUNDLE_VERSION,
+            None => COMPACT_BUNDLE_VERSION_V1,
+        });
+        match RAIL_TAGS.iter().find(|(_, rail)| *rail == self.rail_id) {
+            Some((tag, _)) => out.push(*tag),
+            None => {
+                ensure!(
+                    self.rail_id.len() <= usize::from(u8::MAX),
+                    "rail id of {} bytes is too long for a compact bundle",
+                    self.rail_id.len()
+                );
+                out.push(CUSTOM_RAIL_TAG);
+                write_varint(&mut out, self.rail_id.len() as u64);
+                out.extend_from_slice(self.rail_id.as_bytes());
+            }
+        }
+        write_varint(&mut out, u64::from(self.circuit_version));
+        out.push(match self.proof_encoding {
+            None => 0,
+            Some(ProofEncoding::Zstd) => 1,
+        });
+        write_varint(&mut out, self.proof.len() as u64);
+        out.extend_from_slice(&self.proof);
+        write_varint(&mut out, inputs.len() as u64);
+        out.extend_from_slice(&inputs);
+        match (self.issued_at, self.issued_at_tag) {
+            (Some(issued_at), Some(tag)) => {
+                out.push(1);
+                write_varint(&mut out, issued_at);
+                out.extend_from_slice(&tag);
+            }
+            (None, None) => out.push(0),
+            _ => bail!("issued_at and issued_at_tag must be set together"),
+        }
+        if let Some(nullifiers) = extra_nullifiers {
+            write_varint(&mut out, nullifiers.len() as u64);
+            for nullifier in nullifiers {
+                out.extend_from_slice(nullifier);
+            }
+        }
+        Ok(out)
+    }
+
+    /// Decodes a bundle written by [`Self::to_compact_bytes`].
+    pub fn from_compact_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut reader = Reader { bytes };
+        let version = reader.byte()?;
+        ensure!(
+            version == COMPACT_BUNDLE_VERSION || version == COMPACT_BUNDLE_VERSION_V1,
+            "unsupported compact bundle version {}",
+            version
+        );
+        let rail_id = match reader.byte()? {
+            CUSTOM_RAIL_TAG => {
+                let rail = reader.prefixed(usize::from(u8::MAX))?;
+                String::from_utf8(rail.to_vec()).context("rail id is not UTF-8")?
+            }
+            tag => RAIL_TAGS
+                .iter()
+                .find(|(known, _)| *known == tag)
+                .map(|(_, rail)| rail.to_string())
+                .ok_or_else(|| anyhow!("unknown rail tag {}", tag))?,
+        };
+        let circuit_version =
+            u32::try_from(reader.varint()?).context("circuit version out of range")?;
+        let proof_encoding = match reader.byte()? {
+            0 => None,
+            1 => Some(ProofEncoding::Zstd),
+            other => bail!("unknown proof encoding {}", other),
+        };
+        let proof = reader.prefixed(MAX_DECODED_PROOF_BYTES)?.to_vec();
+        let inputs: CompactInputs = bincode_options()
+            .deserialize(reader.prefixed(MAX_COMPACT_INPUTS_BYTES as usize)?)
+            .context("failed to decode public inputs")?;
+        let (issued_at, issued_at_tag) = match reader.byte()? {
+            0 => (None, None),
+            1 => {
+                let issued_at = reader.varint()?;
+                let tag = reader.fixed::<32>()?;
+                (Some(issued_at), Some(tag))
+            }
+            other => bail!("unknown issuance flag {}", other),
+        };
+        let extra_nullifiers = if version == COMPACT_BUNDLE_VERSION {
+            let count = reader.varint()?;
+            ensure!(
+                count < MAX_AGGREGATE_ACCOUNTS as u64,
+                "{} extra nullifiers exceed the limit of {}",
+                count,
+                MAX_AGGREGATE_ACCOUNTS - 1
+            );
+            Some(
+                (0..count)
+                    .map(|_| reader.fixed::<32>())
+                    .collect::<Result<Vec<_>>>()?,
+            )
+        } else {
+            None
+        };
+        ensure!(
+            reader.bytes.is_empty(),
+            "{} trailing bytes after compact bundle",
+            reader.bytes.len()
+        );
+
+        Ok(Self {
+            rail_id,
+            circuit_version,
+            proof,
+            proof_encoding,
+            issued_at,
+            issued_at_tag,
+            public_inputs: VerifierPublicInputs {
+                extra_nullifiers,
+                ..inputs.into()
+            },
+        })
+    }
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn byte(&mut self) -> Result<u8> {
+        let (&first, rest) = self
+            .bytes
+            .split_first()
+            .ok_or_else(|| anyhow!("compact bundle is truncated"))?;
+        self.bytes = rest;
+        Ok(first)
+    }
+
+    fn varint(&mut self) -> Result<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            let bits = u64::from(byte & 0x7f);
+            ensure!(bits << shift >> shift == bits, "varint overflows u64");
+            value |= bits << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        bail!("varint overflows u64")
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        ensure!(len <= self.bytes.len(), "compact bundle is truncated");
+        let (field, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(field)
+    }
+
+    fn fixed<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let mut out = [0u8; N];
+        out.copy_from_slice(self.take(N)?);
+        Ok(out)
+    }
+
+    /// A varint length of at most `max` followed by that many bytes.
+    fn prefixed(&mut self, max: usize) -> Result<&'a [u8]> {
+        let len = self.varint()?;
+        ensure!(
+            len <= max as u64,
+            "field of {} bytes exceeds the limit of {}",
+            len,
+            max
+        );
+        self.take(len as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bundle(rail_id: &str) -> ProofBundle {
+        let inputs = VerifierPublicInputs {
+            threshold_raw: 1_000_000,
+            required_currency_code: 999_001,
+            current_epoch: 1_700_000_000,
+            verifier_scope_id: 42,
+            policy_id: 7,
+            nullifier: [0xab; 32],
+            custodian_pubkey_hash: [0xcd; 32],
+            snapshot_block_height: Some(2_500_000),
+            snapshot_anchor_orchard: Some([0x11; 32]),
+            holder_binding: Some([0x22; 32]),
+            threshold_max: None,
+            proven_sum: Some(u128::MAX),
+            challenge: None,
+            extra_nullifiers: None,
+        };
+        let proof = (0..1536u32).map(|i| (i * 31 % 251) as u8).collect();
+        ProofBundle::new_with_rail(rail_id, proof, inputs)
+    }
+
+    fn json(bundle: &ProofBundle) -> serde_json::Value {
+        serde_json::to_value(bundle).unwrap()
+    }
+
+    #[test]
+    fn compact_bytes_round_trip_every_rail_kind() {
+        for rail in [DEFAULT_RAIL_ID, "", "ZCASH_ORCHARD", "SOME_NEW_RAIL"] {
+            let original = bundle(rail);
+            let bytes = original.to_compact_bytes().unwrap();
+            let decoded = ProofBundle::from_compact_bytes(&bytes).unwrap();
+            assert_eq!(json(&decoded), json(&original));
+        }
+
+        let mut compressed = bundle(DEFAULT_RAIL_ID).compressed().unwrap();
+        compressed.stamp_issued_at(1_700_000_123, &[9; 32]).unwrap();
+        let bytes = compressed.to_compact_bytes().unwrap();
+        let decoded = ProofBundle::from_compact_bytes(&bytes).unwrap();
+        assert_eq!(json(&decoded), json(&compressed));
+    }
+
+    #[test]
+    fn extra_nullifiers_round_trip_as_version_2() {
+        let plain = bundle(AGGREGATE_RAIL_ID).to_compact_bytes().unwrap();
+        assert_eq!(plain[0], COMPACT_BUNDLE_VERSION_V1);
+
+        let mut aggregate = bundle(AGGREGATE_RAIL_ID);
+        aggregate.public_inputs.extra_nullifiers = Some(vec![[1; 32], [2; 32]]);
+        let bytes = aggregate.to_compact_bytes().unwrap();
+        assert_eq!(bytes[0], COMPACT_BUNDLE_VERSION);
+        let decoded = ProofBundle::from_compact_bytes(&bytes).unwrap();
+        assert_eq!(json(&decoded), json(&aggregate));
+
+        aggregate.public_inputs.extra_nullifiers = Some(vec![[1; 32]; MAX_AGGREGATE_ACCOUNTS]);
+        let too_many = aggregate.to_compact_bytes().unwrap();
+        assert!(ProofBundle::from_compact_bytes(&too_many).is_err());
+    }
+
+    #[test]
+    fn compact_bytes_are_much_smaller_than_json() {
+        let original = bundle(DEFAULT_RAIL_ID);
+        let compact = original.to_compact_bytes().unwrap();
+        let json = serde_json::to_vec(&original).unwrap();
+        // Proof bytes dominate: one byte each instead of up to four in JSON.
+        assert!(compact.len() < original.proof.len() + 256);
+        assert!(compact.len() * 2 < json.len());
+    }
+
+    #[test]
+    fn malformed_envelopes_are_rejected() {
+        let bytes = bundle(DEFAULT_RAIL_ID).to_compact_bytes().unwrap();
+        assert!(ProofBundle::from_compact_bytes(&bytes[..bytes.len() - 1]).is_err());
+
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(ProofBundle::from_compact_bytes(&trailing).is_err());
+
+        let mut unknown_tag = bytes.clone();
+        unknown_tag[1] = 0x7f;
+        assert!(ProofBundle::from_compact_bytes(&unknown_tag).is_err());
+
+        let mut future_version = bytes;
+        future_version[0] = COMPACT_BUNDLE_VERSION + 1;
+        assert!(ProofBundle::from_compact_bytes(&future_version).is_err());
+    }
+}
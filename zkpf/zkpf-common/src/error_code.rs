@@ -0,0 +1,240 @@
+// zkpf/zkpf-common/src/error_code.rs
+// Numan Thabit 2025
+
+//! Error codes shared by the backend, the WASM bindings and the rails.
+//!
+//! Every component reports failures with one of these codes, serialized as the
+//! `SCREAMING_SNAKE_CASE` string clients already match on (`error_code` in HTTP
+//! bodies, the prefix of errors thrown from WASM). The wire strings are part of
+//! the public API: add new variants, never rename existing ones.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    // Verification
+    CircuitVersionMismatch,
+    PublicInputsInvalid,
+    PolicyNotFound,
+    PolicyMismatch,
+    PolicyComposeInvalid,
+    EpochDrift,
+    EpochRewind,
+    ChallengeInvalid,
+    NullifierReplay,
+    NullifierNotFound,
+    NullifierStoreError,
+    ProofInvalid,
+    RailUnknown,
+    SnapshotHeightInvalid,
+    BatchInvalid,
+    BundleInvalid,
+
+    // Proving
+    ProverDisabled,
+    ProofGenerationFailed,
+    WitnessInvalid,
+    ArtifactNotFound,
+    ArtifactInvalid,
+
+    // Attestation
+    AttestationDisabled,
+    AttestationVerificationFailed,
+    AttestationOnchainError,
+
+    // Provider sessions and requests
+    SessionNotFound,
+    SessionStateInvalid,
+    MultipartInvalid,
+    DeepLinkSchemeInvalid,
+    Unauthorized,
+    RateLimited,
+
+    // Cross-chain rails
+    ChainNotFound,
+    NoSubscriptions,
+    UntrustedSource,
+    ReceiptNotFound,
+    CredentialNotFound,
+    CredentialRevoked,
+    InsufficientProof,
+    StaleProof,
+    GmpError,
+    InvalidGas,
+    InvalidHex,
+    InvalidLength,
+    InvalidPayload,
+    InvalidReason,
+    InvalidTier,
+    ReceiptProofMismatch,
+    ReceiptHashMismatch,
+
+    InternalServerError,
+}
+
+impl ErrorCode {
+    /// Every code, in declaration order.
+    pub const ALL: &'static [ErrorCode] = &[
+        ErrorCode::CircuitVersionMismatch,
+        ErrorCode::PublicInputsInvalid,
+        ErrorCode::PolicyNotFound,
+        ErrorCode::PolicyMismatch,
+        ErrorCode::PolicyComposeInvalid,
+        ErrorCode::EpochDrift,
+        ErrorCode::EpochRewind,
+        ErrorCode::ChallengeInvalid,
+        ErrorCode::NullifierReplay,
+        ErrorCode::NullifierNotFound,
+        ErrorCode::NullifierStoreError,
+        ErrorCode::ProofInvalid,
+        ErrorCode::RailUnknown,
+        ErrorCode::SnapshotHeightInvalid,
+        ErrorCode::BatchInvalid,
+        ErrorCode::BundleInvalid,
+        ErrorCode::ProverDisabled,
+        ErrorCode::ProofGenerationFailed,
+        ErrorCode::WitnessInvalid,
+        ErrorCode::ArtifactNotFound,
+        ErrorCode::ArtifactInvalid,
+        ErrorCode::AttestationDisabled,
+        ErrorCode::AttestationVerificationFailed,
+        ErrorCode::AttestationOnchainError,
+        ErrorCode::SessionNotFound,
+        ErrorCode::SessionStateInvalid,
+        ErrorCode::MultipartInvalid,
+        ErrorCode::DeepLinkSchemeInvalid,
+        ErrorCode::Unauthorized,
+        ErrorCode::RateLimited,
+        ErrorCode::ChainNotFound,
+        ErrorCode::NoSubscriptions,
+        ErrorCode::UntrustedSource,
+        ErrorCode::ReceiptNotFound,
+        ErrorCode::CredentialNotFound,
+        ErrorCode::CredentialRevoked,
+        ErrorCode::InsufficientProof,
+        ErrorCode::StaleProof,
+        ErrorCode::GmpError,
+        ErrorCode::InvalidGas,
+        ErrorCode::InvalidHex,
+        ErrorCode::InvalidLength,
+        ErrorCode::InvalidPayload,
+        ErrorCode::InvalidReason,
+        ErrorCode::InvalidTier,
+        ErrorCode::ReceiptProofMismatch,
+        ErrorCode::ReceiptHashMismatch,
+        ErrorCode::InternalServerError,
+    ];
+
+    /// The wire string, identical to the serde representation.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            ErrorCode::CircuitVersionMismatch => "CIRCUIT_VERSION_MISMATCH",
+            ErrorCode::PublicInputsInvalid => "PUBLIC_INPUTS_INVALID",
+            ErrorCode::PolicyNotFound => "POLICY_NOT_FOUND",
+            ErrorCode::PolicyMismatch => "POLICY_MISMATCH",
+            ErrorCode::PolicyComposeInvalid => "POLICY_COMPOSE_INVALID",
+            ErrorCode::EpochDrift => "EPOCH_DRIFT",
+            ErrorCode::EpochRewind => "EPOCH_REWIND",
+            ErrorCode::ChallengeInvalid => "CHALLENGE_INVALID",
+            ErrorCode::NullifierReplay => "NULLIFIER_REPLAY",
+            ErrorCode::NullifierNotFound => "NULLIFIER_NOT_FOUND",
+            ErrorCode::NullifierStoreError => "NULLIFIER_STORE_ERROR",
+            ErrorCode::ProofInvalid => "PROOF_INVALID",
+            ErrorCode::RailUnknown => "RAIL_UNKNOWN",
+            ErrorCode::SnapshotHeightInvalid => "SNAPSHOT_HEIGHT_INVALID",
+            ErrorCode::BatchInvalid => "BATCH_INVALID",
+            ErrorCode::BundleInvalid => "BUNDLE_INVALID",
+            ErrorCode::ProverDisabled => "PROVER_DISABLED",
+            ErrorCode::ProofGenerationFailed => "PROOF_GENERATION_FAILED",
+            ErrorCode::WitnessInvalid => "WITNESS_INVALID",
+            ErrorCode::ArtifactNotFound => "ARTIFACT_NOT_FOUND",
+            ErrorCode::ArtifactInvalid => "ARTIFACT_INVALID",
+            ErrorCode::AttestationDisabled => "ATTESTATION_DISABLED",
+            ErrorCode::AttestationVerificationFailed => "ATTESTATION_VERIFICATION_FAILED",
+            ErrorCode::AttestationOnchainError => "ATTESTATION_ONCHAIN_ERROR",
+            ErrorCode::SessionNotFound => "SESSION_NOT_FOUND",
+            ErrorCode::SessionStateInvalid => "SESSION_STATE_INVALID",
+            ErrorCode::MultipartInvalid => "MULTIPART_INVALID",
+            ErrorCode::DeepLinkSchemeInvalid => "DEEP_LINK_SCHEME_INVALID",
+            ErrorCode::Unauthorized => "UNAUTHORIZED",
+            ErrorCode::RateLimited => "RATE_LIMITED",
+            ErrorCode::ChainNotFound => "CHAIN_NOT_FOUND",
+            ErrorCode::NoSubscriptions => "NO_SUBSCRIPTIONS",
+            ErrorCode::UntrustedSource => "UNTRUSTED_SOURCE",
+            ErrorCode::ReceiptNotFound => "RECEIPT_NOT_FOUND",
+            ErrorCode::CredentialNotFound => "CREDENTIAL_NOT_FOUND",
+            ErrorCode::CredentialRevoked => "CREDENTIAL_REVOKED",
+            ErrorCode::InsufficientProof => "INSUFFICIENT_PROOF",
+            ErrorCode::StaleProof => "STALE_PROOF",
+            ErrorCode::GmpError => "GMP_ERROR",
+            ErrorCode::InvalidGas => "INVALID_GAS",
+            ErrorCode::InvalidHex => "INVALID_HEX",
+            ErrorCode::InvalidLength => "INVALID_LENGTH",
+            ErrorCode::InvalidPayload => "INVALID_PAYLOAD",
+            ErrorCode::InvalidReason => "INVALID_REASON",
+            ErrorCode::InvalidTier => "INVALID_TIER",
+            ErrorCode::ReceiptProofMismatch => "RECEIPT_PROOF_MISMATCH",
+            ErrorCode::ReceiptHashMismatch => "RECEIPT_HASH_MISMATCH",
+            ErrorCode::InternalServerError => "INTERNAL_SERVER_ERROR",
+        }
+    }
+
+    /// Canonical HTTP status for the code.
+    ///
+    /// Handlers use this unless the request context calls for a more specific
+    /// status (an expired session is `410` rather than `409`, for example).
+    pub const fn http_status(self) -> u16 {
+        match self {
+            ErrorCode::Unauthorized => 401,
+            ErrorCode::UntrustedSource | ErrorCode::CredentialRevoked => 403,
+            ErrorCode::PolicyNotFound
+            | ErrorCode::NullifierNotFound
+            | ErrorCode::ArtifactNotFound
+            | ErrorCode::SessionNotFound
+            | ErrorCode::ChainNotFound
+            | ErrorCode::ReceiptNotFound
+            | ErrorCode::CredentialNotFound => 404,
+            ErrorCode::SessionStateInvalid | ErrorCode::StaleProof => 409,
+            ErrorCode::RateLimited => 429,
+            ErrorCode::NullifierStoreError
+            | ErrorCode::ProofGenerationFailed
+            | ErrorCode::InternalServerError => 500,
+            ErrorCode::AttestationOnchainError => 502,
+            ErrorCode::ProverDisabled | ErrorCode::AttestationDisabled => 503,
+            _ => 400,
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn as_str_matches_serde_name() {
+        for code in ErrorCode::ALL {
+            let json = serde_json::to_string(code).unwrap();
+            assert_eq!(json, format!("\"{}\"", code.as_str()));
+            let parsed: ErrorCode = serde_json::from_str(&json).unwrap();
+            assert_eq!(parsed, *code);
+        }
+    }
+
+    #[test]
+    fn http_status_is_an_error_status() {
+        for code in ErrorCode::ALL {
+            assert!((400..600).contains(&code.http_status()), "{code}");
+        }
+        assert_eq!(ErrorCode::PolicyNotFound.http_status(), 404);
+        assert_eq!(ErrorCode::InternalServerError.http_status(), 500);
+    }
+}
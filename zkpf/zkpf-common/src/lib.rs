@@ -1,8 +1,13 @@
 use std::{
-    fs,
-    io::Cursor,
+    borrow::Cow,
+    collections::{hash_map::Entry, HashMap},
+    env, fs,
+    io::{self, Cursor},
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
 };
 
 use anyhow::{anyhow, ensure, Context, Result};
@@ -12,24 +17,48 @@ use halo2_proofs_axiom::{
     SerdeFormat,
 };
 use halo2curves_axiom::{
-    bn256::{Bn256, Fr, G1Affine},
+    bn256::{Bn256, G1Affine},
     ff::{Field, PrimeField},
 };
 use once_cell::sync::OnceCell;
 use poseidon_primitives::poseidon::primitives::{ConstantLength, Hash as PoseidonHash, Spec};
 use serde::{Deserialize, Serialize};
+use subtle::ConstantTimeEq;
 use zkpf_circuit::{
-    gadgets::attestation::{AttestationWitness, EcdsaSignature, Secp256k1Pubkey},
-    public_instances, PublicInputs, ZkpfCircuit,
+    aggregate::ZkpfAggregateCircuitInput,
+    gadgets::{
+        attestation::{AttestationWitness, EcdsaSignature, Secp256k1Pubkey, SignatureFormat},
+        blocklist::{NonMembershipWitness, BLOCKLIST_TREE_DEPTH},
+    },
+    multi_custodian::CUSTODIAN_TREE_DEPTH,
+    ranged::ZkpfRangeCircuitInput,
+    PublicInputs, ZkpfCircuit,
 };
 
-/// Number of public inputs in the legacy custodial circuit layout (V1).
-pub const PUBLIC_INPUT_COUNT: usize = 7;
-/// Number of public inputs in the Orchard layout (V2_ORCHARD): V1 prefix + 3 Orchard fields.
-pub const PUBLIC_INPUT_COUNT_V2_ORCHARD: usize = 10;
-/// Number of public inputs in the Starknet layout (V3_STARKNET): V1 prefix + 4 Starknet fields.
-/// Fields: chain_id_numeric, block_number, account_commitment, holder_binding
-pub const PUBLIC_INPUT_COUNT_V3_STARKNET: usize = 11;
+use crate::merkle::MerkleTree;
+
+pub mod compact;
+mod error_code;
+pub mod merkle;
+
+pub use error_code::ErrorCode;
+
+// The verification surface lives in zkpf-verify-core so lightweight verifiers
+// can link it alone; re-exported here so existing imports keep working.
+pub use zkpf_verify_core::{
+    deserialize_params, deserialize_verifying_key, deserialize_verifying_key_with_columns,
+    fr_from_bytes, public_inputs_to_instances, public_inputs_to_instances_with_layout,
+    reduce_be_bytes_to_fr, snapshot_commitment, verifier_inputs_to_public, PublicInputLayout,
+    VerifierPublicInputs, VerifierPublicInputsBuilder, PUBLIC_INPUT_COUNT,
+    PUBLIC_INPUT_COUNT_CHALLENGE, PUBLIC_INPUT_COUNT_REVEAL_SUM, PUBLIC_INPUT_COUNT_V2_ORCHARD,
+    PUBLIC_INPUT_COUNT_V3_STARKNET, PUBLIC_INPUT_COUNT_V4_AGGREGATE, PUBLIC_INPUT_COUNT_V5_RANGE,
+    PUBLIC_INPUT_COUNT_V6_SNAPSHOT_COMMITMENT,
+};
+
+/// Scalar field of every rail's public instances.
+pub use halo2curves_axiom::bn256::Fr;
+
+pub use zkpf_circuit::wide::BalanceWidth;
 
 // Re-export Poseidon parameters from zkpf-circuit (the canonical source)
 // to maintain backward compatibility for crates that import from zkpf-common.
@@ -37,48 +66,6 @@ pub use zkpf_circuit::gadgets::poseidon::{
     POSEIDON_FULL_ROUNDS, POSEIDON_PARTIAL_ROUNDS, POSEIDON_RATE, POSEIDON_T,
 };
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct VerifierPublicInputs {
-    pub threshold_raw: u64,
-    pub required_currency_code: u32,
-    pub current_epoch: u64,
-    pub verifier_scope_id: u64,
-    pub policy_id: u64,
-    pub nullifier: [u8; 32],
-    pub custodian_pubkey_hash: [u8; 32],
-    /// Optional snapshot metadata for non-custodial rails (e.g. Zcash Orchard).
-    ///
-    /// For the legacy custodial rail this will be `None`, and the corresponding
-    /// public-input layout (V1) does not include these fields.
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub snapshot_block_height: Option<u64>,
-    /// Orchard anchor (Merkle root) at `snapshot_block_height`, if applicable.
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub snapshot_anchor_orchard: Option<[u8; 32]>,
-    /// Optional binding between holder identity and rail-specific key material.
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub holder_binding: Option<[u8; 32]>,
-    /// Optional proven sum for transparency (Starknet rail).
-    /// The actual aggregated balance value that was proven to meet the threshold.
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub proven_sum: Option<u128>,
-}
-
-/// Logical public-input layouts supported by the verifier.
-///
-/// - `V1` – legacy custodial attestation rail (8 public inputs).
-/// - `V2Orchard` – Orchard rail layout: V1 prefix plus Orchard snapshot fields.
-/// - `V3Starknet` – Starknet L2 rail layout: V1 prefix plus Starknet-specific fields.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
-pub enum PublicInputLayout {
-    #[serde(rename = "V1")]
-    V1,
-    #[serde(rename = "V2_ORCHARD")]
-    V2Orchard,
-    #[serde(rename = "V3_STARKNET")]
-    V3Starknet,
-}
-
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ProofBundle {
     /// Logical rail identifier for this proof bundle.
@@ -90,23 +77,72 @@ pub struct ProofBundle {
     pub rail_id: String,
     pub circuit_version: u32,
     pub proof: Vec<u8>,
+    /// Transport encoding of `proof`; absent means raw proof bytes.
+    ///
+    /// Use [`ProofBundle::decoded_proof`] rather than reading `proof` directly
+    /// when a bundle may have been compressed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proof_encoding: Option<ProofEncoding>,
+    /// Unix time the bundle was proven, as stamped by a signing prover.
+    ///
+    /// Only trustworthy together with `issued_at_tag`; read it through
+    /// [`ProofBundle::authenticated_issued_at`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issued_at: Option<u64>,
+    /// Prover's keyed BLAKE3 tag over the proof and `issued_at`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issued_at_tag: Option<[u8; 32]>,
     pub public_inputs: VerifierPublicInputs,
 }
 
+/// Transport encodings for [`ProofBundle::proof`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProofEncoding {
+    #[serde(rename = "zstd")]
+    Zstd,
+}
+
+/// Upper bound on a decompressed proof, so a small compressed payload cannot
+/// expand without limit.
+pub const MAX_DECODED_PROOF_BYTES: usize = 1024 * 1024;
+
+const PROOF_ZSTD_LEVEL: i32 = 19;
+
+/// Custodian-signed attestation as exchanged in JSON.
+///
+/// JSON keys are pinned with explicit renames so they cannot drift with the
+/// Rust field names. Key order in the JSON object carries no meaning: the
+/// signed `message_hash` commits to the fields in the fixed order documented
+/// on [`canonical_attestation_bytes`], not in serialization order.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Attestation {
+    #[serde(rename = "balance_raw")]
     pub balance_raw: u64,
+    #[serde(rename = "currency_code_int")]
     pub currency_code_int: u32,
+    #[serde(rename = "custodian_id")]
     pub custodian_id: u32,
+    #[serde(rename = "attestation_id")]
     pub attestation_id: u64,
+    #[serde(rename = "issued_at")]
     pub issued_at: u64,
+    #[serde(rename = "valid_until")]
     pub valid_until: u64,
-    #[serde(with = "serde_bytes32")]
+    #[serde(rename = "account_id_hash", with = "serde_bytes32")]
     pub account_id_hash: [u8; 32],
+    #[serde(rename = "custodian_pubkey")]
     pub custodian_pubkey: Secp256k1Pubkey,
+    #[serde(rename = "signature")]
     pub signature: EcdsaSignature,
-    #[serde(with = "serde_bytes32")]
+    #[serde(rename = "message_hash", with = "serde_bytes32")]
     pub message_hash: [u8; 32],
+    /// Defaults to [`SignatureFormat::RawHash`], which is not serialized.
+    #[serde(
+        rename = "signature_format",
+        default,
+        skip_serializing_if = "SignatureFormat::is_raw_hash"
+    )]
+    pub signature_format: SignatureFormat,
 }
 
 #[derive(Clone, Debug)]
@@ -143,10 +179,11 @@ impl Attestation {
             attestation_id: self.attestation_id,
             issued_at: self.issued_at,
             valid_until: self.valid_until,
-            account_id_hash: reduce_be_bytes_to_fr(&self.account_id_hash),
+            account_id_hash: account_id_hash_from_tag(&self.account_id_hash),
             custodian_pubkey: self.custodian_pubkey,
             signature: self.signature.clone(),
             message_hash: self.message_hash,
+            signature_format: self.signature_format,
         }
     }
 
@@ -164,8 +201,43 @@ pub fn attestation_from_json(json: &str) -> Result<Attestation> {
     serde_json::from_str(json).context("failed to parse attestation JSON")
 }
 
+/// Number of field elements hashed into an attestation's `message_hash`.
+pub const ATTESTATION_PREIMAGE_LEN: usize = 7;
+
+/// Exact preimage of an attestation's `message_hash`.
+///
+/// Seven 32-byte big-endian field elements, in this order:
+///
+/// | # | field               | encoding                               |
+/// |---|---------------------|----------------------------------------|
+/// | 0 | `balance_raw`       | u64, zero-padded                       |
+/// | 1 | `attestation_id`    | u64, zero-padded                       |
+/// | 2 | `currency_code_int` | u32, zero-padded                       |
+/// | 3 | `custodian_id`      | u32, zero-padded                       |
+/// | 4 | `issued_at`         | u64, zero-padded                       |
+/// | 5 | `valid_until`       | u64, zero-padded                       |
+/// | 6 | `account_id_hash`   | reduced modulo the BN256 scalar field  |
+///
+/// This is the same order the circuit's `hash_attestation` gadget absorbs.
+/// Signers in other languages should match these bytes before hashing; see
+/// `test-vectors/attestation_message_hash.json` for reference values.
+pub fn canonical_attestation_bytes(
+    fields: &AttestationFields<'_>,
+) -> [u8; ATTESTATION_PREIMAGE_LEN * 32] {
+    let mut out = [0u8; ATTESTATION_PREIMAGE_LEN * 32];
+    for (chunk, element) in out.chunks_exact_mut(32).zip(attestation_preimage(fields)) {
+        chunk.copy_from_slice(&fr_to_be_bytes(&element));
+    }
+    out
+}
+
 pub fn attestation_message_hash(fields: &AttestationFields<'_>) -> [u8; 32] {
-    let digest = poseidon_hash(&[
+    let digest = poseidon_hash(&attestation_preimage(fields));
+    fr_to_be_bytes(&digest)
+}
+
+fn attestation_preimage(fields: &AttestationFields<'_>) -> [Fr; ATTESTATION_PREIMAGE_LEN] {
+    [
         Fr::from(fields.balance_raw),
         Fr::from(fields.attestation_id),
         Fr::from(fields.currency_code_int as u64),
@@ -173,13 +245,15 @@ pub fn attestation_message_hash(fields: &AttestationFields<'_>) -> [u8; 32] {
         Fr::from(fields.issued_at),
         Fr::from(fields.valid_until),
         reduce_be_bytes_to_fr(fields.account_id_hash),
-    ]);
-    fr_to_be_bytes(&digest)
+    ]
 }
 
-pub const CIRCUIT_VERSION: u32 = 5;
+pub const CIRCUIT_VERSION: u32 = 6;
 pub const MANIFEST_VERSION: u32 = 1;
 pub const MANIFEST_FILE: &str = "manifest.json";
+/// Overrides the directory manifest artifact paths are resolved against, so
+/// the manifest and the large blobs can live on different volumes.
+pub const ARTIFACT_DIR_ENV: &str = "ZKPF_ARTIFACT_DIR";
 
 // ============================================================
 // Artifact Integrity & Security Notes
@@ -262,6 +336,18 @@ pub struct ArtifactManifest {
     pub params: ArtifactFile,
     pub vk: ArtifactFile,
     pub pk: ArtifactFile,
+    /// Instance columns of the circuit the keys were generated for, when it
+    /// differs from the custodial circuit (e.g. revealing `proven_sum`). Keys
+    /// are deserialized with this shape.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub num_instance_columns: Option<usize>,
+}
+
+impl ArtifactManifest {
+    /// Instance columns to deserialize the keys with.
+    pub fn instance_columns(&self) -> usize {
+        self.num_instance_columns.unwrap_or(PUBLIC_INPUT_COUNT)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -281,6 +367,9 @@ pub struct ProverArtifacts {
     pub vk: plonk::VerifyingKey<G1Affine>,
     pk: OnceCell<Arc<plonk::ProvingKey<G1Affine>>>,
     prover_enabled: bool,
+    /// Set once a lazily loaded proving key fails to deserialize; the prover
+    /// stays disabled from then on instead of re-reading a bad key.
+    pk_corrupt: Arc<AtomicBool>,
 }
 
 impl ProverArtifacts {
@@ -326,6 +415,7 @@ impl ProverArtifacts {
             vk,
             pk: pk_cell,
             prover_enabled,
+            pk_corrupt: Arc::new(AtomicBool::new(false)),
         }
     }
 
@@ -372,19 +462,45 @@ impl ProverArtifacts {
         }
     }
 
+    /// Whether this deployment can prove. Turns false if the proving key
+    /// turned out to be corrupt (see [`Self::prover_degraded`]).
     pub fn prover_enabled(&self) -> bool {
-        self.prover_enabled
+        self.prover_enabled && !self.prover_degraded()
+    }
+
+    /// True once the proving key failed to deserialize or turned out not to
+    /// match the loaded verifying key. Read failures are not counted, since
+    /// they may be transient.
+    pub fn prover_degraded(&self) -> bool {
+        self.pk_corrupt.load(Ordering::Acquire)
     }
 
     pub fn proving_key(&self) -> Result<Arc<plonk::ProvingKey<G1Affine>>> {
         if !self.prover_enabled {
             anyhow::bail!("prover support is disabled for this deployment");
         }
+        if self.prover_degraded() {
+            anyhow::bail!("prover disabled: proving key is corrupt or does not match the vk");
+        }
 
         self.pk
             .get_or_try_init(|| {
                 let bytes = self.pk_blob()?;
-                deserialize_proving_key(&bytes).map(Arc::new)
+                let columns = self.manifest.instance_columns();
+                let pk = deserialize_proving_key_with_columns(&bytes, columns).and_then(|pk| {
+                    ensure_pk_matches_vk(&pk, &self.vk)?;
+                    Ok(pk)
+                });
+                pk.map(Arc::new).map_err(|err| {
+                    if !self.pk_corrupt.swap(true, Ordering::AcqRel) {
+                        eprintln!(
+                            "[ProverArtifacts] proving key at {} is unusable, disabling prover: {:#}",
+                            self.pk_path().display(),
+                            err
+                        );
+                    }
+                    err
+                })
             })
             .map(Arc::clone)
     }
@@ -403,6 +519,15 @@ impl ProverArtifacts {
     pub fn pk_path(&self) -> PathBuf {
         self.manifest.pk.resolve_path(&self.artifact_dir)
     }
+
+    /// Re-hashes the params, vk and pk files on disk and compares them with the
+    /// manifest, failing on the first mismatch.
+    ///
+    /// Unlike loading, nothing is deserialized and files are streamed, so this
+    /// is cheap enough to run periodically as a health check.
+    pub fn verify_integrity(&self) -> Result<()> {
+        check_artifact_files(&self.artifact_dir, &self.manifest)
+    }
 }
 
 pub fn serialize_params(params: &ParamsKZG<Bn256>) -> Result<Vec<u8>> {
@@ -413,11 +538,6 @@ pub fn serialize_params(params: &ParamsKZG<Bn256>) -> Result<Vec<u8>> {
     Ok(buf)
 }
 
-pub fn deserialize_params(bytes: &[u8]) -> Result<ParamsKZG<Bn256>> {
-    let mut reader = Cursor::new(bytes);
-    ParamsKZG::<Bn256>::read(&mut reader).context("failed to deserialize KZG params")
-}
-
 pub fn serialize_verifying_key(vk: &plonk::VerifyingKey<G1Affine>) -> Result<Vec<u8>> {
     let mut buf = vec![];
     vk.write(&mut buf, SerdeFormat::Processed)
@@ -425,25 +545,6 @@ pub fn serialize_verifying_key(vk: &plonk::VerifyingKey<G1Affine>) -> Result<Vec
     Ok(buf)
 }
 
-pub fn deserialize_verifying_key(bytes: &[u8]) -> Result<plonk::VerifyingKey<G1Affine>> {
-    let params = ZkpfCircuit::default().params();
-    let mut reader = Cursor::new(bytes);
-    plonk::VerifyingKey::read::<_, ZkpfCircuit>(&mut reader, SerdeFormat::Processed, params)
-        .context("failed to deserialize verifying key")
-}
-
-pub fn verifier_inputs_to_public(inputs: &VerifierPublicInputs) -> Result<PublicInputs> {
-    Ok(PublicInputs {
-        threshold_raw: inputs.threshold_raw,
-        required_currency_code: inputs.required_currency_code,
-        current_epoch: inputs.current_epoch,
-        verifier_scope_id: inputs.verifier_scope_id,
-        policy_id: inputs.policy_id,
-        nullifier: fr_from_bytes(&inputs.nullifier)?,
-        custodian_pubkey_hash: fr_from_bytes(&inputs.custodian_pubkey_hash)?,
-    })
-}
-
 pub fn public_to_verifier_inputs(public: &PublicInputs) -> VerifierPublicInputs {
     VerifierPublicInputs {
         threshold_raw: public.threshold_raw,
@@ -456,88 +557,29 @@ pub fn public_to_verifier_inputs(public: &PublicInputs) -> VerifierPublicInputs
         snapshot_block_height: None,
         snapshot_anchor_orchard: None,
         holder_binding: None,
-        proven_sum: None,
+        threshold_max: None,
+        proven_sum: public.proven_sum.map(u128::from),
+        challenge: public.challenge.as_ref().map(fr_to_bytes),
+        extra_nullifiers: None,
     }
 }
 
-pub fn public_inputs_to_instances(inputs: &VerifierPublicInputs) -> Result<Vec<Vec<Fr>>> {
-    let public = verifier_inputs_to_public(inputs)?;
-    Ok(public_instances(&public))
+/// Verifier-facing public inputs for a range proof (V5_RANGE layout).
+pub fn range_to_verifier_inputs(input: &ZkpfRangeCircuitInput) -> VerifierPublicInputs {
+    VerifierPublicInputs {
+        threshold_max: Some(input.threshold_max),
+        ..public_to_verifier_inputs(&input.public)
+    }
 }
 
-/// Convert verifier-facing public inputs into Halo2 instances for a specific layout.
-///
-/// - `PublicInputLayout::V1` uses the legacy custodial layout (8 public inputs).
-/// - `PublicInputLayout::V2Orchard` appends Orchard snapshot metadata as additional
-///   instance columns while preserving the V1 prefix ordering.
-pub fn public_inputs_to_instances_with_layout(
-    layout: PublicInputLayout,
-    inputs: &VerifierPublicInputs,
-) -> Result<Vec<Vec<Fr>>> {
-    match layout {
-        PublicInputLayout::V1 => public_inputs_to_instances(inputs),
-        PublicInputLayout::V2Orchard => {
-            let snapshot_height = inputs.snapshot_block_height.ok_or_else(|| {
-                anyhow!("snapshot_block_height is required for V2_ORCHARD public-input layout")
-            })?;
-            let snapshot_anchor_bytes = inputs.snapshot_anchor_orchard.ok_or_else(|| {
-                anyhow!("snapshot_anchor_orchard is required for V2_ORCHARD public-input layout")
-            })?;
-
-            // For now we treat a missing holder_binding as zero; rails that require a
-            // binding can enforce its presence at a higher layer.
-            let holder_binding_bytes = inputs.holder_binding.unwrap_or([0u8; 32]);
-
-            // Reuse the existing PublicInputs conversion for the V1 prefix.
-            let public = verifier_inputs_to_public(inputs)?;
-            let mut cols = public_instances(&public);
-
-            // Orchard-specific trailing fields.
-            let snapshot_height_fr = Fr::from(snapshot_height);
-            let anchor_fr = reduce_be_bytes_to_fr(&snapshot_anchor_bytes);
-            let holder_binding_fr = reduce_be_bytes_to_fr(&holder_binding_bytes);
-
-            cols.push(vec![snapshot_height_fr]);
-            cols.push(vec![anchor_fr]);
-            cols.push(vec![holder_binding_fr]);
-
-            Ok(cols)
-        }
-        PublicInputLayout::V3Starknet => {
-            // Starknet L2 layout: V1 prefix + 4 Starknet-specific fields = 11 columns total
-            let block_number = inputs.snapshot_block_height.ok_or_else(|| {
-                anyhow!("snapshot_block_height (block_number) is required for V3_STARKNET public-input layout")
-            })?;
-            // Reuse snapshot_anchor_orchard field for account_commitment
-            let account_commitment_bytes = inputs.snapshot_anchor_orchard.ok_or_else(|| {
-                anyhow!("snapshot_anchor_orchard (account_commitment) is required for V3_STARKNET public-input layout")
-            })?;
-            let holder_binding_bytes = inputs.holder_binding.unwrap_or([0u8; 32]);
-            // proven_sum is required for V3_STARKNET to match circuit's 11-column layout
-            let proven_sum = inputs.proven_sum.ok_or_else(|| {
-                anyhow!("proven_sum is required for V3_STARKNET public-input layout")
-            })?;
-
-            // Reuse the existing PublicInputs conversion for the V1 prefix (7 columns).
-            let public = verifier_inputs_to_public(inputs)?;
-            let mut cols = public_instances(&public);
-
-            // Starknet-specific trailing fields (4 columns: 7+4=11 total).
-            let block_number_fr = Fr::from(block_number);
-            let account_commitment_fr = reduce_be_bytes_to_fr(&account_commitment_bytes);
-            let holder_binding_fr = reduce_be_bytes_to_fr(&holder_binding_bytes);
-            // proven_sum as u128 -> split into two u64 limbs and pack into Fr
-            // For simplicity, we truncate to u64 here; full u128 support would need
-            // more complex encoding or multiple field elements
-            let proven_sum_fr = Fr::from(proven_sum as u64);
-
-            cols.push(vec![block_number_fr]);
-            cols.push(vec![account_commitment_fr]);
-            cols.push(vec![holder_binding_fr]);
-            cols.push(vec![proven_sum_fr]);
-
-            Ok(cols)
-        }
+/// Verifier-facing public inputs for an aggregate proof (V4_AGGREGATE layout):
+/// the first account's nullifier as `nullifier`, the rest as `extra_nullifiers`.
+pub fn aggregate_to_verifier_inputs(input: &ZkpfAggregateCircuitInput) -> VerifierPublicInputs {
+    let extra_nullifiers = (!input.extra_nullifiers.is_empty())
+        .then(|| input.extra_nullifiers.iter().map(fr_to_bytes).collect());
+    VerifierPublicInputs {
+        extra_nullifiers,
+        ..public_to_verifier_inputs(&input.public)
     }
 }
 
@@ -557,13 +599,27 @@ pub fn verifier_inputs_vector(inputs: &VerifierPublicInputs) -> Result<[Fr; PUBL
     Ok(public_inputs_vector(&verifier_inputs_to_public(inputs)?))
 }
 
+/// Decode V1 instances; an eighth column is read as a revealed `proven_sum`.
+///
+/// Challenge-bound proofs also carry eight columns and cannot be told apart by
+/// shape alone; their public inputs travel in the bundle instead.
 pub fn instances_to_public_inputs(instances: &[Vec<Fr>]) -> Result<PublicInputs> {
     ensure!(
-        instances.len() == PUBLIC_INPUT_COUNT,
-        "expected {} instance columns, got {}",
+        instances.len() == PUBLIC_INPUT_COUNT || instances.len() == PUBLIC_INPUT_COUNT_REVEAL_SUM,
+        "expected {} or {} instance columns, got {}",
         PUBLIC_INPUT_COUNT,
+        PUBLIC_INPUT_COUNT_REVEAL_SUM,
         instances.len()
     );
+    let proven_sum = if instances.len() == PUBLIC_INPUT_COUNT_REVEAL_SUM {
+        Some(fr_to_u64(&first_instance(
+            instances,
+            PUBLIC_INPUT_COUNT,
+            "proven_sum",
+        )?)?)
+    } else {
+        None
+    };
     Ok(PublicInputs {
         threshold_raw: fr_to_u64(&first_instance(instances, 0, "threshold_raw")?)?,
         required_currency_code: fr_to_u32(&first_instance(
@@ -576,6 +632,7 @@ pub fn instances_to_public_inputs(instances: &[Vec<Fr>]) -> Result<PublicInputs>
         policy_id: fr_to_u64(&first_instance(instances, 4, "policy_id")?)?,
         nullifier: first_instance(instances, 5, "nullifier")?,
         custodian_pubkey_hash: first_instance(instances, 6, "custodian_pubkey_hash")?,
+        proven_sum,
     })
 }
 
@@ -598,6 +655,41 @@ pub fn deserialize_verifier_public_inputs(bytes: &[u8]) -> Result<VerifierPublic
 /// consistent rail identification across the system.
 pub const DEFAULT_RAIL_ID: &str = "CUSTODIAL_ATTESTATION";
 
+/// Rail identifier for multi-account aggregate custodial proofs.
+pub const AGGREGATE_RAIL_ID: &str = "CUSTODIAL_AGGREGATE";
+
+/// Rail identifier for custodial proofs that reveal `proven_sum`.
+///
+/// Revealing proofs are verified with their own keys, so they are routed to a
+/// separate rail from `DEFAULT_RAIL_ID`.
+pub const REVEAL_SUM_RAIL_ID: &str = "CUSTODIAL_ATTESTATION_REVEAL_SUM";
+
+/// Rail identifier for custodial proofs bound to a verifier-issued challenge.
+///
+/// Like revealing proofs, challenge-bound proofs carry an extra instance column
+/// and are verified with their own keys.
+pub const CHALLENGE_RAIL_ID: &str = "CUSTODIAL_ATTESTATION_CHALLENGE";
+
+/// Rail identifier for custodial balance-range proofs.
+pub const RANGE_RAIL_ID: &str = "CUSTODIAL_RANGE";
+
+/// Rail identifier for proofs over balances held at several custodians.
+pub const MULTI_CUSTODIAN_RAIL_ID: &str = "CUSTODIAL_MULTI_CUSTODIAN";
+
+/// Rail identifier for custodial proofs over `u128` balances, for assets whose
+/// smallest-unit balances outgrow `u64` (e.g. 18-decimal tokens).
+pub const WIDE_RAIL_ID: &str = "CUSTODIAL_ATTESTATION_WIDE";
+
+/// The balance width proven on `rail_id`. Only [`WIDE_RAIL_ID`] is 128-bit;
+/// every other rail, including ZEC and fiat, keeps `u64` raw units.
+pub fn balance_width(rail_id: &str) -> BalanceWidth {
+    if rail_id == WIDE_RAIL_ID {
+        BalanceWidth::U128
+    } else {
+        BalanceWidth::U64
+    }
+}
+
 impl ProofBundle {
     /// Creates a new proof bundle with an empty rail_id (legacy compatibility).
     ///
@@ -607,6 +699,9 @@ impl ProofBundle {
             rail_id: String::new(),
             circuit_version: CIRCUIT_VERSION,
             proof,
+            proof_encoding: None,
+            issued_at: None,
+            issued_at_tag: None,
             public_inputs,
         }
     }
@@ -623,6 +718,9 @@ impl ProofBundle {
             rail_id: rail_id.into(),
             circuit_version: CIRCUIT_VERSION,
             proof,
+            proof_encoding: None,
+            issued_at: None,
+            issued_at_tag: None,
             public_inputs,
         }
     }
@@ -635,6 +733,191 @@ impl ProofBundle {
         self.rail_id = rail_id.into();
         self
     }
+
+    /// Returns the bundle with its proof zstd-compressed for transport.
+    ///
+    /// The SNARK is unchanged; verifiers recover it with [`Self::decoded_proof`].
+    /// Already-compressed bundles are returned as-is.
+    pub fn compressed(mut self) -> Result<Self> {
+        if self.proof_encoding == Some(ProofEncoding::Zstd) {
+            return Ok(self);
+        }
+        self.proof = zstd::bulk::compress(&self.proof, PROOF_ZSTD_LEVEL)
+            .context("failed to compress proof")?;
+        self.proof_encoding = Some(ProofEncoding::Zstd);
+        Ok(self)
+    }
+
+    /// Raw proof bytes, decompressing according to `proof_encoding`.
+    pub fn decoded_proof(&self) -> Result<Cow<'_, [u8]>> {
+        match self.proof_encoding {
+            None => Ok(Cow::Borrowed(&self.proof)),
+            Some(ProofEncoding::Zstd) => {
+                zstd::bulk::decompress(&self.proof, MAX_DECODED_PROOF_BYTES)
+                    .map(Cow::Owned)
+                    .context("failed to decompress zstd proof")
+            }
+        }
+    }
+
+    /// BLAKE3 hash of the decoded proof, identifying the proof independently
+    /// of its transport encoding.
+    pub fn proof_hash(&self) -> Result<[u8; 32]> {
+        Ok(*blake3::hash(&self.decoded_proof()?).as_bytes())
+    }
+
+    /// Records that the bundle was proven at `issued_at`, tagged under the
+    /// prover's signing `key` so a verifier holding the key can trust it.
+    pub fn stamp_issued_at(&mut self, issued_at: u64, key: &[u8; 32]) -> Result<()> {
+        self.issued_at_tag = Some(self.issuance_tag(issued_at, key)?);
+        self.issued_at = Some(issued_at);
+        Ok(())
+    }
+
+    /// `issued_at`, if the bundle carries a stamp whose tag verifies under
+    /// `key`. Unstamped bundles and forged or mismatched stamps yield `None`.
+    pub fn authenticated_issued_at(&self, key: &[u8; 32]) -> Option<u64> {
+        let (issued_at, tag) = (self.issued_at?, self.issued_at_tag?);
+        let expected = self.issuance_tag(issued_at, key).ok()?;
+        constant_time_eq(&expected, &tag).then_some(issued_at)
+    }
+
+    fn issuance_tag(&self, issued_at: u64, key: &[u8; 32]) -> Result<[u8; 32]> {
+        let mut hasher = blake3::Hasher::new_keyed(key);
+        hasher.update(ISSUANCE_TAG_DOMAIN);
+        hasher.update(&self.proof_hash()?);
+        hasher.update(&issued_at.to_be_bytes());
+        Ok(*hasher.finalize().as_bytes())
+    }
+}
+
+/// Domain separator for [`ProofBundle::stamp_issued_at`] tags.
+const ISSUANCE_TAG_DOMAIN: &[u8] = b"zkpf_proof_bundle_issued_at_v1";
+
+/// Domain separator for [`epoch_nullifier_root`].
+const EPOCH_NULLIFIER_ROOT_DOMAIN: &[u8] = b"zkpf_epoch_proof_set_nullifiers_v2";
+
+/// Shard proofs collected for one epoch, as handed to epoch aggregation.
+///
+/// All entries share a rail and circuit version, are stated for `epoch`, and no
+/// two entries carry the same nullifier: each shard is verified on its own, so a nullifier spent in
+/// two shards is only visible here. `nullifier_root` commits to the set of
+/// nullifiers, so an aggregate proof can be tied back to the exact set it was
+/// built from.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EpochProofSet {
+    pub epoch: u64,
+    pub rail_id: String,
+    pub entries: Vec<ProofBundle>,
+    #[serde(with = "serde_bytes32")]
+    pub nullifier_root: [u8; 32],
+}
+
+impl EpochProofSet {
+    /// Builds a set from `entries`, taking the rail from the first entry and
+    /// computing `nullifier_root`.
+    pub fn new(epoch: u64, entries: Vec<ProofBundle>) -> Result<Self> {
+        let rail_id = entries
+            .first()
+            .map(|entry| entry.rail_id.clone())
+            .ok_or_else(|| anyhow!("epoch proof set has no entries"))?;
+        let set = Self {
+            epoch,
+            rail_id,
+            nullifier_root: epoch_nullifier_root(&entries),
+            entries,
+        };
+        set.validate()?;
+        Ok(set)
+    }
+
+    /// Checks that the entries are homogeneous, are stated for the set's epoch,
+    /// carry distinct nullifiers and that `nullifier_root` matches them.
+    pub fn validate(&self) -> Result<()> {
+        let first = self
+            .entries
+            .first()
+            .ok_or_else(|| anyhow!("epoch proof set has no entries"))?;
+        for (idx, entry) in self.entries.iter().enumerate() {
+            ensure!(
+                entry.rail_id == self.rail_id,
+                "entry {} is on rail {:?}, expected {:?}",
+                idx,
+                entry.rail_id,
+                self.rail_id
+            );
+            ensure!(
+                entry.circuit_version == first.circuit_version,
+                "entry {} has circuit_version {}, expected {}",
+                idx,
+                entry.circuit_version,
+                first.circuit_version
+            );
+            ensure!(
+                entry.public_inputs.current_epoch == self.epoch,
+                "entry {} is for epoch {}, expected {}",
+                idx,
+                entry.public_inputs.current_epoch,
+                self.epoch
+            );
+        }
+        let collisions = nullifier_collisions(&self.entries);
+        ensure!(
+            collisions.is_empty(),
+            "entries share nullifiers (first index, duplicate index): {:?}",
+            collisions
+        );
+        ensure!(
+            self.nullifier_root == epoch_nullifier_root(&self.entries),
+            "nullifier_root does not match the set's entries"
+        );
+        Ok(())
+    }
+}
+
+/// Domain-separated BLAKE3 digest of the entries' nullifiers, sorted and
+/// de-duplicated, so it does not depend on entry order.
+pub fn epoch_nullifier_root(entries: &[ProofBundle]) -> [u8; 32] {
+    let mut nullifiers: Vec<[u8; 32]> = entries
+        .iter()
+        .map(|entry| entry.public_inputs.nullifier)
+        .collect();
+    nullifiers.sort_unstable();
+    nullifiers.dedup();
+
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(EPOCH_NULLIFIER_ROOT_DOMAIN);
+    for nullifier in &nullifiers {
+        hasher.update(nullifier);
+    }
+    *hasher.finalize().as_bytes()
+}
+
+/// Pairs `(first, duplicate)` of entry indices whose nullifiers collide.
+///
+/// Each repeat is paired with the first entry carrying that nullifier; an
+/// empty result means all nullifiers are distinct.
+pub fn nullifier_collisions(entries: &[ProofBundle]) -> Vec<(usize, usize)> {
+    let mut first_seen = HashMap::with_capacity(entries.len());
+    let mut collisions = Vec::new();
+    for (idx, entry) in entries.iter().enumerate() {
+        match first_seen.entry(entry.public_inputs.nullifier) {
+            Entry::Occupied(first) => collisions.push((*first.get(), idx)),
+            Entry::Vacant(slot) => {
+                slot.insert(idx);
+            }
+        }
+    }
+    collisions
+}
+
+/// Reads an [`EpochProofSet`] from JSON and validates it.
+pub fn read_epoch_proof_set(path: impl AsRef<Path>) -> Result<EpochProofSet> {
+    let bytes = fs::read(path.as_ref()).context("failed to read epoch proof set file")?;
+    let set: EpochProofSet =
+        serde_json::from_slice(&bytes).context("failed to parse epoch proof set json")?;
+    set.validate().context("invalid epoch proof set")?;
+    Ok(set)
 }
 
 pub fn write_manifest(path: impl AsRef<Path>, manifest: &ArtifactManifest) -> Result<()> {
@@ -647,12 +930,21 @@ pub fn read_manifest(path: impl AsRef<Path>) -> Result<ArtifactManifest> {
     serde_json::from_slice(&bytes).context("failed to parse manifest json")
 }
 
+/// Checks the artifacts a manifest describes against their recorded sizes and
+/// hashes without loading them; see [`ProverArtifacts::verify_integrity`].
+pub fn verify_artifact_integrity(path: impl AsRef<Path>) -> Result<()> {
+    let manifest_path = path.as_ref();
+    let manifest = read_manifest(manifest_path)?;
+    check_artifact_files(&artifact_base_dir(manifest_path), &manifest)
+}
+
 pub fn load_verifier_artifacts(path: impl AsRef<Path>) -> Result<VerifierArtifacts> {
     let manifest_path = path.as_ref();
     let (manifest, params_bytes, vk_bytes, _) = load_artifact_bytes(manifest_path)?;
 
     let params = deserialize_params(&params_bytes)?;
-    let vk = deserialize_verifying_key(&vk_bytes)?;
+    let vk = deserialize_verifying_key_with_columns(&vk_bytes, manifest.instance_columns())?;
+    ensure_artifact_set(&manifest, &params, &vk, None)?;
 
     Ok(VerifierArtifacts {
         manifest,
@@ -699,7 +991,7 @@ fn load_prover_artifacts_with_mode(
     let manifest_path = path.as_ref();
     let manifest = read_manifest(manifest_path)?;
     ensure_manifest_compat(&manifest)?;
-    let artifact_dir = manifest_dir(manifest_path);
+    let artifact_dir = artifact_base_dir(manifest_path);
 
     let params_bytes = read_artifact_file(&artifact_dir, &manifest.params, "params")?;
     let vk_bytes = read_artifact_file(&artifact_dir, &manifest.vk, "verifying key")?;
@@ -714,12 +1006,14 @@ fn load_prover_artifacts_with_mode(
     };
 
     let params = deserialize_params(&params_bytes)?;
-    let vk = deserialize_verifying_key(&vk_bytes)?;
+    let columns = manifest.instance_columns();
+    let vk = deserialize_verifying_key_with_columns(&vk_bytes, columns)?;
     let pk = if let Some(bytes) = pk_bytes {
-        Some(deserialize_proving_key(&bytes)?)
+        Some(deserialize_proving_key_with_columns(&bytes, columns)?)
     } else {
         None
     };
+    ensure_artifact_set(&manifest, &params, &vk, pk.as_ref())?;
 
     let lazy_prover = mode == LoadPkMode::Lazy;
     Ok(ProverArtifacts::from_parts_with_lazy(
@@ -740,7 +1034,16 @@ pub fn serialize_proving_key(pk: &plonk::ProvingKey<G1Affine>) -> Result<Vec<u8>
 }
 
 pub fn deserialize_proving_key(bytes: &[u8]) -> Result<plonk::ProvingKey<G1Affine>> {
-    let params = ZkpfCircuit::default().params();
+    deserialize_proving_key_with_columns(bytes, PUBLIC_INPUT_COUNT)
+}
+
+/// Proving-key counterpart of [`deserialize_verifying_key_with_columns`].
+pub fn deserialize_proving_key_with_columns(
+    bytes: &[u8],
+    num_instance_columns: usize,
+) -> Result<plonk::ProvingKey<G1Affine>> {
+    let mut params = ZkpfCircuit::default().params();
+    params.num_instance_columns = num_instance_columns;
     let mut reader = Cursor::new(bytes);
     plonk::ProvingKey::read::<_, ZkpfCircuit>(&mut reader, SerdeFormat::Processed, params)
         .context("failed to deserialize proving key")
@@ -750,10 +1053,10 @@ pub fn hash_bytes_hex(bytes: &[u8]) -> String {
     blake3::hash(bytes).to_hex().to_string()
 }
 
-pub fn fr_from_bytes(bytes: &[u8; 32]) -> Result<Fr> {
-    Fr::from_bytes(bytes)
-        .into_option()
-        .ok_or_else(|| anyhow!("invalid bn256 scalar encoding"))
+/// Byte-string equality whose running time does not depend on where the
+/// inputs differ. Use it for digests, MACs and tokens.
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.ct_eq(b).into()
 }
 
 pub fn fr_to_bytes(fr: &Fr) -> [u8; 32] {
@@ -781,53 +1084,6 @@ const BN256_SCALAR_FIELD_MODULUS: [u8; 32] = [
     0x43, 0xe1, 0xf5, 0x93, 0xf0, 0x00, 0x00, 0x01,
 ];
 
-/// Convert big-endian bytes to a field element with **modular reduction**.
-///
-/// # Security Warning
-///
-/// This function performs **implicit modular reduction**. A 32-byte value can represent
-/// integers up to 2^256 - 1, but the BN256 scalar field modulus is approximately 2^254.
-/// This means:
-///
-/// - **Values >= field modulus will wrap around** (reduced mod r)
-/// - **Two different byte inputs can produce the same field element** (collision)
-///
-/// # When to Use
-///
-/// Use this function when:
-/// - You're hashing external data where the output will be further processed (e.g., Poseidon hash)
-/// - Collisions don't matter because the result is used in a collision-resistant hash
-/// - You explicitly want modular reduction behavior
-///
-/// # When NOT to Use
-///
-/// Do NOT use this function when:
-/// - The 32-byte value must be uniquely recoverable from the field element
-/// - You need to verify that the original value fits in the field exactly
-/// - You're processing untrusted inputs that could exploit collisions
-///
-/// For those cases, use [`try_be_bytes_to_fr_exact`] which will error on overflow.
-///
-/// # Example
-///
-/// ```ignore
-/// // Safe: hashing pubkey coordinates for Poseidon - result goes into hash, collisions don't matter
-/// let x_fr = reduce_be_bytes_to_fr(&pubkey.x);
-/// let y_fr = reduce_be_bytes_to_fr(&pubkey.y);
-/// let hash = poseidon_hash(&[x_fr, y_fr]);
-///
-/// // Unsafe without validation: if you need to recover the original bytes
-/// // let value = reduce_be_bytes_to_fr(&untrusted_input); // May lose information!
-/// ```
-pub fn reduce_be_bytes_to_fr(bytes: &[u8; 32]) -> Fr {
-    let mut acc = Fr::zero();
-    let base = Fr::from(256);
-    for byte in bytes.iter() {
-        acc = acc * base + Fr::from(*byte as u64);
-    }
-    acc
-}
-
 /// Convert big-endian bytes to a field element with **exact representation check**.
 ///
 /// Returns an error if the 32-byte value is >= the BN256 scalar field modulus,
@@ -927,6 +1183,100 @@ pub fn nullifier_fr(account_id_hash: Fr, verifier_scope_id: u64, policy_id: u64,
     poseidon_hash(&[account_id_hash, scope_fr, policy_fr, epoch_fr])
 }
 
+/// Bind a holder identifier to a full viewing key with the shared Poseidon
+/// parameters, so the binding can be recomputed inside a circuit.
+///
+/// Both inputs are field elements; callers reduce longer identifiers (e.g. by
+/// hashing them and applying [`reduce_be_bytes_to_fr`]) before binding.
+pub fn holder_binding_poseidon(holder_id_fr: Fr, fvk_fr: Fr) -> Fr {
+    poseidon_hash(&[holder_id_fr, fvk_fr])
+}
+
+/// Check that the native Poseidon (nullifiers, roots, bindings) agrees with
+/// the circuit's sponge for every arity the circuits hash with. Drift here
+/// would make every derived public input unprovable.
+pub fn poseidon_self_test() -> Result<()> {
+    fn check<const L: usize>() -> Result<()> {
+        let values: [Fr; L] = std::array::from_fn(|i| Fr::from(i as u64 + 1));
+        let native = poseidon_hash(&values);
+        let in_circuit = zkpf_circuit::gadgets::poseidon::hash_elements_witness(&values);
+        ensure!(
+            native == in_circuit,
+            "native Poseidon over {L} inputs disagrees with the circuit's"
+        );
+        Ok(())
+    }
+    check::<2>()?;
+    check::<4>()?;
+    check::<7>()
+}
+
+/// Check that every public-input layout yields the column count its
+/// `PUBLIC_INPUT_COUNT_*` constant declares, with the V1 prefix first and in
+/// order, and that custodial inputs survive a round trip through their instances.
+pub fn layout_self_test() -> Result<()> {
+    let prefix = |builder: VerifierPublicInputsBuilder| {
+        builder
+            .threshold_raw(1_000)
+            .required_currency_code(840)
+            .current_epoch(1_700_000_000)
+            .verifier_scope_id(42)
+            .policy_id(7)
+            .nullifier(fr_to_bytes(&Fr::from(11u64)))
+            .custodian_pubkey_hash(fr_to_bytes(&Fr::from(12u64)))
+            .build()
+    };
+    let v1 = prefix(VerifierPublicInputsBuilder::for_v1())?;
+
+    let orchard = VerifierPublicInputsBuilder::for_orchard(2_000_000, [3; 32], [4; 32]);
+    let starknet = VerifierPublicInputsBuilder::for_starknet(900_000, [3; 32], [4; 32], 1_500);
+    let snapshot =
+        VerifierPublicInputsBuilder::for_snapshot_commitment(900_000, [3; 32], [4; 32], 1_500);
+    let range = VerifierPublicInputs {
+        threshold_max: Some(2_000),
+        ..v1.clone()
+    };
+    let revealed = VerifierPublicInputs {
+        proven_sum: Some(1_500),
+        ..v1.clone()
+    };
+    let samples = [
+        (PublicInputLayout::V1, v1.clone()),
+        (PublicInputLayout::V2Orchard, prefix(orchard)?),
+        (PublicInputLayout::V3Starknet, prefix(starknet)?),
+        (PublicInputLayout::V4Aggregate, v1.clone()),
+        (PublicInputLayout::V5Range, range),
+        (PublicInputLayout::V1WithSum, revealed),
+        (PublicInputLayout::V6SnapshotCommitment, prefix(snapshot)?),
+    ];
+    let expected_prefix = verifier_inputs_vector(&v1)?;
+    for (layout, inputs) in samples {
+        let instances = public_inputs_to_instances_with_layout(layout, &inputs)?;
+        ensure!(
+            instances.len() == layout.instance_columns(),
+            "{layout:?} yields {} instance columns, but its constant declares {}",
+            instances.len(),
+            layout.instance_columns()
+        );
+        let prefix: Vec<Fr> = instances[..PUBLIC_INPUT_COUNT]
+            .iter()
+            .map(|column| column[0])
+            .collect();
+        ensure!(
+            prefix == expected_prefix,
+            "{layout:?} does not start with the V1 columns in order"
+        );
+        if matches!(layout, PublicInputLayout::V1 | PublicInputLayout::V1WithSum) {
+            let decoded = instances_to_verifier_inputs(&instances)?;
+            ensure!(
+                serde_json::to_value(&decoded)? == serde_json::to_value(&inputs)?,
+                "{layout:?} public inputs change in a round trip through their instances"
+            );
+        }
+    }
+    Ok(())
+}
+
 pub fn compute_nullifier_fr(
     account_id_hash: &Fr,
     scope_id: u64,
@@ -955,7 +1305,136 @@ pub fn compute_nullifier_bytes(
     ))
 }
 
-fn poseidon_hash<const L: usize>(values: &[Fr; L]) -> Fr {
+/// The circuit's `account_id_hash` for a 32-byte account tag.
+///
+/// The tag is read as a big-endian integer and reduced into the scalar field,
+/// so tags that differ only by a multiple of the field modulus collide.
+pub fn account_id_hash_from_tag(account_tag: &[u8; 32]) -> Fr {
+    reduce_be_bytes_to_fr(account_tag)
+}
+
+/// Derive the nullifier for an account under a verifier scope, policy and epoch.
+///
+/// This is the only derivation the proving entrypoints use:
+/// `Poseidon(account_id_hash_from_tag(account_tag), verifier_scope_id, policy_id, epoch)`.
+/// The tag is `Attestation::account_id_hash` in the Zashi session flow and the
+/// decoded `account_tag` for provider attestations, so the same account gets the
+/// same nullifier whichever entrypoint produced the proof.
+pub fn derive_account_nullifier(
+    account_tag: &[u8; 32],
+    verifier_scope_id: u64,
+    policy_id: u64,
+    epoch: u64,
+) -> Fr {
+    compute_nullifier_fr(
+        &account_id_hash_from_tag(account_tag),
+        verifier_scope_id,
+        policy_id,
+        epoch,
+    )
+}
+
+/// Compute the aggregate nullifier for a set of accounts.
+///
+/// Mirrors the in-circuit `compute_aggregate_nullifier` gadget: each
+/// `account_id_hash` is hashed on its own, the results are summed, and the sum
+/// is used as the account input to [`compute_nullifier_fr`]. The result does
+/// not depend on the order of `account_id_hashes`.
+pub fn compute_aggregate_nullifier_fr(
+    account_id_hashes: &[Fr],
+    scope_id: u64,
+    policy_id: u64,
+    current_epoch: u64,
+) -> Fr {
+    let digest = account_id_hashes
+        .iter()
+        .fold(Fr::zero(), |acc, hash| acc + poseidon_hash(&[*hash]));
+    compute_nullifier_fr(&digest, scope_id, policy_id, current_epoch)
+}
+
+/// Hash a set of custodian public keys into the digest exposed by the
+/// aggregate circuit as `custodian_pubkey_hash`.
+///
+/// This is the sum of [`custodian_pubkey_hash`] over the set, so it is
+/// order-independent and equals the single-key hash for a one-element set.
+pub fn custodian_set_hash(pubkeys: &[Secp256k1Pubkey]) -> Fr {
+    pubkeys.iter().fold(Fr::zero(), |acc, pubkey| {
+        acc + custodian_pubkey_hash(pubkey)
+    })
+}
+
+/// Root of the custodian tree exposed by the multi-custodian circuit as
+/// `custodian_pubkey_hash`.
+///
+/// The leaves are [`custodian_pubkey_hash`] of each key in the order given,
+/// which must be the attestations' order (increasing `custodian_id`), padded
+/// with zero leaves to `zkpf_circuit::multi_custodian::MAX_CUSTODIANS`.
+pub fn custodian_set_root(pubkeys: &[Secp256k1Pubkey]) -> Result<Fr> {
+    let leaves = pubkeys.iter().map(custodian_pubkey_hash).collect();
+    let tree: MerkleTree = MerkleTree::new(leaves, CUSTODIAN_TREE_DEPTH, Fr::zero())?;
+    Ok(tree.root())
+}
+
+/// Root of the sorted blocklist tree checked by the screened circuit.
+///
+/// See [`blocklist_non_membership_witness`] for the tree layout.
+pub fn blocklist_root(blocklist: &[Fr]) -> Result<Fr> {
+    let leaves = sorted_blocklist_leaves(blocklist)?;
+    Ok(blocklist_tree(leaves)?.root())
+}
+
+/// Build the blocklist root and the non-membership witness for `value`.
+///
+/// Leaves are the distinct blocklist entries in ascending integer order,
+/// framed by the sentinels `0` and `p - 1`; unused slots are filled with
+/// `p - 1`. Fails if `value` is on the list or is one of the sentinels.
+pub fn blocklist_non_membership_witness(
+    blocklist: &[Fr],
+    value: &Fr,
+) -> Result<(Fr, NonMembershipWitness)> {
+    let leaves = sorted_blocklist_leaves(blocklist)?;
+    let high = match leaves.binary_search_by_key(&fr_to_be_bytes(value), fr_to_be_bytes) {
+        Ok(_) => return Err(anyhow!("value is on the blocklist or is a sentinel")),
+        Err(pos) => pos,
+    };
+    let low = high - 1;
+    let low_leaf = leaves[low];
+    let high_leaf = leaves[high];
+
+    let tree = blocklist_tree(leaves)?;
+    Ok((
+        tree.root(),
+        NonMembershipWitness {
+            low_leaf,
+            low_path: tree.proof(low)?.into(),
+            high_leaf,
+            high_path: tree.proof(high)?.into(),
+        },
+    ))
+}
+
+fn sorted_blocklist_leaves(blocklist: &[Fr]) -> Result<Vec<Fr>> {
+    let mut leaves = Vec::with_capacity(blocklist.len() + 2);
+    leaves.push(Fr::zero());
+    leaves.extend_from_slice(blocklist);
+    leaves.push(-Fr::one());
+    leaves.sort_by_key(fr_to_be_bytes);
+    leaves.dedup();
+    ensure!(
+        leaves.len() <= 1 << BLOCKLIST_TREE_DEPTH,
+        "blocklist has {} entries, tree holds at most {} including sentinels",
+        blocklist.len(),
+        1usize << BLOCKLIST_TREE_DEPTH
+    );
+    Ok(leaves)
+}
+
+/// The blocklist tree, with unused slots padded by the `p - 1` sentinel.
+fn blocklist_tree(leaves: Vec<Fr>) -> Result<MerkleTree> {
+    MerkleTree::new(leaves, BLOCKLIST_TREE_DEPTH, -Fr::one())
+}
+
+pub(crate) fn poseidon_hash<const L: usize>(values: &[Fr; L]) -> Fr {
     PoseidonHash::<Fr, ZkPoseidonSpec, ConstantLength<L>, POSEIDON_T, POSEIDON_RATE>::init()
         .hash(*values)
 }
@@ -1020,7 +1499,7 @@ fn load_artifact_bytes(
 ) -> Result<(ArtifactManifest, Vec<u8>, Vec<u8>, Vec<u8>)> {
     let manifest = read_manifest(manifest_path)?;
     ensure_manifest_compat(&manifest)?;
-    let base_dir = manifest_dir(manifest_path);
+    let base_dir = artifact_base_dir(manifest_path);
 
     let params_bytes = read_artifact_file(&base_dir, &manifest.params, "params")?;
     let vk_bytes = read_artifact_file(&base_dir, &manifest.vk, "verifying key")?;
@@ -1044,10 +1523,38 @@ fn read_artifact_file(base_dir: &Path, entry: &ArtifactFile, label: &str) -> Res
     Ok(bytes)
 }
 
+fn check_artifact_files(base_dir: &Path, manifest: &ArtifactManifest) -> Result<()> {
+    check_artifact_file(base_dir, &manifest.params, "params")?;
+    check_artifact_file(base_dir, &manifest.vk, "verifying key")?;
+    check_artifact_file(base_dir, &manifest.pk, "proving key")
+}
+
+/// Streaming counterpart of `read_artifact_file`: the file is hashed as it is
+/// read instead of being held in memory.
+fn check_artifact_file(base_dir: &Path, entry: &ArtifactFile, label: &str) -> Result<()> {
+    let path = entry.resolve_path(base_dir);
+    let mut file = fs::File::open(&path)
+        .with_context(|| format!("failed to read {} at {}", label, path.display()))?;
+    let mut hasher = blake3::Hasher::new();
+    let size = io::copy(&mut file, &mut hasher)
+        .with_context(|| format!("failed to read {} at {}", label, path.display()))?;
+    ensure!(
+        size == entry.size,
+        "{} size mismatch, manifest recorded {} bytes but found {}",
+        label,
+        entry.size,
+        size,
+    );
+    ensure_hex_hash(hasher.finalize().to_hex().as_str(), &entry.blake3, label)
+}
+
 fn ensure_hash(bytes: &[u8], expected_hex: &str, label: &str) -> Result<()> {
-    let actual = hash_bytes_hex(bytes);
+    ensure_hex_hash(&hash_bytes_hex(bytes), expected_hex, label)
+}
+
+fn ensure_hex_hash(actual: &str, expected_hex: &str, label: &str) -> Result<()> {
     ensure!(
-        actual == expected_hex,
+        constant_time_eq(actual.as_bytes(), expected_hex.as_bytes()),
         "{} hash mismatch, expected {} but computed {}",
         label,
         expected_hex,
@@ -1056,10 +1563,63 @@ fn ensure_hash(bytes: &[u8], expected_hex: &str, label: &str) -> Result<()> {
     Ok(())
 }
 
-fn manifest_dir(path: &Path) -> PathBuf {
-    path.parent()
-        .map(Path::to_path_buf)
-        .unwrap_or_else(|| PathBuf::from("."))
+/// Directory a manifest's `params`/`vk`/`pk` paths are resolved against:
+/// `ZKPF_ARTIFACT_DIR` when set, otherwise the manifest's own directory.
+pub fn artifact_base_dir(manifest_path: &Path) -> PathBuf {
+    resolve_artifact_base_dir(
+        manifest_path,
+        env::var_os(ARTIFACT_DIR_ENV).map(PathBuf::from),
+    )
+}
+
+fn resolve_artifact_base_dir(manifest_path: &Path, override_dir: Option<PathBuf>) -> PathBuf {
+    match override_dir {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => manifest_path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| PathBuf::from(".")),
+    }
+}
+
+/// Checks that params, vk and pk come from the same keygen run.
+///
+/// A mixed set loads fine but fails every proof or verification later, so
+/// reject it at load time instead.
+fn ensure_artifact_set(
+    manifest: &ArtifactManifest,
+    params: &ParamsKZG<Bn256>,
+    vk: &plonk::VerifyingKey<G1Affine>,
+    pk: Option<&plonk::ProvingKey<G1Affine>>,
+) -> Result<()> {
+    ensure!(
+        params.k() == manifest.k,
+        "artifact set mismatch: params have k={} but manifest declares k={}",
+        params.k(),
+        manifest.k
+    );
+    let vk_k = vk.get_domain().k();
+    ensure!(
+        vk_k == manifest.k,
+        "artifact set mismatch: verifying key has k={} but manifest declares k={}",
+        vk_k,
+        manifest.k
+    );
+    if let Some(pk) = pk {
+        ensure_pk_matches_vk(pk, vk)?;
+    }
+    Ok(())
+}
+
+fn ensure_pk_matches_vk(
+    pk: &plonk::ProvingKey<G1Affine>,
+    vk: &plonk::VerifyingKey<G1Affine>,
+) -> Result<()> {
+    ensure!(
+        pk.get_vk().transcript_repr() == vk.transcript_repr(),
+        "artifact set mismatch: proving key was generated with a different verifying key"
+    );
+    Ok(())
 }
 
 fn ensure_manifest_compat(manifest: &ArtifactManifest) -> Result<()> {
@@ -1175,16 +1735,21 @@ mod serde_bytes32 {
 mod tests {
     use super::*;
     use halo2curves_axiom::bn256::Fr as BnFr;
+    use zkpf_circuit::public_instances;
+
+    const SAMPLE_EPOCH: u64 = 1_700_000_000;
 
     fn sample_public_inputs() -> PublicInputs {
         PublicInputs {
             threshold_raw: 1000,
             required_currency_code: 840,
-            current_epoch: 1_700_000_000,
+            current_epoch: SAMPLE_EPOCH,
             verifier_scope_id: 99,
             policy_id: 7,
             nullifier: Fr::from(123456789u64),
             custodian_pubkey_hash: Fr::from(987654321u64),
+            proven_sum: None,
+            challenge: None,
         }
     }
 
@@ -1210,6 +1775,74 @@ mod tests {
         );
     }
 
+    #[test]
+    fn startup_self_tests_pass() {
+        poseidon_self_test().unwrap();
+        layout_self_test().unwrap();
+    }
+
+    #[test]
+    fn artifact_integrity_detects_modified_file() {
+        let dir = std::env::temp_dir().join(format!("zkpf-integrity-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for (name, contents) in [("params.bin", "params"), ("vk.bin", "vk"), ("pk.bin", "pk")] {
+            fs::write(dir.join(name), contents).unwrap();
+        }
+        let manifest = ArtifactManifest {
+            manifest_version: MANIFEST_VERSION,
+            circuit_version: CIRCUIT_VERSION,
+            k: 4,
+            created_at_unix: 0,
+            params: ArtifactFile::from_bytes("params.bin", b"params"),
+            vk: ArtifactFile::from_bytes("vk.bin", b"vk"),
+            pk: ArtifactFile::from_bytes("pk.bin", b"pk"),
+            num_instance_columns: None,
+        };
+        let manifest_path = dir.join(MANIFEST_FILE);
+        write_manifest(&manifest_path, &manifest).unwrap();
+        verify_artifact_integrity(&manifest_path).unwrap();
+
+        fs::write(dir.join("vk.bin"), b"VK").unwrap();
+        let err = verify_artifact_integrity(&manifest_path).unwrap_err();
+        assert!(err.to_string().contains("verifying key hash mismatch"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn artifact_base_dir_prefers_override() {
+        let manifest = Path::new("/etc/zkpf/manifest.json");
+        assert_eq!(
+            resolve_artifact_base_dir(manifest, None),
+            PathBuf::from("/etc/zkpf")
+        );
+        assert_eq!(
+            resolve_artifact_base_dir(manifest, Some(PathBuf::new())),
+            PathBuf::from("/etc/zkpf")
+        );
+        assert_eq!(
+            resolve_artifact_base_dir(manifest, Some(PathBuf::from("/data/zkpf"))),
+            PathBuf::from("/data/zkpf")
+        );
+    }
+
+    #[test]
+    fn constant_time_eq_matches_slice_equality() {
+        assert!(constant_time_eq(b"abc123", b"abc123"));
+        assert!(!constant_time_eq(b"abc123", b"abc124"));
+        assert!(!constant_time_eq(b"abc123", b"abc12"));
+        assert!(constant_time_eq(b"", b""));
+    }
+
+    #[test]
+    fn holder_binding_poseidon_is_order_sensitive() {
+        let holder = Fr::from(7u64);
+        let fvk = Fr::from(11u64);
+        let binding = holder_binding_poseidon(holder, fvk);
+        assert_eq!(binding, holder_binding_poseidon(holder, fvk));
+        assert_ne!(binding, holder_binding_poseidon(fvk, holder));
+    }
+
     #[test]
     fn fr_bytes_round_trip() {
         let value = Fr::from(2024u64);
@@ -1231,6 +1864,254 @@ mod tests {
         assert_eq!(decoded.nullifier, public.nullifier);
     }
 
+    fn epoch_entry(rail_id: &str, nullifier_byte: u8) -> ProofBundle {
+        let mut public_inputs = public_to_verifier_inputs(&sample_public_inputs());
+        public_inputs.nullifier = [nullifier_byte; 32];
+        ProofBundle::new_with_rail(rail_id, vec![nullifier_byte], public_inputs)
+    }
+
+    #[test]
+    fn epoch_proof_set_commits_to_entry_nullifiers() {
+        let entries = vec![
+            epoch_entry(DEFAULT_RAIL_ID, 1),
+            epoch_entry(DEFAULT_RAIL_ID, 2),
+        ];
+        let set = EpochProofSet::new(SAMPLE_EPOCH, entries.clone()).unwrap();
+        assert_eq!(set.rail_id, DEFAULT_RAIL_ID);
+        assert_eq!(set.nullifier_root, epoch_nullifier_root(&entries));
+
+        let json = serde_json::to_vec(&set).unwrap();
+        let mut decoded: EpochProofSet = serde_json::from_slice(&json).unwrap();
+        decoded.validate().unwrap();
+
+        decoded.entries[1].public_inputs.nullifier = [3u8; 32];
+        assert!(decoded.validate().is_err());
+    }
+
+    #[test]
+    fn epoch_proof_set_rejects_mixed_entries() {
+        assert!(EpochProofSet::new(SAMPLE_EPOCH, Vec::new()).is_err());
+
+        let mixed_rails = vec![
+            epoch_entry(DEFAULT_RAIL_ID, 1),
+            epoch_entry(RANGE_RAIL_ID, 2),
+        ];
+        assert!(EpochProofSet::new(SAMPLE_EPOCH, mixed_rails).is_err());
+
+        let mut old_version = epoch_entry(DEFAULT_RAIL_ID, 2);
+        old_version.circuit_version -= 1;
+        let mixed_versions = vec![epoch_entry(DEFAULT_RAIL_ID, 1), old_version];
+        assert!(EpochProofSet::new(SAMPLE_EPOCH, mixed_versions).is_err());
+
+        let mut earlier = epoch_entry(DEFAULT_RAIL_ID, 2);
+        earlier.public_inputs.current_epoch -= 1;
+        let mixed_epochs = vec![epoch_entry(DEFAULT_RAIL_ID, 1), earlier];
+        assert!(EpochProofSet::new(SAMPLE_EPOCH, mixed_epochs).is_err());
+        let entries = vec![epoch_entry(DEFAULT_RAIL_ID, 1)];
+        assert!(EpochProofSet::new(SAMPLE_EPOCH + 1, entries).is_err());
+    }
+
+    #[test]
+    fn epoch_proof_set_rejects_nullifiers_shared_across_shards() {
+        let entries = vec![
+            epoch_entry(DEFAULT_RAIL_ID, 1),
+            epoch_entry(DEFAULT_RAIL_ID, 2),
+            epoch_entry(DEFAULT_RAIL_ID, 1),
+            epoch_entry(DEFAULT_RAIL_ID, 1),
+        ];
+        assert_eq!(nullifier_collisions(&entries), vec![(0, 2), (0, 3)]);
+        assert!(EpochProofSet::new(SAMPLE_EPOCH, entries).is_err());
+
+        // The root commits to the set, not the order it was collected in.
+        let forward = [
+            epoch_entry(DEFAULT_RAIL_ID, 1),
+            epoch_entry(DEFAULT_RAIL_ID, 2),
+        ];
+        let reversed = [
+            epoch_entry(DEFAULT_RAIL_ID, 2),
+            epoch_entry(DEFAULT_RAIL_ID, 1),
+        ];
+        assert_eq!(
+            epoch_nullifier_root(&forward),
+            epoch_nullifier_root(&reversed)
+        );
+    }
+
+    #[test]
+    fn aggregate_layout_matches_v1_instances() {
+        let verifier = public_to_verifier_inputs(&sample_public_inputs());
+        let v1 = public_inputs_to_instances_with_layout(PublicInputLayout::V1, &verifier).unwrap();
+        let aggregate =
+            public_inputs_to_instances_with_layout(PublicInputLayout::V4Aggregate, &verifier)
+                .unwrap();
+        assert_eq!(aggregate.len(), PUBLIC_INPUT_COUNT_V4_AGGREGATE);
+        assert_eq!(aggregate, v1);
+    }
+
+    #[test]
+    fn range_layout_appends_threshold_max() {
+        let public = sample_public_inputs();
+        let mut verifier = public_to_verifier_inputs(&public);
+        assert!(
+            public_inputs_to_instances_with_layout(PublicInputLayout::V5Range, &verifier).is_err()
+        );
+
+        verifier.threshold_max = Some(50_000);
+        let instances =
+            public_inputs_to_instances_with_layout(PublicInputLayout::V5Range, &verifier).unwrap();
+        assert_eq!(instances.len(), PUBLIC_INPUT_COUNT_V5_RANGE);
+        assert_eq!(
+            instances[..PUBLIC_INPUT_COUNT],
+            public_instances(&public)[..]
+        );
+        assert_eq!(instances[PUBLIC_INPUT_COUNT], vec![Fr::from(50_000u64)]);
+    }
+
+    #[test]
+    fn revealed_sum_round_trips_through_v1_instances() {
+        let mut public = sample_public_inputs();
+        public.proven_sum = Some(5_000_000);
+        let verifier = public_to_verifier_inputs(&public);
+        assert_eq!(verifier.proven_sum, Some(5_000_000));
+
+        let instances =
+            public_inputs_to_instances_with_layout(PublicInputLayout::V1, &verifier).unwrap();
+        assert_eq!(instances.len(), PUBLIC_INPUT_COUNT_REVEAL_SUM);
+        assert_eq!(instances[PUBLIC_INPUT_COUNT], vec![Fr::from(5_000_000u64)]);
+        let decoded = instances_to_public_inputs(&instances).unwrap();
+        assert_eq!(decoded.proven_sum, Some(5_000_000));
+
+        let mut range = verifier.clone();
+        range.threshold_max = Some(u64::MAX);
+        let range_instances =
+            public_inputs_to_instances_with_layout(PublicInputLayout::V5Range, &range).unwrap();
+        assert_eq!(range_instances.len(), PUBLIC_INPUT_COUNT_V5_RANGE);
+    }
+
+    #[test]
+    fn compressed_bundle_round_trips_proof() {
+        let proof: Vec<u8> = (0..4096u32).map(|i| (i % 7) as u8).collect();
+        let bundle = ProofBundle::new(
+            proof.clone(),
+            public_to_verifier_inputs(&sample_public_inputs()),
+        );
+
+        let compressed = bundle.clone().compressed().unwrap();
+        assert_eq!(compressed.proof_encoding, Some(ProofEncoding::Zstd));
+        assert!(compressed.proof.len() < proof.len());
+        assert_eq!(
+            compressed.decoded_proof().unwrap().as_ref(),
+            proof.as_slice()
+        );
+
+        let json = serde_json::to_value(&compressed).unwrap();
+        assert_eq!(json["proof_encoding"], "zstd");
+        let raw_json = serde_json::to_value(&bundle).unwrap();
+        assert!(raw_json.get("proof_encoding").is_none());
+    }
+
+    #[test]
+    fn issued_at_is_only_trusted_with_a_valid_tag() {
+        let key = [7u8; 32];
+        let mut bundle = ProofBundle::new(
+            vec![1, 2, 3],
+            public_to_verifier_inputs(&sample_public_inputs()),
+        );
+        assert_eq!(bundle.authenticated_issued_at(&key), None);
+
+        bundle.stamp_issued_at(1_700_000_000, &key).unwrap();
+        assert_eq!(bundle.authenticated_issued_at(&key), Some(1_700_000_000));
+        assert_eq!(bundle.authenticated_issued_at(&[8u8; 32]), None);
+        let compressed = bundle.clone().compressed().unwrap();
+        assert_eq!(compressed.authenticated_issued_at(&key), bundle.issued_at);
+
+        let mut backdated = bundle.clone();
+        backdated.issued_at = Some(1_800_000_000);
+        assert_eq!(backdated.authenticated_issued_at(&key), None);
+        let mut other_proof = bundle;
+        other_proof.proof = vec![4, 5, 6];
+        assert_eq!(other_proof.authenticated_issued_at(&key), None);
+    }
+
+    #[test]
+    fn account_nullifier_is_pinned_to_the_tag() {
+        let mut tag = [0u8; 32];
+        tag[31] = 1;
+        assert_eq!(account_id_hash_from_tag(&tag), Fr::one());
+        assert_eq!(
+            derive_account_nullifier(&tag, 99, 7, 1_700_000_000),
+            nullifier_fr(Fr::one(), 99, 7, 1_700_000_000)
+        );
+
+        // Each public input is bound into the nullifier.
+        let base = derive_account_nullifier(&tag, 99, 7, 1_700_000_000);
+        assert_ne!(base, derive_account_nullifier(&tag, 100, 7, 1_700_000_000));
+        assert_ne!(base, derive_account_nullifier(&tag, 99, 8, 1_700_000_000));
+        assert_ne!(base, derive_account_nullifier(&tag, 99, 7, 1_700_000_001));
+        tag[0] = 1;
+        assert_ne!(base, derive_account_nullifier(&tag, 99, 7, 1_700_000_000));
+    }
+
+    #[test]
+    fn account_nullifier_matches_attestation_witness() {
+        let tag = [0x5au8; 32];
+        let attestation = Attestation {
+            balance_raw: 1,
+            currency_code_int: 840,
+            custodian_id: 2,
+            attestation_id: 3,
+            issued_at: 4,
+            valid_until: 5,
+            account_id_hash: tag,
+            custodian_pubkey: Secp256k1Pubkey {
+                x: [0u8; 32],
+                y: [0u8; 32],
+            },
+            signature: EcdsaSignature {
+                r: [0u8; 32],
+                s: [0u8; 32],
+            },
+            message_hash: [0u8; 32],
+            signature_format: SignatureFormat::RawHash,
+        };
+        let witness = attestation.to_witness();
+        assert_eq!(
+            derive_account_nullifier(&tag, 99, 7, 1_700_000_000),
+            compute_nullifier_fr(&witness.account_id_hash, 99, 7, 1_700_000_000)
+        );
+    }
+
+    #[test]
+    fn aggregate_nullifier_is_order_independent() {
+        let accounts = [Fr::from(1u64), Fr::from(2u64), Fr::from(3u64)];
+        let reversed = [accounts[2], accounts[1], accounts[0]];
+        assert_eq!(
+            compute_aggregate_nullifier_fr(&accounts, 99, 7, 1_700_000_000),
+            compute_aggregate_nullifier_fr(&reversed, 99, 7, 1_700_000_000)
+        );
+        assert_ne!(
+            compute_aggregate_nullifier_fr(&accounts, 99, 7, 1_700_000_000),
+            compute_aggregate_nullifier_fr(&accounts[..2], 99, 7, 1_700_000_000)
+        );
+    }
+
+    #[test]
+    fn custodian_set_root_pads_with_zero_leaves() {
+        let pubkey = |byte| Secp256k1Pubkey {
+            x: [byte; 32],
+            y: [byte; 32],
+        };
+        let leaf = custodian_pubkey_hash(&pubkey(1));
+        let zero = Fr::zero();
+        let expected = poseidon_hash(&[poseidon_hash(&[leaf, zero]), poseidon_hash(&[zero, zero])]);
+        assert_eq!(custodian_set_root(&[pubkey(1)]).unwrap(), expected);
+        assert_ne!(
+            custodian_set_root(&[pubkey(1), pubkey(2)]).unwrap(),
+            custodian_set_root(&[pubkey(2), pubkey(1)]).unwrap()
+        );
+        assert!(custodian_set_root(&[pubkey(1); 5]).is_err());
+    }
+
     #[test]
     fn instances_to_public_inputs_rejects_wrong_len() {
         let bad_instances = vec![vec![Fr::one()]; PUBLIC_INPUT_COUNT - 1];
@@ -1351,4 +2232,120 @@ mod tests {
             assert_eq!(reduced, exact, "Results differ for value {:?}", value);
         }
     }
+
+    #[test]
+    fn canonical_attestation_bytes_match_published_vectors() {
+        #[derive(Deserialize)]
+        struct Vector {
+            name: String,
+            balance_raw: u64,
+            currency_code_int: u32,
+            custodian_id: u32,
+            attestation_id: u64,
+            issued_at: u64,
+            valid_until: u64,
+            #[serde(with = "serde_bytes32")]
+            account_id_hash: [u8; 32],
+            preimage: String,
+        }
+
+        #[derive(Deserialize)]
+        struct VectorFile {
+            vectors: Vec<Vector>,
+        }
+
+        let file: VectorFile = serde_json::from_str(include_str!(
+            "../test-vectors/attestation_message_hash.json"
+        ))
+        .unwrap();
+        assert!(!file.vectors.is_empty());
+
+        for vector in &file.vectors {
+            let fields = AttestationFields {
+                balance_raw: vector.balance_raw,
+                currency_code_int: vector.currency_code_int,
+                custodian_id: vector.custodian_id,
+                attestation_id: vector.attestation_id,
+                issued_at: vector.issued_at,
+                valid_until: vector.valid_until,
+                account_id_hash: &vector.account_id_hash,
+            };
+            let preimage = canonical_attestation_bytes(&fields);
+            let hex: String = preimage.iter().map(|b| format!("{:02x}", b)).collect();
+            assert_eq!(
+                format!("0x{hex}"),
+                vector.preimage,
+                "preimage mismatch for vector {}",
+                vector.name
+            );
+        }
+    }
+
+    #[test]
+    fn attestation_json_keys_are_pinned() {
+        let attestation = Attestation {
+            balance_raw: 1,
+            currency_code_int: 840,
+            custodian_id: 2,
+            attestation_id: 3,
+            issued_at: 4,
+            valid_until: 5,
+            account_id_hash: [0u8; 32],
+            custodian_pubkey: Secp256k1Pubkey {
+                x: [0u8; 32],
+                y: [0u8; 32],
+            },
+            signature: EcdsaSignature {
+                r: [0u8; 32],
+                s: [0u8; 32],
+            },
+            message_hash: [0u8; 32],
+            signature_format: SignatureFormat::RawHash,
+        };
+        let value = serde_json::to_value(&attestation).unwrap();
+        let mut keys: Vec<&str> = value
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        keys.sort_unstable();
+        assert_eq!(
+            keys,
+            [
+                "account_id_hash",
+                "attestation_id",
+                "balance_raw",
+                "currency_code_int",
+                "custodian_id",
+                "custodian_pubkey",
+                "issued_at",
+                "message_hash",
+                "signature",
+                "valid_until",
+            ]
+        );
+    }
+
+    #[test]
+    fn blocklist_witness_brackets_value() {
+        let blocklist = [Fr::from(30u64), Fr::from(10u64), Fr::from(20u64)];
+        let root = blocklist_root(&blocklist).unwrap();
+
+        let (witness_root, witness) =
+            blocklist_non_membership_witness(&blocklist, &Fr::from(15u64)).unwrap();
+        assert_eq!(witness_root, root);
+        assert_eq!(witness.low_leaf, Fr::from(10u64));
+        assert_eq!(witness.high_leaf, Fr::from(20u64));
+        assert_eq!(witness.high_path.index, witness.low_path.index + 1);
+        assert_eq!(witness.low_path.siblings.len(), BLOCKLIST_TREE_DEPTH);
+
+        let (_, above_all) =
+            blocklist_non_membership_witness(&blocklist, &Fr::from(1_000u64)).unwrap();
+        assert_eq!(above_all.low_leaf, Fr::from(30u64));
+        assert_eq!(above_all.high_leaf, -Fr::one());
+
+        assert!(blocklist_non_membership_witness(&blocklist, &Fr::from(20u64)).is_err());
+        assert!(blocklist_non_membership_witness(&blocklist, &Fr::zero()).is_err());
+    }
 }
@@ -0,0 +1,259 @@
+// zkpf/zkpf-common/src/merkle.rs
+// Numan Thabit 2025
+
+//! Fixed-depth binary Merkle trees shared by every Merkle-based feature.
+//!
+//! A tree is built from its leaves by [`MerkleTree::new`]; missing leaves up to
+//! `2^depth` are filled with a padding node, and each level's padding is the
+//! hash of the level below's, so the root only depends on the real leaves and
+//! the padding choice. A node at `index` is the left child when bit `level` of
+//! `index` is 0, the convention of the in-circuit path check
+//! (`zkpf_circuit::gadgets::blocklist`).
+//!
+//! Which hash to use:
+//!
+//! - [`PoseidonHasher`] for any root a circuit recomputes: the custodian
+//!   allowlist, the blocklist non-membership tree ([`crate::blocklist_root`]
+//!   is built on it) and a nullifier epoch root. It uses the circuit's
+//!   Poseidon parameters, so native and in-circuit roots agree.
+//! - [`Blake3Hasher`] for trees only checked off-circuit, such as audit logs or
+//!   artifact manifests. It is much faster, but a circuit cannot recompute it.
+//!
+//! The Orchard note commitment tree is not one of these: it hashes with
+//! Sinsemilla and stays with the Orchard crates.
+
+use std::fmt::Debug;
+
+use anyhow::{ensure, Result};
+use halo2curves_axiom::bn256::Fr;
+use serde::{Deserialize, Serialize};
+use zkpf_circuit::gadgets::blocklist::MerklePathWitness;
+
+use crate::poseidon_hash;
+
+/// Deepest tree supported; leaf indices must fit in a `u64`.
+pub const MAX_MERKLE_DEPTH: usize = 63;
+
+/// Node hash for a [`MerkleTree`].
+pub trait MerkleHasher {
+    type Node: Copy + Eq + Debug;
+
+    /// Hash two children into their parent.
+    fn hash_pair(left: &Self::Node, right: &Self::Node) -> Self::Node;
+}
+
+/// Poseidon over BN256 `Fr` with the circuit's parameters.
+#[derive(Clone, Copy, Debug)]
+pub struct PoseidonHasher;
+
+impl MerkleHasher for PoseidonHasher {
+    type Node = Fr;
+
+    fn hash_pair(left: &Fr, right: &Fr) -> Fr {
+        poseidon_hash(&[*left, *right])
+    }
+}
+
+/// BLAKE3 over 32-byte nodes, for trees that never enter a circuit.
+///
+/// Leaves and parents are hashed under different prefixes, so a leaf can never
+/// be passed off as an inner node; build leaves with [`Blake3Hasher::leaf`].
+#[derive(Clone, Copy, Debug)]
+pub struct Blake3Hasher;
+
+impl Blake3Hasher {
+    const LEAF_PREFIX: u8 = 0;
+    const NODE_PREFIX: u8 = 1;
+
+    /// The leaf node for `data`.
+    pub fn leaf(data: &[u8]) -> [u8; 32] {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&[Self::LEAF_PREFIX]);
+        hasher.update(data);
+        *hasher.finalize().as_bytes()
+    }
+}
+
+impl MerkleHasher for Blake3Hasher {
+    type Node = [u8; 32];
+
+    fn hash_pair(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&[Self::NODE_PREFIX]);
+        hasher.update(left);
+        hasher.update(right);
+        *hasher.finalize().as_bytes()
+    }
+}
+
+/// Authentication path for one leaf. `siblings[0]` is the leaf's neighbour.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerkleProof<N> {
+    pub index: u64,
+    pub siblings: Vec<N>,
+}
+
+impl From<MerkleProof<Fr>> for MerklePathWitness {
+    fn from(proof: MerkleProof<Fr>) -> Self {
+        Self {
+            index: proof.index,
+            siblings: proof.siblings,
+        }
+    }
+}
+
+/// A Merkle tree with every level kept, so proofs are lookups.
+#[derive(Clone, Debug)]
+pub struct MerkleTree<H: MerkleHasher = PoseidonHasher> {
+    leaf_count: usize,
+    levels: Vec<Vec<H::Node>>,
+}
+
+/// A [`MerkleTree`] hashed with BLAKE3.
+pub type Blake3MerkleTree = MerkleTree<Blake3Hasher>;
+
+impl<H: MerkleHasher> MerkleTree<H> {
+    /// Build a tree of `depth` levels over `leaves`, padding with `padding`.
+    pub fn new(leaves: Vec<H::Node>, depth: usize, padding: H::Node) -> Result<Self> {
+        ensure!(
+            depth <= MAX_MERKLE_DEPTH,
+            "merkle depth {} exceeds the maximum of {}",
+            depth,
+            MAX_MERKLE_DEPTH
+        );
+        ensure!(
+            leaves.len() as u128 <= 1u128 << depth,
+            "{} leaves do not fit in a tree of depth {}",
+            leaves.len(),
+            depth
+        );
+
+        let leaf_count = leaves.len();
+        let mut padding = padding;
+        let mut levels = vec![leaves];
+        for level in 0..depth {
+            let nodes = &mut levels[level];
+            while nodes.is_empty() || nodes.len() % 2 == 1 {
+                nodes.push(padding);
+            }
+            let parents: Vec<H::Node> = nodes
+                .chunks(2)
+                .map(|pair| H::hash_pair(&pair[0], &pair[1]))
+                .collect();
+            levels.push(parents);
+            padding = H::hash_pair(&padding, &padding);
+        }
+        if levels[depth].is_empty() {
+            levels[depth].push(padding);
+        }
+        Ok(Self { leaf_count, levels })
+    }
+
+    pub fn depth(&self) -> usize {
+        self.levels.len() - 1
+    }
+
+    pub fn leaf_count(&self) -> usize {
+        self.leaf_count
+    }
+
+    pub fn root(&self) -> H::Node {
+        self.levels[self.depth()][0]
+    }
+
+    /// The authentication path for the leaf at `index`.
+    pub fn proof(&self, index: usize) -> Result<MerkleProof<H::Node>> {
+        ensure!(
+            index < self.leaf_count,
+            "leaf index {} out of range for {} leaves",
+            index,
+            self.leaf_count
+        );
+        Ok(MerkleProof {
+            index: index as u64,
+            siblings: (0..self.depth())
+                .map(|level| self.levels[level][(index >> level) ^ 1])
+                .collect(),
+        })
+    }
+
+    /// Whether `proof` opens `leaf` under `root`.
+    pub fn verify_proof(root: &H::Node, leaf: &H::Node, proof: &MerkleProof<H::Node>) -> bool {
+        if proof.siblings.len() > MAX_MERKLE_DEPTH || proof.index >> proof.siblings.len() != 0 {
+            return false;
+        }
+        let computed = proof
+            .siblings
+            .iter()
+            .enumerate()
+            .fold(*leaf, |node, (level, sibling)| {
+                if (proof.index >> level) & 1 == 0 {
+                    H::hash_pair(&node, sibling)
+                } else {
+                    H::hash_pair(sibling, &node)
+                }
+            });
+        computed == *root
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2curves_axiom::ff::Field;
+
+    use super::*;
+
+    type Tree = MerkleTree<PoseidonHasher>;
+
+    fn leaves(count: u64) -> Vec<Fr> {
+        (1..=count).map(Fr::from).collect()
+    }
+
+    #[test]
+    fn proofs_verify_for_every_leaf() {
+        let tree = Tree::new(leaves(5), 4, Fr::zero()).unwrap();
+        let root = tree.root();
+        for (index, leaf) in leaves(5).iter().enumerate() {
+            let proof = tree.proof(index).unwrap();
+            assert_eq!(proof.siblings.len(), 4);
+            assert!(Tree::verify_proof(&root, leaf, &proof));
+            assert!(!Tree::verify_proof(&root, &Fr::from(99u64), &proof));
+        }
+        assert!(tree.proof(5).is_err());
+    }
+
+    #[test]
+    fn root_matches_hand_computed_tree() {
+        let hash = PoseidonHasher::hash_pair;
+        let tree = Tree::new(leaves(3), 2, Fr::zero()).unwrap();
+        let left = hash(&Fr::from(1u64), &Fr::from(2u64));
+        let right = hash(&Fr::from(3u64), &Fr::zero());
+        assert_eq!(tree.root(), hash(&left, &right));
+
+        let empty = Tree::new(Vec::new(), 1, Fr::zero()).unwrap();
+        assert_eq!(empty.root(), hash(&Fr::zero(), &Fr::zero()));
+    }
+
+    #[test]
+    fn blake3_tree_rejects_tampered_paths() {
+        let leaves: Vec<[u8; 32]> = [b"a", b"b", b"c"]
+            .iter()
+            .map(|data| Blake3Hasher::leaf(*data))
+            .collect();
+        let tree = Blake3MerkleTree::new(leaves.clone(), 8, [0; 32]).unwrap();
+        let root = tree.root();
+        let mut proof = tree.proof(2).unwrap();
+        assert!(Blake3MerkleTree::verify_proof(&root, &leaves[2], &proof));
+
+        proof.index = 1;
+        assert!(!Blake3MerkleTree::verify_proof(&root, &leaves[2], &proof));
+        proof.index = 2 + (1 << 8);
+        assert!(!Blake3MerkleTree::verify_proof(&root, &leaves[2], &proof));
+    }
+
+    #[test]
+    fn oversized_trees_are_rejected() {
+        assert!(Tree::new(leaves(5), 2, Fr::zero()).is_err());
+        assert!(Blake3MerkleTree::new(Vec::new(), MAX_MERKLE_DEPTH + 1, [0; 32]).is_err());
+    }
+}
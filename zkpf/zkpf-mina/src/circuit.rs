@@ -93,7 +93,10 @@ static SAMPLE_INPUT: Lazy<MinaPofCircuitInput> = Lazy::new(|| MinaPofCircuitInpu
         snapshot_block_height: Some(500_000),
         snapshot_anchor_orchard: Some([0u8; 32]),
         holder_binding: Some([0u8; 32]),
+        threshold_max: None,
         proven_sum: Some(1_000_000),
+        challenge: None,
+        extra_nullifiers: None,
     },
     source_proof_commitments: vec![[0u8; 32]],
 });
@@ -696,7 +699,10 @@ mod tests {
                 snapshot_block_height: Some(500_000),
                 snapshot_anchor_orchard: Some([1u8; 32]),
                 holder_binding: Some([2u8; 32]),
+                threshold_max: None,
                 proven_sum: Some(8_000_000),
+                challenge: None,
+                extra_nullifiers: None,
             },
             source_proof_commitments: vec![[3u8; 32], [4u8; 32]],
         };
@@ -735,7 +741,10 @@ mod tests {
             snapshot_block_height: Some(500_000),
             snapshot_anchor_orchard: Some([1u8; 32]),
             holder_binding: Some([2u8; 32]),
+            threshold_max: None,
             proven_sum: Some(8_000_000),
+            challenge: None,
+            extra_nullifiers: None,
         };
 
         let instances = mina_public_inputs_to_instances(&public_inputs).expect("should work");
@@ -212,7 +212,10 @@ pub fn build_verifier_public_inputs(
         snapshot_block_height: Some(mina_meta.global_slot),
         snapshot_anchor_orchard: Some(mina_meta.recursive_proof_commitment),
         holder_binding: Some(compute_zkapp_commitment(&mina_meta.zkapp_address)),
+        threshold_max: None,
         proven_sum: Some(proven_sum),
+        challenge: None,
+        extra_nullifiers: None,
     }
 }
 
@@ -318,6 +321,9 @@ pub fn prove_mina_recursive(
         rail_id: RAIL_ID_MINA.to_string(),
         circuit_version: CIRCUIT_VERSION,
         proof,
+        proof_encoding: None,
+        issued_at: None,
+        issued_at_tag: None,
         public_inputs,
     })
 }
@@ -601,7 +607,10 @@ pub fn create_proof_of_state_bundle(
         snapshot_block_height: Some(mina_meta.global_slot),
         snapshot_anchor_orchard: Some(rail_inputs.mina_digest), // Store mina_digest
         holder_binding: Some(rail_inputs.holder_binding),
+        threshold_max: None,
         proven_sum: None,
+        challenge: None,
+        extra_nullifiers: None,
     };
 
     // Create wrapper circuit input
@@ -615,6 +624,9 @@ pub fn create_proof_of_state_bundle(
         rail_id: RAIL_ID_MINA.to_string(),
         circuit_version: CIRCUIT_VERSION,
         proof,
+        proof_encoding: None,
+        issued_at: None,
+        issued_at_tag: None,
         public_inputs,
     })
 }
@@ -722,6 +734,9 @@ mod tests {
                 rail_id: "STARKNET_L2".to_string(),
                 circuit_version: CIRCUIT_VERSION,
                 proof: vec![0u8; 64],
+                proof_encoding: None,
+                issued_at: None,
+                issued_at_tag: None,
                 public_inputs: VerifierPublicInputs {
                     threshold_raw: 1_000_000_000_000_000_000, // 1 ETH
                     required_currency_code: 1027,
@@ -733,7 +748,10 @@ mod tests {
                     snapshot_block_height: Some(123456),
                     snapshot_anchor_orchard: Some([1u8; 32]),
                     holder_binding: Some([2u8; 32]),
+                    threshold_max: None,
                     proven_sum: Some(5_000_000_000_000_000_000),
+                    challenge: None,
+                    extra_nullifiers: None,
                 },
             },
             rail_metadata: serde_json::json!({
@@ -486,6 +486,9 @@ mod tests {
             rail_id: RAIL_ID_STARKNET.to_string(),
             circuit_version: CIRCUIT_VERSION,
             proof: vec![0u8; 64], // Placeholder proof
+            proof_encoding: None,
+            issued_at: None,
+            issued_at_tag: None,
             public_inputs: VerifierPublicInputs {
                 threshold_raw: 1_000_000_000_000_000_000, // 1 ETH
                 required_currency_code: 1027,            // ETH
@@ -497,7 +500,10 @@ mod tests {
                 snapshot_block_height: Some(123456),
                 snapshot_anchor_orchard: Some([2u8; 32]), // account_commitment
                 holder_binding: Some([3u8; 32]),
+                threshold_max: None,
                 proven_sum: Some(5_000_000_000_000_000_000), // 5 ETH
+                challenge: None,
+                extra_nullifiers: None,
             },
         }
     }
@@ -303,6 +303,9 @@ mod tests {
                 rail_id: RAIL_ID_MINA.to_string(),
                 circuit_version: CIRCUIT_VERSION,
                 proof: vec![0u8; 64],
+                proof_encoding: None,
+                issued_at: None,
+                issued_at_tag: None,
                 public_inputs: VerifierPublicInputs {
                     threshold_raw: 1_000_000,
                     required_currency_code: 1027,
@@ -314,7 +317,10 @@ mod tests {
                     snapshot_block_height: Some(500_000),
                     snapshot_anchor_orchard: Some([1u8; 32]),
                     holder_binding: Some([2u8; 32]),
+                    threshold_max: None,
                     proven_sum: Some(5_000_000),
+                    challenge: None,
+                    extra_nullifiers: None,
                 },
             },
             mina_meta: MinaPublicMeta {
@@ -43,6 +43,9 @@ fn sample_source_proof() -> SourceProofInput {
             rail_id: "ZCASH_ORCHARD".to_string(),
             circuit_version: CIRCUIT_VERSION,
             proof: vec![0u8; 128],
+            proof_encoding: None,
+            issued_at: None,
+            issued_at_tag: None,
             public_inputs: VerifierPublicInputs {
                 threshold_raw: 1_000_000,
                 required_currency_code: 840,
@@ -54,7 +57,10 @@ fn sample_source_proof() -> SourceProofInput {
                 snapshot_block_height: Some(100),
                 snapshot_anchor_orchard: Some([1u8; 32]),
                 holder_binding: Some([2u8; 32]),
+                threshold_max: None,
                 proven_sum: Some(2_000_000),
+                challenge: None,
+                extra_nullifiers: None,
             },
         },
         rail_metadata: serde_json::json!({
@@ -10,10 +10,24 @@ use halo2_proofs_axiom::{
     transcript::{Blake2bWrite, Challenge255, TranscriptWriterBuffer},
 };
 use halo2curves_axiom::bn256::{Bn256, Fr, G1Affine};
+use k256::ecdsa::{signature::hazmat::PrehashVerifier, Signature, VerifyingKey};
 use rand::{rngs::OsRng, RngCore};
 
-use zkpf_circuit::{ZkpfCircuit, ZkpfCircuitInput};
-use zkpf_common::{public_to_verifier_inputs, ProofBundle, VerifierPublicInputs};
+use zkpf_circuit::{
+    aggregate::{
+        aggregate_circuit_params, aggregate_public_instances, ZkpfAggregateCircuit,
+        ZkpfAggregateCircuitInput,
+    },
+    gadgets::attestation::AttestationWitness,
+    multi_custodian::{ZkpfMultiCustodianCircuit, ZkpfMultiCustodianCircuitInput},
+    ranged::{range_public_instances, ZkpfRangeCircuit, ZkpfRangeCircuitInput},
+    CircuitShape, ZkpfCircuit, ZkpfCircuitInput,
+};
+use zkpf_common::{
+    aggregate_to_verifier_inputs, public_to_verifier_inputs, range_to_verifier_inputs, ProofBundle,
+    VerifierPublicInputs, AGGREGATE_RAIL_ID, CHALLENGE_RAIL_ID, MULTI_CUSTODIAN_RAIL_ID,
+    RANGE_RAIL_ID, REVEAL_SUM_RAIL_ID,
+};
 
 pub struct ProverParams {
     pub params: ParamsKZG<Bn256>,
@@ -30,6 +44,42 @@ pub fn setup(k: u32) -> ProverParams {
     ProverParams { params, vk, pk }
 }
 
+/// Generates parameters and keys for custodial proofs that reveal `proven_sum`.
+///
+/// The revealing circuit has an extra instance column, so its keys are not
+/// interchangeable with those from [`setup`].
+pub fn setup_reveal_sum(k: u32) -> Result<ProverParams, ProofGenError> {
+    setup_shape(
+        k,
+        CircuitShape {
+            reveal_sum: true,
+            challenge: false,
+        },
+    )
+}
+
+/// Generates parameters and keys for custodial proofs bound to a
+/// verifier-issued challenge.
+pub fn setup_challenge(k: u32) -> Result<ProverParams, ProofGenError> {
+    setup_shape(
+        k,
+        CircuitShape {
+            reveal_sum: false,
+            challenge: true,
+        },
+    )
+}
+
+fn setup_shape(k: u32, shape: CircuitShape) -> Result<ProverParams, ProofGenError> {
+    let mut rng = OsRng;
+    let params = ParamsKZG::<Bn256>::setup(k, &mut rng);
+    let empty_circuit = ZkpfCircuit::keygen_for(shape);
+    let vk = keygen_vk(&params, &empty_circuit).map_err(|e| ProofGenError(format!("{:?}", e)))?;
+    let pk = keygen_pk(&params, vk.clone(), &empty_circuit)
+        .map_err(|e| ProofGenError(format!("{:?}", e)))?;
+    Ok(ProverParams { params, vk, pk })
+}
+
 pub fn prove(
     params: &ParamsKZG<Bn256>,
     pk: &plonk::ProvingKey<G1Affine>,
@@ -54,7 +104,20 @@ pub fn prove_bundle(
     input: ZkpfCircuitInput,
 ) -> ProofBundle {
     let (proof, public_inputs) = prove_with_public_inputs(params, pk, input);
-    ProofBundle::new(proof, public_inputs)
+    custodial_bundle(proof, public_inputs)
+}
+
+/// Bundles a custodial proof, tagging revealing proofs with `REVEAL_SUM_RAIL_ID`
+/// and challenge-bound proofs with `CHALLENGE_RAIL_ID` so they are routed to the
+/// matching verifying key.
+fn custodial_bundle(proof: Vec<u8>, public_inputs: VerifierPublicInputs) -> ProofBundle {
+    if public_inputs.proven_sum.is_some() {
+        ProofBundle::new_with_rail(REVEAL_SUM_RAIL_ID, proof, public_inputs)
+    } else if public_inputs.challenge.is_some() {
+        ProofBundle::new_with_rail(CHALLENGE_RAIL_ID, proof, public_inputs)
+    } else {
+        ProofBundle::new(proof, public_inputs)
+    }
 }
 
 // ============================================================
@@ -106,7 +169,7 @@ pub fn prove_bundle_with_rng<R: RngCore>(
     rng: &mut R,
 ) -> ProofBundle {
     let (proof, public_inputs) = prove_with_public_inputs_and_rng(params, pk, input, rng);
-    ProofBundle::new(proof, public_inputs)
+    custodial_bundle(proof, public_inputs)
 }
 
 /// Error type for proof generation failures.
@@ -140,7 +203,266 @@ pub fn prove_bundle_result_with_rng<R: RngCore>(
 ) -> Result<ProofBundle, ProofGenError> {
     let public_inputs = public_to_verifier_inputs(&input.public);
     let proof = create_proof_bytes_with_rng_result(params, pk, input, rng)?;
-    Ok(ProofBundle::new(proof, public_inputs))
+    Ok(custodial_bundle(proof, public_inputs))
+}
+
+// ============================================================
+// Multi-account aggregate proving
+// ============================================================
+
+/// Generates parameters and keys for the aggregate circuit sized to `num_accounts`.
+///
+/// Keys are specific to `num_accounts`; proofs for a different account count
+/// need their own setup. The circuit size is fixed by
+/// [`aggregate_circuit_params`], so the parameters are generated at that `k`.
+pub fn setup_aggregate(num_accounts: usize) -> Result<ProverParams, ProofGenError> {
+    let mut rng = OsRng;
+    let params = ParamsKZG::<Bn256>::setup(aggregate_circuit_params().k as u32, &mut rng);
+    let empty_circuit =
+        ZkpfAggregateCircuit::keygen(num_accounts).map_err(|e| ProofGenError(e.to_string()))?;
+    let vk = keygen_vk(&params, &empty_circuit).map_err(|e| ProofGenError(format!("{:?}", e)))?;
+    let pk = keygen_pk(&params, vk.clone(), &empty_circuit)
+        .map_err(|e| ProofGenError(format!("{:?}", e)))?;
+    Ok(ProverParams { params, vk, pk })
+}
+
+/// Proves that the combined balance of several attestations meets the threshold.
+///
+/// Each attestation signature is checked against its custodian key before
+/// proving, since the circuit does not verify ECDSA. `input.public.nullifier`
+/// and `input.extra_nullifiers` must be the attestations' own nullifiers, in
+/// order. The returned bundle is tagged with `AGGREGATE_RAIL_ID`.
+pub fn prove_aggregate(
+    params: &ParamsKZG<Bn256>,
+    pk: &plonk::ProvingKey<G1Affine>,
+    input: ZkpfAggregateCircuitInput,
+) -> Result<ProofBundle, ProofGenError> {
+    prove_aggregate_with_rng(params, pk, input, &mut OsRng)
+}
+
+/// Same as [`prove_aggregate`] with a custom RNG source.
+pub fn prove_aggregate_with_rng<R: RngCore>(
+    params: &ParamsKZG<Bn256>,
+    pk: &plonk::ProvingKey<G1Affine>,
+    input: ZkpfAggregateCircuitInput,
+    rng: &mut R,
+) -> Result<ProofBundle, ProofGenError> {
+    if input.public.proven_sum.is_some() {
+        return Err(ProofGenError(
+            "aggregate proofs cannot reveal proven_sum".into(),
+        ));
+    }
+    for (idx, attestation) in input.attestations.iter().enumerate() {
+        verify_attestation_signature(attestation)
+            .map_err(|e| ProofGenError(format!("attestation {}: {}", idx, e)))?;
+    }
+
+    let public_inputs = aggregate_to_verifier_inputs(&input);
+    let instance_slices = aggregate_public_instances(&input.public, &input.extra_nullifiers);
+    let instance_refs: Vec<&[Fr]> = instance_slices.iter().map(|col| col.as_slice()).collect();
+
+    let circuit =
+        ZkpfAggregateCircuit::new_prover(input).map_err(|e| ProofGenError(e.to_string()))?;
+
+    let mut transcript = Blake2bWrite::<_, G1Affine, Challenge255<_>>::init(vec![]);
+    create_proof::<KZGCommitmentScheme<Bn256>, ProverGWC<'_, Bn256>, _, _, _, _>(
+        params,
+        pk,
+        &[circuit],
+        &[instance_refs.as_slice()],
+        rng,
+        &mut transcript,
+    )
+    .map_err(|e| ProofGenError(format!("{:?}", e)))?;
+
+    Ok(ProofBundle::new_with_rail(
+        AGGREGATE_RAIL_ID,
+        transcript.finalize(),
+        public_inputs,
+    ))
+}
+
+// ============================================================
+// Multi-custodian proving
+// ============================================================
+
+/// Generates parameters and keys for the multi-custodian circuit sized to
+/// `num_custodians`, at the `k` shared with the aggregate circuit.
+pub fn setup_multi_custodian(num_custodians: usize) -> Result<ProverParams, ProofGenError> {
+    let mut rng = OsRng;
+    let params = ParamsKZG::<Bn256>::setup(aggregate_circuit_params().k as u32, &mut rng);
+    let empty_circuit = ZkpfMultiCustodianCircuit::keygen(num_custodians)
+        .map_err(|e| ProofGenError(e.to_string()))?;
+    let vk = keygen_vk(&params, &empty_circuit).map_err(|e| ProofGenError(format!("{:?}", e)))?;
+    let pk = keygen_pk(&params, vk.clone(), &empty_circuit)
+        .map_err(|e| ProofGenError(format!("{:?}", e)))?;
+    Ok(ProverParams { params, vk, pk })
+}
+
+/// Proves that balances attested by several custodians sum to at least the
+/// threshold.
+///
+/// The attestations are put in `custodian_id` order, the order the circuit
+/// and `custodian_pubkey_hash` (see `zkpf_common::custodian_set_root`) expect.
+/// A repeated custodian or a bad signature is rejected before proving. The
+/// returned bundle is tagged with `MULTI_CUSTODIAN_RAIL_ID`.
+pub fn prove_multi_custodian(
+    params: &ParamsKZG<Bn256>,
+    pk: &plonk::ProvingKey<G1Affine>,
+    input: ZkpfMultiCustodianCircuitInput,
+) -> Result<ProofBundle, ProofGenError> {
+    prove_multi_custodian_with_rng(params, pk, input, &mut OsRng)
+}
+
+/// Same as [`prove_multi_custodian`] with a custom RNG source.
+pub fn prove_multi_custodian_with_rng<R: RngCore>(
+    params: &ParamsKZG<Bn256>,
+    pk: &plonk::ProvingKey<G1Affine>,
+    mut input: ZkpfMultiCustodianCircuitInput,
+    rng: &mut R,
+) -> Result<ProofBundle, ProofGenError> {
+    if input.public.proven_sum.is_some() {
+        return Err(ProofGenError(
+            "multi-custodian proofs cannot reveal proven_sum".into(),
+        ));
+    }
+    input.attestations.sort_by_key(|att| att.custodian_id);
+    if let Some(pair) = input
+        .attestations
+        .windows(2)
+        .find(|pair| pair[0].custodian_id == pair[1].custodian_id)
+    {
+        return Err(ProofGenError(format!(
+            "custodian {} has more than one attestation",
+            pair[0].custodian_id
+        )));
+    }
+    for attestation in &input.attestations {
+        verify_attestation_signature(attestation)
+            .map_err(|e| ProofGenError(format!("custodian {}: {}", attestation.custodian_id, e)))?;
+    }
+
+    let public_inputs = public_to_verifier_inputs(&input.public);
+    let instance_slices = zkpf_circuit::public_instances(&input.public);
+    let instance_refs: Vec<&[Fr]> = instance_slices.iter().map(|col| col.as_slice()).collect();
+
+    let circuit =
+        ZkpfMultiCustodianCircuit::new_prover(input).map_err(|e| ProofGenError(e.to_string()))?;
+
+    let mut transcript = Blake2bWrite::<_, G1Affine, Challenge255<_>>::init(vec![]);
+    create_proof::<KZGCommitmentScheme<Bn256>, ProverGWC<'_, Bn256>, _, _, _, _>(
+        params,
+        pk,
+        &[circuit],
+        &[instance_refs.as_slice()],
+        rng,
+        &mut transcript,
+    )
+    .map_err(|e| ProofGenError(format!("{:?}", e)))?;
+
+    Ok(ProofBundle::new_with_rail(
+        MULTI_CUSTODIAN_RAIL_ID,
+        transcript.finalize(),
+        public_inputs,
+    ))
+}
+
+// ============================================================
+// Balance-range proving
+// ============================================================
+
+/// Generates parameters and keys for the balance-range circuit.
+pub fn setup_range(k: u32) -> Result<ProverParams, ProofGenError> {
+    let mut rng = OsRng;
+    let params = ParamsKZG::<Bn256>::setup(k, &mut rng);
+    let empty_circuit = ZkpfRangeCircuit::default();
+    let vk = keygen_vk(&params, &empty_circuit).map_err(|e| ProofGenError(format!("{:?}", e)))?;
+    let pk = keygen_pk(&params, vk.clone(), &empty_circuit)
+        .map_err(|e| ProofGenError(format!("{:?}", e)))?;
+    Ok(ProverParams { params, vk, pk })
+}
+
+/// Proves that the attested balance lies in `[threshold_raw, threshold_max]`.
+///
+/// The attestation signature is checked before proving, and a band that is
+/// empty or excludes the balance is rejected up front instead of producing an
+/// unsatisfiable circuit. The returned bundle is tagged with `RANGE_RAIL_ID`.
+pub fn prove_range(
+    params: &ParamsKZG<Bn256>,
+    pk: &plonk::ProvingKey<G1Affine>,
+    input: ZkpfRangeCircuitInput,
+) -> Result<ProofBundle, ProofGenError> {
+    prove_range_with_rng(params, pk, input, &mut OsRng)
+}
+
+/// Same as [`prove_range`] with a custom RNG source.
+pub fn prove_range_with_rng<R: RngCore>(
+    params: &ParamsKZG<Bn256>,
+    pk: &plonk::ProvingKey<G1Affine>,
+    input: ZkpfRangeCircuitInput,
+    rng: &mut R,
+) -> Result<ProofBundle, ProofGenError> {
+    let (min, max) = (input.public.threshold_raw, input.threshold_max);
+    if min > max {
+        return Err(ProofGenError(format!(
+            "threshold_raw {} exceeds threshold_max {}",
+            min, max
+        )));
+    }
+    let balance = input.attestation.balance_raw;
+    if balance < min || balance > max {
+        return Err(ProofGenError(format!(
+            "balance is outside the range [{}, {}]",
+            min, max
+        )));
+    }
+    verify_attestation_signature(&input.attestation).map_err(|e| ProofGenError(e.to_string()))?;
+
+    let public_inputs = range_to_verifier_inputs(&input);
+    let instance_slices = range_public_instances(&input.public, input.threshold_max);
+    let instance_refs: Vec<&[Fr]> = instance_slices.iter().map(|col| col.as_slice()).collect();
+
+    let circuit = ZkpfRangeCircuit::new_prover(input);
+
+    let mut transcript = Blake2bWrite::<_, G1Affine, Challenge255<_>>::init(vec![]);
+    create_proof::<KZGCommitmentScheme<Bn256>, ProverGWC<'_, Bn256>, _, _, _, _>(
+        params,
+        pk,
+        &[circuit],
+        &[instance_refs.as_slice()],
+        rng,
+        &mut transcript,
+    )
+    .map_err(|e| ProofGenError(format!("{:?}", e)))?;
+
+    Ok(ProofBundle::new_with_rail(
+        RANGE_RAIL_ID,
+        transcript.finalize(),
+        public_inputs,
+    ))
+}
+
+fn verify_attestation_signature(attestation: &AttestationWitness) -> Result<(), &'static str> {
+    let mut pubkey_bytes = [0u8; 65];
+    pubkey_bytes[0] = 0x04;
+    pubkey_bytes[1..33].copy_from_slice(&attestation.custodian_pubkey.x);
+    pubkey_bytes[33..65].copy_from_slice(&attestation.custodian_pubkey.y);
+    let verifying_key =
+        VerifyingKey::from_sec1_bytes(&pubkey_bytes).map_err(|_| "invalid custodian public key")?;
+
+    let mut sig_bytes = [0u8; 64];
+    sig_bytes[..32].copy_from_slice(&attestation.signature.r);
+    sig_bytes[32..].copy_from_slice(&attestation.signature.s);
+    let signature = Signature::from_slice(&sig_bytes).map_err(|_| "invalid signature format")?;
+
+    // The signed digest (message_hash itself for raw-hash signatures) is
+    // already a hash, so verify it as a prehash.
+    let digest = attestation
+        .signature_format
+        .signed_digest(&attestation.message_hash);
+    verifying_key
+        .verify_prehash(&digest, &signature)
+        .map_err(|_| "signature verification failed")
 }
 
 fn create_proof_bytes(
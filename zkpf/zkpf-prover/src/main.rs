@@ -64,6 +64,9 @@ fn main() -> Result<()> {
                 rail_id: String::new(),
                 circuit_version: artifacts.manifest.circuit_version,
                 proof: proof.clone(),
+                proof_encoding: None,
+                issued_at: None,
+                issued_at_tag: None,
                 public_inputs: public_inputs.clone(),
             },
         )?;
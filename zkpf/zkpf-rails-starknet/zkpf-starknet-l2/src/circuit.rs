@@ -182,7 +182,10 @@ impl Circuit<Fr> for StarknetPofCircuit {
                 snapshot_block_height: Some(0),
                 snapshot_anchor_orchard: Some([0u8; 32]),
                 holder_binding: Some([0u8; 32]),
+                threshold_max: None,
                 proven_sum: Some(0),
+                challenge: None,
+                extra_nullifiers: None,
             },
             account_values: vec![0],
         };
@@ -1131,7 +1134,10 @@ impl StarknetCircuitInputBuilder {
                 snapshot_block_height: Some(block_number),
                 snapshot_anchor_orchard: Some(account_commitment),
                 holder_binding: Some(holder_binding),
+                threshold_max: None,
                 proven_sum: Some(total),
+                challenge: None,
+                extra_nullifiers: None,
             },
             account_values: self.account_values,
         })
@@ -1171,7 +1177,10 @@ mod tests {
                 snapshot_block_height: Some(123456),
                 snapshot_anchor_orchard: Some([1u8; 32]),
                 holder_binding: Some([2u8; 32]),
+                threshold_max: None,
                 proven_sum: Some(8_000_000),
+                challenge: None,
+                extra_nullifiers: None,
             },
             account_values: vec![5_000_000, 3_000_000],
         };
@@ -1220,7 +1229,10 @@ mod tests {
             snapshot_block_height: Some(123456),
             snapshot_anchor_orchard: Some([1u8; 32]),
             holder_binding: Some([2u8; 32]),
+            threshold_max: None,
             proven_sum: Some(8_000_000),
+            challenge: None,
+            extra_nullifiers: None,
         };
 
         let instances = starknet_public_inputs_to_instances(&public_inputs).expect("should work");
@@ -52,7 +52,9 @@ pub mod events;
 
 use blake3::Hasher;
 use serde::{Deserialize, Serialize};
-use zkpf_common::{ProofBundle, VerifierPublicInputs, CIRCUIT_VERSION};
+use zkpf_common::{
+    ProofBundle, VerifierPublicInputs, VerifierPublicInputsBuilder, CIRCUIT_VERSION,
+};
 
 pub use circuit::{
     create_starknet_proof, create_starknet_proof_with_artifacts,
@@ -227,20 +229,21 @@ pub fn build_verifier_public_inputs(
     nullifier: [u8; 32],
     custodian_pubkey_hash: [u8; 32],
 ) -> VerifierPublicInputs {
-    VerifierPublicInputs {
-        threshold_raw: threshold,
-        required_currency_code: meta.required_currency_code,
-        current_epoch: meta.current_epoch,
-        verifier_scope_id: meta.verifier_scope_id,
-        policy_id: meta.policy_id,
-        nullifier,
-        custodian_pubkey_hash,
-        // Starknet-specific fields mapped to the optional snapshot fields
-        snapshot_block_height: Some(starknet_meta.block_number),
-        snapshot_anchor_orchard: Some(starknet_meta.account_commitment), // Reused for account commitment
-        holder_binding: Some(starknet_meta.holder_binding),
-        proven_sum: Some(proven_sum),
-    }
+    VerifierPublicInputsBuilder::for_starknet(
+        starknet_meta.block_number,
+        starknet_meta.account_commitment,
+        starknet_meta.holder_binding,
+        proven_sum,
+    )
+    .threshold_raw(threshold)
+    .required_currency_code(meta.required_currency_code)
+    .current_epoch(meta.current_epoch)
+    .verifier_scope_id(meta.verifier_scope_id)
+    .policy_id(meta.policy_id)
+    .nullifier(nullifier)
+    .custodian_pubkey_hash(custodian_pubkey_hash)
+    .build()
+    .expect("every V3_STARKNET field is set")
 }
 
 /// Generate a Starknet proof-of-funds bundle.
@@ -337,6 +340,9 @@ pub fn prove_starknet_pof(
         rail_id: RAIL_ID_STARKNET_L2.to_string(),
         circuit_version: CIRCUIT_VERSION,
         proof,
+        proof_encoding: None,
+        issued_at: None,
+        issued_at_tag: None,
         public_inputs,
     })
 }
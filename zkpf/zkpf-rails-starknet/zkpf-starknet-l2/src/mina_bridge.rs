@@ -458,6 +458,9 @@ mod tests {
             rail_id: RAIL_ID_MINA.to_string(),
             circuit_version: CIRCUIT_VERSION,
             proof: vec![0u8; 64],
+            proof_encoding: None,
+            issued_at: None,
+            issued_at_tag: None,
             public_inputs: VerifierPublicInputs {
                 threshold_raw: 1_000_000_000_000_000_000,
                 required_currency_code: 1027,
@@ -469,7 +472,10 @@ mod tests {
                 snapshot_block_height: Some(500000), // mina_slot
                 snapshot_anchor_orchard: Some([2u8; 32]), // mina_digest
                 holder_binding: Some([3u8; 32]),
+                threshold_max: None,
                 proven_sum: Some(5_000_000_000_000_000_000),
+                challenge: None,
+                extra_nullifiers: None,
             },
         }
     }
@@ -415,7 +415,10 @@ fn test_proof_verification_rejects_placeholder() {
         snapshot_block_height: Some(500_000),
         snapshot_anchor_orchard: Some([0u8; 32]),
         holder_binding: Some([0u8; 32]),
+        threshold_max: None,
         proven_sum: Some(5_000_000_000_000_000_000),
+        challenge: None,
+        extra_nullifiers: None,
     };
 
     // Verify the fake placeholder proof - should be rejected
@@ -12,9 +12,12 @@ use serde::Deserialize;
 use thiserror::Error;
 use zkpf_common::ProofBundle;
 use zkpf_zcash_orchard_circuit::{
-    prove_orchard_pof, OrchardPublicMeta, OrchardRailError, PublicMetaInputs, RAIL_ID_ZCASH_ORCHARD,
+    prove_orchard_pof, HolderBindingScheme, OrchardPublicMeta, OrchardRailError, PublicMetaInputs,
+    RAIL_ID_ZCASH_ORCHARD,
+};
+use zkpf_zcash_orchard_wallet::{
+    build_snapshot_for_fvk, OrchardFvk, WalletError, NATIVE_ASSET_TYPE,
 };
-use zkpf_zcash_orchard_wallet::{build_snapshot_for_fvk, OrchardFvk, WalletError};
 
 /// Request body for the Orchard rail proof-of-funds API.
 #[derive(Debug, Deserialize)]
@@ -41,6 +44,9 @@ pub enum RailApiError {
 
     #[error("internal error: {0}")]
     Internal(String),
+
+    #[error("service unavailable: {0}")]
+    Unavailable(String),
 }
 
 impl From<OrchardRailError> for RailApiError {
@@ -49,6 +55,12 @@ impl From<OrchardRailError> for RailApiError {
             OrchardRailError::InvalidInput(msg) | OrchardRailError::Wallet(msg) => {
                 RailApiError::BadRequest(msg)
             }
+            err @ (OrchardRailError::AssetMismatch { .. } | OrchardRailError::SumOverflow) => {
+                RailApiError::BadRequest(err.to_string())
+            }
+            err @ OrchardRailError::ArtifactsUnavailable(_) => {
+                RailApiError::Unavailable(err.to_string())
+            }
             OrchardRailError::NotImplemented => {
                 RailApiError::Internal("Orchard rail circuit not implemented".into())
             }
@@ -84,6 +96,7 @@ impl axum::response::IntoResponse for RailApiError {
         let status = match self {
             RailApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
             RailApiError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            RailApiError::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
         };
         let body = ErrorBody {
             error: self.to_string(),
@@ -122,7 +135,10 @@ async fn proof_of_funds_handler(
         pool_id: "ORCHARD".to_string(),
         block_height: snapshot.height,
         anchor_orchard: snapshot.anchor,
-        holder_binding: [0u8; 32], // TODO: H(holder_id || fvk_bytes) in real impl
+        holder_binding: [0u8; 32], // Derived by prove_orchard_pof
+        asset_type: NATIVE_ASSET_TYPE,
+        holder_binding_scheme: HolderBindingScheme::Poseidon,
+        allow_zero_anchor: false,
     };
 
     let public_meta = PublicMetaInputs {
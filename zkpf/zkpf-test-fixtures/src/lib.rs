@@ -13,7 +13,7 @@ use once_cell::sync::OnceCell;
 use poseidon_primitives::poseidon::primitives::{Hash as PoseidonHash, Spec, VariableLengthIden3};
 use sha2::Sha256;
 use zkpf_circuit::{
-    gadgets::attestation::{AttestationWitness, EcdsaSignature, Secp256k1Pubkey},
+    gadgets::attestation::{AttestationWitness, EcdsaSignature, Secp256k1Pubkey, SignatureFormat},
     PublicInputs, ZkpfCircuitInput,
 };
 use zkpf_common::{
@@ -124,6 +124,7 @@ fn build_fixtures() -> Result<TestFixtures> {
         params: ArtifactFile::from_bytes("params.bin", &params_bytes),
         vk: ArtifactFile::from_bytes("vk.bin", &vk_bytes),
         pk: ArtifactFile::from_bytes("pk.bin", &pk_bytes),
+        num_instance_columns: None,
     };
 
     let artifacts = ProverArtifacts::from_parts(manifest, PathBuf::from("."), params, vk, Some(pk));
@@ -231,6 +232,8 @@ fn prepare_input() -> Result<PreparedInput> {
         policy_id,
         nullifier,
         custodian_pubkey_hash: custodian_pubkey_hash_fr,
+        proven_sum: None,
+        challenge: None,
     };
 
     let attestation = AttestationWitness {
@@ -244,6 +247,7 @@ fn prepare_input() -> Result<PreparedInput> {
         custodian_pubkey: derived_pubkey,
         signature: EcdsaSignature { r: sig_r, s: sig_s },
         message_hash,
+        signature_format: SignatureFormat::RawHash,
     };
 
     let circuit_input = ZkpfCircuitInput {
@@ -66,6 +66,9 @@ pub fn sample_mina_bundle() -> ProofBundle {
         rail_id: RAIL_ID_MINA.to_string(),
         circuit_version: CIRCUIT_VERSION,
         proof: create_placeholder_proof(&nullifier),
+        proof_encoding: None,
+        issued_at: None,
+        issued_at_tag: None,
         public_inputs: VerifierPublicInputs {
             threshold_raw: 1_000_000_000_000_000_000, // 1 ETH
             required_currency_code: 1027,             // ETH
@@ -77,7 +80,10 @@ pub fn sample_mina_bundle() -> ProofBundle {
             snapshot_block_height: Some(500_000),
             snapshot_anchor_orchard: Some([1u8; 32]), // Recursive proof commitment
             holder_binding: Some(holder_binding),
+            threshold_max: None,
             proven_sum: Some(5_000_000_000_000_000_000), // 5 ETH
+            challenge: None,
+            extra_nullifiers: None,
         },
     }
 }
@@ -88,6 +94,9 @@ pub fn sample_source_proof_starknet() -> ProofBundle {
         rail_id: "STARKNET_L2".to_string(),
         circuit_version: CIRCUIT_VERSION,
         proof: vec![0u8; 64],
+        proof_encoding: None,
+        issued_at: None,
+        issued_at_tag: None,
         public_inputs: VerifierPublicInputs {
             threshold_raw: 1_000_000_000_000_000_000,
             required_currency_code: 1027,
@@ -99,7 +108,10 @@ pub fn sample_source_proof_starknet() -> ProofBundle {
             snapshot_block_height: Some(123456),
             snapshot_anchor_orchard: Some([4u8; 32]),
             holder_binding: Some([5u8; 32]),
+            threshold_max: None,
             proven_sum: Some(3_000_000_000_000_000_000),
+            challenge: None,
+            extra_nullifiers: None,
         },
     }
 }
@@ -110,6 +122,9 @@ pub fn sample_source_proof_orchard() -> ProofBundle {
         rail_id: "ORCHARD".to_string(),
         circuit_version: CIRCUIT_VERSION,
         proof: vec![1u8; 64],
+        proof_encoding: None,
+        issued_at: None,
+        issued_at_tag: None,
         public_inputs: VerifierPublicInputs {
             threshold_raw: 500_000_000_000_000_000,
             required_currency_code: 1027,
@@ -121,7 +136,10 @@ pub fn sample_source_proof_orchard() -> ProofBundle {
             snapshot_block_height: Some(2_500_000),
             snapshot_anchor_orchard: Some([7u8; 32]),
             holder_binding: Some([8u8; 32]),
+            threshold_max: None,
             proven_sum: Some(2_000_000_000_000_000_000),
+            challenge: None,
+            extra_nullifiers: None,
         },
     }
 }
@@ -1,5 +1,6 @@
 use std::{
     fmt, fs,
+    io::{self, BufReader},
     path::PathBuf,
     time::{SystemTime, UNIX_EPOCH},
 };
@@ -8,17 +9,22 @@ use anyhow::{Context, Result};
 use clap::{Args, Parser, Subcommand, ValueEnum};
 use serde::Serialize;
 use zkpf_common::{
-    load_prover_artifacts, serialize_params, serialize_proving_key, serialize_verifying_key,
-    write_manifest, ArtifactFile, ArtifactManifest, ProverArtifacts, CIRCUIT_VERSION, MANIFEST_FILE,
-    MANIFEST_VERSION,
+    load_prover_artifacts, load_verifier_artifacts, serialize_params, serialize_proving_key,
+    serialize_verifying_key, verify_artifact_integrity, write_manifest, ArtifactFile,
+    ArtifactManifest, ProverArtifacts, PublicInputLayout, CIRCUIT_VERSION, MANIFEST_FILE,
+    MANIFEST_VERSION, PUBLIC_INPUT_COUNT_CHALLENGE, PUBLIC_INPUT_COUNT_REVEAL_SUM,
 };
-use zkpf_prover::setup;
+use zkpf_prover::{setup, setup_challenge, setup_reveal_sum};
 use zkpf_starknet_l2::{
     serialize_starknet_proving_key, serialize_starknet_verifying_key, starknet_keygen,
     STARKNET_DEFAULT_K,
 };
-use zkpf_zcash_orchard_circuit::{orchard_keygen, serialize_break_points, ORCHARD_DEFAULT_K};
+use zkpf_verifier::{verify_ndjson, RailConfig};
+use zkpf_zcash_orchard_circuit::{
+    orchard_keygen, recommended_k_for_orchard, serialize_break_points,
+};
 
+const DEFAULT_K: u32 = 19;
 const DEFAULT_OUTPUT_DIR: &str = "artifacts/local";
 const DEFAULT_MANIFEST_PATH: &str = "artifacts/manifest.json";
 const PARAMS_FILENAME: &str = "params.bin";
@@ -46,6 +52,10 @@ enum Commands {
     DumpParams(DumpArgs),
     /// Print metadata about vk.bin based on the manifest path.
     DumpVk(DumpArgs),
+    /// Verify proof bundles streamed from an NDJSON file (one bundle per line).
+    VerifyNdjson(VerifyNdjsonArgs),
+    /// Re-hash params/vk/pk on disk and check them against the manifest.
+    CheckArtifacts(CheckArtifactsArgs),
 }
 
 /// Rail type for keygen.
@@ -53,9 +63,13 @@ enum Commands {
 enum RailType {
     /// Default zkpf circuit (custodial attestations).
     Default,
+    /// Custodial circuit that also reveals proven_sum (8 public inputs).
+    RevealSum,
+    /// Custodial circuit bound to a verifier-issued challenge (8 public inputs).
+    Challenge,
     /// Starknet L2 rail circuit.
     Starknet,
-    /// Zcash Orchard rail circuit (k=19, 10 public inputs).
+    /// Zcash Orchard rail circuit (10 public inputs).
     Orchard,
 }
 
@@ -67,9 +81,10 @@ impl Default for RailType {
 
 #[derive(Args)]
 struct GenParamsArgs {
-    /// Circuit k parameter (log2 of circuit size).
-    #[arg(long, default_value_t = 19)]
-    k: u32,
+    /// Circuit k parameter (log2 of circuit size). Defaults to 19, the
+    /// Starknet default, or the recommended k for the Orchard rail.
+    #[arg(long)]
+    k: Option<u32>,
     /// Output directory for artifacts.
     #[arg(long, default_value = DEFAULT_OUTPUT_DIR)]
     output_dir: PathBuf,
@@ -89,6 +104,49 @@ struct DumpArgs {
     json: bool,
 }
 
+/// Public-input layout of the bundles being verified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum LayoutArg {
+    V1,
+    V4Aggregate,
+    V5Range,
+    V1WithSum,
+    V6SnapshotCommitment,
+}
+
+impl From<LayoutArg> for PublicInputLayout {
+    fn from(layout: LayoutArg) -> Self {
+        match layout {
+            LayoutArg::V1 => PublicInputLayout::V1,
+            LayoutArg::V4Aggregate => PublicInputLayout::V4Aggregate,
+            LayoutArg::V5Range => PublicInputLayout::V5Range,
+            LayoutArg::V1WithSum => PublicInputLayout::V1WithSum,
+            LayoutArg::V6SnapshotCommitment => PublicInputLayout::V6SnapshotCommitment,
+        }
+    }
+}
+
+#[derive(Args)]
+struct VerifyNdjsonArgs {
+    /// NDJSON file of proof bundles; `-` reads from stdin.
+    input: PathBuf,
+    #[arg(long, default_value = DEFAULT_MANIFEST_PATH)]
+    manifest: PathBuf,
+    #[arg(long, value_enum, default_value_t = LayoutArg::V1)]
+    layout: LayoutArg,
+    /// Reject bundles whose rail_id differs from this value.
+    #[arg(long)]
+    rail_id: Option<String>,
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Args)]
+struct CheckArtifactsArgs {
+    #[arg(long, default_value = DEFAULT_MANIFEST_PATH)]
+    manifest: PathBuf,
+}
+
 #[derive(Args)]
 struct GenBreakPointsArgs {
     /// Output directory containing existing artifacts.
@@ -103,12 +161,14 @@ fn main() -> Result<()> {
         Commands::GenBreakPoints(args) => gen_break_points_only(args),
         Commands::DumpParams(args) => dump_params(args),
         Commands::DumpVk(args) => dump_vk(args),
+        Commands::VerifyNdjson(args) => verify_ndjson_file(args),
+        Commands::CheckArtifacts(args) => check_artifacts(args),
     }
 }
 
 fn gen_params(args: GenParamsArgs) -> Result<()> {
     match args.rail {
-        RailType::Default => gen_default_params(args),
+        RailType::Default | RailType::RevealSum | RailType::Challenge => gen_default_params(args),
         RailType::Starknet => gen_starknet_params(args),
         RailType::Orchard => gen_orchard_params(args),
     }
@@ -118,13 +178,20 @@ fn gen_default_params(args: GenParamsArgs) -> Result<()> {
     fs::create_dir_all(&args.output_dir)
         .with_context(|| format!("failed to create {}", args.output_dir.display()))?;
 
-    println!(
-        "Generating default zkpf circuit artifacts (k={})...",
-        args.k
-    );
+    let (label, num_instance_columns) = match args.rail {
+        RailType::RevealSum => ("reveal-sum", Some(PUBLIC_INPUT_COUNT_REVEAL_SUM)),
+        RailType::Challenge => ("challenge", Some(PUBLIC_INPUT_COUNT_CHALLENGE)),
+        _ => ("default", None),
+    };
+    let k = args.k.unwrap_or(DEFAULT_K);
+    println!("Generating {} zkpf circuit artifacts (k={})...", label, k);
     println!("This may take several minutes...");
 
-    let params = setup(args.k);
+    let params = match args.rail {
+        RailType::RevealSum => setup_reveal_sum(k)?,
+        RailType::Challenge => setup_challenge(k)?,
+        _ => setup(k),
+    };
     let params_bytes = serialize_params(&params.params)?;
     let vk_bytes = serialize_verifying_key(&params.vk)?;
     let pk_bytes = serialize_proving_key(&params.pk)?;
@@ -136,11 +203,12 @@ fn gen_default_params(args: GenParamsArgs) -> Result<()> {
     let manifest = ArtifactManifest {
         manifest_version: MANIFEST_VERSION,
         circuit_version: args.circuit_version,
-        k: args.k,
+        k,
         created_at_unix: current_unix_timestamp(),
         params: ArtifactFile::from_bytes(PARAMS_FILENAME, &params_bytes),
         vk: ArtifactFile::from_bytes(VK_FILENAME, &vk_bytes),
         pk: ArtifactFile::from_bytes(PK_FILENAME, &pk_bytes),
+        num_instance_columns,
     };
 
     let manifest_path = args.output_dir.join(MANIFEST_FILE);
@@ -160,12 +228,7 @@ fn gen_starknet_params(args: GenParamsArgs) -> Result<()> {
     fs::create_dir_all(&args.output_dir)
         .with_context(|| format!("failed to create {}", args.output_dir.display()))?;
 
-    // Use Starknet default k if not specified
-    let k = if args.k == 19 {
-        STARKNET_DEFAULT_K as u32
-    } else {
-        args.k
-    };
+    let k = args.k.unwrap_or(STARKNET_DEFAULT_K as u32);
 
     println!("Generating Starknet L2 rail circuit artifacts (k={})...", k);
     println!("This may take several minutes...");
@@ -187,6 +250,7 @@ fn gen_starknet_params(args: GenParamsArgs) -> Result<()> {
         params: ArtifactFile::from_bytes(PARAMS_FILENAME, &params_bytes),
         vk: ArtifactFile::from_bytes(VK_FILENAME, &vk_bytes),
         pk: ArtifactFile::from_bytes(PK_FILENAME, &pk_bytes),
+        num_instance_columns: None,
     };
 
     let manifest_path = args.output_dir.join(MANIFEST_FILE);
@@ -206,11 +270,19 @@ fn gen_orchard_params(args: GenParamsArgs) -> Result<()> {
     fs::create_dir_all(&args.output_dir)
         .with_context(|| format!("failed to create {}", args.output_dir.display()))?;
 
-    // Use Orchard default k (19) if not specified, otherwise use provided k
-    let k = if args.k == 19 {
-        ORCHARD_DEFAULT_K as u32
-    } else {
-        args.k
+    // Size from the circuit's own cell counts unless --k overrides it.
+    let recommended_k = recommended_k_for_orchard();
+    let k = match args.k {
+        Some(k) => {
+            if k < recommended_k {
+                eprintln!(
+                    "⚠️  --k {} is below the recommended k={} for the Orchard circuit; keygen is likely to fail.",
+                    k, recommended_k
+                );
+            }
+            k
+        }
+        None => recommended_k,
     };
 
     println!("Generating Zcash Orchard rail circuit artifacts (k={})...", k);
@@ -239,6 +311,7 @@ fn gen_orchard_params(args: GenParamsArgs) -> Result<()> {
         params: ArtifactFile::from_bytes(PARAMS_FILENAME, &params_bytes),
         vk: ArtifactFile::from_bytes(VK_FILENAME, &vk_bytes),
         pk: ArtifactFile::from_bytes(PK_FILENAME, &pk_bytes),
+        num_instance_columns: None,
     };
 
     let manifest_path = args.output_dir.join(MANIFEST_FILE);
@@ -341,6 +414,43 @@ fn dump_vk(args: DumpArgs) -> Result<()> {
     output_summary(&summary, args.json)
 }
 
+fn verify_ndjson_file(args: VerifyNdjsonArgs) -> Result<()> {
+    let artifacts = load_verifier_artifacts(&args.manifest)
+        .with_context(|| format!("failed to load manifest {}", args.manifest.display()))?;
+    let rail = RailConfig {
+        params: &artifacts.params,
+        vk: &artifacts.vk,
+        layout: args.layout.into(),
+        circuit_version: artifacts.manifest.circuit_version,
+        rail_id: args.rail_id.as_deref(),
+    };
+
+    let report = if args.input.as_os_str() == "-" {
+        verify_ndjson(io::stdin().lock(), &rail)?
+    } else {
+        let file = fs::File::open(&args.input)
+            .with_context(|| format!("failed to open {}", args.input.display()))?;
+        verify_ndjson(BufReader::new(file), &rail)?
+    };
+
+    output_summary(&report, args.json)?;
+    if !report.is_clean() {
+        anyhow::bail!(
+            "{} of {} bundles failed verification",
+            report.total - report.valid,
+            report.total
+        );
+    }
+    Ok(())
+}
+
+fn check_artifacts(args: CheckArtifactsArgs) -> Result<()> {
+    verify_artifact_integrity(&args.manifest)
+        .with_context(|| format!("artifacts do not match {}", args.manifest.display()))?;
+    println!("artifacts match {}", args.manifest.display());
+    Ok(())
+}
+
 fn write_binary(path: PathBuf, bytes: &[u8]) -> Result<()> {
     fs::write(&path, bytes).with_context(|| format!("failed to write {}", path.display()))
 }
@@ -1,47 +1,231 @@
 // zkpf/zkpf-verifier/src/lib.rs
 // Numan Thabit 2025
 
-use anyhow::Result;
-use halo2_proofs_axiom::{
-    plonk::verify_proof,
-    poly::kzg::{
-        commitment::{KZGCommitmentScheme, ParamsKZG},
-        multiopen::VerifierGWC,
-        strategy::SingleStrategy,
-    },
-    transcript::{Blake2bRead, Challenge255, TranscriptReadBuffer},
-};
-use halo2curves_axiom::bn256::{Bn256, G1Affine};
-use zkpf_common::{public_inputs_to_instances, VerifierPublicInputs};
-
-pub fn verify(
-    params: &ParamsKZG<Bn256>,
-    vk: &halo2_proofs_axiom::plonk::VerifyingKey<G1Affine>,
-    proof_bytes: &[u8],
-    instances: &[Vec<halo2curves_axiom::bn256::Fr>],
-) -> bool {
-    let mut transcript = Blake2bRead::<_, G1Affine, Challenge255<_>>::init(proof_bytes);
+use std::{collections::HashSet, fmt, io::BufRead};
 
-    let instance_columns: Vec<&[halo2curves_axiom::bn256::Fr]> =
-        instances.iter().map(|col| col.as_slice()).collect();
-    let prepared_instances = vec![instance_columns.as_slice()];
+use anyhow::{Context, Result};
+use halo2_proofs_axiom::{plonk::VerifyingKey, poly::kzg::commitment::ParamsKZG};
+use halo2curves_axiom::bn256::{Bn256, G1Affine};
+use serde::Serialize;
+use zkpf_common::{
+    public_inputs_to_instances, public_inputs_to_instances_with_layout, ProofBundle,
+    PublicInputLayout, VerifierPublicInputs,
+};
 
-    verify_proof::<KZGCommitmentScheme<Bn256>, VerifierGWC<'_, Bn256>, _, _, _>(
-        params,
-        vk,
-        SingleStrategy::new(params),
-        &prepared_instances,
-        &mut transcript,
-    )
-    .is_ok()
-}
+pub use zkpf_verify_core::verify;
 
 pub fn verify_with_public_inputs(
     params: &ParamsKZG<Bn256>,
-    vk: &halo2_proofs_axiom::plonk::VerifyingKey<G1Affine>,
+    vk: &VerifyingKey<G1Affine>,
     proof_bytes: &[u8],
     public_inputs: &VerifierPublicInputs,
 ) -> Result<bool> {
     let instances = public_inputs_to_instances(public_inputs)?;
     Ok(verify(params, vk, proof_bytes, &instances))
 }
+
+/// Verifies a V1 bundle, decompressing the proof first if `proof_encoding` is set.
+pub fn verify_bundle(
+    params: &ParamsKZG<Bn256>,
+    vk: &VerifyingKey<G1Affine>,
+    bundle: &ProofBundle,
+) -> Result<bool> {
+    let proof = bundle.decoded_proof()?;
+    verify_with_public_inputs(params, vk, &proof, &bundle.public_inputs)
+}
+
+/// Verifies several V1 bundles against the same params and verifying key.
+///
+/// The params and key are shared across items rather than re-prepared per
+/// bundle. Returns one result per bundle, in order; a malformed bundle is an
+/// `Err` for that item only.
+pub fn verify_bundles(
+    params: &ParamsKZG<Bn256>,
+    vk: &VerifyingKey<G1Affine>,
+    bundles: &[ProofBundle],
+) -> Vec<Result<bool>> {
+    bundles
+        .iter()
+        .map(|bundle| verify_bundle(params, vk, bundle))
+        .collect()
+}
+
+/// Verifier material and expectations for one rail, shared across every line
+/// of an NDJSON stream.
+pub struct RailConfig<'a> {
+    pub params: &'a ParamsKZG<Bn256>,
+    pub vk: &'a VerifyingKey<G1Affine>,
+    pub layout: PublicInputLayout,
+    pub circuit_version: u32,
+    /// When set, bundles tagged with a different `rail_id` are rejected.
+    pub rail_id: Option<&'a str>,
+}
+
+/// How a single NDJSON line was classified.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LineOutcome {
+    Valid,
+    /// The proof did not verify against the bundle's public inputs.
+    Invalid,
+    /// The line is not a bundle, or its proof/public inputs cannot be decoded.
+    Malformed,
+    /// The bundle targets a different rail or circuit version.
+    RailMismatch,
+    /// The proof verified, but its nullifier was already seen earlier in the stream.
+    DuplicateNullifier,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct LineFailure {
+    /// 1-based line number in the input.
+    pub line: usize,
+    pub outcome: LineOutcome,
+    pub detail: String,
+}
+
+/// Summary of an NDJSON run: counts by outcome plus the failing lines.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct VerifyReport {
+    pub total: usize,
+    pub valid: usize,
+    pub invalid: usize,
+    pub malformed: usize,
+    pub rail_mismatch: usize,
+    pub duplicate_nullifier: usize,
+    pub failures: Vec<LineFailure>,
+}
+
+impl VerifyReport {
+    /// True when every bundle in the stream verified with a fresh nullifier.
+    pub fn is_clean(&self) -> bool {
+        self.valid == self.total
+    }
+
+    fn record(&mut self, line: usize, outcome: LineOutcome, detail: impl Into<String>) {
+        self.total += 1;
+        let count = match outcome {
+            LineOutcome::Valid => &mut self.valid,
+            LineOutcome::Invalid => &mut self.invalid,
+            LineOutcome::Malformed => &mut self.malformed,
+            LineOutcome::RailMismatch => &mut self.rail_mismatch,
+            LineOutcome::DuplicateNullifier => &mut self.duplicate_nullifier,
+        };
+        *count += 1;
+        if outcome != LineOutcome::Valid {
+            self.failures.push(LineFailure {
+                line,
+                outcome,
+                detail: detail.into(),
+            });
+        }
+    }
+}
+
+impl fmt::Display for VerifyReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "total: {}", self.total)?;
+        writeln!(f, "valid: {}", self.valid)?;
+        writeln!(f, "invalid: {}", self.invalid)?;
+        writeln!(f, "malformed: {}", self.malformed)?;
+        writeln!(f, "rail_mismatch: {}", self.rail_mismatch)?;
+        write!(f, "duplicate_nullifier: {}", self.duplicate_nullifier)?;
+        for failure in &self.failures {
+            write!(
+                f,
+                "\nline {}: {:?}: {}",
+                failure.line, failure.outcome, failure.detail
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Streams proof bundles from `reader`, one JSON bundle per line, and verifies
+/// each against `rail`.
+///
+/// Blank lines are skipped. A line that fails to parse or verify is recorded
+/// in the report and does not stop the run; only an I/O error on the reader
+/// is returned as `Err`. Nullifiers are tracked per `(verifier_scope_id,
+/// policy_id)` for proofs that verify, so a later valid proof reusing one is
+/// reported as a duplicate.
+pub fn verify_ndjson<R: BufRead>(reader: R, rail: &RailConfig<'_>) -> Result<VerifyReport> {
+    let mut report = VerifyReport::default();
+    let mut seen = HashSet::new();
+
+    for (index, line) in reader.lines().enumerate() {
+        let line_no = index + 1;
+        let line = line.with_context(|| format!("failed to read line {line_no}"))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (outcome, detail) = verify_ndjson_line(&line, rail, &mut seen);
+        report.record(line_no, outcome, detail);
+    }
+
+    Ok(report)
+}
+
+fn verify_ndjson_line(
+    line: &str,
+    rail: &RailConfig<'_>,
+    seen: &mut HashSet<(u64, u64, [u8; 32])>,
+) -> (LineOutcome, String) {
+    let bundle: ProofBundle = match serde_json::from_str(line) {
+        Ok(bundle) => bundle,
+        Err(err) => {
+            return (
+                LineOutcome::Malformed,
+                format!("invalid bundle json: {err}"),
+            )
+        }
+    };
+    if bundle.circuit_version != rail.circuit_version {
+        return (
+            LineOutcome::RailMismatch,
+            format!(
+                "circuit_version {} does not match expected {}",
+                bundle.circuit_version, rail.circuit_version
+            ),
+        );
+    }
+    if let Some(rail_id) = rail.rail_id {
+        if bundle.rail_id != rail_id {
+            return (
+                LineOutcome::RailMismatch,
+                format!(
+                    "rail_id {:?} does not match expected {rail_id:?}",
+                    bundle.rail_id
+                ),
+            );
+        }
+    }
+
+    let proof = match bundle.decoded_proof() {
+        Ok(proof) => proof,
+        Err(err) => return (LineOutcome::Malformed, format!("{err:#}")),
+    };
+    let instances = match public_inputs_to_instances_with_layout(rail.layout, &bundle.public_inputs)
+    {
+        Ok(instances) => instances,
+        Err(err) => return (LineOutcome::Malformed, format!("{err:#}")),
+    };
+    if !verify(rail.params, rail.vk, &proof, &instances) {
+        return (
+            LineOutcome::Invalid,
+            "proof verification failed".to_string(),
+        );
+    }
+
+    let inputs = &bundle.public_inputs;
+    if !seen.insert((inputs.verifier_scope_id, inputs.policy_id, inputs.nullifier)) {
+        return (
+            LineOutcome::DuplicateNullifier,
+            format!(
+                "nullifier already seen for scope {} policy {}",
+                inputs.verifier_scope_id, inputs.policy_id
+            ),
+        );
+    }
+    (LineOutcome::Valid, String::new())
+}
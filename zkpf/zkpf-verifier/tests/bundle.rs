@@ -0,0 +1,105 @@
+use zkpf_common::{ProofEncoding, PublicInputLayout, CIRCUIT_VERSION};
+use zkpf_test_fixtures::fixtures;
+use zkpf_verifier::{verify_bundle, verify_bundles, verify_ndjson, LineOutcome, RailConfig};
+
+#[test]
+fn compressed_and_raw_bundles_verify_identically() {
+    let fixtures = fixtures();
+    let artifacts = fixtures.artifacts();
+    let raw = fixtures.bundle().clone();
+    let compressed = raw.clone().compressed().expect("compress proof");
+    assert_eq!(compressed.proof_encoding, Some(ProofEncoding::Zstd));
+
+    assert!(verify_bundle(&artifacts.params, &artifacts.vk, &raw).unwrap());
+    assert!(verify_bundle(&artifacts.params, &artifacts.vk, &compressed).unwrap());
+
+    let mut raw_tampered = raw;
+    raw_tampered.public_inputs.threshold_raw += 1;
+    let mut compressed_tampered = compressed;
+    compressed_tampered.public_inputs.threshold_raw += 1;
+    assert!(!verify_bundle(&artifacts.params, &artifacts.vk, &raw_tampered).unwrap());
+    assert!(!verify_bundle(&artifacts.params, &artifacts.vk, &compressed_tampered).unwrap());
+}
+
+#[test]
+fn mislabelled_encoding_is_an_error() {
+    let fixtures = fixtures();
+    let artifacts = fixtures.artifacts();
+    let mut bundle = fixtures.bundle().clone();
+    bundle.proof_encoding = Some(ProofEncoding::Zstd);
+    assert!(verify_bundle(&artifacts.params, &artifacts.vk, &bundle).is_err());
+}
+
+#[test]
+fn batch_reports_each_bundle() {
+    let fixtures = fixtures();
+    let artifacts = fixtures.artifacts();
+    let valid = fixtures.bundle().clone();
+    let mut tampered = valid.clone();
+    tampered.public_inputs.threshold_raw += 1;
+    let mut mislabelled = valid.clone();
+    mislabelled.proof_encoding = Some(ProofEncoding::Zstd);
+
+    let results = verify_bundles(
+        &artifacts.params,
+        &artifacts.vk,
+        &[valid, tampered, mislabelled],
+    );
+    assert_eq!(results.len(), 3);
+    assert!(results[0].as_ref().unwrap());
+    assert!(!results[1].as_ref().unwrap());
+    assert!(results[2].is_err());
+}
+
+#[test]
+fn ndjson_stream_counts_each_outcome() {
+    let fixtures = fixtures();
+    let artifacts = fixtures.artifacts();
+    let valid = fixtures.bundle().clone();
+    let mut tampered = valid.clone();
+    tampered.public_inputs.threshold_raw += 1;
+    let mut wrong_version = valid.clone();
+    wrong_version.circuit_version += 1;
+
+    let lines = [
+        serde_json::to_string(&valid).unwrap(),
+        String::new(),
+        serde_json::to_string(&tampered).unwrap(),
+        "{not json".to_string(),
+        serde_json::to_string(&wrong_version).unwrap(),
+        serde_json::to_string(&valid).unwrap(),
+    ];
+    let input = lines.join("\n");
+
+    let rail = RailConfig {
+        params: &artifacts.params,
+        vk: &artifacts.vk,
+        layout: PublicInputLayout::V1,
+        circuit_version: CIRCUIT_VERSION,
+        rail_id: None,
+    };
+    let report = verify_ndjson(input.as_bytes(), &rail).unwrap();
+
+    assert_eq!(report.total, 5);
+    assert_eq!(report.valid, 1);
+    assert_eq!(report.invalid, 1);
+    assert_eq!(report.malformed, 1);
+    assert_eq!(report.rail_mismatch, 1);
+    assert_eq!(report.duplicate_nullifier, 1);
+    assert!(!report.is_clean());
+
+    let failed: Vec<_> = report
+        .failures
+        .iter()
+        .map(|failure| (failure.line, failure.outcome))
+        .collect();
+    assert_eq!(
+        failed,
+        vec![
+            (3, LineOutcome::Invalid),
+            (4, LineOutcome::Malformed),
+            (5, LineOutcome::RailMismatch),
+            (6, LineOutcome::DuplicateNullifier),
+        ]
+    );
+}
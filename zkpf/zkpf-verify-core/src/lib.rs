@@ -0,0 +1,805 @@
+// zkpf/zkpf-verify-core/src/lib.rs
+// Numan Thabit 2025
+
+//! Minimal proof-verification surface for zkpf.
+//!
+//! Everything a verifier needs to check a proof and nothing more: the
+//! public-input types and layouts, params/verifying-key decoding, and the
+//! KZG verifier itself. No async runtime, server, storage, or prover code is
+//! linked. `zkpf-common` and `zkpf-verifier` re-export these items, so the
+//! serialized forms are the same whichever crate a caller goes through.
+//!
+//! The circuit crate is still a dependency: decoding a verifying key needs
+//! the circuit's configuration, and instance columns are laid out by the
+//! circuit's own helpers.
+
+use std::io::Cursor;
+
+use anyhow::{anyhow, ensure, Context, Result};
+use halo2_proofs_axiom::{
+    plonk::{self, verify_proof, Circuit},
+    poly::{
+        commitment::Params,
+        kzg::{
+            commitment::{KZGCommitmentScheme, ParamsKZG},
+            multiopen::VerifierGWC,
+            strategy::SingleStrategy,
+        },
+    },
+    transcript::{Blake2bRead, Challenge255, TranscriptReadBuffer},
+    SerdeFormat,
+};
+use halo2curves_axiom::bn256::{Bn256, Fr, G1Affine};
+use serde::{Deserialize, Serialize};
+use zkpf_circuit::{
+    aggregate::aggregate_public_instances, gadgets::commitment::snapshot_commitment_native,
+    public_instances, ranged::range_public_instances, PublicInputs, ZkpfCircuit,
+};
+
+/// Number of public inputs in the legacy custodial circuit layout (V1).
+pub const PUBLIC_INPUT_COUNT: usize = 7;
+/// Number of public inputs in the custodial layout when `proven_sum` is revealed:
+/// the V1 columns followed by the attested balance.
+pub const PUBLIC_INPUT_COUNT_REVEAL_SUM: usize = PUBLIC_INPUT_COUNT + 1;
+/// Number of public inputs in the custodial layout when the proof is bound to a
+/// verifier-issued challenge: the V1 columns followed by the challenge.
+pub const PUBLIC_INPUT_COUNT_CHALLENGE: usize = PUBLIC_INPUT_COUNT + 1;
+/// Number of public inputs in the Orchard layout (V2_ORCHARD): V1 prefix + 3 Orchard fields.
+pub const PUBLIC_INPUT_COUNT_V2_ORCHARD: usize = 10;
+/// Number of public inputs in the Starknet layout (V3_STARKNET): V1 prefix + 4 Starknet fields.
+/// Fields: chain_id_numeric, block_number, account_commitment, holder_binding
+pub const PUBLIC_INPUT_COUNT_V3_STARKNET: usize = 11;
+/// Number of public inputs in the aggregate custodial layout (V4_AGGREGATE).
+/// Same columns as V1; the nullifier column has one row per account and the
+/// custodian hash is a set-level digest.
+pub const PUBLIC_INPUT_COUNT_V4_AGGREGATE: usize = 7;
+/// Number of public inputs in the balance-range layout (V5_RANGE): V1 columns
+/// (with `threshold_raw` as the lower bound) followed by `threshold_max`.
+pub const PUBLIC_INPUT_COUNT_V5_RANGE: usize = 8;
+/// Number of public inputs in the snapshot-commitment layout (V6_SNAPSHOT_COMMITMENT):
+/// V1 prefix + one Poseidon commitment to the snapshot metadata.
+pub const PUBLIC_INPUT_COUNT_V6_SNAPSHOT_COMMITMENT: usize = PUBLIC_INPUT_COUNT + 1;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VerifierPublicInputs {
+    pub threshold_raw: u64,
+    pub required_currency_code: u32,
+    pub current_epoch: u64,
+    pub verifier_scope_id: u64,
+    pub policy_id: u64,
+    pub nullifier: [u8; 32],
+    pub custodian_pubkey_hash: [u8; 32],
+    /// Optional snapshot metadata for non-custodial rails (e.g. Zcash Orchard).
+    ///
+    /// For the legacy custodial rail this will be `None`, and the corresponding
+    /// public-input layout (V1) does not include these fields.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshot_block_height: Option<u64>,
+    /// Orchard anchor (Merkle root) at `snapshot_block_height`, if applicable.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshot_anchor_orchard: Option<[u8; 32]>,
+    /// Optional binding between holder identity and rail-specific key material.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub holder_binding: Option<[u8; 32]>,
+    /// Upper balance bound for range proofs (V5_RANGE); `threshold_raw` is the lower bound.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub threshold_max: Option<u64>,
+    /// Optional proven sum for transparency (Starknet rail).
+    /// The actual aggregated balance value that was proven to meet the threshold.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proven_sum: Option<u128>,
+    /// Verifier-issued nonce the proof is bound to (custodial layout only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub challenge: Option<[u8; 32]>,
+    /// Nullifiers of the second and later accounts of a multi-account proof
+    /// (V4_AGGREGATE layout only); `nullifier` is the first account's.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extra_nullifiers: Option<Vec<[u8; 32]>>,
+}
+
+/// Logical public-input layouts supported by the verifier.
+///
+/// - `V1` – legacy custodial attestation rail (8 public inputs).
+/// - `V2Orchard` – Orchard rail layout: V1 prefix plus Orchard snapshot fields.
+/// - `V3Starknet` – Starknet L2 rail layout: V1 prefix plus Starknet-specific fields.
+/// - `V4Aggregate` – multi-account custodial rail: V1 columns, where the nullifier
+///   column holds each account's nullifier (`nullifier`, then `extra_nullifiers`)
+///   and the custodian pubkey hash commits to the custodian set.
+/// - `V5Range` – custodial balance-range rail: V1 columns plus `threshold_max`.
+/// - `V1WithSum` – custodial rail with the attested balance disclosed: V1 columns
+///   plus `proven_sum`, which the circuit constrains equal to `balance_raw`.
+/// - `V6SnapshotCommitment` – snapshot rail layout: V1 columns plus a single
+///   [`snapshot_commitment`] in place of the V2/V3 snapshot columns.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PublicInputLayout {
+    #[serde(rename = "V1")]
+    V1,
+    #[serde(rename = "V2_ORCHARD")]
+    V2Orchard,
+    #[serde(rename = "V3_STARKNET")]
+    V3Starknet,
+    #[serde(rename = "V4_AGGREGATE")]
+    V4Aggregate,
+    #[serde(rename = "V5_RANGE")]
+    V5Range,
+    #[serde(rename = "V1_WITH_SUM")]
+    V1WithSum,
+    #[serde(rename = "V6_SNAPSHOT_COMMITMENT")]
+    V6SnapshotCommitment,
+}
+
+impl PublicInputLayout {
+    /// Instance columns a proof in this layout has. Custodial proofs bound to a
+    /// challenge carry one more column than their layout's count.
+    pub fn instance_columns(self) -> usize {
+        match self {
+            Self::V1 => PUBLIC_INPUT_COUNT,
+            Self::V2Orchard => PUBLIC_INPUT_COUNT_V2_ORCHARD,
+            Self::V3Starknet => PUBLIC_INPUT_COUNT_V3_STARKNET,
+            Self::V4Aggregate => PUBLIC_INPUT_COUNT_V4_AGGREGATE,
+            Self::V5Range => PUBLIC_INPUT_COUNT_V5_RANGE,
+            Self::V1WithSum => PUBLIC_INPUT_COUNT_REVEAL_SUM,
+            Self::V6SnapshotCommitment => PUBLIC_INPUT_COUNT_V6_SNAPSHOT_COMMITMENT,
+        }
+    }
+}
+
+/// Builds [`VerifierPublicInputs`] for one layout.
+///
+/// Each constructor takes the fields its layout adds, so the optional fields
+/// are always set in the combination the layout expects; the shared V1 prefix
+/// is filled in with the setters and checked by [`Self::build`].
+#[derive(Clone, Debug)]
+pub struct VerifierPublicInputsBuilder {
+    layout: PublicInputLayout,
+    threshold_raw: Option<u64>,
+    required_currency_code: Option<u32>,
+    current_epoch: Option<u64>,
+    verifier_scope_id: Option<u64>,
+    policy_id: Option<u64>,
+    nullifier: Option<[u8; 32]>,
+    custodian_pubkey_hash: Option<[u8; 32]>,
+    snapshot_block_height: Option<u64>,
+    snapshot_anchor_orchard: Option<[u8; 32]>,
+    holder_binding: Option<[u8; 32]>,
+    proven_sum: Option<u128>,
+}
+
+impl VerifierPublicInputsBuilder {
+    fn new(layout: PublicInputLayout) -> Self {
+        Self {
+            layout,
+            threshold_raw: None,
+            required_currency_code: None,
+            current_epoch: None,
+            verifier_scope_id: None,
+            policy_id: None,
+            nullifier: None,
+            custodian_pubkey_hash: None,
+            snapshot_block_height: None,
+            snapshot_anchor_orchard: None,
+            holder_binding: None,
+            proven_sum: None,
+        }
+    }
+
+    /// Custodial inputs (V1): the prefix only.
+    pub fn for_v1() -> Self {
+        Self::new(PublicInputLayout::V1)
+    }
+
+    /// Orchard inputs (V2_ORCHARD): the snapshot height and anchor the proof
+    /// was made against, and the holder binding.
+    pub fn for_orchard(height: u64, anchor: [u8; 32], holder_binding: [u8; 32]) -> Self {
+        Self {
+            snapshot_block_height: Some(height),
+            snapshot_anchor_orchard: Some(anchor),
+            holder_binding: Some(holder_binding),
+            ..Self::new(PublicInputLayout::V2Orchard)
+        }
+    }
+
+    /// Starknet inputs (V3_STARKNET). The block number and account commitment
+    /// travel in the snapshot height and anchor fields.
+    pub fn for_starknet(
+        block_number: u64,
+        account_commitment: [u8; 32],
+        holder_binding: [u8; 32],
+        proven_sum: u128,
+    ) -> Self {
+        Self {
+            snapshot_block_height: Some(block_number),
+            snapshot_anchor_orchard: Some(account_commitment),
+            holder_binding: Some(holder_binding),
+            proven_sum: Some(proven_sum),
+            ..Self::new(PublicInputLayout::V3Starknet)
+        }
+    }
+
+    /// Snapshot-commitment inputs (V6_SNAPSHOT_COMMITMENT): the same snapshot
+    /// fields as Starknet, which the proof exposes only as their commitment.
+    pub fn for_snapshot_commitment(
+        height: u64,
+        anchor: [u8; 32],
+        holder_binding: [u8; 32],
+        proven_sum: u128,
+    ) -> Self {
+        Self {
+            snapshot_block_height: Some(height),
+            snapshot_anchor_orchard: Some(anchor),
+            holder_binding: Some(holder_binding),
+            proven_sum: Some(proven_sum),
+            ..Self::new(PublicInputLayout::V6SnapshotCommitment)
+        }
+    }
+
+    pub fn layout(&self) -> PublicInputLayout {
+        self.layout
+    }
+
+    pub fn threshold_raw(mut self, threshold_raw: u64) -> Self {
+        self.threshold_raw = Some(threshold_raw);
+        self
+    }
+
+    pub fn required_currency_code(mut self, code: u32) -> Self {
+        self.required_currency_code = Some(code);
+        self
+    }
+
+    pub fn current_epoch(mut self, epoch: u64) -> Self {
+        self.current_epoch = Some(epoch);
+        self
+    }
+
+    pub fn verifier_scope_id(mut self, scope_id: u64) -> Self {
+        self.verifier_scope_id = Some(scope_id);
+        self
+    }
+
+    pub fn policy_id(mut self, policy_id: u64) -> Self {
+        self.policy_id = Some(policy_id);
+        self
+    }
+
+    pub fn nullifier(mut self, nullifier: [u8; 32]) -> Self {
+        self.nullifier = Some(nullifier);
+        self
+    }
+
+    pub fn custodian_pubkey_hash(mut self, hash: [u8; 32]) -> Self {
+        self.custodian_pubkey_hash = Some(hash);
+        self
+    }
+
+    /// The inputs, or an error naming the first prefix field left unset.
+    pub fn build(self) -> Result<VerifierPublicInputs> {
+        let layout = self.layout;
+        let missing = |field: &str| anyhow!("{field} is required for {layout:?} public inputs");
+        Ok(VerifierPublicInputs {
+            threshold_raw: self.threshold_raw.ok_or_else(|| missing("threshold_raw"))?,
+            required_currency_code: self
+                .required_currency_code
+                .ok_or_else(|| missing("required_currency_code"))?,
+            current_epoch: self.current_epoch.ok_or_else(|| missing("current_epoch"))?,
+            verifier_scope_id: self
+                .verifier_scope_id
+                .ok_or_else(|| missing("verifier_scope_id"))?,
+            policy_id: self.policy_id.ok_or_else(|| missing("policy_id"))?,
+            nullifier: self.nullifier.ok_or_else(|| missing("nullifier"))?,
+            custodian_pubkey_hash: self
+                .custodian_pubkey_hash
+                .ok_or_else(|| missing("custodian_pubkey_hash"))?,
+            snapshot_block_height: self.snapshot_block_height,
+            snapshot_anchor_orchard: self.snapshot_anchor_orchard,
+            holder_binding: self.holder_binding,
+            threshold_max: None,
+            proven_sum: self.proven_sum,
+            challenge: None,
+            extra_nullifiers: None,
+        })
+    }
+}
+
+pub fn deserialize_params(bytes: &[u8]) -> Result<ParamsKZG<Bn256>> {
+    let mut reader = Cursor::new(bytes);
+    ParamsKZG::<Bn256>::read(&mut reader).context("failed to deserialize KZG params")
+}
+
+pub fn deserialize_verifying_key(bytes: &[u8]) -> Result<plonk::VerifyingKey<G1Affine>> {
+    deserialize_verifying_key_with_columns(bytes, PUBLIC_INPUT_COUNT)
+}
+
+/// Deserializes a verifying key for a custodial-family circuit with
+/// `num_instance_columns` instance columns (the manifest's `instance_columns`).
+pub fn deserialize_verifying_key_with_columns(
+    bytes: &[u8],
+    num_instance_columns: usize,
+) -> Result<plonk::VerifyingKey<G1Affine>> {
+    let mut params = ZkpfCircuit::default().params();
+    params.num_instance_columns = num_instance_columns;
+    let mut reader = Cursor::new(bytes);
+    plonk::VerifyingKey::read::<_, ZkpfCircuit>(&mut reader, SerdeFormat::Processed, params)
+        .context("failed to deserialize verifying key")
+}
+
+pub fn verifier_inputs_to_public(inputs: &VerifierPublicInputs) -> Result<PublicInputs> {
+    let proven_sum = inputs
+        .proven_sum
+        .map(u64::try_from)
+        .transpose()
+        .map_err(|_| anyhow!("proven_sum does not fit in u64"))?;
+    let challenge = inputs.challenge.as_ref().map(fr_from_bytes).transpose()?;
+    Ok(PublicInputs {
+        proven_sum,
+        challenge,
+        ..v1_prefix_public(inputs)?
+    })
+}
+
+/// The V1 columns that prefix the extended layouts. A revealed `proven_sum`
+/// and a challenge belong to the custodial layout only and are left out.
+fn v1_prefix_public(inputs: &VerifierPublicInputs) -> Result<PublicInputs> {
+    Ok(PublicInputs {
+        threshold_raw: inputs.threshold_raw,
+        required_currency_code: inputs.required_currency_code,
+        current_epoch: inputs.current_epoch,
+        verifier_scope_id: inputs.verifier_scope_id,
+        policy_id: inputs.policy_id,
+        nullifier: fr_from_bytes(&inputs.nullifier)?,
+        custodian_pubkey_hash: fr_from_bytes(&inputs.custodian_pubkey_hash)?,
+        proven_sum: None,
+        challenge: None,
+    })
+}
+
+pub fn public_inputs_to_instances(inputs: &VerifierPublicInputs) -> Result<Vec<Vec<Fr>>> {
+    let public = verifier_inputs_to_public(inputs)?;
+    Ok(public_instances(&public))
+}
+
+/// Convert verifier-facing public inputs into Halo2 instances for a specific layout.
+///
+/// - `PublicInputLayout::V1` uses the legacy custodial layout (8 public inputs).
+/// - `PublicInputLayout::V2Orchard` appends Orchard snapshot metadata as additional
+///   instance columns while preserving the V1 prefix ordering.
+pub fn public_inputs_to_instances_with_layout(
+    layout: PublicInputLayout,
+    inputs: &VerifierPublicInputs,
+) -> Result<Vec<Vec<Fr>>> {
+    ensure!(
+        inputs.extra_nullifiers.is_none() || layout == PublicInputLayout::V4Aggregate,
+        "extra_nullifiers are only part of the V4_AGGREGATE public-input layout"
+    );
+    match layout {
+        PublicInputLayout::V1 => public_inputs_to_instances(inputs),
+        PublicInputLayout::V4Aggregate => {
+            ensure!(
+                inputs.challenge.is_none(),
+                "challenge is not part of the V4_AGGREGATE public-input layout"
+            );
+            let extra_nullifiers = inputs
+                .extra_nullifiers
+                .iter()
+                .flatten()
+                .map(fr_from_bytes)
+                .collect::<Result<Vec<_>>>()?;
+            let public = v1_prefix_public(inputs)?;
+            Ok(aggregate_public_instances(&public, &extra_nullifiers))
+        }
+        PublicInputLayout::V2Orchard => {
+            let snapshot_height = inputs.snapshot_block_height.ok_or_else(|| {
+                anyhow!("snapshot_block_height is required for V2_ORCHARD public-input layout")
+            })?;
+            let snapshot_anchor_bytes = inputs.snapshot_anchor_orchard.ok_or_else(|| {
+                anyhow!("snapshot_anchor_orchard is required for V2_ORCHARD public-input layout")
+            })?;
+
+            // For now we treat a missing holder_binding as zero; rails that require a
+            // binding can enforce its presence at a higher layer.
+            let holder_binding_bytes = inputs.holder_binding.unwrap_or([0u8; 32]);
+
+            // Reuse the existing PublicInputs conversion for the V1 prefix.
+            let public = v1_prefix_public(inputs)?;
+            let mut cols = public_instances(&public);
+
+            // Orchard-specific trailing fields.
+            let snapshot_height_fr = Fr::from(snapshot_height);
+            let anchor_fr = reduce_be_bytes_to_fr(&snapshot_anchor_bytes);
+            let holder_binding_fr = reduce_be_bytes_to_fr(&holder_binding_bytes);
+
+            cols.push(vec![snapshot_height_fr]);
+            cols.push(vec![anchor_fr]);
+            cols.push(vec![holder_binding_fr]);
+
+            Ok(cols)
+        }
+        PublicInputLayout::V3Starknet => {
+            // Starknet L2 layout: V1 prefix + 4 Starknet-specific fields = 11 columns total
+            let block_number = inputs.snapshot_block_height.ok_or_else(|| {
+                anyhow!("snapshot_block_height (block_number) is required for V3_STARKNET public-input layout")
+            })?;
+            // Reuse snapshot_anchor_orchard field for account_commitment
+            let account_commitment_bytes = inputs.snapshot_anchor_orchard.ok_or_else(|| {
+                anyhow!("snapshot_anchor_orchard (account_commitment) is required for V3_STARKNET public-input layout")
+            })?;
+            let holder_binding_bytes = inputs.holder_binding.unwrap_or([0u8; 32]);
+            // proven_sum is required for V3_STARKNET to match circuit's 11-column layout
+            let proven_sum = inputs.proven_sum.ok_or_else(|| {
+                anyhow!("proven_sum is required for V3_STARKNET public-input layout")
+            })?;
+
+            // Reuse the existing PublicInputs conversion for the V1 prefix (7 columns).
+            let public = v1_prefix_public(inputs)?;
+            let mut cols = public_instances(&public);
+
+            // Starknet-specific trailing fields (4 columns: 7+4=11 total).
+            let block_number_fr = Fr::from(block_number);
+            let account_commitment_fr = reduce_be_bytes_to_fr(&account_commitment_bytes);
+            let holder_binding_fr = reduce_be_bytes_to_fr(&holder_binding_bytes);
+            // proven_sum as u128 -> split into two u64 limbs and pack into Fr
+            // For simplicity, we truncate to u64 here; full u128 support would need
+            // more complex encoding or multiple field elements
+            let proven_sum_fr = Fr::from(proven_sum as u64);
+
+            cols.push(vec![block_number_fr]);
+            cols.push(vec![account_commitment_fr]);
+            cols.push(vec![holder_binding_fr]);
+            cols.push(vec![proven_sum_fr]);
+
+            Ok(cols)
+        }
+        PublicInputLayout::V5Range => {
+            let threshold_max = inputs.threshold_max.ok_or_else(|| {
+                anyhow!("threshold_max is required for V5_RANGE public-input layout")
+            })?;
+            let public = v1_prefix_public(inputs)?;
+            Ok(range_public_instances(&public, threshold_max))
+        }
+        PublicInputLayout::V1WithSum => {
+            let proven_sum = inputs.proven_sum.ok_or_else(|| {
+                anyhow!("proven_sum is required for V1_WITH_SUM public-input layout")
+            })?;
+            let proven_sum =
+                u64::try_from(proven_sum).map_err(|_| anyhow!("proven_sum does not fit in u64"))?;
+            ensure!(
+                proven_sum >= inputs.threshold_raw,
+                "proven_sum {} is below threshold_raw {}",
+                proven_sum,
+                inputs.threshold_raw
+            );
+            ensure!(
+                inputs.challenge.is_none(),
+                "challenge is not part of the V1_WITH_SUM public-input layout"
+            );
+            let public = PublicInputs {
+                proven_sum: Some(proven_sum),
+                ..v1_prefix_public(inputs)?
+            };
+            Ok(public_instances(&public))
+        }
+        PublicInputLayout::V6SnapshotCommitment => {
+            let commitment = snapshot_commitment(inputs)?;
+            let public = v1_prefix_public(inputs)?;
+            let mut cols = public_instances(&public);
+            cols.push(vec![commitment]);
+            Ok(cols)
+        }
+    }
+}
+
+/// The Poseidon commitment the V6_SNAPSHOT_COMMITMENT layout exposes in place
+/// of the snapshot fields, recomputed from them.
+///
+/// `snapshot_block_height` and `snapshot_anchor_orchard` are required; a
+/// missing `holder_binding` or `proven_sum` is committed as zero. The anchor and
+/// binding are reduced into the field the same way as their V2_ORCHARD columns.
+pub fn snapshot_commitment(inputs: &VerifierPublicInputs) -> Result<Fr> {
+    let height = inputs.snapshot_block_height.ok_or_else(|| {
+        anyhow!("snapshot_block_height is required for V6_SNAPSHOT_COMMITMENT public-input layout")
+    })?;
+    let anchor = inputs.snapshot_anchor_orchard.ok_or_else(|| {
+        anyhow!(
+            "snapshot_anchor_orchard is required for V6_SNAPSHOT_COMMITMENT public-input layout"
+        )
+    })?;
+    Ok(snapshot_commitment_native(
+        height,
+        reduce_be_bytes_to_fr(&anchor),
+        reduce_be_bytes_to_fr(&inputs.holder_binding.unwrap_or([0u8; 32])),
+        inputs.proven_sum.unwrap_or(0),
+    ))
+}
+
+pub fn fr_from_bytes(bytes: &[u8; 32]) -> Result<Fr> {
+    Fr::from_bytes(bytes)
+        .into_option()
+        .ok_or_else(|| anyhow!("invalid bn256 scalar encoding"))
+}
+
+/// Convert big-endian bytes to a field element with **modular reduction**.
+///
+/// # Security Warning
+///
+/// This function performs **implicit modular reduction**. A 32-byte value can represent
+/// integers up to 2^256 - 1, but the BN256 scalar field modulus is approximately 2^254.
+/// This means:
+///
+/// - **Values >= field modulus will wrap around** (reduced mod r)
+/// - **Two different byte inputs can produce the same field element** (collision)
+///
+/// # When to Use
+///
+/// Use this function when:
+/// - You're hashing external data where the output will be further processed (e.g., Poseidon hash)
+/// - Collisions don't matter because the result is used in a collision-resistant hash
+/// - You explicitly want modular reduction behavior
+///
+/// # When NOT to Use
+///
+/// Do NOT use this function when:
+/// - The 32-byte value must be uniquely recoverable from the field element
+/// - You need to verify that the original value fits in the field exactly
+/// - You're processing untrusted inputs that could exploit collisions
+///
+/// For those cases, use `zkpf_common::try_be_bytes_to_fr_exact`, which errors on overflow.
+///
+/// # Example
+///
+/// ```ignore
+/// // Safe: hashing pubkey coordinates for Poseidon - result goes into hash, collisions don't matter
+/// let x_fr = reduce_be_bytes_to_fr(&pubkey.x);
+/// let y_fr = reduce_be_bytes_to_fr(&pubkey.y);
+/// let hash = poseidon_hash(&[x_fr, y_fr]);
+///
+/// // Unsafe without validation: if you need to recover the original bytes
+/// // let value = reduce_be_bytes_to_fr(&untrusted_input); // May lose information!
+/// ```
+pub fn reduce_be_bytes_to_fr(bytes: &[u8; 32]) -> Fr {
+    let mut acc = Fr::zero();
+    let base = Fr::from(256);
+    for byte in bytes.iter() {
+        acc = acc * base + Fr::from(*byte as u64);
+    }
+    acc
+}
+
+/// Check a KZG (GWC) proof against `vk` and the given instance columns.
+pub fn verify(
+    params: &ParamsKZG<Bn256>,
+    vk: &plonk::VerifyingKey<G1Affine>,
+    proof_bytes: &[u8],
+    instances: &[Vec<Fr>],
+) -> bool {
+    let mut transcript = Blake2bRead::<_, G1Affine, Challenge255<_>>::init(proof_bytes);
+
+    let instance_columns: Vec<&[Fr]> = instances.iter().map(|col| col.as_slice()).collect();
+    let prepared_instances = vec![instance_columns.as_slice()];
+
+    verify_proof::<KZGCommitmentScheme<Bn256>, VerifierGWC<'_, Bn256>, _, _, _>(
+        params,
+        vk,
+        SingleStrategy::new(params),
+        &prepared_instances,
+        &mut transcript,
+    )
+    .is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_inputs() -> VerifierPublicInputs {
+        VerifierPublicInputs {
+            threshold_raw: 1_000,
+            required_currency_code: 840,
+            current_epoch: 1_700_000_000,
+            verifier_scope_id: 42,
+            policy_id: 7,
+            nullifier: [1u8; 32],
+            custodian_pubkey_hash: [2u8; 32],
+            snapshot_block_height: None,
+            snapshot_anchor_orchard: None,
+            holder_binding: None,
+            threshold_max: None,
+            proven_sum: None,
+            challenge: None,
+            extra_nullifiers: None,
+        }
+    }
+
+    #[test]
+    fn v1_inputs_serialize_without_optional_fields() {
+        let json = serde_json::to_value(sample_inputs()).unwrap();
+        let keys: Vec<&str> = json
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        assert_eq!(keys.len(), 7);
+        assert!(!keys.contains(&"snapshot_block_height"));
+        assert!(!keys.contains(&"proven_sum"));
+        assert_eq!(
+            serde_json::to_value(PublicInputLayout::V2Orchard).unwrap(),
+            "V2_ORCHARD"
+        );
+    }
+
+    #[test]
+    fn layouts_produce_expected_column_counts() {
+        let v1 = sample_inputs();
+        assert_eq!(
+            public_inputs_to_instances_with_layout(PublicInputLayout::V1, &v1)
+                .unwrap()
+                .len(),
+            PUBLIC_INPUT_COUNT
+        );
+
+        let orchard = VerifierPublicInputs {
+            snapshot_block_height: Some(2_000_000),
+            snapshot_anchor_orchard: Some([3u8; 32]),
+            ..sample_inputs()
+        };
+        assert_eq!(
+            public_inputs_to_instances_with_layout(PublicInputLayout::V2Orchard, &orchard)
+                .unwrap()
+                .len(),
+            PUBLIC_INPUT_COUNT_V2_ORCHARD
+        );
+        assert!(public_inputs_to_instances_with_layout(PublicInputLayout::V2Orchard, &v1).is_err());
+        assert_eq!(PublicInputLayout::V1.instance_columns(), PUBLIC_INPUT_COUNT);
+        assert_eq!(
+            PublicInputLayout::V2Orchard.instance_columns(),
+            PUBLIC_INPUT_COUNT_V2_ORCHARD
+        );
+    }
+
+    #[test]
+    fn v1_with_sum_requires_a_sum_within_range() {
+        assert!(public_inputs_to_instances_with_layout(
+            PublicInputLayout::V1WithSum,
+            &sample_inputs()
+        )
+        .is_err());
+
+        let below = VerifierPublicInputs {
+            proven_sum: Some(999),
+            ..sample_inputs()
+        };
+        assert!(
+            public_inputs_to_instances_with_layout(PublicInputLayout::V1WithSum, &below).is_err()
+        );
+        let too_wide = VerifierPublicInputs {
+            proven_sum: Some(u128::from(u64::MAX) + 1),
+            ..sample_inputs()
+        };
+        assert!(
+            public_inputs_to_instances_with_layout(PublicInputLayout::V1WithSum, &too_wide)
+                .is_err()
+        );
+
+        let revealed = VerifierPublicInputs {
+            proven_sum: Some(5_000),
+            ..sample_inputs()
+        };
+        let instances =
+            public_inputs_to_instances_with_layout(PublicInputLayout::V1WithSum, &revealed)
+                .unwrap();
+        assert_eq!(instances.len(), PUBLIC_INPUT_COUNT_REVEAL_SUM);
+        assert_eq!(instances[7][0], Fr::from(5_000u64));
+    }
+
+    #[test]
+    fn challenge_appends_a_custodial_column() {
+        let bound = VerifierPublicInputs {
+            challenge: Some([9u8; 32]),
+            ..sample_inputs()
+        };
+        let instances = public_inputs_to_instances(&bound).unwrap();
+        assert_eq!(instances.len(), PUBLIC_INPUT_COUNT_CHALLENGE);
+        assert_eq!(instances[7][0], fr_from_bytes(&[9u8; 32]).unwrap());
+    }
+
+    #[test]
+    fn aggregate_layout_lists_every_account_nullifier() {
+        let aggregate = VerifierPublicInputs {
+            extra_nullifiers: Some(vec![[5u8; 32], [6u8; 32]]),
+            ..sample_inputs()
+        };
+        let instances =
+            public_inputs_to_instances_with_layout(PublicInputLayout::V4Aggregate, &aggregate)
+                .unwrap();
+        assert_eq!(instances.len(), PUBLIC_INPUT_COUNT_V4_AGGREGATE);
+        let nullifiers: Vec<Fr> = [[1u8; 32], [5u8; 32], [6u8; 32]]
+            .iter()
+            .map(|bytes| fr_from_bytes(bytes).unwrap())
+            .collect();
+        assert_eq!(instances[5], nullifiers);
+        assert!(public_inputs_to_instances_with_layout(PublicInputLayout::V1, &aggregate).is_err());
+    }
+
+    #[test]
+    fn builder_sets_the_fields_each_layout_needs() {
+        let prefix = |builder: VerifierPublicInputsBuilder| {
+            builder
+                .threshold_raw(1_000)
+                .required_currency_code(840)
+                .current_epoch(1_700_000_000)
+                .verifier_scope_id(42)
+                .policy_id(7)
+                .nullifier([1u8; 32])
+                .custodian_pubkey_hash([2u8; 32])
+        };
+
+        let v1 = prefix(VerifierPublicInputsBuilder::for_v1()).build();
+        assert_eq!(
+            serde_json::to_value(v1.unwrap()).unwrap(),
+            serde_json::to_value(sample_inputs()).unwrap()
+        );
+
+        let builders = [
+            VerifierPublicInputsBuilder::for_orchard(2_000_000, [3u8; 32], [4u8; 32]),
+            VerifierPublicInputsBuilder::for_starknet(900_000, [3u8; 32], [4u8; 32], 5_000),
+            VerifierPublicInputsBuilder::for_snapshot_commitment(
+                900_000, [3u8; 32], [4u8; 32], 5_000,
+            ),
+        ];
+        for builder in builders {
+            let layout = builder.layout();
+            let inputs = prefix(builder).build().unwrap();
+            let instances = public_inputs_to_instances_with_layout(layout, &inputs).unwrap();
+            assert_eq!(instances.len(), layout.instance_columns());
+        }
+
+        let err = VerifierPublicInputsBuilder::for_v1()
+            .threshold_raw(1_000)
+            .build()
+            .unwrap_err();
+        assert!(err.to_string().contains("required_currency_code"));
+    }
+
+    #[test]
+    fn snapshot_commitment_replaces_the_snapshot_columns() {
+        let snapshot = VerifierPublicInputs {
+            snapshot_block_height: Some(2_000_000),
+            snapshot_anchor_orchard: Some([3u8; 32]),
+            holder_binding: Some([4u8; 32]),
+            proven_sum: Some(5_000),
+            ..sample_inputs()
+        };
+        let layout = PublicInputLayout::V6SnapshotCommitment;
+        let instances = public_inputs_to_instances_with_layout(layout, &snapshot).unwrap();
+        assert_eq!(instances.len(), PUBLIC_INPUT_COUNT_V6_SNAPSHOT_COMMITMENT);
+        assert_eq!(
+            instances[..PUBLIC_INPUT_COUNT],
+            public_inputs_to_instances(&sample_inputs()).unwrap()[..]
+        );
+        let commitment = snapshot_commitment(&snapshot).unwrap();
+        assert_eq!(instances[PUBLIC_INPUT_COUNT], vec![commitment]);
+
+        let changed = [
+            VerifierPublicInputs {
+                snapshot_block_height: Some(2_000_001),
+                ..snapshot.clone()
+            },
+            VerifierPublicInputs {
+                snapshot_anchor_orchard: Some([5u8; 32]),
+                ..snapshot.clone()
+            },
+            VerifierPublicInputs {
+                holder_binding: None,
+                ..snapshot.clone()
+            },
+            VerifierPublicInputs {
+                proven_sum: Some(5_001),
+                ..snapshot.clone()
+            },
+        ];
+        for inputs in &changed {
+            assert_ne!(snapshot_commitment(inputs).unwrap(), commitment);
+        }
+        assert!(public_inputs_to_instances_with_layout(layout, &sample_inputs()).is_err());
+    }
+}
@@ -26,18 +26,77 @@ use zkpf_common::{
     POSEIDON_FULL_ROUNDS, POSEIDON_PARTIAL_ROUNDS, POSEIDON_RATE, POSEIDON_T,
 };
 use zkpf_prover::{prove, prove_bundle_result, prove_with_public_inputs};
-use zkpf_verifier::verify;
+use zkpf_verifier::{verify, verify_bundles};
 use zkpf_zcash_orchard_circuit::{
     deserialize_break_points, OrchardBreakPoints, OrchardPofCircuit, OrchardPofCircuitInput,
-    ORCHARD_DEFAULT_K, RAIL_ID_ZCASH_ORCHARD,
+    ORCHARD_DEFAULT_K, ORCHARD_MAX_NOTES, RAIL_ID_ZCASH_ORCHARD,
 };
 
+/// Codes prefixed to thrown errors; the same set the backend reports.
+pub use zkpf_common::ErrorCode;
+
 // Initialize panic hook at WASM module load time for better error messages
 #[wasm_bindgen(start)]
 pub fn wasm_start() {
     console_error_panic_hook::set_once();
 }
 
+// TypeScript shapes of the serde payloads exchanged with JS. Byte arrays
+// cross the boundary as plain number arrays, `u128` values as `bigint`.
+#[wasm_bindgen(typescript_custom_section)]
+const TS_PAYLOAD_TYPES: &str = r#"
+export type ByteArray = number[];
+
+export interface VerifierPublicInputs {
+  threshold_raw: number;
+  required_currency_code: number;
+  current_epoch: number;
+  verifier_scope_id: number;
+  policy_id: number;
+  nullifier: ByteArray;
+  custodian_pubkey_hash: ByteArray;
+  snapshot_block_height?: number;
+  snapshot_anchor_orchard?: ByteArray;
+  holder_binding?: ByteArray;
+  threshold_max?: number;
+  proven_sum?: bigint;
+  challenge?: ByteArray;
+  extra_nullifiers?: ByteArray[];
+}
+
+export interface ProofBundle {
+  rail_id?: string;
+  circuit_version: number;
+  proof: ByteArray;
+  proof_encoding?: "zstd";
+  issued_at?: number;
+  issued_at_tag?: ByteArray;
+  public_inputs: VerifierPublicInputs;
+}
+
+export interface BatchVerifyOutcome {
+  index: number;
+  valid: boolean;
+  error?: string;
+  error_code?: string;
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    /// A `ProofBundle` object on the JS side.
+    #[wasm_bindgen(typescript_type = "ProofBundle")]
+    pub type ProofBundleJs;
+
+    /// An array of `ProofBundle` objects.
+    #[wasm_bindgen(typescript_type = "ProofBundle[]")]
+    pub type ProofBundleArrayJs;
+
+    /// Per-bundle results of `verifyProofBundlesBatchCached`.
+    #[wasm_bindgen(typescript_type = "BatchVerifyOutcome[]")]
+    pub type BatchVerifyOutcomeArrayJs;
+}
+
 #[wasm_bindgen]
 pub struct VerifyingKeyWasm {
     vk: plonk::VerifyingKey<G1Affine>,
@@ -76,7 +135,8 @@ thread_local! {
 impl VerifyingKeyWasm {
     #[wasm_bindgen(constructor)]
     pub fn new(bytes: &[u8]) -> Result<VerifyingKeyWasm, JsValue> {
-        let vk = deserialize_verifying_key(bytes).map_err(js_error)?;
+        let vk = deserialize_verifying_key(bytes)
+            .map_err(|e| js_error(ErrorCode::ArtifactInvalid, e))?;
         Ok(Self {
             vk,
             serialized: bytes.to_vec(),
@@ -93,11 +153,7 @@ impl VerifyingKeyWasm {
 impl ParamsWasm {
     #[wasm_bindgen(constructor)]
     pub fn new(bytes: &[u8]) -> Result<ParamsWasm, JsValue> {
-        let params = deserialize_params(bytes).map_err(js_error)?;
-        Ok(Self {
-            params,
-            serialized: bytes.to_vec(),
-        })
+        Self::from_vec(bytes.to_vec())
     }
 
     #[wasm_bindgen(js_name = toBytes)]
@@ -110,11 +166,7 @@ impl ParamsWasm {
 impl ProvingKeyWasm {
     #[wasm_bindgen(constructor)]
     pub fn new(bytes: &[u8]) -> Result<ProvingKeyWasm, JsValue> {
-        let pk = deserialize_proving_key(bytes).map_err(js_error)?;
-        Ok(Self {
-            pk,
-            serialized: bytes.to_vec(),
-        })
+        Self::from_vec(bytes.to_vec())
     }
 
     #[wasm_bindgen(js_name = toBytes)]
@@ -123,6 +175,72 @@ impl ProvingKeyWasm {
     }
 }
 
+/// Assembles a `ParamsWasm` from chunks, so JS can stream the download (e.g.
+/// from a `ReadableStream`) and drop each chunk once pushed instead of holding
+/// the whole blob in a second buffer.
+#[wasm_bindgen]
+pub struct ParamsWasmBuilder {
+    buffer: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl ParamsWasmBuilder {
+    /// `expected_len` (e.g. the `Content-Length`) reserves the buffer up front
+    /// so it is not reallocated while chunks arrive.
+    #[wasm_bindgen(constructor)]
+    pub fn new(expected_len: Option<usize>) -> ParamsWasmBuilder {
+        Self {
+            buffer: Vec::with_capacity(expected_len.unwrap_or(0)),
+        }
+    }
+
+    #[wasm_bindgen(js_name = pushChunk)]
+    pub fn push_chunk(&mut self, chunk: &[u8]) {
+        self.buffer.extend_from_slice(chunk);
+    }
+
+    #[wasm_bindgen(getter, js_name = bytesReceived)]
+    pub fn bytes_received(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Deserialize the pushed bytes. The builder is consumed and its buffer
+    /// becomes the params' serialized copy.
+    pub fn finalize(self) -> Result<ParamsWasm, JsValue> {
+        ParamsWasm::from_vec(self.buffer)
+    }
+}
+
+/// Chunked counterpart of `ProvingKeyWasm`; see `ParamsWasmBuilder`.
+#[wasm_bindgen]
+pub struct ProvingKeyWasmBuilder {
+    buffer: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl ProvingKeyWasmBuilder {
+    #[wasm_bindgen(constructor)]
+    pub fn new(expected_len: Option<usize>) -> ProvingKeyWasmBuilder {
+        Self {
+            buffer: Vec::with_capacity(expected_len.unwrap_or(0)),
+        }
+    }
+
+    #[wasm_bindgen(js_name = pushChunk)]
+    pub fn push_chunk(&mut self, chunk: &[u8]) {
+        self.buffer.extend_from_slice(chunk);
+    }
+
+    #[wasm_bindgen(getter, js_name = bytesReceived)]
+    pub fn bytes_received(&self) -> usize {
+        self.buffer.len()
+    }
+
+    pub fn finalize(self) -> Result<ProvingKeyWasm, JsValue> {
+        ProvingKeyWasm::from_vec(self.buffer)
+    }
+}
+
 #[wasm_bindgen]
 impl PublicInputsWasm {
     #[wasm_bindgen(constructor)]
@@ -151,31 +269,37 @@ impl PublicInputsWasm {
                 snapshot_block_height: None,
                 snapshot_anchor_orchard: None,
                 holder_binding: None,
+                threshold_max: None,
                 proven_sum: None,
+                challenge: None,
+                extra_nullifiers: None,
             },
         })
     }
 
     #[wasm_bindgen(js_name = fromJson)]
     pub fn from_json(json: &str) -> Result<PublicInputsWasm, JsValue> {
-        let inputs: VerifierPublicInputs = serde_json::from_str(json).map_err(js_error)?;
+        let inputs: VerifierPublicInputs =
+            serde_json::from_str(json).map_err(|e| js_error(ErrorCode::PublicInputsInvalid, e))?;
         Ok(Self { inputs })
     }
 
     #[wasm_bindgen(js_name = fromBytes)]
     pub fn from_bytes(bytes: &[u8]) -> Result<PublicInputsWasm, JsValue> {
-        let inputs = deserialize_verifier_public_inputs(bytes).map_err(js_error)?;
+        let inputs = deserialize_verifier_public_inputs(bytes)
+            .map_err(|e| js_error(ErrorCode::PublicInputsInvalid, e))?;
         Ok(Self { inputs })
     }
 
     #[wasm_bindgen(js_name = toJson)]
     pub fn to_json(&self) -> Result<String, JsValue> {
-        serde_json::to_string(&self.inputs).map_err(js_error)
+        serde_json::to_string(&self.inputs).map_err(|e| js_error(ErrorCode::PublicInputsInvalid, e))
     }
 
     #[wasm_bindgen(js_name = toBytes)]
     pub fn to_bytes(&self) -> Result<Vec<u8>, JsValue> {
-        serialize_verifier_public_inputs(self.inner()).map_err(js_error)
+        serialize_verifier_public_inputs(self.inner())
+            .map_err(|e| js_error(ErrorCode::PublicInputsInvalid, e))
     }
 
     #[wasm_bindgen(getter)]
@@ -250,6 +374,14 @@ pub fn init_prover_artifacts(params_bytes: &[u8], pk_bytes: &[u8]) -> Result<(),
     Ok(())
 }
 
+/// Cache prover artifacts that were already deserialized, e.g. by
+/// `ParamsWasmBuilder` and `ProvingKeyWasmBuilder`.
+#[wasm_bindgen(js_name = cacheProverArtifacts)]
+pub fn cache_prover_artifacts(params: ParamsWasm, pk: ProvingKeyWasm) {
+    cache_params(params);
+    cache_pk(pk);
+}
+
 #[wasm_bindgen(js_name = resetCachedArtifacts)]
 pub fn reset_cached_artifacts() {
     CACHED_PARAMS.with(|cell| {
@@ -291,7 +423,7 @@ pub fn generate_proof_bundle(
     attestation_json: &str,
     params_bytes: &[u8],
     pk_bytes: &[u8],
-) -> Result<JsValue, JsValue> {
+) -> Result<ProofBundleJs, JsValue> {
     let params = ParamsWasm::new(params_bytes)?;
     let pk = ProvingKeyWasm::new(pk_bytes)?;
     generate_proof_bundle_with_cache(attestation_json, &params, &pk)
@@ -302,9 +434,11 @@ pub fn generate_proof_bundle_with_cache(
     attestation_json: &str,
     params: &ParamsWasm,
     pk: &ProvingKeyWasm,
-) -> Result<JsValue, JsValue> {
+) -> Result<ProofBundleJs, JsValue> {
     let bundle = prove_bundle_with_structs(attestation_json, params, pk)?;
-    to_value(&bundle).map_err(js_error)
+    to_value(&bundle)
+        .map(JsCast::unchecked_into)
+        .map_err(|e| js_error(ErrorCode::InternalServerError, e))
 }
 
 #[wasm_bindgen(js_name = generateProofCached)]
@@ -316,16 +450,19 @@ pub fn generate_proof_cached(attestation_json: &str) -> Result<Vec<u8>, JsValue>
 }
 
 #[wasm_bindgen(js_name = generateProofBundleCached)]
-pub fn generate_proof_bundle_cached(attestation_json: &str) -> Result<JsValue, JsValue> {
+pub fn generate_proof_bundle_cached(attestation_json: &str) -> Result<ProofBundleJs, JsValue> {
     with_cached_prover(|params, pk| {
         let bundle = prove_bundle_with_structs(attestation_json, params, pk)?;
-        to_value(&bundle).map_err(js_error)
+        to_value(&bundle)
+            .map(JsCast::unchecked_into)
+            .map_err(|e| js_error(ErrorCode::InternalServerError, e))
     })
 }
 
 #[wasm_bindgen(js_name = computeAttestationMessageHash)]
 pub fn compute_attestation_message_hash(attestation_json: &str) -> Result<Vec<u8>, JsValue> {
-    let input: ZkpfCircuitInput = serde_json::from_str(attestation_json).map_err(js_error)?;
+    let input: ZkpfCircuitInput = serde_json::from_str(attestation_json)
+        .map_err(|e| js_error(ErrorCode::WitnessInvalid, e))?;
     let att = input.attestation;
     let digest = poseidon_hash([
         Fr::from(att.balance_raw),
@@ -359,7 +496,10 @@ pub fn compute_nullifier(
 #[wasm_bindgen(js_name = computeCustodianPubkeyHash)]
 pub fn compute_custodian_pubkey_hash(pubkey_x: &[u8], pubkey_y: &[u8]) -> Result<Vec<u8>, JsValue> {
     if pubkey_x.len() != 32 || pubkey_y.len() != 32 {
-        return Err(js_error("custodian pubkey coordinates must be 32 bytes"));
+        return Err(js_error(
+            ErrorCode::WitnessInvalid,
+            "custodian pubkey coordinates must be 32 bytes",
+        ));
     }
     let mut x = [0u8; 32];
     x.copy_from_slice(pubkey_x);
@@ -419,7 +559,7 @@ pub fn verify_proof_with_cache_bytes(
 
 #[wasm_bindgen(js_name = verifyProofBundle)]
 pub fn verify_proof_bundle(
-    bundle: &JsValue,
+    bundle: &ProofBundleJs,
     vk_bytes: &[u8],
     params_bytes: &[u8],
 ) -> Result<bool, JsValue> {
@@ -430,11 +570,12 @@ pub fn verify_proof_bundle(
 
 #[wasm_bindgen(js_name = verifyProofBundleWithCache)]
 pub fn verify_proof_bundle_with_cache(
-    bundle: &JsValue,
+    bundle: &ProofBundleJs,
     vk: &VerifyingKeyWasm,
     params: &ParamsWasm,
 ) -> Result<bool, JsValue> {
-    let parsed: ProofBundle = from_value(bundle.clone()).map_err(js_error)?;
+    let parsed: ProofBundle =
+        from_value(JsValue::clone(bundle)).map_err(|e| js_error(ErrorCode::BundleInvalid, e))?;
     ensure_bundle_version(&parsed)?;
     verify_bundle(&parsed, vk, params)
 }
@@ -458,12 +599,96 @@ pub fn verify_proof_cached_bytes(
 }
 
 #[wasm_bindgen(js_name = verifyProofBundleCached)]
-pub fn verify_proof_bundle_cached(bundle: &JsValue) -> Result<bool, JsValue> {
-    let parsed: ProofBundle = from_value(bundle.clone()).map_err(js_error)?;
+pub fn verify_proof_bundle_cached(bundle: &ProofBundleJs) -> Result<bool, JsValue> {
+    let parsed: ProofBundle =
+        from_value(JsValue::clone(bundle)).map_err(|e| js_error(ErrorCode::BundleInvalid, e))?;
     ensure_bundle_version(&parsed)?;
     with_cached_verifier(|params, vk| verify_bundle(&parsed, vk, params))
 }
 
+/// Encodes a bundle in the compact binary envelope, small enough for a QR
+/// code. With `compress`, the proof is zstd-compressed first.
+#[wasm_bindgen(js_name = proofBundleToCompactBytes)]
+pub fn proof_bundle_to_compact_bytes(
+    bundle: &ProofBundleJs,
+    compress: bool,
+) -> Result<Vec<u8>, JsValue> {
+    let mut parsed: ProofBundle =
+        from_value(JsValue::clone(bundle)).map_err(|e| js_error(ErrorCode::BundleInvalid, e))?;
+    if compress {
+        parsed = parsed
+            .compressed()
+            .map_err(|e| js_error(ErrorCode::BundleInvalid, e))?;
+    }
+    parsed
+        .to_compact_bytes()
+        .map_err(|e| js_error(ErrorCode::BundleInvalid, e))
+}
+
+/// Decodes a compact envelope back into a `ProofBundle`.
+#[wasm_bindgen(js_name = proofBundleFromCompactBytes)]
+pub fn proof_bundle_from_compact_bytes(bytes: &[u8]) -> Result<ProofBundleJs, JsValue> {
+    let bundle = ProofBundle::from_compact_bytes(bytes)
+        .map_err(|e| js_error(ErrorCode::BundleInvalid, e))?;
+    to_value(&bundle)
+        .map(JsCast::unchecked_into)
+        .map_err(|e| js_error(ErrorCode::InternalServerError, e))
+}
+
+/// Per-bundle result of `verifyProofBundlesBatchCached`.
+#[derive(serde::Serialize)]
+struct BatchVerifyOutcome {
+    index: usize,
+    valid: bool,
+    error: Option<String>,
+    error_code: Option<ErrorCode>,
+}
+
+/// Verifies an array of bundles against the cached custodial artifacts and
+/// returns `[{ index, valid, error, error_code }]`, one entry per bundle in order.
+///
+/// A bundle with the wrong circuit version or a malformed proof fails on its
+/// own without affecting the others.
+#[wasm_bindgen(js_name = verifyProofBundlesBatchCached)]
+pub fn verify_proof_bundles_batch_cached(
+    bundles: &ProofBundleArrayJs,
+) -> Result<BatchVerifyOutcomeArrayJs, JsValue> {
+    let parsed: Vec<ProofBundle> =
+        from_value(JsValue::clone(bundles)).map_err(|e| js_error(ErrorCode::BundleInvalid, e))?;
+    let outcomes = with_cached_verifier(|params, vk| {
+        let results = verify_bundles(params.inner(), vk.inner(), &parsed);
+        Ok(parsed
+            .iter()
+            .zip(results)
+            .enumerate()
+            .map(|(index, (bundle, result))| {
+                let result = check_bundle_version(bundle)
+                    .map_err(|err| (ErrorCode::CircuitVersionMismatch, err))
+                    .and_then(|()| {
+                        result.map_err(|err| (ErrorCode::ProofInvalid, err.to_string()))
+                    });
+                match result {
+                    Ok(valid) => BatchVerifyOutcome {
+                        index,
+                        valid,
+                        error: (!valid).then(|| "proof verification failed".to_string()),
+                        error_code: (!valid).then_some(ErrorCode::ProofInvalid),
+                    },
+                    Err((code, error)) => BatchVerifyOutcome {
+                        index,
+                        valid: false,
+                        error: Some(error),
+                        error_code: Some(code),
+                    },
+                }
+            })
+            .collect::<Vec<_>>())
+    })?;
+    to_value(&outcomes)
+        .map(JsCast::unchecked_into)
+        .map_err(|e| js_error(ErrorCode::InternalServerError, e))
+}
+
 impl VerifyingKeyWasm {
     fn inner(&self) -> &plonk::VerifyingKey<G1Affine> {
         &self.vk
@@ -471,12 +696,30 @@ impl VerifyingKeyWasm {
 }
 
 impl ParamsWasm {
+    fn from_vec(bytes: Vec<u8>) -> Result<Self, JsValue> {
+        let params =
+            deserialize_params(&bytes).map_err(|e| js_error(ErrorCode::ArtifactInvalid, e))?;
+        Ok(Self {
+            params,
+            serialized: bytes,
+        })
+    }
+
     fn inner(&self) -> &ParamsKZG<Bn256> {
         &self.params
     }
 }
 
 impl ProvingKeyWasm {
+    fn from_vec(bytes: Vec<u8>) -> Result<Self, JsValue> {
+        let pk =
+            deserialize_proving_key(&bytes).map_err(|e| js_error(ErrorCode::ArtifactInvalid, e))?;
+        Ok(Self {
+            pk,
+            serialized: bytes,
+        })
+    }
+
     fn inner(&self) -> &plonk::ProvingKey<G1Affine> {
         &self.pk
     }
@@ -488,7 +731,8 @@ impl PublicInputsWasm {
     }
 
     fn instances(&self) -> Result<Vec<Vec<Fr>>, JsValue> {
-        public_inputs_to_instances(self.inner()).map_err(js_error)
+        public_inputs_to_instances(self.inner())
+            .map_err(|e| js_error(ErrorCode::PublicInputsInvalid, e))
     }
 }
 
@@ -548,7 +792,7 @@ fn prove_bundle_with_structs(
     // panics become opaque "unreachable" traps. By using prove_bundle_result,
     // we get proper error messages instead of aborts.
     let bundle = prove_bundle_result(params.inner(), pk.inner(), input).map_err(|e| {
-        js_error(format!(
+        js_error(ErrorCode::ProofGenerationFailed, format!(
             "Proof generation failed: {}. This may indicate: (1) circuit parameters/proving key \
              mismatch, (2) invalid attestation values that violate constraints, or (3) insufficient \
              memory. Try refreshing and re-downloading artifacts.",
@@ -569,21 +813,29 @@ fn verify_bundle(
     vk: &VerifyingKeyWasm,
     params: &ParamsWasm,
 ) -> Result<bool, JsValue> {
-    let instances = public_inputs_to_instances(&bundle.public_inputs).map_err(js_error)?;
+    let instances = public_inputs_to_instances(&bundle.public_inputs)
+        .map_err(|e| js_error(ErrorCode::PublicInputsInvalid, e))?;
+    let proof = bundle
+        .decoded_proof()
+        .map_err(|e| js_error(ErrorCode::ProofInvalid, e))?;
     Ok(verify(
         params.inner(),
         vk.inner(),
-        &bundle.proof,
+        &proof,
         &instances,
     ))
 }
 
 fn ensure_bundle_version(bundle: &ProofBundle) -> Result<(), JsValue> {
+    check_bundle_version(bundle).map_err(|err| js_error(ErrorCode::CircuitVersionMismatch, err))
+}
+
+fn check_bundle_version(bundle: &ProofBundle) -> Result<(), String> {
     if bundle.circuit_version != CIRCUIT_VERSION {
-        return Err(js_error(format!(
+        return Err(format!(
             "bundle circuit_version {} does not match wasm crate {}",
             bundle.circuit_version, CIRCUIT_VERSION
-        )));
+        ));
     }
     Ok(())
 }
@@ -631,7 +883,12 @@ impl OrchardProvingKeyWasm {
             SerdeFormat::Processed,
             params,
         )
-        .map_err(|e| js_error(format!("failed to deserialize Orchard proving key: {:?}", e)))?;
+        .map_err(|e| {
+            js_error(
+                ErrorCode::ArtifactInvalid,
+                format!("failed to deserialize Orchard proving key: {:?}", e),
+            )
+        })?;
         
         Ok(Self {
             pk,
@@ -720,7 +977,12 @@ pub fn init_orchard_prover_artifacts(
     
     // Deserialize break points - these are REQUIRED for proof generation
     let break_points = deserialize_break_points(break_points_bytes)
-        .map_err(|e| js_error(format!("failed to deserialize Orchard break points: {}", e)))?;
+        .map_err(|e| {
+            js_error(
+                ErrorCode::ArtifactInvalid,
+                format!("failed to deserialize Orchard break points: {}", e),
+            )
+        })?;
     
     web_sys::console::log_1(&format!(
         "[ZKPF Orchard WASM] Break points loaded: {} phases",
@@ -737,20 +999,42 @@ pub fn init_orchard_prover_artifacts(
 }
 
 /// Generate an Orchard proof bundle using the cached Orchard artifacts.
+///
+/// `holder_id` and `fvk` are the strings `holder_binding` was derived from;
+/// the circuit recomputes the binding from them, so both are required.
 #[wasm_bindgen(js_name = generateOrchardProofBundleCached)]
 pub fn generate_orchard_proof_bundle_cached(
     public_inputs_json: &str,
     note_values_json: &str,
-) -> Result<JsValue, JsValue> {
+    holder_id: Option<String>,
+    fvk: Option<String>,
+) -> Result<ProofBundleJs, JsValue> {
     web_sys::console::log_1(&"━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".into());
     web_sys::console::log_1(&"[ZKPF Orchard WASM] generateOrchardProofBundleCached called".into());
     web_sys::console::log_1(&"━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".into());
     
     with_cached_orchard_prover(|params, pk, break_points| {
-        let public_inputs: VerifierPublicInputs =
-            serde_json::from_str(public_inputs_json).map_err(js_error)?;
-        let note_values: Vec<u64> =
-            serde_json::from_str(note_values_json).map_err(js_error)?;
+        let public_inputs: VerifierPublicInputs = serde_json::from_str(public_inputs_json)
+            .map_err(|e| js_error(ErrorCode::PublicInputsInvalid, e))?;
+        let note_values: Vec<u64> = serde_json::from_str(note_values_json)
+            .map_err(|e| js_error(ErrorCode::WitnessInvalid, e))?;
+        let (holder_id, fvk) = match (holder_id.as_deref(), fvk.as_deref()) {
+            (Some(holder_id), Some(fvk)) => (holder_id, fvk),
+            (holder_id, fvk) => {
+                let missing: Vec<&str> = [("holder_id", holder_id), ("fvk", fvk)]
+                    .into_iter()
+                    .filter(|(_, value)| value.is_none())
+                    .map(|(field, _)| field)
+                    .collect();
+                return Err(js_error(
+                    ErrorCode::WitnessInvalid,
+                    format!("missing required fields: {}", missing.join(", ")),
+                ));
+            }
+        };
+        // The wasm prover only receives note values, so every note is native ZEC.
+        let circuit_input =
+            OrchardPofCircuitInput::native(public_inputs, note_values, holder_id, fvk);
         
         // Log artifact key from cached artifacts
         let artifact_key = compute_artifact_key(&params.serialized, &pk.serialized);
@@ -763,15 +1047,120 @@ pub fn generate_orchard_proof_bundle_cached(
             break_points.len()
         ).into());
         
-        let bundle = prove_orchard_bundle_with_structs(
-            public_inputs,
-            note_values,
-            params,
-            pk,
-            break_points,
-        )?;
+        let bundle = prove_orchard_bundle_with_structs(circuit_input, params, pk, break_points)?;
         
-        to_value(&bundle).map_err(js_error)
+        to_value(&bundle)
+            .map(JsCast::unchecked_into)
+            .map_err(|e| js_error(ErrorCode::InternalServerError, e))
+    })
+}
+
+/// Orchard circuit input assembled by `orchardInputFromJson`.
+#[wasm_bindgen]
+pub struct OrchardCircuitInputWasm {
+    input: OrchardPofCircuitInput,
+}
+
+#[wasm_bindgen]
+impl OrchardCircuitInputWasm {
+    #[wasm_bindgen(getter, js_name = noteCount)]
+    pub fn note_count(&self) -> usize {
+        self.input.note_values.len()
+    }
+
+    #[wasm_bindgen(js_name = publicInputs)]
+    pub fn public_inputs(&self) -> PublicInputsWasm {
+        PublicInputsWasm {
+            inputs: self.input.public_inputs.clone(),
+        }
+    }
+}
+
+/// Public-input fields the V2_ORCHARD layout cannot do without.
+const ORCHARD_REQUIRED_PUBLIC_FIELDS: &[&str] = &[
+    "threshold_raw",
+    "required_currency_code",
+    "current_epoch",
+    "verifier_scope_id",
+    "policy_id",
+    "nullifier",
+    "custodian_pubkey_hash",
+    "snapshot_block_height",
+    "snapshot_anchor_orchard",
+];
+
+/// Build an Orchard circuit input from `{ public_inputs, note_values,
+/// holder_id, fvk }`, where `holder_id` and `fvk` are the strings the Poseidon
+/// holder binding was derived from.
+///
+/// Every note is treated as native ZEC. Missing fields are reported together,
+/// as `PUBLIC_INPUTS_INVALID: missing required fields: public_inputs.x, ...`.
+#[wasm_bindgen(js_name = orchardInputFromJson)]
+pub fn orchard_input_from_json(json: &str) -> Result<OrchardCircuitInputWasm, JsValue> {
+    let value: serde_json::Value =
+        serde_json::from_str(json).map_err(|e| js_error(ErrorCode::PublicInputsInvalid, e))?;
+    let present = |field: Option<&serde_json::Value>| field.is_some_and(|v| !v.is_null());
+
+    let public = value.get("public_inputs");
+    let mut missing: Vec<String> = Vec::new();
+    if !present(public) {
+        missing.push("public_inputs".into());
+    } else {
+        missing.extend(
+            ORCHARD_REQUIRED_PUBLIC_FIELDS
+                .iter()
+                .filter(|field| !present(public.and_then(|p| p.get(**field))))
+                .map(|field| format!("public_inputs.{field}")),
+        );
+    }
+    for field in ["note_values", "holder_id", "fvk"] {
+        if !present(value.get(field)) {
+            missing.push(field.into());
+        }
+    }
+    if !missing.is_empty() {
+        return Err(js_error(
+            ErrorCode::PublicInputsInvalid,
+            format!("missing required fields: {}", missing.join(", ")),
+        ));
+    }
+
+    let public_inputs: VerifierPublicInputs =
+        serde_json::from_value(value["public_inputs"].clone())
+            .map_err(|e| js_error(ErrorCode::PublicInputsInvalid, e))?;
+    let note_values: Vec<u64> = serde_json::from_value(value["note_values"].clone())
+        .map_err(|e| js_error(ErrorCode::WitnessInvalid, e))?;
+    if note_values.len() > ORCHARD_MAX_NOTES {
+        return Err(js_error(
+            ErrorCode::WitnessInvalid,
+            format!(
+                "{} notes exceed the Orchard circuit limit of {ORCHARD_MAX_NOTES}",
+                note_values.len()
+            ),
+        ));
+    }
+
+    let holder_id: String = serde_json::from_value(value["holder_id"].clone())
+        .map_err(|e| js_error(ErrorCode::WitnessInvalid, format!("holder_id: {e}")))?;
+    let fvk: String = serde_json::from_value(value["fvk"].clone())
+        .map_err(|e| js_error(ErrorCode::WitnessInvalid, format!("fvk: {e}")))?;
+    let input = OrchardPofCircuitInput::native(public_inputs, note_values, &holder_id, &fvk);
+
+    Ok(OrchardCircuitInputWasm { input })
+}
+
+/// Generate an Orchard proof bundle for an `orchardInputFromJson` input using
+/// the cached Orchard artifacts.
+#[wasm_bindgen(js_name = generateOrchardProofBundleFromInput)]
+pub fn generate_orchard_proof_bundle_from_input(
+    input: &OrchardCircuitInputWasm,
+) -> Result<ProofBundleJs, JsValue> {
+    with_cached_orchard_prover(|params, pk, break_points| {
+        let bundle =
+            prove_orchard_bundle_with_structs(input.input.clone(), params, pk, break_points)?;
+        to_value(&bundle)
+            .map(JsCast::unchecked_into)
+            .map_err(|e| js_error(ErrorCode::InternalServerError, e))
     })
 }
 
@@ -782,17 +1171,17 @@ fn with_cached_orchard_prover<R>(
         let params = params_cell.borrow();
         let params_ref = params
             .as_ref()
-            .ok_or_else(|| js_error("Orchard params not initialized; call initOrchardProverArtifacts"))?;
+            .ok_or_else(|| js_error(ErrorCode::ArtifactNotFound, "Orchard params not initialized; call initOrchardProverArtifacts"))?;
         CACHED_ORCHARD_PK.with(|pk_cell| {
             let pk = pk_cell.borrow();
             let pk_ref = pk
                 .as_ref()
-                .ok_or_else(|| js_error("Orchard proving key not initialized; call initOrchardProverArtifacts"))?;
+                .ok_or_else(|| js_error(ErrorCode::ArtifactNotFound, "Orchard proving key not initialized; call initOrchardProverArtifacts"))?;
             CACHED_ORCHARD_BREAK_POINTS.with(|bp_cell| {
                 let bp = bp_cell.borrow();
                 let bp_ref = bp
                     .as_ref()
-                    .ok_or_else(|| js_error("Orchard break points not initialized; call initOrchardProverArtifacts with break_points_bytes"))?;
+                    .ok_or_else(|| js_error(ErrorCode::ArtifactNotFound, "Orchard break points not initialized; call initOrchardProverArtifacts with break_points_bytes"))?;
                 f(params_ref, pk_ref, bp_ref)
             })
         })
@@ -800,8 +1189,7 @@ fn with_cached_orchard_prover<R>(
 }
 
 fn prove_orchard_bundle_with_structs(
-    public_inputs: VerifierPublicInputs,
-    note_values: Vec<u64>,
+    circuit_input: OrchardPofCircuitInput,
     params: &ParamsWasm,
     pk: &OrchardProvingKeyWasm,
     break_points: &OrchardBreakPoints,
@@ -812,6 +1200,9 @@ fn prove_orchard_bundle_with_structs(
     };
     use rand::rngs::OsRng;
     
+    let public_inputs = circuit_input.public_inputs.clone();
+    let note_values = &circuit_input.note_values;
+
     // Log V2_ORCHARD public input fields
     web_sys::console::log_1(&"[ZKPF Orchard WASM] V2_ORCHARD Public Input Fields (10 columns):".into());
     web_sys::console::log_1(&format!(
@@ -889,12 +1280,6 @@ fn prove_orchard_bundle_with_structs(
         ORCHARD_DEFAULT_K, CIRCUIT_VERSION
     ).into());
     
-    // Build circuit input
-    let circuit_input = OrchardPofCircuitInput {
-        public_inputs: public_inputs.clone(),
-        note_values,
-    };
-    
     // Create circuit in prover mode WITH break points - this is the critical fix
     // Without break points, the prover panics with "break points not set"
     let circuit = OrchardPofCircuit::new_prover(circuit_input, break_points.clone());
@@ -903,7 +1288,12 @@ fn prove_orchard_bundle_with_structs(
     let instances = public_inputs_to_instances_with_layout(
         PublicInputLayout::V2Orchard,
         &public_inputs,
-    ).map_err(|e| js_error(format!("failed to convert public inputs: {}", e)))?;
+    ).map_err(|e| {
+        js_error(
+            ErrorCode::PublicInputsInvalid,
+            format!("failed to convert public inputs: {}", e),
+        )
+    })?;
     
     let instance_refs: Vec<&[Fr]> = instances.iter().map(|col| col.as_slice()).collect();
     
@@ -926,7 +1316,12 @@ fn prove_orchard_bundle_with_structs(
         OsRng,
         &mut transcript,
     )
-    .map_err(|e| js_error(format!("Orchard proof generation failed: {:?}", e)))?;
+    .map_err(|e| {
+        js_error(
+            ErrorCode::ProofGenerationFailed,
+            format!("Orchard proof generation failed: {:?}", e),
+        )
+    })?;
     
     let proof = transcript.finalize();
     
@@ -939,6 +1334,9 @@ fn prove_orchard_bundle_with_structs(
         rail_id: RAIL_ID_ZCASH_ORCHARD.to_string(),
         circuit_version: CIRCUIT_VERSION,
         proof,
+        proof_encoding: None,
+        issued_at: None,
+        issued_at_tag: None,
         public_inputs,
     })
 }
@@ -949,12 +1347,18 @@ fn with_cached_verifier<R>(
     CACHED_PARAMS.with(|params_cell| {
         let params = params_cell.borrow();
         let params_ref = params.as_ref().ok_or_else(|| {
-            js_error("verifier params not initialized; call initVerifierArtifacts")
+            js_error(
+                ErrorCode::ArtifactNotFound,
+                "verifier params not initialized; call initVerifierArtifacts",
+            )
         })?;
         CACHED_VK.with(|vk_cell| {
             let vk = vk_cell.borrow();
             let vk_ref = vk.as_ref().ok_or_else(|| {
-                js_error("verifying key not initialized; call initVerifierArtifacts")
+                js_error(
+                    ErrorCode::ArtifactNotFound,
+                    "verifying key not initialized; call initVerifierArtifacts",
+                )
             })?;
             f(params_ref, vk_ref)
         })
@@ -966,14 +1370,20 @@ fn with_cached_prover<R>(
 ) -> Result<R, JsValue> {
     CACHED_PARAMS.with(|params_cell| {
         let params = params_cell.borrow();
-        let params_ref = params
-            .as_ref()
-            .ok_or_else(|| js_error("prover params not initialized; call initProverArtifacts"))?;
+        let params_ref = params.as_ref().ok_or_else(|| {
+            js_error(
+                ErrorCode::ArtifactNotFound,
+                "prover params not initialized; call initProverArtifacts",
+            )
+        })?;
         CACHED_PK.with(|pk_cell| {
             let pk = pk_cell.borrow();
-            let pk_ref = pk
-                .as_ref()
-                .ok_or_else(|| js_error("proving key not initialized; call initProverArtifacts"))?;
+            let pk_ref = pk.as_ref().ok_or_else(|| {
+                js_error(
+                    ErrorCode::ArtifactNotFound,
+                    "proving key not initialized; call initProverArtifacts",
+                )
+            })?;
             f(params_ref, pk_ref)
         })
     })
@@ -981,23 +1391,31 @@ fn with_cached_prover<R>(
 
 fn parse_input(attestation_json: &str) -> Result<ZkpfCircuitInput, JsValue> {
     serde_json::from_str(attestation_json).map_err(|e| {
-        js_error(format!(
-            "Failed to parse attestation JSON: {}. \
-             Ensure the JSON has 'attestation' and 'public' objects with all required fields. \
-             Common issues: nullifier/custodian_pubkey_hash must be 64-character hex strings, \
-             pubkey x/y and signature r/s must be 32-element number arrays.",
-            e
-        ))
+        js_error(
+            ErrorCode::WitnessInvalid,
+            format!(
+                "Failed to parse attestation JSON: {}. \
+                 Ensure the JSON has 'attestation' and 'public' objects with all required fields. \
+                 Common issues: nullifier/custodian_pubkey_hash must be 64-character hex strings, \
+                 pubkey x/y and signature r/s must be 32-element number arrays.",
+                e
+            ),
+        )
     })
 }
 
-fn js_error(err: impl ToString) -> JsValue {
-    JsValue::from_str(&err.to_string())
+/// Errors cross the boundary as `"<ERROR_CODE>: <message>"` strings so callers
+/// can switch on the same codes the backend returns in `error_code`.
+fn js_error(code: ErrorCode, err: impl ToString) -> JsValue {
+    JsValue::from_str(&format!("{code}: {}", err.to_string()))
 }
 
 fn into_field_bytes(label: &str, bytes: &[u8]) -> Result<[u8; 32], JsValue> {
     if bytes.len() != 32 {
-        return Err(js_error(format!("{label} must be 32 bytes")));
+        return Err(js_error(
+            ErrorCode::PublicInputsInvalid,
+            format!("{label} must be 32 bytes"),
+        ));
     }
     let mut arr = [0u8; 32];
     arr.copy_from_slice(bytes);
@@ -1023,13 +1441,19 @@ fn fr_to_le_bytes(value: &Fr) -> [u8; 32] {
 
 fn fr_from_le_bytes(bytes: &[u8]) -> Result<Fr, JsValue> {
     if bytes.len() != 32 {
-        return Err(js_error("field elements must be 32 bytes"));
+        return Err(js_error(
+            ErrorCode::PublicInputsInvalid,
+            "field elements must be 32 bytes",
+        ));
     }
     let mut arr = [0u8; 32];
     arr.copy_from_slice(bytes);
-    Fr::from_bytes(&arr)
-        .into_option()
-        .ok_or_else(|| js_error("invalid field element encoding"))
+    Fr::from_bytes(&arr).into_option().ok_or_else(|| {
+        js_error(
+            ErrorCode::PublicInputsInvalid,
+            "invalid field element encoding",
+        )
+    })
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -3,9 +3,11 @@
 use wasm_bindgen_test::*;
 use zkpf_test_fixtures::fixtures;
 use zkpf_wasm::{
-    generate_proof, generate_proof_bundle, generate_proof_bundle_cached, init_prover_artifacts,
-    init_verifier_artifacts, reset_cached_artifacts, verify_proof, verify_proof_bundle,
-    verify_proof_bundle_cached,
+    cache_prover_artifacts, generate_proof, generate_proof_bundle, generate_proof_bundle_cached,
+    init_prover_artifacts, init_verifier_artifacts, orchard_input_from_json,
+    proof_bundle_from_compact_bytes, proof_bundle_to_compact_bytes, reset_cached_artifacts,
+    verify_proof, verify_proof_bundle, verify_proof_bundle_cached, ErrorCode, ParamsWasmBuilder,
+    ProvingKeyWasmBuilder, PublicInputsWasm,
 };
 
 #[wasm_bindgen_test]
@@ -49,4 +51,127 @@ fn wasm_round_trip_proof_generation() {
         verify_proof_bundle_cached(&cached_bundle).unwrap(),
         "cached verifier validates cached bundle"
     );
+
+    let compact = proof_bundle_to_compact_bytes(&cached_bundle, true).unwrap();
+    let restored = proof_bundle_from_compact_bytes(&compact).unwrap();
+    assert!(
+        verify_proof_bundle_cached(&restored).unwrap(),
+        "bundle restored from compact bytes still verifies"
+    );
+}
+
+#[wasm_bindgen_test]
+fn streamed_artifacts_match_whole_blobs() {
+    let fixtures = fixtures();
+    reset_cached_artifacts();
+    init_verifier_artifacts(fixtures.params_bytes(), fixtures.vk_bytes()).unwrap();
+
+    let mut params = ParamsWasmBuilder::new(Some(fixtures.params_bytes().len()));
+    for chunk in fixtures.params_bytes().chunks(64 * 1024) {
+        params.push_chunk(chunk);
+    }
+    assert_eq!(params.bytes_received(), fixtures.params_bytes().len());
+    let mut pk = ProvingKeyWasmBuilder::new(None);
+    for chunk in fixtures.pk_bytes().chunks(64 * 1024) {
+        pk.push_chunk(chunk);
+    }
+    let params = params.finalize().unwrap();
+    let pk = pk.finalize().unwrap();
+    assert_eq!(params.to_bytes(), fixtures.params_bytes());
+    cache_prover_artifacts(params, pk);
+
+    let bundle = generate_proof_bundle_cached(fixtures.attestation_json()).unwrap();
+    assert!(verify_proof_bundle_cached(&bundle).unwrap());
+
+    let mut truncated = ProvingKeyWasmBuilder::new(None);
+    truncated.push_chunk(&fixtures.pk_bytes()[..16]);
+    let err = truncated.finalize().err().and_then(|err| err.as_string());
+    assert!(err
+        .expect("string error")
+        .starts_with(&format!("{}: ", ErrorCode::ArtifactInvalid)));
+}
+
+#[wasm_bindgen_test]
+fn wasm_errors_carry_error_code() {
+    let err = PublicInputsWasm::from_json("not json")
+        .err()
+        .and_then(|err| err.as_string())
+        .expect("string error");
+    assert!(
+        err.starts_with(&format!("{}: ", ErrorCode::PublicInputsInvalid)),
+        "unexpected error: {err}"
+    );
+}
+
+#[wasm_bindgen_test]
+fn orchard_input_reports_missing_fields_and_note_limit() {
+    let err = orchard_input_from_json(r#"{ "public_inputs": { "threshold_raw": 1 } }"#)
+        .err()
+        .and_then(|err| err.as_string())
+        .expect("string error");
+    assert!(
+        err.starts_with(&format!("{}: ", ErrorCode::PublicInputsInvalid)),
+        "unexpected error: {err}"
+    );
+    for field in [
+        "public_inputs.policy_id",
+        "public_inputs.snapshot_anchor_orchard",
+        "note_values",
+        "holder_id",
+        "fvk",
+    ] {
+        assert!(err.contains(field), "{field} not reported: {err}");
+    }
+    assert!(!err.contains("public_inputs.threshold_raw"), "{err}");
+
+    let zero = [0u8; 32];
+    let public_inputs = serde_json::json!({
+        "threshold_raw": 1,
+        "required_currency_code": 999001,
+        "current_epoch": 1,
+        "verifier_scope_id": 1,
+        "policy_id": 1,
+        "nullifier": zero,
+        "custodian_pubkey_hash": zero,
+        "snapshot_block_height": 1,
+        "snapshot_anchor_orchard": zero,
+    });
+    let without_fvk = serde_json::json!({
+        "public_inputs": public_inputs,
+        "note_values": [5, 7],
+        "holder_id": "holder-123",
+    });
+    let err = orchard_input_from_json(&without_fvk.to_string())
+        .err()
+        .and_then(|err| err.as_string())
+        .expect("string error");
+    assert!(err.ends_with("missing required fields: fvk"), "{err}");
+
+    let input = orchard_input_from_json(
+        &serde_json::json!({
+            "public_inputs": public_inputs,
+            "note_values": [5, 7],
+            "holder_id": "holder-123",
+            "fvk": "uview-sample",
+        })
+        .to_string(),
+    )
+    .expect("valid input");
+    assert_eq!(input.note_count(), 2);
+
+    let notes = vec![1u64; 17];
+    let too_many = serde_json::json!({
+        "public_inputs": public_inputs,
+        "note_values": notes,
+        "holder_id": "holder-123",
+        "fvk": "uview-sample",
+    });
+    let err = orchard_input_from_json(&too_many.to_string())
+        .err()
+        .and_then(|err| err.as_string())
+        .expect("string error");
+    assert!(
+        err.starts_with(&format!("{}: ", ErrorCode::WitnessInvalid)),
+        "unexpected error: {err}"
+    );
 }
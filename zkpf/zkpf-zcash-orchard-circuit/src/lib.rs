@@ -6,6 +6,8 @@
 //!
 //! The current circuit focuses on:
 //! - enforcing that the sum of private Orchard note values is >= the public threshold,
+//!   counting only notes of the proven asset type (ZEC unless keys were generated
+//!   for a Zcash Shielded Asset),
 //! - exposing Orchard snapshot metadata (height, anchor, holder binding) as public inputs,
 //! - wiring into the shared `ProofBundle` / artifact tooling used by the backend.
 //!
@@ -51,14 +53,19 @@ use once_cell::sync::Lazy;
 use rand::rngs::OsRng;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
-use zkpf_circuit::gadgets::compare;
+use zkpf_circuit::{
+    gadgets::{compare, poseidon},
+    notes::{pad_note_witnesses, pad_notes},
+};
 use zkpf_common::{
-    deserialize_params, hash_bytes_hex, public_inputs_to_instances_with_layout, read_manifest,
+    artifact_base_dir, constant_time_eq, deserialize_params, fr_to_be_bytes, hash_bytes_hex,
+    holder_binding_poseidon, public_inputs_to_instances_with_layout, read_manifest,
     reduce_be_bytes_to_fr, ArtifactFile, ArtifactManifest, ProverArtifacts, PublicInputLayout,
-    VerifierArtifacts, VerifierPublicInputs, CIRCUIT_VERSION, MANIFEST_VERSION,
+    VerifierArtifacts, VerifierPublicInputs, VerifierPublicInputsBuilder, CIRCUIT_VERSION,
+    MANIFEST_VERSION,
 };
 use zkpf_orchard_inner::OrchardInnerPublicInputs;
-use zkpf_zcash_orchard_wallet::{OrchardFvk, OrchardSnapshot};
+use zkpf_zcash_orchard_wallet::{OrchardFvk, OrchardSnapshot, NATIVE_ASSET_TYPE};
 
 // Re-export the shared `ProofBundle` type so downstream crates (e.g. WASM
 // wrappers) can depend only on this crate for Orchard PoF bundles.
@@ -84,6 +91,69 @@ pub struct OrchardPublicMeta {
     pub anchor_orchard: [u8; 32],
     /// Holder binding, e.g. H(holder_id || fvk_bytes).
     pub holder_binding: [u8; 32],
+    /// Asset whose notes count towards the threshold; defaults to native ZEC.
+    #[serde(default)]
+    pub asset_type: [u8; 32],
+    /// Hash used to derive `holder_binding`. Poseidon is the only scheme; the
+    /// field is kept so metadata that names it still parses.
+    #[serde(default)]
+    pub holder_binding_scheme: HolderBindingScheme,
+    /// Accept an all-zero `anchor_orchard`, e.g. for local test chains. An
+    /// all-zero anchor is otherwise taken to be an unset field.
+    #[serde(default)]
+    pub allow_zero_anchor: bool,
+}
+
+impl OrchardPublicMeta {
+    /// Reject metadata that could never produce a useful proof: a zero
+    /// snapshot height, or an all-zero anchor unless `allow_zero_anchor` is set.
+    pub fn validate(&self) -> Result<(), OrchardRailError> {
+        if self.block_height == 0 {
+            return Err(OrchardRailError::InvalidInput(
+                "orchard_meta.block_height must be > 0".into(),
+            ));
+        }
+        if self.anchor_orchard == [0u8; 32] && !self.allow_zero_anchor {
+            return Err(OrchardRailError::InvalidInput(
+                "orchard_meta.anchor_orchard is all zeros (set allow_zero_anchor to accept it)"
+                    .into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Hash function used to bind a holder identifier to an Orchard FVK.
+///
+/// Up to circuit version 5 the binding was `BLAKE3(holder_id || "||" || fvk)`.
+/// The circuit cannot recompute that, so from version 6 the binding is
+/// constrained in-circuit and only Poseidon remains; `"blake3"` is rejected
+/// with a pointer to this change rather than producing unprovable inputs.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HolderBindingScheme {
+    /// [`holder_binding_poseidon`] over the BLAKE3-reduced holder ID and FVK,
+    /// which the bn256 circuit recomputes with its Poseidon gadget.
+    #[default]
+    Poseidon,
+}
+
+impl std::str::FromStr for HolderBindingScheme {
+    type Err = OrchardRailError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "poseidon" => Ok(HolderBindingScheme::Poseidon),
+            "blake3" => Err(OrchardRailError::InvalidInput(
+                "the BLAKE3 holder binding was removed in circuit version 6, which constrains \
+                 the binding in-circuit; use 'poseidon'"
+                    .into(),
+            )),
+            other => Err(OrchardRailError::InvalidInput(format!(
+                "unknown holder binding scheme '{other}' (expected 'poseidon')"
+            ))),
+        }
+    }
 }
 
 /// Aggregated error type for the Orchard rail circuit/prover wrapper.
@@ -97,6 +167,20 @@ pub enum OrchardRailError {
     #[error("invalid input: {0}")]
     InvalidInput(String),
 
+    /// None of the snapshot's notes are of the requested asset type.
+    #[error(
+        "snapshot has no notes of the requested asset type ({other_notes} notes of other assets)"
+    )]
+    AssetMismatch { other_notes: usize },
+
+    /// The note values of the requested asset add up to more than `u64::MAX`.
+    #[error("sum of Orchard note values overflows u64")]
+    SumOverflow,
+
+    /// The Orchard prover artifacts could not be loaded on this node.
+    #[error("Orchard artifacts not available: {0}")]
+    ArtifactsUnavailable(String),
+
     /// Placeholder while the actual circuit implementation is not yet wired.
     #[error("Orchard circuit not implemented")]
     NotImplemented,
@@ -121,15 +205,51 @@ pub struct PublicMetaInputs {
     pub required_currency_code: u32,
 }
 
+/// Earliest plausible `current_epoch`: Orchard activated with NU5 on 2022-05-31.
+pub const MIN_ORCHARD_EPOCH: u64 = 1_653_955_200;
+
+/// Latest plausible `current_epoch`. Epochs are Unix seconds; anything past
+/// 2106 is almost certainly milliseconds or another unit.
+pub const MAX_ORCHARD_EPOCH: u64 = u32::MAX as u64;
+
+impl PublicMetaInputs {
+    /// Reject an epoch that cannot be Unix seconds at or after Orchard activation.
+    pub fn validate(&self) -> Result<(), OrchardRailError> {
+        if !(MIN_ORCHARD_EPOCH..=MAX_ORCHARD_EPOCH).contains(&self.current_epoch) {
+            return Err(OrchardRailError::InvalidInput(format!(
+                "current_epoch {} is not a Unix timestamp in seconds between {} and {}",
+                self.current_epoch, MIN_ORCHARD_EPOCH, MAX_ORCHARD_EPOCH
+            )));
+        }
+        Ok(())
+    }
+}
+
 // === Orchard PoF Halo2 circuit ================================================================
 
 /// Circuit size parameter k for the Orchard PoF circuit (2^k rows).
 pub const ORCHARD_DEFAULT_K: usize = 19;
-const ORCHARD_DEFAULT_LOOKUP_BITS: usize = 18;
+/// 16 divides both the 64- and 32-bit field widths, so every range check in
+/// the circuit splits into whole lookup limbs (see [`range_check_each`]).
+const ORCHARD_DEFAULT_LOOKUP_BITS: usize = 16;
 const ORCHARD_DEFAULT_ADVICE_PER_PHASE: usize = 4;
 const ORCHARD_DEFAULT_FIXED_COLUMNS: usize = 1;
 const ORCHARD_DEFAULT_LOOKUP_ADVICE_PER_PHASE: usize = 1;
-const ORCHARD_MAX_NOTES: usize = 16;
+/// Most notes a single Orchard proof can sum; smaller sets are zero-padded.
+pub const ORCHARD_MAX_NOTES: usize = 16;
+/// Width of the in-circuit note sum. Sixteen 64-bit notes add up to less than
+/// 2^68; this rounds up to whole lookup limbs.
+const ORCHARD_SUM_BITS: usize = 80;
+const _: () = assert!(
+    64 % ORCHARD_DEFAULT_LOOKUP_BITS == 0
+        && 32 % ORCHARD_DEFAULT_LOOKUP_BITS == 0
+        && ORCHARD_SUM_BITS % ORCHARD_DEFAULT_LOOKUP_BITS == 0
+);
+/// First `CIRCUIT_VERSION` with the current Orchard layout: 16-bit lookups,
+/// asset-typed notes padded to `ORCHARD_MAX_NOTES`, a full-width note sum and
+/// the Poseidon holder binding. Artifacts from earlier versions cannot be
+/// loaded and must be regenerated.
+const ORCHARD_LAYOUT_VERSION: u32 = 6;
 
 fn orchard_default_params() -> BaseCircuitParams {
     BaseCircuitParams {
@@ -145,10 +265,54 @@ fn orchard_default_params() -> BaseCircuitParams {
 
 /// Private inputs to the Orchard PoF circuit: the public-input vector plus a bounded
 /// set of Orchard note values whose sum must exceed the threshold.
+///
+/// Only notes whose entry in `note_asset_types` equals `asset_type` are summed.
+/// `asset_type` is loaded as a circuit constant, so it is fixed by the proving
+/// and verifying keys: the published ZCASH_ORCHARD keys prove native ZEC only.
+///
+/// The circuit constrains the public `holder_binding` to equal
+/// `holder_binding_poseidon(holder_id, fvk)`, so a proof commits to the holder
+/// material rather than carrying the binding as free metadata.
 #[derive(Clone, Debug)]
 pub struct OrchardPofCircuitInput {
     pub public_inputs: VerifierPublicInputs,
     pub note_values: Vec<u64>,
+    /// Asset type of each note, parallel to `note_values`.
+    pub note_asset_types: Vec<[u8; 32]>,
+    /// Asset being proven.
+    pub asset_type: [u8; 32],
+    /// Holder identifier, reduced to a field element.
+    pub holder_id: Fr,
+    /// Encoded full viewing key, reduced to a field element.
+    pub fvk: Fr,
+}
+
+impl OrchardPofCircuitInput {
+    /// Input where every note is native ZEC, witnessing the holder ID and FVK
+    /// the public binding was derived from.
+    pub fn native(
+        public_inputs: VerifierPublicInputs,
+        note_values: Vec<u64>,
+        holder_id: &str,
+        fvk_encoded: &str,
+    ) -> Self {
+        let note_asset_types = vec![NATIVE_ASSET_TYPE; note_values.len()];
+        let (holder_id, fvk) = holder_witnesses(holder_id, fvk_encoded);
+        Self {
+            public_inputs,
+            note_values,
+            note_asset_types,
+            asset_type: NATIVE_ASSET_TYPE,
+            holder_id,
+            fvk,
+        }
+    }
+
+    /// Replace the holder witnesses.
+    pub fn with_holder(mut self, holder_id: &str, fvk_encoded: &str) -> Self {
+        (self.holder_id, self.fvk) = holder_witnesses(holder_id, fvk_encoded);
+        self
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -334,11 +498,11 @@ fn build_orchard_constraints(
     let ctx = builder.main(0);
 
     // Core public fields (V1 prefix)
-    let threshold = assign_u64(ctx, &range, pub_in.threshold_raw);
-    let req_currency = assign_u32(ctx, &range, pub_in.required_currency_code);
-    let current_epoch = assign_u64(ctx, &range, pub_in.current_epoch);
-    let verifier_scope = assign_u64(ctx, &range, pub_in.verifier_scope_id);
-    let policy_id = assign_u64(ctx, &range, pub_in.policy_id);
+    let threshold = ctx.load_witness(Fr::from(pub_in.threshold_raw));
+    let req_currency = ctx.load_witness(Fr::from(pub_in.required_currency_code as u64));
+    let current_epoch = ctx.load_witness(Fr::from(pub_in.current_epoch));
+    let verifier_scope = ctx.load_witness(Fr::from(pub_in.verifier_scope_id));
+    let policy_id = ctx.load_witness(Fr::from(pub_in.policy_id));
 
     // Nullifier and custodian_pubkey_hash are treated as opaque scalars; the rail
     // ensures their encoding via off-circuit hashing.
@@ -358,22 +522,63 @@ fn build_orchard_constraints(
         .ok_or(Error::Synthesis)?;
     let holder_binding_bytes = pub_in.holder_binding.ok_or(Error::Synthesis)?;
 
-    let snapshot_height_cell = assign_u64(ctx, &range, snapshot_height);
+    let snapshot_height_cell = ctx.load_witness(Fr::from(snapshot_height));
     let anchor_fr = reduce_be_bytes_to_fr(&snapshot_anchor_bytes);
     let holder_binding_fr = reduce_be_bytes_to_fr(&holder_binding_bytes);
     let anchor_cell = ctx.load_witness(anchor_fr);
     let holder_binding_cell = ctx.load_witness(holder_binding_fr);
 
-    // Sum Orchard note values and enforce Σ v_i >= threshold.
+    // The exposed binding must be the Poseidon hash of the witnessed holder
+    // material.
+    let holder_id_cell = ctx.load_witness(input.holder_id);
+    let fvk_cell = ctx.load_witness(input.fvk);
+    let derived_binding = poseidon::hash_elements(ctx, gate, &[holder_id_cell, fvk_cell]);
+    ctx.constrain_equal(&derived_binding, &holder_binding_cell);
+
+    if input.note_asset_types.len() != input.note_values.len() {
+        return Err(Error::Synthesis);
+    }
+    // Always load ORCHARD_MAX_NOTES notes so the layout, and with it the
+    // verifying key, does not depend on how many notes the wallet holds.
+    let note_values =
+        pad_notes(input.note_values.clone(), ORCHARD_MAX_NOTES).map_err(|_| Error::Synthesis)?;
+    let note_asset_types = pad_note_witnesses(input.note_asset_types.clone(), ORCHARD_MAX_NOTES)
+        .map_err(|_| Error::Synthesis)?;
+    let note_cells: Vec<_> = note_values
+        .iter()
+        .map(|value| ctx.load_witness(Fr::from(*value)))
+        .collect();
+    let note_asset_cells: Vec<_> = note_asset_types
+        .iter()
+        .map(|asset| ctx.load_witness(reduce_be_bytes_to_fr(asset)))
+        .collect();
+    let asset_type = ctx.load_constant(reduce_be_bytes_to_fr(&input.asset_type));
+
+    range_check_each(
+        ctx,
+        &range,
+        &[
+            threshold,
+            current_epoch,
+            verifier_scope,
+            policy_id,
+            snapshot_height_cell,
+        ],
+        64,
+    );
+    range_check_each(ctx, &range, &note_cells, 64);
+    range_check_each(ctx, &range, &[req_currency], 32);
+
+    // Sum the values of notes of the proven asset and enforce Σ v_i >= threshold.
+    // The sum can exceed 64 bits, so it is range-checked and compared at
+    // ORCHARD_SUM_BITS rather than with the 64-bit comparison.
     let mut sum = ctx.load_constant(Fr::zero());
-    for (idx, value) in input.note_values.iter().enumerate() {
-        if idx >= ORCHARD_MAX_NOTES {
-            return Err(Error::Synthesis);
-        }
-        let note_val = assign_u64(ctx, &range, *value);
-        sum = gate.add(ctx, sum, note_val);
+    for (note_val, note_asset) in note_cells.into_iter().zip(note_asset_cells) {
+        let is_asset = gate.is_equal(ctx, note_asset, asset_type);
+        sum = gate.mul_add(ctx, note_val, is_asset, sum);
     }
-    compare::enforce_geq(ctx, gate, &range, sum, threshold);
+    range_check_each(ctx, &range, &[sum], ORCHARD_SUM_BITS);
+    compare::enforce_geq_bits(ctx, gate, &range, sum, threshold, ORCHARD_SUM_BITS);
 
     // Expose all public inputs in the V2_ORCHARD order expected by
     // `public_inputs_to_instances_with_layout`.
@@ -396,16 +601,25 @@ fn build_orchard_constraints(
     Ok(())
 }
 
-fn assign_u64(ctx: &mut Halo2Context<Fr>, range: &RangeChip<Fr>, value: u64) -> AssignedValue<Fr> {
-    let cell = ctx.load_witness(Fr::from(value));
-    range.range_check(ctx, cell, 64);
-    cell
-}
-
-fn assign_u32(ctx: &mut Halo2Context<Fr>, range: &RangeChip<Fr>, value: u32) -> AssignedValue<Fr> {
-    let cell = ctx.load_witness(Fr::from(value as u64));
-    range.range_check(ctx, cell, 32);
-    cell
+/// Range-check each cell to `bits` bits, one `range_check` per cell.
+///
+/// The saving comes from the lookup width rather than from batching: with
+/// `bits` a multiple of it, each value decomposes into whole lookup limbs and
+/// `range_check` skips its remainder constraint on the top limb, saving one
+/// `mul` (4 advice cells) and one lookup per value.
+///
+/// The values are deliberately not packed into a single wider check: a bound
+/// on `a + 2^bits * b` does not bound `a` and `b` individually, since a
+/// too-large `a` can be offset by a smaller `b`.
+fn range_check_each(
+    ctx: &mut Halo2Context<Fr>,
+    range: &RangeChip<Fr>,
+    cells: &[AssignedValue<Fr>],
+    bits: usize,
+) {
+    for cell in cells {
+        range.range_check(ctx, *cell, bits);
+    }
 }
 
 fn expose_orchard_public_inputs(
@@ -423,8 +637,14 @@ fn expose_orchard_public_inputs(
 /// without regenerating the full keygen artifacts.
 ///
 /// For the Orchard circuit, break_points are deterministic based on the
-/// circuit structure. The circuit has ~136 advice cells in phase 0, which
-/// fits in a single column for any k >= 10.
+/// circuit structure. Every note slot up to `ORCHARD_MAX_NOTES` is loaded, so
+/// the circuit has a few hundred advice cells in phase 0 regardless of the
+/// wallet, which fits in a single column for any k >= 10.
+///
+/// Break points from artifacts generated with the older 18-bit lookup table,
+/// before notes carried an asset type, or before note sets were padded to a
+/// fixed width no longer match the circuit layout; regenerate them with this
+/// function.
 ///
 /// # Arguments
 /// * `k` - Circuit size parameter (2^k rows). Should match the k used for keygen.
@@ -432,25 +652,8 @@ fn expose_orchard_public_inputs(
 /// # Returns
 /// Break points needed for proof generation.
 pub fn compute_break_points_for_k(k: u32) -> Result<MultiPhaseThreadBreakPoints> {
-    use zkpf_common::VerifierPublicInputs;
-    
     // Create sample input for computing break points
-    let sample_input = OrchardPofCircuitInput {
-        public_inputs: VerifierPublicInputs {
-            threshold_raw: 0,
-            required_currency_code: 0,
-            current_epoch: 0,
-            verifier_scope_id: 0,
-            policy_id: 0,
-            nullifier: [0u8; 32],
-            custodian_pubkey_hash: [0u8; 32],
-            snapshot_block_height: Some(0),
-            snapshot_anchor_orchard: Some([0u8; 32]),
-            holder_binding: Some([0u8; 32]),
-            proven_sum: None,
-        },
-        note_values: vec![100u64],
-    };
+    let sample_input = keygen_sample_input();
     
     // Create circuit params with the specified k
     let mut params = orchard_default_params();
@@ -479,7 +682,7 @@ pub fn compute_break_points_for_k(k: u32) -> Result<MultiPhaseThreadBreakPoints>
     // - Phase 0: One main thread with all the constraint operations
     // - The thread ends at the total number of advice cells
     // 
-    // Since we have ~136 cells and 2^k rows (k >= 10), everything fits
+    // Since we have ~110 cells and 2^k rows (k >= 10), everything fits
     // in a single column. The break_point is where the thread ends.
     //
     // Format: Vec<Vec<usize>> where break_points[phase][column] = end_row
@@ -556,25 +759,20 @@ pub fn build_verifier_public_inputs(
     nullifier: [u8; 32],
     custodian_pubkey_hash: [u8; 32],
 ) -> VerifierPublicInputs {
-    let mut inputs = VerifierPublicInputs {
-        threshold_raw: threshold_zats,
-        required_currency_code: meta.required_currency_code,
-        current_epoch: meta.current_epoch,
-        verifier_scope_id: meta.verifier_scope_id,
-        policy_id: meta.policy_id,
-        nullifier,
-        custodian_pubkey_hash,
-        snapshot_block_height: None,
-        snapshot_anchor_orchard: None,
-        holder_binding: None,
-        proven_sum: None,
-    };
-
-    inputs.snapshot_block_height = Some(orchard_meta.block_height as u64);
-    inputs.snapshot_anchor_orchard = Some(orchard_meta.anchor_orchard);
-    inputs.holder_binding = Some(orchard_meta.holder_binding);
-
-    inputs
+    VerifierPublicInputsBuilder::for_orchard(
+        orchard_meta.block_height as u64,
+        orchard_meta.anchor_orchard,
+        orchard_meta.holder_binding,
+    )
+    .threshold_raw(threshold_zats)
+    .required_currency_code(meta.required_currency_code)
+    .current_epoch(meta.current_epoch)
+    .verifier_scope_id(meta.verifier_scope_id)
+    .policy_id(meta.policy_id)
+    .nullifier(nullifier)
+    .custodian_pubkey_hash(custodian_pubkey_hash)
+    .build()
+    .expect("every V2_ORCHARD field is set")
 }
 
 /// Map the public inputs of the **inner** Orchard PoF circuit into the
@@ -595,19 +793,20 @@ pub fn map_inner_to_verifier_public_inputs(
     custodian_pubkey_hash: [u8; 32],
     holder_binding: [u8; 32],
 ) -> VerifierPublicInputs {
-    VerifierPublicInputs {
-        threshold_raw: inner.threshold_zats,
-        required_currency_code: meta.required_currency_code,
-        current_epoch: meta.current_epoch,
-        verifier_scope_id: meta.verifier_scope_id,
-        policy_id: meta.policy_id,
-        nullifier,
-        custodian_pubkey_hash,
-        snapshot_block_height: Some(inner.height as u64),
-        snapshot_anchor_orchard: Some(inner.anchor_orchard),
-        holder_binding: Some(holder_binding),
-        proven_sum: None,
-    }
+    VerifierPublicInputsBuilder::for_orchard(
+        inner.height as u64,
+        inner.anchor_orchard,
+        holder_binding,
+    )
+    .threshold_raw(inner.threshold_zats)
+    .required_currency_code(meta.required_currency_code)
+    .current_epoch(meta.current_epoch)
+    .verifier_scope_id(meta.verifier_scope_id)
+    .policy_id(meta.policy_id)
+    .nullifier(nullifier)
+    .custodian_pubkey_hash(custodian_pubkey_hash)
+    .build()
+    .expect("every V2_ORCHARD field is set")
 }
 
 /// High-level entrypoint that the prover rail calls to generate a `ProofBundle` for
@@ -629,6 +828,9 @@ pub fn prove_orchard_pof(
     orchard_meta: &OrchardPublicMeta,
     meta: &PublicMetaInputs,
 ) -> Result<ProofBundle, OrchardRailError> {
+    orchard_meta.validate()?;
+    meta.validate()?;
+
     if snapshot.notes.is_empty() {
         return Err(OrchardRailError::InvalidInput(
             "no Orchard notes discovered for this FVK at the requested height".into(),
@@ -649,8 +851,8 @@ pub fn prove_orchard_pof(
         )));
     }
 
-    // Enforce Σ v_i ≥ threshold_zats based on the snapshot notes.
-    let total_zats: u64 = snapshot.notes.iter().map(|n| n.value_zats).sum();
+    // Enforce Σ v_i ≥ threshold_zats over the snapshot notes of the requested asset.
+    let total_zats = asset_note_total(snapshot, &orchard_meta.asset_type)?;
     if total_zats < threshold_zats {
         return Err(OrchardRailError::InvalidInput(format!(
             "insufficient Orchard funds: total_zats {} < threshold_zats {}",
@@ -658,8 +860,8 @@ pub fn prove_orchard_pof(
         )));
     }
 
-    // Compute a simple holder binding H(holder_id || fvk_bytes) using BLAKE3.
     let holder_binding = compute_holder_binding(holder_id, &fvk.encoded);
+    let (holder_id_fr, fvk_fr) = holder_witnesses(holder_id, &fvk.encoded);
 
     // Derive a PoF nullifier that mixes the binding with the policy/scope/epoch tuple.
     let nullifier = compute_pof_nullifier(
@@ -688,6 +890,10 @@ pub fn prove_orchard_pof(
     let circuit_input = OrchardPofCircuitInput {
         public_inputs: public_inputs.clone(),
         note_values: snapshot.notes.iter().map(|n| n.value_zats).collect(),
+        note_asset_types: snapshot.notes.iter().map(|n| n.asset_type).collect(),
+        asset_type: orchard_meta.asset_type,
+        holder_id: holder_id_fr,
+        fvk: fvk_fr,
     };
 
     let (proof, _) = create_orchard_proof_with_public_inputs(&circuit_input)?;
@@ -696,12 +902,41 @@ pub fn prove_orchard_pof(
         rail_id: RAIL_ID_ZCASH_ORCHARD.to_string(),
         circuit_version: CIRCUIT_VERSION,
         proof,
+        proof_encoding: None,
+        issued_at: None,
+        issued_at_tag: None,
         public_inputs,
     };
 
     Ok(bundle)
 }
 
+/// Sum of the snapshot's note values of `asset_type`.
+///
+/// Notes of other assets are ignored, but a snapshot with no notes of the
+/// requested asset at all is rejected as an asset mismatch, and a total that
+/// does not fit in a `u64` as [`OrchardRailError::SumOverflow`].
+fn asset_note_total(
+    snapshot: &OrchardSnapshot,
+    asset_type: &[u8; 32],
+) -> Result<u64, OrchardRailError> {
+    let matching: Vec<u64> = snapshot
+        .notes
+        .iter()
+        .filter(|note| &note.asset_type == asset_type)
+        .map(|note| note.value_zats)
+        .collect();
+    if matching.is_empty() {
+        return Err(OrchardRailError::AssetMismatch {
+            other_notes: snapshot.notes.len(),
+        });
+    }
+    matching
+        .iter()
+        .try_fold(0u64, |total, value| total.checked_add(*value))
+        .ok_or(OrchardRailError::SumOverflow)
+}
+
 // === Orchard keygen for artifact generation ====================================================
 
 use halo2_proofs_axiom::poly::kzg::commitment::ParamsKZG;
@@ -719,13 +954,83 @@ pub struct OrchardKeygenResult {
     pub break_points: MultiPhaseThreadBreakPoints,
 }
 
+/// Doublings of the row count kept free above the smallest `k` that fits, so
+/// a modest constraint change does not immediately outgrow the keys.
+pub const ORCHARD_K_HEADROOM: u32 = 1;
+/// Rows at the bottom of every column that halo2 reserves for blinding.
+const ORCHARD_RESERVED_ROWS: usize = 10;
+
+/// Placeholder input for keygen and break-point computation. Only the circuit
+/// shape matters, and it needs at least one note.
+fn keygen_sample_input() -> OrchardPofCircuitInput {
+    OrchardPofCircuitInput::native(
+        VerifierPublicInputs {
+            threshold_raw: 0,
+            required_currency_code: 0,
+            current_epoch: 0,
+            verifier_scope_id: 0,
+            policy_id: 0,
+            nullifier: [0u8; 32],
+            custodian_pubkey_hash: [0u8; 32],
+            snapshot_block_height: Some(0),
+            snapshot_anchor_orchard: Some([0u8; 32]),
+            holder_binding: Some([0u8; 32]),
+            threshold_max: None,
+            proven_sum: None,
+            challenge: None,
+            extra_nullifiers: None,
+        },
+        vec![100u64],
+        "",
+        "",
+    )
+}
+
+/// Smallest `k` for [`orchard_keygen`] that fits the circuit, plus
+/// [`ORCHARD_K_HEADROOM`].
+///
+/// Lays the constraints out in keygen mode and sizes the advice and lookup
+/// cells from `builder.statistics()` against the configured column counts.
+/// The lookup table needs `2^lookup_bits` rows, so the result is never below
+/// `lookup_bits + 1`. A keygen `k` below this fails with an opaque
+/// "not enough rows" error rather than naming the circuit as too large.
+pub fn recommended_k_for_orchard() -> u32 {
+    let params = orchard_default_params();
+    let mut builder = BaseCircuitBuilder::<Fr>::from_stage(CircuitBuilderStage::Keygen)
+        .use_params(params.clone())
+        .use_instance_columns(params.num_instance_columns);
+    if let Some(bits) = params.lookup_bits {
+        builder = builder.use_lookup_bits(bits);
+    }
+    build_orchard_constraints(&mut builder, &keygen_sample_input())
+        .expect("failed to build orchard constraints for k estimation");
+
+    let stats = builder.statistics();
+    let advice_cells: usize = stats.gate.total_advice_per_phase.iter().sum();
+    let lookup_cells: usize = stats.total_lookup_advice_per_phase.iter().sum();
+    let advice_columns: usize = params.num_advice_per_phase.iter().sum();
+    let lookup_columns: usize = params.num_lookup_advice_per_phase.iter().sum();
+    let fits = |k: usize| {
+        let rows = (1usize << k) - ORCHARD_RESERVED_ROWS;
+        advice_cells.div_ceil(rows) <= advice_columns
+            && lookup_cells.div_ceil(rows) <= lookup_columns
+    };
+
+    let mut k = params.lookup_bits.map_or(4, |bits| bits + 1);
+    while !fits(k) {
+        k += 1;
+    }
+    k as u32 + ORCHARD_K_HEADROOM
+}
+
 /// Generate proving and verifying keys for the Orchard PoF circuit.
 ///
 /// This function creates new KZG parameters and keys for the OrchardPofCircuit.
 /// The resulting artifacts can be serialized and used for production proving/verification.
 ///
 /// # Arguments
-/// * `k` - Circuit size parameter (2^k rows). Default is 19.
+/// * `k` - Circuit size parameter (2^k rows). Use [`recommended_k_for_orchard`]
+///   unless the artifacts must match an existing `k`.
 ///
 /// # Important
 /// The returned `break_points` MUST be serialized and stored alongside the proving key.
@@ -734,23 +1039,9 @@ pub fn orchard_keygen(k: u32) -> OrchardKeygenResult {
     // Generate KZG parameters
     let params = ParamsKZG::<Bn256>::setup(k, OsRng);
     
-    // Create a sample circuit input for keygen (values don't matter, just structure)
-    let sample_input = OrchardPofCircuitInput {
-        public_inputs: VerifierPublicInputs {
-            threshold_raw: 0,
-            required_currency_code: 0,
-            current_epoch: 0,
-            verifier_scope_id: 0,
-            policy_id: 0,
-            nullifier: [0u8; 32],
-            custodian_pubkey_hash: [0u8; 32],
-            snapshot_block_height: Some(0),
-            snapshot_anchor_orchard: Some([0u8; 32]),
-            holder_binding: Some([0u8; 32]),
-            proven_sum: None,
-        },
-        note_values: vec![100u64], // At least one note for the circuit
-    };
+    // Create a sample circuit input for keygen (values don't matter, just structure;
+    // at least one note for the circuit)
+    let sample_input = keygen_sample_input();
     
     // Create circuit in keygen mode
     let circuit = OrchardPofCircuit::new(Some(sample_input.clone()));
@@ -785,10 +1076,318 @@ mod tests {
                     siblings: vec![[3u8; 32]; 4],
                     position: 0,
                 },
+                asset_type: NATIVE_ASSET_TYPE,
             }],
         }
     }
 
+    fn sample_orchard_meta(snapshot: &OrchardSnapshot) -> OrchardPublicMeta {
+        OrchardPublicMeta {
+            chain_id: "ZEC".to_string(),
+            pool_id: "ORCHARD".to_string(),
+            block_height: snapshot.height,
+            anchor_orchard: snapshot.anchor,
+            holder_binding: [0u8; 32],
+            asset_type: NATIVE_ASSET_TYPE,
+            holder_binding_scheme: HolderBindingScheme::default(),
+            allow_zero_anchor: false,
+        }
+    }
+
+    fn sample_public_meta() -> PublicMetaInputs {
+        PublicMetaInputs {
+            policy_id: 42,
+            verifier_scope_id: 7,
+            current_epoch: 1_700_000_000,
+            required_currency_code: 1337,
+        }
+    }
+
+    fn invalid_input(result: Result<(), OrchardRailError>) -> String {
+        match result {
+            Err(OrchardRailError::InvalidInput(msg)) => msg,
+            other => panic!("expected InvalidInput, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn orchard_meta_validation_rejects_zero_height_and_anchor() {
+        let snapshot = sample_snapshot();
+        assert!(sample_orchard_meta(&snapshot).validate().is_ok());
+
+        let mut meta = sample_orchard_meta(&snapshot);
+        meta.block_height = 0;
+        assert!(invalid_input(meta.validate()).contains("block_height"));
+
+        let mut meta = sample_orchard_meta(&snapshot);
+        meta.anchor_orchard = [0u8; 32];
+        assert!(invalid_input(meta.validate()).contains("anchor_orchard"));
+        meta.allow_zero_anchor = true;
+        assert!(meta.validate().is_ok());
+    }
+
+    #[test]
+    fn public_meta_validation_rejects_implausible_epochs() {
+        assert!(sample_public_meta().validate().is_ok());
+
+        for epoch in [0, MIN_ORCHARD_EPOCH - 1, 1_700_000_000_000] {
+            let mut meta = sample_public_meta();
+            meta.current_epoch = epoch;
+            assert!(invalid_input(meta.validate()).contains("current_epoch"));
+        }
+    }
+
+    #[test]
+    fn meta_structs_round_trip_through_serde() {
+        let orchard_meta = sample_orchard_meta(&sample_snapshot());
+        let json = serde_json::to_value(&orchard_meta).unwrap();
+        let decoded: OrchardPublicMeta = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&decoded).unwrap(), json);
+
+        // Clients that predate `allow_zero_anchor` still decode, with it off.
+        let mut legacy = json;
+        legacy.as_object_mut().unwrap().remove("allow_zero_anchor");
+        let decoded: OrchardPublicMeta = serde_json::from_value(legacy).unwrap();
+        assert!(!decoded.allow_zero_anchor);
+
+        let public_meta = sample_public_meta();
+        let json = serde_json::to_value(&public_meta).unwrap();
+        let decoded: PublicMetaInputs = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&decoded).unwrap(), json);
+    }
+
+    #[test]
+    fn prove_orchard_pof_validates_meta_before_proving() {
+        let snapshot = sample_snapshot();
+        let mut public_meta = sample_public_meta();
+        public_meta.current_epoch = 0;
+
+        let err = prove_orchard_pof(
+            &snapshot,
+            &OrchardFvk {
+                encoded: "uview-sample".to_string(),
+            },
+            &"holder-123".to_string(),
+            1_000_000,
+            &sample_orchard_meta(&snapshot),
+            &public_meta,
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, OrchardRailError::InvalidInput(msg) if msg.contains("current_epoch"))
+        );
+    }
+
+    fn sample_input(threshold_raw: u64, notes: &[(u64, [u8; 32])]) -> OrchardPofCircuitInput {
+        let holder_binding = compute_holder_binding("holder-123", "uview-sample");
+        let public_inputs = VerifierPublicInputs {
+            threshold_raw,
+            required_currency_code: 1337,
+            current_epoch: 1_700_000_000,
+            verifier_scope_id: 7,
+            policy_id: 42,
+            nullifier: [0u8; 32],
+            custodian_pubkey_hash: [0u8; 32],
+            snapshot_block_height: Some(123_456),
+            snapshot_anchor_orchard: Some([1u8; 32]),
+            holder_binding: Some(holder_binding),
+            threshold_max: None,
+            proven_sum: None,
+            challenge: None,
+            extra_nullifiers: None,
+        };
+        let (holder_id, fvk) = holder_witnesses("holder-123", "uview-sample");
+        OrchardPofCircuitInput {
+            public_inputs,
+            note_values: notes.iter().map(|(value, _)| *value).collect(),
+            note_asset_types: notes.iter().map(|(_, asset)| *asset).collect(),
+            asset_type: NATIVE_ASSET_TYPE,
+            holder_id,
+            fvk,
+        }
+    }
+
+    fn mock_prove_input(input: OrchardPofCircuitInput) -> Result<(), Vec<String>> {
+        use halo2_proofs_axiom::dev::MockProver;
+
+        let instances = public_inputs_to_instances_with_layout(
+            PublicInputLayout::V2Orchard,
+            &input.public_inputs,
+        )
+        .expect("instances");
+        MockProver::run(
+            ORCHARD_DEFAULT_K as u32,
+            &OrchardPofCircuit::new(Some(input)),
+            instances,
+        )
+        .expect("mock prover run")
+        .verify()
+        .map_err(|failures| failures.iter().map(|f| f.to_string()).collect())
+    }
+
+    fn mock_prove(threshold_raw: u64, notes: &[(u64, [u8; 32])]) -> Result<(), Vec<String>> {
+        mock_prove_input(sample_input(threshold_raw, notes))
+    }
+
+    #[test]
+    fn circuit_enforces_holder_binding() {
+        let notes = [(5_000_000, NATIVE_ASSET_TYPE)];
+        assert!(mock_prove(5_000_000, &notes).is_ok());
+
+        // A binding derived from other holder material must not verify, even
+        // though it is a well-formed Poseidon binding.
+        let mut input = sample_input(5_000_000, &notes);
+        input.public_inputs.holder_binding =
+            Some(compute_holder_binding("holder-456", "uview-sample"));
+        assert!(mock_prove_input(input).is_err());
+
+        // Nor may the witnesses be swapped for ones matching no public binding.
+        let input = sample_input(5_000_000, &notes).with_holder("holder-123", "uview-other");
+        assert!(mock_prove_input(input).is_err());
+    }
+
+    #[test]
+    fn circuit_sums_only_notes_of_proven_asset() {
+        let zsa = [9u8; 32];
+        let notes = [(5_000_000, NATIVE_ASSET_TYPE), (7_000_000, zsa)];
+
+        assert!(mock_prove(5_000_000, &notes).is_ok());
+        assert!(mock_prove(5_000_001, &notes).is_err());
+    }
+
+    #[test]
+    fn circuit_accepts_full_and_sparse_note_sets() {
+        let full = vec![(1_000_000, NATIVE_ASSET_TYPE); ORCHARD_MAX_NOTES];
+
+        assert!(mock_prove(16_000_000, &full).is_ok());
+        assert!(mock_prove(1_000_000, &full[..1]).is_ok());
+    }
+
+    #[test]
+    fn circuit_compares_sums_wider_than_64_bits() {
+        // Sixteen near-max notes sum to almost 2^68; the comparison must not
+        // wrap or truncate that to 64 bits.
+        let full = vec![(u64::MAX, NATIVE_ASSET_TYPE); ORCHARD_MAX_NOTES];
+        assert!(mock_prove(u64::MAX, &full).is_ok());
+
+        let single = [(u64::MAX - 1, NATIVE_ASSET_TYPE)];
+        assert!(mock_prove(u64::MAX, &single).is_err());
+    }
+
+    #[test]
+    fn asset_note_total_rejects_overflowing_sums() {
+        let mut snapshot = sample_snapshot();
+        snapshot.notes[0].value_zats = u64::MAX;
+        snapshot.notes.push(snapshot.notes[0].clone());
+
+        assert!(matches!(
+            asset_note_total(&snapshot, &NATIVE_ASSET_TYPE),
+            Err(OrchardRailError::SumOverflow)
+        ));
+    }
+
+    #[test]
+    fn poseidon_holder_binding_is_a_canonical_field_element() {
+        let poseidon = compute_holder_binding("holder-123", "uview-sample");
+
+        // The circuit loads the binding with `reduce_be_bytes_to_fr`; a Poseidon
+        // binding must survive that without reduction.
+        assert_eq!(fr_to_be_bytes(&reduce_be_bytes_to_fr(&poseidon)), poseidon);
+
+        assert_eq!(
+            "Poseidon".parse::<HolderBindingScheme>().unwrap(),
+            HolderBindingScheme::Poseidon
+        );
+        assert!("sha256".parse::<HolderBindingScheme>().is_err());
+        let err = "blake3".parse::<HolderBindingScheme>().unwrap_err();
+        assert!(err.to_string().contains("circuit version 6"));
+    }
+
+    #[test]
+    fn prove_orchard_pof_rejects_snapshot_without_requested_asset() {
+        let mut snapshot = sample_snapshot();
+        snapshot.notes[0].asset_type = [9u8; 32];
+        let orchard_meta = sample_orchard_meta(&snapshot);
+        let public_meta = PublicMetaInputs {
+            policy_id: 42,
+            verifier_scope_id: 7,
+            current_epoch: 1_700_000_000,
+            required_currency_code: 1337,
+        };
+
+        let err = prove_orchard_pof(
+            &snapshot,
+            &OrchardFvk {
+                encoded: "uview-sample".to_string(),
+            },
+            &"holder-123".to_string(),
+            1_000_000,
+            &orchard_meta,
+            &public_meta,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            OrchardRailError::AssetMismatch { other_notes: 1 }
+        ));
+    }
+
+    #[test]
+    fn recommended_k_covers_lookup_table_and_configured_k_fits() {
+        let k = recommended_k_for_orchard();
+        assert!(k >= ORCHARD_DEFAULT_LOOKUP_BITS as u32 + 1 + ORCHARD_K_HEADROOM);
+        assert!(k <= ORCHARD_DEFAULT_K as u32);
+    }
+
+    /// `(advice, lookup advice)` cells of the keygen circuit with a
+    /// `lookup_bits`-wide table.
+    fn cell_counts(lookup_bits: usize) -> (usize, usize) {
+        let mut params = orchard_default_params();
+        params.lookup_bits = Some(lookup_bits);
+        let mut builder = BaseCircuitBuilder::<Fr>::from_stage(CircuitBuilderStage::Keygen)
+            .use_params(params.clone())
+            .use_instance_columns(params.num_instance_columns)
+            .use_lookup_bits(lookup_bits);
+        build_orchard_constraints(&mut builder, &keygen_sample_input()).unwrap();
+        let stats = builder.statistics();
+        (
+            stats.gate.total_advice_per_phase[0],
+            stats.total_lookup_advice_per_phase[0],
+        )
+    }
+
+    #[test]
+    fn whole_limb_lookup_table_uses_fewer_cells_than_the_18_bit_table() {
+        let (advice_18, lookups_18) = cell_counts(18);
+        let (advice, lookups) = cell_counts(ORCHARD_DEFAULT_LOOKUP_BITS);
+        assert!(advice < advice_18, "advice {advice} vs {advice_18}");
+        assert!(lookups < lookups_18, "lookups {lookups} vs {lookups_18}");
+    }
+
+    #[test]
+    fn manifests_from_before_the_layout_change_are_rejected() {
+        let file = ArtifactFile {
+            path: String::new(),
+            blake3: String::new(),
+            size: 0,
+        };
+        let mut manifest = ArtifactManifest {
+            manifest_version: MANIFEST_VERSION,
+            circuit_version: ORCHARD_LAYOUT_VERSION - 1,
+            k: ORCHARD_DEFAULT_K as u32,
+            created_at_unix: 0,
+            params: file.clone(),
+            vk: file.clone(),
+            pk: file,
+            num_instance_columns: None,
+        };
+        let err = ensure_manifest_compat_orchard(&manifest).unwrap_err();
+        assert!(err.to_string().contains("break_points.json"));
+
+        manifest.circuit_version = CIRCUIT_VERSION;
+        ensure_manifest_compat_orchard(&manifest).unwrap();
+    }
+
     #[test]
     fn prove_orchard_pof_builds_public_inputs_and_bundle() {
         let snapshot = sample_snapshot();
@@ -797,13 +1396,7 @@ mod tests {
         };
         let holder_id = "holder-123".to_string();
         let threshold_zats = 1_000_000;
-        let orchard_meta = OrchardPublicMeta {
-            chain_id: "ZEC".to_string(),
-            pool_id: "ORCHARD".to_string(),
-            block_height: snapshot.height,
-            anchor_orchard: snapshot.anchor,
-            holder_binding: [0u8; 32],
-        };
+        let orchard_meta = sample_orchard_meta(&snapshot);
         let public_meta = PublicMetaInputs {
             policy_id: 42,
             verifier_scope_id: 7,
@@ -837,12 +1430,26 @@ mod tests {
 }
 
 fn compute_holder_binding(holder_id: &str, fvk_encoded: &str) -> [u8; 32] {
+    let (holder_id_fr, fvk_fr) = holder_witnesses(holder_id, fvk_encoded);
+    // Big-endian, so the circuit's `reduce_be_bytes_to_fr` recovers the
+    // Poseidon output exactly.
+    fr_to_be_bytes(&holder_binding_poseidon(holder_id_fr, fvk_fr))
+}
+
+/// Field-element forms of the holder ID and FVK that the Poseidon binding hashes.
+fn holder_witnesses(holder_id: &str, fvk_encoded: &str) -> (Fr, Fr) {
+    (
+        hash_to_fr(b"zkpf_orchard_holder_id_v1", holder_id.as_bytes()),
+        hash_to_fr(b"zkpf_orchard_fvk_v1", fvk_encoded.as_bytes()),
+    )
+}
+
+/// Map an arbitrary-length input into `Fr` via a domain-separated BLAKE3 hash.
+fn hash_to_fr(domain: &[u8], input: &[u8]) -> Fr {
     let mut hasher = Hasher::new();
-    hasher.update(holder_id.as_bytes());
-    hasher.update(b"||");
-    hasher.update(fvk_encoded.as_bytes());
-    let hash = hasher.finalize();
-    *hash.as_bytes()
+    hasher.update(domain);
+    hasher.update(input);
+    reduce_be_bytes_to_fr(hasher.finalize().as_bytes())
 }
 
 fn compute_pof_nullifier(
@@ -872,8 +1479,25 @@ struct OrchardNativeArtifacts {
     break_points: MultiPhaseThreadBreakPoints,
 }
 
-static ORCHARD_PROVER_ARTIFACTS: Lazy<Arc<OrchardNativeArtifacts>> =
-    Lazy::new(|| Arc::new(load_orchard_prover_artifacts().expect("load Orchard prover artifacts")));
+/// Loaded on first use. A load failure is kept (as its message) so that every
+/// proving attempt on a node without Orchard artifacts fails cleanly instead of
+/// panicking.
+static ORCHARD_PROVER_ARTIFACTS: Lazy<Result<Arc<OrchardNativeArtifacts>, String>> =
+    Lazy::new(|| {
+        load_orchard_prover_artifacts()
+            .map(Arc::new)
+            .map_err(|err| {
+                eprintln!("⚠️ Orchard prover artifacts unavailable: {err:#}");
+                format!("{err:#}")
+            })
+    });
+
+fn orchard_prover_artifacts() -> Result<Arc<OrchardNativeArtifacts>, OrchardRailError> {
+    ORCHARD_PROVER_ARTIFACTS
+        .as_ref()
+        .map(Arc::clone)
+        .map_err(|err| OrchardRailError::ArtifactsUnavailable(err.clone()))
+}
 
 fn orchard_manifest_path() -> PathBuf {
     std::env::var(ORCHARD_MANIFEST_ENV)
@@ -883,13 +1507,18 @@ fn orchard_manifest_path() -> PathBuf {
 
 fn load_orchard_prover_artifacts() -> Result<OrchardNativeArtifacts> {
     let manifest_path = orchard_manifest_path();
-    let (manifest, params_bytes, vk_bytes, pk_bytes) = load_orchard_artifact_bytes(&manifest_path)?;
+    let (manifest, params_bytes, vk_bytes) = load_orchard_artifact_bytes(&manifest_path)?;
+    let pk_bytes = read_orchard_artifact_file(
+        &artifact_base_dir(&manifest_path),
+        &manifest.pk,
+        "proving key",
+    )?;
     let params = deserialize_params(&params_bytes)?;
     let vk = deserialize_orchard_verifying_key(&vk_bytes)?;
     let pk = deserialize_orchard_proving_key(&pk_bytes)?;
 
-    // Load break points from break_points.json alongside the manifest
-    let break_points_path = orchard_manifest_dir(&manifest_path).join(BREAK_POINTS_FILENAME);
+    // Load break points from break_points.json alongside the other artifacts
+    let break_points_path = artifact_base_dir(&manifest_path).join(BREAK_POINTS_FILENAME);
     let break_points = if break_points_path.exists() {
         let bp_bytes = fs::read(&break_points_path)
             .with_context(|| format!("failed to read break_points from {}", break_points_path.display()))?;
@@ -901,28 +1530,13 @@ fn load_orchard_prover_artifacts() -> Result<OrchardNativeArtifacts> {
             "⚠️ break_points.json not found at {}, computing from circuit (this may take a while)...",
             break_points_path.display()
         );
-        let sample_input = OrchardPofCircuitInput {
-            public_inputs: VerifierPublicInputs {
-                threshold_raw: 0,
-                required_currency_code: 0,
-                current_epoch: 0,
-                verifier_scope_id: 0,
-                policy_id: 0,
-                nullifier: [0u8; 32],
-                custodian_pubkey_hash: [0u8; 32],
-                snapshot_block_height: Some(0),
-                snapshot_anchor_orchard: Some([0u8; 32]),
-                holder_binding: Some([0u8; 32]),
-                proven_sum: None,
-            },
-            note_values: vec![100u64],
-        };
+        let sample_input = keygen_sample_input();
         extract_break_points_from_synthesis(&sample_input, &orchard_default_params())
     };
 
     let prover = ProverArtifacts::from_parts(
         manifest,
-        orchard_manifest_dir(&manifest_path),
+        artifact_base_dir(&manifest_path),
         params,
         vk,
         Some(pk),
@@ -931,11 +1545,27 @@ fn load_orchard_prover_artifacts() -> Result<OrchardNativeArtifacts> {
     Ok(OrchardNativeArtifacts { prover, break_points })
 }
 
+/// Loads Orchard verifier artifacts, also checking the proving key blob
+/// against the manifest so a deployment that proves fails fast on a bad pk.
 pub fn load_orchard_verifier_artifacts(
     manifest_path: impl AsRef<Path>,
 ) -> Result<VerifierArtifacts> {
-    let (manifest, params_bytes, vk_bytes, _) =
-        load_orchard_artifact_bytes(manifest_path.as_ref())?;
+    let manifest_path = manifest_path.as_ref();
+    let artifacts = load_orchard_verifier_only(manifest_path)?;
+    read_orchard_artifact_file(
+        &artifact_base_dir(manifest_path),
+        &artifacts.manifest.pk,
+        "proving key",
+    )?;
+    Ok(artifacts)
+}
+
+/// Loads Orchard verifier artifacts without reading the proving key.
+///
+/// The Orchard pk is large; verifier-only deployments can omit it entirely.
+/// Mirrors `load_prover_artifacts_without_pk` for the custodial circuit.
+pub fn load_orchard_verifier_only(manifest_path: impl AsRef<Path>) -> Result<VerifierArtifacts> {
+    let (manifest, params_bytes, vk_bytes) = load_orchard_artifact_bytes(manifest_path.as_ref())?;
 
     let params = deserialize_params(&params_bytes)?;
     let vk = deserialize_orchard_verifying_key(&vk_bytes)?;
@@ -949,19 +1579,18 @@ pub fn load_orchard_verifier_artifacts(
     })
 }
 
-#[allow(clippy::type_complexity)]
+/// Reads the manifest, params and vk; the proving key is read by callers that need it.
 fn load_orchard_artifact_bytes(
     manifest_path: &Path,
-) -> Result<(ArtifactManifest, Vec<u8>, Vec<u8>, Vec<u8>)> {
+) -> Result<(ArtifactManifest, Vec<u8>, Vec<u8>)> {
     let manifest = read_manifest(manifest_path)?;
     ensure_manifest_compat_orchard(&manifest)?;
-    let base_dir = orchard_manifest_dir(manifest_path);
+    let base_dir = artifact_base_dir(manifest_path);
 
     let params_bytes = read_orchard_artifact_file(&base_dir, &manifest.params, "params")?;
     let vk_bytes = read_orchard_artifact_file(&base_dir, &manifest.vk, "verifying key")?;
-    let pk_bytes = read_orchard_artifact_file(&base_dir, &manifest.pk, "proving key")?;
 
-    Ok((manifest, params_bytes, vk_bytes, pk_bytes))
+    Ok((manifest, params_bytes, vk_bytes))
 }
 
 fn read_orchard_artifact_file(
@@ -986,7 +1615,7 @@ fn read_orchard_artifact_file(
 fn ensure_orchard_hash(bytes: &[u8], expected_hex: &str, label: &str) -> Result<()> {
     let actual = hash_bytes_hex(bytes);
     ensure!(
-        actual == expected_hex,
+        constant_time_eq(actual.as_bytes(), expected_hex.as_bytes()),
         "{} hash mismatch, expected {} but computed {}",
         label,
         expected_hex,
@@ -995,12 +1624,6 @@ fn ensure_orchard_hash(bytes: &[u8], expected_hex: &str, label: &str) -> Result<
     Ok(())
 }
 
-fn orchard_manifest_dir(path: &Path) -> PathBuf {
-    path.parent()
-        .map(Path::to_path_buf)
-        .unwrap_or_else(|| PathBuf::from("."))
-}
-
 fn ensure_manifest_compat_orchard(manifest: &ArtifactManifest) -> Result<()> {
     ensure!(
         manifest.manifest_version == MANIFEST_VERSION,
@@ -1008,6 +1631,13 @@ fn ensure_manifest_compat_orchard(manifest: &ArtifactManifest) -> Result<()> {
         manifest.manifest_version,
         MANIFEST_VERSION
     );
+    ensure!(
+        manifest.circuit_version >= ORCHARD_LAYOUT_VERSION,
+        "Orchard artifacts for circuit version {} predate the current layout (version {}); \
+         regenerate params, keys and break_points.json",
+        manifest.circuit_version,
+        ORCHARD_LAYOUT_VERSION
+    );
     ensure!(
         manifest.circuit_version == CIRCUIT_VERSION,
         "circuit version mismatch: manifest {} vs crate {}",
@@ -1034,7 +1664,7 @@ fn deserialize_orchard_proving_key(bytes: &[u8]) -> Result<plonk::ProvingKey<G1A
 fn create_orchard_proof_with_public_inputs(
     input: &OrchardPofCircuitInput,
 ) -> Result<(Vec<u8>, VerifierPublicInputs), OrchardRailError> {
-    let artifacts = ORCHARD_PROVER_ARTIFACTS.clone();
+    let artifacts = orchard_prover_artifacts()?;
     let public_inputs = input.public_inputs.clone();
 
     let instances =
@@ -1162,6 +1792,9 @@ pub fn prove_orchard_pof_wasm(
     meta: &PublicMetaInputs,
     artifacts: &OrchardWasmArtifacts,
 ) -> Result<ProofBundle, OrchardRailError> {
+    orchard_meta.validate()?;
+    meta.validate()?;
+
     if snapshot.notes.is_empty() {
         return Err(OrchardRailError::InvalidInput(
             "no Orchard notes discovered for this FVK at the requested height".into(),
@@ -1182,8 +1815,8 @@ pub fn prove_orchard_pof_wasm(
         )));
     }
 
-    // Enforce Σ v_i ≥ threshold_zats based on the snapshot notes.
-    let total_zats: u64 = snapshot.notes.iter().map(|n| n.value_zats).sum();
+    // Enforce Σ v_i ≥ threshold_zats over the snapshot notes of the requested asset.
+    let total_zats = asset_note_total(snapshot, &orchard_meta.asset_type)?;
     if total_zats < threshold_zats {
         return Err(OrchardRailError::InvalidInput(format!(
             "insufficient Orchard funds: total_zats {} < threshold_zats {}",
@@ -1191,8 +1824,8 @@ pub fn prove_orchard_pof_wasm(
         )));
     }
 
-    // Compute a simple holder binding H(holder_id || fvk_bytes) using BLAKE3.
     let holder_binding = compute_holder_binding(holder_id, &fvk.encoded);
+    let (holder_id_fr, fvk_fr) = holder_witnesses(holder_id, &fvk.encoded);
 
     // Derive a PoF nullifier that mixes the binding with the policy/scope/epoch tuple.
     let nullifier = compute_pof_nullifier(
@@ -1220,6 +1853,10 @@ pub fn prove_orchard_pof_wasm(
     let circuit_input = OrchardPofCircuitInput {
         public_inputs: public_inputs.clone(),
         note_values: snapshot.notes.iter().map(|n| n.value_zats).collect(),
+        note_asset_types: snapshot.notes.iter().map(|n| n.asset_type).collect(),
+        asset_type: orchard_meta.asset_type,
+        holder_id: holder_id_fr,
+        fvk: fvk_fr,
     };
 
     let (proof, _) = create_orchard_proof_with_public_inputs_from_bytes(artifacts, &circuit_input)?;
@@ -1228,6 +1865,9 @@ pub fn prove_orchard_pof_wasm(
         rail_id: RAIL_ID_ZCASH_ORCHARD.to_string(),
         circuit_version: CIRCUIT_VERSION,
         proof,
+        proof_encoding: None,
+        issued_at: None,
+        issued_at_tag: None,
         public_inputs,
     };
 
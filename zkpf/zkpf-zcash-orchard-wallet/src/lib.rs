@@ -280,12 +280,22 @@ pub struct OrchardMerklePath {
     pub position: u64,
 }
 
+/// Asset type of native ZEC notes.
+///
+/// Zcash Shielded Assets carry their 32-byte asset base encoding instead. The
+/// all-zero value is reserved for ZEC so snapshots written before ZSA support
+/// deserialize as plain-ZEC notes.
+pub const NATIVE_ASSET_TYPE: [u8; 32] = [0u8; 32];
+
 /// Note witness metadata surfaced to the circuit layer.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct OrchardNoteWitness {
     pub value_zats: u64,
     pub commitment: [u8; 32],
     pub merkle_path: OrchardMerklePath,
+    /// Asset the note is denominated in; [`NATIVE_ASSET_TYPE`] for ZEC.
+    #[serde(default)]
+    pub asset_type: [u8; 32],
 }
 
 /// Snapshot of all Orchard notes discovered for an FVK at a specific height.
@@ -53,7 +53,10 @@ use tracing::{debug, info, warn};
 
 use crate::sync::db::WalletDb;
 use crate::sync::tree::OrchardTree;
-use crate::{NetworkKind, OrchardMerklePath, OrchardNoteWitness, OrchardSnapshot, WalletError};
+use crate::{
+    NetworkKind, OrchardMerklePath, OrchardNoteWitness, OrchardSnapshot, WalletError,
+    NATIVE_ASSET_TYPE,
+};
 
 // Re-export the tree depth constant
 pub use crate::sync::tree::ORCHARD_TREE_DEPTH;
@@ -599,6 +602,9 @@ impl LightwalletdClient {
                     siblings: siblings.to_vec(),
                     position: note.position,
                 },
+                // Trial decryption only yields native ZEC notes until ZSA
+                // activates on the synced network.
+                asset_type: NATIVE_ASSET_TYPE,
             });
         }
 